@@ -1,5 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use gamey::{Coordinates, GameY, Movement, PlayerId, RenderOptions};
+use gamey::{Coordinates, GameY, MinimaxBot, MinimaxState, Movement, PlayerId, RenderOptions, YBot};
+use rand::seq::SliceRandom;
 
 /// Benchmarks for coordinate conversion functions
 fn bench_coordinates(c: &mut Criterion) {
@@ -120,6 +121,29 @@ fn bench_add_move(c: &mut Criterion) {
         );
     }
 
+    // Benchmark whole playouts in a shuffled order, until one player connects
+    for board_size in [11, 19, 32].iter() {
+        let mut order: Vec<u32> = (0..(board_size * (board_size + 1)) / 2).collect();
+        order.shuffle(&mut gamey::seeded_rng(7));
+        group.bench_with_input(
+            BenchmarkId::new("playout", board_size),
+            board_size,
+            |b, &size| {
+                b.iter(|| {
+                    let mut game = GameY::new(size);
+                    for &idx in &order {
+                        let Some(player) = game.next_player() else {
+                            break;
+                        };
+                        let coords = Coordinates::from_index(idx, size);
+                        let _ = game.add_move(Movement::Placement { player, coords });
+                    }
+                    black_box(game)
+                })
+            },
+        );
+    }
+
     group.finish();
 }
 
@@ -198,6 +222,44 @@ fn bench_touches_side(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmarks for the minimax bot on large boards
+fn bench_minimax_large_boards(c: &mut Criterion) {
+    let mut group = c.benchmark_group("minimax_large");
+    group.sample_size(10);
+
+    for board_size in [19, 26].iter() {
+        // A game with a handful of stones spread over the board
+        let mut game = GameY::new(*board_size);
+        let total_cells = (board_size * (board_size + 1)) / 2;
+        for (i, idx) in (0..total_cells).step_by(37).take(8).enumerate() {
+            let coords = Coordinates::from_index(idx, *board_size);
+            let player = PlayerId::new(i as u32 % 2);
+            let movement = Movement::Placement { player, coords };
+            let _ = game.add_move(movement);
+        }
+
+        group.bench_with_input(
+            BenchmarkId::new("state_new", board_size),
+            &game,
+            |b, game| {
+                b.iter(|| black_box(MinimaxState::new(game, PlayerId::new(0))))
+            },
+        );
+
+        // The bot stops after the first depth
+        let bot = MinimaxBot::new(1000).with_max_depth(1);
+        group.bench_with_input(
+            BenchmarkId::new("choose_move", board_size),
+            &game,
+            |b, game| {
+                b.iter(|| black_box(bot.choose_move(game)))
+            },
+        );
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_coordinates,
@@ -205,6 +267,7 @@ criterion_group!(
     bench_add_move,
     bench_render,
     bench_touches_side,
+    bench_minimax_large_boards,
 );
 
 criterion_main!(benches);
@@ -0,0 +1,20 @@
+//! Parses arbitrary cell names on boards of arbitrary size.
+
+#![no_main]
+
+use gamey::Coordinates;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (&str, u8)| {
+    let (name, board_size) = input;
+    let board_size = u32::from(board_size);
+    if let Some(coords) = Coordinates::from_cell_name(name, board_size) {
+        assert!(coords.is_valid(board_size));
+        let index = coords.to_index(board_size);
+        assert_eq!(Coordinates::from_index(index, board_size), coords);
+        assert_eq!(
+            Coordinates::from_cell_name(&coords.to_cell_name(board_size), board_size),
+            Some(coords)
+        );
+    }
+});
@@ -0,0 +1,21 @@
+//! Reads arbitrary bytes as a game archive.
+
+#![no_main]
+
+use gamey::{ArchiveReader, ArchiveWriter};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(reader) = ArchiveReader::new(data) else {
+        return;
+    };
+    let header = reader.header().clone();
+    let mut writer = ArchiveWriter::new(Vec::new(), header).expect("read headers can be written");
+    for game in reader {
+        let Ok(game) = game else {
+            break;
+        };
+        // Every game read from an archive can be archived again
+        writer.write_game(&game).expect("read games can be written");
+    }
+});
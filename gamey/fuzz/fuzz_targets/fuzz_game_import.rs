@@ -0,0 +1,22 @@
+//! Imports arbitrary text as an SGF record or a move string.
+
+#![no_main]
+
+use gamey::{GameY, import_game_record, validate_record};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|record: &str| {
+    let _ = GameY::from_sgf(record);
+    let _ = GameY::from_move_string(record);
+    let imported = import_game_record(record);
+    if let Ok(game) = validate_record(record) {
+        // A record that validates imports to the same game
+        let imported = imported.expect("validated records import");
+        assert_eq!(imported.history(), game.history());
+        // And exports to SGF that imports to the same game
+        if let Ok(sgf) = game.to_sgf() {
+            let exported = GameY::from_sgf(&sgf).expect("exported records import");
+            assert_eq!(exported.history(), game.history());
+        }
+    }
+});
@@ -0,0 +1,15 @@
+//! Reads arbitrary text as a position string.
+
+#![no_main]
+
+use gamey::GameY;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|position: &str| {
+    if let Ok(game) = GameY::from_position_string(position) {
+        let written = game.to_position_string();
+        let reread =
+            GameY::from_position_string(&written).expect("written positions can be read back");
+        assert_eq!(reread.to_position_string(), written);
+    }
+});
@@ -0,0 +1,25 @@
+//! Parses arbitrary lines as GTP and YTP commands and runs them.
+//!
+//! Commands that start a search on the YTP engine's background thread are
+//! parsed but not run, so every input is processed synchronously.
+
+#![no_main]
+
+use std::sync::Arc;
+
+use gamey::{GtpCommand, GtpEngine, RandomBot, YtpCommand, YtpEngine};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let mut gtp = GtpEngine::new(5, Arc::new(RandomBot::new()));
+    let mut ytp = YtpEngine::new(5, std::io::sink());
+    for line in input.lines() {
+        if let Some(command) = GtpCommand::parse(line) {
+            let _ = gtp.execute(&command);
+        }
+        match YtpCommand::parse(line) {
+            Ok(Some(YtpCommand::Go(_) | YtpCommand::PonderHit)) | Ok(None) | Err(_) => {}
+            Ok(Some(command)) => ytp.execute(command),
+        }
+    }
+});
@@ -0,0 +1,16 @@
+//! Reads arbitrary bytes as a YEN document and builds a game from it.
+
+#![no_main]
+
+use gamey::{GameY, YEN};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(yen) = serde_json::from_slice::<YEN>(data)
+        && let Ok(game) = GameY::try_from(yen)
+    {
+        // A game read from YEN is written back as the same position
+        let position = game.to_position_string();
+        GameY::from_position_string(&position).expect("written positions can be read back");
+    }
+});
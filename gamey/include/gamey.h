@@ -0,0 +1,150 @@
+#ifndef GAMEY_H
+#define GAMEY_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of a call to the C interface.
+typedef enum GameyStatus {
+  // The call succeeded.
+  GAMEY_STATUS_OK = 0,
+  // A required pointer argument was null.
+  GAMEY_STATUS_NULL_POINTER = -1,
+  // The cell index is outside the board.
+  GAMEY_STATUS_INVALID_CELL = -2,
+  // The cell is already occupied.
+  GAMEY_STATUS_OCCUPIED_CELL = -3,
+  // The game is over, so no more moves can be made.
+  GAMEY_STATUS_GAME_OVER = -4,
+  // There is no move to undo.
+  GAMEY_STATUS_NOTHING_TO_UNDO = -5,
+  // The bot found no move to play.
+  GAMEY_STATUS_NO_MOVE = -6,
+  // The move was rejected for another reason.
+  GAMEY_STATUS_ILLEGAL_MOVE = -7,
+  // The engine panicked. The game should be considered unusable.
+  GAMEY_STATUS_PANIC = -99,
+} GameyStatus;
+
+// An opaque game of Y owned by the caller.
+typedef struct GameyGame GameyGame;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates a game with an empty board of the given size.
+//
+// Returns null if the size is 0. The game must be released with
+// [`gamey_game_free`].
+struct GameyGame *gamey_game_new(uint32_t board_size);
+
+// Creates a game from a position string such as `1/2/3 B`.
+//
+// Returns null if the string is null, not valid UTF-8 or not a valid position.
+//
+// # Safety
+//
+// `position` must be null or point to a NUL-terminated string.
+struct GameyGame *gamey_game_from_position(const char *position);
+
+// Releases a game. Passing null does nothing.
+//
+// # Safety
+//
+// `game` must be null or a pointer returned by this library that has not
+// been freed yet.
+void gamey_game_free(struct GameyGame *game);
+
+// Returns the size of the board, or 0 if `game` is null.
+//
+// # Safety
+//
+// `game` must be null or a valid game pointer.
+uint32_t gamey_game_board_size(const struct GameyGame *game);
+
+// Returns the number of cells on the board, or 0 if `game` is null.
+//
+// # Safety
+//
+// `game` must be null or a valid game pointer.
+uint32_t gamey_game_total_cells(const struct GameyGame *game);
+
+// Returns the player to move (0 or 1), or -1 if the game is over or `game`
+// is null.
+//
+// # Safety
+//
+// `game` must be null or a valid game pointer.
+int32_t gamey_game_next_player(const struct GameyGame *game);
+
+// Returns the winner (0 or 1), or -1 if the game is not over or `game` is
+// null.
+//
+// # Safety
+//
+// `game` must be null or a valid game pointer.
+int32_t gamey_game_winner(const struct GameyGame *game);
+
+// Returns the owner of a cell (0 or 1), -1 for an empty cell, or -2 if the
+// index is outside the board or `game` is null.
+//
+// # Safety
+//
+// `game` must be null or a valid game pointer.
+int32_t gamey_game_cell_owner(const struct GameyGame *game, uint32_t index);
+
+// Copies the owner of every cell into `cells`, as [`gamey_game_cell_owner`]
+// would return them, and returns the number of cells on the board.
+//
+// At most `len` values are written; call with a buffer of
+// [`gamey_game_total_cells`] entries to get the whole board. Returns 0 if a
+// pointer is null.
+//
+// # Safety
+//
+// `game` must be null or a valid game pointer, and `cells` must be null or
+// point to at least `len` writable `int32_t`.
+size_t gamey_game_board(const struct GameyGame *game, int32_t *cells, size_t len);
+
+// Places a stone of the player to move on the cell with the given index.
+//
+// # Safety
+//
+// `game` must be null or a valid game pointer.
+enum GameyStatus gamey_game_play(struct GameyGame *game, uint32_t index);
+
+// Takes back the last move.
+//
+// # Safety
+//
+// `game` must be null or a valid game pointer.
+enum GameyStatus gamey_game_undo(struct GameyGame *game);
+
+// Asks the minimax bot for a move for the player to move, searching for
+// about `max_time_ms` milliseconds.
+//
+// On success the chosen cell index is stored in `out_index`; the move is
+// not played.
+//
+// # Safety
+//
+// `game` must be null or a valid game pointer, and `out_index` must be null
+// or point to a writable `uint32_t`.
+enum GameyStatus gamey_bot_move(const struct GameyGame *game,
+                                uint32_t max_time_ms,
+                                uint32_t *out_index);
+
+// Returns a static, NUL-terminated description of a status.
+const char *gamey_status_message(enum GameyStatus status);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* GAMEY_H */
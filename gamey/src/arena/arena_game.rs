@@ -0,0 +1,259 @@
+use std::fmt;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use crate::{GameClock, GameOverReason, GameY, Movement, PlayerId, TimeControl, YBot};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+/// Share of its remaining time a bot may spend on one move, as a divisor.
+const MOVES_TO_GO: u64 = 20;
+
+/// Why a bot lost a game without being beaten on the board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum BotFault {
+    /// The bot panicked while choosing a move.
+    Crash,
+    /// The bot ran out of time on its clock.
+    Timeout,
+    /// The bot returned no move although the game was not over.
+    NoMove,
+    /// The bot chose a cell that cannot be played.
+    IllegalMove,
+}
+
+impl fmt::Display for BotFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            BotFault::Crash => "crash",
+            BotFault::Timeout => "timeout",
+            BotFault::NoMove => "no move",
+            BotFault::IllegalMove => "illegal move",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// The outcome of a game played by [`play_arena_game`].
+#[derive(Debug, Clone)]
+pub struct ArenaGame {
+    /// The game, with its full history.
+    pub game: GameY,
+    /// The player who won.
+    pub winner: PlayerId,
+    /// How the game ended.
+    pub reason: GameOverReason,
+    /// The fault of the loser, if the game was lost by a fault.
+    pub fault: Option<BotFault>,
+}
+
+/// Plays a game between two bots: `bots[0]` plays player 0 and moves first.
+///
+/// With a time control, each bot is asked to move within a share of its
+/// remaining time and loses if its clock runs out. Bots are not interrupted,
+/// so a bot that overruns is only flagged once it returns. A bot that
+/// panics, returns no move or returns an illegal move loses the game by
+/// abandonment.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{RandomBot, play_arena_game};
+///
+/// let outcome = play_arena_game([&RandomBot::new(), &RandomBot::new()], 4, None);
+/// assert!(outcome.game.check_game_over());
+/// ```
+pub fn play_arena_game(
+    bots: [&dyn YBot; 2],
+    board_size: u32,
+    time_control: Option<TimeControl>,
+) -> ArenaGame {
+    play_arena_game_with_clocks(bots, board_size, [time_control; 2])
+}
+
+/// Plays a game between two bots like [`play_arena_game`], with a time
+/// control for each: `time_controls[0]` is the clock of `bots[0]`. A bot
+/// without a time control has no clock and thinks as long as it likes.
+///
+/// Unequal clocks measure how strength grows with thinking time, for example
+/// a new evaluation at 100 ms a move against the old one at 500 ms.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{RandomBot, TimeControl, play_arena_game_with_clocks};
+///
+/// let fast = TimeControl { initial_ms: 1_000, increment_ms: 10 };
+/// let outcome = play_arena_game_with_clocks(
+///     [&RandomBot::new(), &RandomBot::new()],
+///     4,
+///     [Some(fast), None],
+/// );
+/// assert!(outcome.game.check_game_over());
+/// ```
+pub fn play_arena_game_with_clocks(
+    bots: [&dyn YBot; 2],
+    board_size: u32,
+    time_controls: [Option<TimeControl>; 2],
+) -> ArenaGame {
+    let mut game = GameY::new(board_size);
+    // One clock per side, each only running for its own player
+    let mut clocks = time_controls.map(|control| control.map(GameClock::new));
+    let mut fault = None;
+
+    while let Some(player) = game.next_player() {
+        let bot = bots[player.id() as usize];
+        let clock = &mut clocks[player.id() as usize];
+        if let Some(clock) = clock {
+            clock.start(player, Instant::now());
+        }
+        let budget_ms = clock.as_ref().map(|clock| {
+            let remaining = clock.remaining_ms(player, Instant::now());
+            remaining / MOVES_TO_GO + clock.time_control().increment_ms.min(remaining / 2)
+        });
+        let choice = catch_unwind(AssertUnwindSafe(|| match budget_ms {
+            Some(budget_ms) => bot.choose_move_within(&game, budget_ms),
+            None => bot.choose_move(&game),
+        }));
+
+        if let Some(clock) = clock
+            && clock.flagged(Instant::now()).is_some()
+        {
+            fault = Some(BotFault::Timeout);
+            break;
+        }
+        let coords = match choice {
+            Ok(Some(coords)) => coords,
+            Ok(None) => {
+                fault = Some(BotFault::NoMove);
+                break;
+            }
+            Err(_) => {
+                fault = Some(BotFault::Crash);
+                break;
+            }
+        };
+        if game
+            .add_move(Movement::Placement { player, coords })
+            .is_err()
+        {
+            fault = Some(BotFault::IllegalMove);
+            break;
+        }
+        if let Some(clock) = clock
+            && clock.press(None, Instant::now()).is_err()
+        {
+            fault = Some(BotFault::Timeout);
+            break;
+        }
+    }
+
+    if let (Some(fault), Some(loser)) = (&fault, game.next_player()) {
+        let reason = match fault {
+            BotFault::Timeout => GameOverReason::Timeout,
+            _ => GameOverReason::Abandonment,
+        };
+        game.forfeit(loser, reason)
+            .expect("the game is not over yet");
+    }
+    let result = game.result().expect("arena games are played to the end");
+    ArenaGame {
+        winner: result.winner,
+        reason: result.reason,
+        fault,
+        game,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, RandomBot};
+    use std::time::Duration;
+
+    struct PanicBot;
+
+    impl YBot for PanicBot {
+        fn name(&self) -> &str {
+            "panic_bot"
+        }
+
+        fn choose_move(&self, _board: &GameY) -> Option<Coordinates> {
+            panic!("out of ideas")
+        }
+    }
+
+    struct SlowBot;
+
+    impl YBot for SlowBot {
+        fn name(&self) -> &str {
+            "slow_bot"
+        }
+
+        fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+            std::thread::sleep(Duration::from_millis(30));
+            RandomBot::new().choose_move(board)
+        }
+    }
+
+    struct CornerBot;
+
+    impl YBot for CornerBot {
+        fn name(&self) -> &str {
+            "corner_bot"
+        }
+
+        fn choose_move(&self, _board: &GameY) -> Option<Coordinates> {
+            Some(Coordinates::new(3, 0, 0))
+        }
+    }
+
+    #[test]
+    fn test_game_is_played_to_a_connection() {
+        let outcome = play_arena_game([&RandomBot::new(), &RandomBot::new()], 5, None);
+        assert_eq!(outcome.reason, GameOverReason::Connection);
+        assert_eq!(outcome.fault, None);
+        assert_eq!(outcome.game.result().unwrap().winner, outcome.winner);
+    }
+
+    #[test]
+    fn test_crash_loses_the_game() {
+        let outcome = play_arena_game([&RandomBot::new(), &PanicBot], 4, None);
+        assert_eq!(outcome.winner, PlayerId::new(0));
+        assert_eq!(outcome.fault, Some(BotFault::Crash));
+        assert_eq!(outcome.reason, GameOverReason::Abandonment);
+    }
+
+    #[test]
+    fn test_illegal_move_loses_the_game() {
+        // The second bot repeats the first bot's corner
+        let outcome = play_arena_game([&CornerBot, &CornerBot], 4, None);
+        assert_eq!(outcome.winner, PlayerId::new(0));
+        assert_eq!(outcome.fault, Some(BotFault::IllegalMove));
+    }
+
+    #[test]
+    fn test_running_out_of_time_loses_the_game() {
+        let control = TimeControl {
+            initial_ms: 20,
+            increment_ms: 0,
+        };
+        let outcome = play_arena_game([&SlowBot, &RandomBot::new()], 4, Some(control));
+        assert_eq!(outcome.winner, PlayerId::new(1));
+        assert_eq!(outcome.reason, GameOverReason::Timeout);
+        assert_eq!(outcome.fault, Some(BotFault::Timeout));
+    }
+
+    #[test]
+    fn test_only_the_side_with_a_short_clock_runs_out_of_time() {
+        let control = TimeControl {
+            initial_ms: 20,
+            increment_ms: 0,
+        };
+        let outcome = play_arena_game_with_clocks([&SlowBot, &SlowBot], 4, [None, Some(control)]);
+        assert_eq!(outcome.winner, PlayerId::new(0));
+        assert_eq!(outcome.reason, GameOverReason::Timeout);
+    }
+}
@@ -0,0 +1,14 @@
+//! Bot-versus-bot games and tournaments.
+//!
+//! - [`play_arena_game`]: A single game between two bots, with optional
+//!   clocks, where crashes, timeouts and invalid moves lose the game
+//! - [`play_arena_game_with_clocks`]: The same, with a different clock for
+//!   each bot
+//! - [`Tournament`]: Round robin or Swiss tournaments between named bots
+//! - [`TournamentResults`]: Standings and crosstables, exported as CSV or JSON
+
+pub mod arena_game;
+pub mod tournament;
+
+pub use arena_game::*;
+pub use tournament::*;
@@ -0,0 +1,831 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use crate::notation::game_import::sgf_result;
+use crate::{
+    BotFault, GameMetadata, GameOverReason, PlayerId, TimeControl, YBot, derive_seed,
+    play_arena_game_with_clocks, random_seed,
+};
+#[cfg(feature = "serde")]
+use crate::{GameYError, Result};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How the games of a [`Tournament`] are scheduled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pairing {
+    /// Every entrant meets every other entrant. Rounds follow the circle
+    /// method, so each entrant plays at most once per round.
+    RoundRobin,
+    /// Entrants are paired each round with an entrant on a similar score
+    /// they have not met yet. With an odd number of entrants, the lowest
+    /// ranked entrant without a bye sits out the round and scores a point.
+    Swiss {
+        /// Number of rounds to play.
+        rounds: u32,
+    },
+}
+
+/// How one entrant of a [`Tournament`] plays, when the sides are not
+/// meant to be equal.
+///
+/// The settings of each entrant are recorded in the
+/// [results](TournamentResults::settings), so that strength can be
+/// compared per unit of time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EntrantSettings {
+    /// The time control of the entrant. None plays with the time control of
+    /// the tournament; in the results, None means no clock.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub time_control: Option<TimeControl>,
+    /// What else sets the entrant apart, such as the depth or the number of
+    /// threads its bot was configured with.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub description: Option<String>,
+}
+
+impl EntrantSettings {
+    /// Creates settings with the time control of the tournament and no
+    /// description.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gives the entrant its own time control.
+    pub fn with_time_control(mut self, time_control: TimeControl) -> Self {
+        self.time_control = Some(time_control);
+        self
+    }
+
+    /// Describes the settings of the bot of the entrant.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+/// A game played in a tournament.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TournamentGame {
+    /// The round the game was played in, starting at 1.
+    pub round: u32,
+    /// Index of the entrant who played first.
+    pub first: usize,
+    /// Index of the entrant who played second.
+    pub second: usize,
+    /// Index of the winner.
+    pub winner: usize,
+    /// How the game ended.
+    pub reason: GameOverReason,
+    /// The fault of the loser, if the game was lost by a fault.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub fault: Option<BotFault>,
+    /// Number of moves in the game.
+    pub moves: usize,
+    /// The seed the bots were started from for this game.
+    pub seed: u64,
+}
+
+impl TournamentGame {
+    /// Returns the index of the loser.
+    pub fn loser(&self) -> usize {
+        if self.winner == self.first {
+            self.second
+        } else {
+            self.first
+        }
+    }
+}
+
+/// A set of bots playing each other.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use gamey::{Pairing, RandomBot, Tournament};
+///
+/// let results = Tournament::new(4)
+///     .with_entrant("alice", Arc::new(RandomBot::new()))
+///     .with_entrant("bob", Arc::new(RandomBot::new()))
+///     .with_entrant("carol", Arc::new(RandomBot::new()))
+///     .with_pairing(Pairing::RoundRobin)
+///     .run();
+/// assert_eq!(results.games.len(), 3);
+/// assert_eq!(results.standings().len(), 3);
+/// ```
+pub struct Tournament {
+    board_size: u32,
+    entrants: Vec<(String, Arc<dyn YBot>, EntrantSettings)>,
+    pairing: Pairing,
+    games_per_pairing: u32,
+    time_control: Option<TimeControl>,
+    seed: Option<u64>,
+}
+
+impl Tournament {
+    /// Creates a round robin tournament without entrants, played on boards
+    /// of `board_size` without clocks.
+    pub fn new(board_size: u32) -> Self {
+        Self {
+            board_size,
+            entrants: Vec::new(),
+            pairing: Pairing::RoundRobin,
+            games_per_pairing: 1,
+            time_control: None,
+            seed: None,
+        }
+    }
+
+    /// Adds a bot under `name`. The same bot may enter under several names,
+    /// for example with different settings.
+    pub fn with_entrant(self, name: impl Into<String>, bot: Arc<dyn YBot>) -> Self {
+        self.with_entrant_settings(name, bot, EntrantSettings::new())
+    }
+
+    /// Adds a bot under `name`, playing with `settings` instead of the time
+    /// control of the tournament.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use gamey::{EntrantSettings, MinimaxBot, RandomBot, TimeControl, Tournament};
+    ///
+    /// let slow = TimeControl { initial_ms: 10_000, increment_ms: 500 };
+    /// let results = Tournament::new(4)
+    ///     .with_time_control(TimeControl { initial_ms: 2_000, increment_ms: 100 })
+    ///     .with_entrant("fast", Arc::new(MinimaxBot::new(100)))
+    ///     .with_entrant_settings(
+    ///         "slow",
+    ///         Arc::new(MinimaxBot::with_depth(1)),
+    ///         EntrantSettings::new().with_time_control(slow).with_description("depth 1"),
+    ///     )
+    ///     .run();
+    /// assert_eq!(results.settings[1].time_control, Some(slow));
+    /// assert_eq!(results.settings[0].time_control.unwrap().initial_ms, 2_000);
+    /// ```
+    pub fn with_entrant_settings(
+        mut self,
+        name: impl Into<String>,
+        bot: Arc<dyn YBot>,
+        settings: EntrantSettings,
+    ) -> Self {
+        self.entrants.push((name.into(), bot, settings));
+        self
+    }
+
+    /// Sets how the games are scheduled.
+    pub fn with_pairing(mut self, pairing: Pairing) -> Self {
+        self.pairing = pairing;
+        self
+    }
+
+    /// Sets how many games each pair of opponents plays when they meet,
+    /// alternating who plays first. At least one game is played.
+    pub fn with_games_per_pairing(mut self, games: u32) -> Self {
+        self.games_per_pairing = games.max(1);
+        self
+    }
+
+    /// Plays every game with this time control, except for the entrants with
+    /// a time control of their own.
+    pub fn with_time_control(mut self, time_control: TimeControl) -> Self {
+        self.time_control = Some(time_control);
+        self
+    }
+
+    /// Seeds the random choices of the bots, so that the tournament can be
+    /// replayed exactly. Without a seed, a random one is drawn for each run
+    /// and recorded in the results.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Plays the tournament.
+    pub fn run(&self) -> TournamentResults {
+        self.run_with(&mut |_, _| {})
+    }
+
+    /// Plays the tournament, calling `on_game` after every game with the
+    /// results so far.
+    pub fn run_with(
+        &self,
+        on_game: &mut dyn FnMut(&TournamentGame, &TournamentResults),
+    ) -> TournamentResults {
+        let mut results = TournamentResults {
+            board_size: self.board_size,
+            names: self
+                .entrants
+                .iter()
+                .map(|(name, _, _)| name.clone())
+                .collect(),
+            settings: self
+                .entrants
+                .iter()
+                .map(|(_, _, settings)| EntrantSettings {
+                    time_control: settings.time_control.or(self.time_control),
+                    description: settings.description.clone(),
+                })
+                .collect(),
+            games: Vec::new(),
+            byes: Vec::new(),
+            seed: self.seed.unwrap_or_else(random_seed),
+        };
+        let mut first_counts = vec![0u32; self.entrants.len()];
+        let rounds = match self.pairing {
+            Pairing::RoundRobin => {
+                let seats = self.entrants.len() + self.entrants.len() % 2;
+                seats.saturating_sub(1) as u32
+            }
+            Pairing::Swiss { rounds } => rounds,
+        };
+        for round in 1..=rounds {
+            let (pairs, bye) = match self.pairing {
+                Pairing::RoundRobin => round_robin_round(self.entrants.len(), round - 1),
+                Pairing::Swiss { .. } => swiss_round(&results),
+            };
+            if let Some(bye) = bye {
+                results.byes.push((round, bye));
+            }
+            for (a, b) in pairs {
+                // The entrant who has played first less often starts, then
+                // the two alternate
+                let mut order = if first_counts[a] <= first_counts[b] {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+                for _ in 0..self.games_per_pairing {
+                    let (first, second) = order;
+                    order = (second, first);
+                    first_counts[first] += 1;
+                    let seed = derive_seed(results.seed, results.games.len() as u64);
+                    let time_controls =
+                        [first, second].map(|entrant| results.settings[entrant].time_control);
+                    let game = self.play(round, (first, second), time_controls, seed);
+                    results.games.push(game.clone());
+                    on_game(&game, &results);
+                }
+            }
+        }
+        results
+    }
+
+    fn play(
+        &self,
+        round: u32,
+        (first, second): (usize, usize),
+        time_controls: [Option<TimeControl>; 2],
+        seed: u64,
+    ) -> TournamentGame {
+        let bots = [
+            self.entrants[first].1.as_ref(),
+            self.entrants[second].1.as_ref(),
+        ];
+        for (side, bot) in bots.iter().enumerate() {
+            bot.reseed(derive_seed(seed, side as u64));
+        }
+        let outcome = play_arena_game_with_clocks(bots, self.board_size, time_controls);
+        TournamentGame {
+            round,
+            first,
+            second,
+            winner: if outcome.winner.id() == 0 {
+                first
+            } else {
+                second
+            },
+            reason: outcome.reason,
+            fault: outcome.fault,
+            moves: outcome.game.history().len(),
+            seed,
+        }
+    }
+}
+
+/// Returns the pairs of round `round` of a round robin between `entrants`
+/// entrants, and the entrant sitting out, if any.
+fn round_robin_round(entrants: usize, round: u32) -> (Vec<(usize, usize)>, Option<usize>) {
+    // Circle method: the first seat stays, the others rotate. An odd number
+    // of entrants gets an empty seat, whose opponent sits out the round.
+    let seats = entrants + entrants % 2;
+    let mut circle: Vec<usize> = (1..seats).collect();
+    let turns = round as usize % (seats - 1).max(1);
+    circle.rotate_right(turns);
+    circle.insert(0, 0);
+    let mut pairs = Vec::new();
+    let mut bye = None;
+    for i in 0..seats / 2 {
+        let (a, b) = (circle[i], circle[seats - 1 - i]);
+        match (a < entrants, b < entrants) {
+            (true, true) => pairs.push((a, b)),
+            (true, false) => bye = Some(a),
+            (false, true) => bye = Some(b),
+            (false, false) => {}
+        }
+    }
+    (pairs, bye)
+}
+
+/// Returns the pairs of the next Swiss round, given the results so far, and
+/// the entrant sitting out, if any.
+fn swiss_round(results: &TournamentResults) -> (Vec<(usize, usize)>, Option<usize>) {
+    let mut ranking: Vec<usize> = results
+        .standings()
+        .iter()
+        .map(|standing| standing.entrant)
+        .collect();
+    let mut bye = None;
+    if ranking.len() % 2 == 1 {
+        let had_bye: HashSet<usize> = results.byes.iter().map(|(_, entrant)| *entrant).collect();
+        let pos = ranking
+            .iter()
+            .rposition(|entrant| !had_bye.contains(entrant))
+            .unwrap_or(ranking.len() - 1);
+        bye = Some(ranking.remove(pos));
+    }
+    let met: HashSet<(usize, usize)> = results
+        .games
+        .iter()
+        .map(|game| (game.first.min(game.second), game.first.max(game.second)))
+        .collect();
+    let pairs = pair_without_rematches(&ranking, &met).unwrap_or_else(|| {
+        // Everyone has met: pair neighbours in the ranking
+        ranking.chunks(2).map(|pair| (pair[0], pair[1])).collect()
+    });
+    (pairs, bye)
+}
+
+/// Pairs `ranking`, an even number of entrants best first, so that nobody
+/// meets an opponent from `met` again, preferring opponents close in the
+/// ranking. Returns None if there is no such pairing.
+fn pair_without_rematches(
+    ranking: &[usize],
+    met: &HashSet<(usize, usize)>,
+) -> Option<Vec<(usize, usize)>> {
+    let Some((&a, rest)) = ranking.split_first() else {
+        return Some(Vec::new());
+    };
+    rest.iter().enumerate().find_map(|(i, &b)| {
+        if met.contains(&(a.min(b), a.max(b))) {
+            return None;
+        }
+        let others: Vec<usize> = rest
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, &entrant)| entrant)
+            .collect();
+        let mut pairs = pair_without_rematches(&others, met)?;
+        pairs.insert(0, (a, b));
+        Some(pairs)
+    })
+}
+
+/// The results of a [`Tournament`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TournamentResults {
+    /// The size of the board of every game.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub board_size: u32,
+    /// Names of the entrants, indexed by entrant.
+    pub names: Vec<String>,
+    /// The settings each entrant played with, indexed by entrant, with the
+    /// time control of the tournament filled in.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub settings: Vec<EntrantSettings>,
+    /// The games in the order they were played.
+    pub games: Vec<TournamentGame>,
+    /// The rounds each entrant sat out, as `(round, entrant)`.
+    pub byes: Vec<(u32, usize)>,
+    /// The seed of the tournament, from which the seed of each game is
+    /// derived.
+    pub seed: u64,
+}
+
+/// The record of an entrant in a tournament.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Standing {
+    /// Index of the entrant.
+    pub entrant: usize,
+    /// Name of the entrant.
+    pub name: String,
+    /// Games played.
+    pub games: u32,
+    /// Games won.
+    pub wins: u32,
+    /// Games lost.
+    pub losses: u32,
+    /// Games lost by a crash, a timeout or an invalid move.
+    pub faults: u32,
+    /// Rounds sat out, each worth a point.
+    pub byes: u32,
+    /// Wins plus byes.
+    pub points: u32,
+    /// Sum of the points of the opponents met, used to break ties.
+    pub buchholz: u32,
+}
+
+impl TournamentResults {
+    /// Returns the standings, best first. Ties are broken by the
+    /// [Buchholz score](Standing::buchholz), then by order of entry.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(entrant, name)| Standing {
+                entrant,
+                name: name.clone(),
+                games: 0,
+                wins: 0,
+                losses: 0,
+                faults: 0,
+                byes: 0,
+                points: 0,
+                buchholz: 0,
+            })
+            .collect();
+        for game in &self.games {
+            let loser = game.loser();
+            standings[game.first].games += 1;
+            standings[game.second].games += 1;
+            standings[game.winner].wins += 1;
+            standings[loser].losses += 1;
+            if game.fault.is_some() {
+                standings[loser].faults += 1;
+            }
+        }
+        for (_, entrant) in &self.byes {
+            standings[*entrant].byes += 1;
+        }
+        for standing in &mut standings {
+            standing.points = standing.wins + standing.byes;
+        }
+        let points: Vec<u32> = standings.iter().map(|standing| standing.points).collect();
+        for game in &self.games {
+            standings[game.first].buchholz += points[game.second];
+            standings[game.second].buchholz += points[game.first];
+        }
+        standings.sort_by_key(|standing| {
+            (
+                std::cmp::Reverse(standing.points),
+                std::cmp::Reverse(standing.buchholz),
+                standing.entrant,
+            )
+        });
+        standings
+    }
+
+    /// Returns the metadata of `game`, one of the games of the results: the
+    /// names of its entrants with the descriptions of their settings as
+    /// engines, the board size, the round as the SGF property `RO` and the
+    /// result. The time control is only set when both entrants played under
+    /// the same one.
+    pub fn game_metadata(&self, game: &TournamentGame) -> GameMetadata {
+        let entrants = [game.first, game.second];
+        let settings = entrants.map(|entrant| self.settings.get(entrant));
+        let winner = PlayerId::new(if game.winner == game.first { 0 } else { 1 });
+        GameMetadata {
+            players: entrants.map(|entrant| self.names.get(entrant).cloned()),
+            engines: settings.map(|settings| settings.and_then(|s| s.description.clone())),
+            time_control: match settings {
+                [Some(first), Some(second)] if first.time_control == second.time_control => {
+                    first.time_control
+                }
+                _ => None,
+            },
+            board_size: Some(self.board_size),
+            result: Some(sgf_result(winner, game.reason)),
+            ..GameMetadata::new()
+        }
+        .with_other("RO", game.round.to_string())
+    }
+
+    /// Returns the wins of each entrant against each other entrant:
+    /// `crosstable()[a][b]` is the number of games `a` won against `b`.
+    pub fn crosstable(&self) -> Vec<Vec<u32>> {
+        let mut table = vec![vec![0; self.names.len()]; self.names.len()];
+        for game in &self.games {
+            table[game.winner][game.loser()] += 1;
+        }
+        table
+    }
+
+    /// Returns the standings as CSV, with a header line.
+    pub fn standings_csv(&self) -> String {
+        let mut csv = String::from("rank,name,games,wins,losses,faults,byes,points,buchholz\n");
+        for (rank, s) in self.standings().iter().enumerate() {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{}",
+                rank + 1,
+                csv_field(&s.name),
+                s.games,
+                s.wins,
+                s.losses,
+                s.faults,
+                s.byes,
+                s.points,
+                s.buchholz
+            );
+        }
+        csv
+    }
+
+    /// Returns the crosstable as CSV. Each cell holds the wins and losses of
+    /// the row entrant against the column entrant, as `wins-losses`.
+    pub fn crosstable_csv(&self) -> String {
+        let table = self.crosstable();
+        let mut csv = String::new();
+        for name in &self.names {
+            csv.push(',');
+            csv.push_str(&csv_field(name));
+        }
+        csv.push('\n');
+        for (a, (name, row)) in self.names.iter().zip(&table).enumerate() {
+            csv.push_str(&csv_field(name));
+            for (b, wins) in row.iter().enumerate() {
+                if a == b {
+                    csv.push_str(",-");
+                } else {
+                    let _ = write!(csv, ",{}-{}", wins, table[b][a]);
+                }
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Returns the standings, the crosstable and the games as JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String> {
+        let report = serde_json::json!({
+            "standings": self.standings(),
+            "crosstable": self.crosstable(),
+            "names": self.names,
+            "settings": self.settings,
+            "games": self.games,
+            "game_metadata": self
+                .games
+                .iter()
+                .map(|game| self.game_metadata(game))
+                .collect::<Vec<_>>(),
+            "board_size": self.board_size,
+            "byes": self.byes,
+            "seed": self.seed,
+        });
+        serde_json::to_string_pretty(&report).map_err(|e| GameYError::SerdeError { error: e })
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameY, MinimaxBot, RandomBot};
+
+    struct ResignBot;
+
+    impl YBot for ResignBot {
+        fn name(&self) -> &str {
+            "resign_bot"
+        }
+
+        fn choose_move(&self, _board: &GameY) -> Option<Coordinates> {
+            None
+        }
+    }
+
+    struct SleepyBot;
+
+    impl YBot for SleepyBot {
+        fn name(&self) -> &str {
+            "sleepy_bot"
+        }
+
+        fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+            std::thread::sleep(std::time::Duration::from_millis(30));
+            RandomBot::new().choose_move(board)
+        }
+    }
+
+    #[test]
+    fn test_round_robin_meets_everyone_once_per_round() {
+        for entrants in 2..=7 {
+            let mut met = HashSet::new();
+            for round in 0..(entrants + entrants % 2 - 1) as u32 {
+                let (pairs, bye) = round_robin_round(entrants, round);
+                let mut seen: Vec<usize> = pairs.iter().flat_map(|&(a, b)| [a, b]).collect();
+                seen.extend(bye);
+                seen.sort();
+                assert_eq!(seen, (0..entrants).collect::<Vec<_>>());
+                for (a, b) in pairs {
+                    assert!(met.insert((a.min(b), a.max(b))));
+                }
+            }
+            assert_eq!(met.len(), entrants * (entrants - 1) / 2);
+        }
+    }
+
+    #[test]
+    fn test_colours_are_balanced() {
+        let results = Tournament::new(3)
+            .with_entrant("a", Arc::new(RandomBot::new()))
+            .with_entrant("b", Arc::new(RandomBot::new()))
+            .with_entrant("c", Arc::new(RandomBot::new()))
+            .with_entrant("d", Arc::new(RandomBot::new()))
+            .with_games_per_pairing(2)
+            .run();
+        assert_eq!(results.games.len(), 12);
+        for entrant in 0..4 {
+            let first = results.games.iter().filter(|g| g.first == entrant).count();
+            assert_eq!(first, 3);
+        }
+    }
+
+    #[test]
+    fn test_faults_count_as_losses() {
+        let results = Tournament::new(4)
+            .with_entrant("minimax", Arc::new(MinimaxBot::new(10)))
+            .with_entrant("quitter", Arc::new(ResignBot))
+            .with_games_per_pairing(2)
+            .run();
+        let standings = results.standings();
+        assert_eq!(standings[0].name, "minimax");
+        assert_eq!(standings[0].points, 2);
+        assert_eq!(standings[1].faults, 2);
+        assert_eq!(results.crosstable(), vec![vec![0, 2], vec![0, 0]]);
+        assert!(
+            results
+                .games
+                .iter()
+                .all(|g| g.fault == Some(BotFault::NoMove))
+        );
+    }
+
+    #[test]
+    fn test_entrants_play_with_their_own_clocks() {
+        let short = TimeControl {
+            initial_ms: 20,
+            increment_ms: 0,
+        };
+        let results = Tournament::new(4)
+            .with_entrant("patient", Arc::new(SleepyBot))
+            .with_entrant_settings(
+                "hurried",
+                Arc::new(SleepyBot),
+                EntrantSettings::new()
+                    .with_time_control(short)
+                    .with_description("sleeps 30 ms"),
+            )
+            .with_games_per_pairing(2)
+            .run();
+        assert!(results.games.iter().all(|g| g.winner == 0));
+        assert!(
+            results
+                .games
+                .iter()
+                .all(|g| g.reason == GameOverReason::Timeout)
+        );
+        assert_eq!(
+            results.settings,
+            vec![
+                EntrantSettings::new(),
+                EntrantSettings::new()
+                    .with_time_control(short)
+                    .with_description("sleeps 30 ms"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_swiss_avoids_rematches_and_gives_byes() {
+        let mut tournament = Tournament::new(3).with_pairing(Pairing::Swiss { rounds: 3 });
+        for name in ["a", "b", "c", "d", "e"] {
+            tournament = tournament.with_entrant(name, Arc::new(RandomBot::new()));
+        }
+        let results = tournament.run();
+        assert_eq!(results.games.len(), 6);
+        assert_eq!(results.byes.len(), 3);
+        let byes: HashSet<usize> = results.byes.iter().map(|(_, e)| *e).collect();
+        assert_eq!(byes.len(), 3);
+        let pairs: HashSet<(usize, usize)> = results
+            .games
+            .iter()
+            .map(|g| (g.first.min(g.second), g.first.max(g.second)))
+            .collect();
+        assert_eq!(pairs.len(), 6);
+        let points: u32 = results.standings().iter().map(|s| s.points).sum();
+        assert_eq!(points, 9);
+    }
+
+    #[test]
+    fn test_seeded_tournaments_are_reproducible() {
+        let run = |seed| {
+            Tournament::new(5)
+                .with_entrant("a", Arc::new(RandomBot::new()))
+                .with_entrant("b", Arc::new(RandomBot::new()))
+                .with_entrant("c", Arc::new(RandomBot::new()))
+                .with_games_per_pairing(2)
+                .with_seed(seed)
+                .run()
+        };
+        let results = run(17);
+        assert_eq!(results.seed, 17);
+        assert_eq!(run(17), results);
+        let seeds: HashSet<u64> = results.games.iter().map(|g| g.seed).collect();
+        assert_eq!(seeds.len(), results.games.len());
+    }
+
+    #[test]
+    fn test_csv_exports() {
+        let results = TournamentResults {
+            board_size: 5,
+            names: vec!["x".to_string(), "y, the bot".to_string()],
+            settings: Vec::new(),
+            games: vec![TournamentGame {
+                round: 1,
+                first: 1,
+                second: 0,
+                winner: 1,
+                reason: GameOverReason::Connection,
+                fault: None,
+                moves: 5,
+                seed: 0,
+            }],
+            byes: Vec::new(),
+            seed: 0,
+        };
+        assert_eq!(
+            results.standings_csv(),
+            "rank,name,games,wins,losses,faults,byes,points,buchholz\n\
+             1,\"y, the bot\",1,1,0,0,0,1,0\n\
+             2,x,1,0,1,0,0,0,1\n"
+        );
+        assert_eq!(
+            results.crosstable_csv(),
+            ",x,\"y, the bot\"\nx,-,0-1\n\"y, the bot\",1-0,-\n"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_export() {
+        let results = Tournament::new(3)
+            .with_entrant("a", Arc::new(RandomBot::new()))
+            .with_entrant("b", Arc::new(RandomBot::new()))
+            .run();
+        let json: serde_json::Value = serde_json::from_str(&results.to_json().unwrap()).unwrap();
+        assert_eq!(json["standings"].as_array().unwrap().len(), 2);
+        assert_eq!(json["games"][0]["round"], 1);
+        assert_eq!(json["game_metadata"][0]["board_size"], 3);
+    }
+
+    #[test]
+    fn test_game_metadata_describes_the_entrants() {
+        let control = TimeControl {
+            initial_ms: 60_000,
+            increment_ms: 0,
+        };
+        let results = Tournament::new(3)
+            .with_entrant("a", Arc::new(RandomBot::new()))
+            .with_entrant_settings(
+                "b",
+                Arc::new(RandomBot::new()),
+                EntrantSettings::new().with_description("random, seeded"),
+            )
+            .with_time_control(control)
+            .with_seed(5)
+            .run();
+        let game = &results.games[0];
+        let metadata = results.game_metadata(game);
+        let names = [game.first, game.second].map(|entrant| Some(results.names[entrant].clone()));
+        assert_eq!(metadata.players, names);
+        assert!(
+            metadata
+                .engines
+                .contains(&Some("random, seeded".to_string()))
+        );
+        assert_eq!(metadata.time_control, Some(control));
+        assert_eq!(metadata.board_size, Some(3));
+        assert_eq!(metadata.other["RO"], "1");
+        let winner = if game.winner == game.first { "B" } else { "W" };
+        assert!(metadata.result.unwrap().starts_with(winner));
+    }
+}
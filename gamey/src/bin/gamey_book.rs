@@ -0,0 +1,83 @@
+//! Opening book generator for the minimax bot.
+//!
+//! Explores the first moves of games on one board size, by deep searches or
+//! by self-play, and writes an [`gamey::OpeningBook`] as JSON, to be loaded
+//! with [`gamey::OpeningBook::load_from_file`] and given to
+//! [`gamey::MinimaxBot::with_opening_book`].
+//!
+//! # Usage
+//!
+//! ```bash
+//! # Search the first 4 plies on size 7, 5 plies deep
+//! gamey-book --size 7 --plies 4 --depth 5 --output book7.json
+//!
+//! # Learn the first 2 plies from 500 self-play games
+//! gamey-book --size 7 --plies 2 --self-play 500 --output book7.json
+//! ```
+
+use clap::Parser;
+use gamey::{BookSource, OpeningBookBuilder};
+use std::path::PathBuf;
+
+/// Command-line arguments for the book generator.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+#[command(long_about = "gamey-book: generate an opening book for the minimax bot.")]
+struct BookArgs {
+    /// Size of the board the book is for.
+    #[arg(short, long, default_value_t = 7)]
+    size: u32,
+
+    /// Number of moves of a game covered by the book.
+    #[arg(long, default_value_t = 4)]
+    plies: usize,
+
+    /// Moves kept per position when searching.
+    #[arg(long, default_value_t = 3)]
+    width: usize,
+
+    /// Depth of the searches scoring each move, in plies.
+    #[arg(long, default_value_t = 4)]
+    depth: u8,
+
+    /// Largest score gap to the best move for a move to be kept.
+    #[arg(long, default_value_t = 25)]
+    margin: i32,
+
+    /// Learn the book from this many self-play games instead of searching.
+    #[arg(long)]
+    self_play: Option<u32>,
+
+    /// Seed of the random openings of the self-play games.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// File to write the book to.
+    #[arg(short, long)]
+    output: PathBuf,
+}
+
+fn main() {
+    let args = BookArgs::parse();
+    let source = match args.self_play {
+        Some(games) => BookSource::SelfPlay { games },
+        None => BookSource::Search,
+    };
+    let book = OpeningBookBuilder::new(args.size)
+        .with_plies(args.plies)
+        .with_width(args.width)
+        .with_search_depth(args.depth)
+        .with_margin(args.margin)
+        .with_source(source)
+        .with_seed(args.seed)
+        .build();
+    if let Err(e) = book.save_to_file(&args.output) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    println!(
+        "Wrote {} positions to {}",
+        book.len(),
+        args.output.display()
+    );
+}
@@ -0,0 +1,22 @@
+//! Terminal game of Y against the computer.
+//!
+//! # Usage
+//!
+//! ```bash
+//! # A size 9 board against the strongest bot, which plays first
+//! gamey-cli --size 9 --difficulty expert --bot-first
+//!
+//! # Resume a saved game
+//! gamey-cli --load game.json
+//! ```
+
+use clap::Parser;
+use gamey::{PlayArgs, run_play_cli};
+
+fn main() {
+    let args = PlayArgs::parse();
+    if let Err(e) = run_play_cli(&args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
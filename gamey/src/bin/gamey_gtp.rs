@@ -0,0 +1,56 @@
+//! GTP engine binary for the Game of Y.
+//!
+//! Speaks a Go-Text-Protocol-like command set on stdin/stdout so the engine
+//! can be plugged into board-game GUIs and tournament managers. See
+//! [`gamey::gtp`] for the supported commands.
+//!
+//! # Usage
+//!
+//! ```bash
+//! # Use the minimax bot with one second per move
+//! gamey-gtp --bot minimax_bot --maxms 1000
+//! ```
+
+use clap::Parser;
+use gamey::{GtpEngine, MinimaxBot, RandomBot, YBotRegistry};
+use std::sync::Arc;
+
+/// Command-line arguments for the GTP engine.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+#[command(long_about = "gamey-gtp: the Game of Y engine speaking a GTP-style protocol.")]
+struct GtpArgs {
+    /// Initial size of the board, until a `boardsize` command is received.
+    #[arg(short, long, default_value_t = 7)]
+    size: u32,
+
+    /// The bot answering `genmove` commands.
+    #[arg(short, long, default_value = "minimax_bot")]
+    bot: String,
+
+    /// Maximum time in milliseconds for the minimax bot to search.
+    #[arg(long, default_value_t = 1000)]
+    maxms: u64,
+}
+
+fn main() {
+    let args = GtpArgs::parse();
+    let bots = YBotRegistry::new()
+        .with_bot(Arc::new(RandomBot::new()))
+        .with_bot(Arc::new(MinimaxBot::new(args.maxms)));
+    let Some(bot) = bots.find(&args.bot) else {
+        eprintln!(
+            "Bot '{}' not found. Available bots: {:?}",
+            args.bot,
+            bots.names()
+        );
+        std::process::exit(1);
+    };
+
+    let mut engine = GtpEngine::new(args.size, bot).with_bots(bots);
+    let stdin = std::io::stdin();
+    if let Err(e) = engine.run(stdin.lock(), std::io::stdout()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
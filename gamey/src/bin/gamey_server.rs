@@ -0,0 +1,41 @@
+//! HTTP game server for playing the Game of Y against the bots.
+//!
+//! Keeps games in memory and exposes REST endpoints to create games, play
+//! moves, ask a bot for its move and resign. See [`gamey::game_server`] for
+//! the endpoints.
+//!
+//! # Usage
+//!
+//! ```bash
+//! gamey-server --port 3000
+//! curl -X POST localhost:3000/v1/games -H 'Content-Type: application/json' -d '{"size":7}'
+//! ```
+
+use clap::Parser;
+
+/// Command-line arguments for the game server.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+#[command(long_about = "gamey-server: play the Game of Y against the bots over HTTP.")]
+struct ServerArgs {
+    /// Port to listen on.
+    #[arg(short, long, default_value_t = 3000)]
+    port: u16,
+
+    /// Record every game to a file of this directory as it is played.
+    #[arg(long)]
+    record_dir: Option<std::path::PathBuf>,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = ServerArgs::parse();
+    let mut state = gamey::create_default_game_state();
+    if let Some(dir) = args.record_dir {
+        state = state.with_recordings(dir);
+    }
+    if let Err(e) = gamey::run_game_server_with(args.port, state).await {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
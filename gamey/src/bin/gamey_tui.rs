@@ -0,0 +1,29 @@
+//! Full-screen terminal interface for the Game of Y.
+//!
+//! # Usage
+//!
+//! ```bash
+//! # Two players at the same keyboard, five minutes each
+//! gamey-tui --size 9 --clock-secs 300
+//!
+//! # Against the minimax bot
+//! gamey-tui --bot minimax_bot --bot-time-ms 2000
+//! ```
+
+use clap::Parser;
+use gamey::{TuiApp, TuiArgs, run_tui};
+
+fn main() {
+    let args = TuiArgs::parse();
+    let app = match TuiApp::from_args(&args) {
+        Ok(app) => app,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = run_tui(app) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
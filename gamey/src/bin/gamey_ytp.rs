@@ -0,0 +1,33 @@
+//! YTP engine binary for the Game of Y.
+//!
+//! Speaks a UCI-style protocol on stdin/stdout. See [`gamey::ytp`] for the
+//! supported commands.
+//!
+//! # Usage
+//!
+//! ```bash
+//! gamey-ytp --size 9
+//! ```
+
+use clap::Parser;
+use gamey::YtpEngine;
+
+/// Command-line arguments for the YTP engine.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+#[command(long_about = "gamey-ytp: the Game of Y engine speaking a UCI-style protocol.")]
+struct YtpArgs {
+    /// Initial size of the board, until a `newgame` or `position` command is received.
+    #[arg(short, long, default_value_t = 7)]
+    size: u32,
+}
+
+fn main() {
+    let args = YtpArgs::parse();
+    let mut engine = YtpEngine::new(args.size, std::io::stdout());
+    let stdin = std::io::stdin();
+    if let Err(e) = engine.run(stdin.lock()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
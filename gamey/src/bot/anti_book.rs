@@ -0,0 +1,263 @@
+//! Memory of the openings a bot lost.
+//!
+//! An [`AntiBook`] records the moves a player made in the early positions of
+//! the games it lost. A [`MinimaxBot`](crate::MinimaxBot) given one
+//! [with_anti_book](crate::MinimaxBot::with_anti_book) lowers the search
+//! score of those moves when it meets the same positions again, so that a
+//! long-running server does not keep walking into the same prepared trap.
+//! Like [`OpeningBook`](crate::OpeningBook)s, positions are indexed by their
+//! [canonical hash](GameY::canonical_hash), so a loss is remembered in all
+//! the rotations and reflections of its positions.
+
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "serde")]
+use std::path::Path;
+
+#[cfg(feature = "serde")]
+use crate::GameYError;
+use crate::{Coordinates, GameY, Movement, PlayerId};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Score taken off a move of an [`AntiBook`] for each game lost after
+/// playing it. Close to the margin of an
+/// [`OpeningVariety`](crate::OpeningVariety), so one loss makes the bot
+/// prefer a near-equal alternative without giving up a clearly better move.
+pub const ANTI_BOOK_PENALTY: i32 = 40;
+
+/// Number of plies from the start of a game that an [`AntiBook`] records by
+/// default.
+pub const ANTI_BOOK_PLIES: usize = 12;
+
+/// The moves a player made in the early positions of the games it lost,
+/// with the number of games lost after each.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{AntiBook, Coordinates, GameY, Movement, PlayerId};
+///
+/// let place = |game: &mut GameY, player, coords| {
+///     let player = PlayerId::new(player);
+///     game.add_move(Movement::Placement { player, coords }).unwrap();
+/// };
+/// let mut game = GameY::new(5);
+/// place(&mut game, 0, Coordinates::new(4, 0, 0));
+/// place(&mut game, 1, Coordinates::new(2, 1, 1));
+///
+/// let mut anti_book = AntiBook::new(5);
+/// anti_book.record_loss(&game, PlayerId::new(1));
+/// game.undo_move().unwrap();
+/// assert_eq!(anti_book.losing_moves(&game), vec![(Coordinates::new(2, 1, 1), 1)]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AntiBook {
+    board_size: u32,
+    plies: usize,
+    penalty: i32,
+    positions: HashMap<u64, BTreeMap<u32, u32>>,
+}
+
+impl AntiBook {
+    /// Creates an empty anti-book for boards of `board_size`, recording the
+    /// first [`ANTI_BOOK_PLIES`] plies of each lost game and penalising
+    /// their moves by [`ANTI_BOOK_PENALTY`] per loss.
+    pub fn new(board_size: u32) -> Self {
+        Self {
+            board_size,
+            plies: ANTI_BOOK_PLIES,
+            penalty: ANTI_BOOK_PENALTY,
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Sets the number of plies from the start of a game that are recorded.
+    pub fn with_plies(mut self, plies: usize) -> Self {
+        self.plies = plies;
+        self
+    }
+
+    /// Sets the score taken off a move for each game lost after playing it.
+    pub fn with_penalty(mut self, penalty: i32) -> Self {
+        self.penalty = penalty.max(0);
+        self
+    }
+
+    /// Returns the size of the boards the anti-book is for.
+    pub fn board_size(&self) -> u32 {
+        self.board_size
+    }
+
+    /// Returns the number of plies from the start of a game that are
+    /// recorded.
+    pub fn plies(&self) -> usize {
+        self.plies
+    }
+
+    /// Returns the score taken off a move for each game lost after playing
+    /// it.
+    pub fn penalty(&self) -> i32 {
+        self.penalty
+    }
+
+    /// Returns the number of positions in the anti-book.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if the anti-book has no position.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Records the stones `loser` placed in the first plies of `game`, and
+    /// returns how many were recorded.
+    ///
+    /// Nothing is recorded for games on another board size. The game does
+    /// not have to be over: a server may record a game abandoned by its
+    /// loser before the end.
+    pub fn record_loss(&mut self, game: &GameY, loser: PlayerId) -> usize {
+        if game.board_size() != self.board_size {
+            return 0;
+        }
+        let mut replay = game.cleared();
+        let mut recorded = 0;
+        for movement in game.history().iter().take(self.plies) {
+            if let Movement::Placement { player, coords } = movement
+                && *player == loser
+            {
+                let (hash, canonical_move) = self.canonical_entry(&replay, *coords);
+                let losses = self
+                    .positions
+                    .entry(hash)
+                    .or_default()
+                    .entry(canonical_move)
+                    .or_default();
+                *losses = losses.saturating_add(1);
+                recorded += 1;
+            }
+            if replay.add_move(movement.clone()).is_err() {
+                break;
+            }
+        }
+        recorded
+    }
+
+    /// Returns the moves from the position of `game` that were followed by
+    /// a loss, in its orientation, with the number of games lost after
+    /// each, most lost first. In a symmetric position, every image of a
+    /// lost move is returned.
+    pub fn losing_moves(&self, game: &GameY) -> Vec<(Coordinates, u32)> {
+        let size = game.board_size();
+        if size != self.board_size {
+            return Vec::new();
+        }
+        let (hash, symmetries) = game.canonical_position();
+        let Some(moves) = self.positions.get(&hash) else {
+            return Vec::new();
+        };
+        // A move of a symmetric position is lost in all its images
+        let mut moves: Vec<(Coordinates, u32)> = symmetries
+            .iter()
+            .flat_map(|symmetry| {
+                let to_game = symmetry.inverse();
+                moves
+                    .iter()
+                    .filter(|(index, _)| **index < game.total_cells())
+                    .map(move |(index, losses)| {
+                        let coords = to_game.transform(Coordinates::from_index(*index, size));
+                        (coords, *losses)
+                    })
+            })
+            // Guards against the unlikely collision of two position hashes
+            .filter(|(coords, _)| game.cell_owner(coords).is_none())
+            .collect();
+        moves.sort_by_key(|(coords, losses)| (std::cmp::Reverse(*losses), coords.to_index(size)));
+        moves.dedup();
+        moves
+    }
+
+    /// Returns the canonical hash of the position of `game` and the index of
+    /// the canonical image of `coords`.
+    fn canonical_entry(&self, game: &GameY, coords: Coordinates) -> (u64, u32) {
+        let (hash, symmetries) = game.canonical_position();
+        // Like in opening books, a symmetric position keeps the smallest
+        // image of the move
+        let canonical_move = symmetries
+            .iter()
+            .map(|symmetry| symmetry.transform(coords).to_index(self.board_size))
+            .min()
+            .expect("every position has a canonical orientation");
+        (hash, canonical_move)
+    }
+
+    /// Loads an anti-book saved with [`AntiBook::save_to_file`].
+    #[cfg(feature = "serde")]
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let filename = path.as_ref().display().to_string();
+        let content = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to read file: {}", filename),
+            error: e.to_string(),
+        })?;
+        serde_json::from_str(&content).map_err(|e| GameYError::SerdeError { error: e })
+    }
+
+    /// Saves the anti-book to a JSON file.
+    #[cfg(feature = "serde")]
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
+        let content =
+            serde_json::to_string(self).map_err(|e| GameYError::SerdeError { error: e })?;
+        let filename = path.as_ref().display().to_string();
+        std::fs::write(path, content).map_err(|e| GameYError::IoError {
+            message: format!("Failed to write file: {}", filename),
+            error: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(game: &mut GameY, player: u32, coords: Coordinates) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_losses_are_found_in_symmetric_positions() {
+        let mut game = GameY::new(5);
+        place(&mut game, 0, Coordinates::new(4, 0, 0));
+        place(&mut game, 1, Coordinates::new(2, 1, 1));
+        place(&mut game, 0, Coordinates::new(3, 1, 0));
+        let mut anti_book = AntiBook::new(5);
+        assert_eq!(anti_book.record_loss(&game, PlayerId::new(1)), 1);
+        assert_eq!(anti_book.record_loss(&game, PlayerId::new(1)), 1);
+
+        let mut mirrored = GameY::new(5);
+        place(&mut mirrored, 0, Coordinates::new(0, 4, 0));
+        assert_eq!(
+            anti_book.losing_moves(&mirrored),
+            vec![(Coordinates::new(1, 2, 1), 2)]
+        );
+        assert!(anti_book.losing_moves(&GameY::new(5)).is_empty());
+    }
+
+    #[test]
+    fn test_only_the_first_plies_are_recorded() {
+        let mut game = GameY::new(5);
+        place(&mut game, 0, Coordinates::new(4, 0, 0));
+        place(&mut game, 1, Coordinates::new(2, 1, 1));
+        place(&mut game, 0, Coordinates::new(3, 1, 0));
+        place(&mut game, 1, Coordinates::new(0, 4, 0));
+        let mut anti_book = AntiBook::new(5).with_plies(2);
+        assert_eq!(anti_book.record_loss(&game, PlayerId::new(1)), 1);
+        assert_eq!(anti_book.record_loss(&game, PlayerId::new(0)), 1);
+        assert_eq!(anti_book.len(), 2);
+        assert_eq!(AntiBook::new(4).record_loss(&game, PlayerId::new(1)), 0);
+    }
+}
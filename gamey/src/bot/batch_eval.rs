@@ -0,0 +1,220 @@
+//! Evaluation of many positions at once.
+//!
+//! Building a [`MinimaxState`] computes the adjacency, edge and centre caches
+//! of the board, which costs more than evaluating a position. A
+//! [`BatchEvaluator`] builds one state per worker and reuses it for every
+//! position of the batch, and can split the batch across threads.
+
+use std::num::NonZeroUsize;
+
+use crate::{EvalWeights, GameY, MinimaxState, PlayerId, Result, SearchLimits, search_scored};
+
+/// Evaluates batches of positions of the Game of Y.
+///
+/// Scores are from the point of view of player 0, so that positions with
+/// different players to move can be compared: positive scores favour
+/// player 0 and finished games score [`WIN_SCORE`](crate::WIN_SCORE) or
+/// [`LOSE_SCORE`](crate::LOSE_SCORE).
+///
+/// # Example
+///
+/// ```
+/// use gamey::{BatchEvaluator, GameY};
+///
+/// let games = vec![GameY::new(4), GameY::new(5)];
+/// let evaluator = BatchEvaluator::new().with_depth(2).with_threads(2);
+/// let scores = evaluator.evaluate(&games);
+/// assert_eq!(scores.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchEvaluator {
+    weights: EvalWeights,
+    depth: u8,
+    threads: NonZeroUsize,
+}
+
+impl Default for BatchEvaluator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatchEvaluator {
+    /// Creates an evaluator that returns the static evaluation, on the
+    /// calling thread.
+    pub fn new() -> Self {
+        Self {
+            weights: EvalWeights::default(),
+            depth: 0,
+            threads: NonZeroUsize::MIN,
+        }
+    }
+
+    /// Sets the weights of the static evaluation.
+    pub fn with_weights(mut self, weights: EvalWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Searches every position to `depth` plies instead of returning its
+    /// static evaluation. A depth of 0 is the static evaluation.
+    pub fn with_depth(mut self, depth: u8) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Splits batches across up to `threads` threads. 0 uses one thread per
+    /// available core.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = NonZeroUsize::new(threads)
+            .unwrap_or_else(|| std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN));
+        self
+    }
+
+    /// Returns the score of each game, in order.
+    pub fn evaluate(&self, games: &[GameY]) -> Vec<i32> {
+        let threads = self.threads.get().min(games.len());
+        if threads <= 1 {
+            return self.evaluate_chunk(games);
+        }
+        let chunk_size = games.len().div_ceil(threads);
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = games
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(|| self.evaluate_chunk(chunk)))
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("evaluation worker panicked"))
+                .collect()
+        })
+    }
+
+    /// Returns the score of each position, given as produced by
+    /// [`GameY::to_position_string`], in order.
+    ///
+    /// Fails on the first position that cannot be decoded.
+    pub fn evaluate_position_strings<S: AsRef<str>>(&self, positions: &[S]) -> Result<Vec<i32>> {
+        let games = positions
+            .iter()
+            .map(|position| GameY::from_position_string(position.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.evaluate(&games))
+    }
+
+    fn evaluate_chunk(&self, games: &[GameY]) -> Vec<i32> {
+        let mut state: Option<MinimaxState> = None;
+        games
+            .iter()
+            .map(|game| {
+                let to_move = game.next_player().unwrap_or(PlayerId::new(0));
+                let state = match &mut state {
+                    Some(state) => {
+                        state.set_position(game, to_move);
+                        state
+                    }
+                    None => {
+                        state.insert(MinimaxState::new(game, to_move).with_weights(self.weights))
+                    }
+                };
+                let score = self.score(state, game);
+                if to_move == PlayerId::new(0) {
+                    score
+                } else {
+                    -score
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the score of `state` for the player to move.
+    fn score(&self, state: &mut MinimaxState, game: &GameY) -> i32 {
+        if self.depth == 0 || game.check_game_over() {
+            return state.evaluate();
+        }
+        let limits = SearchLimits {
+            max_depth: Some(self.depth),
+            ..SearchLimits::default()
+        };
+        search_scored(state, &limits).map_or_else(|| state.evaluate(), |(_, score)| score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, LOSE_SCORE, Movement, WIN_SCORE};
+
+    fn game_with(size: u32, cells: &[u32]) -> GameY {
+        let mut game = GameY::new(size);
+        for (i, &idx) in cells.iter().enumerate() {
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(i as u32 % 2),
+                coords: Coordinates::from_index(idx, size),
+            })
+            .unwrap();
+        }
+        game
+    }
+
+    fn sample_games() -> Vec<GameY> {
+        vec![
+            game_with(4, &[]),
+            game_with(4, &[4]),
+            game_with(5, &[6, 0, 7]),
+            game_with(4, &[1, 5, 4]),
+            game_with(3, &[0, 1, 2, 3, 4]),
+        ]
+    }
+
+    #[test]
+    fn test_scores_match_single_evaluations() {
+        let games = sample_games();
+        let scores = BatchEvaluator::new().evaluate(&games);
+        for (game, score) in games.iter().zip(scores) {
+            let mut state = MinimaxState::new(game, PlayerId::new(0));
+            assert_eq!(score, state.evaluate());
+        }
+    }
+
+    #[test]
+    fn test_finished_games_score_the_winner() {
+        let game = game_with(2, &[0, 1, 2]);
+        let winner = game.result().unwrap().winner;
+        let score = BatchEvaluator::new().evaluate(std::slice::from_ref(&game))[0];
+        let expected = if winner == PlayerId::new(0) {
+            WIN_SCORE
+        } else {
+            LOSE_SCORE
+        };
+        assert_eq!(score, expected);
+    }
+
+    #[test]
+    fn test_threads_do_not_change_the_scores() {
+        let games = sample_games();
+        let evaluator = BatchEvaluator::new().with_depth(2);
+        let sequential = evaluator.evaluate(&games);
+        assert_eq!(
+            evaluator.clone().with_threads(3).evaluate(&games),
+            sequential
+        );
+        assert_eq!(evaluator.with_threads(0).evaluate(&games), sequential);
+    }
+
+    #[test]
+    fn test_position_strings() {
+        let games = sample_games();
+        let positions: Vec<String> = games.iter().map(GameY::to_position_string).collect();
+        let evaluator = BatchEvaluator::new();
+        assert_eq!(
+            evaluator.evaluate_position_strings(&positions).unwrap(),
+            evaluator.evaluate(&games)
+        );
+        assert!(
+            evaluator
+                .evaluate_position_strings(&["not a position"])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Bitboards of the game of Y for the minimax search.
+//!
+//! The cells are laid out row by row, each row `size + 1` bits after the
+//! previous one, so that the six neighbours of every cell sit at the same
+//! bit offsets: ±1 in its row, and ±(size + 1) and ±(size + 2) in the rows
+//! above and below. The bits past the end of each row are never set, which
+//! keeps shifts from wrapping from one row into the next. Growing a set of
+//! cells to its neighbours then takes a few shifts per word, and winning
+//! groups are found by a flood fill over whole words instead of a
+//! cell-by-cell search.
+
+use std::sync::Arc;
+
+use smallvec::SmallVec;
+
+/// A set of cells in the padded layout. Boards up to size 31 fit inline.
+type Words = SmallVec<[u64; 16]>;
+
+/// The padded layout of the cells of a board of Y, which only depends on
+/// its size.
+#[derive(Debug)]
+pub(crate) struct YLayout {
+    // Bit offsets of the neighbours of a cell
+    shifts: [isize; 3],
+    // Padded bit of each cell index
+    bits: Vec<u32>,
+    sides: [Words; 3],
+}
+
+impl YLayout {
+    /// Lays out a board of `size`, whose cells lie on the sides given by
+    /// `edge_labels`, one bit per side, by cell index.
+    pub(crate) fn new(size: u32, edge_labels: &[u8]) -> Self {
+        let width = size as usize + 1;
+        let empty: Words = SmallVec::from_elem(0, (size as usize * width).div_ceil(64).max(1));
+        let mut layout = Self {
+            shifts: [1, width as isize, width as isize + 1],
+            bits: Vec::with_capacity(edge_labels.len()),
+            sides: [empty.clone(), empty.clone(), empty],
+        };
+        for row in 0..size as usize {
+            for col in 0..=row {
+                layout.bits.push((row * width + col) as u32);
+            }
+        }
+        for (cell, &labels) in edge_labels.iter().enumerate() {
+            let bit = layout.bits[cell];
+            for (side, mask) in layout.sides.iter_mut().enumerate() {
+                if labels & (1 << side) != 0 {
+                    set_bit(mask, bit);
+                }
+            }
+        }
+        layout
+    }
+
+    fn words(&self) -> usize {
+        self.sides[0].len()
+    }
+}
+
+/// The stones of both players of a game of Y, as bitboards.
+#[derive(Debug, Clone)]
+pub(crate) struct YBitboard {
+    layout: Arc<YLayout>,
+    owned: [Words; 2],
+}
+
+impl YBitboard {
+    /// Creates an empty bitboard laid out by `layout`.
+    pub(crate) fn new(layout: Arc<YLayout>) -> Self {
+        let empty: Words = SmallVec::from_elem(0, layout.words());
+        Self {
+            layout,
+            owned: [empty.clone(), empty],
+        }
+    }
+
+    /// Places a stone of `player` (0 or 1) on `cell`.
+    pub(crate) fn insert(&mut self, player: usize, cell: usize) {
+        set_bit(&mut self.owned[player], self.layout.bits[cell]);
+    }
+
+    /// Removes the stone of `player` (0 or 1) from `cell`.
+    pub(crate) fn remove(&mut self, player: usize, cell: usize) {
+        let bit = self.layout.bits[cell];
+        self.owned[player][bit as usize / 64] &= !(1 << (bit % 64));
+    }
+
+    /// Removes every stone.
+    pub(crate) fn clear(&mut self) {
+        self.owned.iter_mut().for_each(|owned| owned.fill(0));
+    }
+
+    /// Returns true if a single group of stones of `player` (0 or 1) touches
+    /// all three sides.
+    pub(crate) fn connects_all_sides(&self, player: usize) -> bool {
+        let owned = &self.owned[player];
+        // Every winning group touches side A: grow each of them in turn
+        let mut starts: Words = owned
+            .iter()
+            .zip(&self.layout.sides[0])
+            .map(|(owned, side)| owned & side)
+            .collect();
+        let mut group: Words = SmallVec::from_elem(0, owned.len());
+        let mut grown = group.clone();
+        while let Some(word) = starts.iter().position(|&word| word != 0) {
+            group.fill(0);
+            group[word] = starts[word] & starts[word].wrapping_neg();
+            loop {
+                self.grow(&group, owned, &mut grown);
+                if grown == group {
+                    break;
+                }
+                std::mem::swap(&mut group, &mut grown);
+            }
+            if intersects(&group, &self.layout.sides[1])
+                && intersects(&group, &self.layout.sides[2])
+            {
+                return true;
+            }
+            for (start, cell) in starts.iter_mut().zip(&group) {
+                *start &= !cell;
+            }
+        }
+        false
+    }
+
+    /// Writes to `grown` the cells of `group` and their neighbours within
+    /// `owned`.
+    fn grow(&self, group: &Words, owned: &Words, grown: &mut Words) {
+        for (i, out) in grown.iter_mut().enumerate() {
+            let mut word = group[i];
+            for &shift in &self.layout.shifts {
+                word |= shifted_word(group, i, shift) | shifted_word(group, i, -shift);
+            }
+            *out = word & owned[i];
+        }
+    }
+}
+
+fn set_bit(words: &mut Words, bit: u32) {
+    words[bit as usize / 64] |= 1 << (bit % 64);
+}
+
+fn intersects(a: &Words, b: &Words) -> bool {
+    a.iter().zip(b).any(|(a, b)| a & b != 0)
+}
+
+/// Returns word `i` of `words` shifted by `shift` bits towards the higher
+/// bits, or towards the lower ones if `shift` is negative.
+fn shifted_word(words: &Words, i: usize, shift: isize) -> u64 {
+    let word = |index: isize| {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| words.get(index))
+            .copied()
+            .unwrap_or(0)
+    };
+    let (whole, part) = (shift.div_euclid(64), shift.rem_euclid(64) as u32);
+    let source = i as isize - whole;
+    if part == 0 {
+        word(source)
+    } else {
+        (word(source) << part) | (word(source - 1) >> (64 - part))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BoardTopology, ConnectEdges, GameY, WinCondition, seeded_rng};
+    use fixedbitset::FixedBitSet;
+    use rand::Rng;
+
+    fn bitboard_of(game: &GameY) -> YBitboard {
+        let labels: Vec<u8> = (0..game.num_cells())
+            .map(|cell| game.edge_labels(cell))
+            .collect();
+        YBitboard::new(Arc::new(YLayout::new(game.board_size(), &labels)))
+    }
+
+    #[test]
+    fn test_neighbours_are_fixed_offsets() {
+        let game = GameY::new(6);
+        let board = bitboard_of(&game);
+        for cell in 0..game.num_cells() {
+            let mut single: Words = SmallVec::from_elem(0, board.owned[0].len());
+            set_bit(&mut single, board.layout.bits[cell]);
+            let mut all: Words = SmallVec::from_elem(0, single.len());
+            (0..game.num_cells()).for_each(|cell| set_bit(&mut all, board.layout.bits[cell]));
+            let mut grown = single.clone();
+            board.grow(&single, &all, &mut grown);
+
+            let mut expected = single;
+            for neighbor in game.neighbors(cell) {
+                set_bit(&mut expected, board.layout.bits[neighbor]);
+            }
+            assert_eq!(grown, expected, "cell {}", cell);
+        }
+    }
+
+    #[test]
+    fn test_wins_match_the_connect_edges_rule() {
+        let rule = ConnectEdges::all_sides_of_y();
+        let mut rng = seeded_rng(5);
+        for size in [1, 2, 3, 7, 12, 40] {
+            let game = GameY::new(size);
+            let cells = game.num_cells();
+            let mut board = bitboard_of(&game);
+            for _ in 0..200 {
+                board.clear();
+                let mut owned = FixedBitSet::with_capacity(cells);
+                let density = rng.random_range(0.2..0.8);
+                for cell in 0..cells {
+                    if rng.random_bool(density) {
+                        owned.insert(cell);
+                        board.insert(1, cell);
+                    } else if rng.random_bool(0.5) {
+                        board.insert(0, cell);
+                    }
+                }
+                assert_eq!(
+                    board.connects_all_sides(1),
+                    rule.is_win(&game, &owned),
+                    "size {}",
+                    size
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_separate_groups_do_not_connect() {
+        // On size 3, cells 0 and 1 touch sides B and C, and the corner 5
+        // touches sides A and C: all three sides, but not as one group
+        let game = GameY::new(3);
+        let mut board = bitboard_of(&game);
+        for cell in [0, 1, 5] {
+            board.insert(0, cell);
+        }
+        assert!(!board.connects_all_sides(0));
+        board.insert(0, 2);
+        assert!(board.connects_all_sides(0));
+        board.remove(0, 2);
+        assert!(!board.connects_all_sides(0));
+    }
+}
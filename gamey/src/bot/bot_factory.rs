@@ -0,0 +1,469 @@
+//! Construction of bots from a type name and parameters.
+//!
+//! Protocol servers, tournament configurations and command lines refer to
+//! bots by a short string such as `minimax` with a few settings such as
+//! `depth=3`. A [`BotFactory`] maps each bot type name to a function that
+//! builds the bot from [`BotParams`]. The [built-in
+//! types](BotFactory::builtin) cover the bots of this crate, and downstream
+//! crates add their own with [`BotFactory::with_bot_type`].
+//!
+//! Unlike a [`YBotRegistry`](crate::YBotRegistry), which holds ready bots,
+//! a factory creates a new bot each time, so the same type can be played
+//! with different settings.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::{
+    ExternalEngineBot, GameYError, MinimaxBot, MirrorBot, OpeningVariety, RandomBot, Result,
+    Symmetry, TEMPERAMENT_MARGIN, Temperament, YBot,
+};
+
+/// Builds a bot from its parameters.
+pub type BotConstructor = Arc<dyn Fn(&BotParams) -> Result<Arc<dyn YBot>> + Send + Sync>;
+
+/// Time budget of `minimax` bots given neither `time_ms` nor `depth`.
+pub const DEFAULT_FACTORY_TIME_MS: u64 = 1000;
+
+/// Named settings of a bot to construct, as text.
+///
+/// # Example
+///
+/// ```
+/// use gamey::BotParams;
+///
+/// let params = BotParams::parse("depth=3, temperament=solid").unwrap();
+/// assert_eq!(params.get("depth"), Some("3"));
+/// assert_eq!(params, BotParams::new().with("depth", 3).with("temperament", "solid"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BotParams {
+    values: BTreeMap<String, String>,
+}
+
+impl BotParams {
+    /// Creates an empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses comma-separated `name=value` pairs. Spaces around names and
+    /// values are ignored, and an empty string gives no parameter.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut params = Self::new();
+        for pair in text
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            let (name, value) =
+                pair.split_once('=')
+                    .ok_or_else(|| GameYError::InvalidBotParameter {
+                        bot_type: String::new(),
+                        parameter: pair.to_string(),
+                        reason: "expected name=value".to_string(),
+                    })?;
+            params.set(name.trim(), value.trim());
+        }
+        Ok(params)
+    }
+
+    /// Sets the parameter `name` and returns the parameters for chaining.
+    pub fn with(mut self, name: &str, value: impl fmt::Display) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Sets the parameter `name`, replacing any earlier value.
+    pub fn set(&mut self, name: &str, value: impl fmt::Display) {
+        self.values.insert(name.to_string(), value.to_string());
+    }
+
+    /// Returns the value of the parameter `name`, if set.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// Returns the names of the parameters set, in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    /// Parses the parameter `name` of a bot of type `bot_type`.
+    ///
+    /// Returns None if the parameter is not set, and an
+    /// [`InvalidBotParameter`](GameYError::InvalidBotParameter) error if its
+    /// value does not parse.
+    pub fn parse_value<T>(&self, bot_type: &str, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.get(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|e: T::Err| GameYError::InvalidBotParameter {
+                        bot_type: bot_type.to_string(),
+                        parameter: name.to_string(),
+                        reason: e.to_string(),
+                    })
+            })
+            .transpose()
+    }
+
+    /// Fails with an [`InvalidBotParameter`](GameYError::InvalidBotParameter)
+    /// error on the first parameter not in `known`, so that misspelt
+    /// settings are not silently ignored.
+    pub fn check_known(&self, bot_type: &str, known: &[&str]) -> Result<()> {
+        match self.names().find(|name| !known.contains(name)) {
+            Some(name) => Err(GameYError::InvalidBotParameter {
+                bot_type: bot_type.to_string(),
+                parameter: name.to_string(),
+                reason: format!("unknown parameter, expected one of: {}", known.join(", ")),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for BotParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, value)) in self.values.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Creates bots from a type name and [`BotParams`].
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use gamey::{BotFactory, BotParams, RandomBot, YBot};
+///
+/// let factory = BotFactory::builtin().with_bot_type("lucky", |params: &BotParams| {
+///     let seed = params.parse_value("lucky", "seed")?.unwrap_or(7);
+///     Ok(Arc::new(RandomBot::with_seed(seed)) as Arc<dyn YBot>)
+/// });
+/// let bot = factory
+///     .create("minimax", &BotParams::parse("depth=2").unwrap())
+///     .unwrap();
+/// assert_eq!(bot.name(), "minimax_bot");
+/// assert!(factory.create("lucky", &BotParams::new()).is_ok());
+/// assert!(factory.create("mcts", &BotParams::new()).is_err());
+/// ```
+#[derive(Clone, Default)]
+pub struct BotFactory {
+    constructors: HashMap<String, BotConstructor>,
+}
+
+impl BotFactory {
+    /// Creates a factory that knows no bot type.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a factory that knows the bot types of this crate:
+    ///
+    /// - `external`: an [`ExternalEngineBot`] running the program `command`,
+    ///   started with the space-separated `args`, with an optional `name`,
+    ///   `timeout_ms` and number of `restarts`.
+    /// - `minimax`: a [`MinimaxBot`] with the parameters `time_ms`, `depth`,
+    ///   `max_nodes`, `temperament` (`solid`, `balanced` or `aggressive`)
+    ///   and `table_entries`. With `depth` but no `time_ms` the bot searches
+    ///   to that fixed depth; with neither it thinks for
+    ///   [`DEFAULT_FACTORY_TIME_MS`]. `variety_moves` and `variety_margin`
+    ///   (by default [`TEMPERAMENT_MARGIN`]) set an [`OpeningVariety`], and
+    ///   `seed` reseeds its draws.
+    /// - `mirror`: a [`MirrorBot`], with an optional `symmetry`
+    ///   (`reflect_ab`, the default, `reflect_bc`, `reflect_ac`, `rotate120`
+    ///   or `rotate240`).
+    /// - `random`: a [`RandomBot`], with an optional `seed`.
+    /// - `rl`: an [`RlBot`](crate::RlBot) playing by the value table saved at
+    ///   the path `table` (with the `serde` feature).
+    pub fn builtin() -> Self {
+        let factory = Self::new()
+            .with_bot_type("external", create_external)
+            .with_bot_type("minimax", create_minimax)
+            .with_bot_type("mirror", create_mirror)
+            .with_bot_type("random", create_random);
+        #[cfg(feature = "serde")]
+        let factory = factory.with_bot_type("rl", create_rl);
+        factory
+    }
+
+    /// Registers the bot type `name`, built by `constructor`, and returns
+    /// the factory for chaining. A type registered under a taken name
+    /// replaces the earlier one.
+    pub fn with_bot_type<F>(mut self, name: &str, constructor: F) -> Self
+    where
+        F: Fn(&BotParams) -> Result<Arc<dyn YBot>> + Send + Sync + 'static,
+    {
+        self.constructors
+            .insert(name.to_string(), Arc::new(constructor));
+        self
+    }
+
+    /// Creates a bot of type `name` from `params`.
+    ///
+    /// Fails with [`UnknownBotType`](GameYError::UnknownBotType) if no such
+    /// type is registered, or with the error of its constructor.
+    pub fn create(&self, name: &str, params: &BotParams) -> Result<Arc<dyn YBot>> {
+        let constructor =
+            self.constructors
+                .get(name)
+                .ok_or_else(|| GameYError::UnknownBotType {
+                    name: name.to_string(),
+                })?;
+        constructor(params)
+    }
+
+    /// Returns the names of the registered bot types, in alphabetical order.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.constructors.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+impl fmt::Debug for BotFactory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BotFactory")
+            .field("bot_types", &self.names())
+            .finish()
+    }
+}
+
+/// Creates a bot of one of the [built-in types](BotFactory::builtin).
+///
+/// # Example
+///
+/// ```
+/// use gamey::{BotParams, GameY, create_bot};
+///
+/// let bot = create_bot("random", &BotParams::new().with("seed", 3)).unwrap();
+/// assert!(bot.choose_move(&GameY::new(5)).is_some());
+/// ```
+pub fn create_bot(name: &str, params: &BotParams) -> Result<Arc<dyn YBot>> {
+    BotFactory::builtin().create(name, params)
+}
+
+fn create_minimax(params: &BotParams) -> Result<Arc<dyn YBot>> {
+    const TYPE: &str = "minimax";
+    params.check_known(
+        TYPE,
+        &[
+            "time_ms",
+            "depth",
+            "max_nodes",
+            "temperament",
+            "table_entries",
+            "variety_moves",
+            "variety_margin",
+            "seed",
+        ],
+    )?;
+    let depth = params.parse_value::<u8>(TYPE, "depth")?;
+    let mut bot = match (params.parse_value(TYPE, "time_ms")?, depth) {
+        (Some(time_ms), Some(depth)) => MinimaxBot::new(time_ms).with_max_depth(depth),
+        (Some(time_ms), None) => MinimaxBot::new(time_ms),
+        (None, Some(depth)) => MinimaxBot::with_depth(depth),
+        (None, None) => MinimaxBot::new(DEFAULT_FACTORY_TIME_MS),
+    };
+    if let Some(max_nodes) = params.parse_value(TYPE, "max_nodes")? {
+        bot = bot.with_max_nodes(max_nodes);
+    }
+    if let Some(temperament) = params.get("temperament") {
+        let temperament = match temperament {
+            "solid" => Temperament::Solid,
+            "balanced" => Temperament::Balanced,
+            "aggressive" => Temperament::Aggressive,
+            other => {
+                return Err(GameYError::InvalidBotParameter {
+                    bot_type: TYPE.to_string(),
+                    parameter: "temperament".to_string(),
+                    reason: format!("unknown temperament '{}'", other),
+                });
+            }
+        };
+        bot = bot.with_temperament(temperament);
+    }
+    if let Some(entries) = params.parse_value(TYPE, "table_entries")? {
+        bot = bot.with_table_entries(entries);
+    }
+    if let Some(moves) = params.parse_value(TYPE, "variety_moves")? {
+        let margin = params
+            .parse_value(TYPE, "variety_margin")?
+            .unwrap_or(TEMPERAMENT_MARGIN);
+        bot = bot.with_opening_variety(OpeningVariety::new(moves, margin));
+    }
+    if let Some(seed) = params.parse_value(TYPE, "seed")? {
+        bot.reseed(seed);
+    }
+    Ok(Arc::new(bot))
+}
+
+fn create_external(params: &BotParams) -> Result<Arc<dyn YBot>> {
+    const TYPE: &str = "external";
+    params.check_known(TYPE, &["command", "args", "name", "timeout_ms", "restarts"])?;
+    let command = params
+        .get("command")
+        .ok_or_else(|| GameYError::InvalidBotParameter {
+            bot_type: TYPE.to_string(),
+            parameter: "command".to_string(),
+            reason: "the program of the engine is required".to_string(),
+        })?;
+    let mut bot = ExternalEngineBot::new(command)
+        .with_args(params.get("args").unwrap_or_default().split_whitespace());
+    if let Some(name) = params.get("name") {
+        bot = bot.with_name(name);
+    }
+    if let Some(timeout_ms) = params.parse_value(TYPE, "timeout_ms")? {
+        bot = bot.with_timeout_ms(timeout_ms);
+    }
+    if let Some(restarts) = params.parse_value(TYPE, "restarts")? {
+        bot = bot.with_restarts(restarts);
+    }
+    Ok(Arc::new(bot))
+}
+
+fn create_mirror(params: &BotParams) -> Result<Arc<dyn YBot>> {
+    const TYPE: &str = "mirror";
+    params.check_known(TYPE, &["symmetry"])?;
+    let symmetry = match params.get("symmetry") {
+        None | Some("reflect_ab") => Symmetry::ReflectAB,
+        Some("reflect_bc") => Symmetry::ReflectBC,
+        Some("reflect_ac") => Symmetry::ReflectAC,
+        Some("rotate120") => Symmetry::Rotate120,
+        Some("rotate240") => Symmetry::Rotate240,
+        Some(other) => {
+            return Err(GameYError::InvalidBotParameter {
+                bot_type: TYPE.to_string(),
+                parameter: "symmetry".to_string(),
+                reason: format!("unknown symmetry '{}'", other),
+            });
+        }
+    };
+    Ok(Arc::new(MirrorBot::new(symmetry)))
+}
+
+fn create_random(params: &BotParams) -> Result<Arc<dyn YBot>> {
+    params.check_known("random", &["seed"])?;
+    let bot = match params.parse_value("random", "seed")? {
+        Some(seed) => RandomBot::with_seed(seed),
+        None => RandomBot::new(),
+    };
+    Ok(Arc::new(bot))
+}
+
+#[cfg(feature = "serde")]
+fn create_rl(params: &BotParams) -> Result<Arc<dyn YBot>> {
+    params.check_known("rl", &["table"])?;
+    let path = params
+        .get("table")
+        .ok_or_else(|| GameYError::InvalidBotParameter {
+            bot_type: "rl".to_string(),
+            parameter: "table".to_string(),
+            reason: "the path of a value table is required".to_string(),
+        })?;
+    let table = crate::ValueTable::load_from_file(path)?;
+    Ok(Arc::new(crate::RlBot::new(table)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params_parse_and_display() {
+        let params = BotParams::parse(" time_ms = 200 ,depth=3,").unwrap();
+        assert_eq!(params.get("time_ms"), Some("200"));
+        assert_eq!(params.to_string(), "depth=3,time_ms=200");
+        assert_eq!(BotParams::parse(&params.to_string()).unwrap(), params);
+        assert_eq!(BotParams::parse("").unwrap(), BotParams::new());
+        assert!(BotParams::parse("depth").is_err());
+    }
+
+    #[test]
+    fn test_minimax_bots_follow_their_params() {
+        let factory = BotFactory::builtin();
+        let bot = factory
+            .create("minimax", &BotParams::parse("depth=2").unwrap())
+            .unwrap();
+        assert_eq!(bot.name(), "minimax_bot");
+        let game = crate::GameY::new(4);
+        assert_eq!(
+            bot.choose_move(&game),
+            MinimaxBot::with_depth(2).choose_move(&game)
+        );
+        assert!(
+            factory
+                .create(
+                    "minimax",
+                    &BotParams::parse("time_ms=50,temperament=solid").unwrap()
+                )
+                .is_ok()
+        );
+        let params = BotParams::parse("depth=2,variety_moves=2,variety_margin=40,seed=5").unwrap();
+        let varied = factory.create("minimax", &params).unwrap();
+        let expected = MinimaxBot::with_depth(2).with_opening_variety(OpeningVariety::new(2, 40));
+        expected.reseed(5);
+        assert_eq!(varied.choose_move(&game), expected.choose_move(&game));
+    }
+
+    #[test]
+    fn test_bad_params_are_rejected() {
+        let factory = BotFactory::builtin();
+        let create = |name, params| factory.create(name, &BotParams::parse(params).unwrap());
+        assert!(matches!(
+            create("minimax", "depth=deep"),
+            Err(GameYError::InvalidBotParameter { parameter, .. }) if parameter == "depth"
+        ));
+        assert!(matches!(
+            create("minimax", "temperament=reckless"),
+            Err(GameYError::InvalidBotParameter { .. })
+        ));
+        assert!(matches!(
+            create("mirror", "symmetry=identity"),
+            Err(GameYError::InvalidBotParameter { parameter, .. }) if parameter == "symmetry"
+        ));
+        assert!(matches!(
+            create("random", "sed=3"),
+            Err(GameYError::InvalidBotParameter { parameter, .. }) if parameter == "sed"
+        ));
+        assert!(matches!(
+            create("external", "args=--quiet"),
+            Err(GameYError::InvalidBotParameter { parameter, .. }) if parameter == "command"
+        ));
+        assert!(matches!(
+            create("mcts", ""),
+            Err(GameYError::UnknownBotType { name }) if name == "mcts"
+        ));
+    }
+
+    #[test]
+    fn test_downstream_bot_types_are_registered() {
+        let factory = BotFactory::new().with_bot_type("seeded", |params: &BotParams| {
+            let seed = params.parse_value("seeded", "seed")?.unwrap_or(1);
+            Ok(Arc::new(RandomBot::with_seed(seed)) as Arc<dyn YBot>)
+        });
+        assert_eq!(factory.names(), vec!["seeded".to_string()]);
+        let game = crate::GameY::new(5);
+        let bot = factory
+            .create("seeded", &BotParams::new().with("seed", 9))
+            .unwrap();
+        assert_eq!(
+            bot.choose_move(&game),
+            RandomBot::with_seed(9).choose_move(&game)
+        );
+        assert!(factory.create("minimax", &BotParams::new()).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+//! Minimax bot settings read from TOML profiles.
+//!
+//! A profile file holds any number of named profiles, each in its own
+//! `[profiles.<name>]` table. Every key is optional and falls back to the
+//! default of [`MinimaxBot`]:
+//!
+//! ```toml
+//! [profiles.aggressive]
+//! time_ms = 2000
+//! max_depth = 8
+//! temperament = "aggressive"
+//!
+//! [profiles.aggressive.weights]
+//! connection = 40
+//! center = 2.5
+//!
+//! [profiles.big_boards.widening]
+//! start_depth = 3
+//! root_moves = 12
+//! ```
+//!
+//! The built-in presets `medium`, `hard` and `expert` match the difficulties
+//! of `gamey-cli` and can be overridden by a profile of the same name.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::{EvalWeights, GameYError, MinimaxBot, Result, Temperament, Widening};
+
+/// Settings of a [`MinimaxBot`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BotProfile {
+    /// Time the bot may think per move, in milliseconds.
+    pub time_ms: u64,
+    /// Maximum depth of the search, in plies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<u8>,
+    /// Maximum number of nodes visited per move.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_nodes: Option<u64>,
+    /// Progressive widening of the deeper iterations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub widening: Option<Widening>,
+    /// How the bot chooses between near-equal moves.
+    pub temperament: Temperament,
+    /// Weights of the static evaluation.
+    pub weights: EvalWeights,
+}
+
+impl Default for BotProfile {
+    fn default() -> Self {
+        Self {
+            time_ms: 1000,
+            max_depth: None,
+            max_nodes: None,
+            widening: None,
+            temperament: Temperament::Balanced,
+            weights: EvalWeights::default(),
+        }
+    }
+}
+
+impl BotProfile {
+    /// Names of the built-in presets.
+    pub const PRESETS: [&'static str; 3] = ["medium", "hard", "expert"];
+
+    /// Returns the built-in preset called `name`, if there is one.
+    pub fn preset(name: &str) -> Option<BotProfile> {
+        let time_ms = match name {
+            "medium" => 200,
+            "hard" => 1000,
+            "expert" => 5000,
+            _ => return None,
+        };
+        Some(BotProfile {
+            time_ms,
+            ..BotProfile::default()
+        })
+    }
+
+    /// Creates a bot with these settings.
+    pub fn build(&self) -> MinimaxBot {
+        let mut bot = MinimaxBot::new(self.time_ms)
+            .with_weights(self.weights)
+            .with_temperament(self.temperament);
+        if let Some(max_depth) = self.max_depth {
+            bot = bot.with_max_depth(max_depth);
+        }
+        if let Some(max_nodes) = self.max_nodes {
+            bot = bot.with_max_nodes(max_nodes);
+        }
+        if let Some(widening) = self.widening {
+            bot = bot.with_widening(widening);
+        }
+        bot
+    }
+}
+
+/// A set of named bot profiles, usually read from a TOML file.
+///
+/// # Example
+///
+/// ```
+/// use gamey::BotProfiles;
+///
+/// let profiles = BotProfiles::from_toml_str(
+///     "[profiles.quick]\ntime_ms = 50\nmax_depth = 3\n",
+/// )
+/// .unwrap();
+/// let bot = profiles.get("quick").unwrap().build();
+/// assert_eq!(bot.max_time_ms(), Some(50));
+/// assert!(profiles.get("expert").is_ok());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BotProfiles {
+    #[serde(default)]
+    profiles: BTreeMap<String, BotProfile>,
+}
+
+impl BotProfiles {
+    /// Creates a set holding only the built-in presets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses profiles from the contents of a TOML file.
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        toml::from_str(content).map_err(|e| GameYError::InvalidBotProfile {
+            reason: e.to_string(),
+        })
+    }
+
+    /// Reads profiles from a TOML file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let filename = path.as_ref().display().to_string();
+        let content = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to read file: {}", filename),
+            error: e.to_string(),
+        })?;
+        Self::from_toml_str(&content)
+    }
+
+    /// Adds a profile, replacing any previous profile called `name`.
+    pub fn insert(&mut self, name: impl Into<String>, profile: BotProfile) {
+        self.profiles.insert(name.into(), profile);
+    }
+
+    /// Returns the profile called `name`, falling back to the built-in
+    /// presets.
+    pub fn get(&self, name: &str) -> Result<BotProfile> {
+        self.profiles
+            .get(name)
+            .cloned()
+            .or_else(|| BotProfile::preset(name))
+            .ok_or_else(|| GameYError::InvalidBotProfile {
+                reason: format!(
+                    "Unknown profile '{}', available: {}",
+                    name,
+                    self.names().join(", ")
+                ),
+            })
+    }
+
+    /// Returns the names of all profiles, including the built-in presets.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.keys().cloned().collect();
+        for preset in BotProfile::PRESETS {
+            if !self.profiles.contains_key(preset) {
+                names.push(preset.to_string());
+            }
+        }
+        names.sort();
+        names
+    }
+
+    /// Serializes the profiles to TOML.
+    pub fn to_toml_string(&self) -> Result<String> {
+        toml::to_string(self).map_err(|e| GameYError::InvalidBotProfile {
+            reason: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_keys_use_defaults() {
+        let profiles = BotProfiles::from_toml_str(
+            "[profiles.light]\ntime_ms = 300\n\n[profiles.light.weights]\nedge = 9\n",
+        )
+        .unwrap();
+        let profile = profiles.get("light").unwrap();
+        assert_eq!(profile.time_ms, 300);
+        assert_eq!(profile.max_depth, None);
+        assert_eq!(profile.widening, None);
+        assert_eq!(profile.weights.edge, 9);
+        assert_eq!(
+            profile.weights.connection,
+            EvalWeights::default().connection
+        );
+    }
+
+    #[test]
+    fn test_build_applies_limits_and_weights() {
+        let profile = BotProfile {
+            time_ms: 100,
+            max_depth: Some(4),
+            max_nodes: Some(10_000),
+            widening: Some(Widening::new(3, 8)),
+            temperament: Temperament::Solid,
+            weights: EvalWeights {
+                center: 1.5,
+                ..EvalWeights::default()
+            },
+        };
+        let bot = profile.build();
+        let limits = bot.limits();
+        assert_eq!(limits.max_time_ms, Some(100));
+        assert_eq!(limits.max_depth, Some(4));
+        assert_eq!(limits.max_nodes, Some(10_000));
+        assert_eq!(limits.widening, Some(Widening::new(3, 8)));
+        assert_eq!(bot.temperament(), Temperament::Solid);
+        assert_eq!(bot.weights().center, 1.5);
+    }
+
+    #[test]
+    fn test_file_profiles_override_presets() {
+        let profiles = BotProfiles::from_toml_str("[profiles.expert]\ntime_ms = 7000\n").unwrap();
+        assert_eq!(profiles.get("expert").unwrap().time_ms, 7000);
+        assert_eq!(profiles.get("hard").unwrap().time_ms, 1000);
+        assert_eq!(profiles.names(), ["expert", "hard", "medium"]);
+    }
+
+    #[test]
+    fn test_rejects_unknown_keys_and_profiles() {
+        assert!(matches!(
+            BotProfiles::from_toml_str("[profiles.typo]\ntime = 10\n"),
+            Err(GameYError::InvalidBotProfile { .. })
+        ));
+        assert!(matches!(
+            BotProfiles::new().get("missing"),
+            Err(GameYError::InvalidBotProfile { .. })
+        ));
+    }
+
+    #[test]
+    fn test_toml_roundtrip() {
+        let mut profiles = BotProfiles::new();
+        profiles.insert(
+            "deep",
+            BotProfile {
+                max_depth: Some(10),
+                ..BotProfile::default()
+            },
+        );
+        let text = profiles.to_toml_string().unwrap();
+        assert_eq!(BotProfiles::from_toml_str(&text).unwrap(), profiles);
+    }
+}
@@ -0,0 +1,550 @@
+//! Automatic tuning of the [`EvalWeights`] of the minimax bot.
+//!
+//! Two tuners are provided:
+//!
+//! - [`TexelTuner`] fits the weights to the results of recorded games: every
+//!   position is labelled with the final result and the weights are moved
+//!   until a logistic function of the evaluation predicts those results as
+//!   well as possible. It is fast and needs only a set of games, for example
+//!   from self-play.
+//! - [`SpsaTuner`] tunes the weights by playing matches between two slightly
+//!   different bots and moving the weights towards the winner (simultaneous
+//!   perturbation stochastic approximation). It optimizes playing strength
+//!   directly, at the cost of many games.
+
+use rand::Rng;
+use rand::prelude::IndexedRandom;
+
+use crate::{
+    Coordinates, EvalFeatures, EvalWeights, GameRng, GameY, MinimaxBot, MinimaxState, Movement,
+    PlayerId, Result, YBot, derive_seed, random_seed, seeded_rng,
+};
+
+/// Step of each weight in the local search of [`TexelTuner::tune`].
+const TEXEL_STEPS: [f64; 4] = [1.0, 1.0, 1.0, 0.5];
+
+/// A position of a finished game with the result it led to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabeledPosition {
+    /// The terms of the evaluation, from the point of view of player 0.
+    pub features: EvalFeatures,
+    /// 1.0 if player 0 won the game, 0.0 otherwise.
+    pub result: f64,
+}
+
+/// The outcome of [`TexelTuner::tune`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TexelReport {
+    /// The tuned weights.
+    pub weights: EvalWeights,
+    /// Scale of the logistic function mapping evaluations to results.
+    pub scale: f64,
+    /// Mean squared error of the initial weights.
+    pub initial_error: f64,
+    /// Mean squared error of the tuned weights.
+    pub final_error: f64,
+    /// Number of passes over the weights.
+    pub iterations: usize,
+}
+
+/// Texel-style tuning: a logistic fit of the weights to game results.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{EvalWeights, MinimaxBot, TexelTuner};
+///
+/// let bot = MinimaxBot::new(1000).with_max_depth(1);
+/// let mut tuner = TexelTuner::new().with_max_iterations(5);
+/// tuner.add_self_play(&bot, 4, 4, 2, 7).unwrap();
+/// let report = tuner.tune(EvalWeights::default());
+/// assert!(report.final_error <= report.initial_error);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TexelTuner {
+    positions: Vec<LabeledPosition>,
+    max_iterations: usize,
+    scale: Option<f64>,
+}
+
+impl Default for TexelTuner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TexelTuner {
+    /// Creates a tuner without positions.
+    pub fn new() -> Self {
+        Self {
+            positions: Vec::new(),
+            max_iterations: 100,
+            scale: None,
+        }
+    }
+
+    /// Caps the number of passes over the weights (100 by default).
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Uses `scale` for the logistic function instead of fitting it to the
+    /// initial weights.
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    /// Returns the positions collected so far.
+    pub fn positions(&self) -> &[LabeledPosition] {
+        &self.positions
+    }
+
+    /// Adds a labelled position.
+    pub fn add_position(&mut self, features: EvalFeatures, result: f64) {
+        self.positions.push(LabeledPosition { features, result });
+    }
+
+    /// Adds every position of `game` that is not over, labelled with its
+    /// result, and returns how many were added. Games without a result add
+    /// nothing.
+    pub fn add_game(&mut self, game: &GameY) -> Result<usize> {
+        let Some(result) = game.result() else {
+            return Ok(0);
+        };
+        let label = if result.winner == PlayerId::new(0) {
+            1.0
+        } else {
+            0.0
+        };
+        let mut position = game.cleared();
+        let mut added = 0;
+        for movement in game.history() {
+            if position.next_player().is_some() {
+                let state = MinimaxState::new(&position, PlayerId::new(0));
+                self.add_position(state.eval_features(), label);
+                added += 1;
+            }
+            position.replay_move(movement.clone())?;
+        }
+        Ok(added)
+    }
+
+    /// Plays `games` games of `bot` against itself and adds their positions.
+    ///
+    /// The first `random_plies` moves of each game are random so that the
+    /// games differ; positions before them are not added. The games are the
+    /// same for the same `seed`. Returns the number of positions added.
+    pub fn add_self_play(
+        &mut self,
+        bot: &MinimaxBot,
+        board_size: u32,
+        games: usize,
+        random_plies: usize,
+        seed: u64,
+    ) -> Result<usize> {
+        let mut added = 0;
+        for index in 0..games {
+            let mut rng = seeded_rng(derive_seed(seed, index as u64));
+            let game = play_game([bot, bot], board_size, random_plies, &mut rng)?;
+            let before = self.positions.len();
+            self.add_game(&game)?;
+            let opening = random_plies.min(self.positions.len() - before);
+            self.positions.drain(before..before + opening);
+            added += self.positions.len() - before;
+        }
+        Ok(added)
+    }
+
+    /// Returns the mean squared error between the results and the
+    /// predictions of `weights`, with the logistic function of `scale`.
+    pub fn error(&self, weights: &EvalWeights, scale: f64) -> f64 {
+        if self.positions.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self
+            .positions
+            .iter()
+            .map(|position| {
+                let predicted = sigmoid(scale * position.features.score(weights));
+                (position.result - predicted).powi(2)
+            })
+            .sum();
+        total / self.positions.len() as f64
+    }
+
+    /// Returns the scale of the logistic function that best predicts the
+    /// results with `weights`.
+    pub fn fit_scale(&self, weights: &EvalWeights) -> f64 {
+        fit_logistic_scale(|scale| self.error(weights, scale))
+    }
+
+    /// Tunes `initial` by local search: each weight in turn is moved one step
+    /// up or down while that lowers the error, until a whole pass over the
+    /// weights changes nothing or the iteration cap is reached.
+    pub fn tune(&self, initial: EvalWeights) -> TexelReport {
+        let scale = self.scale.unwrap_or_else(|| self.fit_scale(&initial));
+        let initial_error = self.error(&initial, scale);
+        let mut params = to_params(&initial);
+        let mut best_error = initial_error;
+        let mut iterations = 0;
+
+        while iterations < self.max_iterations {
+            iterations += 1;
+            let mut improved = false;
+            for (i, step) in TEXEL_STEPS.iter().enumerate() {
+                for direction in [1.0, -1.0] {
+                    let mut candidate = params;
+                    candidate[i] += direction * step;
+                    let error = self.error(&from_params(&candidate), scale);
+                    if error < best_error {
+                        params = candidate;
+                        best_error = error;
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+
+        TexelReport {
+            weights: from_params(&params),
+            scale,
+            initial_error,
+            final_error: best_error,
+            iterations,
+        }
+    }
+}
+
+/// The state of [`SpsaTuner`] after an iteration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpsaIteration {
+    /// Number of the iteration, from 0.
+    pub iteration: usize,
+    /// The weights after the iteration.
+    pub weights: EvalWeights,
+    /// Score of the match of the iteration for the perturbed weights moved
+    /// up, from -1.0 (lost every game) to 1.0 (won every game).
+    pub score: f64,
+}
+
+/// SPSA tuning: the weights follow the results of matches between bots whose
+/// weights are perturbed in opposite directions.
+///
+/// Weights are perturbed and moved in proportion to their initial magnitude,
+/// so that small and large weights are tuned at the same relative pace.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{EvalWeights, SpsaTuner};
+///
+/// let tuner = SpsaTuner::new(4)
+///     .with_iterations(2)
+///     .with_depth(1)
+///     .with_seed(3);
+/// let tuned = tuner.run(EvalWeights::default()).unwrap();
+/// assert_eq!(tuned, tuner.run(EvalWeights::default()).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpsaTuner {
+    board_size: u32,
+    iterations: usize,
+    games_per_iteration: usize,
+    depth: u8,
+    random_plies: usize,
+    learning_rate: f64,
+    perturbation: f64,
+    seed: Option<u64>,
+}
+
+impl SpsaTuner {
+    /// Creates a tuner that plays its matches on boards of `board_size`.
+    pub fn new(board_size: u32) -> Self {
+        Self {
+            board_size,
+            iterations: 100,
+            games_per_iteration: 4,
+            depth: 2,
+            random_plies: 2,
+            learning_rate: 0.02,
+            perturbation: 0.1,
+            seed: None,
+        }
+    }
+
+    /// Sets the number of iterations (100 by default).
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets the number of games of each match, rounded up to an even number
+    /// so that both bots play first equally often (4 by default).
+    pub fn with_games_per_iteration(mut self, games: usize) -> Self {
+        self.games_per_iteration = games.max(1).next_multiple_of(2);
+        self
+    }
+
+    /// Sets the depth the bots search to (2 by default).
+    pub fn with_depth(mut self, depth: u8) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Sets the number of random moves opening each game (2 by default).
+    pub fn with_random_plies(mut self, random_plies: usize) -> Self {
+        self.random_plies = random_plies;
+        self
+    }
+
+    /// Sets the initial step size, relative to the magnitude of each weight
+    /// (0.02 by default).
+    pub fn with_learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Sets the initial perturbation, relative to the magnitude of each
+    /// weight (0.1 by default).
+    pub fn with_perturbation(mut self, perturbation: f64) -> Self {
+        self.perturbation = perturbation;
+        self
+    }
+
+    /// Makes the run reproducible: the same seed plays the same games.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Tunes `initial` and returns the tuned weights.
+    pub fn run(&self, initial: EvalWeights) -> Result<EvalWeights> {
+        self.run_with(initial, &mut |_| {})
+    }
+
+    /// Tunes `initial`, calling `on_iteration` after every iteration.
+    pub fn run_with(
+        &self,
+        initial: EvalWeights,
+        on_iteration: &mut dyn FnMut(&SpsaIteration),
+    ) -> Result<EvalWeights> {
+        let seed = self.seed.unwrap_or_else(random_seed);
+        let mut rng = seeded_rng(seed);
+        let magnitudes = to_params(&initial).map(|param| param.abs().max(1.0));
+        // Weights are tuned relative to their magnitude
+        let mut theta = [1.0; 4];
+        let stability = self.iterations as f64 / 10.0;
+
+        for iteration in 0..self.iterations {
+            let k = iteration as f64 + 1.0;
+            let step = self.learning_rate / (k + stability).powf(0.602);
+            let spread = self.perturbation / k.powf(0.101);
+            let delta: [f64; 4] =
+                std::array::from_fn(|_| if rng.random::<bool>() { 1.0 } else { -1.0 });
+
+            let weights_at = |sign: f64| {
+                let params =
+                    std::array::from_fn(|i| (theta[i] + sign * spread * delta[i]) * magnitudes[i]);
+                from_params(&params)
+            };
+            let plus = self.bot(weights_at(1.0));
+            let minus = self.bot(weights_at(-1.0));
+
+            let mut balance = 0i32;
+            for game in 0..self.games_per_iteration {
+                let mut game_rng =
+                    seeded_rng(derive_seed(seed, ((iteration as u64) << 32) | game as u64));
+                let plus_first = game % 2 == 0;
+                let bots = if plus_first {
+                    [&plus, &minus]
+                } else {
+                    [&minus, &plus]
+                };
+                let played = play_game(bots, self.board_size, self.random_plies, &mut game_rng)?;
+                let first_won = played
+                    .result()
+                    .is_some_and(|result| result.winner == PlayerId::new(0));
+                balance += if first_won == plus_first { 1 } else { -1 };
+            }
+            let score = balance as f64 / self.games_per_iteration as f64;
+
+            for i in 0..theta.len() {
+                theta[i] += step * score / (2.0 * spread * delta[i]);
+            }
+            on_iteration(&SpsaIteration {
+                iteration,
+                weights: from_params(&std::array::from_fn(|i| theta[i] * magnitudes[i])),
+                score,
+            });
+        }
+        Ok(from_params(&std::array::from_fn(|i| {
+            theta[i] * magnitudes[i]
+        })))
+    }
+
+    fn bot(&self, weights: EvalWeights) -> MinimaxBot {
+        // Only the depth limits the search, so that games are reproducible
+        MinimaxBot::new(u64::MAX)
+            .with_max_depth(self.depth)
+            .with_weights(weights)
+    }
+}
+
+/// Plays a game between `bots`, `bots[0]` moving first, opening with
+/// `random_plies` random moves drawn from `rng`.
+pub(crate) fn play_game(
+    bots: [&MinimaxBot; 2],
+    board_size: u32,
+    random_plies: usize,
+    rng: &mut GameRng,
+) -> Result<GameY> {
+    let mut game = GameY::new(board_size);
+    while let Some(player) = game.next_player() {
+        let coords = if game.history().len() < random_plies {
+            game.available_cells()
+                .choose(rng)
+                .map(|&idx| Coordinates::from_index(idx, board_size))
+        } else {
+            bots[player.id() as usize].choose_move(&game)
+        };
+        let Some(coords) = coords else {
+            break;
+        };
+        game.add_move(Movement::Placement { player, coords })?;
+    }
+    Ok(game)
+}
+
+/// Returns the scale, between 1e-6 and 10, that minimizes `error_at`.
+pub(crate) fn fit_logistic_scale(error_at: impl Fn(f64) -> f64) -> f64 {
+    // Golden-section search over the order of magnitude of the scale
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let error_at = |exponent: f64| error_at(10f64.powf(exponent));
+    let (mut low, mut high) = (-6.0, 1.0);
+    for _ in 0..60 {
+        let left = high - ratio * (high - low);
+        let right = low + ratio * (high - low);
+        if error_at(left) <= error_at(right) {
+            high = right;
+        } else {
+            low = left;
+        }
+    }
+    10f64.powf((low + high) / 2.0)
+}
+
+pub(crate) fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn to_params(weights: &EvalWeights) -> [f64; 4] {
+    [
+        weights.edge as f64,
+        weights.connection as f64,
+        weights.well_connected as f64,
+        weights.center as f64,
+    ]
+}
+
+fn from_params(params: &[f64; 4]) -> EvalWeights {
+    EvalWeights {
+        edge: params[0].round() as i32,
+        connection: params[1].round() as i32,
+        well_connected: params[2].round() as i32,
+        center: params[3] as f32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features(edge: f64) -> EvalFeatures {
+        EvalFeatures {
+            edge,
+            ..EvalFeatures::default()
+        }
+    }
+
+    #[test]
+    fn test_texel_tuning_follows_the_results() {
+        // Touching more sides always wins in this data set
+        let mut tuner = TexelTuner::new().with_scale(0.05);
+        for edge in 1..=3 {
+            tuner.add_position(features(edge as f64), 1.0);
+            tuner.add_position(features(-edge as f64), 0.0);
+        }
+        let initial = EvalWeights::default();
+        let report = tuner.tune(initial);
+        assert!(report.weights.edge > initial.edge);
+        assert_eq!(report.weights.connection, initial.connection);
+        assert!(report.final_error < report.initial_error);
+        assert_eq!(report.scale, 0.05);
+    }
+
+    #[test]
+    fn test_fit_scale_reduces_the_error() {
+        let mut tuner = TexelTuner::new();
+        tuner.add_position(features(2.0), 1.0);
+        tuner.add_position(features(-2.0), 0.0);
+        tuner.add_position(features(1.0), 0.0);
+        let weights = EvalWeights::default();
+        let scale = tuner.fit_scale(&weights);
+        let error = tuner.error(&weights, scale);
+        assert!(error <= tuner.error(&weights, scale * 2.0));
+        assert!(error <= tuner.error(&weights, scale / 2.0));
+    }
+
+    #[test]
+    fn test_games_are_labelled_with_their_result() {
+        let bot = MinimaxBot::new(1000).with_max_depth(1);
+        let mut rng = seeded_rng(1);
+        let game = play_game([&bot, &bot], 4, 2, &mut rng).unwrap();
+        let winner = game.result().unwrap().winner;
+
+        let mut tuner = TexelTuner::new();
+        let added = tuner.add_game(&game).unwrap();
+        assert_eq!(added, game.history().len());
+        let expected = if winner == PlayerId::new(0) { 1.0 } else { 0.0 };
+        assert!(tuner.positions().iter().all(|p| p.result == expected));
+
+        assert_eq!(tuner.add_game(&GameY::new(4)).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_self_play_skips_the_random_opening() {
+        let bot = MinimaxBot::new(1000).with_max_depth(1);
+        let mut tuner = TexelTuner::new();
+        let added = tuner.add_self_play(&bot, 4, 3, 2, 11).unwrap();
+        assert_eq!(added, tuner.positions().len());
+
+        let mut again = TexelTuner::new();
+        again.add_self_play(&bot, 4, 3, 2, 11).unwrap();
+        assert_eq!(again.positions(), tuner.positions());
+        // A win on size 4 takes at least 4 stones, so every game adds some
+        assert!(added >= 3 * 2);
+    }
+
+    #[test]
+    fn test_spsa_reports_every_iteration() {
+        let tuner = SpsaTuner::new(4)
+            .with_iterations(3)
+            .with_games_per_iteration(3)
+            .with_depth(1)
+            .with_seed(5);
+        let mut seen = Vec::new();
+        let tuned = tuner
+            .run_with(EvalWeights::default(), &mut |it| seen.push(it.clone()))
+            .unwrap();
+        assert_eq!(seen.len(), 3);
+        assert_eq!(seen[2].weights, tuned);
+        // Four games per iteration, so the score is a multiple of a half
+        assert!(seen.iter().all(|it| (it.score * 2.0).fract() == 0.0));
+    }
+}
@@ -0,0 +1,416 @@
+//! Third-party engines playing through the [`YBot`] trait.
+//!
+//! An [`ExternalEngineBot`] runs an engine as a child process and talks to
+//! it over stdin and stdout with the GTP commands of the `gamey-gtp` binary:
+//! cells are written as board indices, and colours as `b` for player 0 and
+//! `w` for player 1. Before every move the bot replays the whole game with
+//! `boardsize`, `clear_board` and `play`, then asks for a move with
+//! `genmove`, so one engine process can serve any number of games, in any
+//! order. Arenas, tournaments and the bot server can then pit the bots of
+//! this crate against outside engines like against any other bot.
+//!
+//! Every answer is awaited for at most a [timeout](ExternalEngineBot::with_timeout_ms).
+//! An engine that exits, stops answering or fails a command is killed and
+//! started again, up to a [number of restarts](ExternalEngineBot::with_restarts)
+//! per move.
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{Coordinates, GameAction, GameY, GameYError, Movement, PlayerId, Result, YBot};
+
+/// How long an engine may take to answer a command, unless set with
+/// [`ExternalEngineBot::with_timeout_ms`].
+pub const DEFAULT_ENGINE_TIMEOUT_MS: u64 = 10_000;
+
+/// How long an engine asked to quit may take to exit before it is killed.
+const QUIT_GRACE: Duration = Duration::from_millis(200);
+
+/// A running engine process.
+struct EngineProcess {
+    child: Child,
+    stdin: ChildStdin,
+    // The lines written by the engine, read on a background thread so that
+    // waiting for them can time out
+    lines: Receiver<String>,
+}
+
+impl EngineProcess {
+    fn spawn(program: &str, args: &[String]) -> std::io::Result<Self> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(EngineProcess {
+            child,
+            stdin,
+            lines,
+        })
+    }
+
+    /// Sends `command` and returns the text of the engine's successful
+    /// answer, or why there is none.
+    fn send(&mut self, command: &str, timeout: Duration) -> core::result::Result<String, String> {
+        writeln!(self.stdin, "{}", command)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| format!("cannot send '{}': {}", command, e))?;
+
+        let deadline = Instant::now() + timeout;
+        let mut answer: Option<(bool, String)> = None;
+        loop {
+            let line = match self
+                .lines
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(format!(
+                        "no answer to '{}' within {} ms",
+                        command,
+                        timeout.as_millis()
+                    ));
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(format!("the engine exited during '{}'", command));
+                }
+            };
+            let line = line.trim_end();
+            match &mut answer {
+                // An empty line ends the answer
+                Some(_) if line.is_empty() => break,
+                Some((_, text)) => {
+                    text.push('\n');
+                    text.push_str(line);
+                }
+                // Anything before the answer starts is ignored
+                None => {
+                    let Some(rest) = line.strip_prefix(['=', '?']) else {
+                        continue;
+                    };
+                    let text = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+                    answer = Some((line.starts_with('='), text.trim().to_string()));
+                }
+            }
+        }
+        match answer {
+            Some((true, text)) => Ok(text),
+            Some((false, message)) => Err(format!("'{}' failed: {}", command, message)),
+            None => unreachable!("the loop only ends after an answer"),
+        }
+    }
+}
+
+impl Drop for EngineProcess {
+    fn drop(&mut self) {
+        // An engine that does not quit in time is likely hung
+        let _ = writeln!(self.stdin, "quit").and_then(|_| self.stdin.flush());
+        let deadline = Instant::now() + QUIT_GRACE;
+        while matches!(self.child.try_wait(), Ok(None)) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A bot whose moves are chosen by an engine running as a child process.
+///
+/// The engine is started on the first move and kept running between moves.
+/// Games share the one engine process, so moves requested from several
+/// threads at once are chosen one at a time.
+///
+/// # Example
+///
+/// ```no_run
+/// use gamey::{ExternalEngineBot, GameY, YBot};
+///
+/// let bot = ExternalEngineBot::new("gamey-gtp")
+///     .with_args(["--bot", "random_bot"])
+///     .with_timeout_ms(2000);
+/// let coords = bot.choose_move(&GameY::new(7));
+/// ```
+pub struct ExternalEngineBot {
+    name: String,
+    program: String,
+    args: Vec<String>,
+    timeout: Duration,
+    restarts: u32,
+    process: Mutex<Option<EngineProcess>>,
+}
+
+impl ExternalEngineBot {
+    /// Creates a bot running the engine `program`, named after the file
+    /// name of the program.
+    pub fn new(program: impl Into<String>) -> Self {
+        let program = program.into();
+        let name = Path::new(&program).file_stem().map_or_else(
+            || program.clone(),
+            |stem| stem.to_string_lossy().into_owned(),
+        );
+        ExternalEngineBot {
+            name,
+            program,
+            args: Vec::new(),
+            timeout: Duration::from_millis(DEFAULT_ENGINE_TIMEOUT_MS),
+            restarts: 1,
+            process: Mutex::new(None),
+        }
+    }
+
+    /// Sets the command-line arguments the engine is started with.
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the name of the bot.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets how long the engine may take to answer a command, including
+    /// the search of `genmove`.
+    pub fn with_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout = Duration::from_millis(timeout_ms);
+        self
+    }
+
+    /// Sets how many times the engine may be started again while choosing
+    /// one move after it crashed, hung or failed a command. By default it
+    /// is restarted once.
+    pub fn with_restarts(mut self, restarts: u32) -> Self {
+        self.restarts = restarts;
+        self
+    }
+
+    /// Asks the engine for the next movement of `board`.
+    ///
+    /// Returns an [`ExternalEngine`](GameYError::ExternalEngine) error if
+    /// the game is over, if the engine cannot be started, or if it failed
+    /// on every attempt. The movement is checked to be legal before it is
+    /// returned.
+    pub fn request_movement(&self, board: &GameY) -> Result<Movement> {
+        let player = board
+            .next_player()
+            .ok_or_else(|| self.error("the game is over".to_string()))?;
+        let mut process = self
+            .process
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut reason = String::new();
+        for attempt in 0..=self.restarts {
+            if attempt > 0 {
+                tracing::warn!("Restarting engine '{}': {}", self.name, reason);
+            }
+            if process.is_none() {
+                let engine = EngineProcess::spawn(&self.program, &self.args)
+                    .map_err(|e| self.error(format!("cannot start the engine: {}", e)))?;
+                *process = Some(engine);
+            }
+            let engine = process.as_mut().expect("the engine is running");
+            match self.play_on(engine, board, player) {
+                Ok(movement) => return Ok(movement),
+                Err(e) => {
+                    // A fresh process is started for the next attempt
+                    *process = None;
+                    reason = e;
+                }
+            }
+        }
+        Err(self.error(reason))
+    }
+
+    /// Replays `board` on the engine and asks for the move of `player`.
+    fn play_on(
+        &self,
+        engine: &mut EngineProcess,
+        board: &GameY,
+        player: PlayerId,
+    ) -> core::result::Result<Movement, String> {
+        let size = board.board_size();
+        engine.send(&format!("boardsize {}", size), self.timeout)?;
+        engine.send("clear_board", self.timeout)?;
+        for movement in board.history() {
+            let (mover, vertex) = match movement {
+                Movement::Placement { player, coords } => {
+                    (*player, coords.to_index(size).to_string())
+                }
+                Movement::Action {
+                    player,
+                    action: GameAction::Swap,
+                } => (*player, "swap".to_string()),
+                other => return Err(format!("cannot replay {}", other)),
+            };
+            engine.send(&format!("play {} {}", color(mover), vertex), self.timeout)?;
+        }
+
+        let vertex = engine.send(&format!("genmove {}", color(player)), self.timeout)?;
+        let movement = match vertex.to_lowercase().as_str() {
+            "resign" => Movement::Action {
+                player,
+                action: GameAction::Resign,
+            },
+            "swap" => Movement::Action {
+                player,
+                action: GameAction::Swap,
+            },
+            index => {
+                let idx = index
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|idx| *idx < board.total_cells())
+                    .ok_or_else(|| format!("'{}' is not a cell of the board", vertex))?;
+                Movement::Placement {
+                    player,
+                    coords: Coordinates::from_index(idx, size),
+                }
+            }
+        };
+        board
+            .clone()
+            .add_move(movement.clone())
+            .map_err(|e| format!("the engine chose an illegal move: {}", e))?;
+        Ok(movement)
+    }
+
+    fn error(&self, reason: String) -> GameYError {
+        GameYError::ExternalEngine {
+            engine: self.name.clone(),
+            reason,
+        }
+    }
+}
+
+/// The GTP colour of `player`.
+fn color(player: PlayerId) -> &'static str {
+    if player.id() == 0 { "b" } else { "w" }
+}
+
+impl YBot for ExternalEngineBot {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the stone chosen by the engine, or None if the engine
+    /// resigned, swapped or failed.
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        match self.choose_movement(board)? {
+            Movement::Placement { coords, .. } => Some(coords),
+            Movement::Action { .. } => None,
+        }
+    }
+
+    fn choose_movement(&self, board: &GameY) -> Option<Movement> {
+        self.request_movement(board)
+            .inspect_err(|e| tracing::warn!("{}", e))
+            .ok()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// A bot running a shell script as its engine. The script reads one
+    /// command per loop in `$cmd`, with the path `marker` in `$1`.
+    fn scripted(script: &str, marker: &str) -> ExternalEngineBot {
+        let script = format!(
+            "while read cmd rest; do case $cmd in {} quit) exit 0;; *) printf '= \\n\\n';; esac; done",
+            script
+        );
+        ExternalEngineBot::new("sh")
+            .with_args(["-c", &script, "sh", marker])
+            .with_timeout_ms(2000)
+    }
+
+    fn marker(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("gamey-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_moves_of_the_engine() {
+        let bot = scripted("genmove) printf '= 4\\n\\n';;", "").with_name("four");
+        assert_eq!(bot.name(), "four");
+        let mut game = GameY::new(3);
+        assert_eq!(bot.choose_move(&game), Some(Coordinates::from_index(4, 3)));
+
+        // The engine sees the moves of the game before choosing
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::from_index(4, 3),
+        })
+        .unwrap();
+        let err = bot.request_movement(&game).unwrap_err();
+        assert!(err.to_string().contains("illegal move"), "{}", err);
+
+        let resigning = scripted("genmove) printf '=1 resign\\n\\n';;", "");
+        assert_eq!(
+            resigning.request_movement(&GameY::new(3)).unwrap(),
+            Movement::Action {
+                player: PlayerId::new(0),
+                action: GameAction::Resign,
+            }
+        );
+        assert_eq!(resigning.choose_move(&GameY::new(3)), None);
+    }
+
+    #[test]
+    fn test_silent_engines_time_out() {
+        let bot = scripted("genmove) ;;", "")
+            .with_timeout_ms(100)
+            .with_restarts(0);
+        let start = Instant::now();
+        let err = bot.request_movement(&GameY::new(3)).unwrap_err();
+        assert!(err.to_string().contains("no answer"), "{}", err);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_crashed_engines_are_restarted() {
+        // The engine crashes on its first genmove, then plays cell 2
+        let script =
+            "genmove) if [ -e \"$1\" ]; then printf '= 2\\n\\n'; else : > \"$1\"; exit 1; fi;;";
+        let path = marker("crash");
+        let bot = scripted(script, &path).with_restarts(0);
+        let err = bot.request_movement(&GameY::new(3)).unwrap_err();
+        assert!(err.to_string().contains("exited"), "{}", err);
+        let _ = std::fs::remove_file(path);
+
+        let path = marker("restart");
+        let bot = scripted(script, &path);
+        assert_eq!(
+            bot.choose_move(&GameY::new(3)),
+            Some(Coordinates::from_index(2, 3))
+        );
+        let _ = std::fs::remove_file(path);
+
+        let missing = ExternalEngineBot::new("/nonexistent/engine");
+        assert_eq!(missing.name(), "engine");
+        assert!(missing.request_movement(&GameY::new(3)).is_err());
+    }
+}
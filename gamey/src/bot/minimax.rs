@@ -1,10 +1,29 @@
-use crate::{Coordinates, GameY, PlayerId, YBot, game};
+use crate::bot::bitboard::{YBitboard, YLayout};
+use crate::{
+    AntiBook, BoardTopology, Bound, ConnectEdges, Coordinates, GameRng, GameY, GameYBuilder,
+    Neighbors, OpeningBook, OpponentModel, PlayerId, SearchTree, SharedRng, TableStats,
+    TranspositionTable, TreeCaps, TreeRecorder, WinCondition, YBot, derive_seed, edge_templates,
+    game,
+};
+#[cfg(feature = "serde")]
+use crate::{CacheSnapshot, GameYError, ScratchMasks, SearchSnapshot, SearchTrace};
 use fixedbitset::FixedBitSet;
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use std::{
     cmp,
-    time::{Duration, Instant},
+    collections::HashMap,
+    sync::{
+        Arc, Mutex, OnceLock, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    time::Duration,
 };
+// std::time::Instant panics on wasm32; web-time falls back to it elsewhere
+use web_time::Instant;
 
 pub const WIN_SCORE: i32 = 100_000;
 
@@ -12,161 +31,1733 @@ pub const LOSE_SCORE: i32 = -WIN_SCORE;
 
 const INFINITY: i32 = i32::MAX / 2;
 
+/// Boards with at least this many cells (size 19 and up in Y) only search
+/// moves close to the stones already on the board.
+pub const LOCAL_SEARCH_MIN_CELLS: usize = 190;
+
+/// Maximum distance, in steps, between a candidate move and an existing
+/// stone when local search is in use.
+pub const LOCAL_SEARCH_RADIUS: u32 = 2;
+
+/// Compact cell index used in move lists and adjacency caches.
+type CellIdx = u16;
+
+/// Time budgets of at most this many milliseconds are answered with
+/// [`MinimaxBot::instant_move`] instead of searching.
+pub const INSTANT_MOVE_MAX_MS: u64 = 1;
+
+/// Root moves scoring at most this much below the best move are close
+/// enough for the [`Temperament`] of the bot to choose between them.
+pub const TEMPERAMENT_MARGIN: i32 = 15;
+
+/// Maximum depth of the iterative deepening search.
+const MAX_SEARCH_DEPTH: u8 = 100;
+
+/// Default number of entries of the transposition table of a [`MinimaxBot`].
+pub const DEFAULT_TABLE_ENTRIES: usize = 1 << 16;
+
+/// Settings of [`MinimaxBot::for_board`]: the largest board size each row
+/// applies to, the search limits and the number of table entries.
+///
+/// Measured on middle-game positions. The budget grows with what a move is
+/// worth searching on the board, from a full-width search to the end on
+/// size 5 to a narrow one on the largest boards. The bot stops an iteration
+/// that runs past the budget and falls back on the previous one, so the
+/// depth caps spare it from starting iterations it cannot finish; interior
+/// widening is what gets the search past depth 5 from size 10 on.
+const BOARD_DEFAULTS: [(u32, SearchLimits, usize); 5] = [
+    (5, board_limits(100, None, None), 1 << 12),
+    (
+        7,
+        board_limits(300, Some(7), Some(board_widening(4, 10, None))),
+        1 << 14,
+    ),
+    (
+        10,
+        board_limits(600, Some(7), Some(board_widening(3, 10, Some(16)))),
+        1 << 16,
+    ),
+    (
+        14,
+        board_limits(1000, Some(7), Some(board_widening(3, 8, Some(12)))),
+        1 << 17,
+    ),
+    (
+        u32::MAX,
+        board_limits(2000, Some(8), Some(board_widening(3, 8, Some(8)))),
+        1 << 18,
+    ),
+];
+
+const fn board_limits(
+    max_time_ms: u64,
+    max_depth: Option<u8>,
+    widening: Option<Widening>,
+) -> SearchLimits {
+    SearchLimits {
+        max_time_ms: Some(max_time_ms),
+        max_depth,
+        max_nodes: None,
+        widening,
+    }
+}
+
+const fn board_widening(
+    start_depth: u8,
+    root_moves: usize,
+    interior_moves: Option<usize>,
+) -> Widening {
+    Widening {
+        start_depth,
+        root_moves,
+        interior_moves,
+    }
+}
+
+/// Mixed into the hash of positions where the opponent of the bot is to move.
+const OPPONENT_TO_MOVE_KEY: u64 = 0x6a09_e667_f3bc_c908;
+
+/// Zobrist key of a stone of `player` (1-based) on cell `idx`.
+fn stone_key(idx: usize, player: u8) -> u64 {
+    derive_seed(idx as u64, u64::from(player))
+}
+
+/// Limits for a single search. Limits left as None are unbounded.
+///
+/// The time limit is checked between iterations, so a search may overrun it
+/// by the time of its last iteration; a [`MinimaxBot`] also stops the
+/// iteration in progress when its budget runs out. The node limit is
+/// checked at every node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SearchLimits {
+    /// Stop starting new iterations after this many milliseconds.
+    pub max_time_ms: Option<u64>,
+    /// Do not search deeper than this many plies.
+    pub max_depth: Option<u8>,
+    /// Abort the search after visiting this many nodes.
+    pub max_nodes: Option<u64>,
+    /// Search fewer moves in the deeper iterations.
+    pub widening: Option<Widening>,
+}
+
+/// How a [`MinimaxBot`] chooses between moves of nearly the same score.
+///
+/// Moves are compared by how much the opponent's reply matters after them:
+/// the standard deviation of the static evaluations over every reply.
+/// Positions with a large deviation give the opponent tactical resources,
+/// and chances to go wrong.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Temperament {
+    /// Prefers the quietest of the near-equal moves.
+    Solid,
+    /// Plays the best scored move.
+    #[default]
+    Balanced,
+    /// Prefers the sharpest of the near-equal moves.
+    Aggressive,
+}
+
+/// Random choice among near-equal moves in the opening of a game, so that
+/// a [`MinimaxBot`] does not play the same game every time.
+///
+/// For the first `moves` moves of a game, counting those of both players,
+/// the bot draws its move uniformly among the root moves of its last
+/// completed iteration that score at most `margin` below the best one.
+/// Forced wins and losses are never traded for another move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct OpeningVariety {
+    /// Number of moves of the game during which moves are drawn.
+    pub moves: usize,
+    /// Greatest score below the best move of the moves drawn from.
+    pub margin: i32,
+}
+
+impl OpeningVariety {
+    /// Draws among the moves within `margin` of the best one for the first
+    /// `moves` moves of a game.
+    pub fn new(moves: usize, margin: i32) -> Self {
+        Self { moves, margin }
+    }
+}
+
+/// Progressive widening of the search: from `start_depth` on, iterations
+/// only search the moves that did best in the previous iteration.
+///
+/// On large boards this trades breadth for depth, so that the search reaches
+/// tactical lines that a full-width search would not finish in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct Widening {
+    /// The first iteration that is restricted.
+    pub start_depth: u8,
+    /// Number of moves searched at the root, taken from the best scores of
+    /// the previous iteration.
+    pub root_moves: usize,
+    /// Number of moves searched at the other nodes, taken from the best
+    /// static evaluations. None searches every move.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub interior_moves: Option<usize>,
+}
+
+impl Widening {
+    /// Searches only the best `root_moves` root moves from the iteration of
+    /// `start_depth` on.
+    pub fn new(start_depth: u8, root_moves: usize) -> Self {
+        Self {
+            start_depth,
+            root_moves,
+            interior_moves: None,
+        }
+    }
+
+    /// Also searches only the best `moves` moves at the nodes below the root.
+    pub fn with_interior_moves(mut self, moves: usize) -> Self {
+        self.interior_moves = Some(moves);
+        self
+    }
+}
+
+/// Weights of the terms of the static evaluation.
+///
+/// Every term is computed for both players and the opponent's total is
+/// subtracted from the bot's.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct EvalWeights {
+    /// Score per side of the board touched by the player's stones, or
+    /// connected to one of them by an [`EdgeTemplate`](crate::EdgeTemplate).
+    pub edge: i32,
+    /// Score per pair of adjacent stones of the player.
+    pub connection: i32,
+    /// Bonus per stone with at least two friendly neighbours.
+    pub well_connected: i32,
+    /// Weight of closeness to the centre, fading out as the board fills up.
+    pub center: f32,
+}
+
+impl Default for EvalWeights {
+    fn default() -> Self {
+        Self {
+            edge: 5,
+            connection: 25,
+            well_connected: 40,
+            center: 5.0,
+        }
+    }
+}
+
+/// The terms of the static evaluation before they are weighted.
+///
+/// Each field is the bot's value of a term of [`EvalWeights`] minus the
+/// opponent's, so the evaluation of a position that is not won is close to
+/// the dot product of its features with the weights.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EvalFeatures {
+    /// Difference in sides of the board touched or held by edge templates.
+    pub edge: f64,
+    /// Difference in pairs of adjacent stones.
+    pub connection: f64,
+    /// Difference in stones with at least two friendly neighbours.
+    pub well_connected: f64,
+    /// Difference in closeness to the centre, already faded by the progress
+    /// of the game.
+    pub center: f64,
+}
+
+impl EvalFeatures {
+    /// Returns the evaluation these features get with `weights`.
+    pub fn score(&self, weights: &EvalWeights) -> f64 {
+        self.edge * weights.edge as f64
+            + self.connection * weights.connection as f64
+            + self.well_connected * weights.well_connected as f64
+            + self.center * weights.center as f64
+    }
+}
+
+/// Progress of a search, reported after each completed iteration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SearchInfo {
+    /// The depth of the completed iteration.
+    pub depth: u8,
+    /// The score of the best move, from the point of view of the bot.
+    pub score: i32,
+    /// The number of nodes visited so far.
+    pub nodes: u64,
+    /// Milliseconds elapsed since the search started.
+    pub elapsed_ms: u64,
+    /// The principal variation: the best move followed by the expected replies.
+    pub pv: Vec<usize>,
+    /// The moves searched at the root with their scores, best first.
+    pub root_scores: Vec<(usize, i32)>,
+    /// The nodes visited below each root move since the search started, by
+    /// cell index. Moves never searched are left out.
+    pub root_nodes: Vec<(usize, u64)>,
+}
+
+impl SearchInfo {
+    /// Returns the number of plies, counting the moves of both players,
+    /// within which the bot wins whatever the opponent plays, or None if the
+    /// search has not proven a win.
+    ///
+    /// The proof only covers the replies the search considered: searches
+    /// narrowed by widening, by local search on large boards or by an
+    /// opponent model may miss defences.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gamey::{SearchInfo, WIN_SCORE};
+    ///
+    /// let info = SearchInfo {
+    ///     depth: 4,
+    ///     score: WIN_SCORE - 3,
+    ///     nodes: 100,
+    ///     elapsed_ms: 1,
+    ///     pv: vec![7, 2, 9, 4],
+    ///     root_scores: Vec::new(),
+    ///     root_nodes: Vec::new(),
+    /// };
+    /// assert_eq!(info.forced_win_in(), Some(3));
+    /// assert_eq!(info.forcing_line(), &[7, 2, 9]);
+    /// ```
+    pub fn forced_win_in(&self) -> Option<u8> {
+        decided_in(self.score).filter(|_| self.score > 0)
+    }
+
+    /// Returns the number of plies within which the opponent wins whatever
+    /// the bot plays, or None if the search has not proven a loss. The
+    /// proof has the limits of [`SearchInfo::forced_win_in`].
+    pub fn forced_loss_in(&self) -> Option<u8> {
+        decided_in(self.score).filter(|_| self.score < 0)
+    }
+
+    /// Returns the line that forces the win or loss: the principal variation
+    /// up to the move that wins. The line is empty if the search has not
+    /// proven the result.
+    pub fn forcing_line(&self) -> &[usize] {
+        let plies = decided_in(self.score).unwrap_or(0);
+        &self.pv[..self.pv.len().min(plies as usize)]
+    }
+
+    /// Returns where the search spent its effort, indexed by cell index over
+    /// a board of `total_cells` cells: the nodes visited below each root
+    /// move, as a fraction of those of the most searched one. Cells never
+    /// searched are 0.0.
+    ///
+    /// The map can be drawn with
+    /// [`SvgRenderer::with_heatmap`](crate::SvgRenderer::with_heatmap). A
+    /// good move that got little effort points at a move ordering problem,
+    /// and a lot of effort on a poor move at pruning that fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::AtomicBool;
+    /// use gamey::{GameY, MinimaxState, PlayerId, SearchInfo, SearchLimits, search_with_limits};
+    ///
+    /// let game = GameY::new(4);
+    /// let mut state = MinimaxState::new(&game, PlayerId::new(0));
+    /// let limits = SearchLimits {
+    ///     max_depth: Some(3),
+    ///     ..SearchLimits::default()
+    /// };
+    /// let mut last: Option<SearchInfo> = None;
+    /// let stop = AtomicBool::new(false);
+    /// search_with_limits(&mut state, &limits, &stop, &mut |info| last = Some(info.clone()));
+    /// let map = last.unwrap().effort_map(game.total_cells() as usize);
+    /// assert_eq!(map.len(), 10);
+    /// assert!(map.contains(&1.0));
+    /// assert!(map.iter().all(|share| (0.0..=1.0).contains(share)));
+    /// ```
+    pub fn effort_map(&self, total_cells: usize) -> Vec<f32> {
+        let mut map = vec![0.0; total_cells];
+        let most = self.root_nodes.iter().map(|&(_, nodes)| nodes).max();
+        if let Some(most) = most.filter(|&most| most > 0) {
+            for &(cell, nodes) in &self.root_nodes {
+                if let Some(share) = map.get_mut(cell) {
+                    *share = nodes as f32 / most as f32;
+                }
+            }
+        }
+        map
+    }
+}
+
+/// Returns the number of plies to the end of the game of a search score, if
+/// it is a forced win or loss.
+fn decided_in(score: i32) -> Option<u8> {
+    let plies = WIN_SCORE - score.abs();
+    (0..=i32::from(MAX_SEARCH_DEPTH))
+        .contains(&plies)
+        .then_some(plies as u8)
+}
+
+/// Never set: used by searches that cannot be stopped from outside.
+static NEVER_STOP: AtomicBool = AtomicBool::new(false);
+
+/// Bookkeeping shared by every node of a search.
+struct SearchControl<'a> {
+    nodes: u64,
+    max_nodes: Option<u64>,
+    stop: &'a AtomicBool,
+    aborted: bool,
+    // Moves from the root to the node being searched
+    ply: u8,
+    // The cells of those moves, in order
+    line: Vec<usize>,
+    // Best line found below a node, indexed by the node's remaining depth
+    pv_table: Vec<Vec<usize>>,
+    tree: Option<TreeRecorder>,
+    table: Option<&'a mut TranspositionTable>,
+    opponent: Option<&'a dyn OpponentModel>,
+    // Scores of the root moves in the last iteration, best first
+    root_scores: Vec<(CellIdx, i32)>,
+    // Scores of the root moves in the iteration being searched
+    next_root_scores: Vec<(CellIdx, i32)>,
+    // Nodes visited below each root move over every iteration, by cell
+    root_nodes: Vec<u64>,
+    // Moves searched per node when widening, at the root and below it
+    root_limit: Option<usize>,
+    interior_limit: Option<usize>,
+}
+
+impl<'a> SearchControl<'a> {
+    fn new(max_nodes: Option<u64>, stop: &'a AtomicBool) -> Self {
+        SearchControl {
+            nodes: 0,
+            max_nodes,
+            stop,
+            aborted: false,
+            ply: 0,
+            line: Vec::with_capacity(MAX_SEARCH_DEPTH as usize + 1),
+            pv_table: vec![Vec::new(); MAX_SEARCH_DEPTH as usize + 1],
+            tree: None,
+            table: None,
+            opponent: None,
+            root_scores: Vec::new(),
+            next_root_scores: Vec::new(),
+            root_nodes: Vec::new(),
+            root_limit: None,
+            interior_limit: None,
+        }
+    }
+
+    /// Records that `player` plays `cell`, if the tree is being recorded.
+    fn enter(&mut self, cell: usize, player: u8, alpha: i32, beta: i32) {
+        self.ply += 1;
+        self.line.push(cell);
+        if let Some(tree) = &mut self.tree {
+            tree.enter(cell, PlayerId::new(player as u32 - 1), alpha, beta);
+        }
+    }
+
+    /// Records the score of the node being left.
+    fn leave(&mut self, score: i32) {
+        self.ply -= 1;
+        self.line.pop();
+        if let Some(tree) = &mut self.tree {
+            tree.leave(score);
+        }
+    }
+
+    /// Records a cutoff in the node being searched.
+    fn cutoff(&mut self) {
+        if let Some(tree) = &mut self.tree {
+            tree.mark_cutoff();
+        }
+    }
+
+    /// Counts a visited node and returns true if the search must stop.
+    fn visit(&mut self) -> bool {
+        self.nodes += 1;
+        if !self.aborted {
+            self.aborted = self.max_nodes.is_some_and(|max| self.nodes > max)
+                || self.stop.load(Ordering::Relaxed);
+        }
+        self.aborted
+    }
+
+    /// Records `best_move` followed by the line found below it at `depth - 1`.
+    fn update_pv(&mut self, depth: u8, best_move: usize) {
+        let (below, above) = self.pv_table.split_at_mut(depth as usize);
+        let line = &mut above[0];
+        line.clear();
+        line.push(best_move);
+        line.extend_from_slice(&below[depth as usize - 1]);
+    }
+}
+
+/// The parts of the search state that only depend on the board, shared by
+/// every state of the same board.
+#[derive(Clone)]
+struct BoardCaches {
+    center_bonus: Vec<i32>,
+    neighbors: Vec<SmallVec<[CellIdx; 6]>>,
+    edges: Vec<u8>,
+    // Side and carrier of the edge templates of each cell, for the game of
+    // Y only
+    templates: Vec<Vec<(u8, Vec<CellIdx>)>>,
+    // Layout of the bitboards, for the game of Y only
+    y_layout: Option<Arc<YLayout>>,
+    // Blocked cells of the board, in ascending order
+    blocked: Vec<usize>,
+}
+
+impl BoardCaches {
+    fn new(topology: &dyn BoardTopology) -> Self {
+        let total_cells = topology.num_cells();
+        assert!(
+            total_cells <= CellIdx::MAX as usize + 1,
+            "board too large for the minimax bot"
+        );
+        Self {
+            center_bonus: vec![0; total_cells],
+            neighbors: (0..total_cells)
+                .map(|idx| {
+                    topology
+                        .neighbors(idx)
+                        .iter()
+                        .map(|&n| n as CellIdx)
+                        .collect()
+                })
+                .collect(),
+            edges: (0..total_cells)
+                .map(|idx| topology.edge_labels(idx))
+                .collect(),
+            templates: vec![Vec::new(); total_cells],
+            y_layout: None,
+            blocked: Vec::new(),
+        }
+    }
+
+    /// Returns the caches of the board of `game`: the shared caches of its
+    /// size, or a copy with its blocked cells cut out of the board.
+    fn of_game(game: &GameY) -> Arc<Self> {
+        let shared = Self::of_y(game);
+        if game.blocked_cells().is_empty() {
+            return shared;
+        }
+        let mut board = Self::clone(&shared);
+        board.blocked = game
+            .blocked_cells()
+            .iter()
+            .map(|&idx| idx as usize)
+            .collect();
+        let is_blocked = |idx: CellIdx| game.blocked_cells().binary_search(&u32::from(idx)).is_ok();
+        for neighbors in &mut board.neighbors {
+            neighbors.retain(|neighbor| !is_blocked(*neighbor));
+        }
+        // A template through a hole does not connect, and a hole has no stone
+        // to hold a template
+        for templates in &mut board.templates {
+            templates.retain(|(_, carrier)| !carrier.iter().any(|&cell| is_blocked(cell)));
+        }
+        for &idx in &board.blocked {
+            board.templates[idx].clear();
+            board.center_bonus[idx] = 0;
+        }
+        Arc::new(board)
+    }
+
+    /// Returns the caches of the boards of Y of the size of `game`, built
+    /// the first time a board of that size is searched.
+    fn of_y(game: &GameY) -> Arc<Self> {
+        static CACHES: OnceLock<Mutex<HashMap<u32, Arc<BoardCaches>>>> = OnceLock::new();
+        let size = game.board_size();
+        let mut caches = CACHES
+            .get_or_init(Mutex::default)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let shared = caches.entry(size).or_insert_with(|| {
+            // Built from an open board, as the caches are shared by every
+            // board of the size
+            let mut board = Self::new(&GameY::new(size));
+            // Control de centro: bonus por cercanía al centro del triángulo
+            for (idx, bonus) in board.center_bonus.iter_mut().enumerate() {
+                let coords = Coordinates::from_index(idx as u32, size);
+                *bonus = 50 - off_center(coords) as i32;
+            }
+            for (idx, templates) in board.templates.iter_mut().enumerate() {
+                let stone = Coordinates::from_index(idx as u32, size);
+                *templates = edge_templates(stone, size)
+                    .into_iter()
+                    .map(|template| {
+                        let carrier = template
+                            .carrier
+                            .iter()
+                            .map(|cell| cell.to_index(size) as CellIdx)
+                            .collect();
+                        (template.side_label(), carrier)
+                    })
+                    .collect();
+            }
+            board.y_layout = Some(Arc::new(YLayout::new(size, &board.edges)));
+            Arc::new(board)
+        });
+        Arc::clone(shared)
+    }
+}
+
+/// Search state for the minimax bot.
+///
+/// The state only depends on a [`BoardTopology`] and a [`WinCondition`], so
+/// the same search code can play any connection game.
+///
+/// Boards are limited to 65536 cells so that cell indices fit in a `u16`.
+/// Move lists are kept in buffers allocated once per search depth, and moves
+/// are ordered in a scratch buffer of the state, so the search itself does
+/// not allocate once its buffers have grown.
 pub struct MinimaxState {
     board: Vec<u8>,
-    size: u32,
     available_mask: FixedBitSet,
-    coords_cache: Vec<Coordinates>,
-    neighbors_cache: Vec<Vec<usize>>,
-    edges_cache: Vec<u8>,
+    owned: [FixedBitSet; 2],
+    caches: Arc<BoardCaches>,
+    win_condition: Box<dyn WinCondition>,
     bot_id: u8,
     human_id: u8,
-    visited: Vec<bool>,
-    stack: Vec<usize>,
+    // Move lists, one per remaining search depth
+    move_buffers: Vec<Vec<CellIdx>>,
+    // Scored moves while ordering them
+    order_buffer: Vec<(i32, CellIdx)>,
+    // Scratch sets for local move generation
+    near_mask: FixedBitSet,
+    frontier_mask: FixedBitSet,
+    next_mask: FixedBitSet,
+    weights: EvalWeights,
+    // Size of the board of Y the state was built for, None for other games
+    y_board_size: Option<u32>,
+    // The stones as bitboards, for the game of Y only
+    bitboard: Option<YBitboard>,
+    // Zobrist hash of the stones on the board
+    hash: u64,
+}
+
+impl MinimaxState {
+    /// Builds the search state for a game of Y.
+    pub fn new(game: &GameY, bot_player: PlayerId) -> Self {
+        let size = game.board_size();
+        let stones: Vec<Option<PlayerId>> = (0..game.total_cells())
+            .map(|idx| game.owner_at(idx))
+            .collect();
+
+        let caches = BoardCaches::of_game(game);
+        let layout = caches.y_layout.clone().expect("caches of a board of Y");
+        let mut state = Self::with_caches(
+            caches,
+            Box::new(ConnectEdges::all_sides_of_y()),
+            &stones,
+            bot_player,
+        );
+        state.y_board_size = Some(size);
+        let mut bitboard = YBitboard::new(layout);
+        for (player, owned) in state.owned.iter().enumerate() {
+            owned.ones().for_each(|idx| bitboard.insert(player, idx));
+        }
+        state.bitboard = Some(bitboard);
+        state
+    }
+
+    /// Replaces the position with the one of `game`, searched for
+    /// `bot_player`, keeping the weights.
+    ///
+    /// The caches of the board are reused when the state was built for a
+    /// game of Y of the same size without blocked cells, which makes this
+    /// much cheaper than [`MinimaxState::new`] when evaluating many
+    /// positions.
+    pub fn set_position(&mut self, game: &GameY, bot_player: PlayerId) {
+        let size = game.board_size();
+        if self.y_board_size != Some(size)
+            || !self.caches.blocked.is_empty()
+            || !game.blocked_cells().is_empty()
+        {
+            *self = Self::new(game, bot_player).with_weights(self.weights);
+            return;
+        }
+
+        self.board.fill(0);
+        self.available_mask.insert_range(..);
+        self.owned.iter_mut().for_each(FixedBitSet::clear);
+        if let Some(bitboard) = &mut self.bitboard {
+            bitboard.clear();
+        }
+        self.hash = 0;
+        for (coords, owner) in game.stones() {
+            let idx = coords.to_index(size) as usize;
+            self.make_move(idx, owner.id() as u8 + 1);
+        }
+        self.bot_id = bot_player.id() as u8 + 1;
+        self.human_id = game::other_player(bot_player).id() as u8 + 1;
+    }
+
+    /// Builds the search state for any connection game.
+    ///
+    /// `stones` gives the owner of each cell of `topology`, indexed by cell.
+    pub fn with_rules(
+        topology: &dyn BoardTopology,
+        win_condition: Box<dyn WinCondition>,
+        stones: &[Option<PlayerId>],
+        bot_player: PlayerId,
+    ) -> Self {
+        let caches = Arc::new(BoardCaches::new(topology));
+        Self::with_caches(caches, win_condition, stones, bot_player)
+    }
+
+    fn with_caches(
+        caches: Arc<BoardCaches>,
+        win_condition: Box<dyn WinCondition>,
+        stones: &[Option<PlayerId>],
+        bot_player: PlayerId,
+    ) -> Self {
+        let total_cells = caches.edges.len();
+        let mut board: Vec<u8> = vec![0; total_cells];
+        let mut available_mask = FixedBitSet::with_capacity(total_cells);
+        let mut owned = [
+            FixedBitSet::with_capacity(total_cells),
+            FixedBitSet::with_capacity(total_cells),
+        ];
+
+        let bot_id = bot_player.id() as u8 + 1;
+        let human_id = game::other_player(bot_player).id() as u8 + 1;
+
+        // Copiar estado del tablero (1-based, 0 = vacío)
+        let mut hash = 0;
+        for (idx, cell) in board.iter_mut().enumerate() {
+            match stones.get(idx).copied().flatten() {
+                Some(owner) => {
+                    *cell = owner.id() as u8 + 1;
+                    owned[owner.id() as usize].insert(idx);
+                    hash ^= stone_key(idx, *cell);
+                }
+                None if caches.blocked.binary_search(&idx).is_ok() => {}
+                None => available_mask.insert(idx),
+            }
+        }
+
+        Self {
+            board,
+            available_mask,
+            owned,
+            caches,
+            win_condition,
+            bot_id,
+            human_id,
+            move_buffers: Vec::new(),
+            order_buffer: Vec::new(),
+            near_mask: FixedBitSet::with_capacity(total_cells),
+            frontier_mask: FixedBitSet::with_capacity(total_cells),
+            next_mask: FixedBitSet::with_capacity(total_cells),
+            weights: EvalWeights::default(),
+            y_board_size: None,
+            bitboard: None,
+            hash,
+        }
+    }
+
+    /// Uses `weights` for the static evaluation instead of the defaults.
+    pub fn with_weights(mut self, weights: EvalWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    fn make_move(&mut self, idx: usize, player: u8) {
+        self.board[idx] = player;
+        self.available_mask.set(idx, false);
+        self.owned[player as usize - 1].insert(idx);
+        if let Some(bitboard) = &mut self.bitboard {
+            bitboard.insert(player as usize - 1, idx);
+        }
+        self.hash ^= stone_key(idx, player);
+    }
+
+    fn undo_move(&mut self, idx: usize) {
+        let player = self.board[idx];
+        if player != 0 {
+            self.owned[player as usize - 1].set(idx, false);
+            if let Some(bitboard) = &mut self.bitboard {
+                bitboard.remove(player as usize - 1, idx);
+            }
+            self.hash ^= stone_key(idx, player);
+        }
+        self.board[idx] = 0;
+        self.available_mask.set(idx, true);
+    }
+
+    fn available_cells(&self) -> impl Iterator<Item = usize> + '_ {
+        self.available_mask.ones()
+    }
+
+    fn uses_local_search(&self) -> bool {
+        self.board.len() >= LOCAL_SEARCH_MIN_CELLS
+    }
+
+    /// Takes the move buffer of `slot` and fills it with the moves to search.
+    ///
+    /// On large boards only cells within [`LOCAL_SEARCH_RADIUS`] of a stone
+    /// are returned; an empty board or a position without such cells falls
+    /// back to every empty cell. The buffer must be handed back with
+    /// [`MinimaxState::return_moves`].
+    fn take_moves(&mut self, slot: usize) -> Vec<CellIdx> {
+        if self.move_buffers.len() <= slot {
+            let capacity = self.board.len();
+            self.move_buffers
+                .resize_with(slot + 1, || Vec::with_capacity(capacity));
+        }
+        let mut moves = std::mem::take(&mut self.move_buffers[slot]);
+        moves.clear();
+
+        if self.uses_local_search() && self.mark_cells_near_stones() {
+            moves.extend(
+                self.near_mask
+                    .intersection(&self.available_mask)
+                    .map(|idx| idx as CellIdx),
+            );
+        }
+        if moves.is_empty() {
+            moves.extend(self.available_cells().map(|idx| idx as CellIdx));
+        }
+        moves
+    }
+
+    fn return_moves(&mut self, slot: usize, moves: Vec<CellIdx>) {
+        self.move_buffers[slot] = moves;
+    }
+
+    /// Marks in `near_mask` the cells within [`LOCAL_SEARCH_RADIUS`] of a stone.
+    ///
+    /// Returns false if the board has no stones.
+    fn mark_cells_near_stones(&mut self) -> bool {
+        self.near_mask.clear();
+        self.frontier_mask.clone_from(&self.owned[0]);
+        self.frontier_mask.union_with(&self.owned[1]);
+        if self.frontier_mask.is_clear() {
+            return false;
+        }
+        self.near_mask.union_with(&self.frontier_mask);
+
+        for _ in 0..LOCAL_SEARCH_RADIUS {
+            self.next_mask.clear();
+            for idx in self.frontier_mask.ones() {
+                for &neighbor in &self.caches.neighbors[idx] {
+                    if !self.near_mask.contains(neighbor as usize) {
+                        self.next_mask.insert(neighbor as usize);
+                    }
+                }
+            }
+            self.near_mask.union_with(&self.next_mask);
+            std::mem::swap(&mut self.frontier_mask, &mut self.next_mask);
+        }
+        true
+    }
+
+    /// Returns two empty cells where the owner of the stone on `cell` would
+    /// win at once, if there are two.
+    ///
+    /// Only the empty neighbours of the group of the stone are tried: a
+    /// cell that wins because the stone was placed must connect to it.
+    fn double_threat(&mut self, cell: usize) -> Option<(usize, usize)> {
+        let player = self.board[cell];
+        // The group of the stone, grown one ring of neighbours at a time
+        self.near_mask.clear();
+        self.frontier_mask.clear();
+        self.near_mask.insert(cell);
+        self.frontier_mask.insert(cell);
+        while !self.frontier_mask.is_clear() {
+            self.next_mask.clear();
+            for idx in self.frontier_mask.ones() {
+                for &neighbor in &self.caches.neighbors[idx] {
+                    let neighbor = neighbor as usize;
+                    if self.board[neighbor] == player && !self.near_mask.contains(neighbor) {
+                        self.next_mask.insert(neighbor);
+                    }
+                }
+            }
+            self.near_mask.union_with(&self.next_mask);
+            std::mem::swap(&mut self.frontier_mask, &mut self.next_mask);
+        }
+
+        let mut liberties = std::mem::take(&mut self.next_mask);
+        liberties.clear();
+        for idx in self.near_mask.ones() {
+            for &neighbor in &self.caches.neighbors[idx] {
+                if self.board[neighbor as usize] == 0 {
+                    liberties.insert(neighbor as usize);
+                }
+            }
+        }
+        // Filling every liberty at once is a win if any single one is, which
+        // rules out most groups with one test
+        for idx in liberties.ones() {
+            self.make_move(idx, player);
+        }
+        let possible = self.check_win(player);
+        for idx in liberties.ones() {
+            self.undo_move(idx);
+        }
+        let found = if possible {
+            let mut threats = liberties.ones().filter(|&idx| self.wins_with(idx, player));
+            threats.next().zip(threats.next())
+        } else {
+            None
+        };
+        self.next_mask = liberties;
+        found
+    }
+
+    /// Returns true if `player` wins at once by playing some empty cell.
+    fn has_winning_cell(&mut self, player: u8) -> bool {
+        let mut empty = std::mem::take(&mut self.next_mask);
+        empty.clone_from(&self.available_mask);
+        let found = empty.ones().any(|idx| self.wins_with(idx, player));
+        self.next_mask = empty;
+        found
+    }
+
+    /// Returns true if `player` wins by playing the empty `cell`.
+    fn wins_with(&mut self, cell: usize, player: u8) -> bool {
+        self.make_move(cell, player);
+        let won = self.check_win(player);
+        self.undo_move(cell);
+        won
+    }
+
+    /// Returns the static evaluation of the position, from the point of view
+    /// of the bot.
+    pub fn evaluate(&mut self) -> i32 {
+        evaluate_state(self)
+    }
+
+    /// Returns the player the state is searched for.
+    pub fn bot_player(&self) -> PlayerId {
+        PlayerId::new(self.bot_id as u32 - 1)
+    }
+
+    /// Returns the opponent of the bot player.
+    pub fn opponent(&self) -> PlayerId {
+        PlayerId::new(self.human_id as u32 - 1)
+    }
+
+    /// Returns the owner of the stone on `cell`, if any.
+    pub fn stone(&self, cell: usize) -> Option<PlayerId> {
+        match self.board[cell] {
+            0 => None,
+            id => Some(PlayerId::new(id as u32 - 1)),
+        }
+    }
+
+    /// Returns the empty cells.
+    pub fn empty_cells(&self) -> impl Iterator<Item = usize> + '_ {
+        self.available_cells()
+    }
+
+    /// Places a stone of `player` on the empty `cell`. Remove it with
+    /// [`MinimaxState::unplay`].
+    pub fn play(&mut self, cell: usize, player: PlayerId) {
+        self.make_move(cell, player.id() as u8 + 1);
+    }
+
+    /// Removes the stone on `cell`.
+    pub fn unplay(&mut self, cell: usize) {
+        self.undo_move(cell);
+    }
+
+    /// Returns true if the stones of `player` meet the win condition.
+    pub fn is_win(&self, player: PlayerId) -> bool {
+        self.check_win(player.id() as u8 + 1)
+    }
+
+    /// Returns the position as a game of Y with the opponent to move, or
+    /// None if the state was not built for the Game of Y.
+    pub fn to_game(&self) -> Option<GameY> {
+        let size = self.y_board_size?;
+        let blocked = self.caches.blocked.iter();
+        let mut builder = GameYBuilder::new(size)
+            .with_blocked_cells(blocked.map(|&cell| Coordinates::from_index(cell as u32, size)))
+            .with_starting_player(self.opponent())
+            .allow_finished(true);
+        for cell in 0..self.board.len() {
+            if let Some(player) = self.stone(cell) {
+                builder = builder.with_stone(player, Coordinates::from_index(cell as u32, size));
+            }
+        }
+        builder.build().ok()
+    }
+
+    /// Returns a snapshot of the state, to attach to bug reports.
+    ///
+    /// The snapshot holds the state only: [`MinimaxBot::snapshot`] adds the
+    /// settings and the latest search of a bot.
+    #[cfg(feature = "serde")]
+    pub fn snapshot(&self) -> SearchSnapshot {
+        let cells = |mask: &FixedBitSet| mask.ones().collect();
+        let caches = &self.caches;
+        SearchSnapshot {
+            board_size: self.y_board_size,
+            bot_player: self.bot_player(),
+            board: self.board.clone(),
+            available: cells(&self.available_mask),
+            owned: [cells(&self.owned[0]), cells(&self.owned[1])],
+            scratch: ScratchMasks {
+                near: cells(&self.near_mask),
+                frontier: cells(&self.frontier_mask),
+                next: cells(&self.next_mask),
+            },
+            hash: self.hash,
+            weights: self.weights,
+            caches: CacheSnapshot {
+                center_bonus: caches.center_bonus.clone(),
+                neighbors: caches.neighbors.iter().map(|n| n.to_vec()).collect(),
+                edges: caches.edges.clone(),
+                templates: caches.templates.clone(),
+                blocked: caches.blocked.clone(),
+            },
+            limits: None,
+            temperament: None,
+            table_stats: None,
+            last_search: None,
+        }
+    }
+
+    /// Rebuilds a state of the game of Y from `snapshot`, with its caches
+    /// and masks as they were, even where they disagree with the board.
+    #[cfg(feature = "serde")]
+    pub fn from_snapshot(snapshot: &SearchSnapshot) -> crate::Result<Self> {
+        let invalid = |reason: &str| GameYError::InvalidSnapshot {
+            reason: reason.to_string(),
+        };
+        let size = snapshot
+            .board_size
+            .ok_or_else(|| invalid("only states of the game of Y can be loaded"))?;
+        let total_cells = snapshot.board.len();
+        let caches = &snapshot.caches;
+        let expected_cells = (size as usize)
+            .checked_add(1)
+            .and_then(|next| next.checked_mul(size as usize))
+            .map(|cells| cells / 2);
+        if size == 0 || expected_cells != Some(total_cells) {
+            return Err(invalid("the board does not fit the board size"));
+        }
+        if [
+            caches.center_bonus.len(),
+            caches.neighbors.len(),
+            caches.edges.len(),
+            caches.templates.len(),
+        ]
+        .iter()
+        .any(|&len| len != total_cells)
+        {
+            return Err(invalid("the caches do not fit the board"));
+        }
+        let in_board = |cells: &[usize]| cells.iter().all(|&cell| cell < total_cells);
+        let in_board_idx =
+            |cells: &[CellIdx]| cells.iter().all(|&cell| (cell as usize) < total_cells);
+        if !in_board(&snapshot.available)
+            || !snapshot.owned.iter().all(|owned| in_board(owned))
+            || ![
+                &snapshot.scratch.near,
+                &snapshot.scratch.frontier,
+                &snapshot.scratch.next,
+            ]
+            .iter()
+            .all(|mask| in_board(mask))
+            || !in_board(&caches.blocked)
+            || !caches.neighbors.iter().all(|n| in_board_idx(n))
+            || !caches
+                .templates
+                .iter()
+                .flatten()
+                .all(|(_, carrier)| in_board_idx(carrier))
+        {
+            return Err(invalid("a cell is outside the board"));
+        }
+        if snapshot.board.iter().any(|&stone| stone > 2) || snapshot.bot_player.id() > 1 {
+            return Err(invalid("only two players can own stones"));
+        }
+
+        let stones: Vec<Option<PlayerId>> = snapshot
+            .board
+            .iter()
+            .map(|&stone| (stone != 0).then(|| PlayerId::new(u32::from(stone) - 1)))
+            .collect();
+        let board_caches = BoardCaches {
+            center_bonus: caches.center_bonus.clone(),
+            neighbors: caches
+                .neighbors
+                .iter()
+                .map(|n| n.iter().copied().collect())
+                .collect(),
+            edges: caches.edges.clone(),
+            templates: caches.templates.clone(),
+            y_layout: Some(Arc::new(YLayout::new(size, &caches.edges))),
+            blocked: {
+                let mut blocked = caches.blocked.clone();
+                blocked.sort_unstable();
+                blocked.dedup();
+                blocked
+            },
+        };
+        let layout = board_caches
+            .y_layout
+            .clone()
+            .expect("caches of a board of Y");
+        let mut state = Self::with_caches(
+            Arc::new(board_caches),
+            Box::new(ConnectEdges::all_sides_of_y()),
+            &stones,
+            snapshot.bot_player,
+        )
+        .with_weights(snapshot.weights);
+        let mask = |cells: &[usize]| {
+            let mut mask = FixedBitSet::with_capacity(total_cells);
+            cells.iter().for_each(|&cell| mask.insert(cell));
+            mask
+        };
+        state.available_mask = mask(&snapshot.available);
+        state.owned = [mask(&snapshot.owned[0]), mask(&snapshot.owned[1])];
+        state.near_mask = mask(&snapshot.scratch.near);
+        state.frontier_mask = mask(&snapshot.scratch.frontier);
+        state.next_mask = mask(&snapshot.scratch.next);
+        state.hash = snapshot.hash;
+        state.y_board_size = Some(size);
+        let mut bitboard = YBitboard::new(layout);
+        for (player, owned) in state.owned.iter().enumerate() {
+            owned.ones().for_each(|idx| bitboard.insert(player, idx));
+        }
+        state.bitboard = Some(bitboard);
+        Ok(state)
+    }
+
+    /// Returns the unweighted terms of the static evaluation, from the point
+    /// of view of the bot. Wins are not taken into account.
+    pub fn eval_features(&self) -> EvalFeatures {
+        let bot = PlayerTerms::of(self, self.bot_id);
+        let human = PlayerTerms::of(self, self.human_id);
+        let center_weight = 1. - game_progress(self) as f64;
+        EvalFeatures {
+            edge: (bot.edges - human.edges) as f64,
+            connection: (bot.connections - human.connections) as f64,
+            well_connected: (bot.well_connected - human.well_connected) as f64,
+            center: (bot.center_control - human.center_control) as f64 * center_weight,
+        }
+    }
+
+    /// Retorna true si el jugador cumple la condición de victoria
+    fn check_win(&self, player: u8) -> bool {
+        match &self.bitboard {
+            Some(bitboard) => bitboard.connects_all_sides(player as usize - 1),
+            None => self
+                .win_condition
+                .is_win(self, &self.owned[player as usize - 1]),
+        }
+    }
+}
+
+impl BoardTopology for MinimaxState {
+    fn num_cells(&self) -> usize {
+        self.board.len()
+    }
+
+    fn neighbors(&self, cell: usize) -> Neighbors {
+        self.caches.neighbors[cell]
+            .iter()
+            .map(|&n| n as usize)
+            .collect()
+    }
+
+    fn edge_labels(&self, cell: usize) -> u8 {
+        self.caches.edges[cell]
+    }
+}
+
+/// Transposition tables kept by a [`MinimaxBot`] between the moves of its
+/// games.
+///
+/// Each search takes a table out of the pool and puts it back when done, so
+/// that games searched at the same time never share a table; the pool grows
+/// to the number of games searched at once. The table last put back comes
+/// last.
+///
+/// Clones start with an empty pool, and caches always compare equal so that
+/// bots compare by their settings.
+#[derive(Default)]
+struct SearchCache(Mutex<Vec<CachedTable>>);
+
+struct CachedTable {
+    table: TranspositionTable,
+    board_size: u32,
+    bot_player: PlayerId,
+    weights: EvalWeights,
+    history: Vec<crate::Movement>,
+}
+
+impl Clone for SearchCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PartialEq for SearchCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for SearchCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SearchCache")
+    }
+}
+
+/// The settings of a [`MinimaxBot`] that may change while it plays. Clones
+/// copy them, and bots compare by them.
+struct LiveSettings(RwLock<Settings>);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Settings {
+    // No time limit for bots that search to a fixed depth whatever the clock
+    limits: SearchLimits,
+    weights: EvalWeights,
+}
+
+impl LiveSettings {
+    fn new(limits: SearchLimits) -> Self {
+        Self(RwLock::new(Settings {
+            limits,
+            weights: EvalWeights::default(),
+        }))
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Settings> {
+        self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Settings> {
+        self.0.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn get_mut(&mut self) -> &mut Settings {
+        self.0.get_mut().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Clone for LiveSettings {
+    fn clone(&self) -> Self {
+        Self(RwLock::new(*self.read()))
+    }
+}
+
+impl PartialEq for LiveSettings {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other) || *self.read() == *other.read()
+    }
+}
+
+impl std::fmt::Debug for LiveSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.read().fmt(f)
+    }
+}
+
+/// The opponent model of a [`MinimaxBot`]. Models compare by identity.
+#[derive(Clone, Default)]
+struct ModelSlot(Option<Arc<dyn OpponentModel>>);
+
+impl PartialEq for ModelSlot {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+}
+
+impl std::fmt::Debug for ModelSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(model) => write!(f, "Some({})", model.name()),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+/// The search trace of a [`MinimaxBot`]. Traces compare by identity.
+#[cfg(feature = "serde")]
+#[derive(Clone, Default)]
+struct TraceSlot(Option<Arc<SearchTrace>>);
+
+#[cfg(feature = "serde")]
+impl PartialEq for TraceSlot {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::fmt::Debug for TraceSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(trace) => write!(f, "Some({:?})", trace),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+/// Returns the empty cell of `game` closest to the centre, the first one by
+/// index among equals.
+pub(crate) fn central_cell(game: &GameY) -> Option<Coordinates> {
+    let size = game.board_size();
+    game.available_cells()
+        .iter()
+        .map(|&idx| Coordinates::from_index(idx, size))
+        .min_by_key(|&coords| (off_center(coords), coords.to_index(size)))
+}
+
+/// Distance of a cell from the centre of the board, as the sum of the
+/// differences between its coordinates.
+fn off_center(coords: Coordinates) -> u32 {
+    let (x, y, z) = (coords.x(), coords.y(), coords.z());
+    x.abs_diff(y) + y.abs_diff(z) + z.abs_diff(x)
+}
+
+/// The opening book of a [`MinimaxBot`] and the generator drawing its
+/// moves. Books compare by identity.
+#[derive(Default)]
+struct BookSlot(Option<(Arc<OpeningBook>, SharedRng)>);
+
+impl Clone for BookSlot {
+    fn clone(&self) -> Self {
+        Self(
+            self.0
+                .as_ref()
+                .map(|(book, rng)| (Arc::clone(book), SharedRng::new(rng.seed()))),
+        )
+    }
+}
+
+impl PartialEq for BookSlot {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some((a, _)), Some((b, _))) => Arc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+}
+
+impl std::fmt::Debug for BookSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some((book, _)) => write!(f, "Some({} positions)", book.len()),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+/// The anti-book of a [`MinimaxBot`], shared by its clones so that they all
+/// learn from each other's losses. Anti-books compare by identity.
+#[derive(Clone, Default)]
+struct AntiBookSlot(Option<Arc<RwLock<AntiBook>>>);
+
+impl PartialEq for AntiBookSlot {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+}
+
+impl std::fmt::Debug for AntiBookSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(anti_book) => {
+                let positions = anti_book
+                    .read()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .len();
+                write!(f, "Some({} positions)", positions)
+            }
+            None => f.write_str("None"),
+        }
+    }
+}
+
+/// The last iteration of the latest search of a [`MinimaxBot`], in any of
+/// its games. Clones start without one, and bots do not compare by it.
+#[derive(Debug, Default)]
+struct LastSearch(Mutex<Option<SearchInfo>>);
+
+impl Clone for LastSearch {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PartialEq for LastSearch {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// The opening variety of a [`MinimaxBot`] and the generator drawing its
+/// moves.
+#[derive(Debug, Default)]
+struct VarietySlot(Option<(OpeningVariety, SharedRng)>);
+
+impl Clone for VarietySlot {
+    fn clone(&self) -> Self {
+        Self(
+            self.0
+                .as_ref()
+                .map(|(variety, rng)| (*variety, SharedRng::new(rng.seed()))),
+        )
+    }
+}
+
+impl PartialEq for VarietySlot {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_ref().map(|(variety, _)| variety) == other.0.as_ref().map(|(variety, _)| variety)
+    }
 }
 
-impl MinimaxState {
-    pub fn new(game: &GameY, bot_player: PlayerId) -> Self {
-        let size = game.board_size();
-        let total_cells = game.total_cells() as usize;
+/// A bot that chooses its moves by an iterative-deepening alpha-beta
+/// search.
+///
+/// One bot can play many games at once, from as many threads. Each move
+/// is searched with the settings of the bot when it starts, and the
+/// transposition tables it keeps between moves are taken by one search at a
+/// time, so concurrent games never see each other's tables. Only the draws
+/// from an opening book are shared, which makes book moves depend on the
+/// order the games ask for them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinimaxBot {
+    settings: LiveSettings,
+    temperament: Temperament,
+    table_entries: usize,
+    cache: SearchCache,
+    opponent: ModelSlot,
+    book: BookSlot,
+    anti_book: AntiBookSlot,
+    variety: VarietySlot,
+    last_search: LastSearch,
+    #[cfg(feature = "serde")]
+    trace: TraceSlot,
+}
 
-        let mut board: Vec<u8> = vec![0; total_cells];
-        let mut coords_cache: Vec<Coordinates> = vec![Coordinates::new(0, 0, 0); total_cells];
-        let mut available_mask = FixedBitSet::with_capacity(total_cells);
-        let mut neighbors_cache = vec![Vec::new(); total_cells];
-        let mut edges_cache = vec![0; total_cells];
+impl MinimaxBot {
+    pub fn new(max_time_ms: u64) -> Self {
+        Self {
+            settings: LiveSettings::new(SearchLimits {
+                max_time_ms: Some(max_time_ms),
+                ..SearchLimits::default()
+            }),
+            temperament: Temperament::Balanced,
+            table_entries: DEFAULT_TABLE_ENTRIES,
+            cache: SearchCache::default(),
+            opponent: ModelSlot::default(),
+            book: BookSlot::default(),
+            anti_book: AntiBookSlot::default(),
+            variety: VarietySlot::default(),
+            last_search: LastSearch::default(),
+            #[cfg(feature = "serde")]
+            trace: TraceSlot::default(),
+        }
+    }
 
-        let bot_id = bot_player.id() as u8 + 1;
-        let human_id = game::other_player(bot_player).id() as u8 + 1;
+    /// Creates a bot with a time budget, depth cap, widening and table size
+    /// suited to boards of `board_size`: a tenth of a second of full-width
+    /// search on the smallest boards, up to two seconds of narrowed search
+    /// on the largest ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gamey::MinimaxBot;
+    ///
+    /// let small = MinimaxBot::for_board(4);
+    /// let large = MinimaxBot::for_board(15);
+    /// assert!(small.max_time_ms() < large.max_time_ms());
+    /// assert!(small.limits().widening.is_none());
+    /// assert!(large.limits().widening.is_some());
+    /// ```
+    pub fn for_board(board_size: u32) -> Self {
+        let (_, limits, table_entries) = BOARD_DEFAULTS
+            .into_iter()
+            .find(|(max_size, _, _)| board_size <= *max_size)
+            .unwrap_or(BOARD_DEFAULTS[BOARD_DEFAULTS.len() - 1]);
+        let mut bot = Self::new(0).with_table_entries(table_entries);
+        bot.settings.get_mut().limits = limits;
+        bot
+    }
 
-        // Buffers reutilizables para check_win
-        let visited = vec![false; total_cells];
-        let stack = Vec::with_capacity(total_cells / 4); // Capacidad estimada
+    /// Creates a bot that always searches exactly `depth` plies deep, with
+    /// no time limit. Its moves only depend on the position, which makes it
+    /// a reproducible opponent for tests and strength ladders.
+    ///
+    /// The bot also ignores the time budgets of
+    /// [`YBot::choose_move_within`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gamey::{GameY, MinimaxBot, YBot};
+    ///
+    /// let game = GameY::new(5);
+    /// let bot = MinimaxBot::with_depth(2);
+    /// assert_eq!(bot.max_time_ms(), None);
+    /// assert_eq!(bot.choose_move(&game), MinimaxBot::with_depth(2).choose_move(&game));
+    /// ```
+    pub fn with_depth(depth: u8) -> Self {
+        let mut bot = Self::new(0);
+        let limits = &mut bot.settings.get_mut().limits;
+        limits.max_time_ms = None;
+        limits.max_depth = Some(depth);
+        bot
+    }
 
-        // 1. Iterar sobre TODAS las celdas posibles del tablero
-        for idx in 0..total_cells {
-            let coords = Coordinates::from_index(idx as u32, size);
+    /// Sets the number of entries of the transposition table, rounded down to
+    /// a power of two. 0 disables the table.
+    ///
+    /// The table is kept from one move to the next as long as the bot keeps
+    /// playing the same side of the same game, so the positions analysed for
+    /// the previous move do not have to be searched again.
+    pub fn with_table_entries(mut self, entries: usize) -> Self {
+        self.table_entries = entries;
+        self
+    }
 
-            coords_cache[idx as usize] = coords;
+    /// Expects the opponent to play the replies of `opponent` instead of its
+    /// best replies, to set traps for a weak opponent and to search faster.
+    pub fn with_opponent_model(mut self, opponent: Arc<dyn OpponentModel>) -> Self {
+        self.opponent = ModelSlot(Some(opponent));
+        self
+    }
 
-            if !coords.is_valid(size) {
-                continue;
-            }
+    /// Plays the moves of `book` in the positions it covers, drawn according
+    /// to their weights, instead of searching. The draws are random unless
+    /// the bot is [reseeded](YBot::reseed).
+    pub fn with_opening_book(mut self, book: Arc<OpeningBook>) -> Self {
+        self.book = BookSlot(Some((book, SharedRng::from_entropy())));
+        self
+    }
 
-            for n_coords in game.get_neighbors(&coords) {
-                if n_coords.is_valid(size) {
-                    let n_idx = Coordinates::to_index(&n_coords, size) as usize;
-                    neighbors_cache[idx].push(n_idx);
-                }
-            }
+    /// Steers away from the moves `anti_book` recorded in lost games: their
+    /// search scores are lowered by its penalty for each loss, unless they
+    /// decide the game. Every game the bot loses is then recorded in it, as
+    /// told by [`YBot::learn_from_game`].
+    ///
+    /// The anti-book is shared with the clones of the bot.
+    pub fn with_anti_book(mut self, anti_book: AntiBook) -> Self {
+        self.anti_book = AntiBookSlot(Some(Arc::new(RwLock::new(anti_book))));
+        self
+    }
 
-            if coords.touches_side_a() {
-                edges_cache[idx] |= 0b001;
-            }
-            if coords.touches_side_b() {
-                edges_cache[idx] |= 0b010;
-            }
-            if coords.touches_side_c() {
-                edges_cache[idx] |= 0b100;
-            }
-        }
+    /// Returns a copy of the anti-book of the bot, to save what it learned.
+    pub fn anti_book(&self) -> Option<AntiBook> {
+        let anti_book = self.anti_book.0.as_ref()?;
+        Some(
+            anti_book
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone(),
+        )
+    }
 
-        // Copiar estado del tablero
-        for (coords, (_, owner)) in game.board_map() {
-            let idx = Coordinates::to_index(coords, size) as usize;
-            board[idx] = owner.id() as u8 + 1; // 1-based (0 = vacío)
-        }
+    /// Draws the moves of the opening among the near-equal ones, as set by
+    /// `variety`, instead of always playing the best scored move. The draws
+    /// are random unless the bot is [reseeded](YBot::reseed).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gamey::{GameY, MinimaxBot, OpeningVariety, YBot};
+    ///
+    /// let bot = MinimaxBot::with_depth(2).with_opening_variety(OpeningVariety::new(4, 50));
+    /// bot.reseed(7);
+    /// let first = bot.choose_move(&GameY::new(5));
+    /// bot.reseed(7);
+    /// assert_eq!(bot.choose_move(&GameY::new(5)), first);
+    /// ```
+    pub fn with_opening_variety(mut self, variety: OpeningVariety) -> Self {
+        self.variety = VarietySlot(Some((variety, SharedRng::from_entropy())));
+        self
+    }
 
-        // Poblar available_mask usando game.available_cells()
-        for &cell_idx in game.available_cells() {
-            available_mask.insert(cell_idx as usize);
-        }
+    /// Returns how the bot varies its opening moves, if it does.
+    pub fn opening_variety(&self) -> Option<OpeningVariety> {
+        self.variety.0.as_ref().map(|(variety, _)| *variety)
+    }
 
-        Self {
-            board,
-            size,
-            available_mask,
-            coords_cache,
-            neighbors_cache,
-            edges_cache,
-            bot_id,
-            human_id,
-            visited,
-            stack,
-        }
+    /// Writes the progress of every search of the bot to `trace`.
+    #[cfg(feature = "serde")]
+    pub fn with_trace(mut self, trace: Arc<SearchTrace>) -> Self {
+        self.trace = TraceSlot(Some(trace));
+        self
     }
 
-    fn make_move(&mut self, idx: usize, player: u8) {
-        self.board[idx] = player;
-        self.available_mask.set(idx, false);
+    /// Returns the use of the transposition table in the game searched
+    /// last.
+    pub fn table_stats(&self) -> TableStats {
+        let pool = self.cache.0.lock().unwrap_or_else(PoisonError::into_inner);
+        pool.last()
+            .map_or_else(TableStats::default, |cached| cached.table.stats())
     }
 
-    fn undo_move(&mut self, idx: usize) {
-        self.board[idx] = 0;
-        self.available_mask.set(idx, true);
+    /// Returns the last iteration of the latest search of the bot, in any
+    /// of its games, or None if the bot has not searched yet. Moves played
+    /// without searching, such as book moves, leave it as it was.
+    pub fn last_search(&self) -> Option<SearchInfo> {
+        self.last_search
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
     }
 
-    fn available_cells(&self) -> impl Iterator<Item = usize> + '_ {
-        self.available_mask.ones()
+    /// Returns a snapshot of the state the bot searches the position of
+    /// `game` from, with the settings of the bot and its latest search, to
+    /// attach to bug reports.
+    ///
+    /// Returns None if the game is over.
+    #[cfg(feature = "serde")]
+    pub fn snapshot(&self, game: &GameY) -> Option<SearchSnapshot> {
+        let bot_player = game.next_player()?;
+        let state = MinimaxState::new(game, bot_player).with_weights(self.weights());
+        Some(SearchSnapshot {
+            limits: Some(self.limits()),
+            temperament: Some(self.temperament),
+            table_stats: Some(self.table_stats()),
+            last_search: self.last_search(),
+            ..state.snapshot()
+        })
     }
 
-    fn occupied_cells(&self) -> impl Iterator<Item = usize> + '_ {
-        self.available_mask.zeroes()
+    /// Caps the depth of the search, in plies.
+    pub fn with_max_depth(mut self, max_depth: u8) -> Self {
+        self.settings.get_mut().limits.max_depth = Some(max_depth);
+        self
     }
 
-    /// Retorna true si el jugador conectó los 3 bordes
-    fn check_win(&mut self, player: u8) -> bool {
-        // Limpiar buffers
-        self.visited.fill(false);
+    /// Caps the number of nodes visited per move.
+    pub fn with_max_nodes(mut self, max_nodes: u64) -> Self {
+        self.settings.get_mut().limits.max_nodes = Some(max_nodes);
+        self
+    }
 
-        for idx in 0..self.board.len() {
-            if self.board[idx] == player && self.edges_cache[idx] != 0 && !self.visited[idx] {
-                let edges_reached = self.dfs_collect_edges(idx, player);
+    /// Narrows the deeper iterations of the search to the most promising
+    /// moves, to search deeper on large boards.
+    pub fn with_widening(mut self, widening: Widening) -> Self {
+        self.settings.get_mut().limits.widening = Some(widening);
+        self
+    }
 
-                if edges_reached == 0b111 {
-                    return true; // Early exit
-                }
-            }
-        }
+    /// Sets how the bot chooses between moves within [`TEMPERAMENT_MARGIN`]
+    /// of the best one.
+    pub fn with_temperament(mut self, temperament: Temperament) -> Self {
+        self.temperament = temperament;
+        self
+    }
 
-        false
+    /// Returns how the bot chooses between near-equal moves.
+    pub fn temperament(&self) -> Temperament {
+        self.temperament
     }
 
-    /// DFS que acumula los bits de bordes alcanzados
-    fn dfs_collect_edges(&mut self, start: usize, player: u8) -> u8 {
-        let mut edges_mask = 0u8;
+    /// Sets the weights of the static evaluation.
+    pub fn with_weights(mut self, weights: EvalWeights) -> Self {
+        self.settings.get_mut().weights = weights;
+        self
+    }
 
-        self.stack.clear();
-        self.stack.push(start);
-        self.visited[start] = true;
+    /// Returns the time budget per move, in milliseconds, or None if the bot
+    /// searches to a fixed depth.
+    pub fn max_time_ms(&self) -> Option<u64> {
+        self.limits().max_time_ms
+    }
 
-        while let Some(idx) = self.stack.pop() {
-            edges_mask |= self.edges_cache[idx];
+    /// Returns the search limits used for every move.
+    pub fn limits(&self) -> SearchLimits {
+        self.settings.read().limits
+    }
 
-            // Early exit: Si ya tenemos los 3 bordes, no seguimos
-            if edges_mask == 0b111 {
-                return edges_mask;
-            }
+    /// Replaces the search limits of a bot already in use, for example to
+    /// change its difficulty or time budget from one phase of a game to the
+    /// next. Without a time limit the bot searches to a fixed depth, like a
+    /// bot built with [`MinimaxBot::with_depth`].
+    ///
+    /// Searches already running keep the limits they started with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use gamey::{MinimaxBot, SearchLimits};
+    ///
+    /// let bot = Arc::new(MinimaxBot::new(1000));
+    /// bot.set_limits(SearchLimits {
+    ///     max_time_ms: Some(200),
+    ///     max_depth: Some(4),
+    ///     ..bot.limits()
+    /// });
+    /// assert_eq!(bot.max_time_ms(), Some(200));
+    /// ```
+    pub fn set_limits(&self, limits: SearchLimits) {
+        self.settings.write().limits = limits;
+    }
 
-            for &neighbor in &self.neighbors_cache[idx] {
-                if self.board[neighbor] == player && !self.visited[neighbor] {
-                    self.visited[neighbor] = true;
-                    self.stack.push(neighbor);
-                }
-            }
-        }
+    /// Returns the weights of the static evaluation.
+    pub fn weights(&self) -> EvalWeights {
+        self.settings.read().weights
+    }
 
-        edges_mask
+    /// Replaces the weights of the static evaluation of a bot already in
+    /// use. The transposition tables of its games start over, as their
+    /// scores were computed with the old weights.
+    ///
+    /// Searches already running keep the weights they started with.
+    pub fn set_weights(&self, weights: EvalWeights) {
+        self.settings.write().weights = weights;
     }
-}
 
-pub struct MinimaxBot {
-    max_time_ms: u64,
-}
+    /// Returns a move at once, without searching: the book move if the
+    /// position is in the opening book, and otherwise the empty cell closest
+    /// to the centre.
+    ///
+    /// The bot plays this move when its time budget is at most
+    /// [`INSTANT_MOVE_MAX_MS`]. Returns None if the game is over.
+    pub fn instant_move(&self, game: &GameY) -> Option<Coordinates> {
+        game.next_player()?;
+        if let Some(coords) = self.book_move(game) {
+            return Some(coords);
+        }
+        central_cell(game)
+    }
 
-impl MinimaxBot {
-    pub fn new(max_time_ms: u64) -> Self {
-        Self { max_time_ms }
+    /// Chooses a move like [`YBot::choose_move`] and returns it with the tree
+    /// the search explored, to see why the bot preferred it.
+    ///
+    /// Returns None if the game is over.
+    pub fn choose_move_with_tree(
+        &self,
+        game: &GameY,
+        caps: TreeCaps,
+    ) -> Option<(Coordinates, SearchTree)> {
+        let bot_player = game.next_player()?;
+        let mut state = MinimaxState::new(game, bot_player).with_weights(self.weights());
+        let (best_move, mut tree) = search_with_tree(&mut state, &self.limits(), caps);
+        tree.board_size = Some(game.board_size());
+        let coords = Coordinates::from_index(best_move? as u32, game.board_size());
+        Some((coords, tree))
     }
 }
 
@@ -176,74 +1767,609 @@ impl YBot for MinimaxBot {
     }
 
     fn choose_move(&self, game: &GameY) -> Option<Coordinates> {
-        let bot_player = game.next_player()?; // Early exit si terminó el juego
+        self.choose_move_with_limits(game, &self.limits())
+    }
+
+    fn choose_move_within(&self, game: &GameY, max_time_ms: u64) -> Option<Coordinates> {
+        let limits = self.limits();
+        let limits = SearchLimits {
+            max_time_ms: limits.max_time_ms.map(|_| max_time_ms),
+            ..limits
+        };
+        self.choose_move_with_limits(game, &limits)
+    }
+
+    fn choose_movement(&self, game: &GameY) -> Option<crate::Movement> {
+        self.choose_movement_with_swap(game)
+    }
 
-        let mut state = MinimaxState::new(game, bot_player);
+    fn reseed(&self, seed: u64) {
+        if let Some((_, rng)) = &self.book.0 {
+            rng.reseed(seed);
+        }
+        if let Some((_, rng)) = &self.variety.0 {
+            rng.reseed(derive_seed(seed, 1));
+        }
+    }
 
-        if let Some(coordinates) = greedy_search(&mut state) {
-            return Some(coordinates);
+    fn learn_from_game(&self, game: &GameY, player: PlayerId) {
+        let Some(anti_book) = &self.anti_book.0 else {
+            return;
         };
+        if game.result().is_some_and(|result| result.winner != player) {
+            anti_book
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .record_loss(game, player);
+        }
+    }
+}
+
+impl MinimaxBot {
+    fn choose_move_with_limits(&self, game: &GameY, limits: &SearchLimits) -> Option<Coordinates> {
+        // The budget covers setting up the search as well
+        let deadline = limits
+            .max_time_ms
+            .map(|ms| Instant::now() + Duration::from_millis(ms));
+        let bot_player = game.next_player()?; // Early exit si terminó el juego
+        if limits
+            .max_time_ms
+            .is_some_and(|ms| ms <= INSTANT_MOVE_MAX_MS)
+        {
+            return self.instant_move(game);
+        }
+        let avoided = self.avoided_moves(game);
+        // The book and the opening shortcut would walk into the same losses
+        if avoided.is_empty()
+            && let Some(coords) = self.book_move(game)
+        {
+            return Some(coords);
+        }
+        let variety = self
+            .variety
+            .0
+            .as_ref()
+            .filter(|(variety, _)| game.history().len() < variety.moves);
+        // Searches of every empty board from size 3 to 19 open in the centre.
+        // The search ignores opening rules, so a restricted move is the
+        // allowed cell closest to the centre
+        if (game.stone_count() == 0 && variety.is_none() && avoided.is_empty())
+            || game
+                .opening_rule()
+                .restricts_placements(game.history().len())
+        {
+            return central_cell(game);
+        }
 
-        let best_move = iterative_deepening_search(&mut state, self.max_time_ms);
+        let weights = self.weights();
+        let mut state = MinimaxState::new(game, bot_player).with_weights(weights);
+        let mut cached = self.take_table(game, bot_player, weights);
+        let opponent = self.opponent.0.as_deref();
+        let mut root_scores = Vec::new();
+        let mut last_search = None;
+        #[cfg(feature = "serde")]
+        let mut trace = self
+            .trace
+            .0
+            .as_ref()
+            .map(|trace| trace.start_search(game, bot_player));
+        let best_move = within_deadline(deadline, |stop| {
+            run_search(
+                &mut state,
+                limits,
+                stop,
+                cached.as_mut().map(|cached| &mut cached.table),
+                opponent,
+                self.temperament,
+                &mut |info| {
+                    #[cfg(feature = "serde")]
+                    if let Some(record) = &mut trace {
+                        record(info);
+                    }
+                    if variety.is_some() || !avoided.is_empty() {
+                        root_scores.clone_from(&info.root_scores);
+                    }
+                    last_search = Some(info.clone());
+                },
+            )
+        });
+        if last_search.is_some() {
+            *self
+                .last_search
+                .0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = last_search;
+        }
+        if let Some(cached) = cached {
+            let mut pool = self.cache.0.lock().unwrap_or_else(PoisonError::into_inner);
+            pool.push(cached);
+        }
+        let mut best_move = best_move?;
+        penalise_avoided(&mut root_scores, &avoided);
+        if avoided.iter().any(|&(m, _)| m == best_move)
+            && let Some(&(top, _)) = root_scores.first()
+        {
+            best_move = top;
+        }
+        if let Some((variety, rng)) = variety
+            && let Some(drawn) = rng.with(|rng| draw_near_best(&root_scores, variety.margin, rng))
+        {
+            best_move = drawn;
+        }
 
         let coordinates = Coordinates::from_index(best_move as u32, game.board_size());
         Some(coordinates)
     }
+
+    /// Returns the cells of the moves from the position of `game` that the
+    /// anti-book recorded in lost games, with the score to take off each.
+    fn avoided_moves(&self, game: &GameY) -> Vec<(usize, i32)> {
+        let Some(anti_book) = &self.anti_book.0 else {
+            return Vec::new();
+        };
+        let anti_book = anti_book.read().unwrap_or_else(PoisonError::into_inner);
+        let penalty = anti_book.penalty();
+        anti_book
+            .losing_moves(game)
+            .into_iter()
+            .map(|(coords, losses)| {
+                let index = coords.to_index(game.board_size()) as usize;
+                let losses = i32::try_from(losses).unwrap_or(i32::MAX);
+                (index, penalty.saturating_mul(losses))
+            })
+            .collect()
+    }
+
+    /// Draws a move of the opening book, if the position of `game` is in it.
+    fn book_move(&self, game: &GameY) -> Option<Coordinates> {
+        // Books are built from free openings
+        if game
+            .opening_rule()
+            .restricts_placements(game.history().len())
+        {
+            return None;
+        }
+        let (book, rng) = self.book.0.as_ref()?;
+        rng.with(|rng| book.choose(game, rng))
+    }
+
+    /// Takes the table to search `game` with out of the pool: the table
+    /// filled by this bot for the latest earlier move of the game, or else
+    /// the table used least recently, cleared. Games are told apart by
+    /// their moves, so concurrent games never search with each other's
+    /// tables.
+    fn take_table(
+        &self,
+        game: &GameY,
+        bot_player: PlayerId,
+        weights: EvalWeights,
+    ) -> Option<CachedTable> {
+        if self.table_entries == 0 {
+            return None;
+        }
+        let mut pool = self.cache.0.lock().unwrap_or_else(PoisonError::into_inner);
+        // A new game, a takeback or new settings invalidate a table
+        let reusable = pool
+            .iter()
+            .enumerate()
+            .filter(|(_, cached)| {
+                cached.board_size == game.board_size()
+                    && cached.bot_player == bot_player
+                    && cached.weights == weights
+                    && game.history().starts_with(&cached.history)
+            })
+            .max_by_key(|(_, cached)| cached.history.len())
+            .map(|(index, _)| index);
+        let mut cached = match reusable {
+            Some(index) => pool.remove(index),
+            // The table used least recently is the first one
+            None if !pool.is_empty() => {
+                let mut cached = pool.remove(0);
+                cached.table.clear();
+                cached.board_size = game.board_size();
+                cached.bot_player = bot_player;
+                cached.weights = weights;
+                cached
+            }
+            None => CachedTable {
+                table: TranspositionTable::new(self.table_entries),
+                board_size: game.board_size(),
+                bot_player,
+                weights,
+                history: Vec::new(),
+            },
+        };
+        drop(pool);
+        cached.history = game.history().to_vec();
+        Some(cached)
+    }
+
+    /// Searches `state` for the best move of its bot player and returns the cell index.
+    ///
+    /// Returns None if there is no empty cell left.
+    pub fn search(&self, state: &mut MinimaxState) -> Option<usize> {
+        search_with_limits(state, &self.limits(), &NEVER_STOP, &mut |_| {})
+    }
+}
+
+/// Searches `state` for the best move of its bot player within `limits`.
+///
+/// The search also ends as soon as `stop` is set. `on_info` is called after
+/// every completed iteration of the iterative deepening. The only empty
+/// cell, and moves that win or block a win at once, are returned without
+/// searching, and without reports.
+///
+/// Returns None if there is no empty cell left.
+pub fn search_with_limits(
+    state: &mut MinimaxState,
+    limits: &SearchLimits,
+    stop: &AtomicBool,
+    on_info: &mut dyn FnMut(&SearchInfo),
+) -> Option<usize> {
+    run_search(
+        state,
+        limits,
+        stop,
+        None,
+        None,
+        Temperament::Balanced,
+        on_info,
+    )
 }
 
-fn greedy_search(state: &mut MinimaxState) -> Option<Coordinates> {
-    let moves: SmallVec<[usize; 128]> = state.available_cells().collect();
+/// Runs `search` on a worker thread and sets the stop flag it is given at
+/// `deadline`, then waits for it to return.
+///
+/// The search checks the flag at every node, so it returns the best move of
+/// its last completed iteration within a few milliseconds of the budget,
+/// however long the iteration in progress would have taken. Without a
+/// budget, `search` runs on the calling thread and is never stopped.
+fn within_deadline<T: Send>(
+    deadline: Option<Instant>,
+    search: impl FnOnce(&AtomicBool) -> T + Send,
+) -> T {
+    let Some(deadline) = deadline else {
+        return search(&NEVER_STOP);
+    };
+    let stop = AtomicBool::new(false);
+    let (done, finished) = mpsc::channel();
+    std::thread::scope(|scope| {
+        let stop = &stop;
+        let worker = scope.spawn(move || {
+            let result = search(stop);
+            // The caller stops waiting once the search is over
+            let _ = done.send(());
+            result
+        });
+        let wait = deadline.saturating_duration_since(Instant::now());
+        if finished.recv_timeout(wait) == Err(RecvTimeoutError::Timeout) {
+            stop.store(true, Ordering::Relaxed);
+        }
+        worker
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
 
-    for move_idx in moves {
-        state.make_move(move_idx, state.bot_id);
-        if state.check_win(state.bot_id) {
-            println!(">>> INSTANT WIN FOUND at {}", move_idx);
-            return Some(Coordinates::from_index(move_idx as u32, state.size));
+/// Searches `state` like [`search_with_limits`], but expecting the opponent
+/// to play the replies of `opponent` instead of its best replies.
+///
+/// Returns None if there is no empty cell left.
+pub fn search_against(
+    state: &mut MinimaxState,
+    limits: &SearchLimits,
+    opponent: &dyn OpponentModel,
+) -> Option<usize> {
+    run_search(
+        state,
+        limits,
+        &NEVER_STOP,
+        None,
+        Some(opponent),
+        Temperament::Balanced,
+        &mut |_| {},
+    )
+}
+
+/// Runs [`search_with_limits`], keeping the scores it finds in `table` for
+/// later searches, modelling the opponent with `opponent` and choosing
+/// between near-equal moves by `temperament`.
+fn run_search(
+    state: &mut MinimaxState,
+    limits: &SearchLimits,
+    stop: &AtomicBool,
+    mut table: Option<&mut TranspositionTable>,
+    opponent: Option<&dyn OpponentModel>,
+    temperament: Temperament,
+    on_info: &mut dyn FnMut(&SearchInfo),
+) -> Option<usize> {
+    let first = state.available_cells().next()?;
+    if state.available_mask.count_ones(..) == 1 {
+        return Some(first);
+    }
+    if let Some(table) = table.as_deref_mut() {
+        table.new_search();
+    }
+
+    if let Some(idx) = greedy_search(state, stop) {
+        return Some(idx);
+    };
+
+    let mut control = SearchControl::new(limits.max_nodes, stop);
+    control.table = table;
+    control.opponent = opponent;
+    let best_move = iterative_deepening_search(state, limits, &mut control, on_info);
+    if let Some(table) = control.table.as_deref_mut() {
+        table.add_nodes(control.nodes);
+    }
+    let best_move = match control.root_scores.first() {
+        Some(&(best, _)) if best as usize == best_move => {
+            choose_by_temperament(state, &control.root_scores, temperament, stop)
+        }
+        // The last iteration was interrupted before confirming its scores
+        _ => best_move,
+    };
+    Some(best_move)
+}
+
+/// Chooses among the root moves within [`TEMPERAMENT_MARGIN`] of the best
+/// one, by the deviation of the evaluations after the opponent's replies.
+/// `root_scores` must be sorted best first. Once `stop` is set, the best
+/// move is kept.
+fn choose_by_temperament(
+    state: &mut MinimaxState,
+    root_scores: &[(CellIdx, i32)],
+    temperament: Temperament,
+    stop: &AtomicBool,
+) -> usize {
+    let (best_move, best_score) = root_scores[0];
+    // A decided game leaves nothing to choose
+    if temperament == Temperament::Balanced || best_score.abs() >= WIN_SCORE - 100 {
+        return best_move as usize;
+    }
+    let candidates: Option<Vec<_>> = root_scores
+        .iter()
+        .take_while(|&&(_, score)| best_score - score <= TEMPERAMENT_MARGIN)
+        .map(|&(m, score)| {
+            let spread = reply_spread(state, m as usize, stop)? as i32;
+            let sharpness = match temperament {
+                Temperament::Aggressive => spread,
+                _ => -spread,
+            };
+            Some((sharpness, score, m))
+        })
+        .collect();
+    let Some(candidates) = candidates else {
+        return best_move as usize;
+    };
+    candidates
+        .into_iter()
+        .max_by_key(|&(sharpness, score, m)| (sharpness, score, cmp::Reverse(m)))
+        .map_or(best_move as usize, |(_, _, m)| m as usize)
+}
+
+/// Lowers the scores of the `avoided` root moves by their penalties, unless
+/// they decide the game, and sorts `root_scores` best first again.
+fn penalise_avoided(root_scores: &mut [(usize, i32)], avoided: &[(usize, i32)]) {
+    if avoided.is_empty() {
+        return;
+    }
+    for (m, score) in root_scores.iter_mut() {
+        if decided_in(*score).is_some() {
+            continue;
+        }
+        if let Some(&(_, penalty)) = avoided.iter().find(|&&(avoided, _)| avoided == *m) {
+            *score = score.saturating_sub(penalty);
+        }
+    }
+    root_scores.sort_by_key(|&(_, score)| cmp::Reverse(score));
+}
+
+/// Draws uniformly among the root moves within `margin` of the best one.
+/// `root_scores` must be sorted best first. Returns None if there is no
+/// root move, or if the best one decides the game.
+fn draw_near_best(root_scores: &[(usize, i32)], margin: i32, rng: &mut GameRng) -> Option<usize> {
+    let &(_, best_score) = root_scores.first()?;
+    if best_score.abs() >= WIN_SCORE - 100 {
+        return None;
+    }
+    let near = root_scores
+        .iter()
+        .take_while(|&&(_, score)| best_score - score <= margin)
+        .count();
+    Some(root_scores[rng.random_range(0..near)].0)
+}
+
+/// Returns the standard deviation of the static evaluations over every
+/// reply of the opponent to the bot playing `cell`, or 0 if the board is
+/// then full. Returns None if `stop` is set before every reply is scored.
+fn reply_spread(state: &mut MinimaxState, cell: usize, stop: &AtomicBool) -> Option<u32> {
+    state.make_move(cell, state.bot_id);
+    let replies: Vec<usize> = state.available_cells().collect();
+    let scores: Option<Vec<f64>> = replies
+        .into_iter()
+        .map(|reply| {
+            if stop.load(Ordering::Relaxed) {
+                return None;
+            }
+            state.make_move(reply, state.human_id);
+            let score = evaluate_state(state);
+            state.undo_move(reply);
+            Some(f64::from(score))
+        })
+        .collect();
+    state.undo_move(cell);
+    let scores = scores?;
+    if scores.is_empty() {
+        return Some(0);
+    }
+    let mean = scores.iter().sum::<f64>() / scores.len() as f64;
+    let variance = scores
+        .iter()
+        .map(|score| (score - mean).powi(2))
+        .sum::<f64>()
+        / scores.len() as f64;
+    Some(variance.sqrt().round() as u32)
+}
+
+/// Searches `state` within `limits` and returns the best move of its bot
+/// player together with its score.
+///
+/// Unlike [`search_with_limits`], moves that win or block at once are
+/// searched like any other, so that the score is always known. If no
+/// iteration completes, the score is the static evaluation of `state`.
+///
+/// Returns None if there is no empty cell left.
+pub fn search_scored(state: &mut MinimaxState, limits: &SearchLimits) -> Option<(usize, i32)> {
+    state.available_cells().next()?;
+
+    let mut control = SearchControl::new(limits.max_nodes, &NEVER_STOP);
+    let mut score = None;
+    let best_move = iterative_deepening_search(state, limits, &mut control, &mut |info| {
+        score = Some(info.score)
+    });
+    Some((best_move, score.unwrap_or_else(|| state.evaluate())))
+}
+
+/// Searches `state` like [`search_with_limits`] and also returns the tree
+/// explored by the last completed iteration, within `caps`.
+///
+/// The tree is empty when the move was found without searching, because it
+/// wins or blocks a win at once.
+pub fn search_with_tree(
+    state: &mut MinimaxState,
+    limits: &SearchLimits,
+    caps: TreeCaps,
+) -> (Option<usize>, SearchTree) {
+    if state.available_cells().next().is_none() {
+        return (None, SearchTree::default());
+    }
+    if let Some(idx) = greedy_search(state, &NEVER_STOP) {
+        return (Some(idx), SearchTree::default());
+    }
+
+    let mut control = SearchControl::new(limits.max_nodes, &NEVER_STOP);
+    control.tree = Some(TreeRecorder::new(caps));
+    let best_move = iterative_deepening_search(state, limits, &mut control, &mut |_| {});
+    let tree = control.tree.take().map(TreeRecorder::into_tree);
+    (Some(best_move), tree.unwrap_or_default())
+}
+
+/// Returns a move that wins, or blocks a win of the opponent, at once.
+/// Gives up without a move once `stop` is set.
+fn greedy_search(state: &mut MinimaxState, stop: &AtomicBool) -> Option<usize> {
+    // El slot 0 nunca se usa en la búsqueda (profundidad 0 = evaluación)
+    let moves = state.take_moves(0);
+    let mut found = None;
+
+    for &move_idx in &moves {
+        if stop.load(Ordering::Relaxed) {
+            break;
         }
+        let move_idx = move_idx as usize;
+        state.make_move(move_idx, state.bot_id);
+        let wins = state.check_win(state.bot_id);
         state.undo_move(move_idx);
+        if wins {
+            tracing::debug!(">>> INSTANT WIN FOUND at {}", move_idx);
+            found = Some(move_idx);
+            break;
+        }
 
         state.make_move(move_idx, state.human_id);
-        if state.check_win(state.human_id) {
-            println!(">>> BLOCKING IMMEDIATE THREAT at {}", move_idx);
-            return Some(Coordinates::from_index(move_idx as u32, state.size));
-        }
+        let loses = state.check_win(state.human_id);
         state.undo_move(move_idx);
+        if loses {
+            tracing::debug!(">>> BLOCKING IMMEDIATE THREAT at {}", move_idx);
+            found = Some(move_idx);
+            break;
+        }
     }
-    None
+
+    state.return_moves(0, moves);
+    found
 }
 
-fn iterative_deepening_search(state: &mut MinimaxState, max_time_ms: u64) -> usize {
+fn iterative_deepening_search(
+    state: &mut MinimaxState,
+    limits: &SearchLimits,
+    control: &mut SearchControl,
+    on_info: &mut dyn FnMut(&SearchInfo),
+) -> usize {
     let start_time = Instant::now();
-    let time_limit = Duration::from_millis(max_time_ms);
+    let time_limit = limits.max_time_ms.map(Duration::from_millis);
+    let out_of_time = || time_limit.is_some_and(|limit| start_time.elapsed() >= limit);
+    let max_depth = limits
+        .max_depth
+        .unwrap_or(MAX_SEARCH_DEPTH)
+        .clamp(1, MAX_SEARCH_DEPTH);
 
     let mut best_move = state.available_cells().next().expect("No available moves"); // Fallback inicial
     let mut pv_move: Option<usize> = None;
 
-    for depth in 1..=100 {
-        if start_time.elapsed() >= time_limit {
-            println!("Time limit reached at depth {}", depth - 1);
+    for depth in 1..=max_depth {
+        if out_of_time() {
+            tracing::debug!("Time limit reached at depth {}", depth - 1);
             break;
         }
 
-        println!("Searching at depth {}...", depth);
+        tracing::debug!("Searching at depth {}...", depth);
 
-        let (move_found, score) = search_best_move(state, depth, pv_move);
+        let widening = limits
+            .widening
+            .filter(|widening| depth >= widening.start_depth);
+        control.root_limit = widening.map(|widening| widening.root_moves.max(1));
+        control.interior_limit = widening.and_then(|widening| widening.interior_moves);
+        let (move_found, score) = search_best_move(state, depth, pv_move, control);
+
+        if control.aborted {
+            // An interrupted iteration is only trusted if nothing better exists
+            if pv_move.is_none() {
+                best_move = move_found;
+            }
+            tracing::debug!("Search stopped during depth {}", depth);
+            break;
+        }
 
         best_move = move_found;
         pv_move = Some(move_found);
-
-        println!(
+        if let Some(tree) = &mut control.tree {
+            tree.finish_iteration(score);
+        }
+        on_info(&SearchInfo {
+            depth,
+            score,
+            nodes: control.nodes,
+            elapsed_ms: start_time.elapsed().as_millis() as u64,
+            pv: control.pv_table[depth as usize].clone(),
+            root_scores: control
+                .root_scores
+                .iter()
+                .map(|&(cell, score)| (cell as usize, score))
+                .collect(),
+            root_nodes: control
+                .root_nodes
+                .iter()
+                .enumerate()
+                .filter(|&(_, &nodes)| nodes > 0)
+                .map(|(cell, &nodes)| (cell, nodes))
+                .collect(),
+        });
+
+        tracing::debug!(
             "Depth {}: best move = {}, score = {}",
-            depth, move_found, score
+            depth,
+            move_found,
+            score
         );
 
         if score >= WIN_SCORE - 100 {
-            println!("Winning move found at depth {}", depth);
+            tracing::debug!("Winning move found at depth {}", depth);
             break;
         }
 
-        if start_time.elapsed() >= time_limit {
-            println!("Time limit reached after depth {}", depth);
+        if out_of_time() {
+            tracing::debug!("Time limit reached after depth {}", depth);
             break;
         }
     }
@@ -251,12 +2377,27 @@ fn iterative_deepening_search(state: &mut MinimaxState, max_time_ms: u64) -> usi
     best_move
 }
 
-fn search_best_move(state: &mut MinimaxState, depth: u8, pv_move: Option<usize>) -> (usize, i32) {
-    let mut moves: Vec<usize> = state.available_cells().collect();
+fn search_best_move(
+    state: &mut MinimaxState,
+    depth: u8,
+    pv_move: Option<usize>,
+    control: &mut SearchControl,
+) -> (usize, i32) {
+    let mut moves = state.take_moves(depth as usize);
+    // When widening, only the best moves of the last iteration are searched
+    if let Some(limit) = control.root_limit
+        && !control.root_scores.is_empty()
+    {
+        moves.clear();
+        moves.extend(control.root_scores.iter().take(limit).map(|&(m, _)| m));
+    }
+    let mut root_scores = std::mem::take(&mut control.next_root_scores);
+    root_scores.clear();
 
     // Insert PV move at the beginning of the list
+    #[allow(clippy::collapsible_if)]
     if let Some(pv) = pv_move {
-        if let Some(pos) = moves.iter().position(|&m| m == pv) {
+        if let Some(pos) = moves.iter().position(|&m| m as usize == pv) {
             moves.swap(0, pos);
         }
     }
@@ -264,21 +2405,43 @@ fn search_best_move(state: &mut MinimaxState, depth: u8, pv_move: Option<usize>)
     // TODO: Order moves
 
     let mut best_score = -INFINITY;
-    let mut best_move = moves[0]; // Fallback inicial
+    let mut best_move = moves[0] as usize; // Fallback inicial
+    if let Some(tree) = &mut control.tree {
+        tree.start_iteration(depth, -INFINITY, INFINITY);
+    }
+    control.root_nodes.resize(state.num_cells(), 0);
 
-    for move_idx in moves {
+    for &move_idx in &moves {
+        let move_idx = move_idx as usize;
         state.make_move(move_idx, state.bot_id);
 
-        let score = minimax(state, depth - 1, -INFINITY, INFINITY, false);
+        let nodes_before = control.nodes;
+        control.enter(move_idx, state.bot_id, -INFINITY, INFINITY);
+        let score = minimax(state, depth - 1, -INFINITY, INFINITY, false, control);
+        control.leave(score);
+        control.root_nodes[move_idx] += control.nodes - nodes_before;
 
         state.undo_move(move_idx);
 
+        if control.aborted {
+            break;
+        }
+        root_scores.push((move_idx as CellIdx, score));
         if score > best_score {
             best_score = score;
             best_move = move_idx;
+            control.update_pv(depth, move_idx);
         }
     }
 
+    // Only a completed iteration replaces the scores of the last one. The
+    // sort is stable, so that equal scores keep the PV move first
+    if !control.aborted {
+        root_scores.sort_by_key(|&(_, score)| cmp::Reverse(score));
+        std::mem::swap(&mut control.root_scores, &mut root_scores);
+    }
+    control.next_root_scores = root_scores;
+    state.return_moves(depth as usize, moves);
     (best_move, best_score)
 }
 
@@ -288,27 +2451,120 @@ fn minimax(
     mut alpha: i32,
     mut beta: i32,
     maximizing_player: bool,
+    control: &mut SearchControl,
 ) -> i32 {
+    control.pv_table[depth as usize].clear();
+    if control.visit() {
+        return 0;
+    }
     if depth == 0 {
-        return evaluate_state(state);
+        return at_ply(evaluate_state(state), control.ply);
+    }
+    // The player who has just moved may have won, which ends the line
+    if maximizing_player && state.check_win(state.human_id) {
+        return at_ply(LOSE_SCORE, control.ply);
+    }
+    if !maximizing_player && state.check_win(state.bot_id) {
+        return at_ply(WIN_SCORE, control.ply);
+    }
+    // Two winning cells cannot both be blocked, but the horizon hides the
+    // win they force unless two more plies are searched
+    if depth == 1
+        && let Some(&last) = control.line.last()
+        && let Some((block, win)) = state.double_threat(last)
+    {
+        let (to_move, won) = if maximizing_player {
+            (state.bot_id, LOSE_SCORE)
+        } else {
+            (state.human_id, WIN_SCORE)
+        };
+        // Unless the player to move wins first
+        if !state.has_winning_cell(to_move) {
+            control.pv_table[depth as usize].extend([block, win]);
+            return at_ply(won, control.ply + 2);
+        }
+    }
+
+    // A modelled opponent is expected to play its model's reply, not the best one
+    if !maximizing_player
+        && let Some(opponent) = control.opponent
+        && let Some(reply) = opponent.choose_reply(state)
+        && state.available_mask.contains(reply)
+    {
+        state.make_move(reply, state.human_id);
+        control.enter(reply, state.human_id, alpha, beta);
+        let score = minimax(state, depth - 1, alpha, beta, true, control);
+        control.leave(score);
+        state.undo_move(reply);
+        if !control.aborted {
+            control.update_pv(depth, reply);
+        }
+        return score;
+    }
+
+    let key = if maximizing_player {
+        state.hash
+    } else {
+        state.hash ^ OPPONENT_TO_MOVE_KEY
+    };
+    let mut table_move = None;
+    if let Some(table) = control.table.as_deref_mut()
+        && let Some(hit) = table.probe(key, depth, control.ply, alpha, beta)
+    {
+        if let Some(score) = hit.score {
+            return score;
+        }
+        table_move = hit.best_move;
     }
 
-    let moves: SmallVec<[usize; 128]> = state.available_cells().collect();
+    let mut moves = state.take_moves(depth as usize);
+    if moves.is_empty() {
+        // The board filled up before reaching the horizon
+        state.return_moves(depth as usize, moves);
+        return at_ply(evaluate_state(state), control.ply);
+    }
+    // Search the best move of an earlier search first
+    if let Some(best) = table_move
+        && let Some(pos) = moves.iter().position(|&m| m == best)
+    {
+        moves.swap(0, pos);
+    }
+    // Ordering the moves costs about as much as searching leaves, so nodes
+    // just above the horizon keep them all
+    if let Some(limit) = control.interior_limit
+        && depth >= 2
+        && moves.len() > limit
+    {
+        keep_best_moves(state, &mut moves, limit, maximizing_player, table_move);
+    }
 
-    if maximizing_player {
+    let (window_alpha, window_beta) = (alpha, beta);
+    let mut best_move = None;
+    let score = if maximizing_player {
         let mut best_score = -INFINITY;
 
-        for move_idx in moves {
+        for &move_idx in &moves {
+            let move_idx = move_idx as usize;
             state.make_move(move_idx, state.bot_id);
 
-            let score = minimax(state, depth - 1, alpha, beta, false);
+            control.enter(move_idx, state.bot_id, alpha, beta);
+            let score = minimax(state, depth - 1, alpha, beta, false, control);
+            control.leave(score);
 
             state.undo_move(move_idx);
 
-            best_score = cmp::max(best_score, score);
+            if control.aborted {
+                break;
+            }
+            if score > best_score {
+                best_score = score;
+                best_move = Some(move_idx as CellIdx);
+                control.update_pv(depth, move_idx);
+            }
 
             alpha = cmp::max(alpha, score);
             if beta <= alpha {
+                control.cutoff();
                 break;
             }
         }
@@ -316,21 +2572,91 @@ fn minimax(
     } else {
         let mut worst_score = INFINITY;
 
-        for move_idx in moves {
+        for &move_idx in &moves {
+            let move_idx = move_idx as usize;
             state.make_move(move_idx, state.human_id);
 
-            let score = minimax(state, depth - 1, alpha, beta, true);
+            control.enter(move_idx, state.human_id, alpha, beta);
+            let score = minimax(state, depth - 1, alpha, beta, true, control);
+            control.leave(score);
 
             state.undo_move(move_idx);
 
-            worst_score = cmp::min(worst_score, score);
+            if control.aborted {
+                break;
+            }
+            if score < worst_score {
+                worst_score = score;
+                best_move = Some(move_idx as CellIdx);
+                control.update_pv(depth, move_idx);
+            }
 
             beta = cmp::min(beta, score);
             if beta <= alpha {
+                control.cutoff();
                 break;
             }
         }
         worst_score
+    };
+
+    state.return_moves(depth as usize, moves);
+    if !control.aborted
+        && let Some(table) = control.table.as_deref_mut()
+    {
+        let bound = if score <= window_alpha {
+            Bound::Upper
+        } else if score >= window_beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        table.store(key, depth, control.ply, score, bound, best_move);
+    }
+    score
+}
+
+/// Keeps the `limit` moves of `moves` after which the static evaluation is
+/// best for the player to move, best first. `first` is kept first whatever
+/// its evaluation.
+fn keep_best_moves(
+    state: &mut MinimaxState,
+    moves: &mut Vec<CellIdx>,
+    limit: usize,
+    maximizing_player: bool,
+    first: Option<CellIdx>,
+) {
+    let player = if maximizing_player {
+        state.bot_id
+    } else {
+        state.human_id
+    };
+    let mut scored = std::mem::take(&mut state.order_buffer);
+    scored.clear();
+    scored.extend(moves.iter().map(|&m| {
+        if Some(m) == first {
+            return (i32::MAX, m);
+        }
+        state.make_move(m as usize, player);
+        let score = evaluate_state(state);
+        state.undo_move(m as usize);
+        (if maximizing_player { score } else { -score }, m)
+    }));
+    // Moves are unique, so an unstable sort gives the same order
+    scored.sort_unstable_by_key(|&(score, m)| (cmp::Reverse(score), m));
+    moves.clear();
+    moves.extend(scored.iter().take(limit).map(|&(_, m)| m));
+    state.order_buffer = scored;
+}
+
+/// Moves the score of a won or lost position `ply` plies from the root
+/// towards zero by one per ply, so that the search prefers the quickest
+/// wins and the slowest losses.
+fn at_ply(score: i32, ply: u8) -> i32 {
+    match score {
+        WIN_SCORE => WIN_SCORE - i32::from(ply),
+        LOSE_SCORE => LOSE_SCORE + i32::from(ply),
+        score => score,
     }
 }
 
@@ -350,60 +2676,294 @@ fn evaluate_state(state: &mut MinimaxState) -> i32 {
 }
 
 fn evaluate_position_strength(state: &MinimaxState, player: u8) -> i32 {
-    let mut score = 0;
-    let mut edges_touched = 0u8;
-    let mut total_connections = 0;
-    let mut center_control = 0;
+    TermScores::of(state, player).total()
+}
+
+/// The weighted terms of the static evaluation of one player.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TermScores {
+    /// Score for the sides of the board touched or held by edge templates.
+    pub edge: i32,
+    /// Score for the pairs of adjacent stones.
+    pub connection: i32,
+    /// Score for the stones with at least two friendly neighbours.
+    pub well_connected: i32,
+    /// Score for closeness to the centre.
+    pub center: i32,
+}
 
-    // Una sola pasada sobre todas las piezas del jugador
-    for idx in state.occupied_cells() {
-        if state.board[idx] == player {
-            // 1. Control de bordes (peso más alto)
-            edges_touched |= state.edges_cache[idx];
+impl TermScores {
+    fn of(state: &MinimaxState, player: u8) -> Self {
+        let terms = PlayerTerms::of(state, player);
+        Self::weighted(&terms, &state.weights, game_progress(state))
+    }
 
-            // 2. Conectividad
-            let mut neighbors = 0;
-            for &neighbor_idx in &state.neighbors_cache[idx] {
-                if state.board[neighbor_idx] == player {
-                    neighbors += 1;
-                }
-            }
-            total_connections += neighbors;
+    /// Weights `terms` for a board where `progress` of the cells are taken.
+    fn weighted(terms: &PlayerTerms, weights: &EvalWeights, progress: f32) -> Self {
+        let center_weight = (1. - progress) * weights.center;
+        TermScores {
+            edge: terms.edges * weights.edge, // PRIORIDAD 1: Tocar bordes
+            connection: terms.connections * weights.connection, // PRIORIDAD 2: Conectividad
+            well_connected: terms.well_connected * weights.well_connected,
+            center: (terms.center_control as f32 * center_weight) as i32, // PRIORIDAD 3: Control de centro
+        }
+    }
 
-            // 3. Bonus por piezas bien conectadas
-            if neighbors >= 2 {
-                score += 40;
+    /// Returns the sum of the terms.
+    pub fn total(&self) -> i32 {
+        self.well_connected + self.edge + self.connection + self.center
+    }
+}
+
+/// What one stone contributes to the static evaluation of its owner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CellContribution {
+    /// The cell of the stone.
+    pub cell: usize,
+    /// The owner of the stone.
+    pub owner: PlayerId,
+    /// Sides of the board the cell touches, one bit per side.
+    pub edges: u8,
+    /// Number of stones of the owner next to the stone.
+    pub friendly_neighbors: u32,
+    /// Score for the pairs the stone forms with its friendly neighbours.
+    /// Each pair is counted from both of its stones.
+    pub connection: i32,
+    /// Score for having at least two friendly neighbours.
+    pub well_connected: i32,
+    /// Score for closeness to the centre. The centre term of the player is
+    /// the sum of these, rounded towards zero.
+    pub center: f64,
+}
+
+/// The static evaluation of a position split into its terms, from the
+/// point of view of one player.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EvalBreakdown {
+    /// The player the evaluation is for.
+    pub player: PlayerId,
+    /// The terms of the player.
+    pub player_terms: TermScores,
+    /// The terms of the opponent, subtracted from the player's.
+    pub opponent_terms: TermScores,
+    /// The winner, if a player already connects the three sides.
+    pub winner: Option<PlayerId>,
+    /// The evaluation: the difference of the terms, or
+    /// [`WIN_SCORE`]/[`LOSE_SCORE`] if the game is won.
+    pub total: i32,
+    /// The contribution of every stone on the board, by cell.
+    pub cells: Vec<CellContribution>,
+}
+
+/// Returns the static evaluation of `state` from the point of view of
+/// `player`, split into the contributions of each term and each stone.
+///
+/// The total is the score [`MinimaxState::evaluate`] returns when `player`
+/// is the bot player of `state`.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameY, MinimaxState, Movement, PlayerId, evaluate_detailed};
+///
+/// let mut game = GameY::new(5);
+/// game.add_move(Movement::Placement {
+///     player: PlayerId::new(0),
+///     coords: Coordinates::new(2, 1, 1),
+/// })
+/// .unwrap();
+/// let mut state = MinimaxState::new(&game, PlayerId::new(0));
+/// let breakdown = evaluate_detailed(&state, PlayerId::new(0));
+/// assert_eq!(breakdown.total, state.evaluate());
+/// assert_eq!(breakdown.cells.len(), 1);
+/// ```
+pub fn evaluate_detailed(state: &MinimaxState, player: PlayerId) -> EvalBreakdown {
+    let own = player.id() as u8 + 1;
+    let other = if own == state.bot_id {
+        state.human_id
+    } else {
+        state.bot_id
+    };
+    let player_terms = TermScores::of(state, own);
+    let opponent_terms = TermScores::of(state, other);
+    let winner = [own, other]
+        .into_iter()
+        .find(|&id| state.check_win(id))
+        .map(|id| PlayerId::new(id as u32 - 1));
+    let total = match winner {
+        Some(winner) if winner == player => WIN_SCORE,
+        Some(_) => LOSE_SCORE,
+        None => player_terms.total() - opponent_terms.total(),
+    };
+
+    let weights = &state.weights;
+    let center_weight = f64::from((1. - game_progress(state)) * weights.center);
+    let cells = (0..state.board.len())
+        .filter(|&idx| state.board[idx] != 0)
+        .map(|idx| {
+            let owner = state.board[idx];
+            let friendly_neighbors = state.caches.neighbors[idx]
+                .iter()
+                .filter(|&&neighbor| state.board[neighbor as usize] == owner)
+                .count() as u32;
+            CellContribution {
+                cell: idx,
+                owner: PlayerId::new(owner as u32 - 1),
+                edges: state.caches.edges[idx],
+                friendly_neighbors,
+                connection: friendly_neighbors as i32 * weights.connection,
+                well_connected: if friendly_neighbors >= 2 {
+                    weights.well_connected
+                } else {
+                    0
+                },
+                center: f64::from(state.caches.center_bonus[idx]) * center_weight,
             }
+        })
+        .collect();
+
+    EvalBreakdown {
+        player,
+        player_terms,
+        opponent_terms,
+        winner,
+        total,
+        cells,
+    }
+}
 
-            // 4. Control de centro (peso reducido)
-            let coords = state.coords_cache[idx];
-            let x = coords.x() as i32;
-            let y = coords.y() as i32;
-            let z = coords.z() as i32;
-            let off_center = (x - y).abs() + (y - z).abs() + (z - x).abs();
-            center_control += 50 - off_center;
+/// Fraction of the board covered by stones, leaving out blocked cells.
+fn game_progress(state: &MinimaxState) -> f32 {
+    let total_valid_cells = state.board.len() - state.caches.blocked.len();
+    let pieces_on_board = total_valid_cells.saturating_sub(state.available_mask.count_ones(..));
+    pieces_on_board as f32 / total_valid_cells.max(1) as f32
+}
+
+/// Returns the static evaluation of `game` from the point of view of
+/// `perspective`, with the default [`EvalWeights`]. Finished games score
+/// [`WIN_SCORE`] or [`LOSE_SCORE`].
+///
+/// This is the score [`MinimaxState::evaluate`] gives the position, computed
+/// straight from the stones of the game without building a search state, so
+/// that it is cheap enough to show after every move.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameY, Movement, PlayerId, evaluate_position};
+///
+/// let mut game = GameY::new(7);
+/// game.add_move(Movement::Placement {
+///     player: PlayerId::new(0),
+///     coords: Coordinates::new(2, 2, 2),
+/// })
+/// .unwrap();
+/// assert!(evaluate_position(&game, PlayerId::new(0)) > 0);
+/// assert!(evaluate_position(&game, PlayerId::new(1)) < 0);
+/// ```
+pub fn evaluate_position(game: &GameY, perspective: PlayerId) -> i32 {
+    if let Some(result) = game.result() {
+        return if result.winner == perspective {
+            WIN_SCORE
+        } else {
+            LOSE_SCORE
+        };
+    }
+    let mut own = PlayerTerms::default();
+    let mut other = PlayerTerms::default();
+    let (mut own_edges, mut other_edges) = (0u8, 0u8);
+    for (coords, owner) in game.stones() {
+        let (terms, edges) = if owner == perspective {
+            (&mut own, &mut own_edges)
+        } else {
+            (&mut other, &mut other_edges)
+        };
+        *edges |= u8::from(coords.touches_side_a())
+            | u8::from(coords.touches_side_b()) << 1
+            | u8::from(coords.touches_side_c()) << 2;
+        let neighbors = game
+            .get_neighbors(&coords)
+            .iter()
+            .filter(|neighbor| game.cell_owner(neighbor) == Some(owner))
+            .count() as i32;
+        terms.connections += neighbors;
+        if neighbors >= 2 {
+            terms.well_connected += 1;
         }
+        terms.center_control += 50 - off_center(coords) as i32;
     }
+    // Sides reached through edge templates count as touched
+    let template_sides = |player| {
+        game.edge_templates(player)
+            .iter()
+            .fold(0, |sides, template| sides | template.side_label())
+    };
+    own.edges = (own_edges | template_sides(perspective)).count_ones() as i32;
+    other.edges =
+        (other_edges | template_sides(game::other_player(perspective))).count_ones() as i32;
+
+    let weights = EvalWeights::default();
+    let playable_cells = game.total_cells() as usize - game.blocked_cells().len();
+    let progress = game.stone_count() as f32 / playable_cells.max(1) as f32;
+    TermScores::weighted(&own, &weights, progress).total()
+        - TermScores::weighted(&other, &weights, progress).total()
+}
 
-    // Calcular score final con pesos balanceados
-    let edges_count = edges_touched.count_ones() as i32;
-
-    let pieces_on_board = state.occupied_cells().count() as f32;
-    let total_valid_cells = state.board.len() as f32;
-    let game_progress = pieces_on_board / total_valid_cells;
+/// The unweighted terms of the evaluation of one player.
+#[derive(Default)]
+struct PlayerTerms {
+    edges: i32,
+    connections: i32,
+    well_connected: i32,
+    center_control: i32,
+}
 
-    let edge_score = edges_count * 5; // PRIORIDAD 1: Tocar bordes
-    let connections_score = total_connections * 25; // PRIORIDAD 2: Conectividad
+impl PlayerTerms {
+    fn of(state: &MinimaxState, player: u8) -> Self {
+        let mut edges_touched = 0u8;
+        let mut terms = PlayerTerms {
+            edges: 0,
+            connections: 0,
+            well_connected: 0,
+            center_control: 0,
+        };
 
-    let center_weight = (1. - game_progress) * 5.;
+        let opponent = 3 - player;
+        // Una sola pasada sobre las piezas del jugador
+        for idx in state.owned[player as usize - 1].ones() {
+            // 1. Control de bordes (peso más alto), también por plantillas
+            edges_touched |= state.caches.edges[idx];
+            for (side, carrier) in &state.caches.templates[idx] {
+                if edges_touched & side == 0
+                    && carrier
+                        .iter()
+                        .all(|&cell| state.board[cell as usize] != opponent)
+                {
+                    edges_touched |= side;
+                }
+            }
 
-    let center_score = (center_control as f32 * center_weight) as i32; // PRIORIDAD 3: Control de centro
+            // 2. Conectividad
+            let neighbors = state.caches.neighbors[idx]
+                .iter()
+                .filter(|&&neighbor_idx| state.board[neighbor_idx as usize] == player)
+                .count() as i32;
+            terms.connections += neighbors;
 
-    score += edge_score;
-    score += connections_score;
-    score += center_score;
+            // 3. Bonus por piezas bien conectadas
+            if neighbors >= 2 {
+                terms.well_connected += 1;
+            }
 
-    score
+            // 4. Control de centro (peso reducido)
+            terms.center_control += state.caches.center_bonus[idx];
+        }
+        terms.edges = edges_touched.count_ones() as i32;
+        terms
+    }
 }
 
 #[cfg(test)]
@@ -421,6 +2981,18 @@ mod tests {
         MinimaxState::new(&game, PlayerId::new(0))
     }
 
+    /// Creates the control of a search without a node limit
+    fn control() -> SearchControl<'static> {
+        SearchControl::new(None, &NEVER_STOP)
+    }
+
+    /// Edges reached by the group of `player` stones containing `start`
+    fn group_edges(state: &MinimaxState, start: usize, player: u8) -> u8 {
+        crate::flood_fill(state, [start], &state.owned[player as usize - 1])
+            .ones()
+            .fold(0, |mask, idx| mask | state.caches.edges[idx])
+    }
+
     /// Gets the first N valid available cells
     fn get_valid_cells(state: &MinimaxState, count: usize) -> Vec<usize> {
         state.available_cells().take(count).collect()
@@ -435,7 +3007,7 @@ mod tests {
         let game = GameY::new(3);
         let state = MinimaxState::new(&game, PlayerId::new(0));
 
-        assert_eq!(state.size, 3);
+        assert_eq!(state.board.len(), 6);
         assert_eq!(state.bot_id, 1); // PlayerId(0) + 1
         assert_eq!(state.human_id, 2); // PlayerId(1) + 1
 
@@ -500,7 +3072,7 @@ mod tests {
         let mut state = create_empty_state(3);
 
         assert_eq!(
-            state.occupied_cells().count(),
+            state.available_mask.zeroes().count(),
             0,
             "Must have no occupied cells"
         );
@@ -510,7 +3082,7 @@ mod tests {
         state.make_move(cells[1], state.human_id);
 
         assert_eq!(
-            state.occupied_cells().count(),
+            state.available_mask.zeroes().count(),
             2,
             "Must have 2 occupied cells"
         );
@@ -518,7 +3090,7 @@ mod tests {
 
     #[test]
     fn test_check_win_does_not_detect_win_on_empty_board() {
-        let mut state = create_empty_state(3);
+        let state = create_empty_state(3);
 
         assert!(
             !state.check_win(state.bot_id),
@@ -531,6 +3103,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::overly_complex_bool_expr)]
     fn test_check_win_does_not_detect_win_with_isolated_pieces() {
         let mut state = create_empty_state(4); // Larger board
 
@@ -548,32 +3121,32 @@ mod tests {
     }
 
     #[test]
-    fn test_dfs_collect_edges_finds_edges_on_edge_cell() {
+    fn test_group_edges_finds_edges_on_edge_cell() {
         let mut state = create_empty_state(3);
 
         // Find a cell that touches an edge
         let edge_idx = (0..state.board.len())
-            .find(|&idx| state.edges_cache[idx] != 0 && state.available_mask.contains(idx))
+            .find(|&idx| state.caches.edges[idx] != 0 && state.available_mask.contains(idx))
             .expect("Must have at least one available edge cell");
 
         state.make_move(edge_idx, state.bot_id);
 
-        let edges_found = state.dfs_collect_edges(edge_idx, state.bot_id);
+        let edges_found = group_edges(&state, edge_idx, state.bot_id);
 
         assert!(edges_found != 0, "Must find at least one edge");
         assert_eq!(
-            edges_found, state.edges_cache[edge_idx],
+            edges_found, state.caches.edges[edge_idx],
             "Must match the cell's edges"
         );
     }
 
     #[test]
-    fn test_dfs_collect_edges_accumulates_edges_from_connected_pieces() {
+    fn test_group_edges_accumulates_edges_from_connected_pieces() {
         let mut state = create_empty_state(4); // Larger board for more options
 
         // Find two edge cells that are neighbors
         let edge_cells: Vec<usize> = (0..state.board.len())
-            .filter(|&idx| state.edges_cache[idx] != 0 && state.available_mask.contains(idx))
+            .filter(|&idx| state.caches.edges[idx] != 0 && state.available_mask.contains(idx))
             .take(5)
             .collect();
 
@@ -582,14 +3155,14 @@ mod tests {
             state.make_move(first, state.bot_id);
 
             // Find a neighbor that is also an edge
-            for &neighbor in &state.neighbors_cache[first] {
-                if state.available_mask.contains(neighbor) && state.edges_cache[neighbor] != 0 {
+            for neighbor in state.neighbors(first) {
+                if state.available_mask.contains(neighbor) && state.caches.edges[neighbor] != 0 {
                     state.make_move(neighbor, state.bot_id);
 
-                    let edges_found = state.dfs_collect_edges(first, state.bot_id);
+                    let edges_found = group_edges(&state, first, state.bot_id);
 
                     // Must accumulate edges from both cells
-                    let expected = state.edges_cache[first] | state.edges_cache[neighbor];
+                    let expected = state.caches.edges[first] | state.caches.edges[neighbor];
                     assert_eq!(
                         edges_found, expected,
                         "Must accumulate edges from connected cells"
@@ -652,6 +3225,147 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_set_position_matches_a_new_state() {
+        let mut game = GameY::new(5);
+        let mut state = MinimaxState::new(&game, PlayerId::new(0));
+        for (i, idx) in [3u32, 7, 12].into_iter().enumerate() {
+            let player = PlayerId::new(i as u32 % 2);
+            let coords = Coordinates::from_index(idx, 5);
+            game.add_move(crate::Movement::Placement { player, coords })
+                .unwrap();
+        }
+        state.set_position(&game, PlayerId::new(1));
+        let mut fresh = MinimaxState::new(&game, PlayerId::new(1));
+        assert_eq!(state.board, fresh.board);
+        assert_eq!(state.available_mask, fresh.available_mask);
+        assert_eq!(state.owned, fresh.owned);
+        assert_eq!(state.evaluate(), fresh.evaluate());
+
+        // A different size rebuilds the caches
+        let small = GameY::new(3);
+        state.set_position(&small, PlayerId::new(0));
+        assert_eq!(state.board.len(), 6);
+        assert_eq!(
+            state.caches.center_bonus,
+            MinimaxState::new(&small, PlayerId::new(0))
+                .caches
+                .center_bonus
+        );
+    }
+
+    /// Counts the allocations of the current thread, so that tests running
+    /// at the same time do not interfere.
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            ALLOCATIONS.with(|count| count.set(count.get() + 1));
+            unsafe { std::alloc::System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            unsafe { std::alloc::System.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_search_does_not_allocate_once_warmed_up() {
+        let mut game = GameY::new(9);
+        for (i, idx) in [20u32, 24, 31].into_iter().enumerate() {
+            game.add_move(crate::Movement::Placement {
+                player: PlayerId::new(i as u32 % 2),
+                coords: Coordinates::from_index(idx, 9),
+            })
+            .unwrap();
+        }
+        let mut state = MinimaxState::new(&game, PlayerId::new(1));
+        let mut table = TranspositionTable::new(1 << 12);
+        let mut control = control();
+        control.table = Some(&mut table);
+        control.interior_limit = Some(4);
+        let mut deepen = |control: &mut SearchControl| {
+            for depth in 1..=3 {
+                search_best_move(&mut state, depth, None, control);
+            }
+        };
+        deepen(&mut control);
+
+        // The buffers have grown in the first search: the second reuses them
+        let before = ALLOCATIONS.with(std::cell::Cell::get);
+        deepen(&mut control);
+        assert_eq!(ALLOCATIONS.with(std::cell::Cell::get), before);
+    }
+
+    #[test]
+    fn test_states_of_the_same_board_share_caches() {
+        let game = GameY::new(7);
+        let first = MinimaxState::new(&game, PlayerId::new(0));
+        let second = MinimaxState::new(&game, PlayerId::new(1));
+        assert!(Arc::ptr_eq(&first.caches, &second.caches));
+        let other = MinimaxState::new(&GameY::new(8), PlayerId::new(0));
+        assert!(!Arc::ptr_eq(&first.caches, &other.caches));
+
+        // Other topologies build their own
+        let stones = vec![None; game.num_cells()];
+        let rules = |game: &GameY| {
+            MinimaxState::with_rules(
+                game,
+                Box::new(ConnectEdges::all_sides_of_y()),
+                &stones,
+                PlayerId::new(0),
+            )
+        };
+        assert!(!Arc::ptr_eq(&rules(&game).caches, &rules(&game).caches));
+    }
+
+    #[test]
+    fn test_eval_features_match_the_evaluation() {
+        let mut state = create_empty_state(6);
+        for (idx, player) in [(4, 1), (7, 1), (8, 2), (12, 1), (20, 2)] {
+            state.make_move(idx, player);
+        }
+        let weights = EvalWeights {
+            edge: 7,
+            connection: 11,
+            well_connected: 13,
+            center: 3.0,
+        };
+        let mut state = state.with_weights(weights);
+        let features = state.eval_features();
+        assert!(features.connection > 0.0);
+
+        // The centre term is truncated to an integer for each player
+        let expected = features.score(&weights);
+        assert!((state.evaluate() as f64 - expected).abs() <= 2.0);
+    }
+
+    #[test]
+    fn test_edge_templates_count_as_touched_sides() {
+        let mut game = GameY::new(9);
+        let mut play = |player: u32, coords: Coordinates| {
+            game.add_move(crate::Movement::Placement {
+                player: PlayerId::new(player),
+                coords,
+            })
+            .unwrap();
+            let mut state = MinimaxState::new(&game, PlayerId::new(0));
+            assert_eq!(state.evaluate(), evaluate_position(&game, PlayerId::new(0)));
+            state.eval_features().edge
+        };
+        // A stone on the second row holds side A by a bridge
+        assert_eq!(play(0, Coordinates::new(1, 3, 4)), 1.0);
+        // Until the opponent takes a cell of the bridge, and the side
+        assert_eq!(play(1, Coordinates::new(0, 3, 5)), -1.0);
+    }
+
     #[test]
     fn test_evaluate_position_strength_values_connectivity() {
         let mut state = create_empty_state(4);
@@ -662,10 +3376,10 @@ mod tests {
         state.make_move(idx1, state.bot_id);
 
         // Find an available neighbor
-        let neighbor = state.neighbors_cache[idx1]
-            .iter()
-            .find(|&&n| state.available_mask.contains(n))
-            .copied();
+        let neighbor = state
+            .neighbors(idx1)
+            .into_iter()
+            .find(|&n| state.available_mask.contains(n));
 
         if let Some(idx2) = neighbor {
             state.make_move(idx2, state.bot_id);
@@ -689,6 +3403,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::manual_range_contains)]
     fn test_minimax_returns_score_in_valid_range() {
         let mut state = create_empty_state(3);
 
@@ -696,7 +3411,7 @@ mod tests {
         let cell = state.available_cells().next().unwrap();
         state.make_move(cell, state.bot_id);
 
-        let score = minimax(&mut state, 1, -INFINITY, INFINITY, false);
+        let score = minimax(&mut state, 1, -INFINITY, INFINITY, false, &mut control());
 
         assert!(
             score >= LOSE_SCORE && score <= WIN_SCORE,
@@ -713,7 +3428,7 @@ mod tests {
         let cell = state.available_cells().next().unwrap();
         state.make_move(cell, state.bot_id);
 
-        let score = minimax(&mut state, 0, -INFINITY, INFINITY, true);
+        let score = minimax(&mut state, 0, -INFINITY, INFINITY, true, &mut control());
         let eval_score = evaluate_state(&mut state);
 
         assert_eq!(score, eval_score, "With depth 0 must evaluate directly");
@@ -727,7 +3442,7 @@ mod tests {
     fn test_greedy_search_does_not_find_win_on_empty_board() {
         let mut state = create_empty_state(3);
 
-        let result = greedy_search(&mut state);
+        let result = greedy_search(&mut state, &NEVER_STOP);
 
         // On empty board there should be no immediate win
         assert!(
@@ -746,7 +3461,7 @@ mod tests {
         state.make_move(cells[1], state.human_id);
         state.make_move(cells[2], state.bot_id);
 
-        let result = greedy_search(&mut state);
+        let result = greedy_search(&mut state, &NEVER_STOP);
 
         // Verify it doesn't produce errors
         assert!(result.is_some() || result.is_none());
@@ -761,7 +3476,7 @@ mod tests {
         state.make_move(cells[0], state.bot_id);
         state.make_move(cells[1], state.human_id);
 
-        let score_with_pruning = minimax(&mut state, 2, -INFINITY, INFINITY, true);
+        let score_with_pruning = minimax(&mut state, 2, -INFINITY, INFINITY, true, &mut control());
 
         // Score must be within reasonable ranges
         assert!(
@@ -774,7 +3489,7 @@ mod tests {
     fn test_search_best_move_finds_valid_move() {
         let mut state = create_empty_state(3);
 
-        let (best_move, score) = search_best_move(&mut state, 2, None);
+        let (best_move, score) = search_best_move(&mut state, 2, None, &mut control());
 
         assert!(best_move < state.board.len(), "Must return valid index");
         assert!(
@@ -792,12 +3507,12 @@ mod tests {
         let mut state = create_empty_state(3);
 
         let pv_move = state.available_cells().nth(1).unwrap();
-        let (best_move, _) = search_best_move(&mut state, 1, Some(pv_move));
+        let (best_move, _) = search_best_move(&mut state, 1, Some(pv_move), &mut control());
 
         // Returned move must be valid
         assert!(best_move < state.board.len(), "Must return valid move");
         assert!(
-            state.coords_cache.get(best_move).is_some(),
+            state.caches.center_bonus.get(best_move).is_some(),
             "Index must be in cache"
         );
     }
@@ -807,13 +3522,818 @@ mod tests {
         let mut state = create_empty_state(3);
 
         // With very limited time, must iterate at least once
-        let best_move = iterative_deepening_search(&mut state, 50); // 50ms
+        let limits = SearchLimits {
+            max_time_ms: Some(50),
+            ..SearchLimits::default()
+        };
+        let best_move =
+            iterative_deepening_search(&mut state, &limits, &mut control(), &mut |_| {});
 
         assert!(best_move < state.board.len(), "Must find valid move");
         assert!(
-            state.coords_cache.get(best_move).is_some(),
-            "Move must have coordinates"
+            state.caches.center_bonus.get(best_move).is_some(),
+            "Move must have a center bonus"
+        );
+    }
+
+    #[test]
+    fn test_search_reports_each_depth_up_to_the_limit() {
+        let mut state = create_empty_state(4);
+        let limits = SearchLimits {
+            max_depth: Some(3),
+            ..SearchLimits::default()
+        };
+        let mut reports = Vec::new();
+        let best = search_with_limits(&mut state, &limits, &NEVER_STOP, &mut |info| {
+            reports.push(info.clone())
+        });
+
+        assert_eq!(
+            reports.iter().map(|i| i.depth).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        let last = reports.last().unwrap();
+        assert_eq!(last.pv.len(), 3);
+        assert_eq!(Some(last.pv[0]), best);
+        assert!(reports.windows(2).all(|w| w[0].nodes < w[1].nodes));
+    }
+
+    #[test]
+    fn test_fixed_depth_bots_ignore_the_clock() {
+        let mut game = GameY::new(6);
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(5, 0, 0),
+        })
+        .unwrap();
+        let bot = MinimaxBot::with_depth(3);
+        let limits = bot.limits();
+        assert_eq!((limits.max_time_ms, limits.max_depth), (None, Some(3)));
+
+        let mut state = MinimaxState::new(&game, PlayerId::new(1));
+        let searched = search_with_limits(&mut state, &limits, &NEVER_STOP, &mut |_| {});
+        let searched = Coordinates::from_index(searched.unwrap() as u32, 6);
+        assert_eq!(bot.choose_move(&game), Some(searched));
+        assert_eq!(bot.choose_move_within(&game, 0), Some(searched));
+    }
+
+    #[test]
+    fn test_temperament_chooses_between_near_equal_moves() {
+        let mut game = GameY::new(6);
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(3, 1, 1),
+        })
+        .unwrap();
+        let choose = |temperament| {
+            MinimaxBot::with_depth(2)
+                .with_temperament(temperament)
+                .choose_move(&game)
+                .unwrap()
+                .to_index(6) as usize
+        };
+        let balanced = choose(Temperament::Balanced);
+        let solid = choose(Temperament::Solid);
+        let aggressive = choose(Temperament::Aggressive);
+        assert_eq!(
+            balanced,
+            MinimaxBot::with_depth(2)
+                .choose_move(&game)
+                .unwrap()
+                .to_index(6) as usize
+        );
+
+        let mut state = MinimaxState::new(&game, PlayerId::new(1));
+        let mut control = control();
+        search_best_move(&mut state, 2, None, &mut control);
+        let score_of = |cell| {
+            control
+                .root_scores
+                .iter()
+                .find(|&&(m, _)| m as usize == cell)
+                .unwrap()
+                .1
+        };
+        let best = control.root_scores[0].1;
+        assert!(best - score_of(solid) <= TEMPERAMENT_MARGIN);
+        assert!(best - score_of(aggressive) <= TEMPERAMENT_MARGIN);
+        assert!(
+            reply_spread(&mut state, solid, &NEVER_STOP)
+                <= reply_spread(&mut state, balanced, &NEVER_STOP)
+        );
+        assert!(
+            reply_spread(&mut state, aggressive, &NEVER_STOP)
+                >= reply_spread(&mut state, balanced, &NEVER_STOP)
+        );
+        assert_ne!(solid, aggressive);
+    }
+
+    #[test]
+    fn test_anti_book_steers_away_from_lost_openings() {
+        let mut game = GameY::new(6);
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(3, 1, 1),
+        })
+        .unwrap();
+        let usual = MinimaxBot::with_depth(2).choose_move(&game).unwrap();
+
+        let bot = MinimaxBot::with_depth(2).with_anti_book(AntiBook::new(6).with_penalty(1_000));
+        let mut lost = game.clone();
+        lost.add_move(crate::Movement::Placement {
+            player: PlayerId::new(1),
+            coords: usual,
+        })
+        .unwrap();
+        lost.resign(PlayerId::new(1)).unwrap();
+        // Won games teach nothing
+        bot.learn_from_game(&lost, PlayerId::new(0));
+        assert_eq!(bot.choose_move(&game), Some(usual));
+
+        bot.learn_from_game(&lost, PlayerId::new(1));
+        let losing_moves = bot.anti_book().unwrap().losing_moves(&game);
+        assert!(losing_moves.contains(&(usual, 1)));
+        let chosen = bot.choose_move(&game).unwrap();
+        assert!(losing_moves.iter().all(|&(coords, _)| coords != chosen));
+        assert_eq!(bot.clone().choose_move(&game), Some(chosen));
+    }
+
+    #[test]
+    fn test_opening_variety_draws_near_equal_moves() {
+        let variety = OpeningVariety::new(2, 40);
+        let bot = MinimaxBot::with_depth(2).with_opening_variety(variety);
+        let game = GameY::new(5);
+        let openings: Vec<Coordinates> = (0..12)
+            .map(|seed| {
+                bot.reseed(seed);
+                bot.choose_move(&game).unwrap()
+            })
+            .collect();
+        bot.reseed(3);
+        assert_eq!(bot.choose_move(&game), Some(openings[3]));
+        assert!(openings.iter().any(|&coords| coords != openings[0]));
+
+        let mut state = MinimaxState::new(&game, PlayerId::new(0));
+        let mut control = control();
+        search_best_move(&mut state, 2, None, &mut control);
+        let best = control.root_scores[0].1;
+        for coords in &openings {
+            let cell = coords.to_index(5) as CellIdx;
+            let &(_, score) = control
+                .root_scores
+                .iter()
+                .find(|&&(m, _)| m == cell)
+                .unwrap();
+            assert!(best - score <= variety.margin);
+        }
+
+        // Past the opening, the bot plays its best move again
+        let mut game = game;
+        for coords in [openings[0], Coordinates::new(0, 0, 4)] {
+            let player = game.next_player().unwrap();
+            game.add_move(crate::Movement::Placement { player, coords })
+                .unwrap();
+        }
+        let best = MinimaxBot::with_depth(2).choose_move(&game);
+        for seed in 0..4 {
+            bot.reseed(seed);
+            assert_eq!(bot.choose_move(&game), best);
+        }
+    }
+
+    #[test]
+    fn test_evaluation_breakdown_adds_up() {
+        let mut game = GameY::new(5);
+        for (i, idx) in [4usize, 7, 8, 12, 3].into_iter().enumerate() {
+            game.add_move(crate::Movement::Placement {
+                player: PlayerId::new(i as u32 % 2),
+                coords: Coordinates::from_index(idx as u32, 5),
+            })
+            .unwrap();
+        }
+        let mut state = MinimaxState::new(&game, PlayerId::new(1));
+        let breakdown = evaluate_detailed(&state, PlayerId::new(1));
+        assert_eq!(breakdown.winner, None);
+        assert_eq!(breakdown.total, state.evaluate());
+        assert_eq!(
+            evaluate_detailed(&state, PlayerId::new(0)).total,
+            -breakdown.total
+        );
+
+        let own: Vec<&CellContribution> = breakdown
+            .cells
+            .iter()
+            .filter(|cell| cell.owner == PlayerId::new(1))
+            .collect();
+        assert_eq!(own.len(), 2);
+        let terms = breakdown.player_terms;
+        assert_eq!(
+            own.iter().map(|c| c.connection).sum::<i32>(),
+            terms.connection
+        );
+        assert_eq!(
+            own.iter().map(|c| c.well_connected).sum::<i32>(),
+            terms.well_connected
+        );
+        assert_eq!(
+            own.iter().map(|c| c.center).sum::<f64>() as i32,
+            terms.center
+        );
+        let edges = own.iter().fold(0u8, |mask, c| mask | c.edges);
+        assert_eq!(edges.count_ones() as i32 * state.weights.edge, terms.edge);
+    }
+
+    #[test]
+    fn test_position_evaluation_matches_the_search_state() {
+        let mut rng = crate::seeded_rng(3);
+        for size in [4, 6, 9] {
+            let mut game = GameY::new(size);
+            while let Some(player) = game.next_player() {
+                for perspective in [PlayerId::new(0), PlayerId::new(1)] {
+                    let mut state = MinimaxState::new(&game, perspective);
+                    assert_eq!(evaluate_position(&game, perspective), state.evaluate());
+                }
+                let cells = game.available_cells();
+                let idx = cells[rand::Rng::random_range(&mut rng, 0..cells.len())];
+                game.add_move(crate::Movement::Placement {
+                    player,
+                    coords: Coordinates::from_index(idx, size),
+                })
+                .unwrap();
+            }
+            let winner = game.result().unwrap().winner;
+            assert_eq!(evaluate_position(&game, winner), WIN_SCORE);
+        }
+    }
+
+    #[test]
+    fn test_tiny_budgets_play_the_instant_move() {
+        let mut game = GameY::new(7);
+        let bot = MinimaxBot::new(0);
+        let centre = Coordinates::new(2, 2, 2);
+        assert_eq!(bot.instant_move(&game), Some(centre));
+        assert_eq!(bot.choose_move(&game), Some(centre));
+
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(0),
+            coords: centre,
+        })
+        .unwrap();
+        let next = MinimaxBot::new(1000)
+            .choose_move_within(&game, INSTANT_MOVE_MAX_MS)
+            .unwrap();
+        assert_eq!(off_center(next), 4);
+        assert_eq!(Some(next), bot.instant_move(&game));
+    }
+
+    #[test]
+    fn test_restricted_openings_are_legal() {
+        let rule = crate::OpeningRule::ForbiddenCentre { edge_distance: 1 };
+        let game = GameY::new(7).with_opening_rule(rule);
+        let variety = MinimaxBot::with_depth(2).with_opening_variety(OpeningVariety::new(2, 40));
+        for bot in [MinimaxBot::new(0), variety] {
+            let coords = bot.choose_move(&game).unwrap();
+            assert!(!rule.forbids_placement(coords, 0), "{}", coords);
+            // The allowed cells closest to the centre are next to it
+            assert_eq!(off_center(coords), 4);
+        }
+    }
+
+    #[test]
+    fn test_trivial_positions_are_not_searched() {
+        let bot = MinimaxBot::new(60_000);
+        // The opening is played in the centre
+        let game = GameY::new(15);
+        assert_eq!(bot.choose_move(&game), Some(Coordinates::new(5, 4, 5)));
+        assert_eq!(bot.table_stats(), TableStats::default());
+
+        // As is the only empty cell
+        let mut builder = GameYBuilder::new(3);
+        for (idx, player) in [(1, 1), (2, 0), (3, 1), (4, 0), (5, 0)] {
+            builder = builder.with_stone(PlayerId::new(player), Coordinates::from_index(idx, 3));
+        }
+        let game = builder.build().unwrap();
+        assert_eq!(bot.choose_move(&game), Some(Coordinates::from_index(0, 3)));
+        assert_eq!(bot.table_stats().searches, 0);
+    }
+
+    #[test]
+    fn test_forced_wins_are_reported_with_their_line() {
+        use rand::prelude::IndexedRandom;
+        let limits = SearchLimits {
+            max_depth: Some(3),
+            ..SearchLimits::default()
+        };
+        let last_info = |game: &GameY| {
+            let mut state = MinimaxState::new(game, game.next_player().unwrap());
+            let mut last = None;
+            iterative_deepening_search(&mut state, &limits, &mut control(), &mut |info| {
+                last = Some(info.clone())
+            });
+            last.unwrap()
+        };
+        // Play at random until the player to move wins in 3 but not at once
+        let mut rng = crate::seeded_rng(4);
+        let mut game = GameY::new(5);
+        let info = loop {
+            let info = last_info(&game);
+            if info.forced_win_in() == Some(3) {
+                break info;
+            }
+            let player = game.next_player().unwrap();
+            let idx = *game.available_cells().choose(&mut rng).unwrap();
+            let coords = Coordinates::from_index(idx, 5);
+            game.add_move(crate::Movement::Placement { player, coords })
+                .unwrap();
+        };
+        assert_eq!(info.forced_loss_in(), None);
+        let line = info.forcing_line().to_vec();
+        assert_eq!(line.len(), 3);
+
+        // The opponent, once the first move is played, loses in 2
+        let mut state = MinimaxState::new(&game, game.next_player().unwrap());
+        state.make_move(line[0], state.bot_id);
+        let after = state.to_game().unwrap();
+        let reply = last_info(&after);
+        assert_eq!(reply.forced_loss_in(), Some(2));
+        assert_eq!(reply.forced_win_in(), None);
+        assert_eq!(reply.forcing_line().len(), 2);
+
+        // And the line wins on its last move
+        let winner = game.next_player().unwrap();
+        for &cell in &line {
+            let player = game.next_player().unwrap();
+            let coords = Coordinates::from_index(cell as u32, 5);
+            game.add_move(crate::Movement::Placement { player, coords })
+                .unwrap();
+        }
+        assert_eq!(game.result().unwrap().winner, winner);
+    }
+
+    #[test]
+    fn test_double_threats_are_seen_past_the_horizon() {
+        use rand::prelude::IndexedRandom;
+        // Play at random until the player to move can make two threats at
+        // once, but neither player can win at once
+        let mut rng = crate::seeded_rng(3);
+        let mut game = GameY::new(6);
+        let (player, fork) = loop {
+            let player = game.next_player().unwrap();
+            let mut state = MinimaxState::new(&game, player);
+            if !state.has_winning_cell(state.bot_id) && !state.has_winning_cell(state.human_id) {
+                let cells: Vec<usize> = state.empty_cells().collect();
+                let fork = cells.into_iter().find(|&cell| {
+                    state.make_move(cell, state.bot_id);
+                    let forks = state.double_threat(cell).is_some();
+                    state.undo_move(cell);
+                    forks
+                });
+                if let Some(fork) = fork {
+                    break (player, fork);
+                }
+            }
+            let idx = *game.available_cells().choose(&mut rng).unwrap();
+            let coords = Coordinates::from_index(idx, 6);
+            game.add_move(crate::Movement::Placement { player, coords })
+                .unwrap();
+            if game.check_game_over() {
+                game = GameY::new(6);
+            }
+        };
+
+        // Two plies only reach the block of one threat, not the win
+        let mut state = MinimaxState::new(&game, player);
+        let mut control = control();
+        let (_, score) = search_best_move(&mut state, 2, None, &mut control);
+        assert_eq!(score, WIN_SCORE - 3);
+        assert!(
+            control
+                .root_scores
+                .contains(&(fork as CellIdx, WIN_SCORE - 3))
+        );
+
+        let line = control.pv_table[2].clone();
+        assert_eq!(line.len(), 3);
+        for &cell in &line {
+            let player = game.next_player().unwrap();
+            let coords = Coordinates::from_index(cell as u32, 6);
+            game.add_move(crate::Movement::Placement { player, coords })
+                .unwrap();
+        }
+        assert_eq!(game.result().unwrap().winner, player);
+    }
+
+    #[test]
+    fn test_quicker_wins_score_higher() {
+        use rand::prelude::IndexedRandom;
+        // Play at random until the player to move can win at once
+        let mut rng = crate::seeded_rng(2);
+        let mut game = GameY::new(5);
+        let player = loop {
+            let player = game.next_player().unwrap();
+            let mut state = MinimaxState::new(&game, player);
+            if greedy_search(&mut state, &NEVER_STOP).is_some_and(|idx| {
+                state.make_move(idx, state.bot_id);
+                state.check_win(state.bot_id)
+            }) {
+                break player;
+            }
+            let idx = *game.available_cells().choose(&mut rng).unwrap();
+            let coords = Coordinates::from_index(idx, 5);
+            game.add_move(crate::Movement::Placement { player, coords })
+                .unwrap();
+        };
+
+        let mut state = MinimaxState::new(&game, player);
+        let mut control = control();
+        let (_, score) = search_best_move(&mut state, 3, None, &mut control);
+        assert_eq!(score, WIN_SCORE - 1);
+        // Only the moves winning at once score it, slower wins score less
+        for &(cell, score) in &control.root_scores {
+            state.make_move(cell as usize, state.bot_id);
+            assert_eq!(state.check_win(state.bot_id), score == WIN_SCORE - 1);
+            state.undo_move(cell as usize);
+        }
+        assert!(
+            control
+                .root_scores
+                .iter()
+                .any(|&(_, score)| score == WIN_SCORE - 3)
+        );
+    }
+
+    #[test]
+    fn test_widening_searches_only_the_best_root_moves() {
+        let game = GameY::new(6);
+        let search = |widening: Option<Widening>| {
+            let mut state = MinimaxState::new(&game, PlayerId::new(0));
+            let limits = SearchLimits {
+                max_depth: Some(3),
+                widening,
+                ..SearchLimits::default()
+            };
+            let mut reports = Vec::new();
+            search_with_limits(&mut state, &limits, &NEVER_STOP, &mut |info| {
+                reports.push(info.clone())
+            });
+            reports
+        };
+
+        let full = search(None);
+        let narrow = search(Some(Widening::new(2, 1)));
+        // The first iteration is not restricted
+        assert_eq!(narrow[0], full[0]);
+        // Later iterations only follow the best move of the first one
+        assert!(narrow.iter().all(|info| info.pv[0] == full[0].pv[0]));
+        assert!(narrow[2].nodes < full[2].nodes);
+
+        let interior = search(Some(Widening::new(2, 1).with_interior_moves(3)));
+        assert_eq!(interior.len(), 3);
+        assert!(interior[2].nodes < narrow[2].nodes);
+    }
+
+    #[test]
+    fn test_board_defaults_scale_with_the_board_size() {
+        let bots: Vec<MinimaxBot> = [2, 5, 7, 9, 11, 15, 19, 100]
+            .into_iter()
+            .map(MinimaxBot::for_board)
+            .collect();
+        for pair in bots.windows(2) {
+            assert!(pair[0].max_time_ms() <= pair[1].max_time_ms());
+            assert!(pair[0].table_entries <= pair[1].table_entries);
+        }
+        assert_eq!(bots[0].limits(), bots[1].limits());
+        assert_eq!(bots[1].limits().max_depth, None);
+        assert!(bots[5].limits().widening.unwrap().interior_moves.is_some());
+        assert_eq!(bots[6].limits(), bots[7].limits());
+        assert!(bots.iter().all(|bot| bot.table_entries.is_power_of_two()));
+    }
+
+    #[test]
+    fn test_root_nodes_add_up_over_iterations() {
+        let mut state = create_empty_state(5);
+        let limits = SearchLimits {
+            max_depth: Some(3),
+            ..SearchLimits::default()
+        };
+        let mut reports: Vec<SearchInfo> = Vec::new();
+        search_with_limits(&mut state, &limits, &NEVER_STOP, &mut |info| {
+            reports.push(info.clone())
+        });
+        assert_eq!(reports.len(), 3);
+        for info in &reports {
+            assert_eq!(info.root_nodes.len(), 15);
+            let below_root: u64 = info.root_nodes.iter().map(|&(_, nodes)| nodes).sum();
+            assert!(below_root <= info.nodes);
+        }
+        for pair in reports.windows(2) {
+            for (before, after) in pair[0].root_nodes.iter().zip(&pair[1].root_nodes) {
+                assert_eq!(before.0, after.0);
+                assert!(before.1 < after.1);
+            }
+        }
+        let map = reports[2].effort_map(15);
+        assert_eq!(map.iter().filter(|&&share| share == 1.0).count(), 1);
+        assert!(map.iter().all(|&share| share > 0.0));
+    }
+
+    #[test]
+    fn test_search_stops_on_node_limit_and_stop_flag() {
+        let mut state = create_empty_state(5);
+        let limits = SearchLimits {
+            max_nodes: Some(50),
+            ..SearchLimits::default()
+        };
+        let mut deepest = 0;
+        let best = search_with_limits(&mut state, &limits, &NEVER_STOP, &mut |info| {
+            deepest = info.depth;
+            assert!(info.nodes <= 50);
+        });
+        assert!(best.is_some());
+        assert!(deepest < 3);
+
+        // A search that is stopped before it starts still returns a move
+        let stop = AtomicBool::new(true);
+        let best = search_with_limits(&mut state, &SearchLimits::default(), &stop, &mut |_| {
+            panic!("no iteration can complete")
+        });
+        assert!(best.is_some_and(|idx| idx < 15));
+    }
+
+    #[test]
+    fn test_table_is_reused_between_moves() {
+        let bot = MinimaxBot::new(60_000).with_max_depth(4);
+        let plain = MinimaxBot::new(60_000)
+            .with_max_depth(4)
+            .with_table_entries(0);
+        // The first move of a game is played without searching
+        let opened = || {
+            GameYBuilder::new(5)
+                .with_stone(PlayerId::new(1), Coordinates::new(4, 0, 0))
+                .with_starting_player(PlayerId::new(0))
+                .build()
+                .unwrap()
+        };
+        let mut game = opened();
+        for _ in 0..2 {
+            let coords = bot.choose_move(&game).unwrap();
+            assert_eq!(Some(coords), plain.choose_move(&game));
+
+            // The opponent answers with the reply the bot expects
+            let mut state = MinimaxState::new(&game, PlayerId::new(0));
+            let mut pv = Vec::new();
+            search_with_limits(&mut state, &bot.limits(), &NEVER_STOP, &mut |info| {
+                pv = info.pv.clone()
+            });
+            for (i, idx) in pv.into_iter().take(2).enumerate() {
+                game.add_move(crate::Movement::Placement {
+                    player: PlayerId::new(i as u32),
+                    coords: Coordinates::from_index(idx as u32, 5),
+                })
+                .unwrap();
+            }
+        }
+        let stats = bot.table_stats();
+        assert_eq!(stats.searches, 2);
+        assert!(stats.reused_hits > 0);
+        assert!(stats.hits >= stats.reused_hits);
+        assert_eq!(plain.table_stats(), TableStats::default());
+
+        // A new game starts from an empty table
+        bot.choose_move(&opened());
+        assert_eq!(bot.table_stats().searches, 1);
+        assert_eq!(bot.table_stats().reused_hits, 0);
+    }
+
+    #[test]
+    fn test_one_bot_serves_concurrent_games() {
+        let bot = &MinimaxBot::with_depth(3);
+        let play = |bot: &MinimaxBot, opening: u32| {
+            let mut game = GameY::new(5);
+            let mut moves = Vec::new();
+            for ply in 0..6 {
+                let player = PlayerId::new(ply % 2);
+                let coords = if ply == 0 {
+                    Coordinates::from_index(opening, 5)
+                } else {
+                    bot.choose_move(&game).unwrap()
+                };
+                game.add_move(crate::Movement::Placement { player, coords })
+                    .unwrap();
+                moves.push(coords);
+            }
+            moves
+        };
+        let openings = [0, 4, 7, 12];
+        let concurrent: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = openings
+                .iter()
+                .map(|&opening| scope.spawn(move || play(bot, opening)))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        // Each game gets the moves a bot of its own would have played
+        for (opening, moves) in openings.into_iter().zip(concurrent) {
+            assert_eq!(moves, play(&MinimaxBot::with_depth(3), opening));
+        }
+        assert!(bot.table_stats().searches > 0);
+    }
+
+    #[test]
+    fn test_blocked_cells_are_cut_out_of_the_search() {
+        let hole = Coordinates::new(2, 2, 2);
+        let idx = hole.to_index(7) as usize;
+        let mut game = GameYBuilder::new(7)
+            .with_blocked_cell(hole)
+            .build()
+            .unwrap();
+        let state = MinimaxState::new(&game, PlayerId::new(0));
+        assert!(state.empty_cells().all(|cell| cell != idx));
+        assert!(
+            state
+                .caches
+                .neighbors
+                .iter()
+                .all(|neighbors| !neighbors.contains(&(idx as CellIdx)))
+        );
+        assert_eq!(
+            state.to_game().unwrap().blocked_cells(),
+            game.blocked_cells()
+        );
+        // The caches shared by open boards keep the cell
+        let open = MinimaxState::new(&GameY::new(7), PlayerId::new(0));
+        assert!(open.empty_cells().any(|cell| cell == idx));
+
+        let bot = MinimaxBot::with_depth(2);
+        for _ in 0..6 {
+            let player = game.next_player().unwrap();
+            let coords = bot.choose_move(&game).unwrap();
+            assert_ne!(coords, hole);
+            game.add_move(crate::Movement::Placement { player, coords })
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_choose_moves_plays_every_game_of_a_batch() {
+        let bot = MinimaxBot::with_depth(2);
+        let mut games: Vec<GameY> = (0..9).map(|i| GameY::new(4 + i % 3)).collect();
+        for (i, game) in games.iter_mut().enumerate() {
+            let size = game.board_size();
+            game.add_move(crate::Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::from_index(i as u32, size),
+            })
+            .unwrap();
+        }
+        games[4].resign(PlayerId::new(1)).unwrap();
+        let batch: Vec<&GameY> = games.iter().collect();
+        let moves = bot.choose_moves(&batch, 1_000);
+        let single: Vec<_> = games.iter().map(|game| bot.choose_move(game)).collect();
+        assert_eq!(moves, single);
+        assert_eq!(moves[4], None);
+        assert!(bot.choose_moves(&[], 1_000).is_empty());
+    }
+
+    #[test]
+    fn test_settings_change_between_moves() {
+        let bot = Arc::new(MinimaxBot::new(60_000).with_max_depth(1));
+        let game = GameY::new(5);
+        bot.set_limits(SearchLimits {
+            max_time_ms: None,
+            max_depth: Some(3),
+            ..bot.limits()
+        });
+        assert_eq!(bot.max_time_ms(), None);
+        assert_eq!(*bot, MinimaxBot::with_depth(3));
+        assert_eq!(
+            bot.choose_move(&game),
+            MinimaxBot::with_depth(3).choose_move(&game)
         );
+
+        let weights = EvalWeights {
+            center: 0.0,
+            ..EvalWeights::default()
+        };
+        bot.set_weights(weights);
+        assert_eq!(bot.weights(), weights);
+        // Clones take the settings of the bot at the time
+        let clone = MinimaxBot::clone(&bot);
+        bot.set_weights(EvalWeights::default());
+        assert_eq!(clone.weights(), weights);
+        assert_ne!(clone, *bot);
+    }
+
+    struct CountingModel {
+        calls: std::sync::atomic::AtomicU64,
+        reply: bool,
+    }
+
+    impl OpponentModel for CountingModel {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn choose_reply(&self, state: &mut MinimaxState) -> Option<usize> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            self.reply.then(|| state.empty_cells().next()).flatten()
+        }
+    }
+
+    #[test]
+    fn test_search_against_a_model_follows_its_replies() {
+        let limits = SearchLimits {
+            max_depth: Some(3),
+            ..SearchLimits::default()
+        };
+        let model = CountingModel {
+            calls: Default::default(),
+            reply: true,
+        };
+        let mut state = create_empty_state(4);
+        let best = search_against(&mut state, &limits, &model).unwrap();
+        assert!(best < 10);
+        // The model answers for the opponent instead of a search of its moves
+        let calls = model.calls.load(Ordering::Relaxed);
+        assert!(calls > 0 && calls < 200, "{} calls", calls);
+        assert_eq!(state.empty_cells().count(), 10);
+
+        // A model without an answer leaves the search unchanged
+        let silent = CountingModel {
+            calls: Default::default(),
+            reply: false,
+        };
+        let expected = search_with_limits(&mut state, &limits, &NEVER_STOP, &mut |_| {});
+        assert_eq!(search_against(&mut state, &limits, &silent), expected);
+    }
+
+    #[test]
+    fn test_bot_with_opponent_model() {
+        let model: Arc<dyn OpponentModel> = Arc::new(crate::GreedyOpponent);
+        let bot = MinimaxBot::new(1000).with_max_depth(3);
+        let modelled = bot.clone().with_opponent_model(model.clone());
+        assert_ne!(bot, modelled);
+        assert_eq!(modelled, bot.clone().with_opponent_model(model));
+
+        let game = GameY::new(5);
+        let coords = modelled.choose_move(&game).unwrap();
+        assert!(game.available_cells().contains(&coords.to_index(5)));
+    }
+
+    #[test]
+    fn test_search_tree_matches_the_search() {
+        let game = GameY::new(4);
+        let bot = MinimaxBot::new(1000).with_max_depth(3);
+        let (coords, tree) = bot
+            .choose_move_with_tree(&game, TreeCaps::default())
+            .unwrap();
+        assert_eq!(Some(coords), bot.choose_move(&game));
+        assert_eq!(tree.depth, 3);
+        assert!(!tree.truncated);
+
+        // The best child of the root is the chosen move, with the root score
+        let root_score = tree.root().unwrap().score;
+        let best = tree
+            .children(0)
+            .into_iter()
+            .find(|&child| tree.nodes[child].score == root_score)
+            .unwrap();
+        assert_eq!(tree.nodes[best].cell, Some(coords.to_index(4) as usize));
+        assert_eq!(tree.nodes[best].player, Some(PlayerId::new(0)));
+        assert!(tree.nodes.iter().all(|node| node.ply <= 3));
+        assert!(tree.nodes.iter().any(|node| node.cutoff));
+    }
+
+    #[test]
+    fn test_search_tree_respects_caps() {
+        let mut state = create_empty_state(5);
+        let limits = SearchLimits {
+            max_depth: Some(3),
+            ..SearchLimits::default()
+        };
+        let caps = TreeCaps {
+            max_nodes: 50,
+            max_depth: 2,
+        };
+        let (best, tree) = search_with_tree(&mut state, &limits, caps);
+        assert!(best.is_some());
+        assert_eq!(tree.nodes.len(), 50);
+        assert!(tree.truncated);
+        assert!(tree.nodes.iter().all(|node| node.ply <= 2));
+    }
+
+    #[test]
+    fn test_search_scored_searches_past_a_full_board() {
+        // Three empty cells, searched deeper than the board allows
+        let game = GameY::from_position_string("B/RB/B1R/1R1B R").unwrap();
+        let mut state = MinimaxState::new(&game, PlayerId::new(1));
+        let limits = SearchLimits {
+            max_depth: Some(5),
+            ..SearchLimits::default()
+        };
+        let (best, score) = search_scored(&mut state, &limits).unwrap();
+        assert!(state.available_cells().any(|idx| idx == best));
+        assert!((LOSE_SCORE..=WIN_SCORE).contains(&score));
     }
 
     #[test]
@@ -890,10 +4410,15 @@ mod tests {
             state.undo_move(move_idx);
         }
 
-        assert_eq!(state.occupied_cells().count(), 0, "Board must be empty");
+        assert_eq!(
+            state.available_mask.zeroes().count(),
+            0,
+            "Board must be empty"
+        );
     }
 
     #[test]
+    #[allow(clippy::overly_complex_bool_expr)]
     fn test_check_win_with_many_pieces_does_not_panic() {
         let mut state = create_empty_state(4);
 
@@ -923,6 +4448,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_constants_have_correct_values() {
         assert_eq!(WIN_SCORE, 100_000);
         assert_eq!(LOSE_SCORE, -100_000);
@@ -934,6 +4460,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::manual_range_contains)]
     fn test_minimax_respects_alpha_beta_limits() {
         let mut state = create_empty_state(3);
 
@@ -941,11 +4468,157 @@ mod tests {
         state.make_move(cell, state.bot_id);
 
         // With very narrow window, should prune
-        let score = minimax(&mut state, 2, 0, 100, false);
+        let score = minimax(&mut state, 2, 0, 100, false, &mut control());
 
         assert!(
             score >= LOSE_SCORE && score <= WIN_SCORE,
             "Score must be in valid range"
         );
     }
+
+    #[test]
+    fn test_search_plays_havannah_through_rules() {
+        use crate::{GameHavannah, HavannahWinCondition, HexCoordinates};
+
+        // Player 0 needs (2, -1) to bridge the corners (2, -2) and (2, 0)
+        let mut game = GameHavannah::new(3);
+        for (player, q, r) in [(0, 2, -2), (1, -2, 0), (0, 2, 0), (1, -2, 2)] {
+            game.add_move(PlayerId::new(player), HexCoordinates::new(q, r))
+                .unwrap();
+        }
+        let stones: Vec<Option<PlayerId>> = (0..game.total_cells())
+            .map(|idx| game.cell_owner(&HexCoordinates::from_index(idx, 3)))
+            .collect();
+
+        let mut state = MinimaxState::with_rules(
+            &game,
+            Box::new(HavannahWinCondition),
+            &stones,
+            PlayerId::new(0),
+        );
+        let best = MinimaxBot::new(50).search(&mut state).unwrap();
+        assert_eq!(
+            HexCoordinates::from_index(best as u32, 3),
+            HexCoordinates::new(2, -1)
+        );
+    }
+
+    // ============================================================================
+    // LARGE BOARDS
+    // ============================================================================
+
+    /// A game of the given size with a few stones around the centre
+    fn large_game(size: u32) -> GameY {
+        let third = (size - 1) / 3;
+        let rest = size - 1 - 2 * third;
+        let center = Coordinates::new(third, third, rest);
+        let mut game = GameY::new(size);
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(0),
+            coords: center,
+        })
+        .unwrap();
+        let reply = game.get_neighbors(&center)[0];
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(1),
+            coords: reply,
+        })
+        .unwrap();
+        game
+    }
+
+    #[test]
+    fn test_local_search_only_on_large_boards() {
+        let mut small = GameY::new(5);
+        small
+            .add_move(crate::Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(2, 1, 1),
+            })
+            .unwrap();
+        let mut state = MinimaxState::new(&small, PlayerId::new(1));
+        assert_eq!(state.take_moves(1).len(), 14);
+
+        let game = GameY::new(19);
+        let mut state = MinimaxState::new(&game, PlayerId::new(0));
+        assert_eq!(
+            state.take_moves(1).len(),
+            190,
+            "Empty board uses every cell"
+        );
+
+        state.make_move(
+            Coordinates::new(6, 6, 6).to_index(19) as usize,
+            state.bot_id,
+        );
+        // Two rings of neighbours around an interior stone
+        assert_eq!(state.take_moves(1).len(), 18);
+    }
+
+    #[test]
+    fn test_moves_use_u16_indices_and_reuse_buffers() {
+        let game = large_game(26);
+        let mut state = MinimaxState::new(&game, PlayerId::new(0));
+        let moves = state.take_moves(3);
+        let capacity = moves.capacity();
+        state.return_moves(3, moves);
+        let moves = state.take_moves(3);
+        assert_eq!(moves.capacity(), capacity);
+        assert!(moves.iter().all(|&m| (m as usize) < state.board.len()));
+    }
+
+    #[test]
+    fn test_minimax_bot_plays_near_stones_on_large_boards() {
+        for size in [19, 26] {
+            let game = large_game(size);
+            let bot = MinimaxBot::new(50);
+            let coords = bot.choose_move(&game).expect("Must find a move");
+            assert!(game.available_cells().contains(&coords.to_index(size)));
+            let near_stone = game
+                .stones()
+                .any(|(stone, _)| stone.distance(&coords) <= LOCAL_SEARCH_RADIUS);
+            assert!(near_stone, "{} is far from every stone", coords);
+        }
+    }
+
+    #[test]
+    fn test_choose_move_stops_at_the_deadline() {
+        let mut game = GameY::new(12);
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(5, 3, 3),
+        })
+        .unwrap();
+        // Without a depth cap, the third iteration of this search takes
+        // several times the budget
+        let bot = MinimaxBot::new(60);
+        let start = Instant::now();
+        let coords = bot.choose_move(&game).unwrap();
+        let elapsed = start.elapsed();
+        assert_eq!(game.cell_owner(&coords), None);
+        assert!(
+            elapsed < Duration::from_millis(60 + 50),
+            "took {:?}",
+            elapsed
+        );
+    }
+    #[test]
+    fn test_temperaments_keep_to_the_deadline_on_large_boards() {
+        let mut game = GameY::new(40);
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(13, 13, 13),
+        })
+        .unwrap();
+        let bot = MinimaxBot::new(100).with_temperament(Temperament::Aggressive);
+        let start = Instant::now();
+        let coords = bot.choose_move(&game).unwrap();
+        let elapsed = start.elapsed();
+        assert_eq!(game.cell_owner(&coords), None);
+        assert!(
+            elapsed < Duration::from_millis(100 + 50),
+            "took {:?}",
+            elapsed
+        );
+    }
 }
@@ -0,0 +1,181 @@
+//! A bot that mirrors the moves of its opponent.
+//!
+//! This module provides [`MirrorBot`], which answers each move with its
+//! image under a [`Symmetry`] of the board. Copying the opponent is a
+//! classic sparring strategy of connection games: it is easy to beat once
+//! understood, but punishes bots that do not plan their own connections,
+//! and it exercises the symmetry transforms of the board.
+
+use std::sync::Arc;
+
+use crate::bot::minimax::central_cell;
+use crate::{Coordinates, GameY, Movement, Symmetry, YBot};
+
+/// A bot that plays the image of its opponent's last move under a symmetry
+/// of the board.
+///
+/// When the image is taken, lies on the axis of a reflection, or there is
+/// no move to copy, the bot plays its fallback instead: the move of a
+/// fallback bot if it has one, and otherwise the empty cell closest to the
+/// centre.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameY, MirrorBot, Movement, PlayerId, Symmetry, YBot};
+///
+/// let bot = MirrorBot::new(Symmetry::ReflectAB);
+/// let mut game = GameY::new(5);
+/// game.add_move(Movement::Placement {
+///     player: PlayerId::new(0),
+///     coords: Coordinates::new(3, 1, 0),
+/// })
+/// .unwrap();
+/// assert_eq!(bot.choose_move(&game), Some(Coordinates::new(1, 3, 0)));
+/// ```
+#[derive(Clone)]
+pub struct MirrorBot {
+    symmetry: Symmetry,
+    fallback: Option<Arc<dyn YBot>>,
+}
+
+impl MirrorBot {
+    /// Creates a bot that mirrors moves through `symmetry`.
+    pub fn new(symmetry: Symmetry) -> Self {
+        Self {
+            symmetry,
+            fallback: None,
+        }
+    }
+
+    /// Plays the moves of `fallback` when no move can be mirrored.
+    pub fn with_fallback(mut self, fallback: Arc<dyn YBot>) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// Returns the symmetry the bot mirrors moves through.
+    pub fn symmetry(&self) -> Symmetry {
+        self.symmetry
+    }
+
+    /// Returns the image of the last move of `game` if that cell is empty.
+    pub fn mirrored_move(&self, game: &GameY) -> Option<Coordinates> {
+        let Some(Movement::Placement { coords, .. }) = game.history().last() else {
+            return None;
+        };
+        let image = self.symmetry.transform(*coords);
+        game.cell_owner(&image).is_none().then_some(image)
+    }
+}
+
+impl Default for MirrorBot {
+    /// Mirrors moves through the reflection that swaps sides A and B.
+    fn default() -> Self {
+        Self::new(Symmetry::ReflectAB)
+    }
+}
+
+impl std::fmt::Debug for MirrorBot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MirrorBot")
+            .field("symmetry", &self.symmetry)
+            .field("fallback", &self.fallback.as_ref().map(|bot| bot.name()))
+            .finish()
+    }
+}
+
+impl YBot for MirrorBot {
+    fn name(&self) -> &str {
+        "mirror_bot"
+    }
+
+    fn choose_move(&self, game: &GameY) -> Option<Coordinates> {
+        game.next_player()?;
+        if let Some(coords) = self.mirrored_move(game) {
+            return Some(coords);
+        }
+        match &self.fallback {
+            Some(fallback) => fallback.choose_move(game),
+            None => central_cell(game),
+        }
+    }
+
+    fn reseed(&self, seed: u64) {
+        if let Some(fallback) = &self.fallback {
+            fallback.reseed(seed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PlayerId, RandomBot};
+
+    fn place(game: &mut GameY, coords: Coordinates) {
+        let player = game.next_player().unwrap();
+        game.add_move(Movement::Placement { player, coords })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_mirror_games_are_symmetric() {
+        for symmetry in [
+            Symmetry::ReflectAB,
+            Symmetry::ReflectBC,
+            Symmetry::ReflectAC,
+            Symmetry::Rotate120,
+        ] {
+            let mirror = MirrorBot::new(symmetry);
+            let opponent = RandomBot::with_seed(6);
+            let mut game = GameY::new(7);
+            place(&mut game, Coordinates::new(4, 2, 0));
+            while game.next_player() == Some(PlayerId::new(1)) {
+                let mirrored = mirror.mirrored_move(&game);
+                let coords = mirror.choose_move(&game).unwrap();
+                // The bot falls back only when there is no image to play
+                if let Some(image) = mirrored {
+                    assert_eq!(coords, image);
+                }
+                place(&mut game, coords);
+                if game.next_player().is_some() {
+                    let coords = opponent.choose_move(&game).unwrap();
+                    place(&mut game, coords);
+                }
+            }
+            // Some of the moves were copies
+            let copied = game
+                .history()
+                .chunks(2)
+                .filter(|pair| match pair {
+                    [
+                        Movement::Placement { coords: a, .. },
+                        Movement::Placement { coords: b, .. },
+                    ] => symmetry.transform(*a) == *b,
+                    _ => false,
+                })
+                .count();
+            assert!(copied > 0, "{:?}", symmetry);
+        }
+    }
+
+    #[test]
+    fn test_unmirrorable_moves_fall_back() {
+        let bot = MirrorBot::new(Symmetry::ReflectAB);
+        // Nothing to copy on an empty board
+        let mut game = GameY::new(5);
+        assert_eq!(bot.choose_move(&game), Some(Coordinates::new(2, 1, 1)));
+
+        // A stone on the axis of the reflection is its own image
+        place(&mut game, Coordinates::new(1, 1, 2));
+        assert_eq!(bot.mirrored_move(&game), None);
+        assert_eq!(bot.choose_move(&game), Some(Coordinates::new(2, 1, 1)));
+
+        let fallback = RandomBot::with_seed(2);
+        let expected = fallback.choose_move(&game);
+        let bot = bot.with_fallback(Arc::new(RandomBot::with_seed(2)));
+        assert_eq!(bot.choose_move(&game), expected);
+        assert_eq!(bot.name(), "mirror_bot");
+    }
+}
@@ -5,13 +5,79 @@
 //!
 //! - [`YBot`] - A trait that defines the interface for all bots
 //! - [`YBotRegistry`] - A registry for managing multiple bot implementations
+//! - [`BotFactory`] - Construction of bots from a type name and parameters
 //! - [`RandomBot`] - A simple bot that makes random valid moves
+//! - [`ExternalEngineBot`] - Third-party engines played over GTP as child
+//!   processes
+//! - [`MirrorBot`] - A sparring bot that mirrors its opponent's moves
+//! - [`RlBot`] - A bot for small boards that learns by self-play
+//! - [`BatchEvaluator`] - Evaluation of many positions with reused state
+//! - [`TexelTuner`] and [`SpsaTuner`] - Automatic tuning of [`EvalWeights`]
+//! - [`OpeningBook`] and [`OpeningBookBuilder`] - Book moves for the opening,
+//!   generated by search or self-play
+//! - [`AntiBook`] - The openings of lost games, for the minimax bot to avoid
+//! - [`OpponentModel`] - Predicted replies of the opponent of the minimax bot
+//! - [`SearchTree`] - The tree explored by the minimax search, for debugging
+//! - [`SearchTrace`] - JSON Lines traces of the minimax search, for analysis
+//! - [`SearchSnapshot`] - JSON dumps of the minimax search state, for bug
+//!   reports
+//! - [`PositionGenerator`] - Random balanced mid-game positions, for
+//!   benchmarks, tuning and puzzles
+//! - [`StrategicSuite`] - Positions with scored moves, grading the positional
+//!   judgment of bots
+//! - [`evaluate_position`] - The static evaluation of a game, without a bot
+//! - [`evaluate_detailed`] - The static evaluation split into its terms
+//! - [`score_to_winprob`] - Search scores as win probabilities, for display
+//! - [`BotProfiles`] - Named [`MinimaxBot`] settings loaded from TOML files
 
+pub mod anti_book;
+pub mod batch_eval;
+pub(crate) mod bitboard;
+pub mod bot_factory;
+#[cfg(feature = "profiles")]
+pub mod bot_profile;
+pub mod eval_tuning;
+pub mod external_engine;
 pub mod minimax;
+pub mod mirror;
+pub mod opening_book;
+pub mod opponent_model;
+pub mod position_generator;
 pub mod random;
+pub mod rl_bot;
+#[cfg(feature = "serde")]
+pub mod search_snapshot;
+#[cfg(feature = "serde")]
+pub mod search_trace;
+pub mod search_tree;
+pub mod strategic_suite;
+pub mod swap_decision;
+pub mod transposition;
+pub mod win_probability;
 pub mod ybot;
 pub mod ybot_registry;
+pub use anti_book::*;
+pub use batch_eval::*;
+pub use bot_factory::*;
+#[cfg(feature = "profiles")]
+pub use bot_profile::*;
+pub use eval_tuning::*;
+pub use external_engine::*;
 pub use minimax::*;
+pub use mirror::*;
+pub use opening_book::*;
+pub use opponent_model::*;
+pub use position_generator::*;
 pub use random::*;
+pub use rl_bot::*;
+#[cfg(feature = "serde")]
+pub use search_snapshot::*;
+#[cfg(feature = "serde")]
+pub use search_trace::*;
+pub use search_tree::*;
+pub use strategic_suite::*;
+pub use swap_decision::*;
+pub use transposition::*;
+pub use win_probability::*;
 pub use ybot::*;
 pub use ybot_registry::*;
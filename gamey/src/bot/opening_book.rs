@@ -0,0 +1,551 @@
+//! Opening books for the minimax bot.
+//!
+//! An [`OpeningBook`] maps the early positions of a game to weighted moves,
+//! so that the bot answers them at once and with some variety instead of
+//! searching. Books are far too large to write by hand: an
+//! [`OpeningBookBuilder`] generates one from deep searches or from self-play
+//! over the early-move tree. Positions are indexed by their
+//! [canonical hash](GameY::canonical_hash), so each position is analysed once
+//! for all its rotations and reflections.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+#[cfg(feature = "serde")]
+use std::path::Path;
+
+#[cfg(feature = "serde")]
+use crate::GameYError;
+use crate::{
+    Coordinates, EvalWeights, GameRng, GameY, MinimaxBot, MinimaxState, Movement, SearchLimits,
+    WIN_SCORE, YBot, derive_seed, search_scored, seeded_rng,
+};
+use rand::Rng;
+use rand::prelude::IndexedRandom;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A move of an opening book, with its weight among the moves of its
+/// position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BookMove {
+    /// The cell to play, in the orientation of the queried position.
+    pub coords: Coordinates,
+    /// How often the move should be chosen, relative to the other moves.
+    pub weight: u32,
+}
+
+/// Weighted moves for the early positions of games on one board size.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameY, OpeningBook};
+///
+/// let mut book = OpeningBook::new(5);
+/// book.add_move(&GameY::new(5), Coordinates::new(2, 1, 1), 3);
+/// let moves = book.moves(&GameY::new(5));
+/// assert_eq!(moves[0].coords, Coordinates::new(2, 1, 1));
+/// assert_eq!(moves[0].weight, 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OpeningBook {
+    board_size: u32,
+    positions: HashMap<u64, BTreeMap<u32, u32>>,
+}
+
+impl OpeningBook {
+    /// Creates an empty book for boards of `board_size`.
+    pub fn new(board_size: u32) -> Self {
+        Self {
+            board_size,
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Returns the size of the boards the book is for.
+    pub fn board_size(&self) -> u32 {
+        self.board_size
+    }
+
+    /// Returns the number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if the book has no position.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Adds `weight` to the weight of playing `coords` from the position of
+    /// `game`. Moves added from symmetric images of a position add up.
+    ///
+    /// Moves with a weight of 0 and games on another board size are ignored.
+    pub fn add_move(&mut self, game: &GameY, coords: Coordinates, weight: u32) {
+        let size = game.board_size();
+        if weight == 0 || size != self.board_size {
+            return;
+        }
+        let (hash, symmetries) = game.canonical_position();
+        // Like in the game database, a symmetric position keeps the
+        // smallest image of the move
+        let canonical_move = symmetries
+            .iter()
+            .map(|symmetry| symmetry.transform(coords).to_index(size))
+            .min()
+            .expect("every position has a canonical orientation");
+        let entry = self
+            .positions
+            .entry(hash)
+            .or_default()
+            .entry(canonical_move)
+            .or_default();
+        *entry = entry.saturating_add(weight);
+    }
+
+    /// Returns the book moves from the position of `game`, in its
+    /// orientation, heaviest first. The list is empty if the position is
+    /// not in the book.
+    pub fn moves(&self, game: &GameY) -> Vec<BookMove> {
+        let size = game.board_size();
+        if size != self.board_size {
+            return Vec::new();
+        }
+        let (hash, symmetries) = game.canonical_position();
+        let Some(moves) = self.positions.get(&hash) else {
+            return Vec::new();
+        };
+        let to_game = symmetries[0].inverse();
+        let mut moves: Vec<BookMove> = moves
+            .iter()
+            .filter(|(index, _)| **index < game.total_cells())
+            .map(|(index, weight)| BookMove {
+                coords: to_game.transform(Coordinates::from_index(*index, size)),
+                weight: *weight,
+            })
+            // Guards against the unlikely collision of two position hashes
+            .filter(|book_move| game.cell_owner(&book_move.coords).is_none())
+            .collect();
+        moves.sort_by_key(|book_move| {
+            (
+                std::cmp::Reverse(book_move.weight),
+                book_move.coords.to_index(size),
+            )
+        });
+        moves
+    }
+
+    /// Draws one of the book moves from the position of `game`, each with a
+    /// probability proportional to its weight.
+    ///
+    /// Returns None if the position is not in the book.
+    pub fn choose(&self, game: &GameY, rng: &mut GameRng) -> Option<Coordinates> {
+        let moves = self.moves(game);
+        let total: u64 = moves
+            .iter()
+            .map(|book_move| u64::from(book_move.weight))
+            .sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = rng.random_range(0..total);
+        moves.into_iter().find_map(|book_move| {
+            let weight = u64::from(book_move.weight);
+            if pick < weight {
+                Some(book_move.coords)
+            } else {
+                pick -= weight;
+                None
+            }
+        })
+    }
+
+    /// Loads a book saved with [`OpeningBook::save_to_file`].
+    #[cfg(feature = "serde")]
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let filename = path.as_ref().display().to_string();
+        let content = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to read file: {}", filename),
+            error: e.to_string(),
+        })?;
+        serde_json::from_str(&content).map_err(|e| GameYError::SerdeError { error: e })
+    }
+
+    /// Saves the book to a JSON file.
+    #[cfg(feature = "serde")]
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
+        let content =
+            serde_json::to_string(self).map_err(|e| GameYError::SerdeError { error: e })?;
+        let filename = path.as_ref().display().to_string();
+        std::fs::write(path, content).map_err(|e| GameYError::IoError {
+            message: format!("Failed to write file: {}", filename),
+            error: e.to_string(),
+        })
+    }
+}
+
+/// Where an [`OpeningBookBuilder`] takes its moves from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookSource {
+    /// Search every move of every position of the early-move tree and keep
+    /// the best ones, weighted by their score.
+    Search,
+    /// Play `games` games of the minimax bot against itself, opening with
+    /// random moves, and weight each opening move by the games its player
+    /// won.
+    SelfPlay { games: u32 },
+}
+
+/// Generates [`OpeningBook`]s.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{GameY, OpeningBookBuilder};
+///
+/// let book = OpeningBookBuilder::new(4)
+///     .with_plies(2)
+///     .with_search_depth(1)
+///     .build();
+/// assert!(!book.moves(&GameY::new(4)).is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpeningBookBuilder {
+    board_size: u32,
+    plies: usize,
+    width: usize,
+    search_depth: u8,
+    margin: i32,
+    weights: EvalWeights,
+    source: BookSource,
+    seed: u64,
+}
+
+impl OpeningBookBuilder {
+    /// Creates a builder searching the first 4 plies of games on boards of
+    /// `board_size`, 4 plies deep, and keeping up to 3 moves per position.
+    pub fn new(board_size: u32) -> Self {
+        Self {
+            board_size,
+            plies: 4,
+            width: 3,
+            search_depth: 4,
+            margin: 25,
+            weights: EvalWeights::default(),
+            source: BookSource::Search,
+            seed: 0,
+        }
+    }
+
+    /// Sets the number of moves of a game covered by the book.
+    pub fn with_plies(mut self, plies: usize) -> Self {
+        self.plies = plies;
+        self
+    }
+
+    /// Keeps up to `width` moves per position, when searching. Every kept
+    /// move is followed in the next ply, so the size of the book grows as
+    /// `width` to the power of the number of plies.
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width.max(1);
+        self
+    }
+
+    /// Sets the depth, in plies, of the searches scoring each move, and of
+    /// the bot playing the self-play games.
+    pub fn with_search_depth(mut self, depth: u8) -> Self {
+        self.search_depth = depth.max(1);
+        self
+    }
+
+    /// Keeps only the moves scoring at most `margin` below the best move of
+    /// their position, when searching. Moves closer to the best one weigh
+    /// more.
+    pub fn with_margin(mut self, margin: i32) -> Self {
+        self.margin = margin.max(0);
+        self
+    }
+
+    /// Sets the weights of the static evaluation of the searches.
+    pub fn with_weights(mut self, weights: EvalWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Sets where the moves of the book come from.
+    pub fn with_source(mut self, source: BookSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Sets the seed of the random openings of the self-play games.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Generates the book.
+    pub fn build(&self) -> OpeningBook {
+        match self.source {
+            BookSource::Search => self.build_by_search(),
+            BookSource::SelfPlay { games } => self.build_by_self_play(games),
+        }
+    }
+
+    /// Walks the early-move tree breadth first, following only the moves
+    /// kept for the book and each position only once.
+    fn build_by_search(&self) -> OpeningBook {
+        let mut book = OpeningBook::new(self.board_size);
+        let root = GameY::new(self.board_size);
+        let mut seen = HashSet::from([root.canonical_hash()]);
+        let mut frontier = vec![root];
+        for _ in 0..self.plies {
+            let mut next = Vec::new();
+            for position in &frontier {
+                let ranked = self.rank_moves(position);
+                let Some(best) = ranked.first().map(|(_, score, _)| *score) else {
+                    continue;
+                };
+                let kept = ranked
+                    .into_iter()
+                    .take(self.width)
+                    .take_while(|(_, score, _)| best - score <= self.margin);
+                for (coords, score, child) in kept {
+                    let weight = (self.margin - (best - score) + 1) as u32;
+                    book.add_move(position, coords, weight);
+                    if !child.check_game_over() && seen.insert(child.canonical_hash()) {
+                        next.push(child);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        book
+    }
+
+    /// Scores every move of `position` for the player to move, best first.
+    /// Of the moves leading to symmetric positions only one is scored.
+    fn rank_moves(&self, position: &GameY) -> Vec<(Coordinates, i32, GameY)> {
+        let Some(player) = position.next_player() else {
+            return Vec::new();
+        };
+        let size = position.board_size();
+        let limits = SearchLimits {
+            max_depth: Some(self.search_depth - 1),
+            ..SearchLimits::default()
+        };
+        let mut children = HashSet::new();
+        let mut ranked = Vec::new();
+        for &index in position.available_cells() {
+            let coords = Coordinates::from_index(index, size);
+            let mut child = position.clone();
+            if child
+                .add_move(Movement::Placement { player, coords })
+                .is_err()
+                || !children.insert(child.canonical_hash())
+            {
+                continue;
+            }
+            let score = match child.next_player() {
+                _ if child.check_game_over() => WIN_SCORE,
+                Some(opponent) => {
+                    let mut state = MinimaxState::new(&child, opponent).with_weights(self.weights);
+                    let reply = (self.search_depth > 1)
+                        .then(|| search_scored(&mut state, &limits))
+                        .flatten();
+                    -reply.map_or_else(|| state.evaluate(), |(_, score)| score)
+                }
+                None => 0,
+            };
+            ranked.push((coords, score, child));
+        }
+        ranked.sort_by_key(|(coords, score, _)| (std::cmp::Reverse(*score), coords.to_index(size)));
+        ranked
+    }
+
+    /// Plays self-play games and credits the opening moves of each winner.
+    fn build_by_self_play(&self, games: u32) -> OpeningBook {
+        let mut book = OpeningBook::new(self.board_size);
+        let bot = MinimaxBot::new(u64::MAX)
+            .with_max_depth(self.search_depth)
+            .with_weights(self.weights);
+        for index in 0..games {
+            let mut rng = seeded_rng(derive_seed(self.seed, u64::from(index)));
+            let game = self.play_game(&bot, &mut rng);
+            let Some(winner) = game.result().map(|result| result.winner) else {
+                continue;
+            };
+            let mut position = GameY::new(self.board_size);
+            for movement in game.history().iter().take(self.plies) {
+                if let Movement::Placement { player, coords } = movement
+                    && *player == winner
+                {
+                    book.add_move(&position, *coords, 1);
+                }
+                if position.add_move(movement.clone()).is_err() {
+                    break;
+                }
+            }
+        }
+        book
+    }
+
+    /// Plays a game whose first moves are random and the rest are chosen by
+    /// `bot`.
+    fn play_game(&self, bot: &MinimaxBot, rng: &mut GameRng) -> GameY {
+        let mut game = GameY::new(self.board_size);
+        while let Some(player) = game.next_player() {
+            let coords = if game.history().len() < self.plies {
+                game.available_cells()
+                    .choose(rng)
+                    .map(|index| Coordinates::from_index(*index, self.board_size))
+            } else {
+                bot.choose_move(&game)
+            };
+            let Some(coords) = coords else {
+                break;
+            };
+            if game
+                .add_move(Movement::Placement { player, coords })
+                .is_err()
+            {
+                break;
+            }
+        }
+        game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PlayerId, Symmetry};
+    use std::sync::Arc;
+
+    fn opened_with(size: u32, coords: Coordinates) -> GameY {
+        let mut game = GameY::new(size);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords,
+        })
+        .unwrap();
+        game
+    }
+
+    #[test]
+    fn test_moves_are_returned_in_the_queried_orientation() {
+        let opening = opened_with(5, Coordinates::new(3, 1, 0));
+        let reply = Coordinates::new(1, 1, 2);
+        let mut book = OpeningBook::new(5);
+        book.add_move(&opening, reply, 2);
+        let mut expected = opening.clone();
+        expected
+            .add_move(Movement::Placement {
+                player: PlayerId::new(1),
+                coords: reply,
+            })
+            .unwrap();
+
+        for symmetry in Symmetry::ALL {
+            let rotated = opening.transformed(symmetry);
+            let moves = book.moves(&rotated);
+            assert_eq!(moves.len(), 1, "{:?}", symmetry);
+            let mut continued = rotated.clone();
+            continued
+                .add_move(Movement::Placement {
+                    player: PlayerId::new(1),
+                    coords: moves[0].coords,
+                })
+                .unwrap();
+            assert_eq!(continued.canonical_hash(), expected.canonical_hash());
+        }
+        assert!(OpeningBook::new(4).moves(&GameY::new(4)).is_empty());
+    }
+
+    #[test]
+    fn test_choices_follow_the_weights() {
+        let root = GameY::new(5);
+        let mut book = OpeningBook::new(5);
+        book.add_move(&root, Coordinates::new(2, 1, 1), 9);
+        book.add_move(&root, Coordinates::new(4, 0, 0), 1);
+        // Symmetric images of a move add up
+        book.add_move(&root, Coordinates::new(0, 4, 0), 1);
+        let moves = book.moves(&root);
+        assert_eq!(moves.len(), 2);
+        assert_eq!(moves[1].weight, 2);
+
+        let mut rng = seeded_rng(5);
+        let centre = (0..200)
+            .filter(|_| book.choose(&root, &mut rng) == Some(Coordinates::new(2, 1, 1)))
+            .count();
+        assert!((120..200).contains(&centre), "{}", centre);
+        assert_eq!(book.choose(&GameY::new(4), &mut rng), None);
+    }
+
+    #[test]
+    fn test_search_covers_the_kept_moves() {
+        let book = OpeningBookBuilder::new(4)
+            .with_plies(2)
+            .with_width(2)
+            .with_search_depth(2)
+            .with_margin(1000)
+            .build();
+        let root = GameY::new(4);
+        let first = book.moves(&root);
+        assert_eq!(first.len(), 2);
+        assert!(first[0].weight >= first[1].weight);
+        for book_move in first {
+            let mut position = root.clone();
+            position
+                .add_move(Movement::Placement {
+                    player: PlayerId::new(0),
+                    coords: book_move.coords,
+                })
+                .unwrap();
+            assert_eq!(book.moves(&position).len(), 2);
+        }
+        assert_eq!(book.len(), 3);
+    }
+
+    #[test]
+    fn test_self_play_credits_the_winners() {
+        let builder = OpeningBookBuilder::new(4)
+            .with_plies(2)
+            .with_search_depth(1)
+            .with_source(BookSource::SelfPlay { games: 20 })
+            .with_seed(11);
+        let book = builder.build();
+        assert_eq!(book, builder.build());
+        let weights = |game: &GameY| -> u32 { book.moves(game).iter().map(|m| m.weight).sum() };
+        assert!(weights(&GameY::new(4)) > 0);
+        assert!(weights(&GameY::new(4)) <= 20);
+    }
+
+    #[test]
+    fn test_bot_plays_book_moves() {
+        let root = GameY::new(5);
+        let mut book = OpeningBook::new(5);
+        book.add_move(&root, Coordinates::new(4, 0, 0), 1);
+        let bot = MinimaxBot::new(1000)
+            .with_max_depth(2)
+            .with_opening_book(Arc::new(book));
+        assert_eq!(bot.choose_move(&root), Some(Coordinates::new(4, 0, 0)));
+        // Positions out of the book are searched
+        let opened = opened_with(5, Coordinates::new(4, 0, 0));
+        assert!(bot.choose_move(&opened).is_some());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("book.json");
+        let book = OpeningBookBuilder::new(3)
+            .with_plies(2)
+            .with_search_depth(1)
+            .build();
+        book.save_to_file(&path).unwrap();
+        assert_eq!(OpeningBook::load_from_file(&path).unwrap(), book);
+    }
+}
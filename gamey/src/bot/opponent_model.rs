@@ -0,0 +1,137 @@
+//! Models of the opponent for the minimax search.
+//!
+//! By default the minimax search assumes that the opponent always finds its
+//! best reply. Against a weak opponent this wastes time on replies it will
+//! never play and misses traps it would fall into. An [`OpponentModel`]
+//! predicts the reply instead: the search then follows only that reply at
+//! the opponent's turns, which is also much faster than a full search.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{MinimaxState, YBot};
+
+/// Predicts the move of the opponent of a [`MinimaxBot`](crate::MinimaxBot).
+pub trait OpponentModel: Send + Sync {
+    /// Returns the name of the model.
+    fn name(&self) -> &str;
+
+    /// Returns the empty cell the opponent of `state`'s bot is expected to
+    /// play, or None to search every reply as usual.
+    ///
+    /// Models may play moves on `state` to look ahead, but must undo them
+    /// before returning.
+    fn choose_reply(&self, state: &mut MinimaxState) -> Option<usize>;
+}
+
+/// An opponent that wins at once when it can, and otherwise plays the move
+/// after which the static evaluation is the worst for the bot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GreedyOpponent;
+
+impl OpponentModel for GreedyOpponent {
+    fn name(&self) -> &str {
+        "greedy"
+    }
+
+    fn choose_reply(&self, state: &mut MinimaxState) -> Option<usize> {
+        let opponent = state.opponent();
+        let cells: Vec<usize> = state.empty_cells().collect();
+        let mut best: Option<(usize, i32)> = None;
+        for cell in cells {
+            state.play(cell, opponent);
+            let wins = state.is_win(opponent);
+            let score = state.evaluate();
+            state.unplay(cell);
+            if wins {
+                return Some(cell);
+            }
+            if best.is_none_or(|(_, best_score)| score < best_score) {
+                best = Some((cell, score));
+            }
+        }
+        best.map(|(cell, _)| cell)
+    }
+}
+
+/// An opponent that plays like a given bot.
+///
+/// The position is turned into a [`GameY`](crate::GameY) at every node where
+/// the opponent moves, so this model only works for the Game of Y and is
+/// best suited to bots that answer quickly.
+pub struct BotOpponent {
+    bot: Arc<dyn YBot>,
+}
+
+impl BotOpponent {
+    /// Models the opponent as playing the moves of `bot`.
+    pub fn new(bot: Arc<dyn YBot>) -> Self {
+        Self { bot }
+    }
+}
+
+impl fmt::Debug for BotOpponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BotOpponent")
+            .field("bot", &self.bot.name())
+            .finish()
+    }
+}
+
+impl OpponentModel for BotOpponent {
+    fn name(&self) -> &str {
+        self.bot.name()
+    }
+
+    fn choose_reply(&self, state: &mut MinimaxState) -> Option<usize> {
+        let game = state.to_game()?;
+        let coords = self.bot.choose_move(&game)?;
+        Some(coords.to_index(game.board_size()) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameY, GameYBuilder, PlayerId, RandomBot};
+
+    #[test]
+    fn test_greedy_opponent_takes_a_win() {
+        // Player 1 wins on size 3 next to the stones on the side x = 0
+        let game = GameYBuilder::new(3)
+            .with_stones(
+                PlayerId::new(1),
+                [Coordinates::new(0, 2, 0), Coordinates::new(0, 1, 1)],
+            )
+            .with_stone(PlayerId::new(0), Coordinates::new(2, 0, 0))
+            .with_starting_player(PlayerId::new(0))
+            .build()
+            .unwrap();
+        let mut state = MinimaxState::new(&game, PlayerId::new(0));
+        let before = state.evaluate();
+        let reply = GreedyOpponent.choose_reply(&mut state).unwrap();
+        // The state is left as it was found
+        assert_eq!(state.evaluate(), before);
+        assert_eq!(state.empty_cells().count(), 3);
+
+        state.play(reply, PlayerId::new(1));
+        assert!(state.is_win(PlayerId::new(1)));
+    }
+
+    #[test]
+    fn test_bot_opponent_plays_a_legal_move() {
+        let mut game = GameY::new(4);
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(3, 0, 0),
+        })
+        .unwrap();
+        let mut state = MinimaxState::new(&game, PlayerId::new(1));
+        let model = BotOpponent::new(Arc::new(RandomBot::with_seed(1)));
+        // The opponent of a bot playing second is player 0
+        let reply = model.choose_reply(&mut state).unwrap();
+        assert_ne!(reply, Coordinates::new(3, 0, 0).to_index(4) as usize);
+        assert!(state.empty_cells().any(|cell| cell == reply));
+        assert_eq!(model.name(), "random_bot");
+    }
+}
@@ -0,0 +1,235 @@
+//! Random balanced positions.
+//!
+//! A [`PositionGenerator`] plays random moves from an empty board and keeps
+//! the positions that a quick minimax search scores close to even. Filling a
+//! board at random gives absurd positions, often decided already, which make
+//! poor benchmarks, tuning data or puzzle seeds; searching every candidate
+//! keeps only the playable ones. Each candidate is drawn from a seed derived
+//! from the seed of the generator, and [`PositionGenerator::from_seed`]
+//! rebuilds it from that seed alone.
+
+use std::collections::HashSet;
+
+use rand::prelude::IndexedRandom;
+
+use crate::bot::swap_decision::balanced_search;
+use crate::{
+    Coordinates, EvalWeights, GameY, MinimaxState, Movement, SearchLimits, derive_seed, seeded_rng,
+};
+
+/// Largest search score, either way, of a position kept by a
+/// [`PositionGenerator`] by default. A few connections are worth about as
+/// much.
+pub const BALANCED_SCORE_WINDOW: i32 = 100;
+
+/// Number of candidates a [`PositionGenerator`] tries by default for each
+/// position it is asked for.
+pub const GENERATOR_ATTEMPTS: usize = 50;
+
+/// A position made by a [`PositionGenerator`].
+#[derive(Debug, Clone)]
+pub struct GeneratedPosition {
+    /// The position, with the player to move.
+    pub game: GameY,
+    /// The search score of the position for the player to move.
+    pub score: i32,
+    /// The seed the position was drawn from, to rebuild it with
+    /// [`PositionGenerator::from_seed`].
+    pub seed: u64,
+}
+
+/// Generates random mid-game positions that are roughly balanced.
+///
+/// # Example
+///
+/// ```
+/// use gamey::PositionGenerator;
+///
+/// let generator = PositionGenerator::new(7, 8).with_seed(3);
+/// let positions = generator.generate(2);
+/// assert_eq!(positions.len(), 2);
+/// for position in &positions {
+///     assert_eq!(position.game.history().len(), 8);
+///     assert!(position.score.abs() <= generator.window());
+///     let again = generator.from_seed(position.seed).unwrap();
+///     assert_eq!(again.game.history(), position.game.history());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PositionGenerator {
+    board_size: u32,
+    plies: usize,
+    window: i32,
+    search_depth: u8,
+    weights: EvalWeights,
+    attempts: usize,
+    seed: u64,
+}
+
+impl PositionGenerator {
+    /// Creates a generator of positions after `plies` moves on boards of
+    /// `board_size`, kept when a 2-ply search scores them within
+    /// [`BALANCED_SCORE_WINDOW`].
+    pub fn new(board_size: u32, plies: usize) -> Self {
+        Self {
+            board_size,
+            plies,
+            window: BALANCED_SCORE_WINDOW,
+            search_depth: 2,
+            weights: EvalWeights::default(),
+            attempts: GENERATOR_ATTEMPTS,
+            seed: 0,
+        }
+    }
+
+    /// Keeps the positions whose score is at most `window` either way.
+    pub fn with_window(mut self, window: i32) -> Self {
+        self.window = window.max(0);
+        self
+    }
+
+    /// Sets the depth, in plies, of the search scoring each candidate. The
+    /// score is averaged over its last two iterations, so that it does not
+    /// lean towards either player with the parity of the depth.
+    pub fn with_search_depth(mut self, depth: u8) -> Self {
+        self.search_depth = depth.max(1);
+        self
+    }
+
+    /// Sets the weights of the static evaluation of the searches.
+    pub fn with_weights(mut self, weights: EvalWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Sets the number of candidates tried for each position asked for.
+    pub fn with_attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    /// Sets the seed the candidates are drawn from.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the size of the boards of the positions.
+    pub fn board_size(&self) -> u32 {
+        self.board_size
+    }
+
+    /// Returns the number of moves played in each position.
+    pub fn plies(&self) -> usize {
+        self.plies
+    }
+
+    /// Returns the largest score, either way, of a kept position.
+    pub fn window(&self) -> i32 {
+        self.window
+    }
+
+    /// Returns up to `count` balanced positions, no two of them symmetric
+    /// images of each other.
+    ///
+    /// Fewer positions are returned when the candidates run out: when too
+    /// few of them are balanced, or the board is too small to hold that
+    /// many different positions.
+    pub fn generate(&self, count: usize) -> Vec<GeneratedPosition> {
+        let mut positions = Vec::with_capacity(count);
+        let mut seen = HashSet::new();
+        let candidates = count.saturating_mul(self.attempts) as u64;
+        for index in 0..candidates {
+            if positions.len() == count {
+                break;
+            }
+            if let Some(position) = self.from_seed(derive_seed(self.seed, index))
+                && seen.insert(position.game.canonical_hash())
+            {
+                positions.push(position);
+            }
+        }
+        positions
+    }
+
+    /// Draws the candidate of `seed` and returns it if it is balanced.
+    ///
+    /// The moves are chosen at random among those that do not win at once,
+    /// so a candidate is only discarded for its score, or when no such move
+    /// is left before the last ply.
+    pub fn from_seed(&self, seed: u64) -> Option<GeneratedPosition> {
+        let size = self.board_size;
+        let mut rng = seeded_rng(seed);
+        let mut game = GameY::new(size);
+        let mut quiet = Vec::new();
+        while game.history().len() < self.plies {
+            let player = game.next_player()?;
+            quiet.clear();
+            quiet.extend(
+                game.available_cells()
+                    .iter()
+                    .map(|&idx| Coordinates::from_index(idx, size))
+                    .filter(|&coords| !game.is_winning_move(coords, player)),
+            );
+            let coords = *quiet.choose(&mut rng)?;
+            game.add_move(Movement::Placement { player, coords }).ok()?;
+        }
+
+        let player = game.next_player()?;
+        let limits = SearchLimits {
+            max_depth: Some(self.search_depth),
+            ..SearchLimits::default()
+        };
+        let mut state = MinimaxState::new(&game, player).with_weights(self.weights);
+        let (_, score) = balanced_search(&mut state, &limits)?;
+        (score.abs() <= self.window).then_some(GeneratedPosition { game, score, seed })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generation_is_reproducible_and_balanced() {
+        let generator = PositionGenerator::new(7, 10).with_seed(11);
+        let first = generator.generate(3);
+        let second = generator.generate(3);
+        assert_eq!(first.len(), 3);
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!(a.seed, b.seed);
+            assert_eq!(a.game.history(), b.game.history());
+            assert!(a.score.abs() <= BALANCED_SCORE_WINDOW);
+            assert!(!a.game.check_game_over());
+        }
+        let other = PositionGenerator::new(7, 10).with_seed(12).generate(1);
+        assert_ne!(other[0].game.history(), first[0].game.history());
+    }
+
+    #[test]
+    fn test_unbalanced_candidates_are_discarded() {
+        let strict = PositionGenerator::new(7, 10).with_window(0);
+        let loose = PositionGenerator::new(7, 10).with_window(i32::MAX);
+        let seeds = 0..20;
+        let kept = seeds
+            .clone()
+            .filter(|&seed| strict.from_seed(seed).is_some())
+            .count();
+        assert!(kept < 20);
+        assert!(
+            seeds
+                .into_iter()
+                .all(|seed| loose.from_seed(seed).is_some())
+        );
+    }
+
+    #[test]
+    fn test_small_boards_run_out_of_positions() {
+        // Two stones on a board of size 2 leave few different positions
+        let positions = PositionGenerator::new(2, 2)
+            .with_window(i32::MAX)
+            .with_attempts(5)
+            .generate(10);
+        assert!(!positions.is_empty() && positions.len() < 10);
+    }
+}
@@ -3,7 +3,7 @@
 //! This module provides [`RandomBot`], a bot that makes random valid moves.
 //! It is useful for testing and as a baseline opponent.
 
-use crate::{Coordinates, GameY, YBot};
+use crate::{Coordinates, GameY, PlacementGame, SharedRng, YBot};
 use rand::prelude::IndexedRandom;
 
 /// A bot that chooses moves randomly from the available cells.
@@ -12,19 +12,56 @@ use rand::prelude::IndexedRandom;
 /// a random empty cell on the board. While not strategic, it serves as
 /// a useful baseline and testing tool.
 ///
+/// The moves come from a seeded [`SharedRng`]: two bots created with the
+/// same seed make the same choices.
+///
 /// # Example
 ///
 /// ```
 /// use gamey::{GameY, RandomBot, YBot};
 ///
-/// let bot = RandomBot;
+/// let bot = RandomBot::new();
 /// let game = GameY::new(5);
 ///
 /// // The bot will always return Some when there are available moves
 /// let chosen_move = bot.choose_move(&game);
 /// assert!(chosen_move.is_some());
+///
+/// // Seeded bots are reproducible
+/// let a = RandomBot::with_seed(3).choose_move(&game);
+/// assert_eq!(a, RandomBot::with_seed(3).choose_move(&game));
 /// ```
-pub struct RandomBot;
+#[derive(Debug)]
+pub struct RandomBot {
+    rng: SharedRng,
+}
+
+impl RandomBot {
+    /// Creates a bot with a random seed, which [`RandomBot::seed`] returns.
+    pub fn new() -> Self {
+        Self {
+            rng: SharedRng::from_entropy(),
+        }
+    }
+
+    /// Creates a bot whose choices are determined by `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: SharedRng::new(seed),
+        }
+    }
+
+    /// Returns the seed the bot was last started from.
+    pub fn seed(&self) -> u64 {
+        self.rng.seed()
+    }
+}
+
+impl Default for RandomBot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl YBot for RandomBot {
     fn name(&self) -> &str {
@@ -33,26 +70,35 @@ impl YBot for RandomBot {
 
     fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
         let available_cells = board.available_cells();
-        let cell = available_cells.choose(&mut rand::rng())?;
-        let coordinates = Coordinates::from_index(*cell, board.board_size());
+        let cell = self.rng.with(|rng| available_cells.choose(rng).copied())?;
+        let coordinates = Coordinates::from_index(cell, board.board_size());
         Some(coordinates)
     }
+
+    fn choose_cell(&self, game: &dyn PlacementGame) -> Option<u32> {
+        let cells = game.free_cells();
+        self.rng.with(|rng| cells.choose(rng).copied())
+    }
+
+    fn reseed(&self, seed: u64) {
+        self.rng.reseed(seed);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Movement, PlayerId};
+    use crate::{GameHavannah, Movement, PlayerId};
 
     #[test]
     fn test_random_bot_name() {
-        let bot = RandomBot;
+        let bot = RandomBot::new();
         assert_eq!(bot.name(), "random_bot");
     }
 
     #[test]
     fn test_random_bot_returns_move_on_empty_board() {
-        let bot = RandomBot;
+        let bot = RandomBot::new();
         let game = GameY::new(5);
 
         let chosen_move = bot.choose_move(&game);
@@ -61,7 +107,7 @@ mod tests {
 
     #[test]
     fn test_random_bot_returns_valid_coordinates() {
-        let bot = RandomBot;
+        let bot = RandomBot::new();
         let game = GameY::new(5);
 
         let coords = bot.choose_move(&game).unwrap();
@@ -74,7 +120,7 @@ mod tests {
 
     #[test]
     fn test_random_bot_returns_none_on_full_board() {
-        let bot = RandomBot;
+        let bot = RandomBot::new();
         let mut game = GameY::new(2);
 
         // Fill the board (size 2 has 3 cells)
@@ -105,7 +151,7 @@ mod tests {
 
     #[test]
     fn test_random_bot_chooses_from_available_cells() {
-        let bot = RandomBot;
+        let bot = RandomBot::new();
         let mut game = GameY::new(3);
 
         // Make some moves to reduce available cells
@@ -124,7 +170,7 @@ mod tests {
 
     #[test]
     fn test_random_bot_multiple_calls_return_valid_moves() {
-        let bot = RandomBot;
+        let bot = RandomBot::new();
         let game = GameY::new(7);
 
         // Call choose_move multiple times to exercise the randomness
@@ -137,4 +183,36 @@ mod tests {
             assert!(game.available_cells().contains(&index));
         }
     }
+
+    #[test]
+    fn test_random_bot_replays_a_game_from_its_seed() {
+        let play = |bot: &RandomBot| {
+            let mut game = GameY::new(5);
+            while let Some(player) = game.next_player() {
+                let coords = bot.choose_move(&game).unwrap();
+                game.add_move(Movement::Placement { player, coords })
+                    .unwrap();
+            }
+            game.history().to_vec()
+        };
+        let bot = RandomBot::with_seed(11);
+        let first = play(&bot);
+        assert_eq!(play(&RandomBot::with_seed(11)), first);
+        bot.reseed(11);
+        assert_eq!(play(&bot), first);
+        assert_eq!(bot.seed(), 11);
+    }
+
+    #[test]
+    fn test_random_bot_plays_havannah_to_the_end() {
+        let bot = RandomBot::new();
+        let mut game = GameHavannah::new(4);
+
+        while let Some(player) = game.player_to_move() {
+            let cell = bot.choose_cell(&game).unwrap();
+            game.place_stone(player, cell).unwrap();
+        }
+
+        assert!(game.winning_structure().is_some());
+    }
 }
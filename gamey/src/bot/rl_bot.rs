@@ -0,0 +1,378 @@
+//! A bot that learns to play small boards by reinforcement learning.
+//!
+//! The [`RlBot`] knows nothing of the game beyond its rules: it plays the
+//! move leading to the position with the best learned value. The values are
+//! learned by an [`RlTrainer`] from games the bot plays against itself, with
+//! temporal-difference learning over a table indexed by the
+//! [canonical hash](GameY::canonical_hash) of each position.
+//!
+//! A table holds every position met in training, so this only scales to
+//! boards up to size 5 or so. It is meant as a teaching example and as an
+//! opponent independent of the minimax search.
+
+use std::collections::HashMap;
+#[cfg(feature = "serde")]
+use std::path::Path;
+
+#[cfg(feature = "serde")]
+use crate::GameYError;
+use crate::{Coordinates, GameRng, GameY, Movement, YBot, derive_seed, seeded_rng};
+use rand::Rng;
+use rand::prelude::IndexedRandom;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Value of the positions never met in training.
+const UNKNOWN_VALUE: f64 = 0.5;
+
+/// Learned values of the positions of one board size.
+///
+/// The value of a position is the estimated probability that the player who
+/// has just moved wins from it.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ValueTable {
+    board_size: u32,
+    values: HashMap<u64, f64>,
+}
+
+impl ValueTable {
+    /// Creates a table for boards of `board_size` that knows no position.
+    pub fn new(board_size: u32) -> Self {
+        Self {
+            board_size,
+            values: HashMap::new(),
+        }
+    }
+
+    /// Returns the size of the boards the table is for.
+    pub fn board_size(&self) -> u32 {
+        self.board_size
+    }
+
+    /// Returns the number of positions learned.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if no position has been learned.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the value of the position of `game` for the player who has
+    /// just moved. Finished games are worth 1 to their winner and 0 to the
+    /// loser; unknown positions are worth 0.5.
+    pub fn value(&self, game: &GameY) -> f64 {
+        match game.result() {
+            Some(result) => match game.history().last() {
+                Some(Movement::Placement { player, .. } | Movement::Action { player, .. })
+                    if *player == result.winner =>
+                {
+                    1.0
+                }
+                _ => 0.0,
+            },
+            None => self.lookup(game.canonical_hash()),
+        }
+    }
+
+    fn lookup(&self, hash: u64) -> f64 {
+        self.values.get(&hash).copied().unwrap_or(UNKNOWN_VALUE)
+    }
+
+    /// Moves the value of `hash` towards `target` by `rate`.
+    fn update(&mut self, hash: u64, target: f64, rate: f64) {
+        let value = self.values.entry(hash).or_insert(UNKNOWN_VALUE);
+        *value += rate * (target - *value);
+    }
+
+    /// Returns the empty cells of `game` with the value of the position each
+    /// leads to, for the player to move.
+    fn move_values(&self, game: &GameY) -> Vec<(Coordinates, f64)> {
+        let Some(player) = game.next_player() else {
+            return Vec::new();
+        };
+        let size = game.board_size();
+        game.available_cells()
+            .iter()
+            .filter_map(|&index| {
+                let coords = Coordinates::from_index(index, size);
+                let mut next = game.clone();
+                next.add_move(Movement::Placement { player, coords }).ok()?;
+                Some((coords, self.value(&next)))
+            })
+            .collect()
+    }
+
+    /// Returns the move leading to the most valuable position, the first
+    /// empty cell among equals.
+    fn best_move(&self, game: &GameY) -> Option<Coordinates> {
+        self.move_values(game)
+            .into_iter()
+            .fold(
+                None,
+                |best: Option<(Coordinates, f64)>, (coords, value)| match best {
+                    Some((_, best_value)) if best_value >= value => best,
+                    _ => Some((coords, value)),
+                },
+            )
+            .map(|(coords, _)| coords)
+    }
+
+    /// Loads a table saved with [`ValueTable::save_to_file`].
+    #[cfg(feature = "serde")]
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let filename = path.as_ref().display().to_string();
+        let content = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to read file: {}", filename),
+            error: e.to_string(),
+        })?;
+        serde_json::from_str(&content).map_err(|e| GameYError::SerdeError { error: e })
+    }
+
+    /// Saves the table to a JSON file.
+    #[cfg(feature = "serde")]
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
+        let content =
+            serde_json::to_string(self).map_err(|e| GameYError::SerdeError { error: e })?;
+        let filename = path.as_ref().display().to_string();
+        std::fs::write(path, content).map_err(|e| GameYError::IoError {
+            message: format!("Failed to write file: {}", filename),
+            error: e.to_string(),
+        })
+    }
+}
+
+/// Learns a [`ValueTable`] from self-play games.
+///
+/// Each game is played by the table itself, with a random move instead of
+/// the best one at a rate of `exploration`. At the end of the game every
+/// position is moved towards the value of the next position of the same
+/// player (TD(0)), from the last move back to the first, so the result of
+/// the game reaches the opening in a single pass.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{GameY, RlBot, RlTrainer, YBot};
+///
+/// let table = RlTrainer::new(3).with_games(200).with_seed(1).train();
+/// let bot = RlBot::new(table);
+/// assert!(bot.choose_move(&GameY::new(3)).is_some());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RlTrainer {
+    table: ValueTable,
+    games: u32,
+    learning_rate: f64,
+    exploration: f64,
+    seed: u64,
+}
+
+impl RlTrainer {
+    /// Creates a trainer that learns from 1000 games on boards of
+    /// `board_size`, starting from an empty table.
+    pub fn new(board_size: u32) -> Self {
+        Self {
+            table: ValueTable::new(board_size),
+            games: 1000,
+            learning_rate: 0.2,
+            exploration: 0.1,
+            seed: 0,
+        }
+    }
+
+    /// Continues training `table` instead of starting from an empty one.
+    pub fn with_table(mut self, table: ValueTable) -> Self {
+        self.table = table;
+        self
+    }
+
+    /// Sets the number of self-play games.
+    pub fn with_games(mut self, games: u32) -> Self {
+        self.games = games;
+        self
+    }
+
+    /// Sets how far each update moves a value towards its target, between 0
+    /// and 1.
+    pub fn with_learning_rate(mut self, rate: f64) -> Self {
+        self.learning_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the share of moves played at random to explore new positions,
+    /// between 0 and 1.
+    pub fn with_exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the seed of the random moves.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Plays the training games and returns the learned table.
+    pub fn train(mut self) -> ValueTable {
+        for index in 0..self.games {
+            let mut rng = seeded_rng(derive_seed(self.seed, u64::from(index)));
+            self.train_game(&mut rng);
+        }
+        self.table
+    }
+
+    fn train_game(&mut self, rng: &mut GameRng) {
+        let size = self.table.board_size();
+        let mut game = GameY::new(size);
+        let mut positions = Vec::new();
+        while let Some(player) = game.next_player() {
+            let explore = rng.random_bool(self.exploration);
+            let coords = if explore {
+                game.available_cells()
+                    .choose(rng)
+                    .map(|&index| Coordinates::from_index(index, size))
+            } else {
+                self.table.best_move(&game)
+            };
+            let Some(coords) = coords else {
+                break;
+            };
+            if game
+                .add_move(Movement::Placement { player, coords })
+                .is_err()
+            {
+                break;
+            }
+            positions.push(game.canonical_hash());
+        }
+
+        // The last two positions are finished for their player: won by the
+        // last mover and lost by the other one
+        let mut targets = [1.0, 0.0];
+        if game.result().is_none() {
+            targets = [UNKNOWN_VALUE; 2];
+        }
+        for (i, &hash) in positions.iter().rev().enumerate() {
+            let target = if i < 2 {
+                targets[i]
+            } else {
+                self.table.lookup(positions[positions.len() - 1 - i + 2])
+            };
+            self.table.update(hash, target, self.learning_rate);
+        }
+    }
+}
+
+/// A bot that plays the move leading to the position with the best value in
+/// a learned [`ValueTable`].
+///
+/// Positions missing from the table are worth 0.5, so the bot plays
+/// arbitrary moves on boards it was not trained for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RlBot {
+    table: ValueTable,
+}
+
+impl RlBot {
+    /// Creates a bot playing by `table`.
+    pub fn new(table: ValueTable) -> Self {
+        Self { table }
+    }
+
+    /// Returns the table the bot plays by.
+    pub fn table(&self) -> &ValueTable {
+        &self.table
+    }
+}
+
+impl YBot for RlBot {
+    fn name(&self) -> &str {
+        "rl_bot"
+    }
+
+    fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+        self.table.best_move(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PlayerId, RandomBot};
+
+    fn play(first: &dyn YBot, second: &dyn YBot, size: u32) -> PlayerId {
+        let mut game = GameY::new(size);
+        while let Some(player) = game.next_player() {
+            let bot = if player == PlayerId::new(0) {
+                first
+            } else {
+                second
+            };
+            let coords = bot.choose_move(&game).unwrap();
+            game.add_move(Movement::Placement { player, coords })
+                .unwrap();
+        }
+        game.result().unwrap().winner
+    }
+
+    #[test]
+    fn test_finished_positions_are_worth_the_result() {
+        let table = ValueTable::new(2);
+        let mut game = GameY::new(2);
+        assert_eq!(table.value(&game), UNKNOWN_VALUE);
+        for index in 0..3 {
+            let player = game.next_player().unwrap();
+            game.add_move(Movement::Placement {
+                player,
+                coords: Coordinates::from_index(index, 2),
+            })
+            .unwrap();
+        }
+        assert_eq!(table.value(&game), 1.0);
+    }
+
+    #[test]
+    fn test_training_is_reproducible() {
+        let train = |seed| RlTrainer::new(3).with_games(50).with_seed(seed).train();
+        let table = train(4);
+        assert!(!table.is_empty());
+        assert_eq!(table, train(4));
+
+        let more = RlTrainer::new(3)
+            .with_table(table.clone())
+            .with_games(10)
+            .train();
+        assert!(more.len() >= table.len());
+    }
+
+    #[test]
+    fn test_trained_bot_beats_random_play() {
+        let table = RlTrainer::new(4).with_games(3000).with_seed(7).train();
+        let bot = RlBot::new(table);
+        let wins = (0..20)
+            .filter(|&seed| {
+                let random = RandomBot::with_seed(seed);
+                // Alternate colours, as the first player has the edge
+                if seed % 2 == 0 {
+                    play(&bot, &random, 4) == PlayerId::new(0)
+                } else {
+                    play(&random, &bot, 4) == PlayerId::new(1)
+                }
+            })
+            .count();
+        assert!(wins >= 15, "{} wins out of 20", wins);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("values.json");
+        let table = RlTrainer::new(3).with_games(20).train();
+        table.save_to_file(&path).unwrap();
+        assert_eq!(ValueTable::load_from_file(&path).unwrap(), table);
+    }
+}
@@ -0,0 +1,225 @@
+//! Debug snapshots of the minimax search.
+//!
+//! A [`SearchSnapshot`] holds everything the minimax search works from: the
+//! board, the masks of empty and owned cells, the caches of the board, and,
+//! when taken from a [`MinimaxBot`](crate::MinimaxBot), its search limits
+//! and the statistics of its latest search. Snapshots are saved as JSON to
+//! attach to bug reports about strange or illegal moves, and loaded back
+//! with [`SearchSnapshot::to_state`] to reproduce the search exactly, even
+//! from a state that disagrees with its own board.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    EvalWeights, GameYError, MinimaxState, PlayerId, Result, SearchInfo, SearchLimits, TableStats,
+    Temperament,
+};
+
+/// The scratch masks of a [`MinimaxState`], as cell indices.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScratchMasks {
+    /// The cells near the stones, from the latest local move generation.
+    pub near: Vec<usize>,
+    /// The frontier of the latest flood fill.
+    pub frontier: Vec<usize>,
+    /// The next frontier of the latest flood fill.
+    pub next: Vec<usize>,
+}
+
+/// The caches of a [`MinimaxState`], which only depend on the board.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheSnapshot {
+    /// The bonus of each cell for its closeness to the centre.
+    pub center_bonus: Vec<i32>,
+    /// The neighbours of each cell.
+    pub neighbors: Vec<Vec<u16>>,
+    /// The sides each cell lies on, one bit per side.
+    pub edges: Vec<u8>,
+    /// The side and carrier of the edge templates of each cell.
+    pub templates: Vec<Vec<(u8, Vec<u16>)>>,
+    /// The blocked cells of the board.
+    #[serde(default)]
+    pub blocked: Vec<usize>,
+}
+
+/// The state of a minimax search, for bug reports.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameY, MinimaxBot, Movement, PlayerId, SearchSnapshot, YBot};
+///
+/// let mut game = GameY::new(4);
+/// game.add_move(Movement::Placement {
+///     player: PlayerId::new(0),
+///     coords: Coordinates::new(1, 1, 1),
+/// })
+/// .unwrap();
+/// let bot = MinimaxBot::with_depth(2);
+/// bot.choose_move(&game);
+/// let snapshot = bot.snapshot(&game).unwrap();
+/// assert_eq!(snapshot.last_search.as_ref().unwrap().depth, 2);
+///
+/// let json = snapshot.to_json().unwrap();
+/// let loaded = SearchSnapshot::from_json(&json).unwrap();
+/// let state = loaded.to_state().unwrap();
+/// assert_eq!(state.snapshot().board, snapshot.board);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchSnapshot {
+    /// The size of the board of Y, or None for the boards of other games.
+    pub board_size: Option<u32>,
+    /// The player the state is searched for.
+    pub bot_player: PlayerId,
+    /// The stone on each cell: 0 for none, else the player id plus one.
+    pub board: Vec<u8>,
+    /// The cells the search considers empty.
+    pub available: Vec<usize>,
+    /// The cells the search considers owned by each player.
+    pub owned: [Vec<usize>; 2],
+    /// The scratch masks left by the latest search.
+    pub scratch: ScratchMasks,
+    /// The Zobrist hash of the stones.
+    pub hash: u64,
+    /// The weights of the static evaluation.
+    pub weights: EvalWeights,
+    /// The caches of the board.
+    pub caches: CacheSnapshot,
+    /// The search limits of the bot, for snapshots taken from a bot.
+    #[serde(default)]
+    pub limits: Option<SearchLimits>,
+    /// How the bot chooses between near-equal moves, for snapshots taken
+    /// from a bot.
+    #[serde(default)]
+    pub temperament: Option<Temperament>,
+    /// The use of the transposition table in the game the bot searched
+    /// last, for snapshots taken from a bot.
+    #[serde(default)]
+    pub table_stats: Option<TableStats>,
+    /// The last iteration of the latest search of the bot, for snapshots
+    /// taken from a bot that has searched.
+    #[serde(default)]
+    pub last_search: Option<SearchInfo>,
+}
+
+impl SearchSnapshot {
+    /// Rebuilds the search state the snapshot was taken from.
+    ///
+    /// Only states of the game of Y can be rebuilt. Returns an error if the
+    /// snapshot is not consistent with a board of its size.
+    pub fn to_state(&self) -> Result<MinimaxState> {
+        MinimaxState::from_snapshot(self)
+    }
+
+    /// Returns the snapshot as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| GameYError::SerdeError { error: e })
+    }
+
+    /// Parses a snapshot written by [`SearchSnapshot::to_json`].
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| GameYError::SerdeError { error: e })
+    }
+
+    /// Loads a snapshot saved with [`SearchSnapshot::save_to_file`].
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let filename = path.as_ref().display().to_string();
+        let content = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to read file: {}", filename),
+            error: e.to_string(),
+        })?;
+        Self::from_json(&content)
+    }
+
+    /// Saves the snapshot to a JSON file.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let content = self.to_json()?;
+        let filename = path.as_ref().display().to_string();
+        std::fs::write(path, content).map_err(|e| GameYError::IoError {
+            message: format!("Failed to write file: {}", filename),
+            error: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameY, MinimaxBot, Movement, search_scored};
+
+    fn opened_game() -> GameY {
+        let mut game = GameY::new(5);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(2, 1, 1),
+        })
+        .unwrap();
+        game
+    }
+
+    #[test]
+    fn test_loaded_states_search_like_the_original() {
+        let game = opened_game();
+        let limits = SearchLimits {
+            max_depth: Some(3),
+            ..SearchLimits::default()
+        };
+        let mut original = MinimaxState::new(&game, PlayerId::new(1));
+        let json = original.snapshot().to_json().unwrap();
+        let mut loaded = SearchSnapshot::from_json(&json)
+            .unwrap()
+            .to_state()
+            .unwrap();
+        assert_eq!(loaded.snapshot(), original.snapshot());
+        assert_eq!(
+            search_scored(&mut loaded, &limits),
+            search_scored(&mut original, &limits)
+        );
+    }
+
+    #[test]
+    fn test_inconsistent_masks_are_kept() {
+        let mut snapshot = MinimaxState::new(&opened_game(), PlayerId::new(1)).snapshot();
+        snapshot.available.retain(|&cell| cell != 0);
+        let state = snapshot.to_state().unwrap();
+        assert!(state.empty_cells().all(|cell| cell != 0));
+        assert_eq!(state.stone(0), None);
+    }
+
+    #[test]
+    fn test_bot_snapshots_hold_its_settings() {
+        let game = opened_game();
+        let bot = MinimaxBot::with_depth(2);
+        assert_eq!(bot.snapshot(&game).unwrap().last_search, None);
+        crate::YBot::choose_move(&bot, &game);
+        let snapshot = bot.snapshot(&game).unwrap();
+        assert_eq!(snapshot.limits, Some(bot.limits()));
+        assert_eq!(snapshot.last_search, bot.last_search());
+        assert!(snapshot.last_search.is_some());
+    }
+
+    #[test]
+    fn test_invalid_snapshots_are_rejected() {
+        let snapshot = MinimaxState::new(&opened_game(), PlayerId::new(1)).snapshot();
+        let mut wrong_size = snapshot.clone();
+        wrong_size.board_size = Some(6);
+        assert!(wrong_size.to_state().is_err());
+        let mut huge = snapshot.clone();
+        huge.board_size = Some(u32::MAX);
+        assert!(matches!(
+            huge.to_state(),
+            Err(GameYError::InvalidSnapshot { .. })
+        ));
+        let mut outside = snapshot.clone();
+        outside.owned[0].push(snapshot.board.len());
+        assert!(outside.to_state().is_err());
+        let mut not_y = snapshot;
+        not_y.board_size = None;
+        assert!(matches!(
+            not_y.to_state(),
+            Err(GameYError::InvalidSnapshot { .. })
+        ));
+    }
+}
@@ -0,0 +1,289 @@
+//! Machine-readable traces of the minimax search.
+//!
+//! A [`SearchTrace`] appends one JSON object per line (JSON Lines) for each
+//! iteration completed by the searches of a [`MinimaxBot`]: its depth,
+//! score, principal variation, nodes and time, and optionally the score
+//! and nodes of every root move. Lines are written as the search goes, so
+//! that the behaviour of the engine over thousands of games can be analysed
+//! offline by reading the file back with [`read_trace`] or any JSON tool.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GameY, GameYError, PlayerId, Result, SearchInfo};
+
+/// A root move and its score in a [`TraceRecord`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TracedMove {
+    /// The cell index of the move.
+    pub cell: usize,
+    /// The score of the move, from the point of view of the bot.
+    pub score: i32,
+    /// The nodes visited below the move since the search started.
+    #[serde(default)]
+    pub nodes: u64,
+}
+
+/// One line of a search trace: an iteration completed by a search.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceRecord {
+    /// Number of the search in the trace, shared by its iterations.
+    pub search: u64,
+    /// The size of the board searched.
+    pub board_size: u32,
+    /// The number of moves played before the searched position.
+    pub moves_played: usize,
+    /// The player the search is for.
+    pub player: PlayerId,
+    /// The depth of the iteration.
+    pub depth: u8,
+    /// The score of the best move, from the point of view of the player.
+    pub score: i32,
+    /// The principal variation, as cell indices.
+    pub pv: Vec<usize>,
+    /// The number of nodes visited since the search started.
+    pub nodes: u64,
+    /// Milliseconds elapsed since the search started.
+    pub elapsed_ms: u64,
+    /// The root moves searched, best first, if the trace records them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_moves: Option<Vec<TracedMove>>,
+}
+
+/// A writer of search traces, shared by every search of the bots it is
+/// given to.
+///
+/// Lines of concurrent searches may interleave, but each line is written
+/// whole. Write errors are logged and otherwise ignored, so that a full
+/// disk never stops a game.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use gamey::{Coordinates, GameY, MinimaxBot, Movement, PlayerId, SearchTrace, YBot, read_trace};
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("trace.jsonl");
+/// let trace = Arc::new(SearchTrace::create(&path).unwrap().with_root_moves());
+/// let bot = MinimaxBot::with_depth(2).with_trace(trace);
+/// let mut game = GameY::new(5);
+/// game.add_move(Movement::Placement {
+///     player: PlayerId::new(0),
+///     coords: Coordinates::new(4, 0, 0),
+/// })
+/// .unwrap();
+/// bot.choose_move(&game);
+///
+/// let records = read_trace(&path).unwrap();
+/// assert_eq!(records.len(), 2);
+/// assert_eq!(records[1].depth, 2);
+/// ```
+pub struct SearchTrace {
+    writer: Mutex<Box<dyn Write + Send>>,
+    root_moves: bool,
+    searches: AtomicU64,
+}
+
+impl SearchTrace {
+    /// Creates a trace written to `writer`.
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Mutex::new(Box::new(writer)),
+            root_moves: false,
+            searches: AtomicU64::new(0),
+        }
+    }
+
+    /// Creates a trace appended to the file at `path`, which is created if
+    /// it does not exist.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let filename = path.as_ref().display().to_string();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| GameYError::IoError {
+                message: format!("Failed to open file: {}", filename),
+                error: e.to_string(),
+            })?;
+        Ok(Self::new(file))
+    }
+
+    /// Also records the score of every root move of each iteration, and the
+    /// nodes spent below it.
+    pub fn with_root_moves(mut self) -> Self {
+        self.root_moves = true;
+        self
+    }
+
+    /// Starts recording a search of `game` for `player` and returns the
+    /// function to call with the progress of each iteration.
+    pub(crate) fn start_search(
+        &self,
+        game: &GameY,
+        player: PlayerId,
+    ) -> impl FnMut(&SearchInfo) + '_ {
+        let search = self.searches.fetch_add(1, Ordering::Relaxed);
+        let board_size = game.board_size();
+        let moves_played = game.history().len();
+        move |info| {
+            self.write(&TraceRecord {
+                search,
+                board_size,
+                moves_played,
+                player,
+                depth: info.depth,
+                score: info.score,
+                pv: info.pv.clone(),
+                nodes: info.nodes,
+                elapsed_ms: info.elapsed_ms,
+                root_moves: self.root_moves.then(|| {
+                    info.root_scores
+                        .iter()
+                        .map(|&(cell, score)| TracedMove {
+                            cell,
+                            score,
+                            nodes: info
+                                .root_nodes
+                                .iter()
+                                .find(|&&(searched, _)| searched == cell)
+                                .map_or(0, |&(_, nodes)| nodes),
+                        })
+                        .collect()
+                }),
+            })
+        }
+    }
+
+    fn write(&self, record: &TraceRecord) {
+        let result = serde_json::to_string(record)
+            .map_err(std::io::Error::other)
+            .and_then(|line| {
+                let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+                writeln!(writer, "{}", line)?;
+                writer.flush()
+            });
+        if let Err(e) = result {
+            tracing::warn!("Failed to write search trace: {}", e);
+        }
+    }
+}
+
+impl std::fmt::Debug for SearchTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SearchTrace")
+            .field("root_moves", &self.root_moves)
+            .field("searches", &self.searches.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+/// Reads the records of a trace file written by a [`SearchTrace`].
+pub fn read_trace<P: AsRef<Path>>(path: P) -> Result<Vec<TraceRecord>> {
+    let filename = path.as_ref().display().to_string();
+    let io_error = |e: std::io::Error| GameYError::IoError {
+        message: format!("Failed to read file: {}", filename),
+        error: e.to_string(),
+    };
+    let file = File::open(&path).map_err(io_error)?;
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(io_error)?;
+        if !line.trim().is_empty() {
+            records.push(
+                serde_json::from_str(&line).map_err(|e| GameYError::SerdeError { error: e })?,
+            );
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MinimaxBot, YBot};
+    use std::sync::Arc;
+
+    /// A board of `size` with a stone in a corner, as the bot plays the
+    /// first move of a game without searching
+    fn opened(size: u32) -> GameY {
+        let mut game = GameY::new(size);
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(0),
+            coords: crate::Coordinates::new(size - 1, 0, 0),
+        })
+        .unwrap();
+        game
+    }
+
+    #[test]
+    fn test_every_iteration_is_traced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.jsonl");
+        let trace = Arc::new(SearchTrace::create(&path).unwrap());
+        let bot = MinimaxBot::with_depth(3).with_trace(trace);
+        let mut game = opened(5);
+        for _ in 0..2 {
+            let player = game.next_player().unwrap();
+            let coords = bot.choose_move(&game).unwrap();
+            game.add_move(crate::Movement::Placement { player, coords })
+                .unwrap();
+        }
+
+        let records = read_trace(&path).unwrap();
+        assert_eq!(records.len(), 6);
+        for (i, record) in records.iter().enumerate() {
+            assert_eq!(record.search, i as u64 / 3);
+            assert_eq!(record.moves_played, i / 3 + 1);
+            assert_eq!(record.depth as usize, i % 3 + 1);
+            assert_eq!(record.pv.len(), record.depth as usize);
+            assert_eq!(record.root_moves, None);
+        }
+        assert_eq!(records[3].player, PlayerId::new(0));
+        // The move played is the first of the last principal variation
+        let first = game.history()[1].clone();
+        let crate::Movement::Placement { coords, .. } = first else {
+            panic!("expected a placement");
+        };
+        assert_eq!(records[2].pv[0], coords.to_index(5) as usize);
+    }
+
+    #[test]
+    fn test_root_moves_are_traced_best_first() {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        struct Shared(Arc<Mutex<Vec<u8>>>);
+        impl Write for Shared {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let trace = SearchTrace::new(Shared(Arc::clone(&buffer))).with_root_moves();
+        let bot = MinimaxBot::with_depth(2).with_trace(Arc::new(trace));
+        bot.choose_move(&opened(4));
+
+        let text = String::from_utf8(buffer.lock().unwrap().clone()).unwrap();
+        let records: Vec<TraceRecord> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        for record in records {
+            let moves = record.root_moves.unwrap();
+            assert_eq!(moves.len(), 9);
+            assert_eq!(moves[0].cell, record.pv[0]);
+            assert_eq!(moves[0].score, record.score);
+            assert!(moves.windows(2).all(|pair| pair[0].score >= pair[1].score));
+            assert!(moves.iter().all(|traced| traced.nodes > 0));
+        }
+    }
+}
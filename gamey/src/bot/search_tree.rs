@@ -0,0 +1,299 @@
+//! Recording of the tree explored by the minimax search.
+//!
+//! A recorded [`SearchTree`] shows what the search saw when it chose a move:
+//! every node with the move leading to it, its score, the alpha-beta window
+//! it was searched with and whether it was cut off. Trees grow exponentially
+//! with depth, so recording stops at the limits of a [`TreeCaps`].
+
+use std::fmt::Write;
+
+use crate::{Coordinates, PlayerId};
+#[cfg(feature = "serde")]
+use crate::{GameYError, Result};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Limits on the size of a recorded tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeCaps {
+    /// Maximum number of nodes recorded, including the root.
+    pub max_nodes: usize,
+    /// Maximum number of plies below the root.
+    pub max_depth: u8,
+}
+
+impl Default for TreeCaps {
+    fn default() -> Self {
+        Self {
+            max_nodes: 10_000,
+            max_depth: 3,
+        }
+    }
+}
+
+/// A node of a recorded search tree.
+///
+/// Scores and bounds are from the point of view of the searching bot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SearchTreeNode {
+    /// Index of the parent node in [`SearchTree::nodes`], None for the root.
+    pub parent: Option<usize>,
+    /// Plies between the root and the node.
+    pub ply: u8,
+    /// The cell played to reach the node, None for the root.
+    pub cell: Option<usize>,
+    /// The player who played `cell`.
+    pub player: Option<PlayerId>,
+    /// Lower bound of the window the node was searched with.
+    pub alpha: i32,
+    /// Upper bound of the window the node was searched with.
+    pub beta: i32,
+    /// The score the search returned for the node.
+    pub score: Option<i32>,
+    /// True if the search of the node stopped early on a cutoff, leaving
+    /// some of its moves unexplored.
+    pub cutoff: bool,
+}
+
+/// The tree explored by the last completed iteration of a search.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SearchTree {
+    /// Depth of the iteration the tree was recorded from.
+    pub depth: u8,
+    /// The nodes, each after its parent. The root is the first node.
+    pub nodes: Vec<SearchTreeNode>,
+    /// True if some explored nodes were left out because of the caps.
+    pub truncated: bool,
+    /// Size of the board of Y the tree was searched on, used to print cells
+    /// by name. Cells are printed by index when it is None.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub board_size: Option<u32>,
+}
+
+impl SearchTree {
+    /// Returns the root node, if the tree is not empty.
+    pub fn root(&self) -> Option<&SearchTreeNode> {
+        self.nodes.first()
+    }
+
+    /// Returns the indices of the children of node `index`.
+    pub fn children(&self, index: usize) -> Vec<usize> {
+        (index + 1..self.nodes.len())
+            .filter(|&child| self.nodes[child].parent == Some(index))
+            .collect()
+    }
+
+    /// Returns the tree in the DOT language of Graphviz. Cut-off nodes are
+    /// drawn in red.
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph search {\n    node [shape=box, fontname=\"monospace\"];\n");
+        for (index, node) in self.nodes.iter().enumerate() {
+            let name = node
+                .cell
+                .map_or("root".to_string(), |cell| self.cell_name(cell));
+            let score = node
+                .score
+                .map_or("?".to_string(), |score| score.to_string());
+            let _ = write!(
+                dot,
+                "    n{} [label=\"{}\\nscore {}\\n[{}, {}]\"",
+                index,
+                name,
+                score,
+                bound(node.alpha),
+                bound(node.beta)
+            );
+            if node.cutoff {
+                dot.push_str(", color=red");
+            }
+            dot.push_str("];\n");
+            if let Some(parent) = node.parent {
+                let _ = writeln!(dot, "    n{} -> n{};", parent, index);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Returns the tree as JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| GameYError::SerdeError { error: e })
+    }
+
+    fn cell_name(&self, cell: usize) -> String {
+        match self.board_size {
+            Some(size) => Coordinates::from_index(cell as u32, size).to_cell_name(size),
+            None => cell.to_string(),
+        }
+    }
+}
+
+/// Prints a window bound, showing the search's infinities as such.
+fn bound(value: i32) -> String {
+    match value {
+        i32::MIN..=-1_000_000_000 => "-inf".to_string(),
+        1_000_000_000..=i32::MAX => "inf".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+/// Builds a [`SearchTree`] from the events of a search.
+pub(crate) struct TreeRecorder {
+    caps: TreeCaps,
+    current: SearchTree,
+    completed: SearchTree,
+    // Index of the node being searched at each ply, None past the caps
+    stack: Vec<Option<usize>>,
+}
+
+impl TreeRecorder {
+    pub(crate) fn new(caps: TreeCaps) -> Self {
+        Self {
+            caps,
+            current: SearchTree::default(),
+            completed: SearchTree::default(),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Starts recording a new iteration from its root.
+    pub(crate) fn start_iteration(&mut self, depth: u8, alpha: i32, beta: i32) {
+        self.current = SearchTree {
+            depth,
+            ..SearchTree::default()
+        };
+        self.stack.clear();
+        self.push_node(None, None, None, alpha, beta);
+    }
+
+    /// Keeps the iteration being recorded as the result, with the root score.
+    pub(crate) fn finish_iteration(&mut self, score: i32) {
+        if let Some(root) = self.current.nodes.first_mut() {
+            root.score = Some(score);
+        }
+        self.completed = std::mem::take(&mut self.current);
+    }
+
+    /// Records that `player` plays `cell` and the resulting node is searched
+    /// with the window `[alpha, beta]`.
+    pub(crate) fn enter(&mut self, cell: usize, player: PlayerId, alpha: i32, beta: i32) {
+        let parent = self.stack.last().copied().flatten();
+        let ply = self.stack.len();
+        let fits = self.current.nodes.len() < self.caps.max_nodes
+            && ply <= usize::from(self.caps.max_depth);
+        match parent {
+            Some(parent) if fits => {
+                self.push_node(Some(parent), Some(cell), Some(player), alpha, beta)
+            }
+            Some(_) => {
+                self.current.truncated = true;
+                self.stack.push(None);
+            }
+            None => self.stack.push(None),
+        }
+    }
+
+    /// Records the score of the node being left.
+    pub(crate) fn leave(&mut self, score: i32) {
+        if let Some(Some(index)) = self.stack.pop() {
+            self.current.nodes[index].score = Some(score);
+        }
+    }
+
+    /// Marks the node being searched as cut off.
+    pub(crate) fn mark_cutoff(&mut self) {
+        if let Some(Some(index)) = self.stack.last() {
+            self.current.nodes[*index].cutoff = true;
+        }
+    }
+
+    /// Returns the tree of the last completed iteration.
+    pub(crate) fn into_tree(self) -> SearchTree {
+        self.completed
+    }
+
+    fn push_node(
+        &mut self,
+        parent: Option<usize>,
+        cell: Option<usize>,
+        player: Option<PlayerId>,
+        alpha: i32,
+        beta: i32,
+    ) {
+        self.stack.push(Some(self.current.nodes.len()));
+        self.current.nodes.push(SearchTreeNode {
+            parent,
+            ply: (self.stack.len() - 1) as u8,
+            cell,
+            player,
+            alpha,
+            beta,
+            score: None,
+            cutoff: false,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded(caps: TreeCaps) -> SearchTree {
+        let mut recorder = TreeRecorder::new(caps);
+        recorder.start_iteration(2, -10, 10);
+        for cell in 0..2 {
+            recorder.enter(cell, PlayerId::new(0), -10, 10);
+            recorder.enter(5, PlayerId::new(1), -10, 10);
+            recorder.leave(3);
+            recorder.mark_cutoff();
+            recorder.leave(3);
+        }
+        recorder.finish_iteration(3);
+        recorder.into_tree()
+    }
+
+    #[test]
+    fn test_records_nodes_in_order() {
+        let tree = recorded(TreeCaps::default());
+        assert_eq!(tree.nodes.len(), 5);
+        assert_eq!(tree.children(0), vec![1, 3]);
+        assert_eq!(tree.nodes[2].parent, Some(1));
+        assert_eq!(tree.nodes[2].ply, 2);
+        assert!(tree.nodes[1].cutoff);
+        assert_eq!(tree.root().unwrap().score, Some(3));
+        assert!(!tree.truncated);
+    }
+
+    #[test]
+    fn test_caps_truncate_the_tree() {
+        let shallow = recorded(TreeCaps {
+            max_nodes: 100,
+            max_depth: 1,
+        });
+        assert_eq!(shallow.nodes.len(), 3);
+        assert!(shallow.truncated);
+
+        let small = recorded(TreeCaps {
+            max_nodes: 2,
+            max_depth: 5,
+        });
+        assert_eq!(small.nodes.len(), 2);
+        assert!(small.truncated);
+    }
+
+    #[test]
+    fn test_dot_output() {
+        let mut tree = recorded(TreeCaps::default());
+        tree.board_size = Some(3);
+        let dot = tree.to_dot();
+        assert!(dot.starts_with("digraph search {"));
+        assert!(dot.contains("n0 [label=\"root\\nscore 3\\n[-10, 10]\"];"));
+        assert!(dot.contains("n1 [label=\"a1\\nscore 3\\n[-10, 10]\", color=red];"));
+        assert!(dot.contains("n0 -> n3;"));
+        assert!(dot.ends_with("}\n"));
+    }
+}
@@ -0,0 +1,361 @@
+//! Strategic test suites for grading bots.
+//!
+//! A [`StrategicSuite`] is a set of quiet positions, each with the moves
+//! worth playing in it and the points each of them earns, in the spirit of
+//! the Strategic Test Suite of chess engines. [`StrategicSuite::grade`] runs
+//! a bot over every position and adds up the points of its moves into one
+//! strength score. Tactical puzzles only check that a bot finds forced
+//! wins; a suite of positions without one catches changes to the
+//! evaluation that weaken positional judgment.
+//!
+//! Suites are written as text, one position per line: a name, the position
+//! as written by [`GameY::to_position_string`], and the scored moves as
+//! cell names with their points, separated by semicolons. Moves that are
+//! not listed earn nothing. Blank lines and lines starting with `#` are
+//! ignored:
+//!
+//! ```text
+//! # The first move of a game on a board of size 5
+//! open in the centre; 1/2/3/4/5 B; c2=10 d2=10 d3=10
+//! ```
+
+use std::fmt::Display;
+
+use crate::{Coordinates, GameY, GameYError, Result, YBot};
+
+/// The positions of [`StrategicSuite::builtin`].
+///
+/// Every position is on a board of size 5, without a win in one move for
+/// either player, and was solved to the end: the moves that win the
+/// fastest earn 10 points, slower wins earn 5, and losing moves nothing.
+const BUILTIN_SUITE: &str = "\
+# Openings
+open in the centre; 1/2/3/4/5 B; c2=10 d2=10 d3=10
+answer an edge opening; 1/2/3/B3/5 R; c2=10 d2=5
+answer a corner opening; 1/2/3/4/1R2B B; d2=10 d3=10
+take the centre first; 1/2/3/4/1BR2 B; d2=10 c1=5 c2=5
+# Early middle game
+y5.05; 1/2/2R/B3/5 B; b1=10 c2=10 d2=10
+y5.06; 1/2/BR1/B3/5 R; b1=10 a1=5 b2=5
+y5.07; B/2/R2/2B1/2R2 B; d2=10
+y5.08; 1/2/1BR/1B2/B2R1 R; b2=10
+y5.09; R/RB/2B/4/1R2B B; d2=10
+y5.10; B/1R/3/1BR1/B3R B; b1=10 c2=10
+y5.11; 1/2/1RB/1R1B/R1B2 B; b2=10 d1=5 e2=5
+y5.12; 1/BR/3/1R2/2BB1 R; d1=10 e1=10 e2=10
+y5.13; B/1B/R2/1R2/R3B B; c3=10 d3=10
+y5.14; 1/R1/1RR/1B1B/4B B; d3=10 e3=5 e4=5
+";
+
+/// A position of a [`StrategicSuite`] with its scored moves.
+#[derive(Debug, Clone)]
+pub struct StrategicPosition {
+    /// The name of the position in reports.
+    pub name: String,
+    /// The position, with the player to move.
+    pub game: GameY,
+    /// The moves that earn points, with their points.
+    pub moves: Vec<(Coordinates, u32)>,
+}
+
+impl StrategicPosition {
+    /// Returns the points earned by playing `coords`.
+    pub fn points(&self, coords: Coordinates) -> u32 {
+        self.moves
+            .iter()
+            .find(|(cell, _)| *cell == coords)
+            .map_or(0, |&(_, points)| points)
+    }
+
+    /// Returns the points of the best move.
+    pub fn max_points(&self) -> u32 {
+        self.moves
+            .iter()
+            .map(|&(_, points)| points)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// The move a bot chose in a position of a suite, and what it earned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PositionGrade {
+    /// The name of the position.
+    pub name: String,
+    /// The move the bot chose, if any.
+    pub chosen: Option<Coordinates>,
+    /// The points the move earned.
+    pub points: u32,
+    /// The points of the best move of the position.
+    pub max_points: u32,
+}
+
+/// The outcome of [`StrategicSuite::grade`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuiteReport {
+    /// The grade of every position, in the order of the suite.
+    pub positions: Vec<PositionGrade>,
+}
+
+impl SuiteReport {
+    /// Returns the points earned over the whole suite.
+    pub fn score(&self) -> u32 {
+        self.positions.iter().map(|grade| grade.points).sum()
+    }
+
+    /// Returns the points a bot playing the best move everywhere would earn.
+    pub fn max_score(&self) -> u32 {
+        self.positions.iter().map(|grade| grade.max_points).sum()
+    }
+
+    /// Returns the score as a percentage of the maximum score.
+    pub fn percentage(&self) -> f64 {
+        match self.max_score() {
+            0 => 0.0,
+            max => 100.0 * self.score() as f64 / max as f64,
+        }
+    }
+
+    /// Returns the number of positions where the bot played a best move.
+    pub fn best_moves(&self) -> usize {
+        self.positions
+            .iter()
+            .filter(|grade| grade.points == grade.max_points)
+            .count()
+    }
+}
+
+impl Display for SuiteReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for grade in &self.positions {
+            let chosen = match grade.chosen {
+                Some(coords) => format!("{}", coords),
+                None => "none".to_string(),
+            };
+            writeln!(
+                f,
+                "{}: {} ({}/{})",
+                grade.name, chosen, grade.points, grade.max_points
+            )?;
+        }
+        write!(
+            f,
+            "Score: {}/{} ({:.1}%), best move in {} of {} positions",
+            self.score(),
+            self.max_score(),
+            self.percentage(),
+            self.best_moves(),
+            self.positions.len()
+        )
+    }
+}
+
+/// A set of positions with scored moves, to grade the positional judgment
+/// of bots.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{MinimaxBot, StrategicSuite};
+///
+/// let suite = StrategicSuite::parse("open in the centre; 1/2/3/4/5 B; c2=10 d2=10 d3=10").unwrap();
+/// let report = suite.grade(&MinimaxBot::with_depth(2));
+/// assert_eq!(report.max_score(), 10);
+/// assert_eq!(report.score(), 10);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StrategicSuite {
+    positions: Vec<StrategicPosition>,
+}
+
+impl StrategicSuite {
+    /// Creates an empty suite.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the suite shipped with the crate: openings and early middle
+    /// games on a board of size 5, solved to the end.
+    pub fn builtin() -> Self {
+        Self::parse(BUILTIN_SUITE).expect("the built-in suite is valid")
+    }
+
+    /// Reads a suite written one position per line, as described in the
+    /// [module documentation](self).
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut suite = Self::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: String| GameYError::InvalidGameRecord {
+                format: "strategic suite".to_string(),
+                reason: format!("line {}: {}", number + 1, reason),
+            };
+            let fields: Vec<&str> = line.split(';').map(str::trim).collect();
+            let [name, position, moves] = fields[..] else {
+                return Err(invalid(format!(
+                    "expected 3 fields separated by ';', found {}",
+                    fields.len()
+                )));
+            };
+            let game = GameY::from_position_string(position)?;
+            let size = game.board_size();
+            let moves = moves
+                .split_whitespace()
+                .map(|scored| {
+                    let (cell, points) = scored
+                        .split_once('=')
+                        .ok_or_else(|| invalid(format!("'{}' is not cell=points", scored)))?;
+                    let coords = Coordinates::from_cell_name(cell, size)
+                        .ok_or_else(|| invalid(format!("'{}' is not a cell", cell)))?;
+                    let points = points
+                        .parse()
+                        .map_err(|_| invalid(format!("'{}' is not a number of points", points)))?;
+                    Ok((coords, points))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            suite = suite.with_position(name, game, moves)?;
+        }
+        Ok(suite)
+    }
+
+    /// Adds a position where each of `moves` earns its points.
+    ///
+    /// Returns an error if the game is over, or if a move is not an empty
+    /// cell of the board.
+    pub fn with_position(
+        mut self,
+        name: impl Into<String>,
+        game: GameY,
+        moves: Vec<(Coordinates, u32)>,
+    ) -> Result<Self> {
+        let name = name.into();
+        let invalid = |reason: String| GameYError::InvalidGameRecord {
+            format: "strategic suite".to_string(),
+            reason: format!("position '{}': {}", name, reason),
+        };
+        if game.next_player().is_none() {
+            return Err(invalid("the game is over".to_string()));
+        }
+        if let Some((coords, _)) = moves.iter().find(|(coords, _)| {
+            !coords.is_valid(game.board_size()) || game.cell_owner(coords).is_some()
+        }) {
+            return Err(invalid(format!("{} is not an empty cell", coords)));
+        }
+        self.positions.push(StrategicPosition { name, game, moves });
+        Ok(self)
+    }
+
+    /// Returns the positions of the suite.
+    pub fn positions(&self) -> &[StrategicPosition] {
+        &self.positions
+    }
+
+    /// Lets `bot` choose a move in every position and scores its choices.
+    pub fn grade(&self, bot: &dyn YBot) -> SuiteReport {
+        let positions = self
+            .positions
+            .iter()
+            .map(|position| {
+                let chosen = bot.choose_move(&position.game);
+                PositionGrade {
+                    name: position.name.clone(),
+                    chosen,
+                    points: chosen.map_or(0, |coords| position.points(coords)),
+                    max_points: position.max_points(),
+                }
+            })
+            .collect();
+        SuiteReport { positions }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MinimaxBot, Movement};
+
+    /// Plays the first empty cell of the board.
+    struct FirstCellBot;
+
+    impl YBot for FirstCellBot {
+        fn name(&self) -> &str {
+            "first_cell"
+        }
+
+        fn choose_move(&self, board: &GameY) -> Option<Coordinates> {
+            let index = *board.available_cells().first()?;
+            Some(Coordinates::from_index(index, board.board_size()))
+        }
+    }
+
+    #[test]
+    fn test_builtin_positions_are_quiet_and_scored() {
+        let suite = StrategicSuite::builtin();
+        assert_eq!(suite.positions().len(), 14);
+        for position in suite.positions() {
+            assert_eq!(position.max_points(), 10, "{}", position.name);
+            // No move wins at once for the player to move
+            let player = position.game.next_player().unwrap();
+            for &index in position.game.available_cells() {
+                let mut game = position.game.clone();
+                let coords = Coordinates::from_index(index, game.board_size());
+                game.add_move(Movement::Placement { player, coords })
+                    .unwrap();
+                assert!(!game.check_game_over(), "{}", position.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_searching_bots_grade_higher() {
+        let suite = StrategicSuite::builtin();
+        let baseline = suite.grade(&FirstCellBot);
+        let report = suite.grade(&MinimaxBot::with_depth(4));
+        assert_eq!(report.max_score(), 140);
+        assert!(
+            report.score() > baseline.score(),
+            "{}\n{}",
+            report,
+            baseline
+        );
+        assert!(report.to_string().ends_with("of 14 positions"));
+    }
+
+    #[test]
+    fn test_points_of_listed_moves() {
+        let suite = StrategicSuite::parse(
+            "# comment\n\nedge; 1/2/3/B3/5 R; c2=10 d2=5\ncorner; 1/2/3/4/5 B; a1=0",
+        )
+        .unwrap();
+        let report = suite.grade(&FirstCellBot);
+        // The first cell is a1 on both boards
+        assert_eq!(report.score(), 0);
+        assert_eq!(report.max_score(), 10);
+        assert_eq!(report.best_moves(), 1);
+        let edge = &suite.positions()[0];
+        assert_eq!(
+            edge.points(Coordinates::from_cell_name("d2", 5).unwrap()),
+            5
+        );
+        assert_eq!(
+            edge.points(Coordinates::from_cell_name("e5", 5).unwrap()),
+            0
+        );
+    }
+
+    #[test]
+    fn test_invalid_suites_are_rejected() {
+        for text in [
+            "missing fields; 1/2/3/4/5 B",
+            "bad cell; 1/2/3/4/5 B; z9=10",
+            "bad points; 1/2/3/4/5 B; c2=ten",
+            "occupied; 1/2/3/B3/5 R; d1=10",
+            "finished; B R; a1=10",
+        ] {
+            assert!(StrategicSuite::parse(text).is_err(), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Swap rule decisions of the minimax bot.
+//!
+//! Under the swap (pie) rule, the second player may take over the opening
+//! stone instead of placing a stone of their own; the first player then
+//! moves again. The first player is thus pushed to open with a move that is
+//! neither too strong nor too weak, and the second player must judge which
+//! side of that opening is better.
+
+use std::fmt;
+use std::sync::atomic::AtomicBool;
+
+use crate::{
+    Coordinates, GameAction, GameY, GameYBuilder, MinimaxBot, MinimaxState, Movement, PlayerId,
+    SearchLimits, search_with_limits,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Whether to keep the opening of the opponent or to swap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum SwapDecision {
+    /// Answer the opening with a stone.
+    Keep,
+    /// Take over the opening stone.
+    Swap,
+}
+
+impl fmt::Display for SwapDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwapDecision::Keep => write!(f, "keep"),
+            SwapDecision::Swap => write!(f, "swap"),
+        }
+    }
+}
+
+/// The scores of both answers to an opening, from the point of view of the
+/// second player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SwapEvaluation {
+    /// The better of both answers. Ties keep the opening.
+    pub recommendation: SwapDecision,
+    /// Score of answering the opening with the best stone.
+    pub keep_score: i32,
+    /// Score of taking over the opening stone.
+    pub swap_score: i32,
+    /// The best stone to answer the opening with.
+    pub keep_move: Coordinates,
+}
+
+/// Searches `state` and returns the best move with its score, averaged over
+/// the last two iterations. The static evaluation favours the player who
+/// moved last, so the scores of consecutive depths lean opposite ways.
+pub(crate) fn balanced_search(
+    state: &mut MinimaxState,
+    limits: &SearchLimits,
+) -> Option<(usize, i32)> {
+    let mut scores = Vec::new();
+    let stop = AtomicBool::new(false);
+    let best_move = search_with_limits(state, limits, &stop, &mut |info| scores.push(info.score))?;
+    let score = match scores.as_slice() {
+        [.., previous, last] => (previous + last) / 2,
+        [last] => *last,
+        [] => state.evaluate(),
+    };
+    Some((best_move, score))
+}
+
+/// Returns the opening stone if `game` is at the second player's first turn.
+fn opening_stone(game: &GameY) -> Option<Coordinates> {
+    match game.history() {
+        [Movement::Placement { player, coords }]
+            if *player == PlayerId::new(0) && game.next_player() == Some(PlayerId::new(1)) =>
+        {
+            Some(*coords)
+        }
+        _ => None,
+    }
+}
+
+impl MinimaxBot {
+    /// Searches both answers to the opening of `game`, keeping it or
+    /// swapping, and recommends the better one. Each answer is searched
+    /// within the limits of the bot, and scored by the average of its last
+    /// two iterations so that neither answer gains from the parity of the
+    /// search depth.
+    ///
+    /// Returns None unless it is the second player's first turn, right after
+    /// the opening stone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gamey::{Coordinates, GameY, MinimaxBot, Movement, PlayerId, SwapDecision};
+    ///
+    /// let mut game = GameY::new(5);
+    /// game.add_move(Movement::Placement {
+    ///     player: PlayerId::new(0),
+    ///     coords: Coordinates::new(2, 1, 1),
+    /// })
+    /// .unwrap();
+    ///
+    /// // The centre is a strong opening, worth taking over
+    /// let bot = MinimaxBot::new(1000).with_max_depth(2);
+    /// let evaluation = bot.evaluate_swap(&game).unwrap();
+    /// assert_eq!(evaluation.recommendation, SwapDecision::Swap);
+    /// ```
+    pub fn evaluate_swap(&self, game: &GameY) -> Option<SwapEvaluation> {
+        let opening = opening_stone(game)?;
+        let limits = self.limits();
+        let second = PlayerId::new(1);
+
+        let mut keep = MinimaxState::new(game, second).with_weights(self.weights());
+        let (keep_move, keep_score) = balanced_search(&mut keep, &limits)?;
+
+        // After a swap the opening stone is the second player's and the
+        // first player moves again
+        let swapped = GameYBuilder::new(game.board_size())
+            .with_stone(second, opening)
+            .with_starting_player(PlayerId::new(0))
+            .build()
+            .ok()?;
+        let mut swap = MinimaxState::new(&swapped, PlayerId::new(0)).with_weights(self.weights());
+        let swap_score = match balanced_search(&mut swap, &limits) {
+            Some((_, score)) => -score,
+            None => -swap.evaluate(),
+        };
+
+        let recommendation = if swap_score > keep_score {
+            SwapDecision::Swap
+        } else {
+            SwapDecision::Keep
+        };
+        Some(SwapEvaluation {
+            recommendation,
+            keep_score,
+            swap_score,
+            keep_move: Coordinates::from_index(keep_move as u32, game.board_size()),
+        })
+    }
+
+    /// Chooses the next movement of the bot, swapping at the second
+    /// player's first turn when [`MinimaxBot::evaluate_swap`] recommends
+    /// it.
+    pub(crate) fn choose_movement_with_swap(&self, game: &GameY) -> Option<Movement> {
+        let player = game.next_player()?;
+        let coords = match self.evaluate_swap(game) {
+            Some(evaluation) if evaluation.recommendation == SwapDecision::Swap => {
+                return Some(Movement::Action {
+                    player,
+                    action: GameAction::Swap,
+                });
+            }
+            Some(evaluation) => evaluation.keep_move,
+            None => crate::YBot::choose_move(self, game)?,
+        };
+        Some(Movement::Placement { player, coords })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::YBot;
+
+    fn opened_with(coords: Coordinates) -> GameY {
+        let mut game = GameY::new(5);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords,
+        })
+        .unwrap();
+        game
+    }
+
+    #[test]
+    fn test_recommendation_follows_the_scores() {
+        let bot = MinimaxBot::new(1000).with_max_depth(2);
+        let corner = bot
+            .evaluate_swap(&opened_with(Coordinates::new(4, 0, 0)))
+            .unwrap();
+        let centre = bot
+            .evaluate_swap(&opened_with(Coordinates::new(2, 1, 1)))
+            .unwrap();
+        // A corner is a poorer opening than the centre, for both answers
+        assert!(corner.swap_score < centre.swap_score);
+        assert!(corner.keep_score > centre.keep_score);
+
+        for evaluation in [corner, centre] {
+            let expected = if evaluation.swap_score > evaluation.keep_score {
+                SwapDecision::Swap
+            } else {
+                SwapDecision::Keep
+            };
+            assert_eq!(evaluation.recommendation, expected);
+        }
+    }
+
+    #[test]
+    fn test_keep_move_answers_the_opening() {
+        let game = opened_with(Coordinates::new(4, 0, 0));
+        let bot = MinimaxBot::new(1000).with_max_depth(2);
+        let evaluation = bot.evaluate_swap(&game).unwrap();
+        // The best answer is reported even when swapping is better
+        assert!(
+            game.available_cells()
+                .contains(&evaluation.keep_move.to_index(5))
+        );
+        assert_eq!(evaluation.recommendation.to_string(), "swap");
+    }
+
+    #[test]
+    fn test_strong_openings_are_swapped() {
+        let game = opened_with(Coordinates::new(2, 1, 1));
+        let bot = MinimaxBot::new(1000).with_max_depth(2);
+        assert_eq!(
+            bot.choose_movement(&game),
+            Some(Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Swap,
+            })
+        );
+    }
+
+    #[test]
+    fn test_swap_is_only_evaluated_after_the_opening() {
+        let bot = MinimaxBot::new(1000).with_max_depth(2);
+        assert_eq!(bot.evaluate_swap(&GameY::new(5)), None);
+
+        let mut game = opened_with(Coordinates::new(2, 1, 1));
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(4, 0, 0),
+        })
+        .unwrap();
+        assert_eq!(bot.evaluate_swap(&game), None);
+        assert!(matches!(
+            bot.choose_movement(&game),
+            Some(Movement::Placement { .. })
+        ));
+    }
+}
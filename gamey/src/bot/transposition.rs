@@ -0,0 +1,266 @@
+//! Transposition table of the minimax search.
+//!
+//! The table remembers the score of positions already searched, so that a
+//! position reached again, by another move order or in a later search,
+//! is not searched twice. Positions are identified by a Zobrist hash of their
+//! stones, so entries stay valid from one move of a game to the next.
+//!
+//! The search scores forced wins and losses by their distance from its root.
+//! The table keeps their distance from the stored position instead, which
+//! holds however many plies from the root the position is met again.
+
+use crate::WIN_SCORE;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Scores at least this far from zero are forced wins or losses.
+const DECIDED_SCORE: i32 = WIN_SCORE - 100;
+
+/// How the score of an entry relates to the true score of its position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Bound {
+    /// The score is exact.
+    Exact,
+    /// The true score is at least the score.
+    Lower,
+    /// The true score is at most the score.
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TableEntry {
+    key: u64,
+    depth: u8,
+    score: i32,
+    bound: Bound,
+    best_move: Option<u16>,
+    generation: u32,
+}
+
+/// What a probe of the table found for a position.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TableHit {
+    /// The score, if it settles the position for the window searched.
+    pub(crate) score: Option<i32>,
+    /// The best move found when the position was stored.
+    pub(crate) best_move: Option<u16>,
+}
+
+/// Counters of the use of a transposition table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TableStats {
+    /// Number of searches run with the table.
+    pub searches: u64,
+    /// Number of nodes visited by those searches.
+    pub nodes: u64,
+    /// Number of positions looked up.
+    pub probes: u64,
+    /// Number of lookups whose score was used instead of searching.
+    pub hits: u64,
+    /// Number of those hits on entries stored by an earlier search, which
+    /// is the work saved by keeping the table between moves.
+    pub reused_hits: u64,
+}
+
+/// A fixed-size transposition table. Colliding entries are replaced by
+/// entries of the current search or of a greater depth.
+#[derive(Debug, Clone)]
+pub(crate) struct TranspositionTable {
+    entries: Vec<Option<TableEntry>>,
+    generation: u32,
+    stats: TableStats,
+}
+
+impl TranspositionTable {
+    /// Creates a table of about `entries` entries, rounded down to a power of
+    /// two. A table of 0 entries stores nothing.
+    pub(crate) fn new(entries: usize) -> Self {
+        let len = match entries {
+            0 => 0,
+            n => 1 << n.ilog2(),
+        };
+        Self {
+            entries: vec![None; len],
+            generation: 0,
+            stats: TableStats::default(),
+        }
+    }
+
+    /// Forgets every entry and resets the counters.
+    pub(crate) fn clear(&mut self) {
+        self.entries.fill(None);
+        self.generation = 0;
+        self.stats = TableStats::default();
+    }
+
+    /// Marks the start of a new search, whose entries replace older ones.
+    pub(crate) fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        self.stats.searches += 1;
+    }
+
+    /// Adds the nodes visited by a search to the counters.
+    pub(crate) fn add_nodes(&mut self, nodes: u64) {
+        self.stats.nodes += nodes;
+    }
+
+    pub(crate) fn stats(&self) -> TableStats {
+        self.stats
+    }
+
+    /// Looks up the position `key`, met `ply` plies from the root and to be
+    /// searched `depth` plies deep with the window `[alpha, beta]`.
+    pub(crate) fn probe(
+        &mut self,
+        key: u64,
+        depth: u8,
+        ply: u8,
+        alpha: i32,
+        beta: i32,
+    ) -> Option<TableHit> {
+        let slot = self.slot(key)?;
+        self.stats.probes += 1;
+        let entry = slot.filter(|entry| entry.key == key)?;
+        let score = to_root(entry.score, ply);
+        let settled = entry.depth >= depth
+            && match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => score >= beta,
+                Bound::Upper => score <= alpha,
+            };
+        if settled {
+            self.stats.hits += 1;
+            if entry.generation != self.generation {
+                self.stats.reused_hits += 1;
+            }
+        }
+        Some(TableHit {
+            score: settled.then_some(score),
+            best_move: entry.best_move,
+        })
+    }
+
+    /// Stores the result of searching the position `key`, met `ply` plies
+    /// from the root, `depth` plies deep.
+    pub(crate) fn store(
+        &mut self,
+        key: u64,
+        depth: u8,
+        ply: u8,
+        score: i32,
+        bound: Bound,
+        best_move: Option<u16>,
+    ) {
+        let generation = self.generation;
+        let Some(slot) = self.slot_mut(key) else {
+            return;
+        };
+        let replace = slot
+            .is_none_or(|old| old.key == key || old.generation != generation || old.depth <= depth);
+        if replace {
+            *slot = Some(TableEntry {
+                key,
+                depth,
+                score: to_position(score, ply),
+                bound,
+                best_move,
+                generation,
+            });
+        }
+    }
+
+    fn slot(&self, key: u64) -> Option<Option<TableEntry>> {
+        let mask = self.entries.len().checked_sub(1)?;
+        Some(self.entries[key as usize & mask])
+    }
+
+    fn slot_mut(&mut self, key: u64) -> Option<&mut Option<TableEntry>> {
+        let mask = self.entries.len().checked_sub(1)?;
+        Some(&mut self.entries[key as usize & mask])
+    }
+}
+
+/// Turns a score searched from the root into one counted from the position
+/// `ply` plies below it.
+fn to_position(score: i32, ply: u8) -> i32 {
+    match score {
+        s if s >= DECIDED_SCORE => s + i32::from(ply),
+        s if s <= -DECIDED_SCORE => s - i32::from(ply),
+        s => s,
+    }
+}
+
+/// Turns a score stored by [`to_position`] back into one counted from a
+/// root `ply` plies above the position.
+fn to_root(score: i32, ply: u8) -> i32 {
+    match score {
+        s if s >= DECIDED_SCORE => s - i32::from(ply),
+        s if s <= -DECIDED_SCORE => s + i32::from(ply),
+        s => s,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds_settle_only_outside_the_window() {
+        let mut table = TranspositionTable::new(16);
+        table.new_search();
+        table.store(5, 3, 0, 40, Bound::Lower, Some(2));
+        assert_eq!(table.probe(5, 3, 0, 0, 30).unwrap().score, Some(40));
+        let hit = table.probe(5, 3, 0, 0, 50).unwrap();
+        assert_eq!(hit.score, None);
+        assert_eq!(hit.best_move, Some(2));
+        // Shallower entries are not trusted for deeper searches
+        assert_eq!(table.probe(5, 4, 0, 0, 30).unwrap().score, None);
+        assert!(table.probe(6, 1, 0, 0, 30).is_none());
+    }
+
+    #[test]
+    fn test_hits_from_earlier_searches_are_counted() {
+        let mut table = TranspositionTable::new(16);
+        table.new_search();
+        table.store(1, 2, 0, 10, Bound::Exact, None);
+        table.probe(1, 2, 0, 0, 100);
+        table.new_search();
+        table.probe(1, 2, 0, 0, 100);
+        let stats = table.stats();
+        assert_eq!(stats.searches, 2);
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.reused_hits, 1);
+
+        table.clear();
+        assert_eq!(table.stats(), TableStats::default());
+        assert!(table.probe(1, 2, 0, 0, 100).is_none());
+    }
+
+    #[test]
+    fn test_decided_scores_keep_their_distance_from_the_position() {
+        let mut table = TranspositionTable::new(16);
+        table.new_search();
+        // A win 5 plies from the root, stored 2 plies from it, is 3 plies away
+        table.store(1, 4, 2, WIN_SCORE - 5, Bound::Exact, None);
+        table.store(2, 4, 2, -(WIN_SCORE - 5), Bound::Exact, None);
+        assert_eq!(
+            table.probe(1, 4, 4, 0, 1).unwrap().score,
+            Some(WIN_SCORE - 7)
+        );
+        assert_eq!(
+            table.probe(2, 4, 4, 0, 1).unwrap().score,
+            Some(-(WIN_SCORE - 7))
+        );
+        // Other scores are the same at any distance
+        table.store(3, 4, 2, 500, Bound::Exact, None);
+        assert_eq!(table.probe(3, 4, 7, 0, 1).unwrap().score, Some(500));
+    }
+
+    #[test]
+    fn test_empty_table_stores_nothing() {
+        let mut table = TranspositionTable::new(0);
+        table.store(1, 2, 0, 10, Bound::Exact, None);
+        assert!(table.probe(1, 2, 0, 0, 100).is_none());
+    }
+}
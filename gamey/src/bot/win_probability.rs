@@ -0,0 +1,183 @@
+//! Win probabilities from the scores of the minimax search.
+//!
+//! The scores of the search are in the hundreds and their meaning depends on
+//! the evaluation weights and the board size, so they say little to a user.
+//! [`score_to_winprob`] maps a score to the probability that the player it
+//! is for wins the game, by a logistic curve `1 / (1 + e^(-k × score))`
+//! whose scale `k` was fitted for each board size to the results of
+//! self-play games. [`fit_win_probability_scale`] fits it again, for bots
+//! with other weights or limits.
+
+use crate::bot::eval_tuning::{fit_logistic_scale, play_game, sigmoid};
+use crate::bot::swap_decision::balanced_search;
+use crate::{GameY, MinimaxBot, MinimaxState, Result, WIN_SCORE, derive_seed, seeded_rng};
+
+/// Scale of the logistic curve for each board size, fitted with
+/// [`fit_win_probability_scale`] to 100 self-play games of
+/// `MinimaxBot::with_depth(3)` with `size / 2` random opening moves.
+const CALIBRATED_SCALES: [(u32, f64); 9] = [
+    (4, 0.010600),
+    (5, 0.001330),
+    (6, 0.000246),
+    (7, 0.002827),
+    (8, 0.000078),
+    (9, 0.000588),
+    (11, 0.000064),
+    (13, 0.000043),
+    (15, 0.000044),
+];
+
+/// Returns the scale of the logistic curve of [`score_to_winprob`] for
+/// boards of `board_size`.
+///
+/// Sizes between the calibrated ones are interpolated, and sizes outside
+/// them take the scale of the nearest one.
+pub fn win_probability_scale(board_size: u32) -> f64 {
+    let upper = CALIBRATED_SCALES.partition_point(|&(size, _)| size < board_size);
+    match (upper.checked_sub(1), CALIBRATED_SCALES.get(upper)) {
+        (_, Some(&(size, scale))) if size == board_size => scale,
+        (Some(lower), Some(&(high_size, high_scale))) => {
+            let (low_size, low_scale) = CALIBRATED_SCALES[lower];
+            let t = f64::from(board_size - low_size) / f64::from(high_size - low_size);
+            low_scale + t * (high_scale - low_scale)
+        }
+        (None, Some(&(_, scale))) => scale,
+        (Some(lower), None) => CALIBRATED_SCALES[lower].1,
+        (None, None) => unreachable!("no calibrated board size"),
+    }
+}
+
+/// Returns the probability, between 0 and 1, that the player a search
+/// score is for wins a game on a board of `board_size`.
+///
+/// The curve was fitted to scores averaged over the last two iterations of
+/// the search, as in [`MinimaxBot::evaluate_swap`]: the static evaluation
+/// favours the player who moved last, so the score of a single iteration
+/// leans one way or the other with the parity of its depth. Scores of a
+/// forced win or loss give exactly 1 or 0.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{WIN_SCORE, score_to_winprob};
+///
+/// assert_eq!(score_to_winprob(0, 7), 0.5);
+/// assert!(score_to_winprob(200, 7) > 0.5);
+/// assert_eq!(score_to_winprob(WIN_SCORE, 7), 1.0);
+/// ```
+pub fn score_to_winprob(score: i32, board_size: u32) -> f64 {
+    if score >= WIN_SCORE - 100 {
+        1.0
+    } else if score <= -(WIN_SCORE - 100) {
+        0.0
+    } else {
+        sigmoid(win_probability_scale(board_size) * f64::from(score))
+    }
+}
+
+/// Fits the scale of the logistic curve of [`score_to_winprob`] to the
+/// results of `games` games of `bot` against itself on boards of
+/// `board_size`.
+///
+/// The first `random_plies` moves of each game are random so that the
+/// games differ. Every later position is then searched by `bot` for the
+/// player to move, scored by the average of its last two iterations, and
+/// the scale is the one whose curve best predicts from those scores who
+/// won. Positions already won or lost are left out. The games are the same
+/// for the same `seed`.
+///
+/// Returns the calibrated scale of the board size if no position is left.
+pub fn fit_win_probability_scale(
+    bot: &MinimaxBot,
+    board_size: u32,
+    games: usize,
+    random_plies: usize,
+    seed: u64,
+) -> Result<f64> {
+    let limits = bot.limits();
+    let mut samples = Vec::new();
+    for index in 0..games {
+        let mut rng = seeded_rng(derive_seed(seed, index as u64));
+        let game = play_game([bot, bot], board_size, random_plies, &mut rng)?;
+        let Some(result) = game.result() else {
+            continue;
+        };
+        let mut position = GameY::new(board_size);
+        for movement in game.history() {
+            if position.history().len() >= random_plies
+                && let Some(player) = position.next_player()
+            {
+                let mut state = MinimaxState::new(&position, player).with_weights(bot.weights());
+                if let Some((_, score)) = balanced_search(&mut state, &limits)
+                    && score.abs() < WIN_SCORE - 100
+                {
+                    let won = if player == result.winner { 1.0 } else { 0.0 };
+                    samples.push((f64::from(score), won));
+                }
+            }
+            position.replay_move(movement.clone())?;
+        }
+    }
+    if samples.is_empty() {
+        return Ok(win_probability_scale(board_size));
+    }
+    Ok(fit_logistic_scale(|scale| {
+        samples
+            .iter()
+            .map(|&(score, won)| (won - sigmoid(scale * score)).powi(2))
+            .sum::<f64>()
+            / samples.len() as f64
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LOSE_SCORE;
+
+    #[test]
+    fn test_win_probability_is_symmetric_and_increasing() {
+        for size in [4, 7, 10, 20] {
+            assert_eq!(score_to_winprob(0, size), 0.5);
+            let mut previous = 0.0;
+            for score in [-500, -100, -10, 10, 100, 500] {
+                let probability = score_to_winprob(score, size);
+                assert!(probability > previous, "size {}: {}", size, score);
+                let mirrored = score_to_winprob(-score, size);
+                assert!((probability + mirrored - 1.0).abs() < 1e-12);
+                previous = probability;
+            }
+            assert_eq!(score_to_winprob(WIN_SCORE, size), 1.0);
+            assert_eq!(score_to_winprob(LOSE_SCORE, size), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_scales_are_interpolated_between_calibrated_sizes() {
+        let (first, first_scale) = CALIBRATED_SCALES[0];
+        let (last, last_scale) = CALIBRATED_SCALES[CALIBRATED_SCALES.len() - 1];
+        assert_eq!(win_probability_scale(first - 1), first_scale);
+        assert_eq!(win_probability_scale(last + 5), last_scale);
+        for pair in CALIBRATED_SCALES.windows(2) {
+            let [(low, low_scale), (high, high_scale)] = [pair[0], pair[1]];
+            assert_eq!(win_probability_scale(low), low_scale);
+            if high > low + 1 {
+                let scale = win_probability_scale(low + 1);
+                assert!(scale > low_scale.min(high_scale) && scale < low_scale.max(high_scale));
+            }
+        }
+    }
+
+    #[test]
+    fn test_fit_predicts_the_self_play_results() {
+        let bot = MinimaxBot::with_depth(3);
+        let scale = fit_win_probability_scale(&bot, 4, 30, 2, 3).unwrap();
+        assert!(scale > 0.0 && scale.is_finite());
+        assert_eq!(fit_win_probability_scale(&bot, 4, 30, 2, 3).unwrap(), scale);
+        // No game leaves a position once the board is filled at random
+        assert_eq!(
+            fit_win_probability_scale(&bot, 4, 2, 10, 3).unwrap(),
+            win_probability_scale(4)
+        );
+    }
+}
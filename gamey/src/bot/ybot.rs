@@ -1,12 +1,107 @@
-use crate::{Coordinates, GameY};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{Coordinates, GameY, Movement, PlacementGame, PlayerId};
 
 /// Trait representing a Y game bot (YBot)
 /// A YBot is an AI that can choose moves in the game of Y.
 /// Implementors of this trait must provide a name and a method to choose a move given the current game state.
+///
+/// A server may share one bot between all its games and ask it for moves
+/// from several threads at once. Everything a bot needs about a game comes
+/// from the board it is given; any state a bot keeps between calls must sit
+/// behind a lock and must not make one game's moves wrong for another.
 pub trait YBot: Send + Sync {
     /// Returns the name of the bot.
     fn name(&self) -> &str;
 
     /// Chooses a move based on the current game state.
     fn choose_move(&self, board: &GameY) -> Option<Coordinates>;
+
+    /// Chooses a move, spending at most about `max_time_ms` milliseconds.
+    ///
+    /// Bots whose thinking time is not configurable keep the default, which
+    /// ignores the limit and calls [`YBot::choose_move`].
+    fn choose_move_within(&self, board: &GameY, _max_time_ms: u64) -> Option<Coordinates> {
+        self.choose_move(board)
+    }
+
+    /// Chooses a move in each of `games`, spending at most about
+    /// `max_time_ms` milliseconds on each, and returns the moves in order.
+    /// A move is None where the game is over or the bot found no move.
+    ///
+    /// The games are shared out between one thread per available core, each
+    /// taking the next game as soon as it is done with the previous one, so
+    /// that a server playing many games at once needs neither a thread nor
+    /// a lock per game. Bots such as the minimax bot build the caches of a
+    /// board size once and share them between all the games of that size.
+    fn choose_moves(&self, games: &[&GameY], max_time_ms: u64) -> Vec<Option<Coordinates>> {
+        let threads = std::thread::available_parallelism()
+            .map_or(1, NonZeroUsize::get)
+            .min(games.len());
+        if threads <= 1 {
+            return games
+                .iter()
+                .map(|game| self.choose_move_within(game, max_time_ms))
+                .collect();
+        }
+        let next = AtomicUsize::new(0);
+        let mut moves = vec![None; games.len()];
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut chosen = Vec::new();
+                        loop {
+                            let idx = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(game) = games.get(idx) else {
+                                return chosen;
+                            };
+                            chosen.push((idx, self.choose_move_within(game, max_time_ms)));
+                        }
+                    })
+                })
+                .collect();
+            for worker in workers {
+                for (idx, coords) in worker.join().expect("move worker panicked") {
+                    moves[idx] = coords;
+                }
+            }
+        });
+        moves
+    }
+
+    /// Chooses the next movement: a stone to place or, for bots that play
+    /// the swap rule, an action such as a swap.
+    ///
+    /// Bots that only place stones keep the default, which places the stone
+    /// chosen by [`YBot::choose_move`].
+    fn choose_movement(&self, board: &GameY) -> Option<Movement> {
+        let player = board.next_player()?;
+        let coords = self.choose_move(board)?;
+        Some(Movement::Placement { player, coords })
+    }
+
+    /// Restarts the bot's random choices from `seed`, so that its games can
+    /// be replayed exactly.
+    ///
+    /// Bots that make no random choices keep the default, which does
+    /// nothing.
+    fn reseed(&self, _seed: u64) {}
+
+    /// Tells the bot that `game`, which it played as `player`, is over, so
+    /// that it can learn from the result.
+    ///
+    /// Bots that do not learn between games keep the default, which does
+    /// nothing.
+    fn learn_from_game(&self, _game: &GameY, _player: PlayerId) {}
+
+    /// Chooses the index of a cell to play in any [`PlacementGame`], such as
+    /// Havannah.
+    ///
+    /// Bots that only understand the game of Y keep the default, which
+    /// returns None.
+    fn choose_cell(&self, _game: &dyn PlacementGame) -> Option<u32> {
+        None
+    }
 }
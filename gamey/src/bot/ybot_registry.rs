@@ -19,7 +19,7 @@ use crate::YBot;
 /// use gamey::{YBotRegistry, RandomBot};
 ///
 /// let registry = YBotRegistry::new()
-///     .with_bot(Arc::new(RandomBot));
+///     .with_bot(Arc::new(RandomBot::new()));
 ///
 /// let bot = registry.find("random_bot");
 /// assert!(bot.is_some());
@@ -130,7 +130,7 @@ mod tests {
 
     #[test]
     fn test_with_random_bot() {
-        let registry = YBotRegistry::new().with_bot(Arc::new(RandomBot));
+        let registry = YBotRegistry::new().with_bot(Arc::new(RandomBot::new()));
 
         assert!(registry.find("random_bot").is_some());
     }
@@ -79,7 +79,7 @@ pub fn run_cli_game() -> Result<()> {
     let mut render_options = crate::RenderOptions::default();
     let mut rl = DefaultEditor::new()?;
     let bots_registry = YBotRegistry::new()
-        .with_bot(Arc::new(RandomBot))
+        .with_bot(Arc::new(RandomBot::new()))
         .with_bot(Arc::new(MinimaxBot::new(args.maxms)));
     let bot: Arc<dyn YBot> = match bots_registry.find(&args.bot) {
         Some(b) => b,
@@ -1,22 +1,32 @@
-use std::fmt::Display;
+use core::fmt::Display;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Represents special game actions that are not regular piece placements.
 ///
 /// These actions allow players to perform non-placement moves during the game.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum GameAction {
     /// The swap rule: allows the second player to swap colors after the first move.
     /// This is commonly used in games like Hex and Y to balance first-move advantage.
     Swap,
     /// The player resigns the game, conceding victory to the opponent.
     Resign,
+    /// The player ran out of time and loses the game.
+    Timeout,
+    /// The player abandoned the game and loses it.
+    Abandon,
 }
 
 impl Display for GameAction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             GameAction::Swap => write!(f, "Swap"),
             GameAction::Resign => write!(f, "Resign"),
+            GameAction::Timeout => write!(f, "Timeout"),
+            GameAction::Abandon => write!(f, "Abandon"),
         }
     }
 }
@@ -35,6 +45,12 @@ mod tests {
         assert_eq!(format!("{}", GameAction::Resign), "Resign");
     }
 
+    #[test]
+    fn test_display_forfeits() {
+        assert_eq!(format!("{}", GameAction::Timeout), "Timeout");
+        assert_eq!(format!("{}", GameAction::Abandon), "Abandon");
+    }
+
     #[test]
     fn test_equality() {
         assert_eq!(GameAction::Swap, GameAction::Swap);
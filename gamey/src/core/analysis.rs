@@ -0,0 +1,454 @@
+//! Static analysis of game positions.
+//!
+//! These queries inspect a [`GameY`] without modifying it. They are meant for
+//! analysis tools (e.g. greying out cells in a GUI) and for bots that want to
+//! prune their search.
+
+use crate::{Coordinates, GameOverReason, GameY, PlayerId, Side, other_player};
+use alloc::{
+    collections::{BTreeMap, VecDeque},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Factor by which the influence of a stone shrinks with each step of distance.
+#[cfg(feature = "std")]
+const INFLUENCE_DECAY: f32 = 0.5;
+
+/// A group of connected stones and the sides of the board it touches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GroupProgress {
+    /// The stones of the group, in board index order.
+    pub stones: Vec<Coordinates>,
+    /// The sides the group touches, in the order of [`Side::ALL`].
+    pub sides: Vec<Side>,
+}
+
+/// How far the groups of a player are from connecting the three sides, as
+/// returned by [`GameY::connection_progress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConnectionProgress {
+    /// The groups of the player, those touching the most sides first, then
+    /// in the board index order of their first stone.
+    pub groups: Vec<GroupProgress>,
+}
+
+impl ConnectionProgress {
+    /// Returns the most sides touched by a single group: 3 once the player
+    /// has connected, and 0 without stones.
+    pub fn best_sides(&self) -> usize {
+        self.groups.first().map_or(0, |group| group.sides.len())
+    }
+}
+
+impl GameY {
+    /// Returns a per-cell influence score, indexed by board index.
+    ///
+    /// Scores range from -1.0 to 1.0: positive values mean the cell is
+    /// controlled by player 0, negative values by player 1, and values near
+    /// zero mean it is contested or out of reach. Occupied cells score ±1.0.
+    /// For an empty cell, every stone contributes `0.5^distance` to its owner
+    /// and the difference between both players is squashed with `tanh`.
+    #[cfg(feature = "std")]
+    pub fn influence_map(&self) -> Vec<f32> {
+        let board_size = self.board_size();
+        let stones: Vec<(Coordinates, f32)> = self
+            .stones()
+            .map(|(coords, player)| {
+                let sign = if player.id() == 0 { 1.0 } else { -1.0 };
+                (coords, sign)
+            })
+            .collect();
+
+        (0..self.total_cells())
+            .map(|idx| {
+                let cell = Coordinates::from_index(idx, board_size);
+                if let Some(player) = self.cell_owner(&cell) {
+                    return if player.id() == 0 { 1.0 } else { -1.0 };
+                }
+                let balance: f32 = stones
+                    .iter()
+                    .map(|(stone, sign)| sign * INFLUENCE_DECAY.powi(cell.distance(stone) as i32))
+                    .sum();
+                balance.tanh()
+            })
+            .collect()
+    }
+
+    /// Returns the empty cells that provably cannot influence the result.
+    ///
+    /// An empty cell is dead when all of its neighbours are stones of the same
+    /// player. The neighbours of a cell are adjacent to each other in a ring,
+    /// so they already form a single group that touches every side the cell
+    /// touches: a stone of that player would add nothing, and an opponent stone
+    /// would be isolated. A blocked neighbour breaks the ring, so cells next
+    /// to blocked cells are never dead. Once the game is over every empty cell
+    /// is dead.
+    ///
+    /// Cells are returned in board index order.
+    pub fn dead_cells(&self) -> Vec<Coordinates> {
+        let board_size = self.board_size();
+        self.available_cells()
+            .iter()
+            .map(|&idx| Coordinates::from_index(idx, board_size))
+            .filter(|coords| self.check_game_over() || self.is_enclosed_by_one_player(coords))
+            .collect()
+    }
+
+    /// Returns the groups of `player` with the sides each of them touches,
+    /// as tracked by the win detection, for indicators such as "2 of 3
+    /// sides connected".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gamey::{GameY, PlayerId, Side};
+    ///
+    /// let game = GameY::from_position_string("1/B1/R1B R").unwrap();
+    /// let progress = game.connection_progress(PlayerId::new(0));
+    /// assert_eq!(progress.groups.len(), 2);
+    /// assert_eq!(progress.best_sides(), 2);
+    /// assert_eq!(progress.groups[0].sides, vec![Side::A, Side::C]);
+    /// ```
+    pub fn connection_progress(&self, player: PlayerId) -> ConnectionProgress {
+        let board_size = self.board_size();
+        let mut groups: Vec<GroupProgress> = Vec::new();
+        let mut group_of_root = BTreeMap::new();
+        for idx in 0..self.total_cells() {
+            let coords = Coordinates::from_index(idx, board_size);
+            let Some((owner, root, mask)) = self.group_of(&coords) else {
+                continue;
+            };
+            if owner != player {
+                continue;
+            }
+            let group = *group_of_root.entry(root).or_insert_with(|| {
+                let sides = Side::ALL
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(bit, _)| mask & (1 << bit) != 0)
+                    .map(|(_, side)| side)
+                    .collect();
+                groups.push(GroupProgress {
+                    stones: Vec::new(),
+                    sides,
+                });
+                groups.len() - 1
+            });
+            groups[group].stones.push(coords);
+        }
+        // Stable, so groups touching as many sides keep the board order
+        groups.sort_by_key(|group| core::cmp::Reverse(group.sides.len()));
+        ConnectionProgress { groups }
+    }
+
+    /// Returns the fewest stones `player` still has to place to connect the
+    /// three sides, if the opponent never answered.
+    ///
+    /// Stones of `player` cost nothing, and neither stones of the opponent nor
+    /// blocked cells can be crossed. The cheapest connection joins the three sides through a
+    /// single meeting cell, so one shortest-path search from each side finds
+    /// it exactly. Returns `Some(0)` once `player` has connected, and None if
+    /// the opponent has cut off every connection.
+    pub fn stones_to_connect(&self, player: PlayerId) -> Option<u32> {
+        let board_size = self.board_size();
+        let costs: Vec<Option<u32>> = (0..self.total_cells())
+            .map(|idx| {
+                let coords = Coordinates::from_index(idx, board_size);
+                match self.cell_owner(&coords) {
+                    Some(owner) if owner == player => Some(0),
+                    Some(_) => None,
+                    None if self.is_blocked(&coords) => None,
+                    None => Some(1),
+                }
+            })
+            .collect();
+        let [a, b, c] = [Side::A, Side::B, Side::C].map(|side| self.side_distances(&costs, side));
+        // The meeting cell is counted by each of the three paths
+        (0..costs.len())
+            .filter_map(|idx| Some(a[idx]? + b[idx]? + c[idx]? - 2 * costs[idx]?))
+            .min()
+    }
+
+    /// Returns how many stones the loser still needed to connect when the
+    /// game ended, or None while the game is ongoing.
+    ///
+    /// A connection cuts the loser off for good, so a game won by connection
+    /// is measured just before the winning stone: a closeness of 1 means the
+    /// loser was one move away from winning themselves. Games lost by
+    /// resignation or on time are measured as they were left, and give None
+    /// if the loser had already been cut off.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gamey::{GameY, PlayerId};
+    ///
+    /// let mut game = GameY::from_position_string("1/BB/RR1 R").unwrap();
+    /// assert_eq!(game.closeness(), None);
+    /// game.resign(PlayerId::new(1)).unwrap();
+    /// // Red only needed the last cell of the bottom row
+    /// assert_eq!(game.closeness(), Some(1));
+    /// ```
+    pub fn closeness(&self) -> Option<u32> {
+        let result = self.result()?;
+        let loser = other_player(result.winner);
+        if result.reason == GameOverReason::Connection {
+            let mut before = self.clone();
+            before.undo_move().ok()?;
+            return before.stones_to_connect(loser);
+        }
+        self.stones_to_connect(loser)
+    }
+
+    /// Returns, for every cell, the fewest stones on a path from `side` to
+    /// the cell, the cell included, or None if the cell cannot be reached.
+    ///
+    /// `costs` holds the cost of placing a stone on each cell, by board
+    /// index, and None for cells that cannot be crossed.
+    fn side_distances(&self, costs: &[Option<u32>], side: Side) -> Vec<Option<u32>> {
+        let board_size = self.board_size();
+        let mut distances = vec![None; costs.len()];
+        // A 0-1 breadth-first search: free cells go to the front of the queue
+        let mut queue = VecDeque::new();
+        for (idx, cost) in costs.iter().enumerate() {
+            let coords = Coordinates::from_index(idx as u32, board_size);
+            if let Some(cost) = cost
+                && coords.touches_side(side)
+            {
+                distances[idx] = Some(*cost);
+                if *cost == 0 {
+                    queue.push_front((idx, *cost));
+                } else {
+                    queue.push_back((idx, *cost));
+                }
+            }
+        }
+        while let Some((idx, distance)) = queue.pop_front() {
+            if distances[idx] != Some(distance) {
+                // Reached more cheaply since it was queued
+                continue;
+            }
+            let coords = Coordinates::from_index(idx as u32, board_size);
+            for neighbor in self.get_neighbors(&coords) {
+                let next = neighbor.to_index(board_size) as usize;
+                let Some(cost) = costs[next] else {
+                    continue;
+                };
+                let reached = distance + cost;
+                if distances[next].is_none_or(|known| reached < known) {
+                    distances[next] = Some(reached);
+                    if cost == 0 {
+                        queue.push_front((next, reached));
+                    } else {
+                        queue.push_back((next, reached));
+                    }
+                }
+            }
+        }
+        distances
+    }
+
+    /// Returns true if every neighbour of `coords` is a stone of the same
+    /// player, and none of them is blocked.
+    fn is_enclosed_by_one_player(&self, coords: &Coordinates) -> bool {
+        let board_size = self.board_size();
+        if self
+            .blocked_cells()
+            .iter()
+            .any(|&idx| Coordinates::from_index(idx, board_size).distance(coords) == 1)
+        {
+            return false;
+        }
+        let mut owners = self
+            .get_neighbors(coords)
+            .into_iter()
+            .map(|neighbor| self.cell_owner(&neighbor));
+        match owners.next() {
+            Some(Some(first)) => owners.all(|owner| owner == Some(first)),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlayerId;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_influence_map_empty_board_is_neutral() {
+        let map = GameY::new(4).influence_map();
+        assert_eq!(map.len(), 10);
+        assert!(map.iter().all(|&v| v == 0.0));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_influence_map_signs_and_decay() {
+        // B at the top corner, R at the bottom-right corner of a size 5 board
+        let game = GameY::from_position_string("B/2/3/4/4R B").unwrap();
+        let map = game.influence_map();
+        let at = |x, y, z| map[Coordinates::new(x, y, z).to_index(5) as usize];
+
+        assert_eq!(at(4, 0, 0), 1.0);
+        assert_eq!(at(0, 4, 0), -1.0);
+        assert!(at(3, 0, 1) > at(2, 0, 2));
+        assert!(at(2, 0, 2) > 0.0);
+        assert!(at(0, 3, 1) < 0.0);
+        // Cells equidistant from both stones are contested
+        assert!(at(2, 2, 0).abs() < 1e-6);
+        assert!(map.iter().all(|v| (-1.0..=1.0).contains(v)));
+    }
+
+    #[test]
+    fn test_stones_to_connect_on_empty_board() {
+        for size in 1..=6 {
+            let game = GameY::new(size);
+            // A whole side is the shortest connection
+            assert_eq!(game.stones_to_connect(PlayerId::new(0)), Some(size));
+            assert_eq!(game.stones_to_connect(PlayerId::new(1)), Some(size));
+        }
+    }
+
+    #[test]
+    fn test_stones_to_connect_counts_own_stones_and_avoids_opponent() {
+        let game = GameY::from_position_string("1/BB/RR1 B").unwrap();
+        assert_eq!(game.stones_to_connect(PlayerId::new(0)), Some(1));
+        assert_eq!(game.stones_to_connect(PlayerId::new(1)), Some(1));
+        // Blue's wall cuts Red off from the top corner
+        let game = GameY::from_position_string("1/BB/B2/4 R").unwrap();
+        assert_eq!(game.stones_to_connect(PlayerId::new(1)), Some(4));
+    }
+
+    #[test]
+    fn test_stones_to_connect_does_not_cross_blocked_cells() {
+        // The blocked row cuts the stone in the top corner off, so the whole
+        // bottom row is needed instead of one stone in each row below it
+        let game = GameY::from_position_string("B/##/3 R").unwrap();
+        assert_eq!(game.stones_to_connect(PlayerId::new(0)), Some(3));
+    }
+
+    #[test]
+    fn test_closeness_of_a_connection_is_measured_before_the_winning_stone() {
+        let mut game = GameY::from_position_string("1/BB/RR1 B").unwrap();
+        assert_eq!(game.closeness(), None);
+        game.add_move(crate::Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(0, 2, 0),
+        })
+        .unwrap();
+        assert_eq!(game.stones_to_connect(PlayerId::new(0)), Some(0));
+        assert_eq!(game.stones_to_connect(PlayerId::new(1)), None);
+        assert_eq!(game.closeness(), Some(1));
+    }
+
+    #[test]
+    fn test_closeness_of_a_resignation() {
+        let mut game = GameY::new(4);
+        game.resign(PlayerId::new(0)).unwrap();
+        assert_eq!(game.closeness(), Some(4));
+    }
+
+    #[test]
+    fn test_connection_progress_of_groups() {
+        let game = GameY::from_position_string("1/R1/1BR/BB2 R").unwrap();
+        let blue = game.connection_progress(PlayerId::new(0));
+        assert_eq!(blue.best_sides(), 2);
+        assert_eq!(blue.groups.len(), 1);
+        assert_eq!(
+            blue.groups[0].stones,
+            vec![
+                Coordinates::new(1, 1, 1),
+                Coordinates::new(0, 0, 3),
+                Coordinates::new(0, 1, 2)
+            ]
+        );
+        assert_eq!(blue.groups[0].sides, vec![Side::A, Side::B]);
+
+        let red = game.connection_progress(PlayerId::new(1));
+        assert_eq!(red.groups.len(), 2);
+        assert_eq!(red.best_sides(), 1);
+        assert_eq!(red.groups[0].sides, vec![Side::B]);
+        assert_eq!(red.groups[1].sides, vec![Side::C]);
+
+        let empty = GameY::new(3).connection_progress(PlayerId::new(0));
+        assert!(empty.groups.is_empty());
+        assert_eq!(empty.best_sides(), 0);
+    }
+
+    #[test]
+    fn test_connection_progress_after_a_win() {
+        let game = GameY::from_position_string("B/BR/B1R R").unwrap();
+        assert!(game.check_game_over());
+        let progress = game.connection_progress(PlayerId::new(0));
+        assert_eq!(progress.best_sides(), 3);
+        assert_eq!(progress.groups[0].stones.len(), 3);
+    }
+
+    #[test]
+    fn test_no_dead_cells_on_empty_board() {
+        assert!(GameY::new(5).dead_cells().is_empty());
+    }
+
+    #[test]
+    fn test_enclosed_interior_cell_is_dead() {
+        // The interior cell (2, 1, 1) is surrounded by a ring of B stones,
+        // which also encloses the top corner
+        let game = GameY::from_position_string("1/BB/B1B/1BB1/5 R").unwrap();
+        assert_eq!(
+            game.dead_cells(),
+            vec![Coordinates::new(4, 0, 0), Coordinates::new(2, 1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_enclosed_corner_is_dead() {
+        let game = GameY::from_position_string("1/BB/3 R").unwrap();
+        assert_eq!(game.dead_cells(), vec![Coordinates::new(2, 0, 0)]);
+    }
+
+    #[test]
+    fn test_partially_enclosed_cell_is_not_dead() {
+        let game = GameY::from_position_string("1/B1/3 R").unwrap();
+        assert!(game.dead_cells().is_empty());
+    }
+
+    #[test]
+    fn test_cell_with_mixed_neighbours_is_not_dead() {
+        let game = GameY::from_position_string("1/BR/3 B").unwrap();
+        assert!(game.dead_cells().is_empty());
+    }
+
+    #[test]
+    fn test_cells_next_to_blocked_cells_are_not_dead() {
+        // Without the blocked cells, (2, 1, 1) would join two blue groups
+        // and the top corner would reach side B
+        let game = crate::GameYBuilder::new(5)
+            .with_blocked_cells([Coordinates::new(1, 2, 1), Coordinates::new(3, 0, 1)])
+            .with_stones(
+                PlayerId::new(0),
+                [
+                    Coordinates::new(1, 1, 2),
+                    Coordinates::new(2, 0, 2),
+                    Coordinates::new(3, 1, 0),
+                    Coordinates::new(2, 2, 0),
+                ],
+            )
+            .build()
+            .unwrap();
+        assert!(game.dead_cells().is_empty());
+    }
+
+    #[test]
+    fn test_all_empty_cells_dead_when_game_over() {
+        let mut game = GameY::new(3);
+        game.resign(PlayerId::new(0)).unwrap();
+        assert_eq!(game.dead_cells().len(), 6);
+    }
+}
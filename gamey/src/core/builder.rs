@@ -0,0 +1,274 @@
+use crate::{Coordinates, GameStatus, GameY, GameYError, Movement, PlayerId, game};
+use alloc::vec::Vec;
+
+/// Builder for games that start from an arbitrary position.
+///
+/// Allows placing stones before the game starts, choosing which player moves
+/// first, giving a player handicap stones and blocking cells to play on custom
+/// boards. [`GameYBuilder::build`] validates that the stones and blocked cells
+/// are on the board, that stones do not overlap each other or a blocked cell
+/// and, unless explicitly allowed, do not already decide the game.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameYBuilder, PlayerId};
+///
+/// let game = GameYBuilder::new(5)
+///     .with_stone(PlayerId::new(0), Coordinates::new(2, 1, 1))
+///     .with_stone(PlayerId::new(1), Coordinates::new(4, 0, 0))
+///     .with_starting_player(PlayerId::new(1))
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(game.next_player(), Some(PlayerId::new(1)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameYBuilder {
+    board_size: u32,
+    stones: Vec<(PlayerId, Coordinates)>,
+    blocked: Vec<Coordinates>,
+    starting_player: Option<PlayerId>,
+    handicap_player: Option<PlayerId>,
+    allow_finished: bool,
+}
+
+impl GameYBuilder {
+    /// Creates a builder for an empty board of the given size.
+    pub fn new(board_size: u32) -> Self {
+        Self {
+            board_size,
+            stones: Vec::new(),
+            blocked: Vec::new(),
+            starting_player: None,
+            handicap_player: None,
+            allow_finished: false,
+        }
+    }
+
+    /// Places a stone of `player` at `coords` before the game starts.
+    pub fn with_stone(mut self, player: PlayerId, coords: Coordinates) -> Self {
+        self.stones.push((player, coords));
+        self
+    }
+
+    /// Places several stones of `player` before the game starts.
+    pub fn with_stones<I>(mut self, player: PlayerId, coords: I) -> Self
+    where
+        I: IntoIterator<Item = Coordinates>,
+    {
+        self.stones.extend(coords.into_iter().map(|c| (player, c)));
+        self
+    }
+
+    /// Gives `player` handicap stones at the given coordinates.
+    ///
+    /// Unless a starting player is set explicitly, the opponent of the
+    /// handicapped player moves first.
+    pub fn with_handicap<I>(mut self, player: PlayerId, coords: I) -> Self
+    where
+        I: IntoIterator<Item = Coordinates>,
+    {
+        self.handicap_player = Some(player);
+        self.with_stones(player, coords)
+    }
+
+    /// Blocks the cell at `coords`, leaving a hole in the board.
+    ///
+    /// No stone may be placed on a blocked cell, and groups do not connect
+    /// through it.
+    pub fn with_blocked_cell(mut self, coords: Coordinates) -> Self {
+        self.blocked.push(coords);
+        self
+    }
+
+    /// Blocks several cells, for custom boards and puzzles.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gamey::{Coordinates, GameYBuilder};
+    ///
+    /// let game = GameYBuilder::new(4)
+    ///     .with_blocked_cells([Coordinates::new(1, 1, 1), Coordinates::new(0, 3, 0)])
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(game.is_blocked(&Coordinates::new(1, 1, 1)));
+    /// assert_eq!(game.available_cells().len(), 8);
+    /// ```
+    pub fn with_blocked_cells<I>(mut self, coords: I) -> Self
+    where
+        I: IntoIterator<Item = Coordinates>,
+    {
+        self.blocked.extend(coords);
+        self
+    }
+
+    /// Sets the player who makes the first move after the preset stones.
+    pub fn with_starting_player(mut self, player: PlayerId) -> Self {
+        self.starting_player = Some(player);
+        self
+    }
+
+    /// Allows the preset stones to form a position that is already won.
+    pub fn allow_finished(mut self, allow: bool) -> Self {
+        self.allow_finished = allow;
+        self
+    }
+
+    /// Builds the game, validating the preset position.
+    pub fn build(self) -> game::Result<GameY> {
+        let mut game = GameY::new(self.board_size);
+        if let Some(coords) = self.blocked.iter().find(|c| !c.is_valid(self.board_size)) {
+            return Err(GameYError::InvalidCoordinates {
+                coordinates: *coords,
+                board_size: self.board_size,
+            });
+        }
+        if !self.blocked.is_empty() {
+            let blocked = self.blocked.iter().map(|c| c.to_index(self.board_size));
+            game.set_blocked_cells(blocked.collect());
+        }
+        for (player, coords) in self.stones {
+            game.replay_move(Movement::Placement { player, coords })?;
+        }
+
+        if let GameStatus::Finished { winner } = game.status()
+            && !self.allow_finished
+        {
+            return Err(GameYError::PresetPositionFinished { winner: *winner });
+        }
+
+        let starting_player = self
+            .starting_player
+            .or(self.handicap_player.map(game::other_player))
+            .unwrap_or(PlayerId::new(0));
+        game.finish_setup(starting_player);
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_builder_matches_new_game() {
+        let game = GameYBuilder::new(4).build().unwrap();
+        assert_eq!(game.board_size(), 4);
+        assert_eq!(game.available_cells().len(), 10);
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+    }
+
+    #[test]
+    fn test_preset_stones_are_placed() {
+        let game = GameYBuilder::new(4)
+            .with_stone(PlayerId::new(1), Coordinates::new(1, 1, 1))
+            .with_stones(
+                PlayerId::new(0),
+                [Coordinates::new(3, 0, 0), Coordinates::new(0, 3, 0)],
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            game.cell_owner(&Coordinates::new(1, 1, 1)),
+            Some(PlayerId::new(1))
+        );
+        assert_eq!(
+            game.cell_owner(&Coordinates::new(0, 3, 0)),
+            Some(PlayerId::new(0))
+        );
+        assert_eq!(game.available_cells().len(), 7);
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+    }
+
+    #[test]
+    fn test_handicap_gives_first_move_to_opponent() {
+        let game = GameYBuilder::new(5)
+            .with_handicap(PlayerId::new(0), [Coordinates::new(2, 1, 1)])
+            .build()
+            .unwrap();
+        assert_eq!(game.next_player(), Some(PlayerId::new(1)));
+    }
+
+    #[test]
+    fn test_explicit_starting_player_wins_over_handicap() {
+        let game = GameYBuilder::new(5)
+            .with_handicap(PlayerId::new(0), [Coordinates::new(2, 1, 1)])
+            .with_starting_player(PlayerId::new(0))
+            .build()
+            .unwrap();
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+    }
+
+    #[test]
+    fn test_overlapping_stones_are_rejected() {
+        let result = GameYBuilder::new(3)
+            .with_stone(PlayerId::new(0), Coordinates::new(1, 1, 0))
+            .with_stone(PlayerId::new(1), Coordinates::new(1, 1, 0))
+            .build();
+        assert!(matches!(result, Err(GameYError::Occupied { .. })));
+    }
+
+    #[test]
+    fn test_off_board_stone_is_rejected() {
+        let result = GameYBuilder::new(3)
+            .with_stone(PlayerId::new(0), Coordinates::new(3, 0, 0))
+            .build();
+        assert!(matches!(
+            result,
+            Err(GameYError::InvalidCoordinates { board_size: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_blocked_cells_take_no_stone() {
+        let hole = Coordinates::new(1, 1, 1);
+        let game = GameYBuilder::new(4)
+            .with_blocked_cell(hole)
+            .build()
+            .unwrap();
+        assert_eq!(game.blocked_cells(), &[hole.to_index(4)]);
+        assert!(!game.available_cells().contains(&hole.to_index(4)));
+        assert!(
+            !game
+                .get_neighbors(&Coordinates::new(2, 1, 0))
+                .contains(&hole)
+        );
+
+        let result = GameYBuilder::new(4)
+            .with_blocked_cell(hole)
+            .with_stone(PlayerId::new(0), hole)
+            .build();
+        assert!(matches!(result, Err(GameYError::BlockedCell { .. })));
+        let result = GameYBuilder::new(3)
+            .with_blocked_cell(Coordinates::new(3, 0, 0))
+            .build();
+        assert!(matches!(
+            result,
+            Err(GameYError::InvalidCoordinates { board_size: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_finished_position_requires_opt_in() {
+        let builder = GameYBuilder::new(2).with_stones(
+            PlayerId::new(1),
+            [
+                Coordinates::new(1, 0, 0),
+                Coordinates::new(0, 1, 0),
+                Coordinates::new(0, 0, 1),
+            ],
+        );
+
+        let result = builder.clone().build();
+        assert!(matches!(
+            result,
+            Err(GameYError::PresetPositionFinished { winner }) if winner == PlayerId::new(1)
+        ));
+
+        let game = builder.allow_finished(true).build().unwrap();
+        assert!(game.check_game_over());
+    }
+}
@@ -1,4 +1,5 @@
-use std::fmt::Display;
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
@@ -42,7 +43,7 @@ impl Coordinates {
 
     /// Returns true if the coordinates are valid for the given board size.
     pub fn is_valid(&self, board_size: u32) -> bool {
-        self.x + self.y + self.z == board_size - 1
+        self.x + self.y + self.z + 1 == board_size
             && self.x < board_size
             && self.y < board_size
             && self.z < board_size
@@ -55,8 +56,7 @@ impl Coordinates {
     pub fn from_index(index: u32, board_size: u32) -> Self {
         // As i = (r * (r + 1)) / 2
         // r = floor((sqrt(8*i + 1) - 1) / 2)
-        let i_f = index as f64;
-        let r = (((8.0 * i_f + 1.0).sqrt() - 1.0) / 2.0).floor() as u32;
+        let r = (((8 * index as u64 + 1).isqrt() - 1) / 2) as u32;
 
         let row_start_index = (r * (r + 1)) / 2;
         let c = index - row_start_index;
@@ -106,6 +106,149 @@ impl Coordinates {
     pub fn touches_side_c(&self) -> bool {
         self.z == 0
     }
+
+    /// Returns true if this cell touches the given side.
+    pub fn touches_side(&self, side: Side) -> bool {
+        match side {
+            Side::A => self.touches_side_a(),
+            Side::B => self.touches_side_b(),
+            Side::C => self.touches_side_c(),
+        }
+    }
+
+    /// Returns the number of steps between two cells of the same board.
+    pub fn distance(&self, other: &Coordinates) -> u32 {
+        (self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)) / 2
+    }
+
+    /// Returns the number of steps from this cell to the nearest cell of `side`.
+    pub fn distance_to_side(&self, side: Side) -> u32 {
+        match side {
+            Side::A => self.x,
+            Side::B => self.y,
+            Side::C => self.z,
+        }
+    }
+
+    /// Returns the cells of the same board at most `radius` steps away,
+    /// including this one, in board index order.
+    pub fn within_radius(&self, radius: u32) -> Vec<Coordinates> {
+        let max = self.x + self.y + self.z;
+        let mut cells = Vec::new();
+        for x in (self.x.saturating_sub(radius)..=(self.x + radius).min(max)).rev() {
+            for y in self.y.saturating_sub(radius)..=(self.y + radius).min(max - x) {
+                let cell = Coordinates::new(x, y, max - x - y);
+                if cell.distance(self) <= radius {
+                    cells.push(cell);
+                }
+            }
+        }
+        cells
+    }
+
+    /// Returns the shortest straight line of cells from this one to `side`.
+    ///
+    /// The line starts with this cell and ends on the side. Each step moves
+    /// one row closer to the side, alternating between the two diagonal
+    /// directions so that the line heads straight at it.
+    pub fn line_toward(&self, side: Side) -> Vec<Coordinates> {
+        let mut cells = vec![*self];
+        let mut current = *self;
+        while !current.touches_side(side) {
+            let (x, y, z) = (current.x, current.y, current.z);
+            current = match side {
+                Side::A if y <= z => Coordinates::new(x - 1, y + 1, z),
+                Side::A => Coordinates::new(x - 1, y, z + 1),
+                Side::B if x <= z => Coordinates::new(x + 1, y - 1, z),
+                Side::B => Coordinates::new(x, y - 1, z + 1),
+                Side::C if x <= y => Coordinates::new(x + 1, y, z - 1),
+                Side::C => Coordinates::new(x, y + 1, z - 1),
+            };
+            cells.push(current);
+        }
+        cells
+    }
+
+    /// Returns the cell of `side` closest to this one.
+    ///
+    /// Of the cells at the minimum distance, the one facing this cell (the
+    /// end of [`Coordinates::line_toward`]) is returned.
+    pub fn nearest_on_side(&self, side: Side) -> Coordinates {
+        *self
+            .line_toward(side)
+            .last()
+            .expect("a line always contains its starting cell")
+    }
+
+    /// Iterates over the cells of a row, from side B to side C.
+    ///
+    /// Rows are numbered from the top corner (row 0 has a single cell), as in
+    /// board indices. A row outside the board yields no cells.
+    pub fn iter_row(row: u32, board_size: u32) -> impl Iterator<Item = Coordinates> {
+        let len = if row < board_size { row + 1 } else { 0 };
+        let x = board_size.saturating_sub(row + 1);
+        (0..len).map(move |y| Coordinates::new(x, y, row - y))
+    }
+
+    /// Returns the name of the cell in row/column notation, such as `c2`.
+    ///
+    /// Rows are lettered from the top corner (`a` is the single cell of row
+    /// 0) and continue with `aa`, `ab`, ... past `z`. Cells are numbered from
+    /// side B, starting at 1.
+    pub fn to_cell_name(&self, board_size: u32) -> String {
+        let mut row = board_size - 1 - self.x;
+        let mut letters = Vec::new();
+        loop {
+            letters.push(b'a' + (row % 26) as u8);
+            if row < 26 {
+                break;
+            }
+            row = row / 26 - 1;
+        }
+        letters.reverse();
+        format!("{}{}", String::from_utf8_lossy(&letters), self.y + 1)
+    }
+
+    /// Parses a cell name written by [`Coordinates::to_cell_name`].
+    ///
+    /// Letters are case-insensitive. Returns None if the name is malformed or
+    /// does not designate a cell of the board.
+    pub fn from_cell_name(name: &str, board_size: u32) -> Option<Coordinates> {
+        let digits = name.find(|c: char| c.is_ascii_digit())?;
+        let (letters, number) = name.split_at(digits);
+        if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let row = letters
+            .to_ascii_lowercase()
+            .bytes()
+            .try_fold(0u32, |row, c| {
+                row.checked_mul(26)?.checked_add(u32::from(c - b'a') + 1)
+            })?
+            - 1;
+        let column = number.parse::<u32>().ok()?;
+        if row >= board_size || column == 0 || column > row + 1 {
+            return None;
+        }
+        let y = column - 1;
+        Some(Coordinates::new(board_size - 1 - row, y, row - y))
+    }
+}
+
+/// One of the three sides of the triangular board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Side {
+    /// The side where x == 0.
+    A,
+    /// The side where y == 0.
+    B,
+    /// The side where z == 0.
+    C,
+}
+
+impl Side {
+    /// All three sides, in order.
+    pub const ALL: [Side; 3] = [Side::A, Side::B, Side::C];
 }
 
 impl From<Coordinates> for Vec<u32> {
@@ -115,7 +258,7 @@ impl From<Coordinates> for Vec<u32> {
 }
 
 impl Display for Coordinates {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "({}, {}, {})", self.x, self.y, self.z)
     }
 }
@@ -201,6 +344,107 @@ mod tests {
         assert!(top.touches_side_c());
     }
 
+    #[test]
+    fn test_touches_side() {
+        let coords = Coordinates::new(0, 2, 0);
+        assert!(coords.touches_side(Side::A));
+        assert!(!coords.touches_side(Side::B));
+        assert!(coords.touches_side(Side::C));
+    }
+
+    #[test]
+    fn test_distance() {
+        let a = Coordinates::new(2, 1, 1);
+        assert_eq!(a.distance(&a), 0);
+        assert_eq!(a.distance(&Coordinates::new(1, 2, 1)), 1);
+        assert_eq!(a.distance(&Coordinates::new(4, 0, 0)), 2);
+        assert_eq!(
+            Coordinates::new(4, 0, 0).distance(&Coordinates::new(0, 0, 4)),
+            4
+        );
+    }
+
+    #[test]
+    fn test_within_radius() {
+        let center = Coordinates::new(2, 1, 1);
+        assert_eq!(center.within_radius(0), vec![center]);
+
+        let near = center.within_radius(1);
+        assert_eq!(near.len(), 7);
+        assert!(near.iter().all(|c| c.distance(&center) <= 1));
+        let indices: Vec<u32> = near.iter().map(|c| c.to_index(5)).collect();
+        assert!(indices.windows(2).all(|w| w[0] < w[1]));
+
+        // A corner is clipped by the board
+        assert_eq!(Coordinates::new(4, 0, 0).within_radius(1).len(), 3);
+        assert_eq!(Coordinates::new(4, 0, 0).within_radius(10).len(), 15);
+    }
+
+    #[test]
+    fn test_line_toward_side() {
+        let cell = Coordinates::new(4, 1, 1);
+        let line = cell.line_toward(Side::A);
+        assert_eq!(line.len(), 5);
+        assert_eq!(line[0], cell);
+        assert!(line.windows(2).all(|w| w[0].distance(&w[1]) == 1));
+        assert!(line.last().unwrap().touches_side_a());
+        // The line alternates directions and ends facing the start
+        assert_eq!(*line.last().unwrap(), Coordinates::new(0, 3, 3));
+
+        assert_eq!(cell.line_toward(Side::B).len(), 2);
+        assert_eq!(Coordinates::new(0, 2, 2).line_toward(Side::A).len(), 1);
+    }
+
+    #[test]
+    fn test_nearest_on_side() {
+        let cell = Coordinates::new(2, 1, 1);
+        for side in Side::ALL {
+            let nearest = cell.nearest_on_side(side);
+            assert!(nearest.touches_side(side));
+            assert_eq!(nearest.distance(&cell), cell.distance_to_side(side));
+        }
+        assert_eq!(cell.nearest_on_side(Side::B), Coordinates::new(2, 0, 2));
+    }
+
+    #[test]
+    fn test_iter_row() {
+        let row: Vec<Coordinates> = Coordinates::iter_row(2, 4).collect();
+        assert_eq!(
+            row,
+            vec![
+                Coordinates::new(1, 0, 2),
+                Coordinates::new(1, 1, 1),
+                Coordinates::new(1, 2, 0),
+            ]
+        );
+        assert_eq!(Coordinates::iter_row(0, 4).count(), 1);
+        assert_eq!(Coordinates::iter_row(4, 4).count(), 0);
+        assert!(Coordinates::iter_row(3, 4).all(|c| c.touches_side_a()));
+    }
+
+    #[test]
+    fn test_cell_names() {
+        assert_eq!(Coordinates::new(3, 0, 0).to_cell_name(4), "a1");
+        assert_eq!(Coordinates::new(1, 1, 1).to_cell_name(4), "c2");
+        assert_eq!(Coordinates::new(0, 3, 0).to_cell_name(4), "d4");
+        assert_eq!(Coordinates::new(0, 0, 26).to_cell_name(27), "aa1");
+        assert_eq!(
+            Coordinates::from_cell_name("C2", 4),
+            Some(Coordinates::new(1, 1, 1))
+        );
+        assert_eq!(
+            Coordinates::from_cell_name("aa27", 27),
+            Some(Coordinates::new(0, 26, 0))
+        );
+    }
+
+    #[test]
+    fn test_invalid_cell_names() {
+        for name in ["", "c", "2", "c0", "c4", "e1", "c2x", "c-2", "é1"] {
+            assert_eq!(Coordinates::from_cell_name(name, 4), None, "{}", name);
+        }
+    }
+
     #[test]
     fn test_interior_cell_touches_no_sides() {
         let interior = Coordinates::new(1, 1, 1);
@@ -234,6 +478,24 @@ mod tests {
                 sum, board_size - 1, coords, idx, board_size);
         }
 
+        /// Property: Every cell name parses back to the same cell.
+        #[test]
+        fn prop_cell_name_roundtrip(board_size in 1u32..=60, idx_factor in 0.0f64..1.0) {
+            let total_cells = (board_size * (board_size + 1)) / 2;
+            let idx = ((idx_factor * total_cells as f64) as u32).min(total_cells - 1);
+            let coords = Coordinates::from_index(idx, board_size);
+            let name = coords.to_cell_name(board_size);
+            prop_assert_eq!(Coordinates::from_cell_name(&name, board_size), Some(coords));
+        }
+
+        /// Property: Any name either is rejected or names a cell of the board.
+        #[test]
+        fn prop_cell_name_is_on_board_or_rejected(name in "[a-zA-Z]{0,8}[0-9+]{0,12}.?", board_size in 0u32..=60) {
+            if let Some(coords) = Coordinates::from_cell_name(&name, board_size) {
+                prop_assert!(coords.is_valid(board_size), "{} gave {:?}", name, coords);
+            }
+        }
+
         /// Property: For valid coordinates, converting to index and back yields the same coordinates.
         #[test]
         fn prop_coords_to_index_roundtrip(board_size in 2u32..=20, x_ratio in 0.0f64..1.0, y_ratio in 0.0f64..1.0) {
@@ -0,0 +1,343 @@
+//! Edge templates of the game of Y.
+//!
+//! An edge template is a stone near a side together with a set of cells, its
+//! carrier, such that the stone connects to the side whatever the opponent
+//! plays, as long as the carrier holds no opponent stone. The sides of Y are
+//! straight rows of hexagons, so the templates are those of the edges of Hex:
+//!
+//! - the bridge, from the second row, over the two cells of the side below
+//!   the stone;
+//! - the ziggurat, from the third row, over eight cells spreading towards one
+//!   side of the stone or the other.
+//!
+//! Templates whose carrier would leave the board do not apply, which rules
+//! out some of them next to the corners.
+
+use crate::game::cell_sides;
+use crate::{Coordinates, GameY, PlayerId, Side};
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The shape of an [`EdgeTemplate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TemplateKind {
+    /// A stone on the second row, over the two cells of the side below it.
+    Bridge,
+    /// A stone on the third row, over the cell next to it towards higher
+    /// columns, three cells of the second row and four of the side.
+    Ziggurat,
+    /// The mirror image of [`TemplateKind::Ziggurat`].
+    ZigguratMirrored,
+}
+
+/// A cell as (row, column) offsets from the stone of a template: rows count
+/// down towards the side and columns run along it, the cells of the next row
+/// below a cell being at the same column and the next.
+type Offset = (i32, i32);
+
+/// Each kind of template, with the row of its stone and its carrier.
+const SHAPES: [(TemplateKind, i32, &[Offset]); 3] = [
+    (TemplateKind::Bridge, 1, &[(1, 0), (1, 1)]),
+    (
+        TemplateKind::Ziggurat,
+        2,
+        &[
+            (0, 1),
+            (1, 0),
+            (1, 1),
+            (1, 2),
+            (2, 0),
+            (2, 1),
+            (2, 2),
+            (2, 3),
+        ],
+    ),
+    (
+        TemplateKind::ZigguratMirrored,
+        2,
+        &[
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (2, -2),
+            (2, -1),
+            (2, 0),
+            (2, 1),
+        ],
+    ),
+];
+
+/// A stone connected to a side of the board through the empty cells of its
+/// carrier.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EdgeTemplate {
+    /// The shape of the template.
+    pub kind: TemplateKind,
+    /// The side the stone connects to.
+    pub side: Side,
+    /// The cell of the stone.
+    pub stone: Coordinates,
+    /// The cells that must stay free of opponent stones, in board index
+    /// order.
+    pub carrier: Vec<Coordinates>,
+}
+
+impl EdgeTemplate {
+    /// Returns true if `player` has the stone of the template and the
+    /// opponent has no stone in its carrier, which has no blocked cell.
+    pub fn holds_in(&self, game: &GameY, player: PlayerId) -> bool {
+        game.cell_owner(&self.stone) == Some(player)
+            && self.carrier.iter().all(|cell| {
+                !game.is_blocked(cell) && game.cell_owner(cell).is_none_or(|owner| owner == player)
+            })
+    }
+
+    /// Returns the side of the template as an edge label, as in
+    /// [`BoardTopology::edge_labels`](crate::BoardTopology::edge_labels).
+    pub(crate) fn side_label(&self) -> u8 {
+        match self.side {
+            Side::A => 1,
+            Side::B => 2,
+            Side::C => 4,
+        }
+    }
+}
+
+/// Returns the edge templates a stone on `stone` would form on a board of
+/// `board_size`, whatever the other stones on the board.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, Side, TemplateKind, edge_templates};
+///
+/// // A stone on the second row of side A, far from the corners
+/// let templates = edge_templates(Coordinates::new(1, 3, 4), 9);
+/// assert_eq!(templates.len(), 1);
+/// assert_eq!(templates[0].kind, TemplateKind::Bridge);
+/// assert_eq!(templates[0].side, Side::A);
+/// assert_eq!(
+///     templates[0].carrier,
+///     vec![Coordinates::new(0, 3, 5), Coordinates::new(0, 4, 4)]
+/// );
+/// ```
+pub fn edge_templates(stone: Coordinates, board_size: u32) -> Vec<EdgeTemplate> {
+    let mut templates = Vec::new();
+    if !stone.is_valid(board_size) {
+        return templates;
+    }
+    for side in Side::ALL {
+        let (row, column) = side_frame(stone, side);
+        for &(kind, height, offsets) in &SHAPES {
+            if row != height {
+                continue;
+            }
+            let carrier: Option<Vec<Coordinates>> = offsets
+                .iter()
+                .map(|&(down, along)| from_side_frame(row - down, column + along, side, board_size))
+                .collect();
+            if let Some(mut carrier) = carrier {
+                carrier.sort_by_key(|cell| cell.to_index(board_size));
+                templates.push(EdgeTemplate {
+                    kind,
+                    side,
+                    stone,
+                    carrier,
+                });
+            }
+        }
+    }
+    templates
+}
+
+/// Returns the row of `cell` counted from `side` and its column along it.
+fn side_frame(cell: Coordinates, side: Side) -> (i32, i32) {
+    let (x, y, z) = (cell.x() as i32, cell.y() as i32, cell.z() as i32);
+    match side {
+        Side::A => (x, y),
+        Side::B => (y, z),
+        Side::C => (z, x),
+    }
+}
+
+/// Returns the cell at `row` from `side` and `column` along it, if it is on
+/// the board.
+fn from_side_frame(row: i32, column: i32, side: Side, board_size: u32) -> Option<Coordinates> {
+    let rest = board_size as i32 - 1 - row - column;
+    if row < 0 || column < 0 || rest < 0 {
+        return None;
+    }
+    let (row, column, rest) = (row as u32, column as u32, rest as u32);
+    Some(match side {
+        Side::A => Coordinates::new(row, column, rest),
+        Side::B => Coordinates::new(rest, row, column),
+        Side::C => Coordinates::new(column, rest, row),
+    })
+}
+
+impl GameY {
+    /// Returns the edge templates held by the stones of `player`: those
+    /// whose carrier holds no opponent stone, towards sides their stone does
+    /// not already touch.
+    pub fn edge_templates(&self, player: PlayerId) -> Vec<EdgeTemplate> {
+        self.stones()
+            .filter(|(_, owner)| *owner == player)
+            .flat_map(|(stone, _)| edge_templates(stone, self.board_size()))
+            .filter(|template| template.holds_in(self, player))
+            .collect()
+    }
+
+    /// Returns the sides the group of the stone on `coords` is connected to,
+    /// by touching them or through an edge template of one of its stones,
+    /// in the order of [`Side::ALL`]. Returns no side for an empty cell.
+    ///
+    /// Each side is secured against any defence on its own, but templates
+    /// towards different sides may share cells, so securing all three sides
+    /// does not prove a win.
+    pub fn secured_sides(&self, coords: Coordinates) -> Vec<Side> {
+        let Some(player) = self.cell_owner(&coords) else {
+            return Vec::new();
+        };
+        let mut group = vec![coords];
+        let mut labels = 0;
+        let mut next = 0;
+        while let Some(&cell) = group.get(next) {
+            next += 1;
+            labels |= cell_sides(&cell);
+            for template in edge_templates(cell, self.board_size()) {
+                if template.holds_in(self, player) {
+                    labels |= template.side_label();
+                }
+            }
+            for neighbor in self.get_neighbors(&cell) {
+                if self.cell_owner(&neighbor) == Some(player) && !group.contains(&neighbor) {
+                    group.push(neighbor);
+                }
+            }
+        }
+        Side::ALL
+            .into_iter()
+            .enumerate()
+            .filter(|&(bit, _)| labels & (1 << bit) != 0)
+            .map(|(_, side)| side)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Movement;
+    use std::collections::HashSet;
+
+    /// Returns true if the attacker, with the stones `own`, keeps a group of
+    /// `start` reaching `side` whatever the defender plays first among `free`.
+    fn defended(
+        start: Coordinates,
+        side: Side,
+        own: &mut Vec<Coordinates>,
+        free: &[Coordinates],
+    ) -> bool {
+        if reaches(start, side, own) {
+            return true;
+        }
+        free.iter().all(|&taken| {
+            let left: Vec<Coordinates> = free.iter().copied().filter(|&c| c != taken).collect();
+            left.iter().any(|&reply| {
+                own.push(reply);
+                let rest: Vec<Coordinates> = left.iter().copied().filter(|&c| c != reply).collect();
+                let holds = defended(start, side, own, &rest);
+                own.pop();
+                holds
+            })
+        })
+    }
+
+    fn reaches(start: Coordinates, side: Side, own: &[Coordinates]) -> bool {
+        let mut group = vec![start];
+        let mut seen: HashSet<Coordinates> = group.iter().copied().collect();
+        while let Some(cell) = group.pop() {
+            if cell.touches_side(side) {
+                return true;
+            }
+            for &other in own {
+                if cell.distance(&other) == 1 && seen.insert(other) {
+                    group.push(other);
+                }
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn test_templates_hold_against_any_defence() {
+        let size = 9;
+        let mut kinds = HashSet::new();
+        for index in 0..size * (size + 1) / 2 {
+            let stone = Coordinates::from_index(index, size);
+            for template in edge_templates(stone, size) {
+                assert!(
+                    defended(stone, template.side, &mut vec![stone], &template.carrier),
+                    "{:?}",
+                    template
+                );
+                // Without any one of its cells, the carrier is not enough
+                for missing in &template.carrier {
+                    let smaller: Vec<Coordinates> = template
+                        .carrier
+                        .iter()
+                        .copied()
+                        .filter(|cell| cell != missing)
+                        .collect();
+                    assert!(!defended(stone, template.side, &mut vec![stone], &smaller));
+                }
+                kinds.insert(template.kind);
+            }
+        }
+        assert_eq!(kinds.len(), 3);
+    }
+
+    #[test]
+    fn test_templates_stay_on_the_board() {
+        // The corner cell two rows from sides A and B is on side C already
+        let corner = Coordinates::new(2, 2, 0);
+        let templates = edge_templates(corner, 5);
+        let kinds: Vec<(TemplateKind, Side)> = templates.iter().map(|t| (t.kind, t.side)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (TemplateKind::ZigguratMirrored, Side::A),
+                (TemplateKind::Ziggurat, Side::B),
+            ]
+        );
+        assert!(edge_templates(Coordinates::new(1, 1, 1), 2).is_empty());
+    }
+
+    #[test]
+    fn test_opponent_stones_break_templates() {
+        let mut game = GameY::new(9);
+        let stone = Coordinates::new(1, 3, 4);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: stone,
+        })
+        .unwrap();
+        assert_eq!(game.edge_templates(PlayerId::new(0)).len(), 1);
+        assert_eq!(game.secured_sides(stone), vec![Side::A]);
+        assert!(game.edge_templates(PlayerId::new(1)).is_empty());
+
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(0, 3, 5),
+        })
+        .unwrap();
+        assert!(game.edge_templates(PlayerId::new(0)).is_empty());
+        assert!(game.secured_sides(stone).is_empty());
+        assert!(game.secured_sides(Coordinates::new(4, 4, 0)).is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+//! Notifications about changes to a game.
+//!
+//! Observers registered with [`GameY::add_observer`] receive a [`GameEvent`]
+//! every time the game changes, so GUIs and loggers can react to moves
+//! without diffing the board after every call.
+//!
+//! [`GameY::add_observer`]: crate::GameY::add_observer
+
+use crate::{Coordinates, GameResult, Movement, PlayerId};
+use alloc::{boxed::Box, vec::Vec};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::sync::mpsc::Sender;
+
+/// Something that happened in a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum GameEvent {
+    /// A stone was placed on the board.
+    StonePlaced {
+        /// The player who placed the stone.
+        player: PlayerId,
+        /// Where the stone was placed.
+        coords: Coordinates,
+    },
+    /// A player applied the swap rule.
+    SwapApplied {
+        /// The player who swapped.
+        player: PlayerId,
+    },
+    /// The last move was taken back.
+    MoveUndone {
+        /// The move that was removed from the history.
+        movement: Movement,
+    },
+    /// The game ended.
+    GameOver {
+        /// Who won and why.
+        result: GameResult,
+    },
+}
+
+/// Receives the events of a game.
+///
+/// Implemented for closures taking a `&GameEvent` and, with the `std`
+/// feature, for `std::sync::mpsc::Sender<GameEvent>`, which turns the events
+/// into a channel that can be consumed from another thread.
+pub trait GameObserver: Send + Sync {
+    /// Called after the change described by `event` has been applied.
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+impl<F> GameObserver for F
+where
+    F: FnMut(&GameEvent) + Send + Sync,
+{
+    fn on_event(&mut self, event: &GameEvent) {
+        self(event)
+    }
+}
+
+#[cfg(feature = "std")]
+impl GameObserver for Sender<GameEvent> {
+    fn on_event(&mut self, event: &GameEvent) {
+        // A dropped receiver just means nobody is listening anymore
+        let _ = self.send(event.clone());
+    }
+}
+
+/// The observers registered on a game.
+///
+/// Observers belong to the game they were registered on: cloning a game
+/// (e.g. when a bot explores variations) yields a copy without observers.
+#[derive(Default)]
+pub(crate) struct Observers(Vec<Box<dyn GameObserver>>);
+
+impl Observers {
+    pub(crate) fn push(&mut self, observer: Box<dyn GameObserver>) {
+        self.0.push(observer);
+    }
+
+    pub(crate) fn notify(&mut self, event: &GameEvent) {
+        for observer in &mut self.0 {
+            observer.on_event(event);
+        }
+    }
+}
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Observers::default()
+    }
+}
+
+impl core::fmt::Debug for Observers {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Observers({})", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameAction, GameOverReason, GameY};
+    use std::sync::{Arc, Mutex};
+
+    fn place(game: &mut GameY, player: u32, x: u32, y: u32, z: u32) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::new(x, y, z),
+        })
+        .unwrap();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_stone_placed_and_game_over_events() {
+        let mut game = GameY::new(2);
+        let events = game.subscribe();
+
+        place(&mut game, 0, 1, 0, 0);
+        place(&mut game, 1, 0, 1, 0);
+        place(&mut game, 0, 0, 0, 1);
+
+        let received: Vec<GameEvent> = events.try_iter().collect();
+        assert_eq!(received.len(), 4);
+        assert_eq!(
+            received[0],
+            GameEvent::StonePlaced {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(1, 0, 0),
+            }
+        );
+        assert_eq!(
+            received[3],
+            GameEvent::GameOver {
+                result: GameResult {
+                    winner: PlayerId::new(0),
+                    reason: GameOverReason::Connection,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_swap_resign_and_undo_events() {
+        let mut game = GameY::new(3);
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&log);
+        game.add_observer(move |event: &GameEvent| sink.lock().unwrap().push(event.clone()));
+
+        place(&mut game, 0, 2, 0, 0);
+        game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Swap,
+        })
+        .unwrap();
+        game.undo_move().unwrap();
+        game.resign(PlayerId::new(1)).unwrap();
+
+        let log = log.lock().unwrap();
+        assert_eq!(log.len(), 4);
+        assert_eq!(
+            log[1],
+            GameEvent::SwapApplied {
+                player: PlayerId::new(1)
+            }
+        );
+        assert!(matches!(log[2], GameEvent::MoveUndone { .. }));
+        assert!(matches!(
+            log[3],
+            GameEvent::GameOver {
+                result: GameResult {
+                    reason: GameOverReason::Resignation,
+                    ..
+                }
+            }
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_rejected_moves_and_clones_are_silent() {
+        let mut game = GameY::new(3);
+        let events = game.subscribe();
+
+        let mut copy = game.clone();
+        place(&mut copy, 0, 2, 0, 0);
+
+        let result = game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(2, 0, 0),
+        });
+        assert!(result.is_err());
+        assert!(events.try_recv().is_err());
+    }
+}
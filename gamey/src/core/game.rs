@@ -1,12 +1,19 @@
 use crate::core::SetIdx;
+use crate::core::events::Observers;
 use crate::core::player_set::PlayerSet;
-use crate::{Coordinates, GameAction, GameYError, Movement, PlayerId, RenderOptions, YEN};
-use std::collections::HashMap;
-use std::fmt::Write;
+use crate::{
+    Coordinates, GameAction, GameEvent, GameObserver, GameOverReason, GameResult, GameYError,
+    MAX_RECORD_BOARD_SIZE, Movement, OpeningRule, PlayerId, RenderOptions, YEN,
+};
+use alloc::{boxed::Box, format, string::String, string::ToString, vec, vec::Vec};
+use core::fmt::Write;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use std::path::Path;
 
 /// A Result type alias for game operations that may fail with a `GameYError`.
-pub type Result<T> = std::result::Result<T, crate::GameYError>;
+pub type Result<T> = core::result::Result<T, crate::GameYError>;
 
 /// The main game state for a Y game.
 ///
@@ -18,18 +25,44 @@ pub struct GameY {
     // Size of the board (length of one side of the triangular board).
     board_size: u32,
 
-    // Mapping from coordinates to identifiers of players who placed stones there.
-    board_map: HashMap<Coordinates, (SetIdx, PlayerId)>,
+    // The stone on every cell, by board index: the set it belongs to and its owner.
+    cells: Vec<Option<(SetIdx, PlayerId)>>,
 
     status: GameStatus,
 
+    // Why the game ended, set together with a `Finished` status.
+    over_reason: Option<GameOverReason>,
+
     // History of moves made in the game.
     history: Vec<Movement>,
 
     // Union-Find data structure to track connected components for each player
     sets: Vec<PlayerSet>,
 
+    // Empty cells where the next stone may be placed under the opening rule.
     available_cells: Vec<u32>,
+
+    opening_rule: OpeningRule,
+
+    // Blocked cells of a custom board, by board index in ascending order.
+    // They take no stone and connect nothing.
+    blocked: Vec<u32>,
+
+    // The preset position the game was built with, if any. Its stones open
+    // the history, out of turn order.
+    setup: Option<Setup>,
+
+    // Observers notified of every change to the game. Not carried over by `clone`.
+    observers: Observers,
+}
+
+/// The preset position of a game built with stones already on the board:
+/// how many moves at the start of the history are its stones, and who moves
+/// first after them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Setup {
+    pub(crate) stones: usize,
+    pub(crate) next_player: PlayerId,
 }
 
 /// Represents the state of a single cell on the board.
@@ -43,18 +76,118 @@ pub enum Cell {
 
 impl GameY {
     /// Creates a new game with the specified board size and number of players.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the board has more cells than a `u32` can count. Sizes read
+    /// from untrusted input should go through [`GameY::try_new`].
     pub fn new(board_size: u32) -> Self {
-        let total_cells = (board_size * (board_size + 1)) / 2;
+        let total_cells = u64::from(board_size) * (u64::from(board_size) + 1) / 2;
+        let total_cells =
+            u32::try_from(total_cells).expect("the board has more cells than a u32 can count");
         Self {
             board_size,
-            board_map: HashMap::new(),
+            cells: vec![None; total_cells as usize],
             history: Vec::new(),
             sets: Vec::new(),
             status: GameStatus::Ongoing {
                 next_player: PlayerId::new(0),
             },
+            over_reason: None,
             available_cells: (0..total_cells).collect(),
+            opening_rule: OpeningRule::Free,
+            blocked: Vec::new(),
+            setup: None,
+            observers: Observers::default(),
+        }
+    }
+
+    /// Plays the game under `rule`, counting moves from the start of the
+    /// history.
+    ///
+    /// Meant for games started on an empty board. Placements and swaps the
+    /// rule forbids are rejected by [`GameY::add_move`], and cells where the
+    /// next stone may not be placed are left out of
+    /// [`GameY::available_cells`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gamey::{Coordinates, GameY, Movement, OpeningRule, PlayerId};
+    ///
+    /// let rule = OpeningRule::ForbiddenCentre { edge_distance: 1 };
+    /// let mut game = GameY::new(9).with_opening_rule(rule);
+    /// // The 6 cells at least 2 rows away from every side are forbidden
+    /// assert_eq!(game.available_cells().len(), 45 - 6);
+    /// let centre = Movement::Placement {
+    ///     player: PlayerId::new(0),
+    ///     coords: Coordinates::new(3, 3, 2),
+    /// };
+    /// assert!(game.add_move(centre).is_err());
+    /// ```
+    pub fn with_opening_rule(mut self, rule: OpeningRule) -> Self {
+        self.opening_rule = rule;
+        self.refresh_available_cells();
+        self
+    }
+
+    /// Returns the opening rule the game is played under.
+    pub fn opening_rule(&self) -> OpeningRule {
+        self.opening_rule
+    }
+
+    /// Returns the board indices of the blocked cells, in ascending order.
+    ///
+    /// Blocked cells are holes in a custom board, set with
+    /// [`GameYBuilder::with_blocked_cells`](crate::GameYBuilder::with_blocked_cells):
+    /// no stone may be placed on them and they connect no neighbours.
+    pub fn blocked_cells(&self) -> &[u32] {
+        &self.blocked
+    }
+
+    /// Returns true if `coords` is a blocked cell of the board.
+    pub fn is_blocked(&self, coords: &Coordinates) -> bool {
+        coords.is_valid(self.board_size)
+            && self
+                .blocked
+                .binary_search(&coords.to_index(self.board_size))
+                .is_ok()
+    }
+
+    /// Blocks the cells at the given board indices, which must be on the
+    /// board and empty.
+    pub(crate) fn set_blocked_cells(&mut self, mut blocked: Vec<u32>) {
+        blocked.sort_unstable();
+        blocked.dedup();
+        self.blocked = blocked;
+        self.refresh_available_cells();
+    }
+
+    /// Returns an empty board with the size, the opening rule and the
+    /// blocked cells of this game, to replay its moves on.
+    pub(crate) fn cleared(&self) -> GameY {
+        let mut game = GameY::new(self.board_size).with_opening_rule(self.opening_rule);
+        if !self.blocked.is_empty() {
+            game.set_blocked_cells(self.blocked.clone());
         }
+        game
+    }
+
+    /// Rebuilds the available cells from the board, the opening rule and
+    /// the blocked cells.
+    fn refresh_available_cells(&mut self) {
+        let ply = self.history.len();
+        let board_size = self.board_size;
+        let cells = &self.cells;
+        let rule = self.opening_rule;
+        let blocked = &self.blocked;
+        self.available_cells = (0..self.total_cells())
+            .filter(|&idx| {
+                cells[idx as usize].is_none()
+                    && blocked.binary_search(&idx).is_err()
+                    && !rule.forbids_placement(Coordinates::from_index(idx, board_size), ply)
+            })
+            .collect();
     }
 
     /// Returns the current game status.
@@ -62,8 +195,210 @@ impl GameY {
         &self.status
     }
 
-    pub fn board_map(&self) -> &HashMap<Coordinates, (SetIdx, PlayerId)> {
-        &self.board_map
+    /// Returns the owner of the stone on the cell with board index `index`,
+    /// or None if the cell is empty or not on the board.
+    pub fn owner_at(&self, index: u32) -> Option<PlayerId> {
+        self.cells
+            .get(index as usize)
+            .copied()
+            .flatten()
+            .map(|(_, owner)| owner)
+    }
+
+    /// Returns the stones on the board with their owners, in board index
+    /// order.
+    pub fn stones(&self) -> impl Iterator<Item = (Coordinates, PlayerId)> + '_ {
+        self.cells.iter().enumerate().filter_map(|(idx, cell)| {
+            let (_, owner) = (*cell)?;
+            Some((Coordinates::from_index(idx as u32, self.board_size), owner))
+        })
+    }
+
+    /// Returns the number of stones on the board.
+    pub fn stone_count(&self) -> usize {
+        // Every stone starts a set of its own
+        self.sets.len()
+    }
+
+    /// Returns the set and the owner of the stone on `coords`, if any.
+    fn stone_at(&self, coords: &Coordinates) -> Option<(SetIdx, PlayerId)> {
+        if !coords.is_valid(self.board_size) {
+            return None;
+        }
+        self.cells[coords.to_index(self.board_size) as usize]
+    }
+
+    /// Returns the winner and the reason the game ended, or None if the game is ongoing.
+    pub fn result(&self) -> Option<GameResult> {
+        match (&self.status, self.over_reason) {
+            (GameStatus::Finished { winner }, Some(reason)) => Some(GameResult {
+                winner: *winner,
+                reason,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the cells of the winner's group that connects the three sides.
+    ///
+    /// The cells are sorted by board index. Returns None while the game is
+    /// ongoing or if it was not won by connection (e.g. by resignation).
+    pub fn winning_group(&self) -> Option<Vec<Coordinates>> {
+        let GameStatus::Finished { winner } = self.status else {
+            return None;
+        };
+        let winning_root = self
+            .cells
+            .iter()
+            .flatten()
+            .filter(|(_, player)| *player == winner)
+            .map(|(set_idx, _)| self.root_of(*set_idx))
+            .find(|root| self.sets[*root].is_winning_configuration())?;
+
+        let group = self
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| {
+                cell.is_some_and(|(set_idx, _)| self.root_of(set_idx) == winning_root)
+            })
+            .map(|(idx, _)| Coordinates::from_index(idx as u32, self.board_size))
+            .collect();
+        Some(group)
+    }
+
+    /// Returns true if placing a stone of `player` at `coords` would win the game.
+    ///
+    /// The game is not modified. Returns false if the game is over or the cell
+    /// is not an empty cell of the board.
+    pub fn is_winning_move(&self, coords: Coordinates, player: PlayerId) -> bool {
+        if !self.is_playable(&coords) {
+            return false;
+        }
+        self.sides_if_placed(&coords, player, None) == ALL_SIDES
+    }
+
+    /// Returns true if placing a stone of `player` at `coords` would leave
+    /// `player` with a winning move on their next turn.
+    ///
+    /// A move that wins immediately is not considered a threat. The game is
+    /// not modified.
+    pub fn creates_immediate_threat(&self, coords: Coordinates, player: PlayerId) -> bool {
+        if !self.is_playable(&coords) {
+            return false;
+        }
+        let placed_sides = self.sides_if_placed(&coords, player, None);
+        if placed_sides == ALL_SIDES {
+            return false;
+        }
+        let placed_roots = self.neighbor_roots(&coords, player);
+        self.available_cells.iter().any(|&idx| {
+            let follow_up = Coordinates::from_index(idx, self.board_size);
+            follow_up != coords
+                && self.sides_if_placed(
+                    &follow_up,
+                    player,
+                    Some((&coords, &placed_roots, placed_sides)),
+                ) == ALL_SIDES
+        })
+    }
+
+    /// Returns true if the game is ongoing and `coords` is an empty,
+    /// unblocked cell of the board.
+    fn is_playable(&self, coords: &Coordinates) -> bool {
+        !self.check_game_over()
+            && coords.is_valid(self.board_size)
+            && self.stone_at(coords).is_none()
+            && !self.is_blocked(coords)
+    }
+
+    /// Returns the owner of the stone at `coords`, the root of its group
+    /// and the sides the group touches, or None for an empty cell.
+    pub(crate) fn group_of(&self, coords: &Coordinates) -> Option<(PlayerId, SetIdx, u8)> {
+        let (set_idx, owner) = self.stone_at(coords)?;
+        let root = self.root_of(set_idx);
+        Some((owner, root, self.sets[root].sides_mask()))
+    }
+
+    /// Roots of the groups of `player` adjacent to `coords`.
+    fn neighbor_roots(&self, coords: &Coordinates, player: PlayerId) -> Vec<SetIdx> {
+        self.get_neighbors(coords)
+            .iter()
+            .filter_map(|neighbor| match self.stone_at(neighbor) {
+                Some((set_idx, owner)) if owner == player => Some(self.root_of(set_idx)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Sides that the group containing a hypothetical stone of `player` at
+    /// `coords` would touch.
+    ///
+    /// `pending` describes another hypothetical stone of the same player that
+    /// is assumed to be already placed: its coordinates, the roots of the
+    /// groups it joins, and the sides its group touches.
+    fn sides_if_placed(
+        &self,
+        coords: &Coordinates,
+        player: PlayerId,
+        pending: Option<(&Coordinates, &[SetIdx], u8)>,
+    ) -> u8 {
+        let mut sides = cell_sides(coords);
+        for neighbor in self.get_neighbors(coords) {
+            if let Some((pending_coords, _, pending_sides)) = pending
+                && neighbor == *pending_coords
+            {
+                sides |= pending_sides;
+                continue;
+            }
+            if let Some((set_idx, owner)) = self.stone_at(&neighbor)
+                && owner == player
+            {
+                let root = self.root_of(set_idx);
+                sides |= self.sets[root].sides_mask();
+                if let Some((_, pending_roots, pending_sides)) = pending
+                    && pending_roots.contains(&root)
+                {
+                    sides |= pending_sides;
+                }
+            }
+        }
+        sides
+    }
+
+    /// Makes `player` resign, giving the win to the opponent.
+    ///
+    /// Returns an error if the game is already over.
+    pub fn resign(&mut self, player: PlayerId) -> Result<()> {
+        self.forfeit(player, GameOverReason::Resignation)
+    }
+
+    /// Makes `player` lose the game for a reason other than the board position.
+    ///
+    /// The forfeit is recorded in the history as the corresponding
+    /// [`GameAction`]. Returns an error if the game is already over, or if
+    /// `reason` is [`GameOverReason::Connection`], which can only be achieved
+    /// by placing stones.
+    pub fn forfeit(&mut self, player: PlayerId, reason: GameOverReason) -> Result<()> {
+        let action = match reason {
+            GameOverReason::Resignation => GameAction::Resign,
+            GameOverReason::Timeout => GameAction::Timeout,
+            GameOverReason::Abandonment => GameAction::Abandon,
+            GameOverReason::Connection => {
+                return Err(GameYError::InvalidForfeitReason { reason });
+            }
+        };
+        let movement = Movement::Action { player, action };
+        if self.check_game_over() {
+            return Err(GameYError::GameOver { movement });
+        }
+        // A player may resign or lose on time even when it is not their turn
+        self.replay_move(movement)
+    }
+
+    /// Returns the moves played so far, in order.
+    pub fn history(&self) -> &[Movement] {
+        &self.history
     }
 
     /// Returns true if the game has ended (has a winner).
@@ -74,14 +409,16 @@ impl GameY {
         }
     }
 
-    /// Returns the list of available cell indices where pieces can be placed.
+    /// Returns the list of available cell indices where pieces can be
+    /// placed, in board index order.
     pub fn available_cells(&self) -> &Vec<u32> {
         &self.available_cells
     }
 
     /// Returns the total number of cells on the board.
     pub fn total_cells(&self) -> u32 {
-        (self.board_size * (self.board_size + 1)) / 2
+        // `new` only allocates boards whose cells a u32 can count
+        self.cells.len() as u32
     }
 
     /// Checks if the movement is made by the correct player.
@@ -112,7 +449,36 @@ impl GameY {
         }
     }
 
+    /// Overrides the player to move next. Has no effect on finished games.
+    pub(crate) fn set_next_player(&mut self, next_player: PlayerId) {
+        if !self.check_game_over() {
+            self.status = GameStatus::Ongoing { next_player };
+        }
+    }
+
+    /// Ends the preset position of the game: the moves so far are its
+    /// stones, and `next_player` moves first after them.
+    pub(crate) fn finish_setup(&mut self, next_player: PlayerId) {
+        self.set_next_player(next_player);
+        self.setup = Some(Setup {
+            stones: self.history.len(),
+            next_player,
+        });
+    }
+
+    /// Returns the preset position of the game, if it was built with one.
+    pub(crate) fn setup(&self) -> Option<Setup> {
+        self.setup
+    }
+
+    /// Gives the game the preset position `setup`, whose stones must already
+    /// open its history.
+    pub(crate) fn set_setup(&mut self, setup: Option<Setup>) {
+        self.setup = setup;
+    }
+
     /// Loads a game state from a YEN format file.
+    #[cfg(feature = "std")]
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let filename = path.as_ref().display().to_string();
         let file_content = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
@@ -125,6 +491,7 @@ impl GameY {
     }
 
     /// Saves the game state to a file in YEN format.
+    #[cfg(feature = "std")]
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let yen: YEN = self.into();
         let json_content =
@@ -137,25 +504,258 @@ impl GameY {
         Ok(())
     }
 
+    /// Encodes the position as a compact, single-line string.
+    ///
+    /// The format is inspired by chess FEN: rows are separated by '/', player 0
+    /// stones are written as 'B', player 1 stones as 'R', blocked cells as '#',
+    /// runs of empty cells are written as their length, and the side to move
+    /// follows after a space.
+    /// For example, an empty board of size 3 with player 0 to move is `1/2/3 B`.
+    pub fn to_position_string(&self) -> String {
+        let yen: YEN = self.into();
+        let mut result = String::new();
+        let mut empty_run = 0;
+        for cell in yen.layout().chars() {
+            if cell == '.' {
+                empty_run += 1;
+                continue;
+            }
+            if empty_run > 0 {
+                let _ = write!(result, "{}", empty_run);
+                empty_run = 0;
+            }
+            result.push(cell);
+        }
+        if empty_run > 0 {
+            let _ = write!(result, "{}", empty_run);
+        }
+        let side = yen.players()[yen.turn() as usize];
+        let _ = write!(result, " {}", side);
+        result
+    }
+
+    /// Decodes a position produced by [`GameY::to_position_string`].
+    ///
+    /// The board size is given by the number of rows in the layout. The side to
+    /// move is honoured for ongoing games.
+    pub fn from_position_string(position: &str) -> Result<Self> {
+        let invalid = |reason: String| GameYError::InvalidPositionString {
+            position: position.to_string(),
+            reason,
+        };
+        let parts: Vec<&str> = position.split_whitespace().collect();
+        if parts.len() != 2 {
+            return Err(invalid(format!(
+                "expected layout and side to move, found {} fields",
+                parts.len()
+            )));
+        }
+        let next_player = match parts[1] {
+            "B" => PlayerId::new(0),
+            "R" => PlayerId::new(1),
+            other => return Err(invalid(format!("unknown side to move '{}'", other))),
+        };
+
+        // A run of empty cells never spans rows, so no run is longer than the
+        // board is wide
+        let rows = u32::try_from(parts[0].split('/').count()).unwrap_or(u32::MAX);
+        // Checked before the runs are expanded into a layout as large as the
+        // board
+        check_board_size(rows)?;
+        let mut layout = String::new();
+        let mut empty_run: Option<u32> = None;
+        for cell in parts[0].chars().chain(core::iter::once(' ')) {
+            if let Some(digit) = cell.to_digit(10) {
+                empty_run = empty_run
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|run| run.checked_add(digit))
+                    .filter(|run| *run <= rows);
+                if empty_run.is_none() {
+                    return Err(invalid("empty run longer than a row".to_string()));
+                }
+                continue;
+            }
+            match empty_run.take() {
+                Some(0) => return Err(invalid("empty run of length 0".to_string())),
+                Some(run) => layout.push_str(&".".repeat(run as usize)),
+                None => {}
+            }
+            if cell != ' ' {
+                layout.push(cell);
+            }
+        }
+
+        let size = layout.split('/').count() as u32;
+        let yen = YEN::new(size, next_player.id(), vec!['B', 'R'], layout);
+        let mut game = GameY::try_from(yen)?;
+        game.finish_setup(next_player);
+        Ok(game)
+    }
+
+    /// Creates a new game like [`GameY::new`], for a board size read from
+    /// untrusted input.
+    ///
+    /// Returns an error, before allocating the board, for sizes larger than
+    /// [`MAX_RECORD_BOARD_SIZE`].
+    pub fn try_new(board_size: u32) -> Result<Self> {
+        check_board_size(board_size)?;
+        Ok(GameY::new(board_size))
+    }
+
     /// Adds a move to the game.
+    ///
+    /// The move is validated before the board is touched, so an `Err` leaves
+    /// the game unchanged. Returns an error if the game is already over, if it
+    /// is not the moving player's turn, if the opening rule forbids the move,
+    /// if the coordinates are not on the board, or if the cell is already
+    /// occupied.
     pub fn add_move(&mut self, movement: Movement) -> Result<()> {
-        match &movement {
+        if self.check_game_over() {
+            return Err(GameYError::GameOver { movement });
+        }
+        self.check_player_turn(&movement)?;
+        self.check_opening_rule(&movement)?;
+        self.replay_move(movement)
+    }
+
+    /// Checks that the opening rule allows `movement` as the next move.
+    fn check_opening_rule(&self, movement: &Movement) -> Result<()> {
+        let rule = self.opening_rule;
+        let allowed = match movement {
+            Movement::Placement { coords, .. } => {
+                !rule.forbids_placement(*coords, self.history.len())
+            }
+            Movement::Action {
+                action: GameAction::Swap,
+                ..
+            } => rule.allows_swap(),
+            Movement::Action { .. } => true,
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(GameYError::ForbiddenByOpeningRule {
+                movement: movement.clone(),
+                rule,
+            })
+        }
+    }
+
+    /// Applies a move of a stored history with the checks of
+    /// [`GameY::add_move`], except that forfeits may be out of turn, as
+    /// [`GameY::forfeit`] allows.
+    #[cfg(feature = "serde")]
+    fn restore_move(&mut self, movement: Movement) -> Result<()> {
+        if self.check_game_over() {
+            return Err(GameYError::GameOver { movement });
+        }
+        let forfeit = matches!(
+            &movement,
+            Movement::Action { action, .. } if *action != GameAction::Swap
+        );
+        if !forfeit {
+            self.check_player_turn(&movement)?;
+            self.check_opening_rule(&movement)?;
+        }
+        self.replay_move(movement)
+    }
+
+    /// Applies a move without checking whose turn it is or whether the game
+    /// is over.
+    ///
+    /// Used to rebuild positions from sources that do not follow the turn
+    /// order (YEN layouts, presets, stored histories). Coordinates and
+    /// occupancy are still validated.
+    pub(crate) fn replay_move(&mut self, movement: Movement) -> Result<()> {
+        let was_over = self.check_game_over();
+        let event = match &movement {
             Movement::Placement { player, coords } => {
                 self.handle_placement(*player, *coords)?;
+                Some(GameEvent::StonePlaced {
+                    player: *player,
+                    coords: *coords,
+                })
             }
             Movement::Action { player, action } => {
                 self.handle_action(*player, action);
+                (*action == GameAction::Swap).then_some(GameEvent::SwapApplied { player: *player })
             }
-        }
+        };
         self.history.push(movement);
+        if self
+            .opening_rule
+            .restricts_placements(self.history.len() - 1)
+        {
+            // Cells forbidden for the move just played are open for the next
+            self.refresh_available_cells();
+        }
+
+        if let Some(event) = event {
+            self.observers.notify(&event);
+        }
+        if !was_over && let Some(result) = self.result() {
+            self.observers.notify(&GameEvent::GameOver { result });
+        }
         Ok(())
     }
 
+    /// Registers an observer that is notified of every later change to the game.
+    pub fn add_observer<O: GameObserver + 'static>(&mut self, observer: O) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Returns a receiver for the events of this game.
+    ///
+    /// Shorthand for registering the sending half of a new channel.
+    #[cfg(feature = "std")]
+    pub fn subscribe(&mut self) -> std::sync::mpsc::Receiver<GameEvent> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.add_observer(sender);
+        receiver
+    }
+
+    /// Takes back the last move and returns it.
+    ///
+    /// The position is rebuilt by replaying the rest of the history, and the
+    /// player who made the undone move is to move again. Returns an error if
+    /// there is no move to undo.
+    pub fn undo_move(&mut self) -> Result<Movement> {
+        let movement = self.history.pop().ok_or(GameYError::NoMoveToUndo)?;
+        let mut rebuilt = self.cleared();
+        for previous in &self.history {
+            rebuilt.replay_move(previous.clone())?;
+        }
+        let player = match &movement {
+            Movement::Placement { player, .. } | Movement::Action { player, .. } => *player,
+        };
+        rebuilt.set_next_player(player);
+        // Undoing a preset stone leaves a smaller preset position
+        let played = self.history.len();
+        rebuilt.setup = self.setup.map(|setup| {
+            if setup.stones > played {
+                Setup {
+                    stones: played,
+                    next_player: player,
+                }
+            } else {
+                setup
+            }
+        });
+        rebuilt.observers = core::mem::take(&mut self.observers);
+        *self = rebuilt;
+
+        self.observers.notify(&GameEvent::MoveUndone {
+            movement: movement.clone(),
+        });
+        Ok(movement)
+    }
+
     /// Orchestrates the placement logic
     fn handle_placement(&mut self, player: PlayerId, coords: Coordinates) -> Result<()> {
         self.validate_placement(player, coords)?;
 
-        // Update board state (available cells, sets, board_map)
+        // Update board state (available cells, sets, cells)
         let set_idx = self.register_piece(player, coords);
 
         // Connect neighbors and determine if this move won the game
@@ -179,12 +779,12 @@ impl GameY {
         let neighbors = self.get_neighbors(&coords);
 
         for neighbor in neighbors {
-            if let Some((neighbor_idx, neighbor_player)) = self.board_map.get(&neighbor)
-                && *neighbor_player == player
+            if let Some((neighbor_idx, neighbor_player)) = self.stone_at(&neighbor)
+                && neighbor_player == player
             {
                 // Union returns true if the merge resulted in a winning connection
                 //
-                let connection_won = self.union(current_set_idx, *neighbor_idx);
+                let connection_won = self.union(current_set_idx, neighbor_idx);
                 won = won || connection_won;
             }
         }
@@ -198,10 +798,11 @@ impl GameY {
         } else if won {
             tracing::debug!("Player {} wins the game!", player);
             self.status = GameStatus::Finished { winner: player };
+            self.over_reason = Some(GameOverReason::Connection);
         } else {
             // tracing::debug!("No win yet..."); // Optional debug
             self.status = GameStatus::Ongoing {
-                next_player: other_player(player),
+                next_player: self.opening_rule.player_after(player, self.history.len()),
             };
         }
     }
@@ -209,26 +810,42 @@ impl GameY {
     /// Handles non-placement actions (Resign, Swap, etc.)
     fn handle_action(&mut self, player: PlayerId, action: &GameAction) {
         match action {
-            GameAction::Resign => {
-                self.status = GameStatus::Finished {
-                    winner: other_player(player),
-                };
-            }
+            GameAction::Resign => self.finish_by_forfeit(player, GameOverReason::Resignation),
+            GameAction::Timeout => self.finish_by_forfeit(player, GameOverReason::Timeout),
+            GameAction::Abandon => self.finish_by_forfeit(player, GameOverReason::Abandonment),
             GameAction::Swap => {
                 self.status = GameStatus::Ongoing {
-                    next_player: other_player(player),
+                    next_player: self.opening_rule.player_after(player, self.history.len()),
                 };
             }
         }
     }
 
-    /// Handles validation logic (Game Over checks and Occupancy)
+    /// Ends the game with the opponent of `loser` as the winner.
+    fn finish_by_forfeit(&mut self, loser: PlayerId, reason: GameOverReason) {
+        self.status = GameStatus::Finished {
+            winner: other_player(loser),
+        };
+        self.over_reason = Some(reason);
+    }
+
+    /// Handles validation logic (Board bounds, holes and Occupancy)
     fn validate_placement(&self, player: PlayerId, coords: Coordinates) -> Result<()> {
-        if self.check_game_over() {
-            tracing::info!("Game is already over. Move at {} could be ignored", coords);
+        if !coords.is_valid(self.board_size) {
+            return Err(GameYError::InvalidCoordinates {
+                coordinates: coords,
+                board_size: self.board_size,
+            });
         }
 
-        if self.board_map.contains_key(&coords) {
+        if self.is_blocked(&coords) {
+            return Err(GameYError::BlockedCell {
+                coordinates: coords,
+                player,
+            });
+        }
+
+        if self.stone_at(&coords).is_some() {
             return Err(GameYError::Occupied {
                 coordinates: coords,
                 player,
@@ -241,17 +858,21 @@ impl GameY {
     /// Returns the index of the newly created set.
     fn register_piece(&mut self, player: PlayerId, coords: Coordinates) -> usize {
         let cell_idx = coords.to_index(self.board_size);
-        self.available_cells.retain(|&x| x != cell_idx);
+        // The available cells are kept in board index order
+        if let Ok(pos) = self.available_cells.binary_search(&cell_idx) {
+            self.available_cells.remove(pos);
+        }
 
         let set_idx = self.sets.len();
         let new_set = PlayerSet {
             parent: set_idx,
+            size: 1,
             touches_side_a: coords.touches_side_a(),
             touches_side_b: coords.touches_side_b(),
             touches_side_c: coords.touches_side_c(),
         };
         self.sets.push(new_set);
-        self.board_map.insert(coords, (set_idx, player));
+        self.cells[cell_idx as usize] = Some((set_idx, player));
 
         set_idx
     }
@@ -261,7 +882,8 @@ impl GameY {
         self.board_size
     }
 
-    /// Returns the neighboring coordinates for a given cell.
+    /// Returns the neighboring coordinates for a given cell, leaving out
+    /// blocked cells.
     pub fn get_neighbors(&self, coords: &Coordinates) -> Vec<Coordinates> {
         let mut neighbors = Vec::new();
         let x = coords.x();
@@ -280,11 +902,14 @@ impl GameY {
             neighbors.push(Coordinates::new(x + 1, y, z - 1));
             neighbors.push(Coordinates::new(x, y + 1, z - 1));
         }
+        if !self.blocked.is_empty() {
+            neighbors.retain(|neighbor| !self.is_blocked(neighbor));
+        }
         neighbors
     }
 
     pub fn cell_owner(&self, coords: &Coordinates) -> Option<PlayerId> {
-        self.board_map.get(coords).map(|(_, player)| *player)
+        self.stone_at(coords).map(|(_, player)| player)
     }
 
     /// Renders the current state of the board as a text string.
@@ -391,11 +1016,12 @@ impl GameY {
     }
 
     fn format_cell(&self, coords: Coordinates, options: &RenderOptions, width: usize) -> String {
-        let player = self.board_map.get(&coords).map(|(_, p)| *p);
+        let player = self.cell_owner(&coords);
 
         // 1. Base symbol
         let mut symbol = match player {
             Some(p) => format!("{}", p),
+            None if self.is_blocked(&coords) => "#".to_string(),
             None => ".".to_string(),
         };
 
@@ -424,21 +1050,36 @@ impl GameY {
 
     /// Disjoint Set Union 'Find' with path compression
     fn find(&mut self, i: SetIdx) -> SetIdx {
-        if self.sets[i].parent == i {
-            i
-        } else {
-            self.sets[i].parent = self.find(self.sets[i].parent);
-            self.sets[i].parent
+        let root = self.root_of(i);
+        let mut i = i;
+        while i != root {
+            let parent = self.sets[i].parent;
+            self.sets[i].parent = root;
+            i = parent;
+        }
+        root
+    }
+
+    /// Disjoint Set Union 'Find' without path compression, for read-only queries
+    fn root_of(&self, mut i: SetIdx) -> SetIdx {
+        while self.sets[i].parent != i {
+            i = self.sets[i].parent;
         }
+        i
     }
 
-    /// Disjoint Set Union 'Union' operation
+    /// Disjoint Set Union 'Union' operation, by size: the smaller set goes
+    /// under the larger one, so that groups stay shallow on large boards
     fn union(&mut self, i: SetIdx, j: SetIdx) -> bool {
-        let root_i = self.find(i);
-        let root_j = self.find(j);
+        let mut root_i = self.find(i);
+        let mut root_j = self.find(j);
 
         if root_i != root_j {
+            if self.sets[root_i].size > self.sets[root_j].size {
+                core::mem::swap(&mut root_i, &mut root_j);
+            }
             self.sets[root_i].parent = root_j;
+            self.sets[root_j].size += self.sets[root_i].size;
             // Merge side properties
             self.sets[root_j].touches_side_a |= self.sets[root_i].touches_side_a;
             self.sets[root_j].touches_side_b |= self.sets[root_i].touches_side_b;
@@ -451,6 +1092,28 @@ impl GameY {
     }
 }
 
+/// Bit mask with the three sides of the board set.
+pub(crate) const ALL_SIDES: u8 = 0b111;
+
+/// Sides touched by a single cell as a bit mask (A = 1, B = 2, C = 4).
+pub(crate) fn cell_sides(coords: &Coordinates) -> u8 {
+    (coords.touches_side_a() as u8)
+        | (coords.touches_side_b() as u8) << 1
+        | (coords.touches_side_c() as u8) << 2
+}
+
+/// Rejects board sizes read from untrusted input that are larger than
+/// [`MAX_RECORD_BOARD_SIZE`].
+fn check_board_size(board_size: u32) -> Result<()> {
+    if board_size > MAX_RECORD_BOARD_SIZE {
+        return Err(GameYError::BoardTooLarge {
+            board_size,
+            max: MAX_RECORD_BOARD_SIZE,
+        });
+    }
+    Ok(())
+}
+
 fn indent(str: &mut String, level: u32) {
     str.push_str(&" ".repeat(level as usize));
 }
@@ -459,7 +1122,7 @@ impl TryFrom<YEN> for GameY {
     type Error = GameYError;
 
     fn try_from(game: YEN) -> Result<Self> {
-        let mut ygame = GameY::new(game.size());
+        check_board_size(game.size())?;
         let rows: Vec<&str> = game.layout().split('/').collect();
         if rows.len() as u32 != game.size() {
             return Err(GameYError::InvalidYENLayout {
@@ -467,6 +1130,9 @@ impl TryFrom<YEN> for GameY {
                 found: rows.len() as u32,
             });
         }
+        // Only allocated once the layout is known to match the size
+        let mut ygame = GameY::try_new(game.size())?;
+        let mut blocked = Vec::new();
         for (row, row_str) in rows.iter().enumerate() {
             let cells: Vec<char> = row_str.chars().collect();
             if cells.len() as u32 != row as u32 + 1 {
@@ -483,18 +1149,19 @@ impl TryFrom<YEN> for GameY {
                 let coords = Coordinates::new(x, y, z);
                 match cell {
                     'B' => {
-                        ygame.add_move(Movement::Placement {
+                        ygame.replay_move(Movement::Placement {
                             player: PlayerId::new(0),
                             coords,
                         })?;
                     }
                     'R' => {
-                        ygame.add_move(Movement::Placement {
+                        ygame.replay_move(Movement::Placement {
                             player: PlayerId::new(1),
                             coords,
                         })?;
                     }
                     '.' => {}
+                    '#' => blocked.push(coords.to_index(game.size())),
                     _ => {
                         return Err(GameYError::InvalidCharInLayout {
                             char: *cell,
@@ -505,6 +1172,12 @@ impl TryFrom<YEN> for GameY {
                 }
             }
         }
+        if !blocked.is_empty() {
+            ygame.set_blocked_cells(blocked);
+        }
+        // The layout holds no turn order, so its stones are a preset position
+        let next_player = ygame.next_player().unwrap_or(PlayerId::new(0));
+        ygame.finish_setup(next_player);
         Ok(ygame)
     }
 }
@@ -513,7 +1186,7 @@ impl From<&GameY> for YEN {
     fn from(game: &GameY) -> Self {
         let size = game.board_size;
         let turn = match game.status {
-            GameStatus::Finished { winner } => other_player(winner).id() as u32,
+            GameStatus::Finished { winner } => other_player(winner).id(),
             GameStatus::Ongoing { next_player } => next_player.id(),
         };
         let mut layout = String::new();
@@ -521,9 +1194,10 @@ impl From<&GameY> for YEN {
         let players = vec!['B', 'R'];
         for idx in 0..total_cells {
             let coords = Coordinates::from_index(idx, game.board_size);
-            let cell_char = match game.board_map.get(&coords) {
-                Some((_, player)) if player.id() == 0 => 'B',
-                Some((_, player)) if player.id() == 1 => 'R',
+            let cell_char = match game.cell_owner(&coords) {
+                Some(player) if player.id() == 0 => 'B',
+                Some(player) if player.id() == 1 => 'R',
+                _ if game.blocked.binary_search(&idx).is_ok() => '#',
                 _ => '.',
             };
             layout.push(cell_char);
@@ -554,6 +1228,8 @@ fn apply_player_color(symbol: String, player: Option<PlayerId>) -> String {
 
 /// Represents the current status of a game.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "status", rename_all = "snake_case"))]
 pub enum GameStatus {
     /// The game is still in progress with the specified player to move next.
     Ongoing { next_player: PlayerId },
@@ -561,6 +1237,145 @@ pub enum GameStatus {
     Finished { winner: PlayerId },
 }
 
+/// Serialized form of a [`GameY`]: the board size, the preset position,
+/// left out when there is none, the moves played after it, the resulting
+/// status, the opening rule, left out when there is none, and the blocked
+/// cells, left out when there are none.
+///
+/// Deserializing places the preset stones as they come, then replays the
+/// moves with the checks of [`GameY::add_move`], except that forfeits may be
+/// out of turn as with [`GameY::forfeit`], so the rebuilt game always has
+/// consistent internal state (available cells and connected groups). The
+/// stored status must match the replayed game.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct GameRecord {
+    board_size: u32,
+    #[serde(default)]
+    setup: Option<SetupRecord>,
+    history: Vec<Movement>,
+    status: GameStatus,
+    #[serde(default)]
+    opening_rule: OpeningRule,
+    #[serde(default)]
+    blocked: Vec<u32>,
+}
+
+/// Serialized form of the preset position of a [`GameY`]: its stones, and
+/// the player who moves first after them.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SetupRecord {
+    stones: Vec<Movement>,
+    next_player: PlayerId,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for GameY {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let free = self.opening_rule == OpeningRule::Free;
+        let open = self.blocked.is_empty();
+        let fields =
+            3 + usize::from(!free) + usize::from(!open) + usize::from(self.setup.is_some());
+        let mut record = serializer.serialize_struct("GameY", fields)?;
+        record.serialize_field("board_size", &self.board_size)?;
+        let played = match self.setup {
+            Some(setup) => {
+                let preset = SetupRecord {
+                    stones: self.history[..setup.stones].to_vec(),
+                    next_player: setup.next_player,
+                };
+                record.serialize_field("setup", &preset)?;
+                setup.stones
+            }
+            None => {
+                record.skip_field("setup")?;
+                0
+            }
+        };
+        record.serialize_field("history", &self.history[played..])?;
+        record.serialize_field("status", &self.status)?;
+        if free {
+            record.skip_field("opening_rule")?;
+        } else {
+            record.serialize_field("opening_rule", &self.opening_rule)?;
+        }
+        if open {
+            record.skip_field("blocked")?;
+        } else {
+            record.serialize_field("blocked", &self.blocked)?;
+        }
+        record.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for GameY {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        let record = GameRecord::deserialize(deserializer)?;
+        let mut game = GameY::try_new(record.board_size)
+            .map_err(serde::de::Error::custom)?
+            .with_opening_rule(record.opening_rule);
+        if !record.blocked.is_empty() {
+            if let Some(index) = record
+                .blocked
+                .iter()
+                .find(|index| **index >= game.total_cells())
+            {
+                return Err(serde::de::Error::custom(format!(
+                    "blocked cell {} is outside the board",
+                    index
+                )));
+            }
+            game.set_blocked_cells(record.blocked);
+        }
+        if let Some(setup) = record.setup {
+            for stone in setup.stones {
+                if !matches!(stone, Movement::Placement { .. }) {
+                    return Err(serde::de::Error::custom(
+                        "the preset position holds a move that is not a stone",
+                    ));
+                }
+                game.replay_move(stone).map_err(serde::de::Error::custom)?;
+            }
+            if setup.next_player.id() > 1 {
+                return Err(serde::de::Error::custom(
+                    "the player to move after the preset position must be 0 or 1",
+                ));
+            }
+            game.finish_setup(setup.next_player);
+        }
+        for movement in record.history {
+            game.restore_move(movement)
+                .map_err(serde::de::Error::custom)?;
+        }
+        let consistent = match (record.status, &game.status) {
+            (
+                GameStatus::Ongoing { next_player },
+                GameStatus::Ongoing {
+                    next_player: replayed,
+                },
+            ) => next_player == *replayed,
+            (GameStatus::Finished { winner }, GameStatus::Finished { winner: replayed }) => {
+                winner == *replayed
+            }
+            _ => false,
+        };
+        if !consistent {
+            return Err(serde::de::Error::custom(
+                "the status does not match the replayed history",
+            ));
+        }
+        Ok(game)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -682,6 +1497,186 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_result_none_while_ongoing() {
+        let game = GameY::new(3);
+        assert_eq!(game.result(), None);
+    }
+
+    #[test]
+    fn test_result_win_by_connection() {
+        let mut game = GameY::new(1);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(0, 0, 0),
+        })
+        .unwrap();
+        assert_eq!(
+            game.result(),
+            Some(GameResult {
+                winner: PlayerId::new(0),
+                reason: GameOverReason::Connection,
+            })
+        );
+    }
+
+    #[test]
+    fn test_resign_records_reason_and_history() {
+        let mut game = GameY::new(3);
+        game.resign(PlayerId::new(0)).unwrap();
+        assert_eq!(
+            game.result(),
+            Some(GameResult {
+                winner: PlayerId::new(1),
+                reason: GameOverReason::Resignation,
+            })
+        );
+        assert_eq!(game.history().len(), 1);
+    }
+
+    #[test]
+    fn test_forfeit_by_timeout_and_abandonment() {
+        let mut game = GameY::new(3);
+        game.forfeit(PlayerId::new(1), GameOverReason::Timeout)
+            .unwrap();
+        assert_eq!(game.result().unwrap().reason, GameOverReason::Timeout);
+        assert_eq!(game.result().unwrap().winner, PlayerId::new(0));
+
+        let mut game = GameY::new(3);
+        game.forfeit(PlayerId::new(0), GameOverReason::Abandonment)
+            .unwrap();
+        assert_eq!(game.result().unwrap().reason, GameOverReason::Abandonment);
+    }
+
+    #[test]
+    fn test_forfeit_rejected_when_over_or_by_connection() {
+        let mut game = GameY::new(3);
+        assert!(matches!(
+            game.forfeit(PlayerId::new(0), GameOverReason::Connection),
+            Err(GameYError::InvalidForfeitReason { .. })
+        ));
+        game.resign(PlayerId::new(0)).unwrap();
+        assert!(matches!(
+            game.resign(PlayerId::new(1)),
+            Err(GameYError::GameOver { .. })
+        ));
+        assert_eq!(game.result().unwrap().winner, PlayerId::new(1));
+    }
+
+    #[test]
+    fn test_winning_group_contains_only_connected_stones() {
+        let game = GameY::from_position_string("B/BR/BR1 B").unwrap();
+        assert!(game.check_game_over());
+
+        let group = game.winning_group().unwrap();
+        assert_eq!(
+            group,
+            vec![
+                Coordinates::new(2, 0, 0),
+                Coordinates::new(1, 0, 1),
+                Coordinates::new(0, 0, 2),
+            ]
+        );
+
+        // A stone of the winner that is not part of the chain is left out
+        let game_with_extra = GameY::from_position_string("B/BR/BRB B").unwrap();
+        assert_eq!(game_with_extra.winning_group().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_stones_by_board_index() {
+        let game = GameY::from_position_string("1/BR/2R B").unwrap();
+        assert_eq!(game.stone_count(), 3);
+        assert_eq!(game.owner_at(0), None);
+        assert_eq!(game.owner_at(1), Some(PlayerId::new(0)));
+        assert_eq!(game.owner_at(2), Some(PlayerId::new(1)));
+        assert_eq!(game.owner_at(5), Some(PlayerId::new(1)));
+        assert_eq!(game.owner_at(game.total_cells()), None);
+
+        let stones: Vec<(u32, PlayerId)> = game
+            .stones()
+            .map(|(coords, owner)| (coords.to_index(3), owner))
+            .collect();
+        assert_eq!(
+            stones,
+            vec![
+                (1, PlayerId::new(0)),
+                (2, PlayerId::new(1)),
+                (5, PlayerId::new(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_winning_group_none_when_not_won_by_connection() {
+        let mut game = GameY::new(3);
+        assert_eq!(game.winning_group(), None);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(2, 0, 0),
+        })
+        .unwrap();
+        game.resign(PlayerId::new(1)).unwrap();
+        assert_eq!(game.winning_group(), None);
+    }
+
+    #[test]
+    fn test_is_winning_move_does_not_mutate() {
+        // B stones at (2,0,0) and (1,0,1); (0,0,2) completes the chain
+        let game = GameY::from_position_string("B/B1/3 B").unwrap();
+        let before = game.to_position_string();
+
+        assert!(game.is_winning_move(Coordinates::new(0, 0, 2), PlayerId::new(0)));
+        assert!(!game.is_winning_move(Coordinates::new(0, 0, 2), PlayerId::new(1)));
+        assert!(!game.is_winning_move(Coordinates::new(0, 2, 0), PlayerId::new(0)));
+        assert_eq!(game.to_position_string(), before);
+        assert_eq!(game.history().len(), 2);
+    }
+
+    #[test]
+    fn test_is_winning_move_rejects_unplayable_cells() {
+        let game = GameY::from_position_string("B/B1/3 B").unwrap();
+        assert!(!game.is_winning_move(Coordinates::new(1, 0, 1), PlayerId::new(0)));
+        assert!(!game.is_winning_move(Coordinates::new(3, 0, 0), PlayerId::new(0)));
+
+        let mut finished = game.clone();
+        finished.resign(PlayerId::new(1)).unwrap();
+        assert!(!finished.is_winning_move(Coordinates::new(0, 0, 2), PlayerId::new(0)));
+    }
+
+    #[test]
+    fn test_is_winning_move_agrees_with_replay_move() {
+        let game = GameY::from_position_string("1/BR/R1B R").unwrap();
+        for &idx in game.available_cells() {
+            let coords = Coordinates::from_index(idx, game.board_size());
+            for player in [PlayerId::new(0), PlayerId::new(1)] {
+                let mut copy = game.clone();
+                copy.replay_move(Movement::Placement { player, coords })
+                    .unwrap();
+                assert_eq!(
+                    game.is_winning_move(coords, player),
+                    copy.check_game_over(),
+                    "mismatch at {} for player {}",
+                    coords,
+                    player
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_creates_immediate_threat() {
+        // B has (2,0,0); playing (1,0,1) threatens (0,0,2) and (0,1,1)
+        let game = GameY::from_position_string("B/2/3 B").unwrap();
+        assert!(game.creates_immediate_threat(Coordinates::new(1, 0, 1), PlayerId::new(0)));
+        // An isolated stone on an empty board of size 4 creates no threat
+        let empty = GameY::new(4);
+        assert!(!empty.creates_immediate_threat(Coordinates::new(3, 0, 0), PlayerId::new(0)));
+        // A move that already wins is not a threat
+        let near_win = GameY::from_position_string("B/B1/3 B").unwrap();
+        assert!(!near_win.creates_immediate_threat(Coordinates::new(0, 0, 2), PlayerId::new(0)));
+    }
+
     #[test]
     fn test_yen_conversion() {
         let mut game = GameY::new(3);
@@ -713,6 +1708,432 @@ mod tests {
         assert_eq!(yen.layout(), yen_loaded.layout());
     }
 
+    #[test]
+    fn test_position_string_empty_board() {
+        let game = GameY::new(3);
+        assert_eq!(game.to_position_string(), "1/2/3 B");
+    }
+
+    #[test]
+    fn test_position_string_roundtrip() {
+        let mut game = GameY::new(4);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(1, 1, 1),
+        })
+        .unwrap();
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(3, 0, 0),
+        })
+        .unwrap();
+
+        let position = game.to_position_string();
+        assert_eq!(position, "R/2/1B1/4 B");
+
+        let loaded = GameY::from_position_string(&position).unwrap();
+        assert_eq!(loaded.board_size(), 4);
+        assert_eq!(loaded.next_player(), Some(PlayerId::new(0)));
+        assert_eq!(loaded.to_position_string(), position);
+    }
+
+    #[test]
+    fn test_position_string_honours_side_to_move() {
+        let game = GameY::from_position_string("1/B1 B").unwrap();
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+        assert_eq!(
+            game.cell_owner(&Coordinates::new(0, 0, 1)),
+            Some(PlayerId::new(0))
+        );
+    }
+
+    #[test]
+    fn test_position_string_multi_digit_runs() {
+        let game = GameY::new(12);
+        let position = game.to_position_string();
+        assert!(position.starts_with("1/2/3/4/5/6/7/8/9/10/11/12"));
+        let loaded = GameY::from_position_string(&position).unwrap();
+        assert_eq!(loaded.board_size(), 12);
+    }
+
+    #[test]
+    fn test_position_string_invalid() {
+        for position in [
+            "1/2/3",
+            "1/2/3 X",
+            "1/0B1 B",
+            "1/2/4 B",
+            "1/2/1X1 B",
+            "99999999999 B",
+            "1/4294967296 B",
+        ] {
+            assert!(
+                GameY::from_position_string(position).is_err(),
+                "{} should be rejected",
+                position
+            );
+        }
+    }
+
+    #[test]
+    fn test_oversized_boards_are_rejected_before_allocation() {
+        for rows in [MAX_RECORD_BOARD_SIZE + 1, 70_000] {
+            let position = format!("{} B", "/".repeat(rows as usize - 1));
+            assert!(matches!(
+                GameY::from_position_string(&position),
+                Err(GameYError::BoardTooLarge { board_size, .. }) if board_size == rows
+            ));
+        }
+        let yen = YEN::new(100_000, 0, vec!['B', 'R'], "/".repeat(99_999));
+        assert!(matches!(
+            GameY::try_from(yen),
+            Err(GameYError::BoardTooLarge { .. })
+        ));
+        assert!(GameY::try_new(u32::MAX).is_err());
+        assert_eq!(
+            GameY::try_new(MAX_RECORD_BOARD_SIZE).unwrap().board_size(),
+            MAX_RECORD_BOARD_SIZE
+        );
+    }
+
+    #[test]
+    fn test_blocked_cells_survive_notation_and_undo() {
+        let mut game = GameY::from_position_string("1/#1/3 B").unwrap();
+        let hole = Coordinates::new(1, 0, 1);
+        assert_eq!(game.blocked_cells(), &[hole.to_index(3)]);
+        assert_eq!(game.available_cells().len(), 5);
+        assert_eq!(game.to_position_string(), "1/#1/3 B");
+
+        let on_hole = Movement::Placement {
+            player: PlayerId::new(0),
+            coords: hole,
+        };
+        assert!(matches!(
+            game.add_move(on_hole),
+            Err(GameYError::BlockedCell { .. })
+        ));
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(0, 0, 2),
+        })
+        .unwrap();
+        assert!(
+            !game
+                .get_neighbors(&Coordinates::new(0, 0, 2))
+                .contains(&hole)
+        );
+        game.undo_move().unwrap();
+        assert!(game.is_blocked(&hole));
+        assert_eq!(game.available_cells().len(), 5);
+    }
+
+    #[test]
+    fn test_add_move_rejects_out_of_board_coordinates() {
+        let mut game = GameY::new(3);
+        let result = game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(3, 0, 0),
+        });
+        assert!(matches!(
+            result,
+            Err(GameYError::InvalidCoordinates { board_size: 3, .. })
+        ));
+        assert_eq!(game.available_cells().len(), 6);
+        assert!(game.history().is_empty());
+    }
+
+    #[test]
+    fn test_add_move_rejects_wrong_player() {
+        let mut game = GameY::new(3);
+        let result = game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(2, 0, 0),
+        });
+        assert!(matches!(result, Err(GameYError::InvalidPlayerTurn { .. })));
+        assert!(game.history().is_empty());
+    }
+
+    #[test]
+    fn test_add_move_rejects_moves_after_game_over() {
+        let mut game = GameY::new(3);
+        game.resign(PlayerId::new(0)).unwrap();
+        let result = game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::new(2, 0, 0),
+        });
+        assert!(matches!(result, Err(GameYError::GameOver { .. })));
+        assert_eq!(game.history().len(), 1);
+        assert_eq!(game.available_cells().len(), 6);
+    }
+
+    #[test]
+    fn test_undo_move_restores_previous_position() {
+        let mut game = GameY::new(2);
+        assert!(matches!(game.undo_move(), Err(GameYError::NoMoveToUndo)));
+
+        let cells = [
+            Coordinates::new(1, 0, 0),
+            Coordinates::new(0, 1, 0),
+            Coordinates::new(0, 0, 1),
+        ];
+        for (i, coords) in cells.into_iter().enumerate() {
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(i as u32 % 2),
+                coords,
+            })
+            .unwrap();
+        }
+        assert!(game.check_game_over());
+
+        let undone = game.undo_move().unwrap();
+        assert_eq!(
+            undone,
+            Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(0, 0, 1),
+            }
+        );
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+        assert_eq!(game.result(), None);
+        assert_eq!(game.history().len(), 2);
+        assert!(game.is_playable(&Coordinates::new(0, 0, 1)));
+    }
+
+    #[test]
+    fn test_resign_out_of_turn_is_allowed() {
+        let mut game = GameY::new(3);
+        game.resign(PlayerId::new(1)).unwrap();
+        assert_eq!(game.result().unwrap().winner, PlayerId::new(0));
+    }
+
+    #[test]
+    fn test_forbidden_centre_rule() {
+        let rule = OpeningRule::ForbiddenCentre { edge_distance: 0 };
+        let mut game = GameY::new(4).with_opening_rule(rule);
+        let centre = Coordinates::new(1, 1, 1);
+        // Only the centre of a board of size 4 is away from every side
+        assert_eq!(game.available_cells().len(), 9);
+        assert!(game.legal_moves().all(|coords| coords != centre));
+        let result = game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: centre,
+        });
+        assert!(matches!(
+            result,
+            Err(GameYError::ForbiddenByOpeningRule { .. })
+        ));
+        assert!(game.history().is_empty());
+
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(3, 0, 0),
+        })
+        .unwrap();
+        // The second player may answer in the centre
+        assert_eq!(game.available_cells().len(), 9);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: centre,
+        })
+        .unwrap();
+
+        game.undo_move().unwrap();
+        game.undo_move().unwrap();
+        assert_eq!(game.opening_rule(), rule);
+        assert_eq!(game.available_cells().len(), 9);
+    }
+
+    #[test]
+    fn test_one_two_two_rule() {
+        let mut game = GameY::new(5).with_opening_rule(OpeningRule::OneTwoTwo);
+        let mut place = |player: u32, idx: u32| {
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(player),
+                coords: Coordinates::from_index(idx, 5),
+            })
+        };
+        place(0, 4).unwrap();
+        place(1, 7).unwrap();
+        assert!(place(0, 8).is_err());
+        place(1, 8).unwrap();
+        place(0, 12).unwrap();
+        place(0, 13).unwrap();
+        place(1, 0).unwrap();
+        assert!(place(1, 1).is_err());
+        place(0, 1).unwrap();
+
+        let mut game = GameY::new(5).with_opening_rule(OpeningRule::OneTwoTwo);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::from_index(4, 5),
+        })
+        .unwrap();
+        let swap = game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Swap,
+        });
+        assert!(matches!(
+            swap,
+            Err(GameYError::ForbiddenByOpeningRule { .. })
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_keeps_the_blocked_cells() {
+        let game = GameY::from_position_string("1/#B/3 R").unwrap();
+        let json = serde_json::to_string(&game).unwrap();
+        assert!(json.contains("\"blocked\":[1]"));
+        let restored: GameY = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.blocked_cells(), game.blocked_cells());
+        assert_eq!(restored.to_position_string(), "1/#B/3 R");
+        let open = serde_json::to_string(&GameY::new(3)).unwrap();
+        assert!(!open.contains("blocked"));
+        let outside = json.replace("\"blocked\":[1]", "\"blocked\":[6]");
+        assert!(serde_json::from_str::<GameY>(&outside).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_keeps_the_opening_rule() {
+        let mut game = GameY::new(5).with_opening_rule(OpeningRule::OneTwoTwo);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::from_index(4, 5),
+        })
+        .unwrap();
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(1),
+            coords: Coordinates::from_index(7, 5),
+        })
+        .unwrap();
+
+        let json = serde_json::to_string(&game).unwrap();
+        let restored: GameY = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.opening_rule(), OpeningRule::OneTwoTwo);
+        assert_eq!(restored.next_player(), Some(PlayerId::new(1)));
+        let free = serde_json::to_string(&GameY::new(3)).unwrap();
+        assert!(!free.contains("opening_rule"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip_replays_history() {
+        let mut game = GameY::new(3);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(2, 0, 0),
+        })
+        .unwrap();
+        game.add_move(Movement::Action {
+            player: PlayerId::new(1),
+            action: GameAction::Swap,
+        })
+        .unwrap();
+
+        let json = serde_json::to_string(&game).unwrap();
+        assert!(json.contains("\"board_size\":3"));
+        assert!(json.contains("\"swap\""));
+
+        let restored: GameY = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.history().len(), 2);
+        assert_eq!(restored.available_cells().len(), 5);
+        assert_eq!(restored.next_player(), Some(PlayerId::new(0)));
+        assert_eq!(restored.to_position_string(), game.to_position_string());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_inconsistent_history() {
+        let json = r#"{"board_size":2,"history":[
+            {"placement":{"player":0,"coords":{"x":1,"y":0,"z":0}}},
+            {"placement":{"player":1,"coords":{"x":1,"y":0,"z":0}}}
+        ],"status":{"status":"ongoing","next_player":0}}"#;
+        assert!(serde_json::from_str::<GameY>(json).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_keeps_preset_positions() {
+        let roundtrip = |game: &GameY| {
+            let json = serde_json::to_string(game).unwrap();
+            let restored: GameY = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored.to_position_string(), game.to_position_string());
+            assert_eq!(restored.history(), game.history());
+            assert_eq!(restored.next_player(), game.next_player());
+            restored
+        };
+        let built = crate::GameYBuilder::new(4)
+            .with_stones(
+                PlayerId::new(0),
+                [Coordinates::new(3, 0, 0), Coordinates::new(0, 3, 0)],
+            )
+            .build()
+            .unwrap();
+        let mut restored = roundtrip(&built);
+        restored
+            .add_move(Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(1, 1, 1),
+            })
+            .unwrap();
+        roundtrip(&restored);
+        roundtrip(&GameY::from_position_string("1/RB/3 B").unwrap());
+        roundtrip(&GameY::from_position_string("1/BB/3 R").unwrap());
+        let yen = YEN::new(3, 0, vec!['B', 'R'], "R/BB/...".to_string());
+        roundtrip(&GameY::try_from(yen).unwrap());
+
+        // Moves after the preset position still keep to the turn order
+        let json = serde_json::to_string(&built).unwrap().replace(
+            "\"history\":[]",
+            r#""history":[{"placement":{"player":1,"coords":{"x":1,"y":1,"z":1}}}]"#,
+        );
+        assert!(serde_json::from_str::<GameY>(&json).is_err());
+
+        // Undoing a preset stone shrinks the preset position
+        let mut undone = built.clone();
+        undone.undo_move().unwrap();
+        roundtrip(&undone);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_checks_turns_and_status() {
+        let record = |history: &str, status: &str| {
+            format!(r#"{{"board_size":3,"history":[{history}],"status":{status}}}"#)
+        };
+        let first = r#"{"placement":{"player":0,"coords":{"x":2,"y":0,"z":0}}}"#;
+        let again = r#"{"placement":{"player":0,"coords":{"x":0,"y":2,"z":0}}}"#;
+        let resign = r#"{"action":{"player":0,"action":"resign"}}"#;
+        let ongoing = |player: u32| format!(r#"{{"status":"ongoing","next_player":{player}}}"#);
+        let won_by_red = r#"{"status":"finished","winner":1}"#;
+
+        let valid = record(first, &ongoing(1));
+        assert!(serde_json::from_str::<GameY>(&valid).is_ok());
+        let twice = record(&format!("{first},{again}"), &ongoing(1));
+        assert!(serde_json::from_str::<GameY>(&twice).is_err());
+        let wrong_turn = record(first, &ongoing(0));
+        assert!(serde_json::from_str::<GameY>(&wrong_turn).is_err());
+        let not_over = record(first, won_by_red);
+        assert!(serde_json::from_str::<GameY>(&not_over).is_err());
+        let resigned = record(resign, won_by_red);
+        assert!(serde_json::from_str::<GameY>(&resigned).is_ok());
+        let after_end = record(&format!("{resign},{first}"), won_by_red);
+        assert!(serde_json::from_str::<GameY>(&after_end).is_err());
+        let huge = r#"{"board_size":4294967295,"history":[],"status":{"status":"ongoing","next_player":0}}"#;
+        assert!(serde_json::from_str::<GameY>(huge).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_game_status() {
+        let status = GameStatus::Finished {
+            winner: PlayerId::new(1),
+        };
+        let json = serde_json::to_string(&status).unwrap();
+        assert_eq!(json, r#"{"status":"finished","winner":1}"#);
+    }
+
     // Test loading a YEN representation of a finished game
     #[test]
     fn test_load_yen_end2() {
@@ -788,4 +2209,93 @@ mod tests {
             _ => panic!("Game should be ongoing"),
         }
     }
+
+    /// Returns true if the stones of `player` connect the three sides,
+    /// found by a flood fill from every stone.
+    fn connects_by_flood_fill(game: &GameY, player: PlayerId) -> bool {
+        let stones: HashSet<Coordinates> = game
+            .stones()
+            .filter(|(_, owner)| *owner == player)
+            .map(|(coords, _)| coords)
+            .collect();
+        let mut seen = HashSet::new();
+        for start in &stones {
+            if !seen.insert(*start) {
+                continue;
+            }
+            let mut sides = 0;
+            let mut stack = vec![*start];
+            while let Some(coords) = stack.pop() {
+                sides |= cell_sides(&coords);
+                for neighbor in game.get_neighbors(&coords) {
+                    if stones.contains(&neighbor) && seen.insert(neighbor) {
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            if sides == ALL_SIDES {
+                return true;
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn test_incremental_win_detection_matches_a_flood_fill() {
+        use rand::seq::SliceRandom;
+        for seed in 0..40 {
+            let size = 2 + seed as u32 % 11;
+            let mut game = GameY::new(size);
+            let mut cells: Vec<u32> = (0..game.total_cells()).collect();
+            cells.shuffle(&mut crate::seeded_rng(seed));
+            for idx in cells {
+                let player = game.next_player().unwrap();
+                game.add_move(Movement::Placement {
+                    player,
+                    coords: Coordinates::from_index(idx, size),
+                })
+                .unwrap();
+                assert_eq!(
+                    game.check_game_over(),
+                    connects_by_flood_fill(&game, player),
+                    "seed {}",
+                    seed
+                );
+                if game.check_game_over() {
+                    break;
+                }
+            }
+            assert!(game.check_game_over(), "a full board has a winner");
+        }
+    }
+
+    #[test]
+    fn test_long_groups_on_the_largest_boards() {
+        let size = 256;
+        let mut game = GameY::new(size);
+        // Player 0 lays a line along side B from one corner to the other,
+        // while player 1 lays one along side A that cannot reach side B
+        for step in 0..size {
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(size - 1 - step, 0, step),
+            })
+            .unwrap();
+            if step + 1 == size {
+                break;
+            }
+            assert!(!game.check_game_over());
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(1),
+                coords: Coordinates::new(0, step + 1, size - 2 - step),
+            })
+            .unwrap();
+        }
+        assert_eq!(game.result().unwrap().winner, PlayerId::new(0));
+        assert_eq!(game.winning_group().unwrap().len(), size as usize);
+        assert_eq!(
+            game.available_cells().len() as u32,
+            game.total_cells() - 2 * size + 1
+        );
+    }
 }
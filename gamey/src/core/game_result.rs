@@ -0,0 +1,68 @@
+use crate::PlayerId;
+use core::fmt::Display;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The reason why a game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum GameOverReason {
+    /// The winner connected all three sides of the board.
+    Connection,
+    /// The loser resigned.
+    Resignation,
+    /// The loser ran out of time.
+    Timeout,
+    /// The loser abandoned the game (e.g. disconnected and did not return).
+    Abandonment,
+}
+
+impl Display for GameOverReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            GameOverReason::Connection => write!(f, "connection"),
+            GameOverReason::Resignation => write!(f, "resignation"),
+            GameOverReason::Timeout => write!(f, "timeout"),
+            GameOverReason::Abandonment => write!(f, "abandonment"),
+        }
+    }
+}
+
+/// The outcome of a finished game: who won and why.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameResult {
+    /// The player who won the game.
+    pub winner: PlayerId,
+    /// How the game ended.
+    pub reason: GameOverReason,
+}
+
+impl Display for GameResult {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Player {} wins by {}", self.winner, self.reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reason_display() {
+        assert_eq!(format!("{}", GameOverReason::Connection), "connection");
+        assert_eq!(format!("{}", GameOverReason::Resignation), "resignation");
+        assert_eq!(format!("{}", GameOverReason::Timeout), "timeout");
+        assert_eq!(format!("{}", GameOverReason::Abandonment), "abandonment");
+    }
+
+    #[test]
+    fn test_result_display() {
+        let result = GameResult {
+            winner: PlayerId::new(1),
+            reason: GameOverReason::Timeout,
+        };
+        assert_eq!(format!("{}", result), "Player 1 wins by timeout");
+    }
+}
@@ -0,0 +1,171 @@
+//! Iterator over the legal placements of a game.
+//!
+//! [`LegalMoves`] yields the empty cells of an ongoing game as [`Coordinates`]
+//! and can be narrowed down with chainable filters.
+
+use crate::{Coordinates, GameY, PlayerId, Side};
+use alloc::{boxed::Box, vec::Vec};
+
+type MoveFilter<'a> = Box<dyn Fn(&GameY, &Coordinates) -> bool + 'a>;
+
+/// An iterator over the legal placements of a game, in board index order.
+///
+/// Created by [`GameY::legal_moves`]. Filters can be chained before or while
+/// iterating; a cell is yielded only if it passes all of them.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameY, Side};
+///
+/// let game = GameY::new(5);
+/// let near_center: Vec<Coordinates> = game
+///     .legal_moves()
+///     .within_radius(Coordinates::new(2, 1, 1), 1)
+///     .touching_side(Side::B)
+///     .collect();
+/// assert_eq!(near_center.len(), 2);
+/// ```
+pub struct LegalMoves<'a> {
+    game: &'a GameY,
+    cells: core::slice::Iter<'a, u32>,
+    filters: Vec<MoveFilter<'a>>,
+}
+
+impl<'a> LegalMoves<'a> {
+    pub(crate) fn new(game: &'a GameY) -> Self {
+        let cells: &[u32] = if game.check_game_over() {
+            &[]
+        } else {
+            game.available_cells()
+        };
+        Self {
+            game,
+            cells: cells.iter(),
+            filters: Vec::new(),
+        }
+    }
+
+    /// Keeps only cells that satisfy `predicate`.
+    pub fn matching<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&GameY, &Coordinates) -> bool + 'a,
+    {
+        self.filters.push(Box::new(predicate));
+        self
+    }
+
+    /// Keeps only cells on the given side of the board.
+    pub fn touching_side(self, side: Side) -> Self {
+        self.matching(move |_, coords| coords.touches_side(side))
+    }
+
+    /// Keeps only cells adjacent to at least one stone of `player`.
+    pub fn adjacent_to(self, player: PlayerId) -> Self {
+        self.matching(move |game, coords| {
+            game.get_neighbors(coords)
+                .iter()
+                .any(|neighbor| game.cell_owner(neighbor) == Some(player))
+        })
+    }
+
+    /// Keeps only cells at most `radius` steps away from `center`.
+    pub fn within_radius(self, center: Coordinates, radius: u32) -> Self {
+        self.matching(move |_, coords| coords.distance(&center) <= radius)
+    }
+}
+
+impl Iterator for LegalMoves<'_> {
+    type Item = Coordinates;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let board_size = self.game.board_size();
+        self.cells
+            .by_ref()
+            .map(|&idx| Coordinates::from_index(idx, board_size))
+            .find(|coords| self.filters.iter().all(|filter| filter(self.game, coords)))
+    }
+}
+
+impl GameY {
+    /// Returns an iterator over the legal placements, as coordinates.
+    ///
+    /// The iterator is empty when the game is over.
+    pub fn legal_moves(&self) -> LegalMoves<'_> {
+        LegalMoves::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Movement;
+
+    #[test]
+    fn test_all_cells_on_empty_board() {
+        let game = GameY::new(4);
+        let moves: Vec<Coordinates> = game.legal_moves().collect();
+        assert_eq!(moves.len(), 10);
+        assert_eq!(moves[0], Coordinates::new(3, 0, 0));
+    }
+
+    #[test]
+    fn test_no_moves_when_game_over() {
+        let mut game = GameY::new(4);
+        game.resign(PlayerId::new(0)).unwrap();
+        assert_eq!(game.legal_moves().count(), 0);
+    }
+
+    #[test]
+    fn test_touching_side() {
+        let game = GameY::new(4);
+        let moves: Vec<Coordinates> = game.legal_moves().touching_side(Side::A).collect();
+        assert_eq!(moves.len(), 4);
+        assert!(moves.iter().all(|c| c.x() == 0));
+    }
+
+    #[test]
+    fn test_adjacent_to_player() {
+        let mut game = GameY::new(4);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::new(3, 0, 0),
+        })
+        .unwrap();
+
+        let moves: Vec<Coordinates> = game.legal_moves().adjacent_to(PlayerId::new(0)).collect();
+        assert_eq!(
+            moves,
+            vec![Coordinates::new(2, 0, 1), Coordinates::new(2, 1, 0)]
+        );
+        assert_eq!(game.legal_moves().adjacent_to(PlayerId::new(1)).count(), 0);
+    }
+
+    #[test]
+    fn test_within_radius_excludes_occupied_cells() {
+        let mut game = GameY::new(5);
+        let center = Coordinates::new(2, 1, 1);
+        assert_eq!(game.legal_moves().within_radius(center, 1).count(), 7);
+
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: center,
+        })
+        .unwrap();
+        assert_eq!(game.legal_moves().within_radius(center, 1).count(), 6);
+    }
+
+    #[test]
+    fn test_filters_combine() {
+        let game = GameY::new(5);
+        let moves: Vec<Coordinates> = game
+            .legal_moves()
+            .touching_side(Side::B)
+            .matching(|_, coords| coords.z() >= 3)
+            .collect();
+        assert_eq!(
+            moves,
+            vec![Coordinates::new(1, 0, 3), Coordinates::new(0, 0, 4)]
+        );
+    }
+}
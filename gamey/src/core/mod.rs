@@ -2,26 +2,69 @@
 //!
 //! This module contains the fundamental types for representing and playing Y:
 //! - [`Coordinates`]: Barycentric coordinates on the triangular board
+//! - [`EdgeTemplate`]: Stones connected to a side against any defence
 //! - [`GameY`]: The main game state and logic
+//! - [`GameYBuilder`]: Builder for games starting from a preset position
+//! - [`GameEvent`] and [`GameObserver`]: Notifications about changes to a game
 //! - [`GameStatus`]: Whether the game is ongoing or finished
+//! - [`ConnectionProgress`]: The sides touched by each group of a player
+//! - [`GameResult`] and [`GameOverReason`]: Who won a finished game and how
 //! - [`Player`] and [`PlayerId`]: Player representation
 //! - [`Movement`]: A move (placement or action) in the game
+//! - [`PlacementGame`]: Common interface for bots playing placement games
+//! - [`LegalMoves`]: Filterable iterator over the legal placements of a game
 //! - [`GameAction`]: Special actions like swap or resign
+//! - [`OpeningRule`]: Balancing rules restricting the first moves of a game
+//! - [`GameRng`] and [`SharedRng`]: Seeded random numbers for reproducible runs
+//!   ([`SharedRng`] needs the `std` feature)
 //! - [`RenderOptions`]: Configuration for board rendering
+//! - [`GameY::canonical_hash`]: Position hashes shared by symmetric positions
+//! - [`Replay`]: Cursor over the successive states of a game, and
+//!   [`GameY::preview_line`] for the positions along an expected line of play
+//! - [`Symmetry`]: The six rotations and reflections of the board
+//! - [`BoardTopology`] and [`WinCondition`]: Rules of a connection game, as used by search code
+//!   (`bots` feature)
 
 pub mod action;
+pub mod analysis;
+pub mod builder;
 pub mod coord;
+pub mod edge_template;
+pub mod events;
 pub mod game;
+pub mod game_result;
+pub mod game_rng;
+pub mod legal_moves;
 pub mod movement;
+pub mod opening_rule;
+pub mod placement_game;
 pub mod player;
 mod player_set;
+pub mod position_hash;
 pub mod render_options;
+pub mod replay;
+pub mod symmetry;
+#[cfg(feature = "bots")]
+pub mod topology;
 
 pub use action::*;
+pub use analysis::*;
+pub use builder::*;
 pub use coord::*;
+pub use edge_template::*;
+pub use events::*;
 pub use game::*;
+pub use game_result::*;
+pub use game_rng::*;
+pub use legal_moves::*;
 pub use movement::*;
+pub use opening_rule::*;
+pub use placement_game::*;
 pub use player::*;
 pub use render_options::*;
+pub use replay::*;
+pub use symmetry::*;
+#[cfg(feature = "bots")]
+pub use topology::*;
 
 type SetIdx = usize;
@@ -1,11 +1,15 @@
 use crate::{Coordinates, GameAction, PlayerId};
-use std::fmt::Display;
+use core::fmt::Display;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Represents a move that a player can make during the game.
 ///
 /// A movement can either be placing a piece on the board at specific coordinates,
 /// or performing a special game action like swapping or resigning.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Movement {
     /// A piece placement on the board.
     Placement {
@@ -24,7 +28,7 @@ pub enum Movement {
 }
 
 impl Display for Movement {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Movement::Placement { player, coords } => {
                 write!(f, "Player {} places at {}", player, coords)
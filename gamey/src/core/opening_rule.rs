@@ -0,0 +1,120 @@
+//! Balancing rules for the opening of a game.
+//!
+//! The first player of Y has a large advantage. Besides the pie rule
+//! ([`GameAction::Swap`](crate::GameAction::Swap)), which is always
+//! available unless another rule replaces it, a game can be played under an
+//! [`OpeningRule`] set with [`GameY::with_opening_rule`](crate::GameY::with_opening_rule).
+//! The rule is part of the legality checks of the game, so UIs and bots
+//! that play from [`GameY::available_cells`](crate::GameY::available_cells)
+//! respect it without knowing about it.
+
+use crate::{Coordinates, PlayerId, other_player};
+use core::fmt::Display;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A rule restricting the first moves of a game to offset the advantage of
+/// moving first.
+///
+/// Moves are counted from the start of the history of the game.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "rule", rename_all = "snake_case"))]
+pub enum OpeningRule {
+    /// No restriction: only the pie rule balances the game.
+    #[default]
+    Free,
+    /// The first stone of the game must be placed at most `edge_distance`
+    /// rows away from a side, which rules out the centre of the board. With
+    /// an `edge_distance` of 0 the first stone must touch a side.
+    ForbiddenCentre {
+        /// How far from the nearest side the first stone may be placed.
+        edge_distance: u32,
+    },
+    /// The first player places one stone, then the second player places two
+    /// stones, then the first player two, after which turns alternate. The
+    /// pie rule is not available.
+    OneTwoTwo,
+}
+
+impl OpeningRule {
+    /// Returns true if the rule forbids placing the stone of move number
+    /// `ply` (starting at 0) on `coords`.
+    pub fn forbids_placement(&self, coords: Coordinates, ply: usize) -> bool {
+        match self {
+            OpeningRule::ForbiddenCentre { edge_distance } => {
+                ply == 0 && coords.x().min(coords.y()).min(coords.z()) > *edge_distance
+            }
+            OpeningRule::Free | OpeningRule::OneTwoTwo => false,
+        }
+    }
+
+    /// Returns true if the rule forbids some cells for the stone of move
+    /// number `ply` (starting at 0).
+    pub fn restricts_placements(&self, ply: usize) -> bool {
+        matches!(self, OpeningRule::ForbiddenCentre { .. }) && ply == 0
+    }
+
+    /// Returns true if the rule allows the pie rule.
+    pub fn allows_swap(&self) -> bool {
+        *self != OpeningRule::OneTwoTwo
+    }
+
+    /// Returns the player to move after `player` made move number `ply`
+    /// (starting at 0).
+    pub fn player_after(&self, player: PlayerId, ply: usize) -> PlayerId {
+        match self {
+            // Moves 1 and 3 start a turn of two stones
+            OpeningRule::OneTwoTwo if ply == 1 || ply == 3 => player,
+            _ => other_player(player),
+        }
+    }
+}
+
+impl Display for OpeningRule {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            OpeningRule::Free => write!(f, "free opening"),
+            OpeningRule::ForbiddenCentre { edge_distance } => {
+                write!(f, "first stone at most {} rows from a side", edge_distance)
+            }
+            OpeningRule::OneTwoTwo => write!(f, "1-2-2 opening"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forbidden_centre_only_restricts_the_first_move() {
+        let rule = OpeningRule::ForbiddenCentre { edge_distance: 1 };
+        let centre = Coordinates::new(2, 2, 2);
+        assert!(rule.forbids_placement(centre, 0));
+        assert!(!rule.forbids_placement(centre, 1));
+        assert!(!rule.forbids_placement(Coordinates::new(5, 1, 0), 0));
+        assert!(!OpeningRule::Free.forbids_placement(centre, 0));
+        assert!(rule.restricts_placements(0));
+        assert!(!rule.restricts_placements(1));
+    }
+
+    #[test]
+    fn test_one_two_two_turn_order() {
+        let rule = OpeningRule::OneTwoTwo;
+        let (first, second) = (PlayerId::new(0), PlayerId::new(1));
+        let mut player = first;
+        let order: Vec<PlayerId> = (0..7)
+            .map(|ply| {
+                let moving = player;
+                player = rule.player_after(moving, ply);
+                moving
+            })
+            .collect();
+        assert_eq!(
+            order,
+            vec![first, second, second, first, first, second, first]
+        );
+        assert!(!rule.allows_swap());
+    }
+}
@@ -0,0 +1,75 @@
+//! A common interface for two-player stone placement games.
+//!
+//! Bots that only need to know which cells are free and whose turn it is can
+//! play any game implementing [`PlacementGame`], whatever the board shape.
+
+use crate::{Coordinates, GameY, GameYError, Movement, PlayerId, game};
+
+/// A two-player game where players take turns placing stones on cells.
+///
+/// Cells are identified by their board index, from 0 to
+/// `total_cells() - 1`.
+pub trait PlacementGame {
+    /// Returns the total number of cells on the board.
+    fn total_cells(&self) -> u32;
+
+    /// Returns the indices of the empty cells, in ascending order.
+    fn free_cells(&self) -> &[u32];
+
+    /// Returns the player to move, or None if the game is over.
+    fn player_to_move(&self) -> Option<PlayerId>;
+
+    /// Places a stone of `player` on the cell with the given index.
+    fn place_stone(&mut self, player: PlayerId, index: u32) -> game::Result<()>;
+}
+
+impl PlacementGame for GameY {
+    fn total_cells(&self) -> u32 {
+        GameY::total_cells(self)
+    }
+
+    fn free_cells(&self) -> &[u32] {
+        self.available_cells()
+    }
+
+    fn player_to_move(&self) -> Option<PlayerId> {
+        self.next_player()
+    }
+
+    fn place_stone(&mut self, player: PlayerId, index: u32) -> game::Result<()> {
+        let total_cells = GameY::total_cells(self);
+        if index >= total_cells {
+            return Err(GameYError::InvalidCellIndex { index, total_cells });
+        }
+        let coords = Coordinates::from_index(index, self.board_size());
+        self.add_move(Movement::Placement { player, coords })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_y_as_placement_game() {
+        let mut game = GameY::new(3);
+        assert_eq!(PlacementGame::total_cells(&game), 6);
+
+        game.place_stone(PlayerId::new(0), 4).unwrap();
+        assert_eq!(game.free_cells(), &[0, 1, 2, 3, 5]);
+        assert_eq!(game.player_to_move(), Some(PlayerId::new(1)));
+        assert_eq!(
+            game.cell_owner(&Coordinates::from_index(4, 3)),
+            Some(PlayerId::new(0))
+        );
+    }
+
+    #[test]
+    fn test_place_stone_rejects_bad_index() {
+        let mut game = GameY::new(3);
+        assert!(matches!(
+            game.place_stone(PlayerId::new(0), 6),
+            Err(GameYError::InvalidCellIndex { index: 6, .. })
+        ));
+    }
+}
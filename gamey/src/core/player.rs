@@ -1,7 +1,11 @@
-use std::fmt::Display;
+use alloc::string::String;
+use core::fmt::Display;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Represents a player in the game with an identifier and a name.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Player {
     id: PlayerId,
     name: String,
@@ -25,7 +29,7 @@ impl Player {
 }
 
 impl Display for Player {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "Player {}: {}", self.id, self.name)
     }
 }
@@ -35,6 +39,7 @@ impl Display for Player {
 /// This is a lightweight wrapper around a `u32` that provides type safety
 /// for player identification throughout the game.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PlayerId(u32);
 
 impl PlayerId {
@@ -50,7 +55,7 @@ impl PlayerId {
 }
 
 impl Display for PlayerId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
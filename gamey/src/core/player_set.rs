@@ -4,6 +4,8 @@ use crate::core::SetIdx;
 #[derive(Clone, Debug)]
 pub(crate) struct PlayerSet {
     pub parent: SetIdx,
+    // Number of stones in the set, meaningful for roots only
+    pub size: u32,
     // We track which sides this specific set of pieces is touching
     pub touches_side_a: bool,
     pub touches_side_b: bool,
@@ -15,4 +17,11 @@ impl PlayerSet {
     pub fn is_winning_configuration(&self) -> bool {
         self.touches_side_a && self.touches_side_b && self.touches_side_c
     }
+
+    /// Returns the sides touched by this set as a bit mask (A = 1, B = 2, C = 4).
+    pub fn sides_mask(&self) -> u8 {
+        (self.touches_side_a as u8)
+            | (self.touches_side_b as u8) << 1
+            | (self.touches_side_c as u8) << 2
+    }
 }
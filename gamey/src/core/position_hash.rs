@@ -0,0 +1,166 @@
+//! Hashes of board positions.
+//!
+//! A position hash covers the board size, the stones, the blocked cells and
+//! the side to move, but not the order in which the stones were placed. The canonical hash is
+//! the same for the six symmetric images of a position, so it can be used to
+//! index positions in game databases and opening books.
+
+use crate::{GameY, PlayerId, Symmetry};
+use alloc::vec::Vec;
+
+/// Marker mixed into the hash of positions where nobody is to move.
+const NO_PLAYER_TO_MOVE: u64 = 0xff;
+
+/// Marker mixed into the keys of blocked cells, in place of a player id.
+const BLOCKED_CELL: u64 = 0xfe;
+
+/// The SplitMix64 finaliser, used to spread small keys over 64 bits.
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn stone_key(board_size: u32, index: u32, player: PlayerId) -> u64 {
+    mix((u64::from(board_size) << 40) | (u64::from(index) << 8) | u64::from(player.id()))
+}
+
+fn blocked_key(board_size: u32, index: u32) -> u64 {
+    mix((u64::from(board_size) << 40) | (u64::from(index) << 8) | BLOCKED_CELL)
+}
+
+fn side_key(board_size: u32, next_player: Option<PlayerId>) -> u64 {
+    let side = next_player.map_or(NO_PLAYER_TO_MOVE, |player| u64::from(player.id()));
+    mix((u64::from(board_size) << 40) | (0xff_ffff << 8) | side)
+}
+
+impl GameY {
+    /// Returns a hash of the stones on the board, the blocked cells and the
+    /// side to move.
+    ///
+    /// Two games with the same stones, blocked cells and side to move have
+    /// the same hash, whatever the order of their moves.
+    pub fn position_hash(&self) -> u64 {
+        self.position_hash_under(Symmetry::Identity)
+    }
+
+    /// Returns the hash the position would have after applying `symmetry`.
+    pub fn position_hash_under(&self, symmetry: Symmetry) -> u64 {
+        let size = self.board_size();
+        let blocked = self.blocked_cells().iter().map(|&index| {
+            let coords = symmetry.transform(crate::Coordinates::from_index(index, size));
+            blocked_key(size, coords.to_index(size))
+        });
+        self.stones()
+            .map(|(coords, player)| {
+                stone_key(size, symmetry.transform(coords).to_index(size), player)
+            })
+            .chain(blocked)
+            .fold(side_key(size, self.next_player()), |hash, key| hash ^ key)
+    }
+
+    /// Returns the smallest position hash over the six symmetries of the
+    /// board, together with the symmetries that map the position onto its
+    /// canonical image.
+    ///
+    /// More than one symmetry is returned when the position is itself
+    /// symmetric. The list always contains at least one element.
+    pub fn canonical_position(&self) -> (u64, Vec<Symmetry>) {
+        let hashes = Symmetry::ALL.map(|symmetry| (symmetry, self.position_hash_under(symmetry)));
+        let min = hashes
+            .iter()
+            .map(|(_, hash)| *hash)
+            .min()
+            .unwrap_or_default();
+        let symmetries = hashes
+            .iter()
+            .filter(|(_, hash)| *hash == min)
+            .map(|(symmetry, _)| *symmetry)
+            .collect();
+        (min, symmetries)
+    }
+
+    /// Returns the position hash that is shared by all symmetric images of
+    /// the position.
+    pub fn canonical_hash(&self) -> u64 {
+        self.canonical_position().0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, Movement};
+
+    fn play(game: &mut GameY, player: u32, x: u32, y: u32, z: u32) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::new(x, y, z),
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_hash_ignores_move_order() {
+        let mut a = GameY::new(4);
+        play(&mut a, 0, 3, 0, 0);
+        play(&mut a, 1, 0, 3, 0);
+        play(&mut a, 0, 1, 1, 1);
+        play(&mut a, 1, 0, 0, 3);
+
+        let mut b = GameY::new(4);
+        play(&mut b, 0, 1, 1, 1);
+        play(&mut b, 1, 0, 0, 3);
+        play(&mut b, 0, 3, 0, 0);
+        play(&mut b, 1, 0, 3, 0);
+
+        assert_eq!(a.position_hash(), b.position_hash());
+    }
+
+    #[test]
+    fn test_hash_depends_on_side_to_move_and_size() {
+        let mut game = GameY::new(4);
+        let empty = game.position_hash();
+        assert_ne!(empty, GameY::new(5).position_hash());
+        play(&mut game, 0, 1, 1, 1);
+        assert_ne!(game.position_hash(), empty);
+
+        let mut other = GameY::new(4);
+        play(&mut other, 0, 2, 1, 0);
+        assert_ne!(game.position_hash(), other.position_hash());
+    }
+
+    #[test]
+    fn test_canonical_hash_is_shared_by_symmetric_images() {
+        let mut game = GameY::new(5);
+        play(&mut game, 0, 4, 0, 0);
+        play(&mut game, 1, 2, 1, 1);
+        play(&mut game, 0, 0, 1, 3);
+        let hash = game.canonical_hash();
+        for symmetry in Symmetry::ALL {
+            assert_eq!(game.transformed(symmetry).canonical_hash(), hash);
+        }
+        let (_, symmetries) = game.canonical_position();
+        assert_eq!(
+            game.position_hash_under(symmetries[0]),
+            game.canonical_hash()
+        );
+    }
+
+    #[test]
+    fn test_hash_depends_on_blocked_cells() {
+        let open = GameY::from_position_string("1/2/3 B").unwrap();
+        let holed = GameY::from_position_string("1/#1/3 B").unwrap();
+        let mirrored = GameY::from_position_string("1/1#/3 B").unwrap();
+        assert_ne!(open.position_hash(), holed.position_hash());
+        assert_ne!(holed.position_hash(), mirrored.position_hash());
+        assert_eq!(holed.canonical_hash(), mirrored.canonical_hash());
+    }
+
+    #[test]
+    fn test_symmetric_position_has_several_canonical_symmetries() {
+        let (_, symmetries) = GameY::new(4).canonical_position();
+        assert_eq!(symmetries.len(), 6);
+    }
+}
@@ -0,0 +1,256 @@
+//! Step-by-step replay of a game's move history.
+
+use crate::{Coordinates, GameY, GameYError, Movement, PlayerId, Result};
+use alloc::vec::Vec;
+
+/// A cursor over the successive states of a game.
+///
+/// Created by [`GameY::replay`]. The cursor starts on the empty board and can
+/// move forwards and backwards through the history. As an [`Iterator`] it
+/// yields the state after each move, in order.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameY, Movement, PlayerId};
+///
+/// let mut game = GameY::new(3);
+/// game.add_move(Movement::Placement {
+///     player: PlayerId::new(0),
+///     coords: Coordinates::new(1, 1, 0),
+/// })
+/// .unwrap();
+///
+/// let mut replay = game.replay();
+/// assert_eq!(replay.current().history().len(), 0);
+/// replay.seek(1);
+/// assert_eq!(replay.current().history().len(), 1);
+/// ```
+pub struct Replay<'a> {
+    moves: &'a [Movement],
+    state: GameY,
+}
+
+impl<'a> Replay<'a> {
+    fn new(game: &'a GameY) -> Self {
+        Self {
+            moves: game.history(),
+            state: game.cleared(),
+        }
+    }
+
+    /// Returns the state at the current position.
+    pub fn current(&self) -> &GameY {
+        &self.state
+    }
+
+    /// Returns the number of moves applied to reach the current state.
+    pub fn moves_played(&self) -> usize {
+        self.state.history().len()
+    }
+
+    /// Returns the total number of moves in the replayed game.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Returns true if the replayed game has no moves.
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Moves the cursor to the state after `position` moves.
+    ///
+    /// Positions past the end of the game are clamped to the final state.
+    pub fn seek(&mut self, position: usize) -> &GameY {
+        let position = position.min(self.moves.len());
+        if position < self.moves_played() {
+            self.state = self.state.cleared();
+        }
+        while self.moves_played() < position {
+            self.apply_next();
+        }
+        &self.state
+    }
+
+    /// Moves the cursor one move back, or returns None at the start.
+    pub fn prev(&mut self) -> Option<&GameY> {
+        let position = self.moves_played().checked_sub(1)?;
+        Some(self.seek(position))
+    }
+
+    fn apply_next(&mut self) {
+        let movement = self.moves[self.moves_played()].clone();
+        self.state
+            .replay_move(movement)
+            .expect("moves from a game history can be replayed");
+    }
+}
+
+impl Iterator for Replay<'_> {
+    type Item = GameY;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.moves_played() >= self.moves.len() {
+            return None;
+        }
+        self.apply_next();
+        Some(self.state.clone())
+    }
+}
+
+impl GameY {
+    /// Returns a cursor that replays this game's history from the empty board.
+    pub fn replay(&self) -> Replay<'_> {
+        Replay::new(self)
+    }
+
+    /// Plays the cells of `line`, given as indices, from the current
+    /// position and returns the position after each of them, so that the
+    /// continuation expected by an engine (the `pv` of a search report) can
+    /// be stepped through on the board. The stones are placed by whoever is
+    /// to move, and the game itself is left untouched.
+    ///
+    /// Returns the error of the first move that cannot be played, such as an
+    /// index outside the board, an occupied cell or a move after the end of
+    /// the game, which happens when the line was searched from another
+    /// position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gamey::GameY;
+    ///
+    /// let game = GameY::new(3);
+    /// let line = game.preview_line(&[4, 2, 0]).unwrap();
+    /// assert_eq!(line.len(), 3);
+    /// assert_eq!(line[2].history().len(), 3);
+    /// assert!(game.history().is_empty());
+    /// assert!(game.preview_line(&[4, 4]).is_err());
+    /// ```
+    pub fn preview_line(&self, line: &[usize]) -> Result<Vec<GameY>> {
+        let mut state = self.clone();
+        let mut positions = Vec::with_capacity(line.len());
+        let total_cells = self.total_cells();
+        for &index in line {
+            let index = u32::try_from(index).unwrap_or(u32::MAX);
+            if index >= total_cells {
+                return Err(GameYError::InvalidCellIndex { index, total_cells });
+            }
+            // Once the game is over any player will do: the move is rejected anyway
+            let player = state.next_player().unwrap_or(PlayerId::new(0));
+            let coords = Coordinates::from_index(index, self.board_size());
+            state.add_move(Movement::Placement { player, coords })?;
+            positions.push(state.clone());
+        }
+        Ok(positions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, OpeningRule};
+
+    fn sample_game() -> GameY {
+        let mut game = GameY::new(3);
+        let cells = [
+            Coordinates::new(2, 0, 0),
+            Coordinates::new(1, 1, 0),
+            Coordinates::new(1, 0, 1),
+        ];
+        for (i, coords) in cells.into_iter().enumerate() {
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(i as u32 % 2),
+                coords,
+            })
+            .unwrap();
+        }
+        game.resign(PlayerId::new(1)).unwrap();
+        game
+    }
+
+    #[test]
+    fn test_iterates_over_successive_states() {
+        let game = sample_game();
+        let states: Vec<GameY> = game.replay().collect();
+        assert_eq!(states.len(), 4);
+        for (i, state) in states.iter().enumerate() {
+            assert_eq!(state.history().len(), i + 1);
+        }
+        assert_eq!(states[3].result(), game.result());
+        assert_eq!(states[1].to_position_string(), "B/1R/3 B");
+    }
+
+    #[test]
+    fn test_seek_and_prev() {
+        let game = sample_game();
+        let mut replay = game.replay();
+        assert_eq!(replay.len(), 4);
+        assert!(replay.prev().is_none());
+
+        replay.seek(3);
+        assert_eq!(replay.moves_played(), 3);
+        assert_eq!(
+            replay.current().to_position_string(),
+            game.to_position_string()
+        );
+
+        let previous = replay.prev().unwrap().to_position_string();
+        assert_eq!(previous, "B/1R/3 B");
+        assert_eq!(replay.moves_played(), 2);
+
+        replay.seek(100);
+        assert_eq!(replay.moves_played(), 4);
+        assert!(replay.current().check_game_over());
+        assert!(replay.next().is_none());
+    }
+
+    #[test]
+    fn test_empty_history() {
+        let game = GameY::new(3);
+        let mut replay = game.replay();
+        assert!(replay.is_empty());
+        assert!(replay.next().is_none());
+        assert_eq!(replay.current().available_cells().len(), 6);
+    }
+
+    #[test]
+    fn test_preview_line_follows_the_turn_order() {
+        let game = GameY::new(3).with_opening_rule(OpeningRule::OneTwoTwo);
+        let line = [3, 4, 5, 0];
+        let positions = game.preview_line(&line).unwrap();
+        let players: Vec<u32> = positions
+            .iter()
+            .map(|position| match position.history().last() {
+                Some(Movement::Placement { player, .. }) => player.id(),
+                other => panic!("unexpected move {:?}", other),
+            })
+            .collect();
+        assert_eq!(players, vec![0, 1, 1, 0]);
+        assert_eq!(positions[1].history()[..1], positions[0].history()[..]);
+        assert!(game.history().is_empty());
+    }
+
+    #[test]
+    fn test_preview_line_rejects_stale_lines() {
+        let game = sample_game();
+        assert!(matches!(
+            game.preview_line(&[0]),
+            Err(GameYError::GameOver { .. })
+        ));
+        let game = GameY::new(3);
+        assert!(matches!(
+            game.preview_line(&[1, 6]),
+            Err(GameYError::InvalidCellIndex {
+                index: 6,
+                total_cells: 6
+            })
+        ));
+        assert!(matches!(
+            game.preview_line(&[1, 1]),
+            Err(GameYError::Occupied { .. })
+        ));
+        assert!(game.preview_line(&[]).unwrap().is_empty());
+    }
+}
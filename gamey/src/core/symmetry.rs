@@ -0,0 +1,224 @@
+//! Symmetries of the triangular board.
+//!
+//! The board has six symmetries: the identity, two rotations and three
+//! reflections. In barycentric coordinates each of them is a permutation of
+//! the (x, y, z) components.
+
+use crate::{Coordinates, GameY, Movement};
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// One of the six symmetries of the triangular board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Symmetry {
+    /// Leaves every cell in place.
+    Identity,
+    /// Rotation by 120 degrees: (x, y, z) -> (z, x, y).
+    Rotate120,
+    /// Rotation by 240 degrees: (x, y, z) -> (y, z, x).
+    Rotate240,
+    /// Reflection that swaps sides A and B: (x, y, z) -> (y, x, z).
+    ReflectAB,
+    /// Reflection that swaps sides B and C: (x, y, z) -> (x, z, y).
+    ReflectBC,
+    /// Reflection that swaps sides A and C: (x, y, z) -> (z, y, x).
+    ReflectAC,
+}
+
+impl Symmetry {
+    /// All six symmetries, starting with the identity.
+    pub const ALL: [Symmetry; 6] = [
+        Symmetry::Identity,
+        Symmetry::Rotate120,
+        Symmetry::Rotate240,
+        Symmetry::ReflectAB,
+        Symmetry::ReflectBC,
+        Symmetry::ReflectAC,
+    ];
+
+    /// Maps a cell to its image under this symmetry.
+    pub fn transform(&self, coords: Coordinates) -> Coordinates {
+        let (x, y, z) = (coords.x(), coords.y(), coords.z());
+        match self {
+            Symmetry::Identity => coords,
+            Symmetry::Rotate120 => Coordinates::new(z, x, y),
+            Symmetry::Rotate240 => Coordinates::new(y, z, x),
+            Symmetry::ReflectAB => Coordinates::new(y, x, z),
+            Symmetry::ReflectBC => Coordinates::new(x, z, y),
+            Symmetry::ReflectAC => Coordinates::new(z, y, x),
+        }
+    }
+
+    /// Maps every cell of `coords` to its image under this symmetry.
+    pub fn transform_all<I>(&self, coords: I) -> Vec<Coordinates>
+    where
+        I: IntoIterator<Item = Coordinates>,
+    {
+        coords.into_iter().map(|c| self.transform(c)).collect()
+    }
+
+    /// Returns the symmetry that undoes this one.
+    pub fn inverse(&self) -> Symmetry {
+        match self {
+            Symmetry::Rotate120 => Symmetry::Rotate240,
+            Symmetry::Rotate240 => Symmetry::Rotate120,
+            other => *other,
+        }
+    }
+}
+
+impl GameY {
+    /// Returns a copy of the game with every move and blocked cell mapped
+    /// through `symmetry`.
+    ///
+    /// The history is replayed on a fresh board under the same opening rule,
+    /// so the result has the same move order, status and side to move as the
+    /// original.
+    pub fn transformed(&self, symmetry: Symmetry) -> GameY {
+        let size = self.board_size();
+        let mut game = GameY::new(size).with_opening_rule(self.opening_rule());
+        if !self.blocked_cells().is_empty() {
+            let blocked = self.blocked_cells().iter().map(|&index| {
+                symmetry
+                    .transform(Coordinates::from_index(index, size))
+                    .to_index(size)
+            });
+            game.set_blocked_cells(blocked.collect());
+        }
+        for movement in self.history() {
+            let movement = match movement {
+                Movement::Placement { player, coords } => Movement::Placement {
+                    player: *player,
+                    coords: symmetry.transform(*coords),
+                },
+                action => action.clone(),
+            };
+            game.replay_move(movement)
+                .expect("a symmetric image of a legal game is legal");
+        }
+        if let Some(next_player) = self.next_player() {
+            game.set_next_player(next_player);
+        }
+        game.set_setup(self.setup());
+        game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameYBuilder, OpeningRule, PlayerId};
+
+    #[test]
+    fn test_transform_keeps_cells_on_board() {
+        let size = 5;
+        for symmetry in Symmetry::ALL {
+            for idx in 0..15 {
+                let coords = Coordinates::from_index(idx, size);
+                assert!(symmetry.transform(coords).is_valid(size));
+            }
+        }
+    }
+
+    #[test]
+    fn test_transform_is_a_permutation() {
+        let size = 4;
+        let cells: Vec<Coordinates> = (0..10).map(|i| Coordinates::from_index(i, size)).collect();
+        for symmetry in Symmetry::ALL {
+            let mut image = symmetry.transform_all(cells.clone());
+            image.sort_by_key(|c| c.to_index(size));
+            assert_eq!(image, cells, "{:?} is not a permutation", symmetry);
+        }
+    }
+
+    #[test]
+    fn test_inverse_undoes_transform() {
+        let coords = Coordinates::new(3, 1, 0);
+        for symmetry in Symmetry::ALL {
+            let back = symmetry.inverse().transform(symmetry.transform(coords));
+            assert_eq!(back, coords, "{:?}", symmetry);
+        }
+    }
+
+    #[test]
+    fn test_rotation_moves_corners() {
+        let top = Coordinates::new(2, 0, 0);
+        assert_eq!(
+            Symmetry::Rotate120.transform(top),
+            Coordinates::new(0, 2, 0)
+        );
+        assert_eq!(
+            Symmetry::Rotate240.transform(top),
+            Coordinates::new(0, 0, 2)
+        );
+    }
+
+    #[test]
+    fn test_transformed_game_preserves_state() {
+        let game = GameYBuilder::new(4)
+            .with_stone(PlayerId::new(0), Coordinates::new(3, 0, 0))
+            .with_stone(PlayerId::new(1), Coordinates::new(1, 1, 1))
+            .with_starting_player(PlayerId::new(1))
+            .build()
+            .unwrap();
+
+        let rotated = game.transformed(Symmetry::Rotate120);
+        assert_eq!(rotated.history().len(), 2);
+        assert_eq!(rotated.next_player(), Some(PlayerId::new(1)));
+        assert_eq!(
+            rotated.cell_owner(&Coordinates::new(0, 3, 0)),
+            Some(PlayerId::new(0))
+        );
+        assert_eq!(
+            rotated.cell_owner(&Coordinates::new(1, 1, 1)),
+            Some(PlayerId::new(1))
+        );
+
+        let back = rotated.transformed(Symmetry::Rotate240);
+        assert_eq!(back.to_position_string(), game.to_position_string());
+    }
+
+    #[test]
+    fn test_transformed_game_keeps_the_opening_rule() {
+        let mut game = GameY::new(4).with_opening_rule(OpeningRule::OneTwoTwo);
+        for (player, coords) in [
+            (0, Coordinates::new(3, 0, 0)),
+            (1, Coordinates::new(1, 1, 1)),
+        ] {
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(player),
+                coords,
+            })
+            .unwrap();
+        }
+
+        for symmetry in Symmetry::ALL {
+            let mut image = game.transformed(symmetry);
+            assert_eq!(image.opening_rule(), OpeningRule::OneTwoTwo);
+            // Red places its second stone, then Blue places two
+            let play = |image: &mut GameY, player: u32, coords: Coordinates| {
+                image
+                    .add_move(Movement::Placement {
+                        player: PlayerId::new(player),
+                        coords: symmetry.transform(coords),
+                    })
+                    .unwrap();
+            };
+            play(&mut image, 1, Coordinates::new(0, 3, 0));
+            play(&mut image, 0, Coordinates::new(0, 0, 3));
+            assert_eq!(image.next_player(), Some(PlayerId::new(0)));
+        }
+    }
+
+    #[test]
+    fn test_transformed_finished_game_keeps_winner() {
+        let game = GameY::from_position_string("B/BR/BR1 R").unwrap();
+        for symmetry in Symmetry::ALL {
+            let image = game.transformed(symmetry);
+            assert_eq!(image.result(), game.result(), "{:?}", symmetry);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Board topologies and win conditions for connection games.
+//!
+//! A [`BoardTopology`] describes the cells of a board, how they are connected
+//! and which edges each of them lies on. A [`WinCondition`] decides, given the
+//! cells owned by a player, whether that player has won. Together they let
+//! search code such as [`MinimaxState`](crate::MinimaxState) work on any
+//! connection game without knowing its rules.
+
+use crate::{Coordinates, GameY, game};
+use alloc::vec::Vec;
+use fixedbitset::FixedBitSet;
+use smallvec::SmallVec;
+
+/// The neighbours of a cell, as cell indices.
+pub type Neighbors = SmallVec<[usize; 6]>;
+
+/// The shape of a board: its cells, their adjacency and their edge labels.
+///
+/// Cells are identified by index, from 0 to `num_cells() - 1`.
+pub trait BoardTopology {
+    /// Returns the number of cells on the board.
+    fn num_cells(&self) -> usize;
+
+    /// Returns the indices of the cells adjacent to `cell`.
+    fn neighbors(&self, cell: usize) -> Neighbors;
+
+    /// Returns the edges `cell` lies on as a bit mask, one bit per edge.
+    ///
+    /// Interior cells return 0.
+    fn edge_labels(&self, cell: usize) -> u8;
+}
+
+/// A rule deciding whether a set of cells wins the game.
+pub trait WinCondition: Send + Sync {
+    /// Returns true if a player owning exactly the cells in `owned` has won.
+    fn is_win(&self, topology: &dyn BoardTopology, owned: &FixedBitSet) -> bool;
+}
+
+/// Wins by connecting a given set of edges with a single group of stones.
+///
+/// The game of Y uses all three sides of the triangle; Hex would use two
+/// opposite sides per player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectEdges {
+    edges: u8,
+}
+
+impl ConnectEdges {
+    /// Creates a condition requiring a group that touches every edge in `edges`.
+    pub fn new(edges: u8) -> Self {
+        Self { edges }
+    }
+
+    /// The win condition of the game of Y: connect all three sides.
+    pub fn all_sides_of_y() -> Self {
+        Self::new(game::ALL_SIDES)
+    }
+}
+
+impl WinCondition for ConnectEdges {
+    fn is_win(&self, topology: &dyn BoardTopology, owned: &FixedBitSet) -> bool {
+        // Every winning group touches an edge, so only groups reached from an
+        // edge cell are explored, and each stops as soon as it connects
+        let mut visited = FixedBitSet::with_capacity(topology.num_cells());
+        let mut stack = Vec::new();
+        for start in owned.ones() {
+            if visited.contains(start) || topology.edge_labels(start) == 0 {
+                continue;
+            }
+            visited.insert(start);
+            stack.push(start);
+            let mut reached = 0;
+            while let Some(cell) = stack.pop() {
+                reached |= topology.edge_labels(cell);
+                if reached & self.edges == self.edges {
+                    return true;
+                }
+                for neighbor in topology.neighbors(cell) {
+                    if owned.contains(neighbor) && !visited.contains(neighbor) {
+                        visited.insert(neighbor);
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Returns the cells reachable from `starts` by moving between adjacent
+/// cells of `passable`.
+///
+/// Start cells outside `passable` are ignored.
+pub fn flood_fill<I>(topology: &dyn BoardTopology, starts: I, passable: &FixedBitSet) -> FixedBitSet
+where
+    I: IntoIterator<Item = usize>,
+{
+    let mut reached = FixedBitSet::with_capacity(topology.num_cells());
+    let mut stack: Vec<usize> = starts
+        .into_iter()
+        .filter(|&cell| passable.contains(cell))
+        .collect();
+    for &cell in &stack {
+        reached.insert(cell);
+    }
+
+    while let Some(cell) = stack.pop() {
+        for neighbor in topology.neighbors(cell) {
+            if passable.contains(neighbor) && !reached.contains(neighbor) {
+                reached.insert(neighbor);
+                stack.push(neighbor);
+            }
+        }
+    }
+    reached
+}
+
+/// Splits `cells` into groups of adjacent cells.
+pub fn connected_groups(topology: &dyn BoardTopology, cells: &FixedBitSet) -> Vec<Vec<usize>> {
+    let mut assigned = FixedBitSet::with_capacity(topology.num_cells());
+    let mut groups = Vec::new();
+    for cell in cells.ones() {
+        if assigned.contains(cell) {
+            continue;
+        }
+        let group = flood_fill(topology, [cell], cells);
+        assigned.union_with(&group);
+        groups.push(group.ones().collect());
+    }
+    groups
+}
+
+impl BoardTopology for GameY {
+    fn num_cells(&self) -> usize {
+        self.total_cells() as usize
+    }
+
+    fn neighbors(&self, cell: usize) -> Neighbors {
+        let board_size = self.board_size();
+        let coords = Coordinates::from_index(cell as u32, board_size);
+        self.get_neighbors(&coords)
+            .iter()
+            .map(|neighbor| neighbor.to_index(board_size) as usize)
+            .collect()
+    }
+
+    fn edge_labels(&self, cell: usize) -> u8 {
+        game::cell_sides(&Coordinates::from_index(cell as u32, self.board_size()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(num_cells: usize, owned: &[usize]) -> FixedBitSet {
+        let mut set = FixedBitSet::with_capacity(num_cells);
+        for &cell in owned {
+            set.insert(cell);
+        }
+        set
+    }
+
+    #[test]
+    fn test_game_y_topology() {
+        let game = GameY::new(3);
+        assert_eq!(game.num_cells(), 6);
+        // The top corner touches sides B and C, the centre of the bottom row only A
+        assert_eq!(game.edge_labels(0), 0b110);
+        assert_eq!(game.edge_labels(4), 0b001);
+        let mut neighbors = game.neighbors(0).to_vec();
+        neighbors.sort();
+        assert_eq!(neighbors, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_connected_groups() {
+        let game = GameY::new(4);
+        // Indices 0 and 1 are adjacent, 9 is the far corner
+        let groups = connected_groups(&game, &cells(10, &[0, 1, 9]));
+        assert_eq!(groups, vec![vec![0, 1], vec![9]]);
+    }
+
+    #[test]
+    fn test_connect_edges_on_y() {
+        let game = GameY::new(3);
+        let rule = ConnectEdges::all_sides_of_y();
+        // A column down side B: top corner to bottom-left corner
+        assert!(rule.is_win(&game, &cells(6, &[0, 1, 3])));
+        assert!(!rule.is_win(&game, &cells(6, &[0, 3])));
+        assert!(!rule.is_win(&game, &cells(6, &[])));
+    }
+
+    #[test]
+    fn test_flood_fill_ignores_impassable_starts() {
+        let game = GameY::new(3);
+        let reached = flood_fill(&game, [0, 5], &cells(6, &[0, 2, 5]));
+        assert_eq!(reached.ones().collect::<Vec<_>>(), vec![0, 2, 5]);
+        let reached = flood_fill(&game, [1], &cells(6, &[0, 2]));
+        assert_eq!(reached.count_ones(..), 0);
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt;
+
+use crate::{
+    Coordinates, GameY, MinimaxState, Movement, PlayerId, Result, SearchLimits, WIN_SCORE,
+    search_scored,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Scores at least this close to [`WIN_SCORE`] are treated as forced wins.
+const DECISIVE_MARGIN: i32 = 100;
+
+/// How much a move gave away compared to the best move of the engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum MoveQuality {
+    /// The move lost less than an inaccuracy.
+    Good,
+    /// The move lost a little.
+    Inaccuracy,
+    /// The move lost a clear advantage.
+    Mistake,
+    /// The move threw away a won position or lost by a wide margin.
+    Blunder,
+}
+
+impl MoveQuality {
+    /// Returns the usual annotation symbol: `?!`, `?` or `??`, and an empty
+    /// string for good moves.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            MoveQuality::Good => "",
+            MoveQuality::Inaccuracy => "?!",
+            MoveQuality::Mistake => "?",
+            MoveQuality::Blunder => "??",
+        }
+    }
+}
+
+impl fmt::Display for MoveQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            MoveQuality::Good => "good",
+            MoveQuality::Inaccuracy => "inaccuracy",
+            MoveQuality::Mistake => "mistake",
+            MoveQuality::Blunder => "blunder",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// The review of one placement of a game.
+///
+/// Scores are in the units of the minimax evaluation, from the point of view
+/// of the player who made the move.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MoveAnnotation {
+    /// Index of the move in the history of the game.
+    pub ply: usize,
+    /// The player who made the move.
+    pub player: PlayerId,
+    /// The cell played.
+    pub coords: Coordinates,
+    /// The move the engine preferred.
+    pub best_move: Option<Coordinates>,
+    /// Score of the position before the move, with the best move.
+    pub best_score: i32,
+    /// Score of the position after the move played.
+    pub played_score: i32,
+    /// Score given away by the move, never negative.
+    pub loss: i32,
+    /// The verdict on the move.
+    pub quality: MoveQuality,
+}
+
+/// Search depth and thresholds used by [`annotate_game`].
+///
+/// The search depth is fixed rather than timed, so that a review of the same
+/// game always gives the same annotations. The thresholds are score losses; a move that turns a forced win into
+/// anything else, or anything else into a forced loss, is always a blunder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnnotationOptions {
+    /// Depth, in plies, of the search for the best move of each position.
+    /// Depths below 2 are raised to 2.
+    pub depth: u8,
+    /// Smallest loss tagged as an inaccuracy.
+    pub inaccuracy: i32,
+    /// Smallest loss tagged as a mistake.
+    pub mistake: i32,
+    /// Smallest loss tagged as a blunder.
+    pub blunder: i32,
+}
+
+impl Default for AnnotationOptions {
+    fn default() -> Self {
+        Self {
+            depth: 4,
+            inaccuracy: 50,
+            mistake: 150,
+            blunder: 400,
+        }
+    }
+}
+
+impl AnnotationOptions {
+    /// Returns the verdict on a move that scored `played_score` where the
+    /// best move scored `best_score`.
+    pub fn classify(&self, best_score: i32, played_score: i32) -> MoveQuality {
+        let decisive = WIN_SCORE - DECISIVE_MARGIN;
+        let threw_win = best_score >= decisive && played_score < decisive;
+        let into_loss = played_score <= -decisive && best_score > -decisive;
+        let loss = best_score - played_score;
+        if threw_win || into_loss || loss >= self.blunder {
+            MoveQuality::Blunder
+        } else if loss >= self.mistake {
+            MoveQuality::Mistake
+        } else if loss >= self.inaccuracy {
+            MoveQuality::Inaccuracy
+        } else {
+            MoveQuality::Good
+        }
+    }
+}
+
+/// The engine's view of a position.
+struct Evaluation {
+    /// The score for the side to move, or the result if the game is over.
+    score: i32,
+    side_to_move: Option<PlayerId>,
+    best_move: Option<Coordinates>,
+}
+
+impl Evaluation {
+    fn of(game: &GameY, depth: u8) -> Self {
+        if let Some(result) = game.result() {
+            return Evaluation {
+                score: WIN_SCORE,
+                side_to_move: Some(result.winner),
+                best_move: None,
+            };
+        }
+        let Some(player) = game.next_player() else {
+            return Evaluation {
+                score: 0,
+                side_to_move: None,
+                best_move: None,
+            };
+        };
+        let mut state = MinimaxState::new(game, player);
+        let limits = SearchLimits {
+            max_depth: Some(depth),
+            ..SearchLimits::default()
+        };
+        match search_scored(&mut state, &limits) {
+            Some((idx, score)) => Evaluation {
+                score,
+                side_to_move: Some(player),
+                best_move: Some(Coordinates::from_index(idx as u32, game.board_size())),
+            },
+            None => Evaluation {
+                score: 0,
+                side_to_move: Some(player),
+                best_move: None,
+            },
+        }
+    }
+
+    fn score_for(&self, player: PlayerId) -> i32 {
+        match self.side_to_move {
+            Some(side) if side == player => self.score,
+            Some(_) => -self.score,
+            None => 0,
+        }
+    }
+}
+
+/// Replays a game and tags every placement that gave away score.
+///
+/// Each placement is judged by the score it gives away compared to the best
+/// move of a search of the position it was played from. The position after
+/// the move is searched one ply less deep, so that both scores look equally
+/// far ahead. Playing the engine's own choice is always good. Actions such as swap or
+/// resign are replayed but not annotated.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{AnnotationOptions, Coordinates, GameY, Movement, PlayerId, annotate_game};
+///
+/// let mut game = GameY::new(3);
+/// game.add_move(Movement::Placement {
+///     player: PlayerId::new(0),
+///     coords: Coordinates::new(1, 1, 0),
+/// })
+/// .unwrap();
+///
+/// let annotations = annotate_game(&game, &AnnotationOptions::default()).unwrap();
+/// assert_eq!(annotations.len(), 1);
+/// ```
+pub fn annotate_game(game: &GameY, options: &AnnotationOptions) -> Result<Vec<MoveAnnotation>> {
+    let depth = options.depth.max(2);
+    let mut position = game.cleared();
+    let mut annotations = Vec::new();
+    for (ply, movement) in game.history().iter().enumerate() {
+        let Movement::Placement { player, coords } = movement else {
+            position.replay_move(movement.clone())?;
+            continue;
+        };
+        let before = Evaluation::of(&position, depth);
+        position.replay_move(movement.clone())?;
+        let after = Evaluation::of(&position, depth - 1);
+        {
+            let best_score = before.score_for(*player);
+            let played_score = after.score_for(*player);
+            let quality = if before.best_move == Some(*coords) {
+                MoveQuality::Good
+            } else {
+                options.classify(best_score, played_score)
+            };
+            annotations.push(MoveAnnotation {
+                ply,
+                player: *player,
+                coords: *coords,
+                best_move: before.best_move,
+                best_score,
+                played_score,
+                loss: (best_score - played_score).max(0),
+                quality,
+            });
+        }
+    }
+    Ok(annotations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameAction, LOSE_SCORE};
+
+    fn game_from(size: u32, moves: &[(u32, u32, u32)]) -> GameY {
+        let mut game = GameY::new(size);
+        for (i, &(x, y, z)) in moves.iter().enumerate() {
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(i as u32 % 2),
+                coords: Coordinates::new(x, y, z),
+            })
+            .unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_classify_thresholds() {
+        let options = AnnotationOptions::default();
+        assert_eq!(options.classify(100, 80), MoveQuality::Good);
+        assert_eq!(options.classify(100, 50), MoveQuality::Inaccuracy);
+        assert_eq!(options.classify(100, -50), MoveQuality::Mistake);
+        assert_eq!(options.classify(100, -300), MoveQuality::Blunder);
+        assert_eq!(options.classify(WIN_SCORE, 0), MoveQuality::Blunder);
+        assert_eq!(options.classify(-10, LOSE_SCORE), MoveQuality::Blunder);
+        assert_eq!(options.classify(LOSE_SCORE, LOSE_SCORE), MoveQuality::Good);
+    }
+
+    #[test]
+    fn test_missing_the_only_block_is_a_blunder() {
+        // Player 1 lets player 0 join its stones at the centre, which makes
+        // two threats on the bottom side
+        let game = game_from(4, &[(1, 0, 2), (3, 0, 0), (1, 2, 0), (0, 1, 2)]);
+        let annotations = annotate_game(&game, &AnnotationOptions::default()).unwrap();
+        let missed = &annotations[3];
+        assert_eq!(missed.quality, MoveQuality::Blunder);
+        assert_ne!(missed.best_move, Some(missed.coords));
+        assert_eq!(missed.best_move, Some(Coordinates::new(1, 1, 1)));
+        assert!(missed.played_score <= -(WIN_SCORE - DECISIVE_MARGIN));
+    }
+
+    #[test]
+    fn test_winning_move_is_good_and_actions_are_skipped() {
+        let game = game_from(2, &[(1, 0, 0), (0, 1, 0), (0, 0, 1)]);
+        let annotations = annotate_game(&game, &AnnotationOptions::default()).unwrap();
+        assert_eq!(annotations.len(), 3);
+        let last = &annotations[2];
+        assert_eq!(last.quality, MoveQuality::Good);
+        assert_eq!(last.played_score, WIN_SCORE);
+
+        let mut resigned = game_from(3, &[(1, 1, 0)]);
+        resigned
+            .add_move(Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Resign,
+            })
+            .unwrap();
+        let annotations = annotate_game(&resigned, &AnnotationOptions::default()).unwrap();
+        assert_eq!(annotations.len(), 1);
+    }
+
+    #[test]
+    fn test_quality_symbols() {
+        assert_eq!(MoveQuality::Blunder.symbol(), "??");
+        assert_eq!(MoveQuality::Mistake.to_string(), "mistake");
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "serde")]
+use std::path::Path;
+
+#[cfg(feature = "serde")]
+use crate::GameYError;
+use crate::{Coordinates, GameY, Movement, Result};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How often a move was played from a position, and how those games ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MoveStats {
+    /// The cell played, in the orientation of the queried position.
+    pub coords: Coordinates,
+    /// Number of games in which the move was played.
+    pub games: u32,
+    /// Games won by the player who made the move.
+    pub wins: u32,
+    /// Games lost by the player who made the move.
+    pub losses: u32,
+}
+
+impl MoveStats {
+    /// Returns the share of decided games won by the player who made the
+    /// move, or None if none of the games was decided.
+    pub fn win_rate(&self) -> Option<f64> {
+        let decided = self.wins + self.losses;
+        (decided > 0).then(|| f64::from(self.wins) / f64::from(decided))
+    }
+}
+
+/// What the database knows about a position.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PositionStats {
+    /// Number of games that continued from the position with a placement.
+    pub games: u32,
+    /// The moves played from the position, most played first.
+    pub moves: Vec<MoveStats>,
+}
+
+impl PositionStats {
+    /// Returns true if no game in the database continued from the position.
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct MoveEntry {
+    games: u32,
+    wins: u32,
+    losses: u32,
+}
+
+/// A collection of game records indexed by position.
+///
+/// Every position reached in an added game is stored under its
+/// [canonical hash](GameY::canonical_hash), so games that reach the same
+/// position through a different move order or in a rotated or reflected
+/// orientation share their statistics. Moves are stored in the canonical
+/// orientation and mapped back to the orientation of the position when
+/// queried.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameDatabase, GameY, Movement, PlayerId};
+///
+/// let mut game = GameY::new(3);
+/// game.add_move(Movement::Placement {
+///     player: PlayerId::new(0),
+///     coords: Coordinates::new(1, 1, 0),
+/// })
+/// .unwrap();
+///
+/// let mut database = GameDatabase::new();
+/// database.add_game(&game).unwrap();
+/// let stats = database.explore(&GameY::new(3));
+/// assert_eq!(stats.games, 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameDatabase {
+    positions: HashMap<u64, BTreeMap<u32, MoveEntry>>,
+    games: u32,
+    max_depth: Option<usize>,
+}
+
+impl GameDatabase {
+    /// Creates an empty database that indexes every move of every game.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only indexes the first `plies` moves of each game, which is enough
+    /// for an opening explorer and keeps the database small.
+    pub fn with_max_depth(mut self, plies: usize) -> Self {
+        self.max_depth = Some(plies);
+        self
+    }
+
+    /// Returns the number of games added.
+    pub fn game_count(&self) -> u32 {
+        self.games
+    }
+
+    /// Returns the number of distinct positions indexed.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if no position has been indexed.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Adds a game record, replaying its history from an empty board.
+    ///
+    /// Placements are indexed under the position they were played from and
+    /// credited to the winner of the game, if it is finished. Actions such
+    /// as swap or resign are replayed but not indexed.
+    pub fn add_game(&mut self, game: &GameY) -> Result<()> {
+        let size = game.board_size();
+        let winner = game.result().map(|result| result.winner);
+        let depth = self.max_depth.unwrap_or(usize::MAX);
+        let mut position = GameY::new(size);
+        for movement in game.history().iter().take(depth) {
+            if let Movement::Placement { player, coords } = movement {
+                let (hash, symmetries) = position.canonical_position();
+                // A symmetric position has several canonical orientations;
+                // taking the smallest image merges equivalent moves.
+                let canonical_move = symmetries
+                    .iter()
+                    .map(|symmetry| symmetry.transform(*coords).to_index(size))
+                    .min()
+                    .expect("every position has a canonical orientation");
+                let entry = self
+                    .positions
+                    .entry(hash)
+                    .or_default()
+                    .entry(canonical_move)
+                    .or_default();
+                entry.games += 1;
+                match winner {
+                    Some(winner) if winner == *player => entry.wins += 1,
+                    Some(_) => entry.losses += 1,
+                    None => {}
+                }
+            }
+            position.replay_move(movement.clone())?;
+        }
+        self.games += 1;
+        Ok(())
+    }
+
+    /// Adds several game records, stopping at the first invalid one.
+    pub fn add_games<'a, I>(&mut self, games: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a GameY>,
+    {
+        games.into_iter().try_for_each(|game| self.add_game(game))
+    }
+
+    /// Returns the moves played from the position of `game`, with their
+    /// results, in the orientation of `game`.
+    pub fn explore(&self, game: &GameY) -> PositionStats {
+        let size = game.board_size();
+        let (hash, symmetries) = game.canonical_position();
+        let Some(moves) = self.positions.get(&hash) else {
+            return PositionStats::default();
+        };
+        let to_game = symmetries[0].inverse();
+        let mut moves: Vec<MoveStats> = moves
+            .iter()
+            .filter(|(index, _)| **index < game.total_cells())
+            .map(|(index, entry)| MoveStats {
+                coords: to_game.transform(Coordinates::from_index(*index, size)),
+                games: entry.games,
+                wins: entry.wins,
+                losses: entry.losses,
+            })
+            // Guards against the unlikely collision of two position hashes
+            .filter(|stats| game.cell_owner(&stats.coords).is_none())
+            .collect();
+        moves.sort_by_key(|stats| (std::cmp::Reverse(stats.games), stats.coords.to_index(size)));
+        PositionStats {
+            games: moves.iter().map(|stats| stats.games).sum(),
+            moves,
+        }
+    }
+
+    /// Loads a database saved with [`GameDatabase::save_to_file`].
+    #[cfg(feature = "serde")]
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let filename = path.as_ref().display().to_string();
+        let content = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to read file: {}", filename),
+            error: e.to_string(),
+        })?;
+        serde_json::from_str(&content).map_err(|e| GameYError::SerdeError { error: e })
+    }
+
+    /// Saves the database to a JSON file.
+    #[cfg(feature = "serde")]
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let content =
+            serde_json::to_string(self).map_err(|e| GameYError::SerdeError { error: e })?;
+        let filename = path.as_ref().display().to_string();
+        std::fs::write(path, content).map_err(|e| GameYError::IoError {
+            message: format!("Failed to write file: {}", filename),
+            error: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameAction, PlayerId, Symmetry};
+
+    fn game_from(size: u32, moves: &[(u32, u32, u32)]) -> GameY {
+        let mut game = GameY::new(size);
+        for (i, &(x, y, z)) in moves.iter().enumerate() {
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(i as u32 % 2),
+                coords: Coordinates::new(x, y, z),
+            })
+            .unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_counts_moves_and_results() {
+        // Player 0 wins on a board of size 2
+        let won = game_from(2, &[(1, 0, 0), (0, 1, 0), (0, 0, 1)]);
+        let mut resigned = game_from(2, &[(1, 0, 0)]);
+        resigned
+            .add_move(Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Resign,
+            })
+            .unwrap();
+        let unfinished = game_from(2, &[(0, 1, 0)]);
+
+        let mut database = GameDatabase::new();
+        database.add_games([&won, &resigned, &unfinished]).unwrap();
+        assert_eq!(database.game_count(), 3);
+
+        let stats = database.explore(&GameY::new(2));
+        assert_eq!(stats.games, 3);
+        // The top cell and the bottom-left cell are equivalent on an empty
+        // board, so all three games played the same move
+        assert_eq!(stats.moves.len(), 1);
+        let first = stats.moves[0];
+        assert_eq!((first.games, first.wins, first.losses), (3, 2, 0));
+        assert_eq!(first.win_rate(), Some(1.0));
+    }
+
+    #[test]
+    fn test_transpositions_share_statistics() {
+        let a = game_from(4, &[(3, 0, 0), (0, 3, 0), (1, 1, 1), (0, 0, 3)]);
+        let b = game_from(4, &[(1, 1, 1), (0, 0, 3), (3, 0, 0), (0, 3, 0)]);
+        let mut database = GameDatabase::new();
+        database.add_games([&a, &b]).unwrap();
+
+        let position = game_from(4, &[(3, 0, 0), (0, 3, 0)]);
+        let after_b = game_from(4, &[(1, 1, 1), (0, 0, 3)]);
+        let stats = database.explore(&position);
+        assert_eq!(stats.games, 1);
+        assert_eq!(stats.moves[0].coords, Coordinates::new(1, 1, 1));
+        // The position is symmetric, so either mirror image of the move may be
+        // returned
+        let reply = database.explore(&after_b).moves[0].coords;
+        assert!([Coordinates::new(3, 0, 0), Coordinates::new(0, 3, 0)].contains(&reply));
+
+        // After three moves both games reach mirror images of each other
+        let stats = database.explore(&game_from(4, &[(3, 0, 0), (0, 3, 0), (1, 1, 1)]));
+        assert_eq!(stats.games, 2);
+        assert_eq!(stats.moves.len(), 1);
+    }
+
+    #[test]
+    fn test_moves_are_returned_in_the_queried_orientation() {
+        let game = game_from(4, &[(2, 1, 0), (0, 1, 2)]);
+        let mut database = GameDatabase::new();
+        database.add_game(&game).unwrap();
+
+        let opening = game_from(4, &[(2, 1, 0)]);
+        for symmetry in Symmetry::ALL {
+            let rotated = opening.transformed(symmetry);
+            let stats = database.explore(&rotated);
+            assert_eq!(stats.moves.len(), 1, "{:?}", symmetry);
+            let reply = stats.moves[0].coords;
+            let mut continued = rotated.clone();
+            continued
+                .add_move(Movement::Placement {
+                    player: PlayerId::new(1),
+                    coords: reply,
+                })
+                .unwrap();
+            assert_eq!(continued.canonical_hash(), game.canonical_hash());
+        }
+    }
+
+    #[test]
+    fn test_max_depth_limits_indexed_moves() {
+        let game = game_from(4, &[(3, 0, 0), (0, 3, 0), (1, 1, 1)]);
+        let mut database = GameDatabase::new().with_max_depth(2);
+        database.add_game(&game).unwrap();
+        assert_eq!(database.len(), 2);
+        assert!(
+            database
+                .explore(&game_from(4, &[(3, 0, 0), (0, 3, 0)]))
+                .is_empty()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("games.json");
+        let mut database = GameDatabase::new();
+        database
+            .add_game(&game_from(3, &[(2, 0, 0), (0, 1, 1)]))
+            .unwrap();
+        database.save_to_file(&path).unwrap();
+
+        let loaded = GameDatabase::load_from_file(&path).unwrap();
+        assert_eq!(loaded.game_count(), 1);
+        assert_eq!(
+            loaded.explore(&GameY::new(3)),
+            database.explore(&GameY::new(3))
+        );
+    }
+}
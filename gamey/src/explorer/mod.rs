@@ -0,0 +1,15 @@
+//! Game databases and opening exploration.
+//!
+//! - [`GameDatabase`]: Game records indexed by canonical position, answering
+//!   which moves were played from a position and how those games ended
+//! - [`annotate_game`]: Post-game review tagging blunders, mistakes and
+//!   inaccuracies (`bots` feature)
+//! - [`PositionStats`] and [`MoveStats`]: The answers to such queries
+
+#[cfg(feature = "bots")]
+pub mod game_annotation;
+pub mod game_database;
+
+#[cfg(feature = "bots")]
+pub use game_annotation::*;
+pub use game_database::*;
@@ -0,0 +1,389 @@
+//! C interface for embedding the engine in other languages.
+//!
+//! Enabled by the `ffi` feature. Build the crate as a `cdylib` with
+//! `cargo rustc --lib --release --features ffi --crate-type cdylib`; the
+//! matching header is `include/gamey.h`, generated with
+//! [cbindgen](https://github.com/mozilla/cbindgen) from this module.
+//!
+//! Games are opaque [`GameyGame`] handles created with [`gamey_game_new`]
+//! and released with [`gamey_game_free`]. Cells are board indices and
+//! players are 0 and 1. Functions that can fail return a [`GameyStatus`].
+//!
+//! No panic crosses the boundary: every entry point catches panics and
+//! reports them as [`GameyStatus::Panic`] (or the documented fallback value).
+
+use std::ffi::{CStr, c_char};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{Coordinates, GameY, GameYError, MinimaxBot, Movement, PlayerId, YBot};
+
+/// Result of a call to the C interface.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameyStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = -1,
+    /// The cell index is outside the board, or the cell is blocked.
+    InvalidCell = -2,
+    /// The cell is already occupied.
+    OccupiedCell = -3,
+    /// The game is over, so no more moves can be made.
+    GameOver = -4,
+    /// There is no move to undo.
+    NothingToUndo = -5,
+    /// The bot found no move to play.
+    NoMove = -6,
+    /// The move was rejected for another reason.
+    IllegalMove = -7,
+    /// The engine panicked. The game should be considered unusable.
+    Panic = -99,
+}
+
+impl From<GameYError> for GameyStatus {
+    fn from(error: GameYError) -> Self {
+        match error {
+            GameYError::InvalidCellIndex { .. }
+            | GameYError::InvalidCoordinates { .. }
+            | GameYError::BlockedCell { .. } => GameyStatus::InvalidCell,
+            GameYError::Occupied { .. } => GameyStatus::OccupiedCell,
+            GameYError::GameOver { .. } => GameyStatus::GameOver,
+            GameYError::NoMoveToUndo => GameyStatus::NothingToUndo,
+            _ => GameyStatus::IllegalMove,
+        }
+    }
+}
+
+/// An opaque game of Y owned by the caller.
+pub struct GameyGame {
+    game: GameY,
+}
+
+/// Runs `f`, turning a panic into `fallback`.
+fn guard<T>(fallback: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(fallback)
+}
+
+/// Creates a game with an empty board of the given size.
+///
+/// Returns null if the size is 0 or larger than
+/// [`MAX_RECORD_BOARD_SIZE`](crate::MAX_RECORD_BOARD_SIZE). The game must be
+/// released with [`gamey_game_free`].
+#[unsafe(no_mangle)]
+pub extern "C" fn gamey_game_new(board_size: u32) -> *mut GameyGame {
+    guard(std::ptr::null_mut(), || match GameY::try_new(board_size) {
+        Ok(game) if board_size > 0 => Box::into_raw(Box::new(GameyGame { game })),
+        _ => std::ptr::null_mut(),
+    })
+}
+
+/// Creates a game from a position string such as `1/2/3 B`.
+///
+/// Returns null if the string is null, not valid UTF-8 or not a valid position.
+///
+/// # Safety
+///
+/// `position` must be null or point to a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gamey_game_from_position(position: *const c_char) -> *mut GameyGame {
+    if position.is_null() {
+        return std::ptr::null_mut();
+    }
+    // SAFETY: the caller guarantees a NUL-terminated string
+    let position = unsafe { CStr::from_ptr(position) };
+    guard(std::ptr::null_mut(), || {
+        let Ok(position) = position.to_str() else {
+            return std::ptr::null_mut();
+        };
+        match GameY::from_position_string(position) {
+            Ok(game) => Box::into_raw(Box::new(GameyGame { game })),
+            Err(_) => std::ptr::null_mut(),
+        }
+    })
+}
+
+/// Releases a game. Passing null does nothing.
+///
+/// # Safety
+///
+/// `game` must be null or a pointer returned by this library that has not
+/// been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gamey_game_free(game: *mut GameyGame) {
+    if !game.is_null() {
+        // SAFETY: the pointer comes from Box::into_raw and is freed only once
+        drop(unsafe { Box::from_raw(game) });
+    }
+}
+
+/// Returns the size of the board, or 0 if `game` is null.
+///
+/// # Safety
+///
+/// `game` must be null or a valid game pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gamey_game_board_size(game: *const GameyGame) -> u32 {
+    // SAFETY: the caller guarantees a valid or null pointer
+    unsafe { game.as_ref() }.map_or(0, |g| g.game.board_size())
+}
+
+/// Returns the number of cells on the board, or 0 if `game` is null.
+///
+/// # Safety
+///
+/// `game` must be null or a valid game pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gamey_game_total_cells(game: *const GameyGame) -> u32 {
+    // SAFETY: the caller guarantees a valid or null pointer
+    unsafe { game.as_ref() }.map_or(0, |g| g.game.total_cells())
+}
+
+/// Returns the player to move (0 or 1), or -1 if the game is over or `game`
+/// is null.
+///
+/// # Safety
+///
+/// `game` must be null or a valid game pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gamey_game_next_player(game: *const GameyGame) -> i32 {
+    // SAFETY: the caller guarantees a valid or null pointer
+    unsafe { game.as_ref() }
+        .and_then(|g| g.game.next_player())
+        .map_or(-1, |player| player.id() as i32)
+}
+
+/// Returns the winner (0 or 1), or -1 if the game is not over or `game` is
+/// null.
+///
+/// # Safety
+///
+/// `game` must be null or a valid game pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gamey_game_winner(game: *const GameyGame) -> i32 {
+    // SAFETY: the caller guarantees a valid or null pointer
+    unsafe { game.as_ref() }
+        .and_then(|g| g.game.result())
+        .map_or(-1, |result| result.winner.id() as i32)
+}
+
+/// Returns the owner of a cell (0 or 1), -1 for an empty cell, or -2 if the
+/// index is outside the board or `game` is null.
+///
+/// # Safety
+///
+/// `game` must be null or a valid game pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gamey_game_cell_owner(game: *const GameyGame, index: u32) -> i32 {
+    // SAFETY: the caller guarantees a valid or null pointer
+    let Some(game) = (unsafe { game.as_ref() }) else {
+        return -2;
+    };
+    cell_owner(&game.game, index)
+}
+
+fn cell_owner(game: &GameY, index: u32) -> i32 {
+    if index >= game.total_cells() {
+        return -2;
+    }
+    game.cell_owner(&Coordinates::from_index(index, game.board_size()))
+        .map_or(-1, |player| player.id() as i32)
+}
+
+/// Copies the owner of every cell into `cells`, as [`gamey_game_cell_owner`]
+/// would return them, and returns the number of cells on the board.
+///
+/// At most `len` values are written; call with a buffer of
+/// [`gamey_game_total_cells`] entries to get the whole board. Returns 0 if a
+/// pointer is null.
+///
+/// # Safety
+///
+/// `game` must be null or a valid game pointer, and `cells` must be null or
+/// point to at least `len` writable `int32_t`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gamey_game_board(
+    game: *const GameyGame,
+    cells: *mut i32,
+    len: usize,
+) -> usize {
+    // SAFETY: the caller guarantees a valid or null pointer
+    let Some(game) = (unsafe { game.as_ref() }) else {
+        return 0;
+    };
+    if cells.is_null() {
+        return 0;
+    }
+    // SAFETY: the caller guarantees `len` writable values
+    let cells = unsafe { std::slice::from_raw_parts_mut(cells, len) };
+    guard(0, || {
+        let total = game.game.total_cells();
+        for (index, cell) in (0..total).zip(cells.iter_mut()) {
+            *cell = cell_owner(&game.game, index);
+        }
+        total as usize
+    })
+}
+
+/// Places a stone of the player to move on the cell with the given index.
+///
+/// # Safety
+///
+/// `game` must be null or a valid game pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gamey_game_play(game: *mut GameyGame, index: u32) -> GameyStatus {
+    // SAFETY: the caller guarantees a valid or null pointer
+    let Some(game) = (unsafe { game.as_mut() }) else {
+        return GameyStatus::NullPointer;
+    };
+    guard(GameyStatus::Panic, || match play(&mut game.game, index) {
+        Ok(()) => GameyStatus::Ok,
+        Err(e) => e.into(),
+    })
+}
+
+fn play(game: &mut GameY, index: u32) -> crate::game::Result<()> {
+    let total_cells = game.total_cells();
+    if index >= total_cells {
+        return Err(GameYError::InvalidCellIndex { index, total_cells });
+    }
+    // Once the game is over any player will do: the move is rejected anyway
+    let player = game.next_player().unwrap_or(PlayerId::new(0));
+    let coords = Coordinates::from_index(index, game.board_size());
+    game.add_move(Movement::Placement { player, coords })
+}
+
+/// Takes back the last move.
+///
+/// # Safety
+///
+/// `game` must be null or a valid game pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gamey_game_undo(game: *mut GameyGame) -> GameyStatus {
+    // SAFETY: the caller guarantees a valid or null pointer
+    let Some(game) = (unsafe { game.as_mut() }) else {
+        return GameyStatus::NullPointer;
+    };
+    guard(GameyStatus::Panic, || match game.game.undo_move() {
+        Ok(_) => GameyStatus::Ok,
+        Err(e) => e.into(),
+    })
+}
+
+/// Asks the minimax bot for a move for the player to move, searching for
+/// about `max_time_ms` milliseconds.
+///
+/// On success the chosen cell index is stored in `out_index`; the move is
+/// not played.
+///
+/// # Safety
+///
+/// `game` must be null or a valid game pointer, and `out_index` must be null
+/// or point to a writable `uint32_t`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gamey_bot_move(
+    game: *const GameyGame,
+    max_time_ms: u32,
+    out_index: *mut u32,
+) -> GameyStatus {
+    // SAFETY: the caller guarantees valid or null pointers
+    let (Some(game), Some(out_index)) = (unsafe { game.as_ref() }, unsafe { out_index.as_mut() })
+    else {
+        return GameyStatus::NullPointer;
+    };
+    if game.game.check_game_over() {
+        return GameyStatus::GameOver;
+    }
+    guard(GameyStatus::Panic, || {
+        match MinimaxBot::new(max_time_ms as u64).choose_move(&game.game) {
+            Some(coords) => {
+                *out_index = coords.to_index(game.game.board_size());
+                GameyStatus::Ok
+            }
+            None => GameyStatus::NoMove,
+        }
+    })
+}
+
+/// Returns a static, NUL-terminated description of a status.
+#[unsafe(no_mangle)]
+pub extern "C" fn gamey_status_message(status: GameyStatus) -> *const c_char {
+    let message: &CStr = match status {
+        GameyStatus::Ok => c"ok",
+        GameyStatus::NullPointer => c"null pointer",
+        GameyStatus::InvalidCell => c"cell index outside the board",
+        GameyStatus::OccupiedCell => c"cell already occupied",
+        GameyStatus::GameOver => c"game is over",
+        GameyStatus::NothingToUndo => c"no move to undo",
+        GameyStatus::NoMove => c"no move available",
+        GameyStatus::IllegalMove => c"illegal move",
+        GameyStatus::Panic => c"internal engine error",
+    };
+    message.as_ptr()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_lifecycle() {
+        let game = gamey_game_new(3);
+        unsafe {
+            assert_eq!(gamey_game_total_cells(game), 6);
+            assert_eq!(gamey_game_next_player(game), 0);
+            for index in [0, 4, 1, 5] {
+                assert_eq!(gamey_game_play(game, index), GameyStatus::Ok);
+            }
+            assert_eq!(gamey_game_play(game, 4), GameyStatus::OccupiedCell);
+            assert_eq!(gamey_game_play(game, 6), GameyStatus::InvalidCell);
+            assert_eq!(gamey_game_play(game, 3), GameyStatus::Ok);
+            assert_eq!(gamey_game_winner(game), 0);
+            assert_eq!(gamey_game_next_player(game), -1);
+            assert_eq!(gamey_game_play(game, 2), GameyStatus::GameOver);
+
+            let mut cells = [9; 6];
+            assert_eq!(gamey_game_board(game, cells.as_mut_ptr(), cells.len()), 6);
+            assert_eq!(cells, [0, 0, -1, 0, 1, 1]);
+
+            assert_eq!(gamey_game_undo(game), GameyStatus::Ok);
+            assert_eq!(gamey_game_cell_owner(game, 3), -1);
+            gamey_game_free(game);
+        }
+    }
+
+    #[test]
+    fn test_null_pointers_are_rejected() {
+        assert!(gamey_game_new(0).is_null());
+        assert!(gamey_game_new(u32::MAX).is_null());
+        unsafe {
+            assert_eq!(
+                gamey_game_play(std::ptr::null_mut(), 0),
+                GameyStatus::NullPointer
+            );
+            assert_eq!(gamey_game_board_size(std::ptr::null()), 0);
+            assert!(gamey_game_from_position(std::ptr::null()).is_null());
+            gamey_game_free(std::ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_bot_move_and_position() {
+        unsafe {
+            let game = gamey_game_from_position(c"1/2/1B1 R".as_ptr());
+            assert!(!game.is_null());
+            let mut index = u32::MAX;
+            assert_eq!(gamey_bot_move(game, 20, &mut index), GameyStatus::Ok);
+            assert_eq!(gamey_game_cell_owner(game, index), -1);
+            gamey_game_free(game);
+
+            assert!(gamey_game_from_position(c"not a position".as_ptr()).is_null());
+        }
+    }
+
+    #[test]
+    fn test_status_messages() {
+        let message = unsafe { CStr::from_ptr(gamey_status_message(GameyStatus::GameOver)) };
+        assert_eq!(message.to_str(), Ok("game is over"));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{ErrorResponse, GameYError};
+use axum::{Json, http::StatusCode, response::IntoResponse};
+
+/// An error returned by the game server, with the HTTP status to send.
+///
+/// The body is an [`ErrorResponse`], the same JSON shape the bot server uses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    /// The HTTP status of the response.
+    pub status: StatusCode,
+    /// The JSON body of the response.
+    pub body: ErrorResponse,
+}
+
+impl ApiError {
+    /// Creates an error with the given status and message.
+    pub fn new(status: StatusCode, message: &str) -> Self {
+        Self {
+            status,
+            body: ErrorResponse::error(message, None, None),
+        }
+    }
+
+    /// The requested game does not exist.
+    pub fn game_not_found(game_id: u64) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            &format!("Game not found: {}", game_id),
+        )
+    }
+
+    /// The requested room does not exist.
+    pub fn room_not_found(room_id: u64) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            &format!("Room not found: {}", room_id),
+        )
+    }
+
+    /// The request is well-formed but not allowed in the current position.
+    pub fn conflict(message: &str) -> Self {
+        Self::new(StatusCode::CONFLICT, message)
+    }
+
+    /// The request is malformed.
+    pub fn bad_request(message: &str) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+}
+
+impl From<ErrorResponse> for ApiError {
+    fn from(body: ErrorResponse) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            body,
+        }
+    }
+}
+
+impl From<GameYError> for ApiError {
+    /// Moves that do not fit the position (an occupied cell, the wrong
+    /// player, a finished game, a move the opening rule forbids) and
+    /// proposals that do not fit the session are conflicts; anything else
+    /// is a bad request.
+    fn from(error: GameYError) -> Self {
+        let message = error.to_string();
+        match error {
+            GameYError::Occupied { .. }
+            | GameYError::GameOver { .. }
+            | GameYError::InvalidPlayerTurn { .. }
+            | GameYError::ForbiddenByOpeningRule { .. }
+            | GameYError::SessionOver
+            | GameYError::ProposalPending { .. }
+            | GameYError::NoProposal => Self::conflict(&message),
+            _ => Self::bad_request(&message),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> axum::response::Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, PlayerId};
+
+    #[test]
+    fn test_game_errors_map_to_status() {
+        let occupied = GameYError::Occupied {
+            coordinates: Coordinates::new(0, 0, 2),
+            player: PlayerId::new(1),
+        };
+        assert_eq!(ApiError::from(occupied).status, StatusCode::CONFLICT);
+
+        let index = GameYError::InvalidCellIndex {
+            index: 9,
+            total_cells: 6,
+        };
+        let error = ApiError::from(index);
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert!(error.body.message.contains("Cell index 9"));
+    }
+
+    #[test]
+    fn test_not_found() {
+        let error = ApiError::game_not_found(12);
+        assert_eq!(error.status, StatusCode::NOT_FOUND);
+        assert_eq!(error.body.message, "Game not found: 12");
+    }
+}
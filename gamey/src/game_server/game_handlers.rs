@@ -0,0 +1,251 @@
+use crate::{
+    BotMoveRequest, BotMoveResponse, Coordinates, GameStateResponse, GameY, GameYError,
+    MAX_SERVER_BOARD_SIZE, MoveRequest, Movement, NewGameRequest, OpeningRule, PlayerId,
+    ResignRequest, check_api_version,
+    game_server::{ApiError, GameServerState},
+    validate_record,
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+
+/// The time a bot may search when the request does not say.
+pub const DEFAULT_BOT_TIME_MS: u64 = 1000;
+
+/// The longest time a request may let a bot search.
+pub const MAX_BOT_TIME_MS: u64 = 30_000;
+
+/// Path parameters of the endpoints that create games.
+#[derive(Deserialize)]
+pub struct VersionParams {
+    /// The API version (e.g., "v1").
+    pub(crate) api_version: String,
+}
+
+/// Path parameters of the endpoints that act on one game.
+#[derive(Deserialize)]
+pub struct GameParams {
+    /// The API version (e.g., "v1").
+    api_version: String,
+    /// The identifier of the game.
+    game_id: u64,
+}
+
+/// Creates a game with an empty board, or from the record of the request.
+///
+/// Records are checked with [`validate_record`]: a record that is not a
+/// consistent game of the requested size is a bad request. Games started on
+/// an empty board are played under the opening rule of the request.
+///
+/// # Route
+/// `POST /{api_version}/games`
+///
+/// # Response
+/// `201 Created` with the [`GameStateResponse`] of the new game.
+pub async fn create_game(
+    State(state): State<GameServerState>,
+    Path(params): Path<VersionParams>,
+    Json(request): Json<NewGameRequest>,
+) -> Result<(StatusCode, Json<GameStateResponse>), ApiError> {
+    check_api_version(&params.api_version)?;
+    check_board_size(request.size)?;
+    let game = match &request.record {
+        Some(_) if request.opening_rule != OpeningRule::Free => {
+            return Err(ApiError::bad_request(
+                "An opening rule can only be set for a game started on an empty board",
+            ));
+        }
+        Some(record) => {
+            let game = validate_record(record)
+                .map_err(|e| ApiError::bad_request(&format!("Invalid game record: {}", e)))?;
+            if game.board_size() != request.size {
+                return Err(ApiError::bad_request(&format!(
+                    "The record is of board size {}, not {}",
+                    game.board_size(),
+                    request.size
+                )));
+            }
+            game
+        }
+        None => GameY::new(request.size).with_opening_rule(request.opening_rule),
+    };
+    let response = GameStateResponse::new(0, &game);
+    let game_id = state.insert(game);
+    Ok((
+        StatusCode::CREATED,
+        Json(GameStateResponse {
+            game_id,
+            ..response
+        }),
+    ))
+}
+
+/// Returns the state of a game.
+///
+/// # Route
+/// `GET /{api_version}/games/{game_id}`
+pub async fn get_game(
+    State(state): State<GameServerState>,
+    Path(params): Path<GameParams>,
+) -> Result<Json<GameStateResponse>, ApiError> {
+    check_api_version(&params.api_version)?;
+    let game = state
+        .get(params.game_id)
+        .ok_or(ApiError::game_not_found(params.game_id))?;
+    Ok(Json(GameStateResponse::new(params.game_id, &game)))
+}
+
+/// Places a stone.
+///
+/// # Route
+/// `POST /{api_version}/games/{game_id}/moves`
+///
+/// # Response
+/// The state after the move. Playing out of turn, on an occupied cell or in
+/// a finished game is a `409 Conflict`.
+pub async fn play_move(
+    State(state): State<GameServerState>,
+    Path(params): Path<GameParams>,
+    Json(request): Json<MoveRequest>,
+) -> Result<Json<GameStateResponse>, ApiError> {
+    check_api_version(&params.api_version)?;
+    let player = parse_player(request.player)?;
+    state
+        .with_game(params.game_id, |game| {
+            let coords = cell_coords(game, request.cell)?;
+            game.add_move(Movement::Placement { player, coords })?;
+            Ok(GameStateResponse::new(params.game_id, game))
+        })
+        .ok_or(ApiError::game_not_found(params.game_id))?
+        .map(Json)
+}
+
+/// Asks a bot to play the next move for the player to move.
+///
+/// The bot searches on a blocking thread so the server keeps answering other
+/// requests. If the game changes while the bot is thinking, the move is
+/// discarded and the request fails with `409 Conflict`. A search time above
+/// [`MAX_BOT_TIME_MS`] is a bad request.
+///
+/// # Route
+/// `POST /{api_version}/games/{game_id}/bot-move`
+pub async fn bot_move(
+    State(state): State<GameServerState>,
+    Path(params): Path<GameParams>,
+    Json(request): Json<BotMoveRequest>,
+) -> Result<Json<BotMoveResponse>, ApiError> {
+    check_api_version(&params.api_version)?;
+    let max_time_ms = request.max_time_ms.unwrap_or(DEFAULT_BOT_TIME_MS);
+    check_bot_time(max_time_ms)?;
+    let bot = state.bots().find(&request.bot_id).ok_or_else(|| {
+        ApiError::new(
+            StatusCode::NOT_FOUND,
+            &format!(
+                "Bot not found: {}, available bots: [{}]",
+                request.bot_id,
+                state.bots().names().join(", ")
+            ),
+        )
+    })?;
+    let game = state
+        .get(params.game_id)
+        .ok_or(ApiError::game_not_found(params.game_id))?;
+    let Some(player) = game.next_player() else {
+        return Err(ApiError::conflict("The game is already over"));
+    };
+    let moves_played = game.history().len();
+
+    let coords = tokio::task::spawn_blocking(move || bot.choose_move_within(&game, max_time_ms))
+        .await
+        .map_err(|e| {
+            ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &format!("Bot {} failed: {}", request.bot_id, e),
+            )
+        })?
+        .ok_or_else(|| ApiError::conflict("The bot found no move to play"))?;
+
+    state
+        .with_game(params.game_id, |game| {
+            if game.history().len() != moves_played {
+                return Err(ApiError::conflict(
+                    "The game changed while the bot was thinking",
+                ));
+            }
+            game.add_move(Movement::Placement { player, coords })?;
+            Ok(BotMoveResponse {
+                bot_id: request.bot_id.clone(),
+                cell: coords.to_index(game.board_size()),
+                coords,
+                state: GameStateResponse::new(params.game_id, game),
+            })
+        })
+        .ok_or(ApiError::game_not_found(params.game_id))?
+        .map(Json)
+}
+
+/// Makes a player resign.
+///
+/// Either player may resign, whoever is to move.
+///
+/// # Route
+/// `POST /{api_version}/games/{game_id}/resign`
+pub async fn resign(
+    State(state): State<GameServerState>,
+    Path(params): Path<GameParams>,
+    Json(request): Json<ResignRequest>,
+) -> Result<Json<GameStateResponse>, ApiError> {
+    check_api_version(&params.api_version)?;
+    let player = parse_player(request.player)?;
+    state
+        .with_game(params.game_id, |game| {
+            game.resign(player)?;
+            Ok(GameStateResponse::new(params.game_id, game))
+        })
+        .ok_or(ApiError::game_not_found(params.game_id))?
+        .map(Json)
+}
+
+pub(crate) fn check_board_size(size: u32) -> Result<(), ApiError> {
+    if size == 0 || size > MAX_SERVER_BOARD_SIZE {
+        return Err(ApiError::bad_request(&format!(
+            "Invalid board size: {}, expected 1 to {}",
+            size, MAX_SERVER_BOARD_SIZE
+        )));
+    }
+    Ok(())
+}
+
+fn check_bot_time(max_time_ms: u64) -> Result<(), ApiError> {
+    if max_time_ms > MAX_BOT_TIME_MS {
+        return Err(ApiError::bad_request(&format!(
+            "Invalid bot time: {} ms, expected at most {}",
+            max_time_ms, MAX_BOT_TIME_MS
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_player(player: u32) -> Result<PlayerId, ApiError> {
+    if player > 1 {
+        return Err(ApiError::bad_request(&format!(
+            "Invalid player: {}, expected 0 or 1",
+            player
+        )));
+    }
+    Ok(PlayerId::new(player))
+}
+
+pub(crate) fn cell_coords(game: &GameY, cell: u32) -> Result<Coordinates, GameYError> {
+    let total_cells = game.total_cells();
+    if cell >= total_cells {
+        return Err(GameYError::InvalidCellIndex {
+            index: cell,
+            total_cells,
+        });
+    }
+    Ok(Coordinates::from_index(cell, game.board_size()))
+}
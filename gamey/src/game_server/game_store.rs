@@ -0,0 +1,150 @@
+use crate::{GameY, RoomHub, YBotRegistry};
+use std::collections::HashMap;
+#[cfg(feature = "serde")]
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The largest board the game server agrees to create.
+pub const MAX_SERVER_BOARD_SIZE: u32 = 64;
+
+/// Shared state of the game server.
+///
+/// Holds the games in progress, keyed by their identifier, the WebSocket
+/// rooms and the bots that can be asked to play in them. Cloning is cheap: every clone refers to the
+/// same games.
+#[derive(Clone)]
+pub struct GameServerState {
+    games: Arc<Mutex<HashMap<u64, GameY>>>,
+    next_id: Arc<AtomicU64>,
+    rooms: Arc<RoomHub>,
+    bots: Arc<YBotRegistry>,
+    #[cfg(feature = "serde")]
+    recordings: Option<Arc<PathBuf>>,
+}
+
+impl GameServerState {
+    /// Creates a state with no games and the given bot registry.
+    pub fn new(bots: YBotRegistry) -> Self {
+        Self {
+            games: Arc::new(Mutex::new(HashMap::new())),
+            next_id: Arc::new(AtomicU64::new(1)),
+            rooms: Arc::new(RoomHub::new()),
+            bots: Arc::new(bots),
+            #[cfg(feature = "serde")]
+            recordings: None,
+        }
+    }
+
+    /// Records every game inserted from now on to a file of the directory
+    /// `dir`, named after the identifier of the game, as with
+    /// [`GameY::record_to`].
+    #[cfg(feature = "serde")]
+    pub fn with_recordings<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.recordings = Some(Arc::new(dir.into()));
+        self
+    }
+
+    /// Returns a clone of the Arc-wrapped bot registry.
+    pub fn bots(&self) -> Arc<YBotRegistry> {
+        Arc::clone(&self.bots)
+    }
+
+    /// Returns the WebSocket rooms.
+    pub fn rooms(&self) -> &RoomHub {
+        &self.rooms
+    }
+
+    /// Stores `game` and returns its new identifier.
+    ///
+    /// A game that cannot be recorded is stored anyway, and the failure
+    /// logged.
+    pub fn insert(&self, game: GameY) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "serde")]
+        let game = {
+            let mut game = game;
+            if let Some(dir) = &self.recordings
+                && let Err(e) = game.record_to(dir.join(format!("game-{}.jsonl", id)))
+            {
+                tracing::warn!("Failed to record game {}: {}", id, e);
+            }
+            game
+        };
+        self.lock().insert(id, game);
+        id
+    }
+
+    /// Returns a copy of the game with the given identifier.
+    pub fn get(&self, id: u64) -> Option<GameY> {
+        self.lock().get(&id).cloned()
+    }
+
+    /// Runs `f` on the game with the given identifier, holding the lock for
+    /// the duration of the call.
+    ///
+    /// Returns None if there is no such game.
+    pub fn with_game<T>(&self, id: u64, f: impl FnOnce(&mut GameY) -> T) -> Option<T> {
+        self.lock().get_mut(&id).map(f)
+    }
+
+    /// Returns the number of stored games.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if no game is stored.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, GameY>> {
+        // A panic while holding the lock cannot leave a game half-updated:
+        // GameY methods either apply a move or return an error.
+        self.games.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_assigns_distinct_ids() {
+        let state = GameServerState::new(YBotRegistry::new());
+        let first = state.insert(GameY::new(3));
+        let second = state.insert(GameY::new(4));
+        assert_ne!(first, second);
+        assert_eq!(state.len(), 2);
+        assert_eq!(state.get(second).unwrap().board_size(), 4);
+    }
+
+    #[test]
+    fn test_with_game_updates_stored_game() {
+        let state = GameServerState::new(YBotRegistry::new());
+        let id = state.insert(GameY::new(3));
+        let resigned = state.with_game(id, |game| game.resign(crate::PlayerId::new(0)));
+        assert!(resigned.unwrap().is_ok());
+        assert!(state.get(id).unwrap().check_game_over());
+        assert!(state.with_game(id + 1, |_| ()).is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_inserted_games_are_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = GameServerState::new(YBotRegistry::new()).with_recordings(dir.path());
+        let id = state.insert(GameY::new(3));
+        state.with_game(id, |game| game.resign(crate::PlayerId::new(1)).unwrap());
+        let path = dir.path().join(format!("game-{}.jsonl", id));
+        assert!(GameY::load_recording(path).unwrap().check_game_over());
+    }
+
+    #[test]
+    fn test_clones_share_games() {
+        let state = GameServerState::new(YBotRegistry::new());
+        let clone = state.clone();
+        clone.insert(GameY::new(3));
+        assert!(!state.is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+//! HTTP server for playing games of Y against the bots.
+//!
+//! Unlike the stateless [`bot_server`](crate::bot_server), this server keeps
+//! the games in memory so a client only sends its own moves. Request and
+//! response bodies are the types in [`notation::game_api`](crate::notation::game_api),
+//! which clients can reuse to agree on the schema.
+//!
+//! # Endpoints
+//! - `GET /status` - Health check endpoint
+//! - `POST /{api_version}/games` - Create a game
+//! - `GET /{api_version}/games/{game_id}` - Get the state of a game
+//! - `POST /{api_version}/games/{game_id}/moves` - Place a stone
+//! - `POST /{api_version}/games/{game_id}/bot-move` - Let a bot play the next move
+//! - `POST /{api_version}/games/{game_id}/resign` - Resign
+//! - `POST /{api_version}/rooms` - Create a multiplayer room
+//! - `GET /{api_version}/rooms/{room_id}` - Get the state of a room
+//! - `GET /{api_version}/rooms/{room_id}/ws` - Join or watch a room over WebSocket
+//!
+//! # Rooms
+//!
+//! A room is a [`GameSession`](crate::GameSession) shared over WebSockets:
+//! two players (or a player and a bot) take its seats, every move is relayed
+//! to all connections at once, clocks are enforced by the server and
+//! spectators can follow along with `?spectate=true`. A player who loses
+//! their connection takes their seat back by sending the token they received
+//! when joining. The messages are [`RoomClientMessage`](crate::RoomClientMessage)
+//! and [`RoomServerMessage`](crate::RoomServerMessage).
+//!
+//! # Example
+//! ```no_run
+//! use gamey::run_game_server;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     if let Err(e) = run_game_server(3000).await {
+//!         eprintln!("Server error: {}", e);
+//!     }
+//! }
+//! ```
+
+pub mod api_error;
+pub mod game_handlers;
+pub mod game_store;
+pub mod room_hub;
+pub mod room_socket;
+pub use api_error::*;
+pub use game_handlers::*;
+pub use game_store::*;
+pub use room_hub::*;
+pub use room_socket::*;
+
+use crate::{GameYError, MinimaxBot, RandomBot, YBotRegistry, status};
+use axum::routing::{get, post};
+use std::sync::Arc;
+
+/// Creates the Axum router of the game server with the given state.
+///
+/// This is useful for testing the API without binding to a network port.
+pub fn create_game_router(state: GameServerState) -> axum::Router {
+    axum::Router::new()
+        .route("/status", get(status))
+        .route("/{api_version}/games", post(create_game))
+        .route("/{api_version}/games/{game_id}", get(get_game))
+        .route("/{api_version}/games/{game_id}/moves", post(play_move))
+        .route("/{api_version}/games/{game_id}/bot-move", post(bot_move))
+        .route("/{api_version}/games/{game_id}/resign", post(resign))
+        .route("/{api_version}/rooms", post(create_room))
+        .route("/{api_version}/rooms/{room_id}", get(get_room))
+        .route("/{api_version}/rooms/{room_id}/ws", get(room_socket))
+        .with_state(state)
+}
+
+/// Creates the default game server state, with the random and minimax bots.
+pub fn create_default_game_state() -> GameServerState {
+    let bots = YBotRegistry::new()
+        .with_bot(Arc::new(RandomBot::new()))
+        .with_bot(Arc::new(MinimaxBot::new(DEFAULT_BOT_TIME_MS)));
+    GameServerState::new(bots)
+}
+
+/// Starts the game server on the specified port.
+///
+/// This function blocks until the server is shut down.
+///
+/// # Errors
+/// Returns `GameYError::ServerError` if the port cannot be bound or the
+/// server fails while running.
+pub async fn run_game_server(port: u16) -> Result<(), GameYError> {
+    run_game_server_with(port, create_default_game_state()).await
+}
+
+/// Starts the game server on the specified port with the given state, for
+/// example to record its games with [`GameServerState::with_recordings`].
+///
+/// This function blocks until the server is shut down.
+///
+/// # Errors
+/// Returns `GameYError::ServerError` if the port cannot be bound or the
+/// server fails while running.
+pub async fn run_game_server_with(port: u16, state: GameServerState) -> Result<(), GameYError> {
+    let app = create_game_router(state);
+
+    let addr = format!("0.0.0.0:{}", port);
+    let listener =
+        tokio::net::TcpListener::bind(&addr)
+            .await
+            .map_err(|e| GameYError::ServerError {
+                message: format!("Failed to bind to {}: {}", addr, e),
+            })?;
+
+    println!("Game server: Listening on http://{}", addr);
+    axum::serve(listener, app)
+        .await
+        .map_err(|e| GameYError::ServerError {
+            message: format!("Server error: {}", e),
+        })
+}
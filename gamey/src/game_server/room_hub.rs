@@ -0,0 +1,157 @@
+use crate::{GameSession, GameYError, PlayerId, RoomStateResponse};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::broadcast;
+use web_time::Instant;
+
+/// Number of updates a slow connection may fall behind before it skips to
+/// the latest state.
+const ROOM_UPDATE_CAPACITY: usize = 32;
+
+/// A game session shared by the connections of its players and spectators.
+///
+/// Every change to the session is published to the subscribers of the room.
+pub struct Room {
+    id: u64,
+    session: Mutex<GameSession>,
+    updates: broadcast::Sender<RoomStateResponse>,
+    learned: AtomicBool,
+}
+
+impl Room {
+    fn new(id: u64, session: GameSession) -> Self {
+        let (updates, _) = broadcast::channel(ROOM_UPDATE_CAPACITY);
+        Self {
+            id,
+            session: Mutex::new(session),
+            updates,
+            learned: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the identifier of the room.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the state of the room, with the clocks read now.
+    pub fn state(&self) -> RoomStateResponse {
+        RoomStateResponse::new(self.id, &self.lock(), Instant::now())
+    }
+
+    /// Returns a copy of the session.
+    pub fn session(&self) -> GameSession {
+        self.lock().clone()
+    }
+
+    /// Subscribes to the changes of the room.
+    pub fn subscribe(&self) -> broadcast::Receiver<RoomStateResponse> {
+        self.updates.subscribe()
+    }
+
+    /// Returns a receiver for the events of the session, as with
+    /// [`GameSession::spectate`].
+    pub fn spectate(&self) -> std::sync::mpsc::Receiver<crate::SpectatorEvent> {
+        self.lock().spectate()
+    }
+
+    /// Runs `f` on the session and publishes the new state if it succeeds.
+    pub fn update<T>(
+        &self,
+        f: impl FnOnce(&mut GameSession, Instant) -> Result<T, GameYError>,
+    ) -> Result<T, GameYError> {
+        let now = Instant::now();
+        let mut session = self.lock();
+        let value = f(&mut session, now)?;
+        self.publish(&session, now);
+        Ok(value)
+    }
+
+    /// Makes the player to move lose on time if their clock has run out, and
+    /// publishes the result.
+    ///
+    /// Returns the player who lost, if any.
+    pub fn check_time(&self) -> Option<PlayerId> {
+        let now = Instant::now();
+        let mut session = self.lock();
+        let player = session.check_time(now)?;
+        self.publish(&session, now);
+        Some(player)
+    }
+
+    /// Returns true the first time it is called, to tell the bots of the
+    /// room how its game ended only once.
+    pub fn take_game_over(&self) -> bool {
+        !self.learned.swap(true, Ordering::Relaxed)
+    }
+
+    fn publish(&self, session: &GameSession, now: Instant) {
+        // Nobody listening is not an error: the state is sent on connection
+        let _ = self
+            .updates
+            .send(RoomStateResponse::new(self.id, session, now));
+    }
+
+    fn lock(&self) -> MutexGuard<'_, GameSession> {
+        self.session.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The rooms of the game server.
+#[derive(Default)]
+pub struct RoomHub {
+    rooms: Mutex<HashMap<u64, Arc<Room>>>,
+    next_id: AtomicU64,
+}
+
+impl RoomHub {
+    /// Creates a hub with no rooms.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens a room for `session` and returns it.
+    pub fn create(&self, session: GameSession) -> Arc<Room> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let room = Arc::new(Room::new(id, session));
+        self.lock().insert(id, Arc::clone(&room));
+        room
+    }
+
+    /// Returns the room with the given identifier.
+    pub fn get(&self, id: u64) -> Option<Arc<Room>> {
+        self.lock().get(&id).cloned()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, Arc<Room>>> {
+        self.rooms.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_and_get_room() {
+        let hub = RoomHub::new();
+        let room = hub.create(GameSession::new(3));
+        assert_eq!(hub.get(room.id()).unwrap().id(), room.id());
+        assert!(hub.get(room.id() + 1).is_none());
+    }
+
+    #[test]
+    fn test_successful_updates_are_published() {
+        let room = RoomHub::new().create(GameSession::new(3));
+        let mut updates = room.subscribe();
+        let joined = room.update(|session, now| session.join("ana", None, now));
+        assert_eq!(joined.unwrap().0, PlayerId::new(0));
+        let state = updates.try_recv().unwrap();
+        assert!(state.seats[0].occupant.is_some());
+
+        let rejected = room.update(|session, now| session.join("bea", Some(PlayerId::new(0)), now));
+        assert!(rejected.is_err());
+        assert!(updates.try_recv().is_err());
+    }
+}
@@ -0,0 +1,325 @@
+use crate::{
+    DEFAULT_BOT_TIME_MS, GameAction, GameSession, GameYError, NewRoomRequest, PlayerId,
+    RoomClientMessage, RoomServerMessage, RoomStateResponse, SeatOccupant, check_api_version,
+    game_server::{
+        ApiError, GameServerState, Room, VersionParams, cell_coords, check_board_size, parse_player,
+    },
+};
+use axum::{
+    Json,
+    extract::{
+        Path, Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    http::StatusCode,
+    response::Response,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use web_time::{Duration, Instant};
+
+/// Path parameters of the endpoints that act on one room.
+#[derive(Deserialize)]
+pub struct RoomParams {
+    /// The API version (e.g., "v1").
+    api_version: String,
+    /// The identifier of the room.
+    room_id: u64,
+}
+
+/// Query parameters of the room WebSocket endpoint.
+#[derive(Deserialize)]
+pub struct RoomSocketQuery {
+    /// Connect as a spectator, receiving updates without being able to join.
+    #[serde(default)]
+    spectate: bool,
+}
+
+/// Creates a room, seating a bot as player 1 if requested.
+///
+/// # Route
+/// `POST /{api_version}/rooms`
+///
+/// # Response
+/// `201 Created` with the [`RoomStateResponse`] of the new room.
+pub async fn create_room(
+    State(state): State<GameServerState>,
+    Path(params): Path<VersionParams>,
+    Json(request): Json<NewRoomRequest>,
+) -> Result<(StatusCode, Json<RoomStateResponse>), ApiError> {
+    check_api_version(&params.api_version)?;
+    check_board_size(request.size)?;
+    if let Some(bot_id) = &request.bot_id
+        && state.bots().find(bot_id).is_none()
+    {
+        return Err(ApiError::new(
+            StatusCode::NOT_FOUND,
+            &format!("Bot not found: {}", bot_id),
+        ));
+    }
+    let mut session = GameSession::new(request.size);
+    if let Some(control) = request.time_control {
+        if control.initial_ms == 0 {
+            return Err(ApiError::bad_request("The initial time must be positive"));
+        }
+        session = session.with_time_control(control);
+    }
+
+    let room = state.rooms().create(session);
+    if let Some(bot_id) = &request.bot_id {
+        room.update(|session, now| session.add_bot(PlayerId::new(1), bot_id, now))?;
+    }
+    Ok((StatusCode::CREATED, Json(room.state())))
+}
+
+/// Returns the state of a room.
+///
+/// # Route
+/// `GET /{api_version}/rooms/{room_id}`
+pub async fn get_room(
+    State(state): State<GameServerState>,
+    Path(params): Path<RoomParams>,
+) -> Result<Json<RoomStateResponse>, ApiError> {
+    check_api_version(&params.api_version)?;
+    let room = state
+        .rooms()
+        .get(params.room_id)
+        .ok_or(ApiError::room_not_found(params.room_id))?;
+    Ok(Json(room.state()))
+}
+
+/// Opens a WebSocket connection to a room.
+///
+/// The server sends a [`RoomServerMessage::State`] on connection and after
+/// every change to the room. Players send [`RoomClientMessage`]s: `join` or
+/// `rejoin` first, then their moves, and `propose` or `answer` to take back
+/// moves or abort the game by agreement. Connections opened with
+/// `?spectate=true` only receive updates.
+///
+/// # Route
+/// `GET /{api_version}/rooms/{room_id}/ws`
+pub async fn room_socket(
+    ws: WebSocketUpgrade,
+    State(state): State<GameServerState>,
+    Path(params): Path<RoomParams>,
+    Query(query): Query<RoomSocketQuery>,
+) -> Result<Response, ApiError> {
+    check_api_version(&params.api_version)?;
+    let room = state
+        .rooms()
+        .get(params.room_id)
+        .ok_or(ApiError::room_not_found(params.room_id))?;
+    Ok(ws.on_upgrade(move |socket| run_room_socket(socket, state, room, query.spectate)))
+}
+
+/// Relays client messages to the room and room updates to the client until
+/// either side closes the connection.
+async fn run_room_socket(
+    mut socket: WebSocket,
+    state: GameServerState,
+    room: Arc<Room>,
+    spectate: bool,
+) {
+    let mut updates = room.subscribe();
+    let mut token = None;
+    let mut reply = Some(RoomServerMessage::State { room: room.state() });
+    loop {
+        if let Some(message) = reply.take()
+            && send(&mut socket, &message).await.is_err()
+        {
+            break;
+        }
+        reply = tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let result = if spectate {
+                        Err("Spectators cannot play".to_string())
+                    } else {
+                        handle_message(&room, &mut token, &text)
+                    };
+                    match result {
+                        Ok(reply) => {
+                            schedule(&state, &room);
+                            reply
+                        }
+                        Err(message) => Some(RoomServerMessage::Error { message }),
+                    }
+                }
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => None,
+            },
+            update = updates.recv() => match update {
+                Ok(update) => Some(RoomServerMessage::State { room: update }),
+                Err(RecvError::Lagged(_)) => Some(RoomServerMessage::State { room: room.state() }),
+                Err(RecvError::Closed) => break,
+            },
+        };
+    }
+    if let Some(token) = token {
+        // The seat stays reserved for a later `rejoin`
+        let _ = room.update(|session, _| {
+            session
+                .disconnect(&token)
+                .ok_or(GameYError::UnknownSeatToken)
+        });
+    }
+}
+
+/// Applies one client message to the room.
+///
+/// `token` is the seat token held by the connection, set by a successful
+/// `join` or `rejoin`.
+fn handle_message(
+    room: &Room,
+    token: &mut Option<String>,
+    text: &str,
+) -> Result<Option<RoomServerMessage>, String> {
+    let message: RoomClientMessage =
+        serde_json::from_str(text).map_err(|e| format!("Invalid message: {}", e))?;
+    match message {
+        RoomClientMessage::Join { name, player } => {
+            check_unseated(token)?;
+            let preferred = player
+                .map(parse_player)
+                .transpose()
+                .map_err(|e| e.body.message)?;
+            let (player, new_token) = room
+                .update(|session, now| session.join(&name, preferred, now))
+                .map_err(|e| e.to_string())?;
+            *token = Some(new_token.clone());
+            Ok(Some(RoomServerMessage::Joined {
+                player: player.id(),
+                token: new_token,
+            }))
+        }
+        RoomClientMessage::Rejoin { token: old_token } => {
+            check_unseated(token)?;
+            let player = room
+                .update(|session, _| session.reconnect(&old_token))
+                .map_err(|e| e.to_string())?;
+            *token = Some(old_token.clone());
+            Ok(Some(RoomServerMessage::Joined {
+                player: player.id(),
+                token: old_token,
+            }))
+        }
+        RoomClientMessage::Play { cell } => {
+            let token = seat_token(token)?;
+            room.update(|session, now| {
+                let coords = cell_coords(session.game(), cell)?;
+                session.place(token, coords, now)
+            })
+            .map_err(|e| e.to_string())?;
+            Ok(None)
+        }
+        RoomClientMessage::Swap => act(room, token, GameAction::Swap),
+        RoomClientMessage::Resign => act(room, token, GameAction::Resign),
+        RoomClientMessage::Propose { proposal } => {
+            let token = seat_token(token)?;
+            room.update(|session, now| session.propose(token, proposal, now))
+                .map_err(|e| e.to_string())?;
+            Ok(None)
+        }
+        RoomClientMessage::Answer { accept } => {
+            let token = seat_token(token)?;
+            room.update(|session, now| session.answer(token, accept, now))
+                .map_err(|e| e.to_string())?;
+            Ok(None)
+        }
+    }
+}
+
+fn act(
+    room: &Room,
+    token: &Option<String>,
+    action: GameAction,
+) -> Result<Option<RoomServerMessage>, String> {
+    let token = seat_token(token)?;
+    room.update(|session, now| session.act(token, action, now))
+        .map_err(|e| e.to_string())?;
+    Ok(None)
+}
+
+fn check_unseated(token: &Option<String>) -> Result<(), String> {
+    match token {
+        Some(_) => Err("This connection already holds a seat".to_string()),
+        None => Ok(()),
+    }
+}
+
+fn seat_token(token: &Option<String>) -> Result<&str, String> {
+    token
+        .as_deref()
+        .ok_or_else(|| "Join the room before playing".to_string())
+}
+
+/// Starts the server-side work the room now needs: a timer that ends the
+/// game when the running clock reaches zero, and the move of a bot seat.
+/// Once the game is over, its bots are told the result to learn from it.
+fn schedule(state: &GameServerState, room: &Arc<Room>) {
+    let session = room.session();
+    if session.game().check_game_over() {
+        if room.take_game_over() {
+            teach_bots(state, &session);
+        }
+        return;
+    }
+    let now = Instant::now();
+    if let Some(wait) = session.clock().and_then(|clock| clock.time_to_flag(now)) {
+        let (state, room) = (state.clone(), Arc::clone(room));
+        tokio::spawn(async move {
+            // A timer outlived by a move finds a clock that has not run out
+            tokio::time::sleep(wait + Duration::from_millis(1)).await;
+            if room.check_time().is_some() {
+                schedule(&state, &room);
+            }
+        });
+    }
+    let Some((player, bot_id)) = session.bot_to_move() else {
+        return;
+    };
+    let Some(bot) = state.bots().find(bot_id) else {
+        return;
+    };
+    // Leave the bot a twentieth of its remaining time, as a human would
+    let max_time_ms = session.clock().map_or(DEFAULT_BOT_TIME_MS, |clock| {
+        (clock.remaining_ms(player, now) / 20).clamp(1, DEFAULT_BOT_TIME_MS)
+    });
+    let game = session.game().clone();
+    let (state, room) = (state.clone(), Arc::clone(room));
+    tokio::spawn(async move {
+        let choice =
+            tokio::task::spawn_blocking(move || bot.choose_move_within(&game, max_time_ms));
+        let Ok(Some(coords)) = choice.await else {
+            return;
+        };
+        // Fails harmlessly if the game ended, or a move was taken back, while
+        // the bot was thinking
+        if room
+            .update(|session, now| session.play_as_bot(player, coords, now))
+            .is_ok()
+        {
+            schedule(&state, &room);
+        }
+    });
+}
+
+/// Tells every bot seated in `session` how its game ended.
+fn teach_bots(state: &GameServerState, session: &GameSession) {
+    for player in [PlayerId::new(0), PlayerId::new(1)] {
+        let Some(SeatOccupant::Bot { bot_id }) = session.seat(player).occupant() else {
+            continue;
+        };
+        let Some(bot) = state.bots().find(bot_id) else {
+            continue;
+        };
+        let game = session.game().clone();
+        tokio::task::spawn_blocking(move || bot.learn_from_game(&game, player));
+    }
+}
+
+async fn send(socket: &mut WebSocket, message: &RoomServerMessage) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).map_err(axum::Error::new)?;
+    socket.send(Message::Text(text.into())).await
+}
@@ -3,9 +3,10 @@
 //! This module defines all error types that can occur during game operations,
 //! including I/O errors, parsing errors, and game rule violations.
 
+use alloc::{string::String, string::ToString};
 use thiserror::Error;
 
-use crate::{Coordinates, Movement, PlayerId};
+use crate::{Coordinates, GameOverReason, HexCoordinates, Movement, OpeningRule, PlayerId};
 
 /// Errors that can occur during Y game operations.
 ///
@@ -51,6 +52,15 @@ pub enum GameYError {
         board_size: u32,
     },
 
+    /// Coordinates do not designate a cell of the board.
+    #[error("Coordinates {coordinates} are not valid for board size {board_size}")]
+    InvalidCoordinates {
+        /// The rejected coordinates.
+        coordinates: Coordinates,
+        /// The board size the coordinates were checked against.
+        board_size: u32,
+    },
+
     /// Attempted to place a piece on an already occupied cell.
     #[error("Player {player} tries to place a stone on an occupied position: {coordinates}")]
     Occupied {
@@ -60,6 +70,15 @@ pub enum GameYError {
         player: PlayerId,
     },
 
+    /// Attempted to place a piece on a blocked cell of a custom board.
+    #[error("Player {player} tries to place a stone on a blocked cell: {coordinates}")]
+    BlockedCell {
+        /// The coordinates of the blocked cell.
+        coordinates: Coordinates,
+        /// The player who attempted the placement.
+        player: PlayerId,
+    },
+
     /// Invalid character found in a YEN layout string.
     #[error("Invalid character '{char}' in layout at row {row}, column {col}")]
     InvalidCharInLayout {
@@ -87,6 +106,15 @@ pub enum GameYError {
         found: PlayerId,
     },
 
+    /// The opening rule of the game forbids the move.
+    #[error("Movement {movement} is forbidden by the opening rule: {rule}")]
+    ForbiddenByOpeningRule {
+        /// The movement that was attempted.
+        movement: Movement,
+        /// The opening rule of the game.
+        rule: OpeningRule,
+    },
+
     /// Invalid number of players specified.
     #[error("Invalid number of players: {num_players}, expected {expected}")]
     InvalidNumPlayers {
@@ -116,6 +144,114 @@ pub enum GameYError {
         line: u32,
     },
 
+    /// A compact position string could not be parsed.
+    #[error("Invalid position string '{position}': {reason}")]
+    InvalidPositionString {
+        /// The position string that was rejected.
+        position: String,
+        /// Why the position string is invalid.
+        reason: String,
+    },
+
+    /// A game record imported from another site could not be parsed.
+    #[error("Board size {board_size} is larger than the maximum of {max}")]
+    BoardTooLarge {
+        /// The requested board size.
+        board_size: u32,
+        /// The largest board size accepted.
+        max: u32,
+    },
+
+    #[error("Invalid {format} record: {reason}")]
+    InvalidGameRecord {
+        /// The name of the record format.
+        format: String,
+        /// Why the record is invalid.
+        reason: String,
+    },
+
+    /// A saved game was written by a newer version of the library.
+    #[error("Unsupported saved game version {found}, expected at most {supported}")]
+    UnsupportedSaveVersion {
+        /// The version found in the file.
+        found: u32,
+        /// The newest version this library reads.
+        supported: u32,
+    },
+
+    /// A bot profile could not be read or does not exist.
+    #[error("Invalid bot profile: {reason}")]
+    InvalidBotProfile {
+        /// Why the profile was rejected.
+        reason: String,
+    },
+
+    /// No bot type of the given name is registered in a bot factory.
+    #[error("Unknown bot type '{name}'")]
+    UnknownBotType {
+        /// The requested bot type.
+        name: String,
+    },
+
+    /// A parameter given to construct a bot is unknown or has a bad value.
+    #[error("Invalid parameter '{parameter}' for bot type '{bot_type}': {reason}")]
+    InvalidBotParameter {
+        /// The bot type being constructed.
+        bot_type: String,
+        /// The name of the parameter.
+        parameter: String,
+        /// Why the parameter was rejected.
+        reason: String,
+    },
+
+    /// The preset stones of a built game already decide the game.
+    #[error("Preset stones already form a winning connection for player {winner}")]
+    PresetPositionFinished {
+        /// The player whose preset stones connect all three sides.
+        winner: PlayerId,
+    },
+
+    /// A game cannot be forfeited for the given reason.
+    #[error("A game cannot be forfeited by {reason}")]
+    InvalidForfeitReason {
+        /// The rejected reason.
+        reason: GameOverReason,
+    },
+
+    /// A cell index is outside the board.
+    #[error("Cell index {index} is out of range for a board with {total_cells} cells")]
+    InvalidCellIndex {
+        /// The rejected index.
+        index: u32,
+        /// The number of cells on the board.
+        total_cells: u32,
+    },
+
+    /// Hexagonal coordinates do not designate a cell of a Havannah board.
+    #[error("Coordinates {coordinates} are not valid for Havannah board size {board_size}")]
+    InvalidHexCoordinates {
+        /// The rejected coordinates.
+        coordinates: HexCoordinates,
+        /// The board size the coordinates were checked against.
+        board_size: u32,
+    },
+
+    /// Attempted to place a stone on an occupied cell of a Havannah board.
+    #[error("Player {player} tries to place a stone on an occupied position: {coordinates}")]
+    OccupiedHex {
+        /// The coordinates of the occupied cell.
+        coordinates: HexCoordinates,
+        /// The player who attempted the placement.
+        player: PlayerId,
+    },
+
+    /// Attempted to place a stone in a finished Havannah game.
+    #[error("Attempt to place a stone at {coordinates} in a finished game")]
+    HavannahGameOver {
+        /// The coordinates of the attempted placement.
+        coordinates: HexCoordinates,
+    },
+
     /// Server operation failed.
     #[error("Server error: {message}")]
     ServerError {
@@ -126,6 +262,117 @@ pub enum GameYError {
     /// Attempted to undo a move while the history is empty.
     #[error("Invalid attempt to undo a move with an empty history")]
     NoMoveToUndo,
+
+    /// Attempted to take a seat of a game session that is already occupied.
+    #[error("The seat of player {player} is already taken")]
+    SeatTaken {
+        /// The player whose seat was requested.
+        player: PlayerId,
+    },
+
+    /// Attempted to join a game session whose seats are all taken.
+    #[error("Both seats are already taken")]
+    NoFreeSeat,
+
+    /// A seat token does not belong to any seat of the game session.
+    #[error("Unknown seat token")]
+    UnknownSeatToken,
+
+    /// Attempted to play before both seats of a game session are taken.
+    #[error("The game starts once both seats are taken")]
+    SessionNotReady,
+
+    /// An external engine could not be started, crashed, did not answer in
+    /// time or sent an answer that does not fit the game.
+    #[error("External engine '{engine}' failed: {reason}")]
+    ExternalEngine {
+        /// The name of the engine.
+        engine: String,
+        /// What went wrong.
+        reason: String,
+    },
+
+    /// A search snapshot does not describe a search state that can be
+    /// loaded.
+    #[error("Invalid search snapshot: {reason}")]
+    InvalidSnapshot {
+        /// What is wrong with the snapshot.
+        reason: String,
+    },
+
+    /// A player ran out of time on the game clock.
+    #[error("Player {player} ran out of time")]
+    TimeExpired {
+        /// The player whose clock reached zero.
+        player: PlayerId,
+    },
+    /// The game of a session is over or was aborted, so nothing more can be
+    /// played or proposed in it.
+    #[error("The game of the session is over")]
+    SessionOver,
+
+    /// A player made a proposal while another one is waiting for an answer.
+    #[error("Player {player} has a proposal waiting for an answer")]
+    ProposalPending {
+        /// The player who made the waiting proposal.
+        player: PlayerId,
+    },
+
+    /// A player answered when no proposal of their opponent is waiting.
+    #[error("There is no proposal to answer")]
+    NoProposal,
+}
+
+/// Why a game record failed [`validate_record`](crate::validate_record).
+#[derive(Debug, Error)]
+pub enum ValidationError {
+    /// The record could not be read.
+    #[error(transparent)]
+    Unreadable(GameYError),
+
+    /// The board of the record is larger than records may declare.
+    #[error("Board size {board_size} is larger than the maximum of {max}")]
+    BoardTooLarge {
+        /// The declared board size.
+        board_size: u32,
+        /// The largest board size accepted.
+        max: u32,
+    },
+
+    /// A move of the record is not legal in the position before it, or is
+    /// played out of turn.
+    #[error("Illegal move {number}: {error}")]
+    IllegalMove {
+        /// The number of the move in the record, starting at 1.
+        number: usize,
+        /// Why the move is illegal.
+        error: GameYError,
+    },
+
+    /// The record declares a winner other than the one of its moves.
+    #[error("The record declares player {declared} the winner, but player {actual} won")]
+    ResultMismatch {
+        /// The winner declared by the record.
+        declared: PlayerId,
+        /// The winner of the game replayed from the moves.
+        actual: PlayerId,
+    },
+}
+
+impl From<ValidationError> for GameYError {
+    /// Keeps the error of an unreadable record or an illegal move, and
+    /// reports the others as an invalid record.
+    fn from(error: ValidationError) -> Self {
+        match error {
+            ValidationError::Unreadable(error) | ValidationError::IllegalMove { error, .. } => {
+                error
+            }
+            other => GameYError::InvalidGameRecord {
+                format: "game".to_string(),
+                reason: other.to_string(),
+            },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -178,6 +425,40 @@ mod tests {
         assert!(msg.contains("occupied"));
     }
 
+    #[test]
+    fn test_invalid_coordinates_display() {
+        let err = GameYError::InvalidCoordinates {
+            coordinates: Coordinates::new(3, 0, 0),
+            board_size: 3,
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("(3, 0, 0)"));
+        assert!(msg.contains("board size 3"));
+    }
+
+    #[test]
+    fn test_invalid_hex_coordinates_display() {
+        let err = GameYError::InvalidHexCoordinates {
+            coordinates: HexCoordinates::new(3, -1),
+            board_size: 3,
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("(3, -1)"));
+        assert!(msg.contains("Havannah board size 3"));
+    }
+
+    #[test]
+    fn test_invalid_cell_index_display() {
+        let err = GameYError::InvalidCellIndex {
+            index: 7,
+            total_cells: 6,
+        };
+        assert_eq!(
+            format!("{}", err),
+            "Cell index 7 is out of range for a board with 6 cells"
+        );
+    }
+
     #[test]
     fn test_invalid_char_in_layout_display() {
         let err = GameYError::InvalidCharInLayout {
@@ -237,6 +518,39 @@ mod tests {
         assert!(msg.contains("line 3"));
     }
 
+    #[test]
+    fn test_invalid_position_string_display() {
+        let err = GameYError::InvalidPositionString {
+            position: "1/2 X".to_string(),
+            reason: "unknown side to move 'X'".to_string(),
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("'1/2 X'"));
+        assert!(msg.contains("unknown side to move"));
+    }
+
+    #[test]
+    fn test_invalid_game_record_display() {
+        let err = GameYError::InvalidGameRecord {
+            format: "SGF".to_string(),
+            reason: "missing board size".to_string(),
+        };
+        assert_eq!(err.to_string(), "Invalid SGF record: missing board size");
+    }
+
+    #[test]
+    fn test_invalid_bot_parameter_display() {
+        let err = GameYError::InvalidBotParameter {
+            bot_type: "minimax".to_string(),
+            parameter: "depth".to_string(),
+            reason: "invalid digit found in string".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Invalid parameter 'depth' for bot type 'minimax': invalid digit found in string"
+        );
+    }
+
     #[test]
     fn test_server_error_display() {
         let err = GameYError::ServerError {
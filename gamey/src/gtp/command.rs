@@ -0,0 +1,116 @@
+use std::fmt::Display;
+
+/// A single command line sent to the engine.
+///
+/// A line has the form `[id] command_name [arguments]`. Comments (from `#`
+/// to the end of the line) and control characters are removed before
+/// parsing, as required by GTP.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GtpCommand {
+    /// Optional numeric id, echoed back in the response.
+    pub id: Option<u32>,
+    /// The command name, e.g. `play`.
+    pub name: String,
+    /// The arguments following the command name.
+    pub args: Vec<String>,
+}
+
+impl GtpCommand {
+    /// Parses a command line.
+    ///
+    /// Returns None for lines that contain no command (empty lines and
+    /// comments), which the engine must ignore.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.split('#').next().unwrap_or_default();
+        let cleaned: String = line
+            .chars()
+            .map(|c| if c == '\t' { ' ' } else { c })
+            .filter(|c| !c.is_control())
+            .collect();
+        let mut parts = cleaned.split_whitespace();
+        let first = parts.next()?;
+        let (id, name) = match first.parse::<u32>() {
+            Ok(id) => (Some(id), parts.next()?),
+            Err(_) => (None, first),
+        };
+        Some(GtpCommand {
+            id,
+            name: name.to_string(),
+            args: parts.map(str::to_string).collect(),
+        })
+    }
+}
+
+/// The engine's answer to a command.
+///
+/// Displayed as `=[id] result` on success or `?[id] message` on failure,
+/// followed by an empty line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GtpResponse {
+    /// The id of the command being answered.
+    pub id: Option<u32>,
+    /// The response text, or the error message.
+    pub result: Result<String, String>,
+}
+
+impl GtpResponse {
+    /// Creates a successful response.
+    pub fn success(id: Option<u32>, text: impl Into<String>) -> Self {
+        GtpResponse {
+            id,
+            result: Ok(text.into()),
+        }
+    }
+
+    /// Creates a failure response.
+    pub fn failure(id: Option<u32>, message: impl Into<String>) -> Self {
+        GtpResponse {
+            id,
+            result: Err(message.into()),
+        }
+    }
+}
+
+impl Display for GtpResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (prefix, text) = match &self.result {
+            Ok(text) => ('=', text),
+            Err(message) => ('?', message),
+        };
+        let id = self.id.map(|id| id.to_string()).unwrap_or_default();
+        write!(f, "{}{} {}\n\n", prefix, id, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command_with_id() {
+        let command = GtpCommand::parse("12 play b 4").unwrap();
+        assert_eq!(command.id, Some(12));
+        assert_eq!(command.name, "play");
+        assert_eq!(command.args, vec!["b", "4"]);
+    }
+
+    #[test]
+    fn test_parse_strips_comments_and_control_characters() {
+        let command = GtpCommand::parse("genmove\tw # reply quickly\r").unwrap();
+        assert_eq!(command.id, None);
+        assert_eq!(command.name, "genmove");
+        assert_eq!(command.args, vec!["w"]);
+        assert_eq!(GtpCommand::parse("   # only a comment"), None);
+        assert_eq!(GtpCommand::parse(""), None);
+        assert_eq!(GtpCommand::parse("7"), None);
+    }
+
+    #[test]
+    fn test_response_display() {
+        assert_eq!(GtpResponse::success(None, "2").to_string(), "= 2\n\n");
+        assert_eq!(
+            GtpResponse::failure(Some(3), "unknown command").to_string(),
+            "?3 unknown command\n\n"
+        );
+    }
+}
@@ -0,0 +1,400 @@
+use std::io::{BufRead, Write};
+use std::sync::Arc;
+
+use crate::{
+    Coordinates, GameAction, GameY, GtpCommand, GtpResponse, Movement, PlayerId, RenderOptions,
+    YBot, YBotRegistry, parse_idx,
+};
+
+/// The commands understood by [`GtpEngine`], as reported by `list_commands`.
+///
+/// Commands prefixed with `gamey-` are engine-specific analysis commands.
+pub const GTP_COMMANDS: &[&str] = &[
+    "protocol_version",
+    "name",
+    "version",
+    "known_command",
+    "list_commands",
+    "quit",
+    "boardsize",
+    "clear_board",
+    "play",
+    "genmove",
+    "undo",
+    "showboard",
+    "final_score",
+    "gamey-position",
+    "gamey-winning_moves",
+    "gamey-dead_cells",
+    "gamey-influence",
+    "gamey-bot",
+    "gamey-list_bots",
+];
+
+/// The largest board the GTP and YTP engines accept from a command.
+pub const MAX_ENGINE_BOARD_SIZE: u32 = 256;
+
+/// A game of Y driven by GTP commands.
+///
+/// The engine owns the current game and the bot used by `genmove`. Other
+/// bots can be made available to the `gamey-bot` command with
+/// [`GtpEngine::with_bots`].
+pub struct GtpEngine {
+    game: GameY,
+    bot: Arc<dyn YBot>,
+    bots: YBotRegistry,
+    quit: bool,
+}
+
+impl GtpEngine {
+    /// Creates an engine with an empty board of the given size.
+    pub fn new(board_size: u32, bot: Arc<dyn YBot>) -> Self {
+        GtpEngine {
+            game: GameY::new(board_size),
+            bot,
+            bots: YBotRegistry::new(),
+            quit: false,
+        }
+    }
+
+    /// Sets the bots that can be selected with the `gamey-bot` command.
+    pub fn with_bots(mut self, bots: YBotRegistry) -> Self {
+        self.bots = bots;
+        self
+    }
+
+    /// Returns the current game.
+    pub fn game(&self) -> &GameY {
+        &self.game
+    }
+
+    /// Returns true once the `quit` command has been received.
+    pub fn has_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Executes a single command and returns the response to send back.
+    pub fn execute(&mut self, command: &GtpCommand) -> GtpResponse {
+        let args: Vec<&str> = command.args.iter().map(String::as_str).collect();
+        let result = match command.name.as_str() {
+            "protocol_version" => Ok("2".to_string()),
+            "name" => Ok("gamey".to_string()),
+            "version" => Ok(env!("CARGO_PKG_VERSION").to_string()),
+            "known_command" => {
+                let name = args.first().copied().unwrap_or_default();
+                Ok(GTP_COMMANDS.contains(&name).to_string())
+            }
+            "list_commands" => Ok(GTP_COMMANDS.join("\n")),
+            "quit" => {
+                self.quit = true;
+                Ok(String::new())
+            }
+            "boardsize" => self.boardsize(&args),
+            "clear_board" => {
+                self.game = GameY::new(self.game.board_size());
+                Ok(String::new())
+            }
+            "play" => self.play(&args),
+            "genmove" => self.genmove(&args),
+            "undo" => self
+                .game
+                .undo_move()
+                .map(|_| String::new())
+                .map_err(|_| "cannot undo".to_string()),
+            "showboard" => Ok(self.showboard()),
+            "final_score" => self.final_score(),
+            "gamey-position" => Ok(self.game.to_position_string()),
+            "gamey-winning_moves" => self.winning_moves(&args),
+            "gamey-dead_cells" => Ok(self.indices(self.game.dead_cells())),
+            "gamey-influence" => Ok(self.influence()),
+            "gamey-bot" => self.select_bot(&args),
+            "gamey-list_bots" => {
+                let mut names = self.bots.names();
+                names.sort();
+                Ok(names.join(" "))
+            }
+            _ => Err("unknown command".to_string()),
+        };
+        GtpResponse {
+            id: command.id,
+            result,
+        }
+    }
+
+    /// Reads commands from `input` and writes the responses to `output`
+    /// until `quit` is received or the input ends.
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, mut output: W) -> std::io::Result<()> {
+        for line in input.lines() {
+            let Some(command) = GtpCommand::parse(&line?) else {
+                continue;
+            };
+            let response = self.execute(&command);
+            write!(output, "{}", response)?;
+            output.flush()?;
+            if self.quit {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles `boardsize <size>`: starts a new game on a board of that size.
+    fn boardsize(&mut self, args: &[&str]) -> Result<String, String> {
+        let size = args
+            .first()
+            .ok_or("boardsize requires a size")?
+            .parse::<u32>()
+            .map_err(|_| "boardsize is not a number")?;
+        if size == 0 || size > MAX_ENGINE_BOARD_SIZE {
+            return Err("unacceptable size".to_string());
+        }
+        self.game = GameY::new(size);
+        Ok(String::new())
+    }
+
+    /// Handles `play <color> <vertex>`.
+    ///
+    /// The vertex is a cell index, `swap` or `resign`.
+    fn play(&mut self, args: &[&str]) -> Result<String, String> {
+        let [color, vertex] = args else {
+            return Err("play requires a color and a vertex".to_string());
+        };
+        let player = parse_color(color)?;
+        let movement = match vertex.to_lowercase().as_str() {
+            "resign" => Movement::Action {
+                player,
+                action: GameAction::Resign,
+            },
+            "swap" => Movement::Action {
+                player,
+                action: GameAction::Swap,
+            },
+            index => {
+                let idx = parse_idx(index, self.game.total_cells())
+                    .map_err(|e| format!("invalid vertex: {}", e))?;
+                Movement::Placement {
+                    player,
+                    coords: Coordinates::from_index(idx, self.game.board_size()),
+                }
+            }
+        };
+        self.game
+            .add_move(movement)
+            .map(|_| String::new())
+            .map_err(|e| format!("illegal move: {}", e))
+    }
+
+    /// Handles `genmove <color>`: asks the bot for a move and plays it.
+    ///
+    /// The bot resigns when it has no move to offer.
+    fn genmove(&mut self, args: &[&str]) -> Result<String, String> {
+        let player = parse_color(args.first().ok_or("genmove requires a color")?)?;
+        if self.game.check_game_over() {
+            return Err("game is over".to_string());
+        }
+        if self.game.next_player() != Some(player) {
+            return Err(format!("it is not the turn of player {}", player));
+        }
+        let (movement, vertex) = match self.bot.choose_move(&self.game) {
+            Some(coords) => (
+                Movement::Placement { player, coords },
+                coords.to_index(self.game.board_size()).to_string(),
+            ),
+            None => (
+                Movement::Action {
+                    player,
+                    action: GameAction::Resign,
+                },
+                "resign".to_string(),
+            ),
+        };
+        self.game.add_move(movement).map_err(|e| e.to_string())?;
+        Ok(vertex)
+    }
+
+    /// Handles `showboard`: the board as text, starting on a new line.
+    ///
+    /// Blank lines are dropped, since an empty line ends a GTP response.
+    fn showboard(&self) -> String {
+        let options = RenderOptions {
+            show_3d_coords: false,
+            show_idx: true,
+            show_colors: false,
+        };
+        self.game
+            .render(&options)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .fold(String::new(), |board, line| board + "\n" + line.trim_end())
+    }
+
+    /// Handles `final_score`: `B+` or `W+` followed by how the game ended.
+    fn final_score(&self) -> Result<String, String> {
+        let result = self.game.result().ok_or("game is not over")?;
+        let color = if result.winner.id() == 0 { 'B' } else { 'W' };
+        Ok(format!("{}+{}", color, result.reason))
+    }
+
+    /// Handles `gamey-winning_moves <color>`: the cells that win at once.
+    fn winning_moves(&self, args: &[&str]) -> Result<String, String> {
+        let player = parse_color(args.first().ok_or("gamey-winning_moves requires a color")?)?;
+        let moves = self
+            .game
+            .legal_moves()
+            .filter(|coords| self.game.is_winning_move(*coords, player))
+            .collect();
+        Ok(self.indices(moves))
+    }
+
+    /// Handles `gamey-influence`: one score per cell, in board index order.
+    fn influence(&self) -> String {
+        self.game
+            .influence_map()
+            .iter()
+            .map(|score| format!("{:.2}", score))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Handles `gamey-bot [name]`: shows or changes the bot used by `genmove`.
+    fn select_bot(&mut self, args: &[&str]) -> Result<String, String> {
+        if let Some(name) = args.first() {
+            self.bot = self
+                .bots
+                .find(name)
+                .ok_or_else(|| format!("unknown bot {}", name))?;
+        }
+        Ok(self.bot.name().to_string())
+    }
+
+    /// Formats cells as space-separated board indices.
+    fn indices(&self, cells: Vec<Coordinates>) -> String {
+        cells
+            .iter()
+            .map(|coords| coords.to_index(self.game.board_size()).to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Parses a GTP colour: `b`/`black` is player 0 and `w`/`white` player 1.
+pub fn parse_color(color: &str) -> Result<PlayerId, String> {
+    match color.to_lowercase().as_str() {
+        "b" | "black" => Ok(PlayerId::new(0)),
+        "w" | "white" => Ok(PlayerId::new(1)),
+        _ => Err(format!("invalid color {}", color)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RandomBot;
+
+    fn engine() -> GtpEngine {
+        GtpEngine::new(3, Arc::new(RandomBot::new()))
+            .with_bots(YBotRegistry::new().with_bot(Arc::new(RandomBot::new())))
+    }
+
+    fn execute(engine: &mut GtpEngine, line: &str) -> Result<String, String> {
+        engine.execute(&GtpCommand::parse(line).unwrap()).result
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("B"), Ok(PlayerId::new(0)));
+        assert_eq!(parse_color("white"), Ok(PlayerId::new(1)));
+        assert!(parse_color("red").is_err());
+    }
+
+    #[test]
+    fn test_play_and_undo() {
+        let mut engine = engine();
+        assert_eq!(execute(&mut engine, "play b 4"), Ok(String::new()));
+        assert!(
+            execute(&mut engine, "play b 5")
+                .unwrap_err()
+                .starts_with("illegal move")
+        );
+        assert!(
+            execute(&mut engine, "play w 6")
+                .unwrap_err()
+                .starts_with("invalid vertex")
+        );
+        assert_eq!(engine.game().history().len(), 1);
+
+        assert_eq!(execute(&mut engine, "undo"), Ok(String::new()));
+        assert!(engine.game().history().is_empty());
+        assert_eq!(execute(&mut engine, "undo"), Err("cannot undo".to_string()));
+    }
+
+    #[test]
+    fn test_genmove_plays_for_the_side_to_move() {
+        let mut engine = engine();
+        assert!(execute(&mut engine, "genmove w").is_err());
+        let vertex = execute(&mut engine, "genmove b").unwrap();
+        let idx = vertex.parse::<u32>().unwrap();
+        assert!(idx < 6);
+        assert_eq!(engine.game().next_player(), Some(PlayerId::new(1)));
+    }
+
+    #[test]
+    fn test_final_score_and_winning_moves() {
+        let mut engine = engine();
+        assert!(execute(&mut engine, "final_score").is_err());
+        // Player 0 holds the top corner and the middle of side B
+        execute(&mut engine, "play b 0").unwrap();
+        execute(&mut engine, "play w 4").unwrap();
+        execute(&mut engine, "play b 1").unwrap();
+        assert_eq!(
+            execute(&mut engine, "gamey-winning_moves b"),
+            Ok("3".to_string())
+        );
+        execute(&mut engine, "play w 5").unwrap();
+        execute(&mut engine, "play b 3").unwrap();
+        assert_eq!(
+            execute(&mut engine, "final_score"),
+            Ok("B+connection".to_string())
+        );
+        assert_eq!(
+            execute(&mut engine, "genmove w"),
+            Err("game is over".to_string())
+        );
+    }
+
+    #[test]
+    fn test_boardsize_and_bot_selection() {
+        let mut engine = engine();
+        assert_eq!(execute(&mut engine, "boardsize 5"), Ok(String::new()));
+        assert_eq!(engine.game().board_size(), 5);
+        assert!(execute(&mut engine, "boardsize 0").is_err());
+        assert!(execute(&mut engine, "boardsize 100000").is_err());
+        assert_eq!(engine.game().board_size(), 5);
+        assert_eq!(
+            execute(&mut engine, "gamey-bot"),
+            Ok("random_bot".to_string())
+        );
+        assert!(execute(&mut engine, "gamey-bot missing").is_err());
+        assert_eq!(
+            execute(&mut engine, "gamey-list_bots"),
+            Ok("random_bot".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unknown_and_known_commands() {
+        let mut engine = engine();
+        assert_eq!(
+            execute(&mut engine, "known_command play"),
+            Ok("true".to_string())
+        );
+        assert_eq!(
+            execute(&mut engine, "known_command fly"),
+            Ok("false".to_string())
+        );
+        assert_eq!(
+            execute(&mut engine, "fly"),
+            Err("unknown command".to_string())
+        );
+    }
+}
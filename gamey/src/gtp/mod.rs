@@ -0,0 +1,28 @@
+//! A text protocol for driving the engine from board-game GUIs.
+//!
+//! The protocol follows the Go Text Protocol (GTP version 2), adapted to the
+//! game of Y so that existing GUIs and tournament managers can talk to the
+//! engine over stdin/stdout:
+//!
+//! - [`GtpCommand`] and [`GtpResponse`]: parsing and formatting of the wire format
+//! - [`GtpEngine`]: the engine state and the command set
+//!
+//! Cells (GTP "vertices") are written as board indices, the same numbers the
+//! CLI shows. Colours are `b`/`black` for player 0 and `w`/`white` for player 1.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//! use gamey::{GtpEngine, RandomBot};
+//!
+//! let mut engine = GtpEngine::new(5, Arc::new(RandomBot::new()));
+//! let mut output = Vec::new();
+//! engine.run("boardsize 3\nplay b 0\n".as_bytes(), &mut output).unwrap();
+//! assert_eq!(String::from_utf8(output).unwrap(), "= \n\n= \n\n");
+//! ```
+
+pub mod command;
+pub mod engine;
+pub use command::*;
+pub use engine::*;
@@ -0,0 +1,377 @@
+use crate::{
+    BoardTopology, GameStatus, GameYError, HavannahWinCondition, HexCoordinates, Neighbors,
+    PlacementGame, PlayerId, game,
+};
+use alloc::{vec, vec::Vec};
+use core::fmt::Display;
+use fixedbitset::FixedBitSet;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The structures that win a game of Havannah.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum HavannahWin {
+    /// A loop of stones around one or more cells.
+    Ring,
+    /// A chain connecting two corners.
+    Bridge,
+    /// A chain connecting three edges (corners do not count as edges).
+    Fork,
+}
+
+impl Display for HavannahWin {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HavannahWin::Ring => write!(f, "ring"),
+            HavannahWin::Bridge => write!(f, "bridge"),
+            HavannahWin::Fork => write!(f, "fork"),
+        }
+    }
+}
+
+/// The game state for Havannah.
+///
+/// Havannah is played on a hexagonal board. A player wins by building a
+/// ring, a bridge between two corners or a fork between three edges.
+#[derive(Debug, Clone)]
+pub struct GameHavannah {
+    // Number of cells along each side of the hexagon.
+    board_size: u32,
+
+    // Owner of each cell, indexed by board index.
+    cells: Vec<Option<PlayerId>>,
+
+    status: GameStatus,
+
+    // The structure that won the game, set together with a `Finished` status.
+    win: Option<HavannahWin>,
+
+    // Placements made so far, in order.
+    history: Vec<HexCoordinates>,
+
+    available_cells: Vec<u32>,
+}
+
+impl GameHavannah {
+    /// Creates a new game on a hexagonal board with `board_size` cells per side.
+    pub fn new(board_size: u32) -> Self {
+        let total_cells = Self::cells_for_size(board_size);
+        Self {
+            board_size,
+            cells: vec![None; total_cells as usize],
+            status: GameStatus::Ongoing {
+                next_player: PlayerId::new(0),
+            },
+            win: None,
+            history: Vec::new(),
+            available_cells: (0..total_cells).collect(),
+        }
+    }
+
+    fn cells_for_size(board_size: u32) -> u32 {
+        if board_size == 0 {
+            0
+        } else {
+            3 * board_size * (board_size - 1) + 1
+        }
+    }
+
+    /// Returns the number of cells along each side of the board.
+    pub fn board_size(&self) -> u32 {
+        self.board_size
+    }
+
+    /// Returns the total number of cells on the board.
+    pub fn total_cells(&self) -> u32 {
+        self.cells.len() as u32
+    }
+
+    /// Returns the current game status.
+    pub fn status(&self) -> &GameStatus {
+        &self.status
+    }
+
+    /// Returns true if the game has ended.
+    pub fn check_game_over(&self) -> bool {
+        matches!(self.status, GameStatus::Finished { .. })
+    }
+
+    /// Returns the player who should make the next move, or None if the game is over.
+    pub fn next_player(&self) -> Option<PlayerId> {
+        match self.status {
+            GameStatus::Ongoing { next_player } => Some(next_player),
+            GameStatus::Finished { .. } => None,
+        }
+    }
+
+    /// Returns the structure that won the game, or None if the game is ongoing.
+    pub fn winning_structure(&self) -> Option<HavannahWin> {
+        self.win
+    }
+
+    /// Returns the placements made so far, in order.
+    pub fn history(&self) -> &[HexCoordinates] {
+        &self.history
+    }
+
+    /// Returns the list of available cell indices where pieces can be placed.
+    pub fn available_cells(&self) -> &Vec<u32> {
+        &self.available_cells
+    }
+
+    /// Returns the player owning the stone at `coords`, or None if the cell is
+    /// empty or not on the board.
+    pub fn cell_owner(&self, coords: &HexCoordinates) -> Option<PlayerId> {
+        if !coords.is_valid(self.board_size) {
+            return None;
+        }
+        self.cells[coords.to_index(self.board_size) as usize]
+    }
+
+    /// Returns the neighbours of `coords` that are on the board.
+    pub fn get_neighbors(&self, coords: &HexCoordinates) -> Vec<HexCoordinates> {
+        coords
+            .neighbors()
+            .into_iter()
+            .filter(|n| n.is_valid(self.board_size))
+            .collect()
+    }
+
+    /// Places a stone of `player` at `coords`.
+    ///
+    /// Returns an error if the game is over, if it is not the player's turn,
+    /// or if the cell is off the board or already occupied.
+    pub fn add_move(&mut self, player: PlayerId, coords: HexCoordinates) -> game::Result<()> {
+        let next_player = match self.status {
+            GameStatus::Ongoing { next_player } => next_player,
+            GameStatus::Finished { .. } => {
+                return Err(GameYError::HavannahGameOver {
+                    coordinates: coords,
+                });
+            }
+        };
+        if player != next_player {
+            return Err(GameYError::InvalidPlayerTurn {
+                expected: next_player,
+                found: player,
+            });
+        }
+        if !coords.is_valid(self.board_size) {
+            return Err(GameYError::InvalidHexCoordinates {
+                coordinates: coords,
+                board_size: self.board_size,
+            });
+        }
+        let index = coords.to_index(self.board_size);
+        if self.cells[index as usize].is_some() {
+            return Err(GameYError::OccupiedHex {
+                coordinates: coords,
+                player,
+            });
+        }
+
+        self.cells[index as usize] = Some(player);
+        if let Ok(pos) = self.available_cells.binary_search(&index) {
+            self.available_cells.remove(pos);
+        }
+        self.history.push(coords);
+
+        self.win = HavannahWinCondition.winning_structure(self, &self.owned_cells(player));
+        self.status = if self.win.is_some() {
+            GameStatus::Finished { winner: player }
+        } else {
+            GameStatus::Ongoing {
+                next_player: game::other_player(player),
+            }
+        };
+        Ok(())
+    }
+
+    /// Returns the cells owned by `player` as a set of board indices.
+    pub fn owned_cells(&self, player: PlayerId) -> FixedBitSet {
+        let mut owned = FixedBitSet::with_capacity(self.cells.len());
+        for (idx, owner) in self.cells.iter().enumerate() {
+            if *owner == Some(player) {
+                owned.insert(idx);
+            }
+        }
+        owned
+    }
+}
+
+impl BoardTopology for GameHavannah {
+    fn num_cells(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn neighbors(&self, cell: usize) -> Neighbors {
+        let coords = HexCoordinates::from_index(cell as u32, self.board_size);
+        self.get_neighbors(&coords)
+            .iter()
+            .map(|neighbor| neighbor.to_index(self.board_size) as usize)
+            .collect()
+    }
+
+    fn edge_labels(&self, cell: usize) -> u8 {
+        HexCoordinates::from_index(cell as u32, self.board_size).edge_mask(self.board_size)
+    }
+}
+
+impl PlacementGame for GameHavannah {
+    fn total_cells(&self) -> u32 {
+        GameHavannah::total_cells(self)
+    }
+
+    fn free_cells(&self) -> &[u32] {
+        self.available_cells()
+    }
+
+    fn player_to_move(&self) -> Option<PlayerId> {
+        self.next_player()
+    }
+
+    fn place_stone(&mut self, player: PlayerId, index: u32) -> game::Result<()> {
+        let total_cells = GameHavannah::total_cells(self);
+        if index >= total_cells {
+            return Err(GameYError::InvalidCellIndex { index, total_cells });
+        }
+        self.add_move(player, HexCoordinates::from_index(index, self.board_size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays `stones` for player 0, answering each with a move of player 1
+    /// taken from `replies`.
+    fn play(game: &mut GameHavannah, stones: &[(i32, i32)], replies: &[(i32, i32)]) {
+        for (i, &(q, r)) in stones.iter().enumerate() {
+            game.add_move(PlayerId::new(0), HexCoordinates::new(q, r))
+                .unwrap();
+            if game.check_game_over() {
+                break;
+            }
+            let (rq, rr) = replies[i];
+            game.add_move(PlayerId::new(1), HexCoordinates::new(rq, rr))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_new_board() {
+        let game = GameHavannah::new(4);
+        assert_eq!(game.total_cells(), 37);
+        assert_eq!(game.available_cells().len(), 37);
+        assert_eq!(game.next_player(), Some(PlayerId::new(0)));
+    }
+
+    #[test]
+    fn test_bridge_between_two_corners() {
+        let mut game = GameHavannah::new(3);
+        // Corners (2, -2) and (2, 0) joined along the q = 2 edge
+        play(
+            &mut game,
+            &[(2, -2), (2, -1), (2, 0)],
+            &[(-2, 0), (-2, 1), (-2, 2)],
+        );
+        assert_eq!(game.winning_structure(), Some(HavannahWin::Bridge));
+        assert!(matches!(
+            game.status(),
+            GameStatus::Finished { winner } if *winner == PlayerId::new(0)
+        ));
+    }
+
+    #[test]
+    fn test_fork_between_three_edges() {
+        let mut game = GameHavannah::new(4);
+        // From the centre to the middle of three alternate edges
+        play(
+            &mut game,
+            &[
+                (0, 0),
+                (1, -1),
+                (2, -2),
+                (3, -2),
+                (-1, 0),
+                (-2, 0),
+                (-3, 1),
+                (0, 1),
+                (0, 2),
+                (-1, 3),
+            ],
+            &[
+                (3, -3),
+                (-3, 3),
+                (2, 0),
+                (-1, -1),
+                (1, -3),
+                (-2, 2),
+                (0, -2),
+                (2, -1),
+                (-1, 2),
+            ],
+        );
+        assert_eq!(game.winning_structure(), Some(HavannahWin::Fork));
+    }
+
+    #[test]
+    fn test_ring_around_a_cell() {
+        let mut game = GameHavannah::new(4);
+        let ring = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+        play(
+            &mut game,
+            &ring,
+            &[(3, -3), (-3, 3), (0, -3), (-3, 0), (3, 0)],
+        );
+        assert_eq!(game.winning_structure(), Some(HavannahWin::Ring));
+        assert_eq!(game.history().len(), 11);
+    }
+
+    #[test]
+    fn test_ring_around_an_opponent_stone() {
+        let mut game = GameHavannah::new(4);
+        game.add_move(PlayerId::new(0), HexCoordinates::new(1, 0))
+            .unwrap();
+        game.add_move(PlayerId::new(1), HexCoordinates::new(0, 0))
+            .unwrap();
+        play(
+            &mut game,
+            &[(1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)],
+            &[(3, -3), (-3, 3), (0, -3), (-3, 0)],
+        );
+        assert_eq!(game.winning_structure(), Some(HavannahWin::Ring));
+    }
+
+    #[test]
+    fn test_closed_triangle_is_not_a_ring() {
+        let mut game = GameHavannah::new(4);
+        play(
+            &mut game,
+            &[(0, 0), (1, 0), (1, -1)],
+            &[(3, -3), (3, -2), (3, -1)],
+        );
+        assert!(!game.check_game_over());
+    }
+
+    #[test]
+    fn test_add_move_errors() {
+        let mut game = GameHavannah::new(3);
+        assert!(matches!(
+            game.add_move(PlayerId::new(1), HexCoordinates::new(0, 0)),
+            Err(GameYError::InvalidPlayerTurn { .. })
+        ));
+        assert!(matches!(
+            game.add_move(PlayerId::new(0), HexCoordinates::new(3, 0)),
+            Err(GameYError::InvalidHexCoordinates { .. })
+        ));
+        game.add_move(PlayerId::new(0), HexCoordinates::new(0, 0))
+            .unwrap();
+        assert!(matches!(
+            game.add_move(PlayerId::new(1), HexCoordinates::new(0, 0)),
+            Err(GameYError::OccupiedHex { .. })
+        ));
+    }
+}
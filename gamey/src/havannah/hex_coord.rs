@@ -0,0 +1,173 @@
+use core::fmt::Display;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Axial coordinates (q, r) on a hexagonal Havannah board.
+///
+/// The board is centred on (0, 0). The implicit third coordinate is
+/// `s = -q - r`, and on a board of size N a cell is valid when
+/// `max(|q|, |r|, |s|) <= N - 1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HexCoordinates {
+    q: i32,
+    r: i32,
+}
+
+/// Offsets of the six neighbours of a cell, in axial coordinates.
+const DIRECTIONS: [(i32, i32); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+impl HexCoordinates {
+    /// Creates new coordinates with the given q and r values.
+    pub fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    /// Returns the q coordinate.
+    pub fn q(&self) -> i32 {
+        self.q
+    }
+
+    /// Returns the r coordinate.
+    pub fn r(&self) -> i32 {
+        self.r
+    }
+
+    /// Returns the implicit s coordinate (`-q - r`).
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// Returns true if the coordinates are valid for the given board size.
+    pub fn is_valid(&self, board_size: u32) -> bool {
+        board_size > 0 && self.ring() < board_size
+    }
+
+    /// Returns the distance from the centre of the board.
+    fn ring(&self) -> u32 {
+        self.q
+            .unsigned_abs()
+            .max(self.r.unsigned_abs())
+            .max(self.s().unsigned_abs())
+    }
+
+    /// Returns the six adjacent positions, including those off the board.
+    pub fn neighbors(&self) -> [HexCoordinates; 6] {
+        DIRECTIONS.map(|(dq, dr)| HexCoordinates::new(self.q + dq, self.r + dr))
+    }
+
+    /// Returns a bitmask of the board edges this cell lies on.
+    ///
+    /// There is one bit per edge (q, r and s at their maximum and minimum).
+    /// Interior cells return 0, edge cells one bit and corners two bits.
+    pub fn edge_mask(&self, board_size: u32) -> u8 {
+        let limit = board_size as i32 - 1;
+        [self.q, self.r, self.s()]
+            .iter()
+            .enumerate()
+            .fold(0, |mask, (axis, &value)| {
+                if value == limit {
+                    mask | 1 << (2 * axis)
+                } else if value == -limit {
+                    mask | 1 << (2 * axis + 1)
+                } else {
+                    mask
+                }
+            })
+    }
+
+    /// Returns true if the cell is one of the six corners of the board.
+    pub fn is_corner(&self, board_size: u32) -> bool {
+        self.edge_mask(board_size).count_ones() == 2
+    }
+
+    /// Converts coordinates to a linear index.
+    ///
+    /// Cells are numbered row by row, from the row with the smallest r and
+    /// by increasing q within a row.
+    pub fn to_index(&self, board_size: u32) -> u32 {
+        let n = board_size as i32;
+        let rows_before = self.r + n - 1;
+        let offset = if self.r <= 0 {
+            rows_before * n + rows_before * (rows_before - 1) / 2
+        } else {
+            let upper = n * n + n * (n - 1) / 2;
+            let j = self.r - 1;
+            upper + j * (2 * n - 1) - j * (j + 1) / 2
+        };
+        let q_min = (-(n - 1)).max(-(n - 1) - self.r);
+        (offset + self.q - q_min) as u32
+    }
+
+    /// Converts a linear index to coordinates.
+    ///
+    /// Inverse of [`HexCoordinates::to_index`].
+    pub fn from_index(index: u32, board_size: u32) -> Self {
+        let n = board_size as i32;
+        let mut remaining = index as i32;
+        for r in -(n - 1)..n {
+            let q_min = (-(n - 1)).max(-(n - 1) - r);
+            let q_max = (n - 1).min(n - 1 - r);
+            let len = q_max - q_min + 1;
+            if remaining < len {
+                return HexCoordinates::new(q_min + remaining, r);
+            }
+            remaining -= len;
+        }
+        // Past the last cell: return a position that is not on the board
+        HexCoordinates::new(n, n)
+    }
+}
+
+impl Display for HexCoordinates {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "({}, {})", self.q, self.r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_roundtrip() {
+        let size = 4;
+        let total = 3 * size * (size - 1) + 1;
+        for idx in 0..total {
+            let coords = HexCoordinates::from_index(idx, size);
+            assert!(coords.is_valid(size), "{} is not valid", coords);
+            assert_eq!(coords.to_index(size), idx);
+        }
+        assert!(!HexCoordinates::from_index(total, size).is_valid(size));
+    }
+
+    #[test]
+    fn test_is_valid() {
+        assert!(HexCoordinates::new(0, 0).is_valid(1));
+        assert!(HexCoordinates::new(2, -2).is_valid(3));
+        assert!(!HexCoordinates::new(2, 1).is_valid(3));
+        assert!(!HexCoordinates::new(0, 0).is_valid(0));
+    }
+
+    #[test]
+    fn test_corners_and_edges() {
+        let size = 3;
+        let corners = (0..19)
+            .map(|i| HexCoordinates::from_index(i, size))
+            .filter(|c| c.is_corner(size))
+            .count();
+        assert_eq!(corners, 6);
+        assert_eq!(HexCoordinates::new(0, 0).edge_mask(size), 0);
+        assert_eq!(HexCoordinates::new(2, -1).edge_mask(size).count_ones(), 1);
+        assert!(HexCoordinates::new(2, -2).is_corner(size));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let neighbors = HexCoordinates::new(0, 0).neighbors();
+        assert_eq!(neighbors.len(), 6);
+        assert!(neighbors.contains(&HexCoordinates::new(1, -1)));
+        assert!(neighbors.iter().all(|n| n.ring() == 1));
+    }
+}
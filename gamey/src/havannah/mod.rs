@@ -0,0 +1,26 @@
+//! The Havannah variant.
+//!
+//! Havannah is a connection game played on a hexagonal board. A player wins
+//! by completing one of three structures:
+//! - a ring around one or more cells,
+//! - a bridge connecting two corners,
+//! - a fork connecting three edges.
+//!
+//! [`GameHavannah`] implements [`PlacementGame`](crate::PlacementGame), so
+//! bots that support that interface can play it as well as Y, and
+//! [`BoardTopology`](crate::BoardTopology), so that together with
+//! [`HavannahWinCondition`] it can be searched by the minimax bot. Its rules
+//! are written in terms of that topology, so the game needs the `bots`
+//! feature; only [`HexCoordinates`] are always available.
+
+#[cfg(feature = "bots")]
+pub mod game_havannah;
+pub mod hex_coord;
+#[cfg(feature = "bots")]
+pub mod win_condition;
+
+#[cfg(feature = "bots")]
+pub use game_havannah::*;
+pub use hex_coord::*;
+#[cfg(feature = "bots")]
+pub use win_condition::*;
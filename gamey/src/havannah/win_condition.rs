@@ -0,0 +1,103 @@
+use crate::{BoardTopology, HavannahWin, WinCondition, connected_groups, flood_fill};
+use alloc::vec::Vec;
+use fixedbitset::FixedBitSet;
+
+/// The Havannah win condition: a ring, a bridge or a fork.
+///
+/// Works on any [`BoardTopology`] whose corners are labelled with two edge
+/// bits and whose other border cells with one, as [`GameHavannah`] does.
+///
+/// [`GameHavannah`]: crate::GameHavannah
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HavannahWinCondition;
+
+impl HavannahWinCondition {
+    /// Returns the winning structure formed by `owned`, if any.
+    ///
+    /// When several structures are present, bridges are reported before
+    /// forks and forks before rings.
+    pub fn winning_structure(
+        &self,
+        topology: &dyn BoardTopology,
+        owned: &FixedBitSet,
+    ) -> Option<HavannahWin> {
+        for group in connected_groups(topology, owned) {
+            let mut corners = 0;
+            let mut edges = 0u8;
+            for cell in group {
+                let labels = topology.edge_labels(cell);
+                match labels.count_ones() {
+                    2 => corners += 1,
+                    1 => edges |= labels,
+                    _ => {}
+                }
+            }
+            if corners >= 2 {
+                return Some(HavannahWin::Bridge);
+            }
+            if edges.count_ones() >= 3 {
+                return Some(HavannahWin::Fork);
+            }
+        }
+        encloses_cells(topology, owned).then_some(HavannahWin::Ring)
+    }
+}
+
+impl WinCondition for HavannahWinCondition {
+    fn is_win(&self, topology: &dyn BoardTopology, owned: &FixedBitSet) -> bool {
+        self.winning_structure(topology, owned).is_some()
+    }
+}
+
+/// Returns true if some cell outside `owned` is cut off from the border of
+/// the board by the cells of `owned`.
+fn encloses_cells(topology: &dyn BoardTopology, owned: &FixedBitSet) -> bool {
+    let mut free = owned.clone();
+    free.grow(topology.num_cells());
+    free.toggle_range(..);
+    let border = free
+        .ones()
+        .filter(|&cell| topology.edge_labels(cell) != 0)
+        .collect::<Vec<_>>();
+    flood_fill(topology, border, &free).count_ones(..) < free.count_ones(..)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameHavannah, HexCoordinates};
+
+    fn owned(game: &GameHavannah, cells: &[(i32, i32)]) -> FixedBitSet {
+        let mut set = FixedBitSet::with_capacity(game.total_cells() as usize);
+        for &(q, r) in cells {
+            set.insert(HexCoordinates::new(q, r).to_index(game.board_size()) as usize);
+        }
+        set
+    }
+
+    #[test]
+    fn test_structures() {
+        let game = GameHavannah::new(4);
+        let rule = HavannahWinCondition;
+        let ring = owned(&game, &[(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)]);
+        assert_eq!(
+            rule.winning_structure(&game, &ring),
+            Some(HavannahWin::Ring)
+        );
+        let bridge = owned(&game, &[(3, -3), (3, -2), (3, -1), (3, 0)]);
+        assert_eq!(
+            rule.winning_structure(&game, &bridge),
+            Some(HavannahWin::Bridge)
+        );
+        let open = owned(&game, &[(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1)]);
+        assert!(!rule.is_win(&game, &open));
+    }
+
+    #[test]
+    fn test_ring_along_the_border_is_not_enclosing_border_cells() {
+        let game = GameHavannah::new(3);
+        // A chain on the edge next to a corner does not enclose the corner
+        let chain = owned(&game, &[(1, -2), (2, -1)]);
+        assert!(!HavannahWinCondition.is_win(&game, &chain));
+    }
+}
@@ -6,12 +6,36 @@
 //! # Modules
 //!
 //! - [`core`]: Core game types including board, coordinates, and game logic
-//! - [`bot`]: Bot implementations for computer opponents
-//! - [`bot_server`]: HTTP server for bot API
-//! - [`cli`]: Command-line interface for interactive play
+//! - [`arena`]: Bot-versus-bot games and tournaments (`bots` feature)
+//! - [`bot`]: Bot implementations for computer opponents (`bots` feature)
+//! - [`bot_server`]: HTTP server for bot API (`server` feature)
+//! - [`game_server`]: HTTP server for playing games against the bots (`server` feature)
+//! - [`cli`]: Command-line interface for interactive play (`cli` feature)
+//! - [`gtp`]: GTP-style text protocol for GUIs and tournament managers (`cli` feature)
+//! - [`ytp`]: UCI-style stdio protocol with streaming search info (`cli` feature)
+//! - [`play_cli`]: Terminal game against a bot, used by `gamey-cli` (`cli` feature)
+//! - `wasm`: wasm-bindgen bindings for the browser (`wasm` feature)
+//! - `ffi`: C interface for embedding the engine (`ffi` feature)
+//! - [`explorer`]: Game databases indexed by position, for opening exploration (`std` feature)
 //! - [`notation`]: Game notation formats (YEN)
+//! - [`render`]: Text and SVG rendering of board positions (`std` feature)
+//! - [`session`]: Seats, turns and clocks for online games (`std` feature)
+//! - `tui`: Full-screen terminal interface (`tui` feature)
+//! - [`havannah`]: The Havannah variant on a hexagonal board (`bots` feature)
 //! - [`gamey_error`]: Error types for the library
 //!
+//! # `no_std`
+//!
+//! Without the default `std` feature, the crate is `no_std` and only needs
+//! `alloc`: it keeps [`GameY`], [`Coordinates`], moves, win detection and
+//! the [`YEN`] notation, so that the rules can run on embedded devices and
+//! in constrained WebAssembly runtimes. Everything else, from the bots to
+//! file I/O, needs `std`:
+//!
+//! ```toml
+//! gamey = { version = "0.1", default-features = false, features = ["serde"] }
+//! ```
+//!
 //! # Example
 //!
 //! ```
@@ -28,15 +52,71 @@
 //! game.add_move(movement).unwrap();
 //! ```
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "bots")]
+pub mod arena;
+#[cfg(feature = "bots")]
 pub mod bot;
+#[cfg(feature = "cli")]
 pub mod cli;
 pub mod core;
+#[cfg(feature = "std")]
+pub mod explorer;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod gamey_error;
+#[cfg(feature = "cli")]
+pub mod gtp;
+pub mod havannah;
 pub mod notation;
+#[cfg(feature = "cli")]
+pub mod play_cli;
+#[cfg(feature = "std")]
+pub mod render;
+#[cfg(feature = "std")]
+pub mod session;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "cli")]
+pub mod ytp;
+#[cfg(feature = "server")]
 pub mod bot_server;
+#[cfg(feature = "server")]
+pub mod game_server;
+#[cfg(feature = "bots")]
+pub use arena::*;
+#[cfg(feature = "bots")]
 pub use bot::*;
+#[cfg(feature = "cli")]
 pub use cli::*;
 pub use core::*;
+#[cfg(feature = "std")]
+pub use explorer::*;
+#[cfg(feature = "ffi")]
+pub use ffi::*;
 pub use gamey_error::*;
+#[cfg(feature = "cli")]
+pub use gtp::*;
+pub use havannah::*;
 pub use notation::*;
+#[cfg(feature = "cli")]
+pub use play_cli::*;
+#[cfg(feature = "std")]
+pub use render::*;
+#[cfg(feature = "std")]
+pub use session::*;
+#[cfg(feature = "tui")]
+pub use tui::*;
+#[cfg(feature = "wasm")]
+pub use wasm::*;
+#[cfg(feature = "cli")]
+pub use ytp::*;
+#[cfg(feature = "server")]
 pub use bot_server::*;
+#[cfg(feature = "server")]
+pub use game_server::*;
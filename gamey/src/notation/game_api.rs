@@ -0,0 +1,141 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Coordinates, GameResult, GameY, Movement, OpeningRule, YEN};
+
+/// Request body for creating a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NewGameRequest {
+    /// The size of the board.
+    pub size: u32,
+    /// A game record to start from, in a format read by
+    /// [`import_game_record`](crate::import_game_record), of a board of the
+    /// same size. The game starts from an empty board if None.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub record: Option<String>,
+    /// The opening rule of the game. Only games started on an empty board
+    /// may set one.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub opening_rule: OpeningRule,
+}
+
+/// Request body for placing a stone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MoveRequest {
+    /// The player placing the stone (0 or 1).
+    pub player: u32,
+    /// The board index of the cell.
+    pub cell: u32,
+}
+
+/// Request body for resigning a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ResignRequest {
+    /// The player who resigns (0 or 1).
+    pub player: u32,
+}
+
+/// Request body for asking a bot to play the next move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BotMoveRequest {
+    /// The name of the bot, e.g. `minimax_bot`.
+    pub bot_id: String,
+    /// The time the bot may spend searching, at most `MAX_BOT_TIME_MS` on the
+    /// game server. Bots that do not search ignore it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_time_ms: Option<u64>,
+}
+
+/// The state of a game, as returned by every game endpoint.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameStateResponse {
+    /// The identifier of the game on the server.
+    pub game_id: u64,
+    /// The position in YEN format.
+    pub yen: YEN,
+    /// The player to move, or None once the game is over.
+    pub next_player: Option<u32>,
+    /// The result, once the game is over.
+    pub result: Option<GameResult>,
+    /// How many stones the loser still needed to connect, once the game is
+    /// over (see [`GameY::closeness`]).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub closeness: Option<u32>,
+    /// Every move played so far, in order.
+    pub history: Vec<Movement>,
+}
+
+impl GameStateResponse {
+    /// Describes `game`, stored on the server as `game_id`.
+    pub fn new(game_id: u64, game: &GameY) -> Self {
+        GameStateResponse {
+            game_id,
+            yen: game.into(),
+            next_player: game.next_player().map(|player| player.id()),
+            result: game.result(),
+            closeness: game.closeness(),
+            history: game.history().to_vec(),
+        }
+    }
+}
+
+/// Response to a bot move request: the move played and the new state.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BotMoveResponse {
+    /// The bot that played.
+    pub bot_id: String,
+    /// The board index of the cell the bot played.
+    pub cell: u32,
+    /// The coordinates of that cell.
+    pub coords: Coordinates,
+    /// The game after the move.
+    pub state: GameStateResponse,
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::PlayerId;
+
+    #[test]
+    fn test_game_state_response_describes_game() {
+        let mut game = GameY::new(3);
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(0),
+            coords: Coordinates::from_index(4, 3),
+        })
+        .unwrap();
+        let state = GameStateResponse::new(7, &game);
+        assert_eq!(state.game_id, 7);
+        assert_eq!(state.next_player, Some(1));
+        assert!(state.result.is_none());
+        assert!(state.closeness.is_none());
+
+        let json = serde_json::to_value(&state).unwrap();
+        assert_eq!(json["yen"]["layout"], "./../.B.");
+        assert_eq!(json["history"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_game_state_response_reports_closeness() {
+        let mut game = GameY::new(3);
+        game.resign(PlayerId::new(1)).unwrap();
+        let json = serde_json::to_value(GameStateResponse::new(1, &game)).unwrap();
+        assert_eq!(json["closeness"], 3);
+    }
+
+    #[test]
+    fn test_bot_move_request_time_is_optional() {
+        let request: BotMoveRequest = serde_json::from_str(r#"{"bot_id":"random_bot"}"#).unwrap();
+        assert_eq!(request.max_time_ms, None);
+    }
+}
@@ -0,0 +1,668 @@
+//! Packed binary archives of many games.
+//!
+//! Self-play datasets hold millions of games, for which JSON records are
+//! far larger than needed. An archive written by an [`ArchiveWriter`] and
+//! read back by an [`ArchiveReader`] has one header for the whole file, with
+//! the board size, the opening rule and free-form metadata, followed by the
+//! games. Every number is a LEB128 varint and a move is a single number, so
+//! a game on a board of up to 14 takes one byte per move.
+//!
+//! The layout of a file:
+//!
+//! ```text
+//! magic       the bytes "YARC"
+//! version     one byte, currently 1
+//! board size  varint
+//! rule        varint: 0 free, 1 forbidden centre followed by its edge
+//!             distance as a varint, 2 one-two-two
+//! metadata    varint count, then the key and value of every entry, each as
+//!             a varint length and UTF-8 bytes
+//! games       for every game, a varint number of moves, then the moves
+//! ```
+//!
+//! A move is 0 for a swap, 1 to 6 for a forfeit (a resignation, a loss on
+//! time or an abandonment, by the player to move or by the other one) and
+//! the cell index plus 8 for a stone. Stones and swaps are played by the
+//! player to move.
+//!
+//! Archives convert from and to SGF through [`GameY::from_sgf`] and
+//! [`GameY::to_sgf`]:
+//!
+//! ```
+//! use gamey::{ArchiveHeader, ArchiveReader, ArchiveWriter, GameY};
+//!
+//! let sgf = "(;FF[4]SZ[5]RE[W+Resign];B[cc];W[bd];B[ae])";
+//! let mut writer = ArchiveWriter::new(Vec::new(), ArchiveHeader::new(5)).unwrap();
+//! writer.write_game(&GameY::from_sgf(sgf).unwrap()).unwrap();
+//! let bytes = writer.finish().unwrap();
+//! assert_eq!(bytes.len(), 13);
+//!
+//! let games: Vec<GameY> = ArchiveReader::new(&bytes[..])
+//!     .unwrap()
+//!     .collect::<gamey::Result<_>>()
+//!     .unwrap();
+//! assert_eq!(games[0].to_sgf().unwrap(), sgf);
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+use crate::{
+    Coordinates, GameAction, GameOverReason, GameY, GameYError, MAX_RECORD_BOARD_SIZE, Movement,
+    OpeningRule, PlayerId, Result, other_player,
+};
+
+/// The version of the archive format written by [`ArchiveWriter`].
+pub const ARCHIVE_VERSION: u8 = 1;
+
+const MAGIC: &[u8; 4] = b"YARC";
+const ARCHIVE: &str = "archive";
+
+const SWAP_CODE: u64 = 0;
+/// Forfeits are coded from 1 on, two codes per action: by the player to
+/// move, then by the other player.
+const FORFEIT_ACTIONS: [GameAction; 3] =
+    [GameAction::Resign, GameAction::Timeout, GameAction::Abandon];
+const FIRST_CELL_CODE: u64 = 8;
+
+fn invalid(reason: impl Into<String>) -> GameYError {
+    GameYError::InvalidGameRecord {
+        format: ARCHIVE.to_string(),
+        reason: reason.into(),
+    }
+}
+
+fn io_error(message: &str) -> impl FnOnce(std::io::Error) -> GameYError + '_ {
+    move |e| {
+        if e.kind() == ErrorKind::UnexpectedEof {
+            invalid("the file is cut short")
+        } else {
+            GameYError::IoError {
+                message: message.to_string(),
+                error: e.to_string(),
+            }
+        }
+    }
+}
+
+/// What every game of an archive shares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveHeader {
+    /// The size of the board of every game.
+    pub board_size: u32,
+    /// The opening rule of every game.
+    pub opening_rule: OpeningRule,
+    /// Free-form information about the games, such as the engine that
+    /// played them or its settings.
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl ArchiveHeader {
+    /// Creates the header of an archive of games on boards of `board_size`
+    /// with a free opening and no metadata.
+    pub fn new(board_size: u32) -> Self {
+        Self {
+            board_size,
+            opening_rule: OpeningRule::Free,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the opening rule of the games.
+    pub fn with_opening_rule(mut self, rule: OpeningRule) -> Self {
+        self.opening_rule = rule;
+        self
+    }
+
+    /// Adds an entry to the metadata.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns an empty game with the board size and opening rule of the
+    /// archive.
+    fn new_game(&self) -> GameY {
+        GameY::new(self.board_size).with_opening_rule(self.opening_rule)
+    }
+}
+
+/// Appends games to an archive.
+///
+/// The writer is buffered by [`ArchiveWriter::create`] but not by
+/// [`ArchiveWriter::new`]: wrap other writers in a
+/// [`BufWriter`] when they are costly to write to.
+pub struct ArchiveWriter<W: Write> {
+    writer: W,
+    header: ArchiveHeader,
+    games: u64,
+    buffer: Vec<u8>,
+}
+
+impl ArchiveWriter<BufWriter<File>> {
+    /// Creates the file at `path`, replacing any file there, and writes the
+    /// header.
+    pub fn create<P: AsRef<Path>>(path: P, header: ArchiveHeader) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to create file: {}", path.display()),
+            error: e.to_string(),
+        })?;
+        Self::new(BufWriter::new(file), header)
+    }
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    /// Writes the header to `writer`.
+    ///
+    /// Returns an error if the board size is 0 or larger than
+    /// [`MAX_RECORD_BOARD_SIZE`].
+    pub fn new(mut writer: W, header: ArchiveHeader) -> Result<Self> {
+        check_board_size(header.board_size)?;
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(ARCHIVE_VERSION);
+        write_varint(&mut bytes, u64::from(header.board_size));
+        match header.opening_rule {
+            OpeningRule::Free => write_varint(&mut bytes, 0),
+            OpeningRule::ForbiddenCentre { edge_distance } => {
+                write_varint(&mut bytes, 1);
+                write_varint(&mut bytes, u64::from(edge_distance));
+            }
+            OpeningRule::OneTwoTwo => write_varint(&mut bytes, 2),
+        }
+        write_varint(&mut bytes, header.metadata.len() as u64);
+        for (key, value) in &header.metadata {
+            for text in [key, value] {
+                write_varint(&mut bytes, text.len() as u64);
+                bytes.extend_from_slice(text.as_bytes());
+            }
+        }
+        writer
+            .write_all(&bytes)
+            .map_err(io_error("Failed to write archive header"))?;
+        Ok(Self {
+            writer,
+            header,
+            games: 0,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Returns the header of the archive.
+    pub fn header(&self) -> &ArchiveHeader {
+        &self.header
+    }
+
+    /// Returns the number of games written so far.
+    pub fn games_written(&self) -> u64 {
+        self.games
+    }
+
+    /// Appends the history of `game`.
+    ///
+    /// Returns an error, without writing anything, if the board size or the
+    /// opening rule of the game is not the one of the archive, or if its
+    /// history cannot be replayed from the empty board, as with the preset
+    /// stones of a [`GameYBuilder`](crate::GameYBuilder).
+    pub fn write_game(&mut self, game: &GameY) -> Result<()> {
+        if game.board_size() != self.header.board_size {
+            return Err(invalid(format!(
+                "a game of size {} in an archive of size {}",
+                game.board_size(),
+                self.header.board_size
+            )));
+        }
+        if game.opening_rule() != self.header.opening_rule {
+            return Err(invalid(format!(
+                "a game with the {} in an archive with the {}",
+                game.opening_rule(),
+                self.header.opening_rule
+            )));
+        }
+        let mut replayed = self.header.new_game();
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.clear();
+        write_varint(&mut buffer, game.history().len() as u64);
+        for (index, movement) in game.history().iter().enumerate() {
+            let code = replayed
+                .next_player()
+                .and_then(|to_move| encode_move(movement, to_move, game.board_size()))
+                .ok_or_else(|| invalid(format!("move {} cannot be archived", index + 1)))?;
+            write_varint(&mut buffer, code);
+            play(&mut replayed, movement.clone())?;
+        }
+        let written = self
+            .writer
+            .write_all(&buffer)
+            .map_err(io_error("Failed to write archive"));
+        self.buffer = buffer;
+        written?;
+        self.games += 1;
+        Ok(())
+    }
+
+    /// Flushes the archive and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        self.writer
+            .flush()
+            .map_err(io_error("Failed to write archive"))?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads the games of an archive, one at a time.
+///
+/// As an [`Iterator`] the reader yields every game replayed from its moves,
+/// and stops after the first error.
+pub struct ArchiveReader<R: Read> {
+    reader: R,
+    header: ArchiveHeader,
+    games: u64,
+    failed: bool,
+}
+
+impl ArchiveReader<BufReader<File>> {
+    /// Opens the archive at `path` and reads its header.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to open file: {}", path.display()),
+            error: e.to_string(),
+        })?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read> ArchiveReader<R> {
+    /// Reads the header of the archive from `reader`.
+    ///
+    /// Returns an error if the data is not an archive, was written by a
+    /// newer version of the format, or declares a board larger than
+    /// [`MAX_RECORD_BOARD_SIZE`].
+    pub fn new(mut reader: R) -> Result<Self> {
+        let read_error = io_error("Failed to read archive header");
+        let mut start = [0; 5];
+        reader.read_exact(&mut start).map_err(read_error)?;
+        if &start[..4] != MAGIC {
+            return Err(invalid("not an archive"));
+        }
+        if start[4] > ARCHIVE_VERSION {
+            return Err(invalid(format!(
+                "unsupported version {}, expected at most {}",
+                start[4], ARCHIVE_VERSION
+            )));
+        }
+        let board_size = u32::try_from(read_varint(&mut reader)?).unwrap_or(u32::MAX);
+        check_board_size(board_size)?;
+        let opening_rule = match read_varint(&mut reader)? {
+            0 => OpeningRule::Free,
+            1 => OpeningRule::ForbiddenCentre {
+                edge_distance: u32::try_from(read_varint(&mut reader)?)
+                    .map_err(|_| invalid("invalid edge distance"))?,
+            },
+            2 => OpeningRule::OneTwoTwo,
+            other => return Err(invalid(format!("unknown opening rule {}", other))),
+        };
+        let mut metadata = BTreeMap::new();
+        for _ in 0..read_varint(&mut reader)? {
+            let key = read_string(&mut reader)?;
+            let value = read_string(&mut reader)?;
+            metadata.insert(key, value);
+        }
+        Ok(Self {
+            reader,
+            header: ArchiveHeader {
+                board_size,
+                opening_rule,
+                metadata,
+            },
+            games: 0,
+            failed: false,
+        })
+    }
+
+    /// Returns the header of the archive.
+    pub fn header(&self) -> &ArchiveHeader {
+        &self.header
+    }
+
+    /// Reads the next game, or returns None at the end of the archive.
+    pub fn read_game(&mut self) -> Result<Option<GameY>> {
+        let mut first = [0];
+        loop {
+            match self.reader.read(&mut first) {
+                Ok(0) => return Ok(None),
+                Ok(_) => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(io_error("Failed to read archive")(e)),
+            }
+        }
+        let number = self.games + 1;
+        let in_game = |e: GameYError| match e {
+            GameYError::InvalidGameRecord { reason, .. } => {
+                invalid(format!("game {}: {}", number, reason))
+            }
+            e => e,
+        };
+        let moves = read_varint_after(first[0], &mut self.reader).map_err(in_game)?;
+        // Every move but a swap and a forfeit fills a cell
+        let total_cells =
+            u64::from(self.header.board_size) * u64::from(self.header.board_size + 1) / 2;
+        if moves > total_cells + 2 {
+            return Err(in_game(invalid(format!("{} moves", moves))));
+        }
+        let mut game = self.header.new_game();
+        for index in 0..moves {
+            let code = read_varint(&mut self.reader).map_err(in_game)?;
+            let movement = game
+                .next_player()
+                .and_then(|to_move| decode_move(code, to_move, self.header.board_size))
+                .ok_or_else(|| in_game(invalid(format!("invalid move {}", index + 1))))?;
+            play(&mut game, movement).map_err(|error| {
+                in_game(invalid(format!("illegal move {}: {}", index + 1, error)))
+            })?;
+        }
+        self.games += 1;
+        Ok(Some(game))
+    }
+}
+
+impl<R: Read> Iterator for ArchiveReader<R> {
+    type Item = Result<GameY>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let read = self.read_game();
+        self.failed = read.is_err();
+        read.transpose()
+    }
+}
+
+fn check_board_size(board_size: u32) -> Result<()> {
+    if board_size == 0 {
+        return Err(invalid("board size 0"));
+    }
+    if board_size > MAX_RECORD_BOARD_SIZE {
+        return Err(invalid(format!(
+            "board size {} is larger than the maximum of {}",
+            board_size, MAX_RECORD_BOARD_SIZE
+        )));
+    }
+    Ok(())
+}
+
+/// Returns the code of `movement` played when `to_move` is to move, or None
+/// if the move has no code.
+fn encode_move(movement: &Movement, to_move: PlayerId, board_size: u32) -> Option<u64> {
+    match movement {
+        Movement::Placement { player, coords } if *player == to_move => {
+            Some(FIRST_CELL_CODE + u64::from(coords.to_index(board_size)))
+        }
+        Movement::Action {
+            player,
+            action: GameAction::Swap,
+        } if *player == to_move => Some(SWAP_CODE),
+        Movement::Action { player, action } => {
+            let kind = FORFEIT_ACTIONS.iter().position(|a| a == action)? as u64;
+            Some(1 + 2 * kind + u64::from(*player != to_move))
+        }
+        Movement::Placement { .. } => None,
+    }
+}
+
+/// Returns the move of `code` when `to_move` is to move, or None for an
+/// unknown code.
+fn decode_move(code: u64, to_move: PlayerId, board_size: u32) -> Option<Movement> {
+    if code >= FIRST_CELL_CODE {
+        let index = u32::try_from(code - FIRST_CELL_CODE).ok()?;
+        if index >= board_size * (board_size + 1) / 2 {
+            return None;
+        }
+        return Some(Movement::Placement {
+            player: to_move,
+            coords: Coordinates::from_index(index, board_size),
+        });
+    }
+    let action = match code {
+        SWAP_CODE => GameAction::Swap,
+        code => FORFEIT_ACTIONS.get((code as usize - 1) / 2)?.clone(),
+    };
+    let by_other = code != SWAP_CODE && (code - 1) % 2 == 1;
+    Some(Movement::Action {
+        player: if by_other {
+            other_player(to_move)
+        } else {
+            to_move
+        },
+        action,
+    })
+}
+
+/// Plays a move of an archived game. Forfeits may be out of turn.
+fn play(game: &mut GameY, movement: Movement) -> Result<()> {
+    let reason = match &movement {
+        Movement::Action {
+            action: GameAction::Resign,
+            ..
+        } => GameOverReason::Resignation,
+        Movement::Action {
+            action: GameAction::Timeout,
+            ..
+        } => GameOverReason::Timeout,
+        Movement::Action {
+            action: GameAction::Abandon,
+            ..
+        } => GameOverReason::Abandonment,
+        _ => return game.add_move(movement),
+    };
+    let Movement::Action { player, .. } = movement else {
+        unreachable!("only actions are forfeits")
+    };
+    game.forfeit(player, reason)
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(reader: &mut impl Read) -> Result<u64> {
+    let mut byte = [0];
+    reader
+        .read_exact(&mut byte)
+        .map_err(io_error("Failed to read archive"))?;
+    read_varint_after(byte[0], reader)
+}
+
+/// Reads the rest of a varint whose first byte is `first`.
+fn read_varint_after(first: u8, reader: &mut impl Read) -> Result<u64> {
+    let mut value = u64::from(first & 0x7f);
+    let mut byte = [first];
+    let mut shift = 7;
+    while byte[0] & 0x80 != 0 {
+        if shift >= 64 {
+            return Err(invalid("a number is too large"));
+        }
+        reader
+            .read_exact(&mut byte)
+            .map_err(io_error("Failed to read archive"))?;
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        shift += 7;
+    }
+    Ok(value)
+}
+
+fn read_string(reader: &mut impl Read) -> Result<String> {
+    let len = read_varint(reader)?;
+    // Read through `take`, so that a corrupt length cannot allocate more
+    // than the data there is
+    let mut bytes = Vec::new();
+    reader
+        .take(len)
+        .read_to_end(&mut bytes)
+        .map_err(io_error("Failed to read archive header"))?;
+    if bytes.len() as u64 != len {
+        return Err(invalid("the file is cut short"));
+    }
+    String::from_utf8(bytes).map_err(|_| invalid("metadata is not UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays a whole game, spreading the stones over the board.
+    fn self_play(board_size: u32, rule: OpeningRule) -> GameY {
+        let mut game = GameY::new(board_size).with_opening_rule(rule);
+        while let Some(player) = game.next_player() {
+            let cells = game.available_cells();
+            let index = cells[game.history().len() * 7 % cells.len()];
+            let coords = Coordinates::from_index(index, board_size);
+            game.add_move(Movement::Placement { player, coords })
+                .unwrap();
+        }
+        game
+    }
+
+    fn write(header: ArchiveHeader, games: &[GameY]) -> Vec<u8> {
+        let mut writer = ArchiveWriter::new(Vec::new(), header).unwrap();
+        for game in games {
+            writer.write_game(game).unwrap();
+        }
+        assert_eq!(writer.games_written(), games.len() as u64);
+        writer.finish().unwrap()
+    }
+
+    fn read(bytes: &[u8]) -> Result<Vec<GameY>> {
+        ArchiveReader::new(bytes)?.collect()
+    }
+
+    #[test]
+    fn test_games_round_trip() {
+        let mut swapped = GameY::new(13);
+        swapped
+            .add_move(Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(6, 3, 3),
+            })
+            .unwrap();
+        swapped
+            .add_move(Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Swap,
+            })
+            .unwrap();
+        // Out of turn
+        let mut resigned = swapped.clone();
+        resigned.resign(PlayerId::new(1)).unwrap();
+        let mut timed_out = GameY::new(13);
+        timed_out
+            .forfeit(PlayerId::new(0), GameOverReason::Timeout)
+            .unwrap();
+        let games = [
+            self_play(13, OpeningRule::Free),
+            swapped,
+            resigned,
+            timed_out,
+            GameY::new(13),
+        ];
+
+        let header = ArchiveHeader::new(13).with_metadata("engine", "spread");
+        let bytes = write(header.clone(), &games);
+        let reader = ArchiveReader::new(&bytes[..]).unwrap();
+        assert_eq!(reader.header(), &header);
+        let read: Vec<GameY> = reader.collect::<Result<_>>().unwrap();
+        assert_eq!(read.len(), games.len());
+        for (read, game) in read.iter().zip(&games) {
+            assert_eq!(read.history(), game.history());
+            assert_eq!(read.result(), game.result());
+        }
+        // One byte per move and per number of moves, as the board has less
+        // than 120 cells
+        let moves: usize = games.iter().map(|game| game.history().len() + 1).sum();
+        let header_len = 4 + 1 + 1 + 1 + 1 + 1 + "engine".len() + 1 + "spread".len();
+        assert_eq!(bytes.len(), header_len + moves);
+    }
+
+    #[test]
+    fn test_opening_rules_are_kept() {
+        for rule in [
+            OpeningRule::ForbiddenCentre { edge_distance: 1 },
+            OpeningRule::OneTwoTwo,
+        ] {
+            let game = self_play(7, rule);
+            let bytes = write(
+                ArchiveHeader::new(7).with_opening_rule(rule),
+                std::slice::from_ref(&game),
+            );
+            let read = read(&bytes).unwrap();
+            assert_eq!(read[0].opening_rule(), rule);
+            assert_eq!(read[0].history(), game.history());
+        }
+    }
+
+    #[test]
+    fn test_writer_rejects_games_of_another_archive() {
+        let mut writer = ArchiveWriter::new(Vec::new(), ArchiveHeader::new(5)).unwrap();
+        assert!(writer.write_game(&GameY::new(6)).is_err());
+        let rule = GameY::new(5).with_opening_rule(OpeningRule::OneTwoTwo);
+        assert!(writer.write_game(&rule).is_err());
+        let preset = crate::GameYBuilder::new(5)
+            .with_stone(PlayerId::new(1), Coordinates::new(2, 1, 1))
+            .build()
+            .unwrap();
+        assert!(writer.write_game(&preset).is_err());
+        assert_eq!(writer.games_written(), 0);
+        assert_eq!(writer.finish().unwrap().len(), 8);
+        assert!(ArchiveWriter::new(Vec::new(), ArchiveHeader::new(0)).is_err());
+    }
+
+    #[test]
+    fn test_reader_rejects_corrupt_archives() {
+        let bytes = write(ArchiveHeader::new(5), &[self_play(5, OpeningRule::Free)]);
+        assert_eq!(read(&bytes).unwrap().len(), 1);
+        // Cut in the middle of the game
+        assert!(read(&bytes[..bytes.len() - 1]).is_err());
+        // Not an archive, a newer version, a board too large
+        assert!(read(b"YAR").is_err());
+        assert!(read(b"JSON\x01\x05\x00\x00").is_err());
+        assert!(read(b"YARC\x02\x05\x00\x00").is_err());
+        assert!(read(b"YARC\x01\x81\x02\x00\x00").is_err());
+        // A metadata length past the end of the file
+        assert!(read(b"YARC\x01\x05\x00\x01\xff\xff\xff\x0f").is_err());
+        // A stone on a cell that does not exist, and on an occupied cell
+        assert!(read(b"YARC\x01\x02\x00\x00\x01\x0b").is_err());
+        assert!(read(b"YARC\x01\x02\x00\x00\x02\x08\x08").is_err());
+        // More moves than a game can have
+        assert!(read(b"YARC\x01\x02\x00\x00\x06").is_err());
+
+        let mut reader = ArchiveReader::new(&b"YARC\x01\x02\x00\x00\x01\x0b\x00"[..]).unwrap();
+        let error = reader.next().unwrap().unwrap_err().to_string();
+        assert!(error.contains("game 1"), "{}", error);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("games.yarc");
+        let game = self_play(9, OpeningRule::Free);
+        let mut writer = ArchiveWriter::create(&path, ArchiveHeader::new(9)).unwrap();
+        writer.write_game(&game).unwrap();
+        writer.write_game(&game).unwrap();
+        writer.finish().unwrap();
+        let games: Vec<GameY> = ArchiveReader::open(&path)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[1].history(), game.history());
+    }
+}
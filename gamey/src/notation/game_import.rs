@@ -0,0 +1,700 @@
+use crate::{
+    Coordinates, GameAction, GameOverReason, GameY, GameYError, Movement, PlayerId, Result,
+    ValidationError,
+};
+use alloc::{format, string::String, string::ToString, vec::Vec};
+
+const SGF: &str = "SGF";
+const MOVE_STRING: &str = "move string";
+
+fn invalid(format: &str, reason: impl Into<String>) -> GameYError {
+    GameYError::InvalidGameRecord {
+        format: format.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// The largest board a game record may declare to be imported.
+pub const MAX_RECORD_BOARD_SIZE: u32 = 256;
+
+/// Imports a game record, detecting its format.
+///
+/// Records starting with `(;` are read with [`GameY::from_sgf`], anything
+/// else with [`GameY::from_move_string`]. Like every importer, it rejects
+/// boards larger than [`MAX_RECORD_BOARD_SIZE`] before allocating them.
+pub fn import_game_record(record: &str) -> Result<GameY> {
+    Ok(parse_record(record)?.replay()?)
+}
+
+/// Imports a game record like [`import_game_record`], rejecting any record
+/// that is not a consistent game.
+///
+/// Every move is replayed and must be legal and played in turn, and a
+/// result declared by the record (the `RE` property of SGF) must name the
+/// winner of the replayed game. The importers accept such a result on its
+/// own terms when the moves do not finish the game, as a resignation or a
+/// loss on time, and ignore it when the moves decide the game otherwise.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{ValidationError, validate_record};
+///
+/// let game = validate_record("(;SZ[3]RE[B+Resign];B[aa];W[bb])").unwrap();
+/// assert!(game.check_game_over());
+/// assert!(matches!(
+///     validate_record("(;SZ[3];B[aa];W[aa])"),
+///     Err(ValidationError::IllegalMove { number: 2, .. })
+/// ));
+/// assert!(matches!(
+///     validate_record("(;SZ[2]RE[W];B[aa];W[ab];B[bb])"),
+///     Err(ValidationError::ResultMismatch { .. })
+/// ));
+/// ```
+pub fn validate_record(record: &str) -> core::result::Result<GameY, ValidationError> {
+    let parsed = parse_record(record).map_err(ValidationError::Unreadable)?;
+    let game = parsed.replay()?;
+    if let (Some((declared, _)), Some(result)) = (parsed.declared, game.result())
+        && declared != result.winner
+    {
+        return Err(ValidationError::ResultMismatch {
+            declared,
+            actual: result.winner,
+        });
+    }
+    Ok(game)
+}
+
+fn parse_record(record: &str) -> Result<ParsedRecord> {
+    if record.trim_start().starts_with("(;") {
+        parse_sgf(record)
+    } else {
+        parse_move_string(record)
+    }
+}
+
+/// A game record read from text, before its moves are replayed.
+struct ParsedRecord {
+    format: &'static str,
+    board_size: u32,
+    /// The moves, with their player if the record names it, and otherwise
+    /// played by the player to move.
+    moves: Vec<(Option<PlayerId>, RecordedMove)>,
+    /// The winner and how they won, as declared by the record.
+    declared: Option<(PlayerId, GameOverReason)>,
+}
+
+enum RecordedMove {
+    Stone(Coordinates),
+    Action(GameAction),
+}
+
+impl ParsedRecord {
+    /// Replays the moves, then applies the declared result if the moves did
+    /// not finish the game.
+    ///
+    /// Boards larger than [`MAX_RECORD_BOARD_SIZE`] are rejected before any
+    /// cell is allocated.
+    fn replay(&self) -> core::result::Result<GameY, ValidationError> {
+        if self.board_size > MAX_RECORD_BOARD_SIZE {
+            return Err(ValidationError::BoardTooLarge {
+                board_size: self.board_size,
+                max: MAX_RECORD_BOARD_SIZE,
+            });
+        }
+        let mut game = GameY::new(self.board_size);
+        for (index, (player, recorded)) in self.moves.iter().enumerate() {
+            let illegal = |error| ValidationError::IllegalMove {
+                number: index + 1,
+                error,
+            };
+            let player = match player.or_else(|| game.next_player()) {
+                Some(player) => player,
+                None => {
+                    return Err(illegal(invalid(
+                        self.format,
+                        format!("move {} after the end of the game", index + 1),
+                    )));
+                }
+            };
+            let movement = match recorded {
+                RecordedMove::Stone(coords) => Movement::Placement {
+                    player,
+                    coords: *coords,
+                },
+                RecordedMove::Action(action) => Movement::Action {
+                    player,
+                    action: action.clone(),
+                },
+            };
+            game.add_move(movement).map_err(illegal)?;
+        }
+        if let Some((winner, reason)) = self.declared {
+            apply_result(&mut game, winner, reason).map_err(ValidationError::Unreadable)?;
+        }
+        Ok(game)
+    }
+}
+
+/// A property of an SGF node, such as `SZ[13]`.
+pub(crate) struct SgfProperty {
+    pub(crate) name: String,
+    pub(crate) values: Vec<String>,
+}
+
+/// Reads the nodes of the main line of an SGF game tree.
+///
+/// Variations are skipped: the first branch of every fork is followed and
+/// reading stops at the end of it.
+pub(crate) fn parse_sgf_main_line(sgf: &str) -> Result<Vec<Vec<SgfProperty>>> {
+    let mut chars = sgf.trim().chars().peekable();
+    if chars.next() != Some('(') {
+        return Err(invalid(SGF, "expected '(' at the start of the game tree"));
+    }
+    let mut nodes: Vec<Vec<SgfProperty>> = Vec::new();
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => nodes.push(Vec::new()),
+            '(' => {}
+            ')' => return Ok(nodes),
+            c if c.is_whitespace() => {}
+            c if c.is_ascii_alphabetic() => {
+                let node = nodes
+                    .last_mut()
+                    .ok_or_else(|| invalid(SGF, "property outside of a node"))?;
+                let mut name = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+                    name.push(c);
+                }
+                let mut values = Vec::new();
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                while chars.next_if_eq(&'[').is_some() {
+                    let mut value = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('\\') => value.extend(chars.next()),
+                            Some(']') => break,
+                            Some(c) => value.push(c),
+                            None => {
+                                return Err(invalid(SGF, format!("unterminated {} value", name)));
+                            }
+                        }
+                    }
+                    values.push(value);
+                    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                }
+                if values.is_empty() {
+                    return Err(invalid(SGF, format!("property {} has no value", name)));
+                }
+                node.push(SgfProperty { name, values });
+            }
+            other => return Err(invalid(SGF, format!("unexpected character '{}'", other))),
+        }
+    }
+    Err(invalid(SGF, "unterminated game tree"))
+}
+
+/// Parses an SGF point: the column within the row, then the row from the top.
+fn parse_sgf_point(point: &str, board_size: u32) -> Option<Coordinates> {
+    let bytes = point.as_bytes();
+    if bytes.len() != 2 || !bytes.iter().all(u8::is_ascii_lowercase) {
+        return None;
+    }
+    let (column, row) = (u32::from(bytes[0] - b'a'), u32::from(bytes[1] - b'a'));
+    if row >= board_size || column > row {
+        return None;
+    }
+    Some(Coordinates::new(board_size - 1 - row, column, row - column))
+}
+
+/// Writes an SGF point, the inverse of [`parse_sgf_point`]. Only boards up to
+/// size 26 have points.
+fn write_sgf_point(coords: Coordinates, board_size: u32) -> String {
+    let row = board_size - 1 - coords.x();
+    [coords.y(), row]
+        .iter()
+        .map(|&n| char::from(b'a' + n as u8))
+        .collect()
+}
+
+/// Writes the value of the SGF result property for a game won by `winner`.
+pub(crate) fn sgf_result(winner: PlayerId, reason: GameOverReason) -> String {
+    let how = match reason {
+        GameOverReason::Connection => "",
+        GameOverReason::Resignation => "+Resign",
+        GameOverReason::Timeout => "+Time",
+        GameOverReason::Abandonment => "+Forfeit",
+    };
+    format!("{}{}", sgf_color(winner), how)
+}
+
+/// Returns the SGF colour of `player`: player 0 plays black.
+pub(crate) fn sgf_color(player: PlayerId) -> &'static str {
+    if player.id() == 0 { "B" } else { "W" }
+}
+
+/// Applies the result of a record to a game that the moves did not finish.
+fn apply_result(game: &mut GameY, winner: PlayerId, reason: GameOverReason) -> Result<()> {
+    if game.check_game_over() {
+        return Ok(());
+    }
+    game.forfeit(crate::other_player(winner), reason)
+}
+
+impl GameY {
+    /// Imports a game in the SGF dialect used by Little Golem.
+    ///
+    /// The board size is read from `SZ` and moves from the `B` and `W`
+    /// properties of the main line. A point such as `[cf]` names the third
+    /// cell of the sixth row: the first letter is the column within the row
+    /// and the second letter the row, both starting at `a`, with row `a` the
+    /// top corner. The values `swap` and `resign` are accepted in place of a
+    /// point. The colour that moves first becomes player 0.
+    ///
+    /// If the moves do not finish the game and `RE` names a winner (`B`,
+    /// `W+Resign`, `B+Time`, ...), the loser is recorded as resigning, or as
+    /// losing on time for a `T`/`Time` result.
+    ///
+    /// ```
+    /// use gamey::GameY;
+    ///
+    /// let game = GameY::from_sgf("(;FF[4]SZ[3]PB[alice]PW[bob];B[ab];W[swap];B[bc])").unwrap();
+    /// assert_eq!(game.history().len(), 3);
+    /// ```
+    pub fn from_sgf(sgf: &str) -> Result<GameY> {
+        Ok(parse_sgf(sgf)?.replay()?)
+    }
+
+    /// Exports the game in the SGF dialect read by [`GameY::from_sgf`].
+    ///
+    /// Player 0 plays black. Stones and swaps are written as moves and the
+    /// result as `RE`: the winner alone after a connection, or with
+    /// `+Resign`, `+Time` or `+Forfeit` (for an abandonment, which is read
+    /// back as a resignation) after a forfeit. Returns an error for boards
+    /// larger than 26, whose cells have no SGF point, and for games under an
+    /// [`OpeningRule`](crate::OpeningRule) other than the free opening, which
+    /// SGF cannot record.
+    ///
+    /// ```
+    /// use gamey::GameY;
+    ///
+    /// let sgf = "(;FF[4]SZ[3];B[ab];W[swap];B[bc])";
+    /// let game = GameY::from_sgf(sgf).unwrap();
+    /// assert_eq!(game.to_sgf().unwrap(), sgf);
+    /// ```
+    pub fn to_sgf(&self) -> Result<String> {
+        self.write_sgf(&[])
+    }
+
+    /// Writes the game like [`GameY::to_sgf`], with `properties` added to
+    /// the root node after the board size. A result property among them is
+    /// only written for games that have no result of their own.
+    pub(crate) fn write_sgf(&self, properties: &[(&str, String)]) -> Result<String> {
+        let board_size = self.board_size();
+        if board_size > 26 {
+            return Err(invalid(
+                SGF,
+                format!("board size {} is larger than 26", board_size),
+            ));
+        }
+        if self.opening_rule() != crate::OpeningRule::Free {
+            return Err(invalid(
+                SGF,
+                format!("the {} cannot be recorded", self.opening_rule()),
+            ));
+        }
+        let mut moves = String::new();
+        for movement in self.history() {
+            match movement {
+                Movement::Placement { player, coords } => {
+                    let point = write_sgf_point(*coords, board_size);
+                    moves.push_str(&format!(";{}[{}]", sgf_color(*player), point));
+                }
+                Movement::Action {
+                    player,
+                    action: GameAction::Swap,
+                } => moves.push_str(&format!(";{}[swap]", sgf_color(*player))),
+                // Forfeits are written as the result
+                Movement::Action { .. } => {}
+            }
+        }
+        let mut root = format!("FF[4]SZ[{}]", board_size);
+        let result = self.result();
+        for (name, value) in properties {
+            if *name == "RE" && result.is_some() {
+                continue;
+            }
+            let value = value.replace('\\', "\\\\").replace(']', "\\]");
+            root.push_str(&format!("{}[{}]", name, value));
+        }
+        if let Some(result) = result {
+            root.push_str(&format!("RE[{}]", sgf_result(result.winner, result.reason)));
+        }
+        Ok(format!("(;{}{})", root, moves))
+    }
+
+    /// Imports a game written as a HexWorld-style move string.
+    ///
+    /// The string starts with the board size followed by a comma, optionally
+    /// with a `c1` marker after the size, and continues with the moves, which
+    /// may be run together or separated by spaces or commas. Cells are named
+    /// as in [`Coordinates::to_cell_name`], `:s` (or `swap`) is the swap and
+    /// `:r` (or `resign`) a resignation. A board URL is accepted too; only
+    /// the part after `#` is read.
+    ///
+    /// ```
+    /// use gamey::GameY;
+    ///
+    /// let game = GameY::from_move_string("5c1,c2b1:sd3").unwrap();
+    /// assert_eq!(game.board_size(), 5);
+    /// assert_eq!(game.history().len(), 4);
+    /// ```
+    pub fn from_move_string(moves: &str) -> Result<GameY> {
+        Ok(parse_move_string(moves)?.replay()?)
+    }
+}
+
+fn parse_sgf(sgf: &str) -> Result<ParsedRecord> {
+    let nodes = parse_sgf_main_line(sgf)?;
+    let property = |name: &str| {
+        nodes
+            .iter()
+            .flatten()
+            .find(|property| property.name == name)
+            .map(|property| property.values[0].trim())
+    };
+    let size_value = property("SZ").ok_or_else(|| invalid(SGF, "missing board size (SZ)"))?;
+    // Some editors write rectangular sizes as "cols:rows"
+    let board_size: u32 = size_value
+        .split(':')
+        .next()
+        .and_then(|size| size.parse().ok())
+        .filter(|size| *size > 0)
+        .ok_or_else(|| invalid(SGF, format!("invalid board size '{}'", size_value)))?;
+
+    let mut moves = Vec::new();
+    let mut first_color: Option<&str> = None;
+    for property in nodes.iter().flatten() {
+        let color = property.name.as_str();
+        if color != "B" && color != "W" {
+            continue;
+        }
+        let first = *first_color.get_or_insert(color);
+        let player = PlayerId::new(if color == first { 0 } else { 1 });
+        let value = property.values[0].trim();
+        let recorded = match value.to_ascii_lowercase().as_str() {
+            "swap" | "swap-pieces" | "swap-sides" => RecordedMove::Action(GameAction::Swap),
+            "resign" => RecordedMove::Action(GameAction::Resign),
+            point => RecordedMove::Stone(
+                parse_sgf_point(point, board_size)
+                    .ok_or_else(|| invalid(SGF, format!("invalid point {}[{}]", color, value)))?,
+            ),
+        };
+        moves.push((Some(player), recorded));
+    }
+
+    let mut declared = None;
+    if let (Some(result), Some(first)) = (property("RE"), first_color) {
+        let (winner, how) = result.split_once('+').unwrap_or((result, ""));
+        let winner = match winner.trim() {
+            "B" | "W" if winner.trim() == first => Some(PlayerId::new(0)),
+            "B" | "W" => Some(PlayerId::new(1)),
+            _ => None,
+        };
+        if let Some(winner) = winner {
+            let reason = if how.trim().starts_with('T') {
+                GameOverReason::Timeout
+            } else {
+                GameOverReason::Resignation
+            };
+            declared = Some((winner, reason));
+        }
+    }
+    Ok(ParsedRecord {
+        format: SGF,
+        board_size,
+        moves,
+        declared,
+    })
+}
+
+fn parse_move_string(moves: &str) -> Result<ParsedRecord> {
+    let moves = moves.trim();
+    let moves = moves
+        .rsplit_once('#')
+        .map_or(moves, |(_, fragment)| fragment);
+    let (header, body) = moves
+        .split_once(',')
+        .ok_or_else(|| invalid(MOVE_STRING, "expected the board size followed by ','"))?;
+    let size = header.trim().trim_end_matches("c1");
+    let board_size: u32 = size
+        .parse()
+        .ok()
+        .filter(|size| *size > 0)
+        .ok_or_else(|| invalid(MOVE_STRING, format!("invalid board size '{}'", header)))?;
+
+    let mut recorded = Vec::new();
+    let mut chars = body.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() || c == ',' {
+            chars.next();
+            continue;
+        }
+        let mut token = String::new();
+        if c == ':' {
+            chars.next();
+            token.push(':');
+            token.extend(chars.next());
+        } else {
+            while let Some(c) = chars.next_if(char::is_ascii_alphabetic) {
+                token.push(c);
+            }
+            while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                token.push(c);
+            }
+            if token.is_empty() {
+                return Err(invalid(
+                    MOVE_STRING,
+                    format!("unexpected character '{}'", c),
+                ));
+            }
+        }
+        let movement = match token.to_ascii_lowercase().as_str() {
+            ":s" | "swap" => RecordedMove::Action(GameAction::Swap),
+            ":r" | "resign" => RecordedMove::Action(GameAction::Resign),
+            name => RecordedMove::Stone(
+                Coordinates::from_cell_name(name, board_size)
+                    .ok_or_else(|| invalid(MOVE_STRING, format!("invalid cell '{}'", token)))?,
+            ),
+        };
+        // The players of a move string take turns
+        recorded.push((None, movement));
+    }
+    Ok(ParsedRecord {
+        format: MOVE_STRING,
+        board_size,
+        moves: recorded,
+        declared: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_sgf_moves_and_swap() {
+        let game = GameY::from_sgf(
+            "(;FF[4]EV[y.mig.23]PB[alice]PW[bob]SZ[4]\n;B[bc];W[swap]\n;B[aa];W[cd])",
+        )
+        .unwrap();
+        assert_eq!(game.board_size(), 4);
+        let history = game.history();
+        assert_eq!(history.len(), 4);
+        assert_eq!(
+            history[0],
+            Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(1, 1, 1),
+            }
+        );
+        assert_eq!(
+            history[1],
+            Movement::Action {
+                player: PlayerId::new(1),
+                action: GameAction::Swap,
+            }
+        );
+        assert_eq!(
+            history[3],
+            Movement::Placement {
+                player: PlayerId::new(1),
+                coords: Coordinates::new(0, 2, 1),
+            }
+        );
+    }
+
+    #[test]
+    fn test_sgf_first_colour_is_player_zero() {
+        let game = GameY::from_sgf("(;SZ[3];W[aa];B[ab])").unwrap();
+        assert_eq!(
+            game.history()[1],
+            Movement::Placement {
+                player: PlayerId::new(1),
+                coords: Coordinates::new(1, 0, 1),
+            }
+        );
+    }
+
+    #[test]
+    fn test_sgf_result_records_resignation_or_timeout() {
+        let game = GameY::from_sgf("(;SZ[4]RE[W+Resign];B[aa];W[bb])").unwrap();
+        let result = game.result().unwrap();
+        assert_eq!(result.winner, PlayerId::new(1));
+        assert_eq!(result.reason, GameOverReason::Resignation);
+
+        let game = GameY::from_sgf("(;SZ[4]RE[B+T];B[aa];W[bb])").unwrap();
+        assert_eq!(game.result().unwrap().reason, GameOverReason::Timeout);
+
+        // A game won on the board keeps its connection result
+        let game = GameY::from_sgf("(;SZ[2]RE[B];B[aa];W[ab];B[bb])").unwrap();
+        assert_eq!(game.result().unwrap().reason, GameOverReason::Connection);
+    }
+
+    #[test]
+    fn test_sgf_follows_the_main_line() {
+        let game = GameY::from_sgf("(;SZ[4];B[aa](;W[ab];B[bb])(;W[bb]))").unwrap();
+        assert_eq!(game.history().len(), 3);
+    }
+
+    #[test]
+    fn test_sgf_errors() {
+        assert!(GameY::from_sgf("(;B[aa])").is_err());
+        assert!(GameY::from_sgf("(;SZ[3];B[ba])").is_err());
+        assert!(GameY::from_sgf("(;SZ[3];B[aa]").is_err());
+        assert!(matches!(
+            GameY::from_sgf("(;SZ[3];B[aa];B[ab])"),
+            Err(GameYError::InvalidPlayerTurn { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sgf_export_round_trips() {
+        let game = GameY::from_sgf("(;SZ[5];B[ce];W[swap];B[bd];W[cc])").unwrap();
+        let mut resigned = game.clone();
+        // Out of turn, which SGF can only record as the result
+        resigned.resign(PlayerId::new(0)).unwrap();
+        let mut timed_out = game.clone();
+        timed_out
+            .forfeit(PlayerId::new(1), GameOverReason::Timeout)
+            .unwrap();
+        for game in [game, resigned, timed_out] {
+            let sgf = game.to_sgf().unwrap();
+            let imported = GameY::from_sgf(&sgf).unwrap();
+            assert_eq!(imported.history(), game.history(), "{}", sgf);
+            assert_eq!(imported.result(), game.result(), "{}", sgf);
+        }
+
+        let won = GameY::from_sgf("(;SZ[2];B[aa];W[ab];B[bb])").unwrap();
+        assert_eq!(
+            won.to_sgf().unwrap(),
+            "(;FF[4]SZ[2]RE[B];B[aa];W[ab];B[bb])"
+        );
+    }
+
+    #[test]
+    fn test_sgf_export_errors() {
+        assert!(GameY::new(27).to_sgf().is_err());
+        assert!(GameY::new(26).to_sgf().is_ok());
+        let game = GameY::new(5).with_opening_rule(crate::OpeningRule::OneTwoTwo);
+        assert!(game.to_sgf().is_err());
+    }
+
+    #[test]
+    fn test_move_string() {
+        let game =
+            GameY::from_move_string("https://hexworld.org/board/#4c1,b2a1:s c3, d4").unwrap();
+        assert_eq!(game.board_size(), 4);
+        let history = game.history();
+        assert_eq!(history.len(), 5);
+        assert_eq!(
+            history[0],
+            Movement::Placement {
+                player: PlayerId::new(0),
+                coords: Coordinates::new(2, 1, 0),
+            }
+        );
+        assert_eq!(
+            history[2],
+            Movement::Action {
+                player: PlayerId::new(0),
+                action: GameAction::Swap,
+            }
+        );
+
+        let game = GameY::from_move_string("3,a1 b1 resign").unwrap();
+        assert_eq!(game.result().unwrap().reason, GameOverReason::Resignation);
+    }
+
+    #[test]
+    fn test_move_string_errors() {
+        assert!(GameY::from_move_string("a1b1").is_err());
+        assert!(GameY::from_move_string("x,a1").is_err());
+        assert!(GameY::from_move_string("3,a2").is_err());
+        assert!(GameY::from_move_string("3,a1!").is_err());
+        assert!(matches!(
+            GameY::from_move_string("3,a1a1"),
+            Err(GameYError::Occupied { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validation_rejects_inconsistent_records() {
+        assert_eq!(
+            validate_record("4,b2a1:s").unwrap().history(),
+            GameY::from_move_string("4,b2a1:s").unwrap().history()
+        );
+        assert!(matches!(
+            validate_record("(;SZ[3];B[aa];B[ab])"),
+            Err(ValidationError::IllegalMove {
+                number: 2,
+                error: GameYError::InvalidPlayerTurn { .. }
+            })
+        ));
+        // The game is won by the third move
+        assert!(matches!(
+            validate_record("2,a1b1b2b1"),
+            Err(ValidationError::IllegalMove { number: 4, .. })
+        ));
+        assert!(matches!(
+            validate_record("(;SZ[3]"),
+            Err(ValidationError::Unreadable(_))
+        ));
+        assert!(matches!(
+            validate_record("100000,a1"),
+            Err(ValidationError::BoardTooLarge {
+                board_size: 100000,
+                ..
+            })
+        ));
+        assert!(GameY::from_sgf("(;SZ[4294967295];B[aa])").is_err());
+        assert!(GameY::from_move_string("100000,a1").is_err());
+
+        // The importers keep the connection and ignore the contradiction
+        let record = "(;SZ[2]RE[W+Resign];B[aa];W[ab];B[bb])";
+        let imported = GameY::from_sgf(record).unwrap();
+        assert_eq!(imported.result().unwrap().winner, PlayerId::new(0));
+        let error = validate_record(record).unwrap_err();
+        assert!(matches!(
+            error,
+            ValidationError::ResultMismatch { declared, actual }
+                if declared == PlayerId::new(1) && actual == PlayerId::new(0)
+        ));
+        assert!(matches!(
+            GameYError::from(error),
+            GameYError::InvalidGameRecord { .. }
+        ));
+    }
+
+    #[test]
+    fn test_import_detects_the_format() {
+        assert_eq!(
+            import_game_record("(;SZ[3];B[aa])")
+                .unwrap()
+                .history()
+                .len(),
+            1
+        );
+        assert_eq!(import_game_record("3,a1").unwrap().history().len(), 1);
+    }
+
+    proptest! {
+        /// Property: Importing any text returns a game or an error, never panics.
+        #[test]
+        fn prop_import_never_panics(record in "(\\(;)?[A-Z\\[\\]a-z0-9+,:;() \\\\#]{0,40}") {
+            let _ = import_game_record(&record);
+            let _ = validate_record(&record);
+        }
+    }
+}
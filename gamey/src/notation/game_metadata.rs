@@ -0,0 +1,363 @@
+//! Where a game comes from: who played it, when, under which clock and how
+//! it ended.
+//!
+//! A [`GameMetadata`] is carried by [`SavedGame`](crate::SavedGame)s and
+//! maps to the game information properties of SGF, read by
+//! [`GameY::from_sgf_with_metadata`] and written by
+//! [`GameY::to_sgf_with_metadata`]. SGF has no property for the engine of a
+//! player, so engines are written as the private properties `BE` and `WE`,
+//! which other readers ignore.
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::notation::game_import::{parse_sgf_main_line, sgf_result};
+use crate::{GameY, Result, TimeControl};
+
+/// The SGF properties of the names, ratings and engines of player 0 (black)
+/// and player 1 (white).
+const PLAYER_PROPERTIES: [[&str; 2]; 3] = [["PB", "PW"], ["BR", "WR"], ["BE", "WE"]];
+
+/// The SGF properties read into the fields of a [`GameMetadata`], or
+/// describing the record and its moves, which are not kept among its
+/// [other](GameMetadata::other) entries.
+const KNOWN_PROPERTIES: [&str; 17] = [
+    "PB", "PW", "BR", "WR", "BE", "WE", "EV", "DT", "TM", "OT", "SZ", "RE", "FF", "GM", "CA", "B",
+    "W",
+];
+
+/// Information about a game that its moves do not tell.
+///
+/// Player names, ratings and engines are indexed by player id. Every field
+/// is optional, and [other](GameMetadata::other) entries hold anything else,
+/// such as the round of a tournament or the place of play.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{GameMetadata, GameY, TimeControl};
+///
+/// let metadata = GameMetadata::new()
+///     .with_player(0, "alice")
+///     .with_player(1, "bob")
+///     .with_event("Club championship")
+///     .with_time_control(TimeControl {
+///         initial_ms: 300_000,
+///         increment_ms: 5000,
+///     });
+/// let sgf = GameY::new(5).to_sgf_with_metadata(&metadata).unwrap();
+/// assert_eq!(
+///     sgf,
+///     "(;FF[4]SZ[5]PB[alice]PW[bob]EV[Club championship]TM[300]OT[5 fischer])"
+/// );
+///
+/// let (_, read) = GameY::from_sgf_with_metadata(&sgf).unwrap();
+/// assert_eq!(read.players[1].as_deref(), Some("bob"));
+/// assert_eq!(read.time_control, metadata.time_control);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameMetadata {
+    /// The name of each player (SGF `PB` and `PW`).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub players: [Option<String>; 2],
+    /// The rating of each player, such as `1850` (SGF `BR` and `WR`).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ratings: [Option<String>; 2],
+    /// The engine and version of each player played by a bot, such as
+    /// `gamey 0.1 minimax`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub engines: [Option<String>; 2],
+    /// The event the game was played in (SGF `EV`).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub event: Option<String>,
+    /// The date the game was played, as `YYYY-MM-DD` (SGF `DT`).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub date: Option<String>,
+    /// The time control of the game (SGF `TM`, with the increment in `OT`).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub time_control: Option<TimeControl>,
+    /// The size of the board (SGF `SZ`).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub board_size: Option<u32>,
+    /// The result as SGF writes it, such as `B+Resign` (SGF `RE`).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub result: Option<String>,
+    /// Any other information, by name. Names that are SGF property names,
+    /// such as `RO` for the round or `PC` for the place, are written to SGF.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub other: BTreeMap<String, String>,
+}
+
+impl GameMetadata {
+    /// Creates empty metadata.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Describes `game`: its board size and, if it is over, its result.
+    pub fn of_game(game: &GameY) -> Self {
+        Self {
+            board_size: Some(game.board_size()),
+            result: game
+                .result()
+                .map(|result| sgf_result(result.winner, result.reason)),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the name of player `player` (0 or 1).
+    pub fn with_player(mut self, player: u32, name: impl Into<String>) -> Self {
+        if let Some(slot) = self.players.get_mut(player as usize) {
+            *slot = Some(name.into());
+        }
+        self
+    }
+
+    /// Sets the rating of player `player` (0 or 1).
+    pub fn with_rating(mut self, player: u32, rating: impl Into<String>) -> Self {
+        if let Some(slot) = self.ratings.get_mut(player as usize) {
+            *slot = Some(rating.into());
+        }
+        self
+    }
+
+    /// Sets the engine of player `player` (0 or 1).
+    pub fn with_engine(mut self, player: u32, engine: impl Into<String>) -> Self {
+        if let Some(slot) = self.engines.get_mut(player as usize) {
+            *slot = Some(engine.into());
+        }
+        self
+    }
+
+    /// Sets the event.
+    pub fn with_event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Sets the date, as `YYYY-MM-DD`.
+    pub fn with_date(mut self, date: impl Into<String>) -> Self {
+        self.date = Some(date.into());
+        self
+    }
+
+    /// Sets the time control.
+    pub fn with_time_control(mut self, time_control: TimeControl) -> Self {
+        self.time_control = Some(time_control);
+        self
+    }
+
+    /// Adds an entry to the other information, replacing any previous value
+    /// for `key`.
+    pub fn with_other(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.other.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns true if no information is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns the SGF properties of the metadata, with player 0 as black.
+    fn sgf_properties(&self) -> Vec<(&str, String)> {
+        let mut properties = Vec::new();
+        for (values, names) in [&self.players, &self.ratings, &self.engines]
+            .into_iter()
+            .zip(PLAYER_PROPERTIES)
+        {
+            for (value, name) in values.iter().zip(names) {
+                if let Some(value) = value {
+                    properties.push((name, value.clone()));
+                }
+            }
+        }
+        if let Some(event) = &self.event {
+            properties.push(("EV", event.clone()));
+        }
+        if let Some(date) = &self.date {
+            properties.push(("DT", date.clone()));
+        }
+        if let Some(control) = self.time_control {
+            properties.push(("TM", seconds(control.initial_ms)));
+            if control.increment_ms > 0 {
+                properties.push(("OT", format!("{} fischer", seconds(control.increment_ms))));
+            }
+        }
+        for (key, value) in &self.other {
+            let is_property = !key.is_empty() && key.bytes().all(|b| b.is_ascii_uppercase());
+            if is_property && !KNOWN_PROPERTIES.contains(&key.as_str()) {
+                properties.push((key.as_str(), value.clone()));
+            }
+        }
+        if let Some(result) = &self.result {
+            properties.push(("RE", result.clone()));
+        }
+        properties
+    }
+
+    /// Reads the game information properties of the root node of `sgf`.
+    fn from_sgf(sgf: &str) -> Result<Self> {
+        let nodes = parse_sgf_main_line(sgf)?;
+        // Like the importer, the colour that moves first is player 0
+        let black_first = nodes
+            .iter()
+            .flatten()
+            .map(|property| property.name.as_str())
+            .find(|name| *name == "B" || *name == "W")
+            .is_none_or(|name| name == "B");
+        let mut metadata = Self::new();
+        let (mut initial_ms, mut increment_ms) = (None, None);
+        for property in nodes.first().into_iter().flatten() {
+            let name = property.name.as_str();
+            let value = property.values[0].trim().to_string();
+            let player_property =
+                PLAYER_PROPERTIES
+                    .iter()
+                    .enumerate()
+                    .find_map(|(field, names)| {
+                        let color = names.iter().position(|n| *n == name)?;
+                        Some((field, color))
+                    });
+            if let Some((field, color)) = player_property {
+                let player = usize::from((color == 0) != black_first);
+                let fields = [
+                    &mut metadata.players,
+                    &mut metadata.ratings,
+                    &mut metadata.engines,
+                ];
+                fields[field][player] = Some(value);
+                continue;
+            }
+            match name {
+                "EV" => metadata.event = Some(value),
+                "DT" => metadata.date = Some(value),
+                "RE" => metadata.result = Some(value),
+                "SZ" => {
+                    metadata.board_size = value.split(':').next().and_then(|s| s.parse().ok());
+                }
+                "TM" => initial_ms = milliseconds(&value),
+                "OT" => {
+                    increment_ms = value
+                        .strip_suffix("fischer")
+                        .and_then(|increment| milliseconds(increment.trim()));
+                }
+                _ if KNOWN_PROPERTIES.contains(&name) => {}
+                _ => {
+                    metadata.other.insert(name.to_string(), value);
+                }
+            }
+        }
+        // Overtime other than a Fischer increment, such as byo-yomi, is dropped
+        if let Some(initial_ms) = initial_ms {
+            metadata.time_control = Some(TimeControl {
+                initial_ms,
+                increment_ms: increment_ms.unwrap_or(0),
+            });
+        }
+        Ok(metadata)
+    }
+}
+
+/// Writes a duration in milliseconds as seconds, as SGF times are.
+fn seconds(ms: u64) -> String {
+    // Whole seconds are written without a fraction
+    format!("{}", ms as f64 / 1000.0)
+}
+
+/// Reads a duration written in seconds, the inverse of [`seconds`].
+fn milliseconds(seconds: &str) -> Option<u64> {
+    let seconds: f64 = seconds.parse().ok()?;
+    (seconds.is_finite() && seconds >= 0.0).then(|| (seconds * 1000.0).round() as u64)
+}
+
+impl GameY {
+    /// Imports a game like [`GameY::from_sgf`], with the game information of
+    /// the record.
+    ///
+    /// The names, ratings and engines of the colour that moves first go to
+    /// player 0. Root properties that are not read into a field are kept
+    /// among the [other](GameMetadata::other) entries.
+    pub fn from_sgf_with_metadata(sgf: &str) -> Result<(GameY, GameMetadata)> {
+        let game = GameY::from_sgf(sgf)?;
+        let metadata = GameMetadata::from_sgf(sgf)?;
+        Ok((game, metadata))
+    }
+
+    /// Exports the game like [`GameY::to_sgf`], with the game information
+    /// of `metadata`.
+    ///
+    /// The board size and result are those of the game; the
+    /// [result](GameMetadata::result) of the metadata is only written for a
+    /// game that has none, such as an adjourned or abandoned one.
+    pub fn to_sgf_with_metadata(&self, metadata: &GameMetadata) -> Result<String> {
+        self.write_sgf(&metadata.sgf_properties())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_players_follow_the_colour_that_moves_first() {
+        let (game, metadata) =
+            GameY::from_sgf_with_metadata("(;SZ[3]PB[bob]PW[alice]WR[5d]PC[Madrid];W[aa];B[ab])")
+                .unwrap();
+        assert_eq!(game.history().len(), 2);
+        assert_eq!(metadata.players, [Some("alice".into()), Some("bob".into())]);
+        assert_eq!(metadata.ratings, [Some("5d".into()), None]);
+        assert_eq!(metadata.board_size, Some(3));
+        assert_eq!(metadata.other["PC"], "Madrid");
+        assert!(!metadata.other.contains_key("SZ"));
+    }
+
+    #[test]
+    fn test_values_are_escaped_and_unknown_keys_skipped() {
+        let metadata = GameMetadata::new()
+            .with_event("Cup [final]")
+            .with_other("note", "kept in JSON only")
+            .with_other("B", "not a move");
+        let sgf = GameY::new(2).to_sgf_with_metadata(&metadata).unwrap();
+        assert_eq!(sgf, "(;FF[4]SZ[2]EV[Cup [final\\]])");
+        let (_, read) = GameY::from_sgf_with_metadata(&sgf).unwrap();
+        assert_eq!(read.event.as_deref(), Some("Cup [final]"));
+    }
+
+    #[test]
+    fn test_result_of_unfinished_games_is_kept() {
+        let metadata = GameMetadata::new().with_other("GN", "adjourned");
+        let metadata = GameMetadata {
+            result: Some("Void".to_string()),
+            ..metadata
+        };
+        let sgf = GameY::new(3).to_sgf_with_metadata(&metadata).unwrap();
+        assert_eq!(sgf, "(;FF[4]SZ[3]GN[adjourned]RE[Void])");
+        let (game, read) = GameY::from_sgf_with_metadata(&sgf).unwrap();
+        assert!(!game.check_game_over());
+        assert_eq!(read.result.as_deref(), Some("Void"));
+        assert!(GameMetadata::new().is_empty());
+        assert!(!read.is_empty());
+    }
+}
@@ -0,0 +1,309 @@
+//! Live records of games, written as they are played.
+//!
+//! A [`GameRecorder`] observes a game and appends one JSON object per line
+//! (JSON Lines) to a file for every change to it: the event, when it
+//! happened and, optionally, the evaluation of the position it left. Each
+//! line is flushed before the game goes on, so a crash loses at most the
+//! line being written, and [`read_recording`] skips a last line cut short.
+//!
+//! Unlike [`Autosave`](crate::Autosave), which rewrites the whole game after
+//! every move, a recording keeps the order and timing of the moves,
+//! including those taken back.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use web_time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::notation::saved_game::apply_event;
+use crate::{GameEvent, GameObserver, GameY, GameYError, Movement, Result};
+
+/// One line of a game recording.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordLine {
+    /// The first line: the game when the recording started.
+    Start {
+        /// The size of the board.
+        board_size: u32,
+        /// Milliseconds since the Unix epoch.
+        unix_ms: u64,
+        /// The moves played before the recording started.
+        history: Vec<Movement>,
+    },
+    /// A change to the game.
+    Event {
+        /// Milliseconds since the Unix epoch.
+        unix_ms: u64,
+        /// Milliseconds since the recording started.
+        elapsed_ms: u64,
+        /// What happened.
+        event: GameEvent,
+        /// The evaluation of the position after the event, if the recorder
+        /// has an evaluator.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        evaluation: Option<i32>,
+    },
+}
+
+/// A function scoring the positions of a recorded game.
+type Evaluator = Box<dyn Fn(&GameY) -> i32 + Send + Sync>;
+
+/// Appends every change to a game to a file, as it happens.
+///
+/// The recorder keeps its own copy of the game, updated from the events it
+/// receives, to evaluate the positions. Register it with
+/// [`GameY::record_to`], or with [`GameY::add_observer`] to add an
+/// evaluator. Failed writes are logged and otherwise ignored, so that a
+/// full disk never stops a game.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameRecorder, GameY, Movement, PlayerId, evaluate_position};
+/// use gamey::{RecordLine, read_recording};
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("game.jsonl");
+/// let mut game = GameY::new(5);
+/// let recorder = GameRecorder::create(&path, &game)
+///     .unwrap()
+///     .with_evaluator(|game| evaluate_position(game, PlayerId::new(0)));
+/// game.add_observer(recorder);
+/// game.add_move(Movement::Placement {
+///     player: PlayerId::new(0),
+///     coords: Coordinates::new(2, 1, 1),
+/// })
+/// .unwrap();
+///
+/// let lines = read_recording(&path).unwrap();
+/// assert_eq!(lines.len(), 2);
+/// assert!(matches!(lines[1], RecordLine::Event { evaluation: Some(_), .. }));
+/// assert_eq!(GameY::load_recording(&path).unwrap().history(), game.history());
+/// ```
+pub struct GameRecorder {
+    file: File,
+    path: PathBuf,
+    game: GameY,
+    started: Instant,
+    evaluator: Option<Evaluator>,
+}
+
+impl GameRecorder {
+    /// Creates the file at `path`, replacing any file there, and records
+    /// the start of `game`, which must be the game the recorder is then
+    /// registered on.
+    pub fn create<P: AsRef<Path>>(path: P, game: &GameY) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::create(&path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to create file: {}", path.display()),
+            error: e.to_string(),
+        })?;
+        let mut recorder = Self {
+            file,
+            path,
+            game: game.clone(),
+            started: Instant::now(),
+            evaluator: None,
+        };
+        let start = RecordLine::Start {
+            board_size: game.board_size(),
+            unix_ms: unix_ms(),
+            history: game.history().to_vec(),
+        };
+        recorder.write(&start).map_err(|e| GameYError::IoError {
+            message: format!("Failed to write file: {}", recorder.path.display()),
+            error: e.to_string(),
+        })?;
+        Ok(recorder)
+    }
+
+    /// Records with every event the score `evaluator` gives the position
+    /// after it.
+    pub fn with_evaluator<F>(mut self, evaluator: F) -> Self
+    where
+        F: Fn(&GameY) -> i32 + Send + Sync + 'static,
+    {
+        self.evaluator = Some(Box::new(evaluator));
+        self
+    }
+
+    fn write(&mut self, line: &RecordLine) -> std::io::Result<()> {
+        let mut text = serde_json::to_string(line).map_err(std::io::Error::other)?;
+        text.push('\n');
+        // Written whole, so that a crash can only cut the last line short
+        self.file.write_all(text.as_bytes())?;
+        self.file.flush()
+    }
+}
+
+impl GameObserver for GameRecorder {
+    fn on_event(&mut self, event: &GameEvent) {
+        let evaluation = match apply_event(&mut self.game, event) {
+            Ok(()) => self.evaluator.as_ref().map(|evaluate| evaluate(&self.game)),
+            Err(e) => {
+                tracing::warn!("Recorder lost track of the game: {}", e);
+                None
+            }
+        };
+        let line = RecordLine::Event {
+            unix_ms: unix_ms(),
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            event: event.clone(),
+            evaluation,
+        };
+        if let Err(e) = self.write(&line) {
+            tracing::warn!("Recording to {} failed: {}", self.path.display(), e);
+        }
+    }
+}
+
+impl std::fmt::Debug for GameRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GameRecorder")
+            .field("path", &self.path)
+            .field("moves", &self.game.history().len())
+            .field("evaluator", &self.evaluator.is_some())
+            .finish()
+    }
+}
+
+/// Returns the milliseconds elapsed since the Unix epoch.
+fn unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+/// Reads the lines of a recording written by a [`GameRecorder`].
+///
+/// A last line without its line break, as left by a crash while it was
+/// written, is skipped if it cannot be read.
+pub fn read_recording<P: AsRef<Path>>(path: P) -> Result<Vec<RecordLine>> {
+    let filename = path.as_ref().display().to_string();
+    let content = std::fs::read_to_string(&path).map_err(|e| GameYError::IoError {
+        message: format!("Failed to read file: {}", filename),
+        error: e.to_string(),
+    })?;
+    let complete = content.ends_with('\n');
+    let mut lines = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+    let mut records = Vec::new();
+    while let Some(line) = lines.next() {
+        match serde_json::from_str(line) {
+            Ok(record) => records.push(record),
+            Err(_) if !complete && lines.peek().is_none() => break,
+            Err(e) => return Err(GameYError::SerdeError { error: e }),
+        }
+    }
+    Ok(records)
+}
+
+impl GameY {
+    /// Records the game to `path` from now on, as a [`GameRecorder`]
+    /// without evaluations.
+    pub fn record_to<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let recorder = GameRecorder::create(path, self)?;
+        self.add_observer(recorder);
+        Ok(())
+    }
+
+    /// Rebuilds the game recorded in the file at `path` by a
+    /// [`GameRecorder`], as it was after its last recorded event.
+    pub fn load_recording<P: AsRef<Path>>(path: P) -> Result<GameY> {
+        let lines = read_recording(path)?;
+        let Some(RecordLine::Start {
+            board_size,
+            history,
+            ..
+        }) = lines.first()
+        else {
+            return Err(GameYError::InvalidGameRecord {
+                format: "recording".to_string(),
+                reason: "the first line is not the start of the game".to_string(),
+            });
+        };
+        let mut game = GameY::new(*board_size);
+        for movement in history {
+            game.replay_move(movement.clone())?;
+        }
+        for line in &lines[1..] {
+            if let RecordLine::Event { event, .. } = line {
+                apply_event(&mut game, event)?;
+            }
+        }
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameOverReason, PlayerId};
+
+    fn place(game: &mut GameY, player: u32, x: u32, y: u32, z: u32) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::new(x, y, z),
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_every_change_is_recorded_at_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game.jsonl");
+        let mut game = GameY::new(4);
+        place(&mut game, 0, 3, 0, 0);
+        game.record_to(&path).unwrap();
+        assert_eq!(read_recording(&path).unwrap().len(), 1);
+
+        place(&mut game, 1, 1, 1, 1);
+        place(&mut game, 0, 0, 3, 0);
+        // Written before the next move, without dropping the recorder
+        assert_eq!(read_recording(&path).unwrap().len(), 3);
+        game.undo_move().unwrap();
+        game.resign(PlayerId::new(0)).unwrap();
+
+        let lines = read_recording(&path).unwrap();
+        assert_eq!(lines.len(), 5);
+        let RecordLine::Start { history, .. } = &lines[0] else {
+            panic!("expected the start of the game");
+        };
+        assert_eq!(history.len(), 1);
+        assert!(matches!(
+            &lines[3],
+            RecordLine::Event {
+                event: GameEvent::MoveUndone { .. },
+                evaluation: None,
+                ..
+            }
+        ));
+        let loaded = GameY::load_recording(&path).unwrap();
+        assert_eq!(loaded.history(), game.history());
+        assert_eq!(loaded.result().unwrap().reason, GameOverReason::Resignation);
+    }
+
+    #[test]
+    fn test_a_line_cut_short_by_a_crash_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game.jsonl");
+        let mut game = GameY::new(4);
+        game.record_to(&path).unwrap();
+        place(&mut game, 0, 3, 0, 0);
+        place(&mut game, 1, 1, 1, 1);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, &content[..content.len() - 10]).unwrap();
+        let loaded = GameY::load_recording(&path).unwrap();
+        assert_eq!(loaded.history(), &game.history()[..1]);
+
+        // Anywhere else, a bad line is an error
+        std::fs::write(&path, format!("{{}}\n{}", content)).unwrap();
+        assert!(read_recording(&path).is_err());
+    }
+}
@@ -4,6 +4,50 @@
 //! in a compact, portable way. Currently supported:
 //!
 //! - [`YEN`]: Y Exchange Notation - a JSON-based format inspired by chess FEN
+//! - [`GameY::from_sgf`](crate::GameY::from_sgf) and
+//!   [`GameY::from_move_string`](crate::GameY::from_move_string): importers for game
+//!   records from Little Golem (SGF) and HexWorld-style move strings, and
+//!   [`validate_record`], which also rejects records contradicting their
+//!   declared result; [`GameY::to_sgf`](crate::GameY::to_sgf) exports SGF
+//! - [`GameMetadata`]: players, event, date, clock and result of a game,
+//!   mapped to the game information properties of SGF (`std` feature)
+//! - [`SavedGame`]: versioned save files with history, clocks and metadata,
+//!   and the [`Autosave`] observer that keeps one up to date (`std` and
+//!   `serde` features)
+//! - [`ArchiveWriter`] and [`ArchiveReader`]: packed binary archives of many
+//!   games, for self-play datasets (`std` feature)
+//! - [`GameRecorder`]: live JSON Lines records of games, written move by
+//!   move (`std` and `serde` features)
+//! - [`GameStateResponse`] and the other request and response bodies of the
+//!   game HTTP API, shared by the server and its clients (`std` feature)
+//! - [`RoomClientMessage`] and [`RoomServerMessage`]: the messages of the
+//!   WebSocket game rooms (`std` feature)
 
+#[cfg(feature = "std")]
+pub mod game_api;
+#[cfg(feature = "std")]
+pub mod game_archive;
+pub mod game_import;
+#[cfg(feature = "std")]
+pub mod game_metadata;
+#[cfg(all(feature = "std", feature = "serde"))]
+pub mod game_recording;
+#[cfg(feature = "std")]
+pub mod room_api;
+#[cfg(all(feature = "std", feature = "serde"))]
+pub mod saved_game;
 pub mod yen;
+#[cfg(feature = "std")]
+pub use game_api::*;
+#[cfg(feature = "std")]
+pub use game_archive::*;
+pub use game_import::*;
+#[cfg(feature = "std")]
+pub use game_metadata::*;
+#[cfg(all(feature = "std", feature = "serde"))]
+pub use game_recording::*;
+#[cfg(feature = "std")]
+pub use room_api::*;
+#[cfg(all(feature = "std", feature = "serde"))]
+pub use saved_game::*;
 pub use yen::*;
@@ -0,0 +1,208 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+use crate::{GameSession, GameStateResponse, PlayerId, Proposal, SeatOccupant, TimeControl};
+
+/// Request body for creating a room.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NewRoomRequest {
+    /// The size of the board.
+    pub size: u32,
+    /// The clock of the room; no clock if omitted.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub time_control: Option<TimeControl>,
+    /// A bot to seat as player 1, leaving player 0 to a human.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bot_id: Option<String>,
+}
+
+/// Public view of a seat of a room.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SeatInfo {
+    /// The player the seat plays as.
+    pub player: u32,
+    /// Who sits in the seat, or None if it is free.
+    pub occupant: Option<SeatOccupant>,
+    /// Whether the occupant is currently connected.
+    pub connected: bool,
+    /// Time left on the player's clock, if the room has one.
+    pub remaining_ms: Option<u64>,
+}
+
+/// A proposal waiting for an answer in a room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PendingProposal {
+    /// The player who made the proposal.
+    pub player: u32,
+    /// What was proposed.
+    pub proposal: Proposal,
+}
+
+/// The state of a room, as sent to players and spectators.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RoomStateResponse {
+    /// The identifier of the room.
+    pub room_id: u64,
+    /// The game, with the room identifier as its game identifier.
+    pub game: GameStateResponse,
+    /// The two seats, player 0 first.
+    pub seats: Vec<SeatInfo>,
+    /// The time control of the room, if it has a clock.
+    pub time_control: Option<TimeControl>,
+    /// The proposal waiting for an answer, if any.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub proposal: Option<PendingProposal>,
+    /// Whether the players agreed to abort the game.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub aborted: bool,
+}
+
+impl RoomStateResponse {
+    /// Describes `session`, stored as `room_id`, with the clocks read at `now`.
+    pub fn new(room_id: u64, session: &GameSession, now: Instant) -> Self {
+        let clock = session.clock();
+        let seats = (0..2)
+            .map(PlayerId::new)
+            .map(|player| {
+                let seat = session.seat(player);
+                SeatInfo {
+                    player: player.id(),
+                    occupant: seat.occupant().cloned(),
+                    connected: seat.is_connected(),
+                    remaining_ms: clock.map(|clock| clock.remaining_ms(player, now)),
+                }
+            })
+            .collect();
+        RoomStateResponse {
+            room_id,
+            game: GameStateResponse::new(room_id, session.game()),
+            seats,
+            time_control: clock.map(|clock| clock.time_control()),
+            proposal: session
+                .proposal()
+                .map(|(player, proposal)| PendingProposal {
+                    player: player.id(),
+                    proposal,
+                }),
+            aborted: session.is_aborted(),
+        }
+    }
+}
+
+/// A message sent by a client over the room WebSocket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum RoomClientMessage {
+    /// Takes a free seat, that of `player` if given.
+    Join {
+        /// The name shown to the other players.
+        name: String,
+        /// The seat to take (0 or 1).
+        #[cfg_attr(feature = "serde", serde(default))]
+        player: Option<u32>,
+    },
+    /// Takes back a seat after a lost connection.
+    Rejoin {
+        /// The token received when joining.
+        token: String,
+    },
+    /// Places a stone on the cell with the given board index.
+    Play {
+        /// The board index of the cell.
+        cell: u32,
+    },
+    /// Swaps sides, as allowed by the swap rule.
+    Swap,
+    /// Resigns the game.
+    Resign,
+    /// Proposes a takeback or an abort to the opponent.
+    Propose {
+        /// What is proposed.
+        proposal: Proposal,
+    },
+    /// Accepts or declines the proposal of the opponent.
+    Answer {
+        /// Whether the proposal is accepted.
+        accept: bool,
+    },
+}
+
+/// A message sent by the server over the room WebSocket.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum RoomServerMessage {
+    /// The connection now holds a seat.
+    Joined {
+        /// The player of the seat.
+        player: u32,
+        /// The token to send with `rejoin` after a lost connection.
+        token: String,
+    },
+    /// The room changed. Sent to players and spectators alike.
+    State {
+        /// The new state of the room.
+        room: RoomStateResponse,
+    },
+    /// The last client message was rejected.
+    Error {
+        /// Why the message was rejected.
+        message: String,
+    },
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_messages_are_tagged() {
+        let join: RoomClientMessage =
+            serde_json::from_str(r#"{"type":"join","name":"ana"}"#).unwrap();
+        assert_eq!(
+            join,
+            RoomClientMessage::Join {
+                name: "ana".to_string(),
+                player: None
+            }
+        );
+        let play: RoomClientMessage = serde_json::from_str(r#"{"type":"play","cell":4}"#).unwrap();
+        assert_eq!(play, RoomClientMessage::Play { cell: 4 });
+        let propose: RoomClientMessage =
+            serde_json::from_str(r#"{"type":"propose","proposal":"takeback"}"#).unwrap();
+        assert_eq!(
+            propose,
+            RoomClientMessage::Propose {
+                proposal: Proposal::Takeback
+            }
+        );
+    }
+
+    #[test]
+    fn test_room_state_describes_seats() {
+        let now = Instant::now();
+        let mut session = GameSession::new(3).with_time_control(TimeControl {
+            initial_ms: 5000,
+            increment_ms: 0,
+        });
+        session
+            .add_bot(PlayerId::new(1), "random_bot", now)
+            .unwrap();
+        let state = RoomStateResponse::new(3, &session, now);
+        assert_eq!(state.game.game_id, 3);
+        assert_eq!(state.seats[0].occupant, None);
+        assert_eq!(state.seats[1].remaining_ms, Some(5000));
+        assert_eq!(state.proposal, None);
+        assert!(!state.aborted);
+
+        let json = serde_json::to_value(RoomServerMessage::State { room: state }).unwrap();
+        assert_eq!(json["type"], "state");
+        assert_eq!(json["room"]["seats"][1]["occupant"]["kind"], "bot");
+    }
+}
@@ -0,0 +1,431 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    ClockSnapshot, GameAction, GameEvent, GameMetadata, GameObserver, GameOverReason, GameY,
+    GameYError, Movement, Result, YEN, other_player,
+};
+
+/// Version of the saved game format written by this library.
+pub const SAVED_GAME_VERSION: u32 = 2;
+
+/// A game saved to a file: the full history, the clocks and the
+/// [metadata](GameMetadata) of the game, such as player names.
+///
+/// The file is JSON with a `format_version` field, so that later versions of
+/// the format can still read older files. The free-form metadata of files
+/// of version 1 is read into the [other](GameMetadata::other) entries. Files are written to a temporary
+/// file first and then renamed over the target, so a crash while saving
+/// never leaves a truncated file behind.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{GameMetadata, GameY, SavedGame};
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("game.json");
+/// SavedGame::new(GameY::new(5))
+///     .with_game_metadata(GameMetadata::new().with_player(1, "alice"))
+///     .with_metadata("opening", "centre")
+///     .save(&path)
+///     .unwrap();
+///
+/// let saved = SavedGame::load(&path).unwrap();
+/// assert_eq!(saved.metadata.players[1].as_deref(), Some("alice"));
+/// assert_eq!(saved.metadata.other["opening"], "centre");
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    /// Version of the format the file was written with.
+    pub format_version: u32,
+    /// The game, with its complete history.
+    pub game: GameY,
+    /// The seed of the random choices of the bots, to replay the game
+    /// exactly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// The clocks at the time of saving, if the game is timed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock: Option<ClockSnapshot>,
+    /// Information about the game, such as player names or the event.
+    #[serde(default, skip_serializing_if = "GameMetadata::is_empty")]
+    pub metadata: GameMetadata,
+}
+
+impl SavedGame {
+    /// Wraps a game for saving, without clocks or metadata.
+    pub fn new(game: GameY) -> Self {
+        Self {
+            format_version: SAVED_GAME_VERSION,
+            game,
+            seed: None,
+            clock: None,
+            metadata: GameMetadata::new(),
+        }
+    }
+
+    /// Records the seed of the bots' random choices.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Adds the state of the clocks.
+    pub fn with_clock(mut self, clock: ClockSnapshot) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Sets the metadata of the game, replacing any previous metadata.
+    pub fn with_game_metadata(mut self, metadata: GameMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Adds an entry to the other information of the metadata, replacing
+    /// any previous value for `key`.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.other.insert(key.into(), value.into());
+        self
+    }
+
+    /// Exports the game and its metadata as SGF, like
+    /// [`GameY::to_sgf_with_metadata`].
+    pub fn to_sgf(&self) -> Result<String> {
+        self.game.to_sgf_with_metadata(&self.metadata)
+    }
+
+    /// Imports a game and its metadata from SGF, like
+    /// [`GameY::from_sgf_with_metadata`].
+    pub fn from_sgf(sgf: &str) -> Result<Self> {
+        let (game, metadata) = GameY::from_sgf_with_metadata(sgf)?;
+        Ok(Self::new(game).with_game_metadata(metadata))
+    }
+
+    /// Writes the saved game to `path`, replacing it atomically.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let content =
+            serde_json::to_string_pretty(self).map_err(|e| GameYError::SerdeError { error: e })?;
+        let temp = temp_path(path);
+        let io_error = |message: String| {
+            move |e: std::io::Error| GameYError::IoError {
+                message,
+                error: e.to_string(),
+            }
+        };
+        std::fs::write(&temp, content).map_err(io_error(format!(
+            "Failed to write file: {}",
+            temp.display()
+        )))?;
+        std::fs::rename(&temp, path).map_err(io_error(format!(
+            "Failed to replace file: {}",
+            path.display()
+        )))
+    }
+
+    /// Reads a saved game from `path`.
+    ///
+    /// Files in the older YEN format written by [`GameY::save_to_file`] are
+    /// accepted too; they hold the position but no history. Returns an error
+    /// for files written by a newer version of the format.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let filename = path.as_ref().display().to_string();
+        let content = std::fs::read_to_string(path).map_err(|e| GameYError::IoError {
+            message: format!("Failed to read file: {}", filename),
+            error: e.to_string(),
+        })?;
+        let value: serde_json::Value =
+            serde_json::from_str(&content).map_err(|e| GameYError::SerdeError { error: e })?;
+        let Some(version) = value.get("format_version").cloned() else {
+            let yen: YEN =
+                serde_json::from_value(value).map_err(|e| GameYError::SerdeError { error: e })?;
+            return Ok(SavedGame::new(GameY::try_from(yen)?));
+        };
+        let found = version.as_u64().unwrap_or(u64::MAX);
+        if found > u64::from(SAVED_GAME_VERSION) {
+            return Err(GameYError::UnsupportedSaveVersion {
+                found: u32::try_from(found).unwrap_or(u32::MAX),
+                supported: SAVED_GAME_VERSION,
+            });
+        }
+        let mut value = value;
+        if found < 2
+            && let Some(metadata) = value.get_mut("metadata")
+        {
+            // Version 1 only had free-form metadata
+            *metadata = serde_json::json!({ "other": metadata.take() });
+        }
+        serde_json::from_value(value).map_err(|e| GameYError::SerdeError { error: e })
+    }
+}
+
+/// Returns the hidden temporary file next to `path` used while saving.
+fn temp_path(path: &Path) -> PathBuf {
+    match path.file_name() {
+        Some(name) => path.with_file_name(format!(".{}.tmp", name.to_string_lossy())),
+        None => path.with_extension("tmp"),
+    }
+}
+
+/// Saves a game to a file after every change, so that it can be resumed
+/// after a crash.
+///
+/// The observer keeps its own copy of the game, updated from the events it
+/// receives, and writes it with [`SavedGame::save`]. Register it with
+/// [`GameY::autosave_to`], or with [`GameY::add_observer`] to include
+/// metadata. Failed writes are logged and retried on the next change.
+pub struct Autosave {
+    path: PathBuf,
+    saved: SavedGame,
+}
+
+impl Autosave {
+    /// Creates an observer that keeps `path` in sync with `saved`, whose
+    /// game must be the game the observer is registered on.
+    pub fn new<P: Into<PathBuf>>(path: P, saved: SavedGame) -> Self {
+        Self {
+            path: path.into(),
+            saved,
+        }
+    }
+}
+
+/// Applies to `game` the change described by `event`, to keep a copy of the
+/// game an observer is registered on in sync with it.
+pub(crate) fn apply_event(game: &mut GameY, event: &GameEvent) -> Result<()> {
+    match event {
+        GameEvent::StonePlaced { player, coords } => game.replay_move(Movement::Placement {
+            player: *player,
+            coords: *coords,
+        }),
+        GameEvent::SwapApplied { player } => game.replay_move(Movement::Action {
+            player: *player,
+            action: GameAction::Swap,
+        }),
+        GameEvent::MoveUndone { .. } => game.undo_move().map(|_| ()),
+        // Wins by connection were already applied with the last stone
+        GameEvent::GameOver { result } if result.reason != GameOverReason::Connection => {
+            game.forfeit(other_player(result.winner), result.reason)
+        }
+        GameEvent::GameOver { .. } => Ok(()),
+    }
+}
+
+impl GameObserver for Autosave {
+    fn on_event(&mut self, event: &GameEvent) {
+        if let Err(e) = apply_event(&mut self.saved.game, event) {
+            tracing::warn!("Autosave lost track of the game: {}", e);
+            return;
+        }
+        if let Err(e) = self.saved.save(&self.path) {
+            tracing::warn!("Autosave to {} failed: {}", self.path.display(), e);
+        }
+    }
+}
+
+impl GameY {
+    /// Saves the game with its full history, in the format of [`SavedGame`].
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        SavedGame::new(self.clone()).save(path)
+    }
+
+    /// Loads a game written by [`GameY::save`] or [`SavedGame::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<GameY> {
+        Ok(SavedGame::load(path)?.game)
+    }
+
+    /// Saves the game to `path` now and after every later change.
+    pub fn autosave_to<P: Into<PathBuf>>(&mut self, path: P) -> Result<()> {
+        self.autosave_with(path, SavedGame::new(self.clone()))
+    }
+
+    /// Like [`GameY::autosave_to`], keeping the seed and metadata of
+    /// `saved`, whose game must be this game.
+    pub fn autosave_with<P: Into<PathBuf>>(&mut self, path: P, saved: SavedGame) -> Result<()> {
+        let autosave = Autosave::new(path, saved);
+        autosave.saved.save(&autosave.path)?;
+        self.add_observer(autosave);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, GameClock, PlayerId, TimeControl};
+    use web_time::Instant;
+
+    fn place(game: &mut GameY, player: u32, x: u32, y: u32, z: u32) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::new(x, y, z),
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_save_and_load_keep_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game.json");
+        let mut game = GameY::new(4);
+        place(&mut game, 0, 3, 0, 0);
+        place(&mut game, 1, 1, 1, 1);
+        game.save(&path).unwrap();
+
+        let loaded = GameY::load(&path).unwrap();
+        assert_eq!(loaded.history(), game.history());
+        assert_eq!(loaded.next_player(), Some(PlayerId::new(0)));
+        assert!(!dir.path().join(".game.json.tmp").exists());
+    }
+
+    #[test]
+    fn test_clock_seed_and_metadata_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game.json");
+        let mut clock = GameClock::new(TimeControl {
+            initial_ms: 60_000,
+            increment_ms: 1000,
+        });
+        let now = Instant::now();
+        clock.start(PlayerId::new(0), now);
+        SavedGame::new(GameY::new(3))
+            .with_clock(clock.snapshot(now))
+            .with_seed(99)
+            .with_metadata("black", "bob")
+            .save(&path)
+            .unwrap();
+
+        let saved = SavedGame::load(&path).unwrap();
+        assert_eq!(saved.format_version, SAVED_GAME_VERSION);
+        assert_eq!(saved.clock, Some(clock.snapshot(now)));
+        assert_eq!(saved.seed, Some(99));
+        assert_eq!(
+            saved.metadata.other.get("black").map(String::as_str),
+            Some("bob")
+        );
+    }
+
+    #[test]
+    fn test_game_metadata_roundtrips_through_files_and_sgf() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game.json");
+        let mut game = GameY::new(4);
+        place(&mut game, 0, 3, 0, 0);
+        place(&mut game, 1, 1, 1, 1);
+        game.resign(PlayerId::new(0)).unwrap();
+        let metadata = GameMetadata::of_game(&game)
+            .with_player(0, "alice")
+            .with_player(1, "bob")
+            .with_rating(1, "1850")
+            .with_engine(1, "gamey 0.1 minimax")
+            .with_event("Club championship")
+            .with_date("2026-10-16")
+            .with_time_control(TimeControl {
+                initial_ms: 90_000,
+                increment_ms: 1500,
+            })
+            .with_other("RO", "3")
+            .with_other("note", "not in SGF");
+        let saved = SavedGame::new(game).with_game_metadata(metadata.clone());
+        saved.save(&path).unwrap();
+        assert_eq!(SavedGame::load(&path).unwrap().metadata, metadata);
+
+        let sgf = saved.to_sgf().unwrap();
+        assert_eq!(
+            sgf,
+            "(;FF[4]SZ[4]PB[alice]PW[bob]WR[1850]WE[gamey 0.1 minimax]EV[Club championship]\
+             DT[2026-10-16]TM[90]OT[1.5 fischer]RO[3]RE[W+Resign];B[aa];W[bc])"
+        );
+        let imported = SavedGame::from_sgf(&sgf).unwrap();
+        assert_eq!(imported.game.result(), saved.game.result());
+        let mut expected = metadata;
+        expected.other.remove("note");
+        assert_eq!(imported.metadata, expected);
+    }
+
+    #[test]
+    fn test_version_one_metadata_is_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game.json");
+        let mut value = serde_json::to_value(SavedGame::new(GameY::new(3))).unwrap();
+        value["format_version"] = 1.into();
+        value["metadata"] = serde_json::json!({ "white": "alice", "time_control": "5+0" });
+        std::fs::write(&path, value.to_string()).unwrap();
+
+        let saved = SavedGame::load(&path).unwrap();
+        assert_eq!(saved.metadata.other["white"], "alice");
+        assert_eq!(saved.metadata.other["time_control"], "5+0");
+        assert_eq!(saved.metadata.time_control, None);
+    }
+
+    #[test]
+    fn test_load_accepts_yen_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("position.json");
+        let mut game = GameY::new(3);
+        place(&mut game, 0, 2, 0, 0);
+        game.save_to_file(&path).unwrap();
+
+        let loaded = GameY::load(&path).unwrap();
+        assert_eq!(
+            loaded.cell_owner(&Coordinates::new(2, 0, 0)),
+            Some(PlayerId::new(0))
+        );
+    }
+
+    #[test]
+    fn test_yen_files_survive_saving_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("position.json");
+        let mut game = GameY::new(3);
+        place(&mut game, 0, 2, 0, 0);
+        place(&mut game, 1, 0, 2, 0);
+        place(&mut game, 0, 0, 0, 2);
+        game.save_to_file(&path).unwrap();
+
+        let loaded = SavedGame::load(&path).unwrap();
+        loaded.save(&path).unwrap();
+        let reloaded = SavedGame::load(&path).unwrap();
+        assert_eq!(
+            reloaded.game.to_position_string(),
+            loaded.game.to_position_string()
+        );
+        assert_eq!(reloaded.game.stone_count(), 3);
+    }
+
+    #[test]
+    fn test_load_rejects_newer_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game.json");
+        let mut saved = SavedGame::new(GameY::new(3));
+        saved.format_version = SAVED_GAME_VERSION + 1;
+        saved.save(&path).unwrap();
+        assert!(matches!(
+            SavedGame::load(&path),
+            Err(GameYError::UnsupportedSaveVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_autosave_follows_moves_undo_and_resignation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("autosave.json");
+        let mut game = GameY::new(4);
+        game.autosave_to(&path).unwrap();
+        assert!(GameY::load(&path).unwrap().history().is_empty());
+
+        place(&mut game, 0, 3, 0, 0);
+        place(&mut game, 1, 1, 1, 1);
+        assert_eq!(GameY::load(&path).unwrap().history().len(), 2);
+
+        game.undo_move().unwrap();
+        assert_eq!(GameY::load(&path).unwrap().history().len(), 1);
+
+        game.resign(PlayerId::new(1)).unwrap();
+        let loaded = GameY::load(&path).unwrap();
+        assert_eq!(loaded.history(), game.history());
+        assert_eq!(loaded.result(), game.result());
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::{string::String, vec::Vec};
 use serde::{Deserialize, Serialize};
 
 /// Y Exchange Notation (YEN) - a compact format for representing Y game states.
@@ -10,7 +11,8 @@ use serde::{Deserialize, Serialize};
 /// - `turn`: Which player's turn it is (0 or 1)
 /// - `players`: Character symbols for each player (e.g., ['B', 'R'] for Blue/Red)
 /// - `layout`: A compact string where rows are separated by '/', and cells are
-///   represented by player symbols or '.' for empty cells
+///   represented by player symbols, '.' for empty cells or '#' for the
+///   blocked cells of a custom board
 ///
 /// # Example
 /// ```json
@@ -31,8 +33,8 @@ pub struct YEN {
     players: Vec<char>,
     /// A compact string representation of the board.
     ///
-    /// Rows are separated by '/', with cells represented by player symbols
-    /// or '.' for empty cells. Example: "B/..R/.B.R"
+    /// Rows are separated by '/', with cells represented by player symbols,
+    /// '.' for empty cells or '#' for blocked cells. Example: "B/..R/.B.R"
     layout: String,
 }
 
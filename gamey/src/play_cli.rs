@@ -0,0 +1,469 @@
+//! Terminal front-end for playing a game of Y against a bot.
+//!
+//! Used by the `gamey-cli` binary. The board is drawn with lettered rows and
+//! moves are entered as cell names (`c2` is the second cell of row `c`),
+//! as barycentric coordinates (`1,1,1`) or as board indices.
+
+use crate::{
+    BotProfiles, Coordinates, GameRecorder, GameY, GameYError, MinimaxBot, Movement, PlayerId,
+    RandomBot, SavedGame, TextRenderer, YBot, evaluate_position, random_seed,
+};
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Command-line arguments for `gamey-cli`.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+#[command(long_about = "gamey-cli: play the Game of Y against the computer.")]
+pub struct PlayArgs {
+    /// Size of the triangular board (length of one side).
+    #[arg(short, long, default_value_t = 7)]
+    pub size: u32,
+
+    /// Strength of the computer opponent.
+    #[arg(short, long, default_value_t = Difficulty::Medium)]
+    pub difficulty: Difficulty,
+
+    /// Let the computer play first.
+    #[arg(long, default_value_t = false)]
+    pub bot_first: bool,
+
+    /// Time in milliseconds the computer may think per move, overriding the
+    /// budget of the difficulty.
+    #[arg(long)]
+    pub time_ms: Option<u64>,
+
+    /// Resume a game saved with the `save` command.
+    #[arg(long)]
+    pub load: Option<PathBuf>,
+
+    /// Play against a minimax bot configured by this profile instead of the
+    /// difficulty. Built-in profiles: medium, hard, expert.
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// TOML file with additional bot profiles for `--profile`.
+    #[arg(long, requires = "profile")]
+    pub profiles: Option<PathBuf>,
+
+    /// Seed for the bot's random choices. Without it a random seed is used;
+    /// either way the seed is recorded in saved games and reused when they
+    /// are resumed.
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Save the game to this file after every move, resuming it if the file
+    /// already exists.
+    #[arg(long)]
+    pub autosave: Option<PathBuf>,
+
+    /// Record every move to this file as it is played, with its time and
+    /// the static evaluation for the first player.
+    #[arg(long)]
+    pub record: Option<PathBuf>,
+}
+
+/// Strength of the computer opponent.
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum Difficulty {
+    /// Plays random moves.
+    Easy,
+    /// Searches for a fifth of a second.
+    Medium,
+    /// Searches for a second.
+    Hard,
+    /// Searches for five seconds.
+    Expert,
+}
+
+impl Difficulty {
+    /// Returns the bot playing at this difficulty.
+    pub fn bot(self) -> Arc<dyn YBot> {
+        match self {
+            Difficulty::Easy => Arc::new(RandomBot::new()),
+            _ => Arc::new(MinimaxBot::new(self.time_budget_ms())),
+        }
+    }
+
+    /// Returns the time the bot may think per move, in milliseconds.
+    pub fn time_budget_ms(self) -> u64 {
+        match self {
+            Difficulty::Easy => 0,
+            Difficulty::Medium => 200,
+            Difficulty::Hard => 1000,
+            Difficulty::Expert => 5000,
+        }
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A command typed at the `gamey-cli` prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayCommand {
+    /// Place a stone on a cell.
+    Play(Coordinates),
+    /// Take back the last move of each side.
+    Undo,
+    /// Save the game to a file.
+    Save(PathBuf),
+    /// Resign the game.
+    Resign,
+    /// Show the commands.
+    Help,
+    /// Leave the program.
+    Quit,
+}
+
+/// Parses a line typed at the prompt. Returns None for a blank line.
+pub fn parse_play_command(input: &str, board_size: u32) -> Result<Option<PlayCommand>, String> {
+    let parts: Vec<&str> = input.split_whitespace().collect();
+    let Some(&first) = parts.first() else {
+        return Ok(None);
+    };
+    let command = match first.to_ascii_lowercase().as_str() {
+        "undo" => PlayCommand::Undo,
+        "save" => match parts.get(1) {
+            Some(path) => PlayCommand::Save(PathBuf::from(path)),
+            None => return Err("Filename required for save command".to_string()),
+        },
+        "resign" => PlayCommand::Resign,
+        "help" | "?" => PlayCommand::Help,
+        "quit" | "exit" => PlayCommand::Quit,
+        _ => PlayCommand::Play(parse_cell(&parts.concat(), board_size)?),
+    };
+    Ok(Some(command))
+}
+
+/// Parses a cell written as a cell name (`c2`), barycentric coordinates
+/// (`1,1,1`, optionally in parentheses) or a board index (`4`).
+pub fn parse_cell(text: &str, board_size: u32) -> Result<Coordinates, String> {
+    let text = text.trim();
+    if let Some(coords) = Coordinates::from_cell_name(text, board_size) {
+        return Ok(coords);
+    }
+    let inner = text.trim_start_matches('(').trim_end_matches(')');
+    if inner.contains(',') {
+        let parts = inner
+            .split(',')
+            .map(|part| part.trim().parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("Invalid coordinates: {}", text))?;
+        return Coordinates::from_vec(&parts)
+            .filter(|coords| coords.is_valid(board_size))
+            .ok_or_else(|| format!("Coordinates {} are not on the board", text));
+    }
+    let total_cells = board_size * (board_size + 1) / 2;
+    match text.parse::<u32>() {
+        Ok(index) if index < total_cells => Ok(Coordinates::from_index(index, board_size)),
+        Ok(index) => Err(format!(
+            "Index {} is out of range, the board has {} cells",
+            index, total_cells
+        )),
+        Err(_) => Err(format!("Unknown command or cell: {}", text)),
+    }
+}
+
+/// A game between a human and a bot.
+pub struct VsBotGame {
+    game: GameY,
+    human: PlayerId,
+    bot: Arc<dyn YBot>,
+    time_ms: u64,
+}
+
+impl VsBotGame {
+    /// Creates a match on `game` where the human plays `human` and the bot
+    /// thinks for about `time_ms` milliseconds per move.
+    pub fn new(game: GameY, human: PlayerId, bot: Arc<dyn YBot>, time_ms: u64) -> Self {
+        Self {
+            game,
+            human,
+            bot,
+            time_ms,
+        }
+    }
+
+    /// Returns the game being played.
+    pub fn game(&self) -> &GameY {
+        &self.game
+    }
+
+    /// Returns the player the human plays as.
+    pub fn human(&self) -> PlayerId {
+        self.human
+    }
+
+    /// Returns true if the bot is to move.
+    pub fn is_bot_turn(&self) -> bool {
+        self.game
+            .next_player()
+            .is_some_and(|player| player != self.human)
+    }
+
+    /// Places a stone for the human.
+    pub fn play(&mut self, coords: Coordinates) -> Result<(), GameYError> {
+        self.game.add_move(Movement::Placement {
+            player: self.human,
+            coords,
+        })
+    }
+
+    /// Lets the bot play its move and returns it, or None if the bot is not
+    /// to move or has no move.
+    pub fn bot_move(&mut self) -> Option<Coordinates> {
+        if !self.is_bot_turn() {
+            return None;
+        }
+        let player = self.game.next_player()?;
+        let coords = self.bot.choose_move_within(&self.game, self.time_ms)?;
+        self.game
+            .add_move(Movement::Placement { player, coords })
+            .ok()?;
+        Some(coords)
+    }
+
+    /// Takes back moves until the human is to move again, removing at least
+    /// their last move and the bot's reply to it.
+    ///
+    /// Returns the number of moves taken back.
+    pub fn undo(&mut self) -> Result<usize, GameYError> {
+        let mut undone = 0;
+        loop {
+            self.game.undo_move()?;
+            undone += 1;
+            if self.game.next_player() == Some(self.human) || self.game.history().is_empty() {
+                return Ok(undone);
+            }
+        }
+    }
+
+    /// Makes the human resign.
+    pub fn resign(&mut self) -> Result<(), GameYError> {
+        self.game.resign(self.human)
+    }
+}
+
+/// Draws the board with lettered rows, as used by cell names.
+///
+/// Stones are shown as the number of their player, in color if `colors` is
+/// set, and the last move is marked with brackets.
+pub fn render_board(game: &GameY, colors: bool) -> String {
+    TextRenderer::new().with_colors(colors).render(game)
+}
+
+/// Runs an interactive game against the bot until the player quits.
+pub fn run_play_cli(args: &PlayArgs) -> Result<()> {
+    let saved = match (&args.load, &args.autosave) {
+        (Some(path), _) => Some(SavedGame::load(path)?),
+        (None, Some(path)) if path.exists() => {
+            println!("Resuming the game saved in {}", path.display());
+            Some(SavedGame::load(path)?)
+        }
+        _ => None,
+    };
+    // A resumed game keeps the seed it was started with
+    let seed = args
+        .seed
+        .or_else(|| saved.as_ref().and_then(|saved| saved.seed))
+        .unwrap_or_else(random_seed);
+    let mut game = saved.map_or_else(|| GameY::new(args.size), |saved| saved.game);
+    if let Some(path) = &args.autosave {
+        let saved = SavedGame::new(game.clone()).with_seed(seed);
+        game.autosave_with(path, saved)?;
+    }
+    if let Some(path) = &args.record {
+        let recorder = GameRecorder::create(path, &game)?
+            .with_evaluator(|game| evaluate_position(game, PlayerId::new(0)));
+        game.add_observer(recorder);
+    }
+    let human = PlayerId::new(if args.bot_first { 1 } else { 0 });
+    let (bot, budget_ms, opponent): (Arc<dyn YBot>, u64, String) = match &args.profile {
+        Some(name) => {
+            let profiles = match &args.profiles {
+                Some(path) => BotProfiles::load(path)?,
+                None => BotProfiles::new(),
+            };
+            let profile = profiles.get(name)?;
+            let bot = Arc::new(profile.build());
+            (bot, profile.time_ms, format!("profile {}", name))
+        }
+        None => (
+            args.difficulty.bot(),
+            args.difficulty.time_budget_ms(),
+            format!("{} difficulty", args.difficulty),
+        ),
+    };
+    bot.reseed(seed);
+    let time_ms = args.time_ms.unwrap_or(budget_ms);
+    let mut session = VsBotGame::new(game, human, bot, time_ms);
+    let mut rl = DefaultEditor::new()?;
+
+    println!(
+        "Game of Y, size {}, {}. You play {}. Type help for commands.",
+        session.game().board_size(),
+        opponent,
+        human
+    );
+    loop {
+        if session.is_bot_turn() {
+            println!("{}", render_board(session.game(), true));
+            println!("Thinking...");
+            match session.bot_move() {
+                Some(coords) => println!(
+                    "Bot plays {}",
+                    coords.to_cell_name(session.game().board_size())
+                ),
+                None => {
+                    println!("The bot has no move to play.");
+                    return Ok(());
+                }
+            }
+            continue;
+        }
+
+        println!("{}", render_board(session.game(), true));
+        let prompt = match session.game().result() {
+            Some(result) if result.winner == human => {
+                format!("You win ({})! undo, save or quit? ", result.reason)
+            }
+            Some(result) => format!("You lose ({}). undo, save or quit? ", result.reason),
+            None => "Your move? ".to_string(),
+        };
+        let line = match rl.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        rl.add_history_entry(line.as_str())?;
+        let command = match parse_play_command(&line, session.game().board_size()) {
+            Ok(Some(command)) => command,
+            Ok(None) => continue,
+            Err(message) => {
+                println!("{}", message);
+                continue;
+            }
+        };
+        match command {
+            PlayCommand::Play(coords) => {
+                if let Err(e) = session.play(coords) {
+                    println!("Illegal move: {}", e);
+                }
+            }
+            PlayCommand::Undo => match session.undo() {
+                Ok(undone) => println!("Took back {} move(s)", undone),
+                Err(e) => println!("{}", e),
+            },
+            PlayCommand::Save(path) => match SavedGame::new(session.game().clone())
+                .with_seed(seed)
+                .save(&path)
+            {
+                Ok(()) => println!("Game saved to {}", path.display()),
+                Err(e) => println!("{}", e),
+            },
+            PlayCommand::Resign => {
+                if let Err(e) = session.resign() {
+                    println!("{}", e);
+                }
+            }
+            PlayCommand::Help => print_play_help(),
+            PlayCommand::Quit => return Ok(()),
+        }
+    }
+}
+
+fn print_play_help() {
+    println!("Available commands:");
+    println!("  <cell>        - Play a cell: c2 (row c, second cell), 1,1,1 or an index");
+    println!("  undo          - Take back your last move and the bot's reply");
+    println!("  save <file>   - Save the game; resume it with --load <file>");
+    println!("  resign        - Resign the game");
+    println!("  quit          - Leave the game");
+    println!("  help          - Show this help message");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vs_random(size: u32, human: u32) -> VsBotGame {
+        VsBotGame::new(
+            GameY::new(size),
+            PlayerId::new(human),
+            Arc::new(RandomBot::new()),
+            0,
+        )
+    }
+
+    #[test]
+    fn test_parse_cell_notations() {
+        let center = Coordinates::new(1, 1, 1);
+        assert_eq!(parse_cell("c2", 4), Ok(center));
+        assert_eq!(parse_cell("1,1,1", 4), Ok(center));
+        assert_eq!(parse_cell("(1, 1, 1)", 4), Ok(center));
+        assert_eq!(parse_cell("4", 4), Ok(center));
+        assert!(parse_cell("2,2,2", 4).is_err());
+        assert!(parse_cell("10", 4).is_err());
+        assert!(parse_cell("z9", 4).is_err());
+    }
+
+    #[test]
+    fn test_parse_play_command() {
+        assert_eq!(parse_play_command("  ", 4), Ok(None));
+        assert_eq!(parse_play_command("UNDO", 4), Ok(Some(PlayCommand::Undo)));
+        assert_eq!(
+            parse_play_command("save game.json", 4),
+            Ok(Some(PlayCommand::Save(PathBuf::from("game.json"))))
+        );
+        assert!(parse_play_command("save", 4).is_err());
+        assert_eq!(
+            parse_play_command("1, 1, 1", 4),
+            Ok(Some(PlayCommand::Play(Coordinates::new(1, 1, 1))))
+        );
+    }
+
+    #[test]
+    fn test_bot_replies_and_undo_takes_back_both_moves() {
+        let mut session = vs_random(4, 0);
+        assert!(!session.is_bot_turn());
+        session.play(Coordinates::new(1, 1, 1)).unwrap();
+        assert!(session.is_bot_turn());
+        assert!(session.bot_move().is_some());
+        assert_eq!(session.game().history().len(), 2);
+
+        assert_eq!(session.undo().unwrap(), 2);
+        assert!(session.game().history().is_empty());
+        assert!(matches!(session.undo(), Err(GameYError::NoMoveToUndo)));
+    }
+
+    #[test]
+    fn test_undo_when_bot_played_first() {
+        let mut session = vs_random(4, 1);
+        assert!(session.bot_move().is_some());
+        assert_eq!(session.undo().unwrap(), 1);
+        assert!(session.is_bot_turn());
+    }
+
+    #[test]
+    fn test_render_board_labels_rows_and_marks_last_move() {
+        let mut session = vs_random(3, 0);
+        session.play(Coordinates::new(0, 1, 1)).unwrap();
+        let board = render_board(session.game(), false);
+        assert_eq!(board, "a      .\nb    .   .\nc  .  [0]  .\n   1   2   3\n");
+    }
+}
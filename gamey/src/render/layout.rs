@@ -0,0 +1,225 @@
+//! Screen geometry of the board.
+//!
+//! [`BoardLayout`] places the cells of a board as pointy-top hexagons
+//! arranged in a triangle, with the top corner as row `a`, as drawn by
+//! [`SvgRenderer`](crate::SvgRenderer). It maps cells to the positions of
+//! their centres and corners, and positions back to the cells under them,
+//! so that graphical front ends share one set of formulas.
+//!
+//! Positions are in the usual screen convention: `x` grows to the right and
+//! `y` grows downwards, with the origin at the top left corner of the
+//! bounding box.
+
+use crate::Coordinates;
+
+/// A position on the screen, as `(x, y)`.
+pub type Point = (f64, f64);
+
+/// The positions of the cells of a board drawn as hexagons.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{BoardLayout, Coordinates};
+///
+/// let layout = BoardLayout::new(5, 20.0);
+/// let top = Coordinates::new(4, 0, 0);
+/// let (x, y) = layout.center(top);
+/// assert_eq!(layout.cell_at(x, y), Some(top));
+/// // Just above the top corner of the board
+/// assert_eq!(layout.cell_at(x, -1.0), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoardLayout {
+    board_size: u32,
+    cell_radius: f64,
+    margin: f64,
+}
+
+impl BoardLayout {
+    /// Creates the layout of a board of `board_size`, whose hexagons have
+    /// corners `cell_radius` pixels away from their centres.
+    pub fn new(board_size: u32, cell_radius: f64) -> Self {
+        Self {
+            board_size,
+            cell_radius,
+            margin: 0.0,
+        }
+    }
+
+    /// Leaves `margin` pixels of empty space around the board.
+    pub fn with_margin(mut self, margin: f64) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Returns the size of the board laid out.
+    pub fn board_size(&self) -> u32 {
+        self.board_size
+    }
+
+    /// Returns the distance from the centre of a cell to its corners.
+    pub fn cell_radius(&self) -> f64 {
+        self.cell_radius
+    }
+
+    /// Returns the space left around the board.
+    pub fn margin(&self) -> f64 {
+        self.margin
+    }
+
+    /// Returns the width of a cell, from one flat side to the other.
+    pub fn cell_width(&self) -> f64 {
+        3f64.sqrt() * self.cell_radius
+    }
+
+    /// Returns the width and height of the board, margins included.
+    pub fn bounding_box(&self) -> (f64, f64) {
+        let r = self.cell_radius;
+        let width = self.cell_width() * self.board_size as f64;
+        let height = if self.board_size == 0 {
+            0.0
+        } else {
+            2.0 * r + 1.5 * r * (self.board_size - 1) as f64
+        };
+        (width + 2.0 * self.margin, height + 2.0 * self.margin)
+    }
+
+    /// Returns the centre of the cell at `coords`.
+    pub fn center(&self, coords: Coordinates) -> Point {
+        let size = self.board_size as f64;
+        let row = self.board_size as f64 - 1.0 - coords.x() as f64;
+        let x = self.cell_width() * (coords.y() as f64 + (size - 1.0 - row) / 2.0 + 0.5);
+        let y = self.cell_radius + 1.5 * self.cell_radius * row;
+        (self.margin + x, self.margin + y)
+    }
+
+    /// Returns the corners of the cell at `coords`, clockwise from the top.
+    ///
+    /// Neighbouring cells share two corners, so the hexagons tile the
+    /// triangle without gaps.
+    pub fn vertices(&self, coords: Coordinates) -> [Point; 6] {
+        hexagon_corners(self.center(coords), self.cell_radius)
+    }
+
+    /// Returns the cell whose hexagon contains the point `(x, y)`, or
+    /// `None` if the point is off the board.
+    ///
+    /// Points on the border between two cells go to either of them.
+    pub fn cell_at(&self, x: f64, y: f64) -> Option<Coordinates> {
+        if self.board_size == 0 || self.cell_radius <= 0.0 {
+            return None;
+        }
+        // Axial coordinates of the hexagonal grid, with the top cell at the
+        // origin: `r` counts rows down, `q` the cells along a row, minus
+        // the row, so that both axes are straight lines of cells
+        let half = (self.board_size - 1) as f64 / 2.0 + 0.5;
+        let px = (x - self.margin) / self.cell_width() - half;
+        let r = (y - self.margin - self.cell_radius) / (1.5 * self.cell_radius);
+        let q = px - r / 2.0;
+        let (q, r) = round_axial(q, r);
+        let (row, column) = (r, q + r);
+        if row < 0 || row >= self.board_size as i64 || column < 0 || column > row {
+            return None;
+        }
+        let (row, column) = (row as u32, column as u32);
+        Some(Coordinates::new(
+            self.board_size - 1 - row,
+            column,
+            row - column,
+        ))
+    }
+}
+
+/// Returns the corners of a pointy-top hexagon, clockwise from the top.
+pub(crate) fn hexagon_corners(center: Point, radius: f64) -> [Point; 6] {
+    std::array::from_fn(|corner| {
+        let angle = std::f64::consts::PI / 3.0 * corner as f64 - std::f64::consts::PI / 2.0;
+        (
+            center.0 + radius * angle.cos(),
+            center.1 + radius * angle.sin(),
+        )
+    })
+}
+
+/// Rounds fractional axial coordinates to those of the hexagon containing
+/// them, through the cube coordinates of the grid.
+fn round_axial(q: f64, r: f64) -> (i64, i64) {
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    // The coordinate rounded the furthest is the one to fix
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+    (rq as i64, rr as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(size: u32) -> impl Iterator<Item = Coordinates> {
+        (0..size * (size + 1) / 2).map(move |index| Coordinates::from_index(index, size))
+    }
+
+    fn close(a: Point, b: Point) -> bool {
+        (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_points_inside_a_cell_hit_it() {
+        let layout = BoardLayout::new(6, 12.0).with_margin(7.0);
+        for coords in cells(6) {
+            let center = layout.center(coords);
+            assert_eq!(layout.cell_at(center.0, center.1), Some(coords));
+            // Just inside each corner
+            for (x, y) in layout.vertices(coords) {
+                let inside = (x + (center.0 - x) * 0.05, y + (center.1 - y) * 0.05);
+                assert_eq!(layout.cell_at(inside.0, inside.1), Some(coords));
+            }
+        }
+    }
+
+    #[test]
+    fn test_neighbours_share_an_edge() {
+        let layout = BoardLayout::new(5, 10.0);
+        for coords in cells(5) {
+            let corners = layout.vertices(coords);
+            for neighbor in cells(5).filter(|other| coords.distance(other) == 1) {
+                let shared = layout
+                    .vertices(neighbor)
+                    .iter()
+                    .filter(|corner| corners.iter().any(|own| close(**corner, *own)))
+                    .count();
+                assert_eq!(shared, 2, "{:?} {:?}", coords, neighbor);
+            }
+        }
+    }
+
+    #[test]
+    fn test_points_off_the_board_hit_nothing() {
+        let layout = BoardLayout::new(4, 10.0).with_margin(5.0);
+        let (width, height) = layout.bounding_box();
+        let width_of_cells = layout.cell_width() * 4.0;
+        assert!((width - width_of_cells - 10.0).abs() < 1e-9);
+        assert!((height - (20.0 + 45.0 + 10.0)).abs() < 1e-9);
+        for (x, y) in [
+            (2.0, 2.0),
+            (width - 2.0, 2.0),
+            (-50.0, height / 2.0),
+            (width / 2.0, height + 1.0),
+            (width / 2.0, 4.0),
+        ] {
+            assert_eq!(layout.cell_at(x, y), None, "({}, {})", x, y);
+        }
+        // The corners of the bounding box just fit the corner cells
+        let bottom_left = Coordinates::new(0, 0, 3);
+        let (x, y) = layout.center(bottom_left);
+        assert!((x - 5.0 - layout.cell_width() / 2.0).abs() < 1e-9);
+        assert!((y - (height - 15.0)).abs() < 1e-9);
+        assert_eq!(BoardLayout::new(0, 10.0).cell_at(0.0, 0.0), None);
+    }
+}
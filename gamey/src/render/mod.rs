@@ -0,0 +1,15 @@
+//! Rendering of board positions as text and images.
+//!
+//! - [`BoardLayout`]: Screen positions of the cells, and the cells under
+//!   given positions, for graphical front ends
+//! - [`TextRenderer`]: Text diagrams with labelled rows and columns, also used
+//!   by the `Display` implementation of [`GameY`](crate::GameY)
+//! - [`SvgRenderer`]: SVG images of a position, for web UIs and documentation
+
+pub mod layout;
+pub mod svg;
+pub mod text;
+
+pub use layout::*;
+pub use svg::*;
+pub use text::*;
@@ -0,0 +1,342 @@
+use std::fmt::Write;
+
+use crate::render::layout::hexagon_corners;
+use crate::{BoardLayout, Coordinates, GameY, Movement, PlayerId, Point};
+
+/// Fill colours of the stones, by player id.
+const STONE_COLORS: [&str; 2] = ["#2563eb", "#dc2626"];
+/// Heatmap tints: positive values use the first, negative values the second.
+const HEAT_COLORS: [&str; 2] = ["#3b82f6", "#ef4444"];
+const CELL_FILL: &str = "#f5deb3";
+const BLOCKED_FILL: &str = "#6b5a45";
+const CELL_STROKE: &str = "#8b6b3d";
+const WINNING_STROKE: &str = "#f59e0b";
+const LABEL_COLOR: &str = "#5b4636";
+
+/// Renders a [`GameY`] position as an SVG image.
+///
+/// Cells are drawn as hexagons arranged in a triangle, with the top corner
+/// as row `a`, matching [`Coordinates::to_cell_name`], at the positions given
+/// by [`SvgRenderer::layout`]. Every element carries
+/// a class (`cell`, `cell blocked`, `stone player-0`, `last-move`,
+/// `winning`, `heat`, `label`) so that the image can be restyled with CSS.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{GameY, SvgRenderer};
+///
+/// let game = GameY::new(5);
+/// let svg = SvgRenderer::new().with_labels(false).render(&game);
+/// assert!(svg.starts_with("<svg"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SvgRenderer {
+    cell_radius: f64,
+    labels: bool,
+    last_move: bool,
+    winning_path: bool,
+    heatmap: Option<Vec<f32>>,
+}
+
+impl Default for SvgRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SvgRenderer {
+    /// Creates a renderer with labels, the last-move marker and the winning
+    /// path enabled, and no heatmap.
+    pub fn new() -> Self {
+        Self {
+            cell_radius: 20.0,
+            labels: true,
+            last_move: true,
+            winning_path: true,
+            heatmap: None,
+        }
+    }
+
+    /// Sets the distance from the centre of a cell to its corners, in pixels.
+    pub fn with_cell_radius(mut self, cell_radius: f64) -> Self {
+        self.cell_radius = cell_radius;
+        self
+    }
+
+    /// Shows or hides the row letters and column numbers around the board.
+    pub fn with_labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Shows or hides the marker on the last stone placed.
+    pub fn with_last_move(mut self, last_move: bool) -> Self {
+        self.last_move = last_move;
+        self
+    }
+
+    /// Shows or hides the outline around the winning group.
+    pub fn with_winning_path(mut self, winning_path: bool) -> Self {
+        self.winning_path = winning_path;
+        self
+    }
+
+    /// Tints the cells with a heatmap, indexed by board index.
+    ///
+    /// Values range from -1.0 to 1.0: positive values are tinted with player
+    /// 0's colour, negative values with player 1's, and the magnitude sets the
+    /// opacity. The output of [`GameY::influence_map`] can be used directly;
+    /// other data, such as visit counts, should be normalised first. Missing
+    /// or out-of-range values are ignored and clamped respectively.
+    pub fn with_heatmap(mut self, heatmap: Vec<f32>) -> Self {
+        self.heatmap = Some(heatmap);
+        self
+    }
+
+    /// Returns the positions of the cells in the images of boards of
+    /// `board_size`, to map clicks on a rendered image back to cells.
+    pub fn layout(&self, board_size: u32) -> BoardLayout {
+        let r = self.cell_radius;
+        let margin = if self.labels { 1.5 * r } else { 0.5 * r };
+        BoardLayout::new(board_size, r).with_margin(margin)
+    }
+
+    /// Renders the position as a standalone SVG document.
+    pub fn render(&self, game: &GameY) -> String {
+        let size = game.board_size();
+        let r = self.cell_radius;
+        let layout = self.layout(size);
+        let width = layout.cell_width();
+        let (total_width, total_height) = layout.bounding_box();
+        let center = |coords: &Coordinates| layout.center(*coords);
+        let last = match game.history().last() {
+            Some(Movement::Placement { coords, .. }) if self.last_move => Some(*coords),
+            _ => None,
+        };
+        let winning = if self.winning_path {
+            game.winning_group().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="0 0 {w:.2} {h:.2}">"#,
+            w = total_width,
+            h = total_height
+        );
+        let _ = writeln!(
+            svg,
+            r#"<title>Game of Y (size {}), {} moves</title>"#,
+            size,
+            game.history().len()
+        );
+
+        for row in 0..size {
+            for coords in Coordinates::iter_row(row, size) {
+                let (cx, cy) = center(&coords);
+                let points = hexagon_points((cx, cy), r);
+                let (class, fill) = if game.is_blocked(&coords) {
+                    ("cell blocked", BLOCKED_FILL)
+                } else {
+                    ("cell", CELL_FILL)
+                };
+                let _ = writeln!(
+                    svg,
+                    r#"<polygon class="{}" points="{}" fill="{}" stroke="{}" stroke-width="1"/>"#,
+                    class, points, fill, CELL_STROKE
+                );
+                let heat = self
+                    .heatmap
+                    .as_ref()
+                    .and_then(|heatmap| heatmap.get(coords.to_index(size) as usize))
+                    .map(|value| value.clamp(-1.0, 1.0))
+                    .filter(|value| *value != 0.0);
+                if let Some(value) = heat {
+                    let color = HEAT_COLORS[usize::from(value < 0.0)];
+                    let _ = writeln!(
+                        svg,
+                        r#"<polygon class="heat" points="{}" fill="{}" fill-opacity="{:.2}"/>"#,
+                        points,
+                        color,
+                        value.abs()
+                    );
+                }
+            }
+        }
+
+        // Drawn after every cell so that no neighbour covers the outline
+        for coords in &winning {
+            let (cx, cy) = center(coords);
+            let _ = writeln!(
+                svg,
+                r#"<polygon class="winning" points="{}" fill="none" stroke="{}" stroke-width="{:.2}"/>"#,
+                hexagon_points((cx, cy), r * 0.9),
+                WINNING_STROKE,
+                r * 0.15
+            );
+        }
+
+        for row in 0..size {
+            for coords in Coordinates::iter_row(row, size) {
+                let Some(player) = game.cell_owner(&coords) else {
+                    continue;
+                };
+                let (cx, cy) = center(&coords);
+                let _ = writeln!(
+                    svg,
+                    r#"<circle class="stone player-{}" cx="{:.2}" cy="{:.2}" r="{:.2}" fill="{}"/>"#,
+                    player,
+                    cx,
+                    cy,
+                    r * 0.7,
+                    stone_color(player)
+                );
+            }
+        }
+
+        if let Some(coords) = last {
+            let (cx, cy) = center(&coords);
+            let _ = writeln!(
+                svg,
+                r##"<circle class="last-move" cx="{:.2}" cy="{:.2}" r="{:.2}" fill="#ffffff"/>"##,
+                cx,
+                cy,
+                r * 0.2
+            );
+        }
+
+        if self.labels {
+            let font_size = r * 0.7;
+            for row in 0..size {
+                let first = Coordinates::new(size - 1 - row, 0, row);
+                let name = first.to_cell_name(size);
+                let letters = name.trim_end_matches(|c: char| c.is_ascii_digit());
+                let (cx, cy) = center(&first);
+                let _ = writeln!(
+                    svg,
+                    r#"<text class="label" x="{:.2}" y="{:.2}" font-size="{:.2}" text-anchor="end" dominant-baseline="middle" fill="{}">{}</text>"#,
+                    cx - width,
+                    cy,
+                    font_size,
+                    LABEL_COLOR,
+                    letters
+                );
+            }
+            for coords in Coordinates::iter_row(size - 1, size) {
+                let (cx, cy) = center(&coords);
+                let _ = writeln!(
+                    svg,
+                    r#"<text class="label" x="{:.2}" y="{:.2}" font-size="{:.2}" text-anchor="middle" dominant-baseline="middle" fill="{}">{}</text>"#,
+                    cx,
+                    cy + 1.6 * r,
+                    font_size,
+                    LABEL_COLOR,
+                    coords.y() + 1
+                );
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Renders a position as SVG with the default [`SvgRenderer`] settings.
+pub fn render_svg(game: &GameY) -> String {
+    SvgRenderer::new().render(game)
+}
+
+fn stone_color(player: PlayerId) -> &'static str {
+    STONE_COLORS[player.id() as usize % STONE_COLORS.len()]
+}
+
+/// Returns the corners of a pointy-top hexagon as an SVG `points` list.
+fn hexagon_points(center: Point, r: f64) -> String {
+    hexagon_corners(center, r)
+        .iter()
+        .map(|(x, y)| format!("{:.2},{:.2}", x, y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(game: &mut GameY, player: u32, x: u32, y: u32, z: u32) {
+        game.add_move(Movement::Placement {
+            player: PlayerId::new(player),
+            coords: Coordinates::new(x, y, z),
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_empty_board_has_one_cell_per_index() {
+        let svg = render_svg(&GameY::new(4));
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("class=\"cell\"").count(), 10);
+        assert_eq!(svg.matches("class=\"stone").count(), 0);
+        assert!(!svg.contains("last-move"));
+        // Four row letters and four column numbers
+        assert_eq!(svg.matches("class=\"label\"").count(), 8);
+        assert!(svg.contains(">d</text>"));
+    }
+
+    #[test]
+    fn test_stones_and_last_move() {
+        let mut game = GameY::new(3);
+        play(&mut game, 0, 2, 0, 0);
+        play(&mut game, 1, 0, 1, 1);
+        let svg = SvgRenderer::new().with_labels(false).render(&game);
+        assert_eq!(svg.matches("class=\"stone player-0\"").count(), 1);
+        assert_eq!(svg.matches("class=\"stone player-1\"").count(), 1);
+        assert_eq!(svg.matches("class=\"last-move\"").count(), 1);
+        assert!(!svg.contains("class=\"label\""));
+
+        let svg = SvgRenderer::new().with_last_move(false).render(&game);
+        assert!(!svg.contains("last-move"));
+    }
+
+    #[test]
+    fn test_winning_path_is_outlined() {
+        let mut game = GameY::new(2);
+        play(&mut game, 0, 1, 0, 0);
+        play(&mut game, 1, 0, 1, 0);
+        play(&mut game, 0, 0, 0, 1);
+        let svg = render_svg(&game);
+        assert_eq!(svg.matches("class=\"winning\"").count(), 2);
+
+        let svg = SvgRenderer::new().with_winning_path(false).render(&game);
+        assert!(!svg.contains("class=\"winning\""));
+    }
+
+    #[test]
+    fn test_heatmap_tints_nonzero_cells() {
+        let game = GameY::new(2);
+        let svg = SvgRenderer::new()
+            .with_heatmap(vec![0.5, 0.0, -2.0])
+            .render(&game);
+        assert_eq!(svg.matches("class=\"heat\"").count(), 2);
+        assert!(svg.contains(&format!(
+            "fill=\"{}\" fill-opacity=\"0.50\"",
+            HEAT_COLORS[0]
+        )));
+        assert!(svg.contains(&format!(
+            "fill=\"{}\" fill-opacity=\"1.00\"",
+            HEAT_COLORS[1]
+        )));
+    }
+
+    #[test]
+    fn test_cells_are_laid_out_as_a_triangle() {
+        let renderer = SvgRenderer::new().with_labels(false).with_cell_radius(10.0);
+        let svg = renderer.render(&GameY::new(3));
+        // Three hexagons wide and two and a half rows of 15px high, plus margins
+        assert!(svg.contains("width=\"62\" height=\"60\""));
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt::{self, Write};
+
+use crate::{Coordinates, GameY, Movement, PlayerId};
+
+/// Renders a [`GameY`] position as text, with lettered rows and numbered
+/// columns as used by [`Coordinates::to_cell_name`].
+///
+/// Empty cells are shown as `.` and stones as the number of their player, or
+/// as `·`, `●` and `○` with [`with_unicode`](Self::with_unicode). Blocked
+/// cells are shown as `#`. The last stone placed is put in brackets. This is also what the [`Display`]
+/// implementation of [`GameY`] prints; the alternate form (`{:#}`) uses the
+/// Unicode symbols.
+///
+/// ```
+/// use gamey::{Coordinates, GameY, Movement, PlayerId};
+///
+/// let mut game = GameY::new(3);
+/// game.add_move(Movement::Placement {
+///     player: PlayerId::new(0),
+///     coords: Coordinates::new(0, 1, 1),
+/// })
+/// .unwrap();
+/// assert_eq!(
+///     game.to_string(),
+///     "a      .\nb    .   .\nc  .  [0]  .\n   1   2   3\n"
+/// );
+/// ```
+///
+/// [`Display`]: std::fmt::Display
+#[derive(Debug, Clone, Default)]
+pub struct TextRenderer {
+    unicode: bool,
+    colors: bool,
+    hide_last_move: bool,
+}
+
+impl TextRenderer {
+    /// Creates a renderer with ASCII symbols, no colors and the last move
+    /// marked.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses `·`, `●` (player 0) and `○` (player 1) instead of `.`, `0` and `1`.
+    pub fn with_unicode(mut self, unicode: bool) -> Self {
+        self.unicode = unicode;
+        self
+    }
+
+    /// Colors the stones with ANSI escape codes: blue for player 0, red for
+    /// player 1.
+    pub fn with_colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Shows or hides the brackets around the last stone placed.
+    pub fn with_last_move(mut self, last_move: bool) -> Self {
+        self.hide_last_move = !last_move;
+        self
+    }
+
+    /// Renders the position, one line per row followed by the column numbers.
+    pub fn render(&self, game: &GameY) -> String {
+        let mut result = String::new();
+        let _ = self.write_board(&mut result, game);
+        result
+    }
+
+    fn write_board(&self, out: &mut impl Write, game: &GameY) -> fmt::Result {
+        let size = game.board_size();
+        let label_width = Coordinates::new(0, 0, size - 1).to_cell_name(size).len() - 1;
+        let last = match game.history().last() {
+            Some(Movement::Placement { coords, .. }) if !self.hide_last_move => Some(*coords),
+            _ => None,
+        };
+        for row in 0..size {
+            let label = Coordinates::new(size - 1 - row, 0, row).to_cell_name(size);
+            let mut line = format!(
+                "{:>w$} {}",
+                &label[..label.len() - 1],
+                " ".repeat(2 * (size - 1 - row) as usize),
+                w = label_width
+            );
+            for coords in Coordinates::iter_row(row, size) {
+                let symbol = if game.is_blocked(&coords) {
+                    "#".to_string()
+                } else {
+                    self.symbol(game.cell_owner(&coords))
+                };
+                if Some(coords) == last {
+                    let _ = write!(line, "[{}] ", symbol);
+                } else {
+                    let _ = write!(line, " {}  ", symbol);
+                }
+            }
+            writeln!(out, "{}", line.trim_end())?;
+        }
+        let mut footer = format!("{:w$} ", "", w = label_width);
+        for column in 1..=size {
+            let _ = write!(footer, "{:^4}", column);
+        }
+        writeln!(out, "{}", footer.trim_end())
+    }
+
+    fn symbol(&self, owner: Option<PlayerId>) -> String {
+        let symbol = match (owner, self.unicode) {
+            (None, false) => ".".to_string(),
+            (None, true) => "·".to_string(),
+            (Some(player), false) => player.to_string(),
+            (Some(player), true) if player.id() == 0 => "●".to_string(),
+            (Some(_), true) => "○".to_string(),
+        };
+        match owner {
+            Some(player) if self.colors => {
+                let color = if player.id() == 0 { 34 } else { 31 };
+                format!("\x1b[{}m{}\x1b[0m", color, symbol)
+            }
+            _ => symbol,
+        }
+    }
+}
+
+impl fmt::Display for GameY {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        TextRenderer::new()
+            .with_unicode(f.alternate())
+            .write_board(f, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game_with_moves(size: u32, moves: &[(u32, u32, u32)]) -> GameY {
+        let mut game = GameY::new(size);
+        for (i, &(x, y, z)) in moves.iter().enumerate() {
+            game.add_move(Movement::Placement {
+                player: PlayerId::new(i as u32 % 2),
+                coords: Coordinates::new(x, y, z),
+            })
+            .unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_display_empty_board() {
+        assert_eq!(GameY::new(2).to_string(), "a    .\nb  .   .\n   1   2\n");
+    }
+
+    #[test]
+    fn test_display_marks_last_move() {
+        let game = game_with_moves(3, &[(2, 0, 0), (0, 1, 1)]);
+        assert_eq!(
+            game.to_string(),
+            "a      0\nb    .   .\nc  .  [1]  .\n   1   2   3\n"
+        );
+    }
+
+    #[test]
+    fn test_alternate_display_uses_unicode() {
+        let game = game_with_moves(2, &[(1, 0, 0), (0, 1, 0)]);
+        assert_eq!(format!("{:#}", game), "a    ●\nb  ·  [○]\n   1   2\n");
+    }
+
+    #[test]
+    fn test_renderer_options() {
+        let game = game_with_moves(2, &[(1, 0, 0)]);
+        let plain = TextRenderer::new().with_last_move(false).render(&game);
+        assert_eq!(plain, "a    0\nb  .   .\n   1   2\n");
+        let colored = TextRenderer::new().with_colors(true).render(&game);
+        assert!(colored.contains("[\x1b[34m0\x1b[0m]"));
+    }
+
+    #[test]
+    fn test_display_shows_blocked_cells() {
+        let game = GameY::from_position_string("1/#1/3 B").unwrap();
+        assert_eq!(
+            game.to_string(),
+            "a      .\nb    #   .\nc  .   .   .\n   1   2   3\n"
+        );
+    }
+
+    #[test]
+    fn test_labels_use_two_letters_past_z() {
+        let board = GameY::new(27).to_string();
+        let lines: Vec<&str> = board.lines().collect();
+        assert!(lines[0].starts_with(" a "));
+        assert!(lines[26].starts_with("aa "));
+        assert!(lines[27].ends_with("27"));
+    }
+}
@@ -0,0 +1,227 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use web_time::{Duration, Instant};
+
+use crate::PlayerId;
+
+/// Time allowed to each player: a starting budget plus an increment added
+/// after every move (Fischer clock).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimeControl {
+    /// Time each player starts with, in milliseconds.
+    pub initial_ms: u64,
+    /// Time added to a player's clock after each of their moves, in milliseconds.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub increment_ms: u64,
+}
+
+/// The state of a [`GameClock`] at some instant, for saving it to a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClockSnapshot {
+    /// The time control of the clock.
+    pub time_control: TimeControl,
+    /// Time left to each player, indexed by player id, in milliseconds.
+    pub remaining_ms: [u64; 2],
+    /// The player whose clock was running, if any.
+    pub running: Option<PlayerId>,
+}
+
+/// A two-player chess-style clock.
+///
+/// At most one clock runs at a time. Every method takes the current instant
+/// so callers (and tests) decide what "now" is.
+#[derive(Debug, Clone)]
+pub struct GameClock {
+    control: TimeControl,
+    remaining_ms: [u64; 2],
+    running: Option<(PlayerId, Instant)>,
+}
+
+impl GameClock {
+    /// Creates a stopped clock with the full budget for both players.
+    pub fn new(control: TimeControl) -> Self {
+        Self {
+            control,
+            remaining_ms: [control.initial_ms; 2],
+            running: None,
+        }
+    }
+
+    /// Restores a clock from a snapshot. A clock that was running when the
+    /// snapshot was taken runs again from `now`.
+    pub fn from_snapshot(snapshot: ClockSnapshot, now: Instant) -> Self {
+        Self {
+            control: snapshot.time_control,
+            remaining_ms: snapshot.remaining_ms,
+            running: snapshot.running.map(|player| (player, now)),
+        }
+    }
+
+    /// Returns the state of the clock at `now`.
+    pub fn snapshot(&self, now: Instant) -> ClockSnapshot {
+        ClockSnapshot {
+            time_control: self.control,
+            remaining_ms: [PlayerId::new(0), PlayerId::new(1)]
+                .map(|player| self.remaining_ms(player, now)),
+            running: self.running(),
+        }
+    }
+
+    /// Returns the time control of the clock.
+    pub fn time_control(&self) -> TimeControl {
+        self.control
+    }
+
+    /// Returns the player whose clock is running, if any.
+    pub fn running(&self) -> Option<PlayerId> {
+        self.running.map(|(player, _)| player)
+    }
+
+    /// Returns the time `player` has left at `now`.
+    pub fn remaining_ms(&self, player: PlayerId, now: Instant) -> u64 {
+        let remaining = self.remaining_ms[player.id() as usize];
+        match self.running {
+            Some((running, since)) if running == player => {
+                remaining.saturating_sub(elapsed_ms(since, now))
+            }
+            _ => remaining,
+        }
+    }
+
+    /// Returns how long until the running clock reaches zero, or None if no
+    /// clock is running.
+    pub fn time_to_flag(&self, now: Instant) -> Option<Duration> {
+        self.running
+            .map(|(player, _)| Duration::from_millis(self.remaining_ms(player, now)))
+    }
+
+    /// Returns the player whose clock has reached zero at `now`, if any.
+    pub fn flagged(&self, now: Instant) -> Option<PlayerId> {
+        let (player, _) = self.running?;
+        (self.remaining_ms(player, now) == 0).then_some(player)
+    }
+
+    /// Starts the clock of `player`, stopping the one that was running
+    /// without adding an increment.
+    pub fn start(&mut self, player: PlayerId, now: Instant) {
+        self.stop(now);
+        self.running = Some((player, now));
+    }
+
+    /// Stops the running clock, if any, keeping the time it used.
+    pub fn stop(&mut self, now: Instant) {
+        if let Some((player, _)) = self.running {
+            self.remaining_ms[player.id() as usize] = self.remaining_ms(player, now);
+            self.running = None;
+        }
+    }
+
+    /// Ends the turn of the running player: stops their clock, adds the
+    /// increment and starts the clock of `next`, if any.
+    ///
+    /// Returns an error, leaving the clock stopped, if the running player
+    /// had already run out of time.
+    pub fn press(&mut self, next: Option<PlayerId>, now: Instant) -> Result<(), PlayerId> {
+        if let Some(player) = self.flagged(now) {
+            self.stop(now);
+            return Err(player);
+        }
+        if let Some((player, _)) = self.running {
+            self.stop(now);
+            self.remaining_ms[player.id() as usize] += self.control.increment_ms;
+        }
+        if let Some(next) = next {
+            self.running = Some((next, now));
+        }
+        Ok(())
+    }
+}
+
+fn elapsed_ms(since: Instant, now: Instant) -> u64 {
+    now.saturating_duration_since(since).as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock() -> GameClock {
+        GameClock::new(TimeControl {
+            initial_ms: 1000,
+            increment_ms: 100,
+        })
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_running_clock_counts_down() {
+        let mut clock = clock();
+        let start = Instant::now();
+        let first = PlayerId::new(0);
+        clock.start(first, start);
+        assert_eq!(clock.remaining_ms(first, start + ms(300)), 700);
+        assert_eq!(clock.remaining_ms(PlayerId::new(1), start + ms(300)), 1000);
+        assert_eq!(clock.time_to_flag(start + ms(300)), Some(ms(700)));
+    }
+
+    #[test]
+    fn test_press_adds_increment_and_switches() {
+        let mut clock = clock();
+        let start = Instant::now();
+        clock.start(PlayerId::new(0), start);
+        assert!(clock.press(Some(PlayerId::new(1)), start + ms(300)).is_ok());
+        assert_eq!(clock.running(), Some(PlayerId::new(1)));
+        let later = start + ms(500);
+        assert_eq!(clock.remaining_ms(PlayerId::new(0), later), 800);
+        assert_eq!(clock.remaining_ms(PlayerId::new(1), later), 800);
+    }
+
+    #[test]
+    fn test_press_after_flag_fails() {
+        let mut clock = clock();
+        let start = Instant::now();
+        clock.start(PlayerId::new(0), start);
+        let late = start + ms(1200);
+        assert_eq!(clock.flagged(late), Some(PlayerId::new(0)));
+        assert_eq!(
+            clock.press(Some(PlayerId::new(1)), late),
+            Err(PlayerId::new(0))
+        );
+        assert_eq!(clock.running(), None);
+        assert_eq!(clock.remaining_ms(PlayerId::new(0), late), 0);
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let mut clock = clock();
+        let start = Instant::now();
+        clock.start(PlayerId::new(1), start);
+        let snapshot = clock.snapshot(start + ms(400));
+        assert_eq!(snapshot.remaining_ms, [1000, 600]);
+        assert_eq!(snapshot.running, Some(PlayerId::new(1)));
+
+        // The time between saving and restoring is not charged
+        let resumed = start + ms(5000);
+        let restored = GameClock::from_snapshot(snapshot, resumed);
+        assert_eq!(
+            restored.remaining_ms(PlayerId::new(1), resumed + ms(100)),
+            500
+        );
+        assert_eq!(restored.running(), Some(PlayerId::new(1)));
+    }
+
+    #[test]
+    fn test_stop_keeps_used_time() {
+        let mut clock = clock();
+        let start = Instant::now();
+        clock.start(PlayerId::new(1), start);
+        clock.stop(start + ms(250));
+        assert_eq!(clock.time_to_flag(start + ms(900)), None);
+        assert_eq!(clock.remaining_ms(PlayerId::new(1), start + ms(900)), 750);
+    }
+}
@@ -0,0 +1,842 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+use crate::{
+    ClockSnapshot, Coordinates, GameAction, GameClock, GameOverReason, GameY, GameYError, Movement,
+    PlayerId, SpectatorEvent, SpectatorHub, TimeControl, game,
+};
+
+type Result<T> = std::result::Result<T, GameYError>;
+
+/// Who sits in a seat of a [`GameSession`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum SeatOccupant {
+    /// A person, playing through a connection identified by a seat token.
+    Human {
+        /// The name the player joined with.
+        name: String,
+    },
+    /// A bot from the registry, played by the server.
+    Bot {
+        /// The name of the bot.
+        bot_id: String,
+    },
+}
+
+/// What a player of a [`GameSession`] may ask their opponent to agree to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Proposal {
+    /// Take back the last move of the proposer, and the reply to it if
+    /// there was one, so that the proposer is to move again.
+    Takeback,
+    /// Stop the game without a winner. A game of Y cannot be drawn, so this
+    /// is also how players agree to call a game even.
+    Abort,
+}
+
+/// One of the two seats of a [`GameSession`].
+#[derive(Debug, Clone, Default)]
+pub struct Seat {
+    occupant: Option<SeatOccupant>,
+    token: Option<String>,
+    connected: bool,
+}
+
+impl Seat {
+    /// Returns who sits in the seat, or None if it is free.
+    pub fn occupant(&self) -> Option<&SeatOccupant> {
+        self.occupant.as_ref()
+    }
+
+    /// Returns true if a human holds the seat through a live connection.
+    /// Bots are always connected.
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Returns true if nobody sits in the seat.
+    pub fn is_free(&self) -> bool {
+        self.occupant.is_none()
+    }
+}
+
+/// A game between two seated players, with turn enforcement and an
+/// optional clock.
+///
+/// Humans take a seat with [`GameSession::join`] and receive a secret seat
+/// token that authorizes their moves. A player who loses their connection
+/// keeps the seat and can take it back with [`GameSession::reconnect`]; their
+/// clock keeps running meanwhile. The game and the clock start once both
+/// seats are taken.
+///
+/// The session does not know about networking or bots: a server relays
+/// moves, plays the bot seats with [`GameSession::play_as_bot`] and calls
+/// [`GameSession::check_time`] when a clock may have run out.
+///
+/// A player may [propose](GameSession::propose) a takeback or an abort,
+/// which their opponent [accepts or declines](GameSession::answer). A
+/// proposal to a bot is accepted at once, and a proposal still waiting
+/// lapses when a move is played or the game ends.
+///
+/// Spectators follow the moves, clocks, evaluations and proposals of the
+/// session through [`GameSession::spectate`]. Like the observers of a game,
+/// they are not carried over to clones of the session.
+#[derive(Debug)]
+pub struct GameSession {
+    game: GameY,
+    seats: [Seat; 2],
+    clock: Option<GameClock>,
+    spectators: SpectatorHub,
+    proposal: Option<(PlayerId, Proposal)>,
+    aborted: bool,
+    // Time left to each player when each position of the game was reached,
+    // by number of moves, to rewind the clock on a takeback
+    clock_marks: Vec<[u64; 2]>,
+}
+
+impl Clone for GameSession {
+    fn clone(&self) -> Self {
+        let mut session =
+            Self::with_game(self.game.clone(), self.seats.clone(), self.clock.clone());
+        session.proposal = self.proposal;
+        session.aborted = self.aborted;
+        session.clock_marks = self.clock_marks.clone();
+        session
+    }
+}
+
+impl GameSession {
+    /// Creates a session with an empty board, free seats and no clock.
+    pub fn new(board_size: u32) -> Self {
+        Self::with_game(GameY::new(board_size), Default::default(), None)
+    }
+
+    fn with_game(mut game: GameY, seats: [Seat; 2], clock: Option<GameClock>) -> Self {
+        let spectators = SpectatorHub::new();
+        game.add_observer(spectators.clone());
+        Self {
+            game,
+            seats,
+            clock,
+            spectators,
+            proposal: None,
+            aborted: false,
+            clock_marks: Vec::new(),
+        }
+    }
+
+    /// Adds a clock with the given time control.
+    pub fn with_time_control(mut self, control: TimeControl) -> Self {
+        self.clock = Some(GameClock::new(control));
+        self
+    }
+
+    /// Returns the game being played.
+    pub fn game(&self) -> &GameY {
+        &self.game
+    }
+
+    /// Returns the seat of `player`.
+    pub fn seat(&self, player: PlayerId) -> &Seat {
+        &self.seats[player.id() as usize]
+    }
+
+    /// Returns the clock, if the session has one.
+    pub fn clock(&self) -> Option<&GameClock> {
+        self.clock.as_ref()
+    }
+
+    /// Returns a receiver for the events of the session from now on: its
+    /// moves, the end of its game, every start, press and stop of its
+    /// clock, and the evaluations published with
+    /// [`GameSession::publish_evaluation`].
+    pub fn spectate(&self) -> std::sync::mpsc::Receiver<SpectatorEvent> {
+        self.spectators.subscribe()
+    }
+
+    /// Sends the spectators the score of the current position for
+    /// `perspective`, as computed by an engine following the game.
+    pub fn publish_evaluation(&self, perspective: PlayerId, score: i32) {
+        self.spectators
+            .publish(SpectatorEvent::Evaluation { perspective, score });
+    }
+
+    /// Returns the proposal waiting for an answer, with the player who made
+    /// it.
+    pub fn proposal(&self) -> Option<(PlayerId, Proposal)> {
+        self.proposal
+    }
+
+    /// Returns true if the players agreed to abort the game.
+    pub fn is_aborted(&self) -> bool {
+        self.aborted
+    }
+
+    /// Returns true once both seats are taken.
+    pub fn is_ready(&self) -> bool {
+        self.seats.iter().all(|seat| !seat.is_free())
+    }
+
+    /// Seats a human, in the seat of `preferred` if given or else in the
+    /// first free seat.
+    ///
+    /// Returns the player they play as and the token authorizing their moves.
+    pub fn join(
+        &mut self,
+        name: &str,
+        preferred: Option<PlayerId>,
+        now: Instant,
+    ) -> Result<(PlayerId, String)> {
+        let player = self.free_seat(preferred)?;
+        let token = new_seat_token();
+        self.seats[player.id() as usize] = Seat {
+            occupant: Some(SeatOccupant::Human {
+                name: name.to_string(),
+            }),
+            token: Some(token.clone()),
+            connected: true,
+        };
+        self.start_if_ready(now);
+        Ok((player, token))
+    }
+
+    /// Seats a bot as `player`.
+    pub fn add_bot(&mut self, player: PlayerId, bot_id: &str, now: Instant) -> Result<()> {
+        self.free_seat(Some(player))?;
+        self.seats[player.id() as usize] = Seat {
+            occupant: Some(SeatOccupant::Bot {
+                bot_id: bot_id.to_string(),
+            }),
+            token: None,
+            connected: true,
+        };
+        self.start_if_ready(now);
+        Ok(())
+    }
+
+    /// Returns the player whose seat `token` was issued for.
+    pub fn player_for(&self, token: &str) -> Option<PlayerId> {
+        self.seats
+            .iter()
+            .position(|seat| seat.token.as_deref() == Some(token))
+            .map(|idx| PlayerId::new(idx as u32))
+    }
+
+    /// Marks the seat of `token` as connected again.
+    pub fn reconnect(&mut self, token: &str) -> Result<PlayerId> {
+        let player = self.player_for(token).ok_or(GameYError::UnknownSeatToken)?;
+        self.seats[player.id() as usize].connected = true;
+        Ok(player)
+    }
+
+    /// Marks the seat of `token` as disconnected. The player keeps the seat.
+    pub fn disconnect(&mut self, token: &str) -> Option<PlayerId> {
+        let player = self.player_for(token)?;
+        self.seats[player.id() as usize].connected = false;
+        Some(player)
+    }
+
+    /// Returns the player to move and the bot playing for them, if a bot is
+    /// to move in a started game.
+    pub fn bot_to_move(&self) -> Option<(PlayerId, &str)> {
+        if !self.is_ready() || self.aborted {
+            return None;
+        }
+        let player = self.game.next_player()?;
+        match self.seat(player).occupant()? {
+            SeatOccupant::Bot { bot_id } => Some((player, bot_id.as_str())),
+            SeatOccupant::Human { .. } => None,
+        }
+    }
+
+    /// Places a stone for the holder of `token`.
+    pub fn place(&mut self, token: &str, coords: Coordinates, now: Instant) -> Result<()> {
+        let player = self.player_for(token).ok_or(GameYError::UnknownSeatToken)?;
+        self.apply(Movement::Placement { player, coords }, now)
+    }
+
+    /// Performs an action for the holder of `token`.
+    ///
+    /// Resigning is allowed at any time; other actions must be played on the
+    /// player's turn.
+    pub fn act(&mut self, token: &str, action: GameAction, now: Instant) -> Result<()> {
+        let player = self.player_for(token).ok_or(GameYError::UnknownSeatToken)?;
+        if action == GameAction::Resign {
+            if self.aborted {
+                return Err(GameYError::SessionOver);
+            }
+            self.game.resign(player)?;
+            self.proposal = None;
+            self.stop_clock(now);
+            return Ok(());
+        }
+        self.apply(Movement::Action { player, action }, now)
+    }
+
+    /// Places a stone for the bot sitting in the seat of `player`.
+    pub fn play_as_bot(
+        &mut self,
+        player: PlayerId,
+        coords: Coordinates,
+        now: Instant,
+    ) -> Result<()> {
+        if !matches!(self.seat(player).occupant(), Some(SeatOccupant::Bot { .. })) {
+            return Err(GameYError::UnknownSeatToken);
+        }
+        self.apply(Movement::Placement { player, coords }, now)
+    }
+
+    /// Proposes `proposal` to the opponent of the holder of `token`.
+    ///
+    /// The proposal waits for the opponent to [answer](GameSession::answer)
+    /// it, unless the opponent is a bot, which accepts it at once. Returns
+    /// true if it was accepted at once.
+    ///
+    /// Returns an error if the game has not started or is over, if a
+    /// proposal is already waiting, or for a takeback before the proposer
+    /// has moved.
+    pub fn propose(&mut self, token: &str, proposal: Proposal, now: Instant) -> Result<bool> {
+        let player = self.player_for(token).ok_or(GameYError::UnknownSeatToken)?;
+        self.check_ongoing()?;
+        if let Some((proposer, _)) = self.proposal {
+            return Err(GameYError::ProposalPending { player: proposer });
+        }
+        if proposal == Proposal::Takeback && self.takeback_plies(player).is_none() {
+            return Err(GameYError::NoMoveToUndo);
+        }
+        self.spectators
+            .publish(SpectatorEvent::Proposed { player, proposal });
+        let opponent = game::other_player(player);
+        if matches!(
+            self.seat(opponent).occupant(),
+            Some(SeatOccupant::Bot { .. })
+        ) {
+            self.settle(opponent, proposal, true, now);
+            return Ok(true);
+        }
+        self.proposal = Some((player, proposal));
+        Ok(false)
+    }
+
+    /// Accepts or declines, for the holder of `token`, the proposal waiting
+    /// from their opponent, and returns it.
+    ///
+    /// An accepted takeback undoes the moves, sending spectators a
+    /// [`GameEvent::MoveUndone`](crate::GameEvent::MoveUndone) for each,
+    /// and puts the clocks back to the times they showed when the restored
+    /// position was reached. An accepted abort stops the game and its clock
+    /// for good, without a winner.
+    pub fn answer(&mut self, token: &str, accept: bool, now: Instant) -> Result<Proposal> {
+        let player = self.player_for(token).ok_or(GameYError::UnknownSeatToken)?;
+        let proposal = match self.proposal {
+            Some((proposer, proposal)) if proposer != player => proposal,
+            _ => return Err(GameYError::NoProposal),
+        };
+        self.proposal = None;
+        self.settle(player, proposal, accept, now);
+        Ok(proposal)
+    }
+
+    /// Makes the player to move lose on time if their clock has reached zero.
+    ///
+    /// Returns the player who lost, if any.
+    pub fn check_time(&mut self, now: Instant) -> Option<PlayerId> {
+        let player = self.clock.as_ref()?.flagged(now)?;
+        self.stop_clock(now);
+        self.game.forfeit(player, GameOverReason::Timeout).ok()?;
+        self.proposal = None;
+        Some(player)
+    }
+
+    fn apply(&mut self, movement: Movement, now: Instant) -> Result<()> {
+        if !self.is_ready() {
+            return Err(GameYError::SessionNotReady);
+        }
+        if self.aborted {
+            return Err(GameYError::SessionOver);
+        }
+        if let Some(player) = self.check_time(now) {
+            return Err(GameYError::TimeExpired { player });
+        }
+        self.game.add_move(movement)?;
+        self.proposal = None;
+        let next = self.game.next_player();
+        if let Some(clock) = &mut self.clock {
+            // The flag was checked above, so pressing cannot fail
+            let _ = clock.press(next, now);
+        }
+        self.mark_clock(now);
+        self.publish_clock(now);
+        Ok(())
+    }
+
+    fn check_ongoing(&self) -> Result<()> {
+        if !self.is_ready() {
+            return Err(GameYError::SessionNotReady);
+        }
+        if self.aborted || self.game.check_game_over() {
+            return Err(GameYError::SessionOver);
+        }
+        Ok(())
+    }
+
+    /// Carries out `proposal` if `player`, the opponent of the proposer,
+    /// accepted it.
+    fn settle(&mut self, player: PlayerId, proposal: Proposal, accepted: bool, now: Instant) {
+        self.spectators.publish(SpectatorEvent::Answered {
+            player,
+            proposal,
+            accepted,
+        });
+        if !accepted {
+            return;
+        }
+        match proposal {
+            Proposal::Takeback => {
+                let plies = self.takeback_plies(game::other_player(player)).unwrap_or(0);
+                for _ in 0..plies {
+                    // The moves to undo were counted in the history
+                    let _ = self.game.undo_move();
+                }
+                self.rewind_clock(now);
+            }
+            Proposal::Abort => {
+                self.aborted = true;
+                self.stop_clock(now);
+            }
+        }
+    }
+
+    /// Returns the number of moves a takeback proposed by `player` undoes:
+    /// their last move and the reply to it, if any.
+    fn takeback_plies(&self, player: PlayerId) -> Option<usize> {
+        self.game
+            .history()
+            .iter()
+            .rev()
+            .take(2)
+            .position(|movement| match movement {
+                Movement::Placement { player: mover, .. }
+                | Movement::Action { player: mover, .. } => *mover == player,
+            })
+            .map(|index| index + 1)
+    }
+
+    /// Records the time left to each player in the position just reached.
+    fn mark_clock(&mut self, now: Instant) {
+        if let Some(clock) = &self.clock {
+            self.clock_marks.truncate(self.game.history().len());
+            self.clock_marks.push(clock.snapshot(now).remaining_ms);
+        }
+    }
+
+    /// Puts the clock back to the times it showed when the current position
+    /// was reached, running for the player to move.
+    fn rewind_clock(&mut self, now: Instant) {
+        let ply = self.game.history().len();
+        let (Some(clock), Some(&remaining_ms)) = (&mut self.clock, self.clock_marks.get(ply))
+        else {
+            return;
+        };
+        *clock = GameClock::from_snapshot(
+            ClockSnapshot {
+                time_control: clock.time_control(),
+                remaining_ms,
+                running: self.game.next_player(),
+            },
+            now,
+        );
+        self.clock_marks.truncate(ply + 1);
+        self.publish_clock(now);
+    }
+
+    fn free_seat(&self, preferred: Option<PlayerId>) -> Result<PlayerId> {
+        match preferred {
+            Some(player) if player.id() > 1 => Err(GameYError::InvalidNumPlayers {
+                num_players: player.id() + 1,
+                expected: 2,
+            }),
+            Some(player) if !self.seat(player).is_free() => Err(GameYError::SeatTaken { player }),
+            Some(player) => Ok(player),
+            None => self
+                .seats
+                .iter()
+                .position(Seat::is_free)
+                .map(|idx| PlayerId::new(idx as u32))
+                .ok_or(GameYError::NoFreeSeat),
+        }
+    }
+
+    fn start_if_ready(&mut self, now: Instant) {
+        if self.is_ready()
+            && let (Some(clock), Some(player)) = (&mut self.clock, self.game.next_player())
+        {
+            clock.start(player, now);
+            self.mark_clock(now);
+            self.publish_clock(now);
+        }
+    }
+
+    fn stop_clock(&mut self, now: Instant) {
+        if let Some(clock) = &mut self.clock {
+            clock.stop(now);
+            self.publish_clock(now);
+        }
+    }
+
+    fn publish_clock(&self, now: Instant) {
+        if let Some(clock) = &self.clock {
+            self.spectators
+                .publish(SpectatorEvent::Clock(clock.snapshot(now)));
+        }
+    }
+}
+
+/// Creates a random, hard to guess seat token.
+fn new_seat_token() -> String {
+    format!(
+        "{:016x}{:016x}",
+        rand::random::<u64>(),
+        rand::random::<u64>()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use web_time::Duration;
+
+    fn coords(index: u32) -> Coordinates {
+        Coordinates::from_index(index, 3)
+    }
+
+    #[test]
+    fn test_join_fills_seats_in_order() {
+        let mut session = GameSession::new(3);
+        let now = Instant::now();
+        let (first, first_token) = session.join("ana", None, now).unwrap();
+        let (second, second_token) = session.join("bea", None, now).unwrap();
+        assert_eq!(first, PlayerId::new(0));
+        assert_eq!(second, PlayerId::new(1));
+        assert_ne!(first_token, second_token);
+        assert!(session.is_ready());
+        assert!(matches!(
+            session.join("cid", None, now),
+            Err(GameYError::NoFreeSeat)
+        ));
+    }
+
+    #[test]
+    fn test_join_preferred_seat() {
+        let mut session = GameSession::new(3);
+        let now = Instant::now();
+        let (player, _) = session.join("ana", Some(PlayerId::new(1)), now).unwrap();
+        assert_eq!(player, PlayerId::new(1));
+        assert!(matches!(
+            session.join("bea", Some(PlayerId::new(1)), now),
+            Err(GameYError::SeatTaken { .. })
+        ));
+    }
+
+    #[test]
+    fn test_moves_need_both_seats_and_the_right_turn() {
+        let mut session = GameSession::new(3);
+        let now = Instant::now();
+        let (_, first) = session.join("ana", None, now).unwrap();
+        assert!(matches!(
+            session.place(&first, coords(0), now),
+            Err(GameYError::SessionNotReady)
+        ));
+        let (_, second) = session.join("bea", None, now).unwrap();
+        assert!(matches!(
+            session.place(&second, coords(0), now),
+            Err(GameYError::InvalidPlayerTurn { .. })
+        ));
+        session.place(&first, coords(0), now).unwrap();
+        session.place(&second, coords(4), now).unwrap();
+        assert!(matches!(
+            session.place("forged", coords(1), now),
+            Err(GameYError::UnknownSeatToken)
+        ));
+    }
+
+    #[test]
+    fn test_reconnect_keeps_seat() {
+        let mut session = GameSession::new(3);
+        let now = Instant::now();
+        let (player, token) = session.join("ana", None, now).unwrap();
+        assert_eq!(session.disconnect(&token), Some(player));
+        assert!(!session.seat(player).is_connected());
+        assert!(!session.seat(player).is_free());
+        assert_eq!(session.reconnect(&token).unwrap(), player);
+        assert!(session.seat(player).is_connected());
+    }
+
+    #[test]
+    fn test_bot_to_move() {
+        let mut session = GameSession::new(3);
+        let now = Instant::now();
+        session
+            .add_bot(PlayerId::new(1), "random_bot", now)
+            .unwrap();
+        assert_eq!(session.bot_to_move(), None);
+        let (_, token) = session.join("ana", None, now).unwrap();
+        session.place(&token, coords(0), now).unwrap();
+        assert_eq!(
+            session.bot_to_move(),
+            Some((PlayerId::new(1), "random_bot"))
+        );
+        session
+            .play_as_bot(PlayerId::new(1), coords(4), now)
+            .unwrap();
+        assert!(
+            session
+                .play_as_bot(PlayerId::new(0), coords(1), now)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_clock_is_enforced() {
+        let control = TimeControl {
+            initial_ms: 1000,
+            increment_ms: 0,
+        };
+        let mut session = GameSession::new(3).with_time_control(control);
+        let start = Instant::now();
+        let (_, first) = session.join("ana", None, start).unwrap();
+        let (_, second) = session.join("bea", None, start).unwrap();
+        session
+            .place(&first, coords(0), start + Duration::from_millis(400))
+            .unwrap();
+        let late = start + Duration::from_millis(1500);
+        assert!(matches!(
+            session.place(&second, coords(4), late),
+            Err(GameYError::TimeExpired { player }) if player == PlayerId::new(1)
+        ));
+        let result = session.game().result().unwrap();
+        assert_eq!(result.winner, PlayerId::new(0));
+        assert_eq!(result.reason, GameOverReason::Timeout);
+        assert_eq!(session.check_time(late), None);
+    }
+
+    #[test]
+    fn test_resign_out_of_turn() {
+        let mut session = GameSession::new(3);
+        let now = Instant::now();
+        session.join("ana", None, now).unwrap();
+        let (_, second) = session.join("bea", None, now).unwrap();
+        session.act(&second, GameAction::Resign, now).unwrap();
+        assert_eq!(session.game().result().unwrap().winner, PlayerId::new(0));
+    }
+
+    #[test]
+    fn test_spectators_follow_moves_clocks_and_evaluations() {
+        let control = TimeControl {
+            initial_ms: 1000,
+            increment_ms: 0,
+        };
+        let mut session = GameSession::new(3).with_time_control(control);
+        let events = session.spectate();
+        let also = session.spectate();
+        let start = Instant::now();
+        let (_, first) = session.join("ana", None, start).unwrap();
+        session.join("bea", None, start).unwrap();
+        session.place(&first, coords(0), start).unwrap();
+        session.publish_evaluation(PlayerId::new(1), -30);
+        session.act(&first, GameAction::Resign, start).unwrap();
+
+        let events: Vec<SpectatorEvent> = events.try_iter().collect();
+        let kinds: Vec<&str> = events
+            .iter()
+            .map(|event| match event {
+                SpectatorEvent::Game(crate::GameEvent::StonePlaced { .. }) => "stone",
+                SpectatorEvent::Game(crate::GameEvent::GameOver { .. }) => "over",
+                SpectatorEvent::Game(_) => "game",
+                SpectatorEvent::Clock(_) => "clock",
+                SpectatorEvent::Evaluation { .. } => "evaluation",
+                SpectatorEvent::Proposed { .. } | SpectatorEvent::Answered { .. } => "proposal",
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec!["clock", "stone", "clock", "evaluation", "over", "clock"]
+        );
+        let SpectatorEvent::Clock(snapshot) = &events[2] else {
+            panic!("expected a clock update");
+        };
+        assert_eq!(snapshot.running, Some(PlayerId::new(1)));
+        assert_eq!(also.try_iter().count(), events.len());
+
+        // Copies of the session have spectators of their own
+        let watching = session.spectate();
+        let copy = session.clone();
+        copy.publish_evaluation(PlayerId::new(0), 0);
+        assert!(watching.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_accepted_takeback_rewinds_moves_and_clock() {
+        let control = TimeControl {
+            initial_ms: 10_000,
+            increment_ms: 0,
+        };
+        let mut session = GameSession::new(3).with_time_control(control);
+        let start = Instant::now();
+        let (_, first) = session.join("ana", None, start).unwrap();
+        let (_, second) = session.join("bea", None, start).unwrap();
+        let later = |ms| start + Duration::from_millis(ms);
+        session.place(&first, coords(0), later(1000)).unwrap();
+        session.place(&second, coords(4), later(3000)).unwrap();
+        let events = session.spectate();
+
+        assert!(
+            !session
+                .propose(&first, Proposal::Takeback, later(4000))
+                .unwrap()
+        );
+        assert_eq!(
+            session.proposal(),
+            Some((PlayerId::new(0), Proposal::Takeback))
+        );
+        assert_eq!(
+            session.answer(&second, true, later(5000)).unwrap(),
+            Proposal::Takeback
+        );
+        assert_eq!(session.proposal(), None);
+        assert!(session.game().history().is_empty());
+        let clock = session.clock().unwrap();
+        assert_eq!(clock.running(), Some(PlayerId::new(0)));
+        assert_eq!(clock.remaining_ms(PlayerId::new(0), later(5000)), 10_000);
+        assert_eq!(clock.remaining_ms(PlayerId::new(1), later(5000)), 10_000);
+        let events: Vec<SpectatorEvent> = events.try_iter().collect();
+        assert!(matches!(
+            events[1],
+            SpectatorEvent::Answered { accepted: true, .. }
+        ));
+        assert!(matches!(events.last(), Some(SpectatorEvent::Clock(_))));
+
+        // The second player takes back their own move only
+        session.place(&first, coords(0), later(6000)).unwrap();
+        session.place(&second, coords(4), later(7000)).unwrap();
+        session
+            .propose(&second, Proposal::Takeback, later(7000))
+            .unwrap();
+        session.answer(&first, true, later(8000)).unwrap();
+        assert_eq!(session.game().history().len(), 1);
+        assert_eq!(session.clock().unwrap().running(), Some(PlayerId::new(1)));
+        assert_eq!(
+            session
+                .clock()
+                .unwrap()
+                .remaining_ms(PlayerId::new(0), later(8000)),
+            9000
+        );
+    }
+
+    #[test]
+    fn test_declined_and_lapsed_proposals_change_nothing() {
+        let mut session = GameSession::new(3);
+        let now = Instant::now();
+        let (_, first) = session.join("ana", None, now).unwrap();
+        let (_, second) = session.join("bea", None, now).unwrap();
+        session.place(&first, coords(0), now).unwrap();
+        session.propose(&first, Proposal::Takeback, now).unwrap();
+        assert!(matches!(
+            session.propose(&second, Proposal::Abort, now),
+            Err(GameYError::ProposalPending { player }) if player == PlayerId::new(0)
+        ));
+        assert!(matches!(
+            session.answer(&first, true, now),
+            Err(GameYError::NoProposal)
+        ));
+        session.answer(&second, false, now).unwrap();
+        assert_eq!(session.game().history().len(), 1);
+
+        session.propose(&first, Proposal::Abort, now).unwrap();
+        session.place(&second, coords(4), now).unwrap();
+        assert_eq!(session.proposal(), None);
+        assert!(matches!(
+            session.answer(&second, true, now),
+            Err(GameYError::NoProposal)
+        ));
+        assert!(!session.is_aborted());
+    }
+
+    #[test]
+    fn test_invalid_proposals_are_rejected() {
+        let mut session = GameSession::new(3);
+        let now = Instant::now();
+        let (_, first) = session.join("ana", None, now).unwrap();
+        assert!(matches!(
+            session.propose(&first, Proposal::Abort, now),
+            Err(GameYError::SessionNotReady)
+        ));
+        let (_, second) = session.join("bea", None, now).unwrap();
+        assert!(matches!(
+            session.propose(&first, Proposal::Takeback, now),
+            Err(GameYError::NoMoveToUndo)
+        ));
+        assert!(matches!(
+            session.propose("forged", Proposal::Abort, now),
+            Err(GameYError::UnknownSeatToken)
+        ));
+        session.act(&second, GameAction::Resign, now).unwrap();
+        assert!(matches!(
+            session.propose(&first, Proposal::Abort, now),
+            Err(GameYError::SessionOver)
+        ));
+    }
+
+    #[test]
+    fn test_aborted_games_stop_for_good() {
+        let control = TimeControl {
+            initial_ms: 1000,
+            increment_ms: 0,
+        };
+        let mut session = GameSession::new(3).with_time_control(control);
+        let now = Instant::now();
+        let (_, first) = session.join("ana", None, now).unwrap();
+        let (_, second) = session.join("bea", None, now).unwrap();
+        session.propose(&second, Proposal::Abort, now).unwrap();
+        session.answer(&first, true, now).unwrap();
+        assert!(session.is_aborted());
+        assert_eq!(session.clock().unwrap().running(), None);
+        assert!(session.game().result().is_none());
+        assert!(matches!(
+            session.place(&first, coords(0), now),
+            Err(GameYError::SessionOver)
+        ));
+        assert!(matches!(
+            session.act(&second, GameAction::Resign, now),
+            Err(GameYError::SessionOver)
+        ));
+        assert!(session.clone().is_aborted());
+    }
+
+    #[test]
+    fn test_bots_accept_proposals_at_once() {
+        let mut session = GameSession::new(3);
+        let now = Instant::now();
+        session
+            .add_bot(PlayerId::new(1), "random_bot", now)
+            .unwrap();
+        let (_, token) = session.join("ana", None, now).unwrap();
+        session.place(&token, coords(0), now).unwrap();
+        session
+            .play_as_bot(PlayerId::new(1), coords(4), now)
+            .unwrap();
+        assert!(session.propose(&token, Proposal::Takeback, now).unwrap());
+        assert!(session.game().history().is_empty());
+        assert_eq!(session.proposal(), None);
+        assert_eq!(session.bot_to_move(), None);
+        assert!(session.propose(&token, Proposal::Abort, now).unwrap());
+        assert!(session.is_aborted());
+        assert_eq!(session.bot_to_move(), None);
+    }
+}
@@ -0,0 +1,18 @@
+//! Game sessions: seats, turns and clocks around a [`GameY`](crate::GameY).
+//!
+//! This module holds the transport-independent part of online play:
+//! - [`GameSession`]: Two seats, turn enforcement, reconnection, and
+//!   takebacks or aborts agreed as a [`Proposal`]
+//! - [`GameClock`] and [`TimeControl`]: Server-side chess-style clocks
+//! - [`SpectatorHub`]: [`SpectatorEvent`]s of a game sent to any number of
+//!   subscribers
+//!
+//! The WebSocket rooms of the game server are built on it.
+
+pub mod game_clock;
+pub mod game_session;
+pub mod spectators;
+
+pub use game_clock::*;
+pub use game_session::*;
+pub use spectators::*;
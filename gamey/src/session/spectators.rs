@@ -0,0 +1,136 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::{ClockSnapshot, GameEvent, GameObserver, PlayerId, Proposal};
+
+/// Something that happened in a game, as seen by its spectators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SpectatorEvent {
+    /// A change to the game: a move, a takeback or the end of the game.
+    Game(GameEvent),
+    /// The clocks were started, pressed or stopped.
+    Clock(ClockSnapshot),
+    /// An engine evaluated the current position.
+    Evaluation {
+        /// The player the score is for.
+        perspective: PlayerId,
+        /// The score, as returned by the minimax search.
+        score: i32,
+    },
+    /// A player made a proposal to their opponent.
+    Proposed {
+        /// The player who made the proposal.
+        player: PlayerId,
+        /// What they proposed.
+        proposal: Proposal,
+    },
+    /// A proposal was accepted or declined. An accepted takeback is
+    /// followed by the moves it undid and the rewound clocks.
+    Answered {
+        /// The player who answered.
+        player: PlayerId,
+        /// The proposal they answered.
+        proposal: Proposal,
+        /// Whether they accepted it.
+        accepted: bool,
+    },
+}
+
+/// Sends the events of a game to any number of subscribers.
+///
+/// Every subscriber receives every event published after it subscribed, on
+/// its own channel, so it can be consumed from another thread at its own
+/// pace. Subscribers whose receiver was dropped are forgotten. Clones of a
+/// hub share its subscribers.
+///
+/// A hub is also a [`GameObserver`], to follow a [`GameY`](crate::GameY)
+/// without a session.
+///
+/// # Example
+///
+/// ```
+/// use gamey::{Coordinates, GameEvent, GameY, Movement, PlayerId, SpectatorEvent, SpectatorHub};
+///
+/// let hub = SpectatorHub::new();
+/// let first = hub.subscribe();
+/// let second = hub.subscribe();
+/// let mut game = GameY::new(3);
+/// game.add_observer(hub.clone());
+/// game.add_move(Movement::Placement {
+///     player: PlayerId::new(0),
+///     coords: Coordinates::new(2, 0, 0),
+/// })
+/// .unwrap();
+/// for events in [first, second] {
+///     assert!(matches!(
+///         events.try_recv(),
+///         Ok(SpectatorEvent::Game(GameEvent::StonePlaced { .. }))
+///     ));
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SpectatorHub {
+    subscribers: Arc<Mutex<Vec<Sender<SpectatorEvent>>>>,
+}
+
+impl SpectatorHub {
+    /// Creates a hub with no subscribers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a receiver for the events published from now on.
+    pub fn subscribe(&self) -> Receiver<SpectatorEvent> {
+        let (sender, receiver) = channel();
+        self.lock().push(sender);
+        receiver
+    }
+
+    /// Sends `event` to every subscriber.
+    pub fn publish(&self, event: SpectatorEvent) {
+        self.lock()
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    /// Returns the number of subscribers still listening, as of the last
+    /// event published.
+    pub fn subscribers(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Sender<SpectatorEvent>>> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl GameObserver for SpectatorHub {
+    fn on_event(&mut self, event: &GameEvent) {
+        self.publish(SpectatorEvent::Game(event.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropped_subscribers_are_forgotten() {
+        let hub = SpectatorHub::new();
+        let kept = hub.subscribe();
+        drop(hub.subscribe());
+        assert_eq!(hub.subscribers(), 2);
+        let event = SpectatorEvent::Evaluation {
+            perspective: PlayerId::new(1),
+            score: 40,
+        };
+        hub.clone().publish(event.clone());
+        assert_eq!(hub.subscribers(), 1);
+        assert_eq!(kept.try_recv(), Ok(event));
+    }
+}
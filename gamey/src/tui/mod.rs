@@ -0,0 +1,43 @@
+//! Full-screen terminal interface, built on [ratatui](https://ratatui.rs).
+//!
+//! Enabled by the `tui` feature and used by the `gamey-tui` binary. The
+//! screen shows the triangular board with a cursor, the clocks, a live
+//! analysis panel with the engine's evaluation and principal variation, and
+//! the list of moves.
+//!
+//! - [`TuiApp`]: State and key handling, usable without a terminal
+//! - [`draw`]: Renders a [`TuiApp`] into a ratatui frame
+
+pub mod tui_app;
+pub mod tui_view;
+
+pub use tui_app::*;
+pub use tui_view::*;
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use std::time::Duration;
+use web_time::Instant;
+
+/// How long to wait for a key before refreshing clocks and analysis.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Runs the terminal interface until the user quits.
+///
+/// Takes over the terminal (raw mode, alternate screen) and restores it on
+/// return, including when an error occurs.
+pub fn run_tui(mut app: TuiApp) -> std::io::Result<()> {
+    app.start(Instant::now());
+    ratatui::run(|terminal| {
+        while !app.should_quit() {
+            terminal.draw(|frame| draw(frame, &app))?;
+            if event::poll(REFRESH_INTERVAL)?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                app.handle_key(key.code, Instant::now());
+            }
+            app.tick(Instant::now());
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,530 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread::{self, JoinHandle};
+
+use clap::Parser;
+use ratatui::crossterm::event::KeyCode;
+use web_time::Instant;
+
+use crate::{
+    Coordinates, GameClock, GameOverReason, GameY, MinimaxBot, MinimaxState, Movement, PlayerId,
+    RandomBot, SearchInfo, SearchLimits, TimeControl, YBot, YBotRegistry, search_with_limits,
+};
+
+/// Command-line arguments for `gamey-tui`.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+#[command(long_about = "gamey-tui: a full-screen terminal interface for the Game of Y.")]
+pub struct TuiArgs {
+    /// Size of the triangular board (length of one side).
+    #[arg(short, long, default_value_t = 7)]
+    pub size: u32,
+
+    /// The bot to play against; two humans play if omitted.
+    #[arg(short, long)]
+    pub bot: Option<String>,
+
+    /// Let the bot play first.
+    #[arg(long, default_value_t = false)]
+    pub bot_first: bool,
+
+    /// Time in milliseconds the bot may think per move.
+    #[arg(long, default_value_t = 1000)]
+    pub bot_time_ms: u64,
+
+    /// Starting time on each clock, in seconds; no clocks if omitted.
+    #[arg(long)]
+    pub clock_secs: Option<u64>,
+
+    /// Seconds added to a clock after each move.
+    #[arg(long, default_value_t = 0)]
+    pub increment_secs: u64,
+}
+
+/// A message from the analysis thread.
+enum AnalysisUpdate {
+    Info(SearchInfo),
+    Done(Option<usize>),
+}
+
+/// A search analysing the current position on a background thread.
+struct Analysis {
+    stop: Arc<AtomicBool>,
+    updates: Receiver<AnalysisUpdate>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for Analysis {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// What the analysis panel shows.
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisReport {
+    /// The player the scores are given for.
+    pub player: Option<PlayerId>,
+    /// The last completed iteration, if any.
+    pub info: Option<SearchInfo>,
+    /// The move found when the search ended.
+    pub best_move: Option<usize>,
+    /// True while the search is running.
+    pub running: bool,
+}
+
+/// A bot move being computed on a background thread.
+struct PendingBotMove {
+    moves_played: usize,
+    reply: Receiver<Option<Coordinates>>,
+}
+
+/// State and key handling of the terminal interface.
+///
+/// The drawing lives in [`draw`](crate::tui::draw); this type only reacts to
+/// keys and to the passage of time, so it can be driven without a terminal.
+pub struct TuiApp {
+    game: GameY,
+    cursor: Coordinates,
+    time_control: Option<TimeControl>,
+    clock: Option<GameClock>,
+    bot: Option<(PlayerId, Arc<dyn YBot>)>,
+    bot_time_ms: u64,
+    pending_bot: Option<PendingBotMove>,
+    analysis_enabled: bool,
+    analysis: Option<Analysis>,
+    report: AnalysisReport,
+    message: String,
+    quit: bool,
+}
+
+impl TuiApp {
+    /// Creates the interface for a new game of the given size, between two
+    /// humans.
+    pub fn new(board_size: u32) -> Self {
+        Self {
+            game: GameY::new(board_size),
+            cursor: Coordinates::new(board_size - 1, 0, 0),
+            time_control: None,
+            clock: None,
+            bot: None,
+            bot_time_ms: 1000,
+            pending_bot: None,
+            analysis_enabled: false,
+            analysis: None,
+            report: AnalysisReport::default(),
+            message: "Arrows move, Enter plays, ? for help".to_string(),
+            quit: false,
+        }
+    }
+
+    /// Creates the interface described by the command-line arguments.
+    pub fn from_args(args: &TuiArgs) -> Result<Self, String> {
+        let mut app = TuiApp::new(args.size);
+        if let Some(bot_id) = &args.bot {
+            let bots = YBotRegistry::new()
+                .with_bot(Arc::new(RandomBot::new()))
+                .with_bot(Arc::new(MinimaxBot::new(args.bot_time_ms)));
+            let bot = bots.find(bot_id).ok_or_else(|| {
+                format!(
+                    "Bot '{}' not found. Available bots: {:?}",
+                    bot_id,
+                    bots.names()
+                )
+            })?;
+            let player = PlayerId::new(if args.bot_first { 0 } else { 1 });
+            app = app.with_bot(player, bot, args.bot_time_ms);
+        }
+        if let Some(secs) = args.clock_secs {
+            app = app.with_time_control(TimeControl {
+                initial_ms: secs * 1000,
+                increment_ms: args.increment_secs * 1000,
+            });
+        }
+        Ok(app)
+    }
+
+    /// Lets `bot` play as `player`, thinking for about `time_ms` per move.
+    pub fn with_bot(mut self, player: PlayerId, bot: Arc<dyn YBot>, time_ms: u64) -> Self {
+        self.bot = Some((player, bot));
+        self.bot_time_ms = time_ms;
+        self
+    }
+
+    /// Adds clocks with the given time control.
+    pub fn with_time_control(mut self, control: TimeControl) -> Self {
+        self.time_control = Some(control);
+        self
+    }
+
+    /// Starts the clocks and, if it is to move, the bot.
+    pub fn start(&mut self, now: Instant) {
+        self.clock = self.time_control.map(GameClock::new);
+        if let (Some(clock), Some(next)) = (&mut self.clock, self.game.next_player()) {
+            clock.start(next, now);
+        }
+        self.position_changed(now);
+    }
+
+    /// Returns the game being played.
+    pub fn game(&self) -> &GameY {
+        &self.game
+    }
+
+    /// Returns the cell under the cursor.
+    pub fn cursor(&self) -> Coordinates {
+        self.cursor
+    }
+
+    /// Returns the clocks, if the game has them.
+    pub fn clock(&self) -> Option<&GameClock> {
+        self.clock.as_ref()
+    }
+
+    /// Returns the player the bot plays as, if there is a bot.
+    pub fn bot_player(&self) -> Option<PlayerId> {
+        self.bot.as_ref().map(|(player, _)| *player)
+    }
+
+    /// Returns true while the bot is thinking.
+    pub fn is_bot_thinking(&self) -> bool {
+        self.pending_bot.is_some()
+    }
+
+    /// Returns true if the analysis panel is enabled.
+    pub fn is_analysis_enabled(&self) -> bool {
+        self.analysis_enabled
+    }
+
+    /// Returns what the analysis panel shows.
+    pub fn analysis(&self) -> &AnalysisReport {
+        &self.report
+    }
+
+    /// Returns the status line message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns true once the user asked to quit.
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Reacts to a key press.
+    pub fn handle_key(&mut self, key: KeyCode, now: Instant) {
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1, 0),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(0, -1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(0, 1),
+            KeyCode::Enter | KeyCode::Char(' ') => self.play_cursor(now),
+            KeyCode::Char('u') => self.undo(now),
+            KeyCode::Char('a') => self.toggle_analysis(now),
+            KeyCode::Char('n') => self.new_game(now),
+            KeyCode::Char('?') => {
+                self.message =
+                    "Arrows/hjkl move, Enter play, u undo, a analysis, n new game, q quit"
+                        .to_string()
+            }
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            _ => {}
+        }
+    }
+
+    /// Advances background work: collects analysis reports and the bot's
+    /// move, and ends the game when a clock runs out.
+    pub fn tick(&mut self, now: Instant) {
+        self.poll_analysis();
+        self.poll_bot(now);
+        self.check_time(now);
+    }
+
+    fn move_cursor(&mut self, rows: i64, cells: i64) {
+        let size = self.game.board_size() as i64;
+        let row = (size - 1 - self.cursor.x() as i64 + rows).clamp(0, size - 1);
+        let y = (self.cursor.y() as i64 + cells).clamp(0, row);
+        self.cursor = Coordinates::new((size - 1 - row) as u32, y as u32, (row - y) as u32);
+    }
+
+    fn play_cursor(&mut self, now: Instant) {
+        let Some(player) = self.game.next_player() else {
+            self.message = "The game is over: n starts a new one".to_string();
+            return;
+        };
+        if self.bot_player() == Some(player) {
+            self.message = "Wait for the bot to move".to_string();
+            return;
+        }
+        self.play(player, self.cursor, now);
+    }
+
+    fn play(&mut self, player: PlayerId, coords: Coordinates, now: Instant) {
+        let movement = Movement::Placement { player, coords };
+        match self.game.add_move(movement) {
+            Ok(()) => {
+                self.message = format!(
+                    "Player {} played {}",
+                    player,
+                    coords.to_cell_name(self.game.board_size())
+                );
+                if let Some(clock) = &mut self.clock {
+                    let _ = clock.press(self.game.next_player(), now);
+                }
+                self.position_changed(now);
+            }
+            Err(e) => self.message = e.to_string(),
+        }
+    }
+
+    /// Takes back moves until a human is to move, so that against a bot the
+    /// bot's reply goes with the human move.
+    fn undo(&mut self, now: Instant) {
+        self.pending_bot = None;
+        let mut undone = 0;
+        while self.game.undo_move().is_ok() {
+            undone += 1;
+            let next = self.game.next_player();
+            if next != self.bot_player() || self.game.history().is_empty() {
+                break;
+            }
+        }
+        self.message = format!("Took back {} move(s)", undone);
+        if let (Some(clock), Some(next)) = (&mut self.clock, self.game.next_player()) {
+            clock.start(next, now);
+        }
+        self.position_changed(now);
+    }
+
+    fn new_game(&mut self, now: Instant) {
+        self.game = GameY::new(self.game.board_size());
+        self.pending_bot = None;
+        self.message = "New game".to_string();
+        self.start(now);
+    }
+
+    fn toggle_analysis(&mut self, now: Instant) {
+        self.analysis_enabled = !self.analysis_enabled;
+        self.position_changed(now);
+    }
+
+    fn check_time(&mut self, now: Instant) {
+        let Some(player) = self.clock.as_ref().and_then(|clock| clock.flagged(now)) else {
+            return;
+        };
+        if let Some(clock) = &mut self.clock {
+            clock.stop(now);
+        }
+        if self.game.forfeit(player, GameOverReason::Timeout).is_ok() {
+            self.message = format!("Player {} ran out of time", player);
+            self.position_changed(now);
+        }
+    }
+
+    /// Restarts the analysis and the bot after the position changed.
+    fn position_changed(&mut self, now: Instant) {
+        self.analysis = None;
+        self.report = AnalysisReport::default();
+        if self.game.check_game_over() {
+            self.pending_bot = None;
+            if let Some(clock) = &mut self.clock {
+                clock.stop(now);
+            }
+            if let Some(result) = self.game.result() {
+                self.message = format!("Player {} wins by {}", result.winner, result.reason);
+            }
+            return;
+        }
+        if self.analysis_enabled {
+            self.start_analysis();
+        }
+        self.start_bot();
+    }
+
+    fn start_analysis(&mut self) {
+        let Some(player) = self.game.next_player() else {
+            return;
+        };
+        let mut state = MinimaxState::new(&self.game, player);
+        // Deeper iterations than there are empty cells would search the same tree
+        let empty_cells = self.game.available_cells().len();
+        let limits = SearchLimits {
+            max_depth: Some(empty_cells.min(u8::MAX as usize) as u8),
+            ..SearchLimits::default()
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, updates) = mpsc::channel();
+        let thread_stop = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            let best = search_with_limits(&mut state, &limits, &thread_stop, &mut |info| {
+                let _ = sender.send(AnalysisUpdate::Info(info.clone()));
+            });
+            let _ = sender.send(AnalysisUpdate::Done(best));
+        });
+        self.report = AnalysisReport {
+            player: Some(player),
+            running: true,
+            ..AnalysisReport::default()
+        };
+        self.analysis = Some(Analysis {
+            stop,
+            updates,
+            handle: Some(handle),
+        });
+    }
+
+    fn poll_analysis(&mut self) {
+        let Some(analysis) = &self.analysis else {
+            return;
+        };
+        loop {
+            match analysis.updates.try_recv() {
+                Ok(AnalysisUpdate::Info(info)) => {
+                    self.report.best_move = info.pv.first().copied();
+                    self.report.info = Some(info);
+                }
+                Ok(AnalysisUpdate::Done(best)) => {
+                    self.report.best_move = best.or(self.report.best_move);
+                    self.report.running = false;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.report.running = false;
+                    break;
+                }
+            }
+        }
+    }
+
+    fn start_bot(&mut self) {
+        let Some((player, bot)) = &self.bot else {
+            return;
+        };
+        if self.game.next_player() != Some(*player) {
+            return;
+        }
+        let (sender, reply) = mpsc::channel();
+        let (bot, game, time_ms) = (Arc::clone(bot), self.game.clone(), self.bot_time_ms);
+        thread::spawn(move || {
+            let _ = sender.send(bot.choose_move_within(&game, time_ms));
+        });
+        self.pending_bot = Some(PendingBotMove {
+            moves_played: self.game.history().len(),
+            reply,
+        });
+    }
+
+    fn poll_bot(&mut self, now: Instant) {
+        let Some(pending) = &self.pending_bot else {
+            return;
+        };
+        let choice = match pending.reply.try_recv() {
+            Ok(choice) => choice,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => None,
+        };
+        let moves_played = pending.moves_played;
+        self.pending_bot = None;
+        match (choice, self.bot_player()) {
+            (Some(coords), Some(player)) if self.game.history().len() == moves_played => {
+                self.play(player, coords, now);
+            }
+            (None, _) => self.message = "The bot has no move to play".to_string(),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn wait_until(app: &mut TuiApp, done: impl Fn(&TuiApp) -> bool) {
+        let start = Instant::now();
+        while !done(app) {
+            assert!(start.elapsed() < Duration::from_secs(10), "timed out");
+            thread::sleep(Duration::from_millis(5));
+            app.tick(Instant::now());
+        }
+    }
+
+    #[test]
+    fn test_cursor_stays_on_board() {
+        let mut app = TuiApp::new(3);
+        let now = Instant::now();
+        app.handle_key(KeyCode::Up, now);
+        assert_eq!(app.cursor(), Coordinates::new(2, 0, 0));
+        app.handle_key(KeyCode::Down, now);
+        app.handle_key(KeyCode::Down, now);
+        app.handle_key(KeyCode::Right, now);
+        app.handle_key(KeyCode::Right, now);
+        app.handle_key(KeyCode::Right, now);
+        assert_eq!(app.cursor(), Coordinates::new(0, 2, 0));
+        app.handle_key(KeyCode::Up, now);
+        assert_eq!(app.cursor(), Coordinates::new(1, 1, 0));
+    }
+
+    #[test]
+    fn test_enter_plays_and_presses_clock() {
+        let start = Instant::now();
+        let mut app = TuiApp::new(3).with_time_control(TimeControl {
+            initial_ms: 10_000,
+            increment_ms: 0,
+        });
+        app.start(start);
+        app.handle_key(KeyCode::Enter, start + Duration::from_millis(300));
+        assert_eq!(app.game().history().len(), 1);
+        let clock = app.clock().unwrap();
+        assert_eq!(clock.running(), Some(PlayerId::new(1)));
+        assert_eq!(
+            clock.remaining_ms(PlayerId::new(0), start + Duration::from_secs(1)),
+            9700
+        );
+        app.handle_key(KeyCode::Enter, start);
+        assert!(app.message().contains("occupied"));
+    }
+
+    #[test]
+    fn test_clock_runs_out() {
+        let start = Instant::now();
+        let mut app = TuiApp::new(3).with_time_control(TimeControl {
+            initial_ms: 100,
+            increment_ms: 0,
+        });
+        app.start(start);
+        app.tick(start + Duration::from_millis(200));
+        let result = app.game().result().unwrap();
+        assert_eq!(result.winner, PlayerId::new(1));
+        assert_eq!(result.reason, GameOverReason::Timeout);
+    }
+
+    #[test]
+    fn test_bot_replies_and_undo_takes_back_both() {
+        let mut app = TuiApp::new(4).with_bot(PlayerId::new(1), Arc::new(RandomBot::new()), 0);
+        app.start(Instant::now());
+        app.handle_key(KeyCode::Enter, Instant::now());
+        wait_until(&mut app, |app| app.game().history().len() == 2);
+        assert!(!app.is_bot_thinking());
+
+        app.handle_key(KeyCode::Char('u'), Instant::now());
+        assert!(app.game().history().is_empty());
+        assert!(!app.is_bot_thinking());
+    }
+
+    #[test]
+    fn test_analysis_reports_best_move() {
+        let mut app = TuiApp::new(4);
+        app.start(Instant::now());
+        app.handle_key(KeyCode::Char('a'), Instant::now());
+        assert!(app.is_analysis_enabled());
+        assert_eq!(app.analysis().player, Some(PlayerId::new(0)));
+        wait_until(&mut app, |app| !app.analysis().running);
+        assert!(app.analysis().best_move.is_some());
+        assert!(app.analysis().info.is_some());
+    }
+}
@@ -0,0 +1,275 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Wrap},
+};
+use web_time::Instant;
+
+use crate::{Coordinates, Movement, PlayerId, TuiApp};
+
+/// Width of the side panel with clocks, analysis and moves.
+const SIDE_PANEL_WIDTH: u16 = 34;
+
+/// Draws the whole interface: the board, the side panel and the status line.
+pub fn draw(frame: &mut Frame, app: &TuiApp) {
+    let [main, status] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+    let [board, side] =
+        Layout::horizontal([Constraint::Min(0), Constraint::Length(SIDE_PANEL_WIDTH)]).areas(main);
+    let [clocks, analysis, moves] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Length(8),
+        Constraint::Min(3),
+    ])
+    .areas(side);
+
+    draw_board(frame, app, board);
+    draw_clocks(frame, app, clocks);
+    draw_analysis(frame, app, analysis);
+    draw_moves(frame, app, moves);
+    frame.render_widget(
+        Paragraph::new(app.message()).style(Style::new().add_modifier(Modifier::DIM)),
+        status,
+    );
+}
+
+fn player_color(player: PlayerId) -> Color {
+    if player.id() == 0 {
+        Color::Blue
+    } else {
+        Color::Red
+    }
+}
+
+fn player_name(app: &TuiApp, player: PlayerId) -> String {
+    if app.bot_player() == Some(player) {
+        format!("Player {} (bot)", player)
+    } else {
+        format!("Player {}", player)
+    }
+}
+
+fn cell_name(index: usize, board_size: u32) -> String {
+    Coordinates::from_index(index as u32, board_size).to_cell_name(board_size)
+}
+
+/// Formats milliseconds as `m:ss.t`.
+fn format_clock(ms: u64) -> String {
+    format!("{}:{:02}.{}", ms / 60_000, ms / 1000 % 60, ms / 100 % 10)
+}
+
+fn draw_board(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let game = app.game();
+    let size = game.board_size();
+    let label_width = Coordinates::new(0, 0, size - 1).to_cell_name(size).len() - 1;
+    let last = match game.history().last() {
+        Some(Movement::Placement { coords, .. }) => Some(*coords),
+        _ => None,
+    };
+
+    let mut lines = vec![Line::default()];
+    for row in 0..size {
+        let label = Coordinates::new(size - 1 - row, 0, row).to_cell_name(size);
+        let mut spans = vec![
+            Span::raw(format!(
+                "{:>w$} ",
+                &label[..label.len() - 1],
+                w = label_width
+            )),
+            Span::raw(" ".repeat(2 * (size - 1 - row) as usize)),
+        ];
+        for coords in Coordinates::iter_row(row, size) {
+            let (symbol, mut style) = match game.cell_owner(&coords) {
+                Some(player) => ("●", Style::new().fg(player_color(player))),
+                None => ("·", Style::new().add_modifier(Modifier::DIM)),
+            };
+            if Some(coords) == last {
+                style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+            }
+            if coords == app.cursor() {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            spans.push(Span::styled(format!(" {} ", symbol), style));
+            spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(spans));
+    }
+    let numbers: String = (1..=size).map(|column| format!("{:^4}", column)).collect();
+    lines.push(
+        Line::from(format!("{:w$} {}", "", numbers, w = label_width))
+            .style(Style::new().add_modifier(Modifier::DIM)),
+    );
+    lines.push(Line::default());
+    lines.push(Line::from(format!(
+        "Cursor: {}",
+        app.cursor().to_cell_name(size)
+    )));
+
+    let status = match (game.result(), game.next_player()) {
+        (Some(result), _) => format!(
+            "{} wins by {}",
+            player_name(app, result.winner),
+            result.reason
+        ),
+        (None, Some(player)) if app.is_bot_thinking() => {
+            format!("{} is thinking...", player_name(app, player))
+        }
+        (None, Some(player)) => format!("{} to move", player_name(app, player)),
+        (None, None) => String::new(),
+    };
+    let block = Block::bordered()
+        .title(format!(" Game of Y (size {}) ", size))
+        .title_bottom(format!(" {} ", status));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_clocks(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let now = Instant::now();
+    let next = app.game().next_player();
+    let lines: Vec<Line> = (0..2)
+        .map(PlayerId::new)
+        .map(|player| {
+            let marker = if next == Some(player) { "▶ " } else { "  " };
+            let time = app.clock().map_or(String::new(), |clock| {
+                format_clock(clock.remaining_ms(player, now))
+            });
+            Line::from(vec![
+                Span::raw(marker),
+                Span::styled("● ", Style::new().fg(player_color(player))),
+                Span::raw(format!("{:<18}{:>8}", player_name(app, player), time)),
+            ])
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Clocks ")),
+        area,
+    );
+}
+
+fn draw_analysis(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let size = app.game().board_size();
+    let report = app.analysis();
+    let lines = if !app.is_analysis_enabled() {
+        vec![Line::from("Press a to analyse")]
+    } else if report.player.is_none() {
+        vec![Line::from("Nothing to analyse")]
+    } else {
+        let mut lines = Vec::new();
+        match &report.info {
+            Some(info) => {
+                lines.push(Line::from(format!(
+                    "Depth {}  Score {:+}",
+                    info.depth, info.score
+                )));
+                lines.push(Line::from(format!(
+                    "{} nodes in {} ms",
+                    info.nodes, info.elapsed_ms
+                )));
+                if let Some(plies) = info.forced_win_in() {
+                    lines.push(Line::from(format!("Forced win in {} plies", plies)));
+                } else if let Some(plies) = info.forced_loss_in() {
+                    lines.push(Line::from(format!("Forced loss in {} plies", plies)));
+                }
+            }
+            None => lines.push(Line::from("Searching...")),
+        }
+        if let Some(best) = report.best_move {
+            lines.push(Line::from(format!("Best: {}", cell_name(best, size))));
+        }
+        if let Some(info) = &report.info {
+            let pv: Vec<String> = info.pv.iter().map(|&idx| cell_name(idx, size)).collect();
+            lines.push(Line::from(format!("PV: {}", pv.join(" "))));
+        }
+        lines
+    };
+    let title = if report.running {
+        " Analysis (searching) "
+    } else {
+        " Analysis "
+    };
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title(title)),
+        area,
+    );
+}
+
+fn draw_moves(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let size = app.game().board_size();
+    let history = app.game().history();
+    let lines: Vec<Line> = history
+        .iter()
+        .enumerate()
+        .map(|(number, movement)| {
+            let (player, text) = match movement {
+                Movement::Placement { player, coords } => (*player, coords.to_cell_name(size)),
+                Movement::Action { player, action } => (*player, action.to_string()),
+            };
+            Line::from(vec![
+                Span::raw(format!("{:>3}. ", number + 1)),
+                Span::styled("● ", Style::new().fg(player_color(player))),
+                Span::raw(text),
+            ])
+        })
+        .collect();
+    // Keep the latest moves in view
+    let visible = area.height.saturating_sub(2) as usize;
+    let lines = lines[lines.len().saturating_sub(visible)..].to_vec();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Moves ")),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    fn render(app: &TuiApp) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|frame| draw(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_format_clock() {
+        assert_eq!(format_clock(0), "0:00.0");
+        assert_eq!(format_clock(83_450), "1:23.4");
+    }
+
+    #[test]
+    fn test_draw_shows_board_and_panels() {
+        let mut app = TuiApp::new(4);
+        app.start(Instant::now());
+        let screen = render(&app);
+        assert!(screen.contains("Game of Y (size 4)"));
+        assert!(screen.contains("Player 0 to move"));
+        assert!(screen.contains("Press a to analyse"));
+        assert!(screen.contains("d  ·   ·   ·   ·"));
+        assert!(screen.contains("Cursor: a1"));
+    }
+
+    #[test]
+    fn test_draw_lists_moves() {
+        use ratatui::crossterm::event::KeyCode;
+        let mut app = TuiApp::new(4);
+        app.start(Instant::now());
+        app.handle_key(KeyCode::Down, Instant::now());
+        app.handle_key(KeyCode::Enter, Instant::now());
+        let screen = render(&app);
+        assert!(screen.contains("1. ● b1"));
+        assert!(screen.contains("Player 1 to move"));
+    }
+}
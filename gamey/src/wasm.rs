@@ -0,0 +1,224 @@
+//! WebAssembly bindings for running the engine in a browser.
+//!
+//! Enabled by the `wasm` feature. [`WasmGame`] wraps a [`GameY`] with a
+//! JavaScript-friendly API: cells are board indices, players are numbers and
+//! errors are thrown as JavaScript `Error`s.
+//!
+//! ```js
+//! import init, { WasmGame } from "./pkg/gamey.js";
+//!
+//! await init();
+//! const game = new WasmGame(7);
+//! game.play(12);
+//! const reply = game.chooseMove(500);
+//! if (reply !== undefined) game.play(reply);
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Coordinates, GameAction, GameY, GameYError, MinimaxBot, Movement, PlayerId, YBot};
+
+/// A game of Y exposed to JavaScript.
+#[wasm_bindgen]
+pub struct WasmGame {
+    game: GameY,
+}
+
+#[wasm_bindgen]
+impl WasmGame {
+    /// Creates a game with an empty board of the given size.
+    #[wasm_bindgen(constructor)]
+    pub fn new(board_size: u32) -> WasmGame {
+        WasmGame {
+            game: GameY::new(board_size),
+        }
+    }
+
+    /// Restores a game from a position string such as `1/2/3 B`.
+    #[wasm_bindgen(js_name = fromPositionString)]
+    pub fn from_position_string(position: &str) -> Result<WasmGame, JsError> {
+        let game = GameY::from_position_string(position).map_err(to_js_error)?;
+        Ok(WasmGame { game })
+    }
+
+    /// Encodes the position as a position string.
+    #[wasm_bindgen(js_name = toPositionString)]
+    pub fn to_position_string(&self) -> String {
+        self.game.to_position_string()
+    }
+
+    /// Returns the size of the board.
+    #[wasm_bindgen(js_name = boardSize)]
+    pub fn board_size(&self) -> u32 {
+        self.game.board_size()
+    }
+
+    /// Returns the number of cells on the board.
+    #[wasm_bindgen(js_name = totalCells)]
+    pub fn total_cells(&self) -> u32 {
+        self.game.total_cells()
+    }
+
+    /// Returns the owner of every cell, by board index: 0 or 1 for a stone,
+    /// -1 for an empty cell.
+    pub fn cells(&self) -> Vec<i32> {
+        let size = self.game.board_size();
+        (0..self.game.total_cells())
+            .map(|idx| {
+                self.game
+                    .cell_owner(&Coordinates::from_index(idx, size))
+                    .map_or(-1, |player| player.id() as i32)
+            })
+            .collect()
+    }
+
+    /// Returns the indices of the cells the next player may play.
+    #[wasm_bindgen(js_name = legalMoves)]
+    pub fn legal_moves(&self) -> Vec<u32> {
+        if self.game.check_game_over() {
+            return Vec::new();
+        }
+        self.game.available_cells().clone()
+    }
+
+    /// Returns the player to move, or undefined if the game is over.
+    #[wasm_bindgen(js_name = nextPlayer)]
+    pub fn next_player(&self) -> Option<u32> {
+        self.game.next_player().map(|player| player.id())
+    }
+
+    /// Returns true if the game is over.
+    #[wasm_bindgen(js_name = isOver)]
+    pub fn is_over(&self) -> bool {
+        self.game.check_game_over()
+    }
+
+    /// Returns the winner, or undefined while the game is ongoing.
+    pub fn winner(&self) -> Option<u32> {
+        self.game.result().map(|result| result.winner.id())
+    }
+
+    /// Returns the indices of the winning group, or an empty list if the
+    /// game was not won by connection.
+    #[wasm_bindgen(js_name = winningGroup)]
+    pub fn winning_group(&self) -> Vec<u32> {
+        let size = self.game.board_size();
+        self.game
+            .winning_group()
+            .unwrap_or_default()
+            .iter()
+            .map(|coords| coords.to_index(size))
+            .collect()
+    }
+
+    /// Returns the position as an SVG image, for example to embed in the page
+    /// with `innerHTML`.
+    #[wasm_bindgen(js_name = toSvg)]
+    pub fn to_svg(&self) -> String {
+        crate::render_svg(&self.game)
+    }
+
+    /// Places a stone of the player to move on the cell with the given index.
+    pub fn play(&mut self, index: u32) -> Result<(), JsError> {
+        self.place(index).map_err(to_js_error)
+    }
+
+    /// Swaps sides, as allowed by the swap rule.
+    pub fn swap(&mut self) -> Result<(), JsError> {
+        self.act(GameAction::Swap).map_err(to_js_error)
+    }
+
+    /// Resigns the game on behalf of the player to move.
+    pub fn resign(&mut self) -> Result<(), JsError> {
+        self.act(GameAction::Resign).map_err(to_js_error)
+    }
+
+    /// Takes back the last move.
+    pub fn undo(&mut self) -> Result<(), JsError> {
+        self.game.undo_move().map(|_| ()).map_err(to_js_error)
+    }
+
+    /// Asks the minimax bot for a move for the player to move, searching for
+    /// at most about `max_time_ms` milliseconds.
+    ///
+    /// Returns the index of the chosen cell without playing it, or undefined
+    /// if the game is over.
+    #[wasm_bindgen(js_name = chooseMove)]
+    pub fn choose_move(&self, max_time_ms: u32) -> Option<u32> {
+        MinimaxBot::new(max_time_ms as u64)
+            .choose_move(&self.game)
+            .map(|coords| coords.to_index(self.game.board_size()))
+    }
+}
+
+impl WasmGame {
+    fn place(&mut self, index: u32) -> Result<(), GameYError> {
+        let total_cells = self.game.total_cells();
+        if index >= total_cells {
+            return Err(GameYError::InvalidCellIndex { index, total_cells });
+        }
+        let player = self.player_to_move();
+        let coords = Coordinates::from_index(index, self.game.board_size());
+        self.game.add_move(Movement::Placement { player, coords })
+    }
+
+    fn act(&mut self, action: GameAction) -> Result<(), GameYError> {
+        let player = self.player_to_move();
+        self.game.add_move(Movement::Action { player, action })
+    }
+
+    /// Returns the player to move.
+    ///
+    /// Once the game is over any player will do: the move is rejected anyway.
+    fn player_to_move(&self) -> PlayerId {
+        self.game.next_player().unwrap_or(PlayerId::new(0))
+    }
+}
+
+fn to_js_error(error: GameYError) -> JsError {
+    JsError::new(&error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // JavaScript values cannot be created outside wasm32, so the tests stay
+    // on the paths that do not throw.
+
+    #[test]
+    fn test_play_until_win() {
+        let mut game = WasmGame::new(3);
+        assert_eq!(game.legal_moves(), vec![0, 1, 2, 3, 4, 5]);
+        for index in [0, 4, 1, 5, 3] {
+            game.place(index).unwrap();
+        }
+        assert!(game.is_over());
+        assert_eq!(game.winner(), Some(0));
+        assert_eq!(game.winning_group(), vec![0, 1, 3]);
+        assert!(game.legal_moves().is_empty());
+        assert_eq!(game.cells(), vec![0, 0, -1, 0, 1, 1]);
+        assert!(game.place(2).is_err());
+    }
+
+    #[test]
+    fn test_choose_move_returns_a_legal_cell() {
+        let mut game = WasmGame::new(4);
+        game.place(4).unwrap();
+        let reply = game.choose_move(20).unwrap();
+        assert!(game.legal_moves().contains(&reply));
+        assert_eq!(game.next_player(), Some(1));
+    }
+
+    #[test]
+    fn test_position_string_round_trip() {
+        let mut game = WasmGame::new(3);
+        game.place(4).unwrap();
+        let position = game.to_position_string();
+        let restored = WasmGame {
+            game: GameY::from_position_string(&position).unwrap(),
+        };
+        assert_eq!(restored.cells(), game.cells());
+        assert_eq!(restored.next_player(), Some(1));
+    }
+}
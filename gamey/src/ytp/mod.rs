@@ -0,0 +1,31 @@
+//! A UCI-style stdio protocol for GUIs and analysis tools ("YTP").
+//!
+//! Where [`gtp`](crate::gtp) mimics the Go Text Protocol, YTP follows the
+//! conventions of UCI chess engines: the GUI sets up a position, starts a
+//! search with `go` and receives `info` lines while the engine thinks,
+//! followed by a `bestmove` line.
+//!
+//! - [`YtpCommand`]: parsing of the commands sent by the GUI
+//! - [`YtpEngine`]: the engine, searching on a background thread
+//!
+//! Cells are written as board indices, as in the CLI and GTP.
+//!
+//! # Commands
+//!
+//! - `ytp`: identify the engine, answered with `id` lines and `ytpok`
+//! - `isready`: answered with `readyok`
+//! - `newgame [size]`: start a new game
+//! - `position startpos [size <n>] [moves <m>...]`
+//! - `position string <layout> <side> [moves <m>...]`: start from a position
+//!   string (see [`GameY::to_position_string`](crate::GameY::to_position_string))
+//! - `go [movetime <ms>] [depth <d>] [nodes <n>] [infinite] [ponder]`
+//! - `stop`, `ponderhit`, `quit`
+//! - `show`, `eval`: analysis helpers printing the board and its static evaluation
+//!
+//! Each completed search iteration is reported as
+//! `info depth <d> score <s> nodes <n> time <ms> pv <cells>`.
+
+pub mod ytp_command;
+pub mod ytp_engine;
+pub use ytp_command::*;
+pub use ytp_engine::*;
@@ -0,0 +1,203 @@
+use crate::{MAX_ENGINE_BOARD_SIZE, SearchLimits};
+
+/// Where a `position` command starts from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PositionSetup {
+    /// An empty board. Without a size, the size of the current game is kept.
+    StartPos { size: Option<u32> },
+    /// A position string such as `1/2/3 B`.
+    PositionString(String),
+}
+
+/// Options of a `go` command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GoOptions {
+    /// Limits of the search.
+    pub limits: SearchLimits,
+    /// Search until `stop`, ignoring the limits.
+    pub infinite: bool,
+    /// Search on the opponent's time. The time limit only starts counting at
+    /// `ponderhit`, and no `bestmove` is sent before `ponderhit` or `stop`.
+    pub ponder: bool,
+}
+
+/// A command sent to a [`YtpEngine`](crate::YtpEngine).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum YtpCommand {
+    /// Asks the engine to identify itself.
+    Ytp,
+    /// Asks the engine to answer when it is ready.
+    IsReady,
+    /// Starts a new game, optionally on a board of a different size.
+    NewGame { size: Option<u32> },
+    /// Sets up a position and plays the given moves from it.
+    Position {
+        setup: PositionSetup,
+        moves: Vec<String>,
+    },
+    /// Starts searching the current position.
+    Go(GoOptions),
+    /// Stops the current search; the engine answers with `bestmove`.
+    Stop,
+    /// The opponent played the move the engine was pondering on.
+    PonderHit,
+    /// Prints the board.
+    Show,
+    /// Prints the static evaluation of the position.
+    Eval,
+    /// Stops the engine.
+    Quit,
+}
+
+impl YtpCommand {
+    /// Parses a command line.
+    ///
+    /// Returns Ok(None) for empty lines.
+    pub fn parse(line: &str) -> Result<Option<Self>, String> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let Some((&name, args)) = parts.split_first() else {
+            return Ok(None);
+        };
+        let command = match name {
+            "ytp" => YtpCommand::Ytp,
+            "isready" => YtpCommand::IsReady,
+            "newgame" => YtpCommand::NewGame {
+                size: args.first().map(|size| parse_size(size)).transpose()?,
+            },
+            "position" => parse_position(args)?,
+            "go" => YtpCommand::Go(parse_go(args)?),
+            "stop" => YtpCommand::Stop,
+            "ponderhit" => YtpCommand::PonderHit,
+            "show" => YtpCommand::Show,
+            "eval" => YtpCommand::Eval,
+            "quit" => YtpCommand::Quit,
+            other => return Err(format!("unknown command '{}'", other)),
+        };
+        Ok(Some(command))
+    }
+}
+
+/// Parses the arguments of `position`.
+fn parse_position(args: &[&str]) -> Result<YtpCommand, String> {
+    let (setup_args, moves) = match args.iter().position(|&arg| arg == "moves") {
+        Some(pos) => (&args[..pos], &args[pos + 1..]),
+        None => (args, &[][..]),
+    };
+    let setup = match setup_args {
+        ["startpos"] => PositionSetup::StartPos { size: None },
+        ["startpos", "size", size] => PositionSetup::StartPos {
+            size: Some(parse_size(size)?),
+        },
+        ["string", layout, side] => PositionSetup::PositionString(format!("{} {}", layout, side)),
+        _ => {
+            return Err(
+                "expected 'position startpos [size <n>]' or 'position string <layout> <side>'"
+                    .to_string(),
+            );
+        }
+    };
+    Ok(YtpCommand::Position {
+        setup,
+        moves: moves.iter().map(|m| m.to_string()).collect(),
+    })
+}
+
+/// Parses the arguments of `go`.
+fn parse_go(args: &[&str]) -> Result<GoOptions, String> {
+    let mut options = GoOptions::default();
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for '{}'", arg))
+                .and_then(|value| parse_number(value))
+        };
+        match arg {
+            "movetime" => options.limits.max_time_ms = Some(value()?),
+            "nodes" => options.limits.max_nodes = Some(value()?),
+            "depth" => {
+                let depth = value()?;
+                options.limits.max_depth =
+                    Some(u8::try_from(depth).map_err(|_| format!("depth {} is too large", depth))?);
+            }
+            "infinite" => options.infinite = true,
+            "ponder" => options.ponder = true,
+            other => return Err(format!("unknown go option '{}'", other)),
+        }
+    }
+    Ok(options)
+}
+
+/// Parses a board size, rejecting empty boards and boards larger than
+/// [`MAX_ENGINE_BOARD_SIZE`].
+fn parse_size(value: &str) -> Result<u32, String> {
+    let size = parse_number(value)?;
+    if size == 0 || size > MAX_ENGINE_BOARD_SIZE {
+        return Err(format!(
+            "board size {} is not between 1 and {}",
+            size, MAX_ENGINE_BOARD_SIZE
+        ));
+    }
+    Ok(size)
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid number", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_commands() {
+        assert_eq!(YtpCommand::parse("ytp"), Ok(Some(YtpCommand::Ytp)));
+        assert_eq!(YtpCommand::parse("  "), Ok(None));
+        assert_eq!(
+            YtpCommand::parse("newgame 9"),
+            Ok(Some(YtpCommand::NewGame { size: Some(9) }))
+        );
+        assert!(YtpCommand::parse("fly").is_err());
+        assert!(YtpCommand::parse("newgame 0").is_err());
+        assert!(YtpCommand::parse("newgame 4294967295").is_err());
+    }
+
+    #[test]
+    fn test_parse_position() {
+        assert_eq!(
+            YtpCommand::parse("position startpos size 5 moves 3 swap 7"),
+            Ok(Some(YtpCommand::Position {
+                setup: PositionSetup::StartPos { size: Some(5) },
+                moves: vec!["3".to_string(), "swap".to_string(), "7".to_string()],
+            }))
+        );
+        assert_eq!(
+            YtpCommand::parse("position string 1/2/3 R"),
+            Ok(Some(YtpCommand::Position {
+                setup: PositionSetup::PositionString("1/2/3 R".to_string()),
+                moves: vec![],
+            }))
+        );
+        assert!(YtpCommand::parse("position 1/2/3").is_err());
+    }
+
+    #[test]
+    fn test_parse_go() {
+        let Ok(Some(YtpCommand::Go(options))) =
+            YtpCommand::parse("go movetime 200 depth 4 nodes 1000 ponder")
+        else {
+            panic!("expected a go command");
+        };
+        assert_eq!(options.limits.max_time_ms, Some(200));
+        assert_eq!(options.limits.max_depth, Some(4));
+        assert_eq!(options.limits.max_nodes, Some(1000));
+        assert!(options.ponder);
+        assert!(!options.infinite);
+
+        assert!(YtpCommand::parse("go depth").is_err());
+        assert!(YtpCommand::parse("go depth 300").is_err());
+        assert!(YtpCommand::parse("go fast").is_err());
+    }
+}
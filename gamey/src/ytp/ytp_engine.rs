@@ -0,0 +1,400 @@
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{
+    Coordinates, GameAction, GameY, GoOptions, MinimaxState, Movement, PositionSetup,
+    RenderOptions, SearchInfo, SearchLimits, YtpCommand, parse_idx, search_with_limits,
+};
+
+/// How often a finished search checks whether it may send `bestmove`.
+const HOLD_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Output shared between the engine and its search thread.
+type SharedOutput = Arc<Mutex<Box<dyn Write + Send>>>;
+
+/// A search running on a background thread.
+struct RunningSearch {
+    stop: Arc<AtomicBool>,
+    // Set while `bestmove` must be held back (infinite and ponder searches)
+    hold: Arc<AtomicBool>,
+    // Time limit of a ponder search, started at `ponderhit`
+    ponder_time_ms: Option<u64>,
+    handle: JoinHandle<()>,
+}
+
+/// An engine speaking the YTP protocol.
+///
+/// Searches run on a background thread, so `stop`, `ponderhit` and
+/// `isready` are answered while the engine is thinking. The engine plays
+/// with the minimax search of [`MinimaxBot`](crate::MinimaxBot).
+pub struct YtpEngine {
+    game: GameY,
+    output: SharedOutput,
+    search: Option<RunningSearch>,
+    quit: bool,
+}
+
+impl YtpEngine {
+    /// Creates an engine with an empty board of the given size, writing its
+    /// responses to `output`.
+    pub fn new(board_size: u32, output: impl Write + Send + 'static) -> Self {
+        YtpEngine {
+            game: GameY::new(board_size),
+            output: Arc::new(Mutex::new(Box::new(output))),
+            search: None,
+            quit: false,
+        }
+    }
+
+    /// Returns the current game.
+    pub fn game(&self) -> &GameY {
+        &self.game
+    }
+
+    /// Returns true once the `quit` command has been received.
+    pub fn has_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Reads commands from `input` until `quit` is received or the input
+    /// ends. A running search is stopped before returning.
+    pub fn run<R: BufRead>(&mut self, input: R) -> std::io::Result<()> {
+        for line in input.lines() {
+            self.execute_line(&line?);
+            if self.quit {
+                break;
+            }
+        }
+        self.finish_search();
+        Ok(())
+    }
+
+    /// Parses and executes a single command line.
+    ///
+    /// Errors are reported to the GUI as `info string error: ...` lines.
+    pub fn execute_line(&mut self, line: &str) {
+        match YtpCommand::parse(line) {
+            Ok(Some(command)) => self.execute(command),
+            Ok(None) => {}
+            Err(e) => self.send_error(&e),
+        }
+    }
+
+    /// Executes a single command.
+    pub fn execute(&mut self, command: YtpCommand) {
+        match command {
+            YtpCommand::Ytp => {
+                self.send(&format!("id name gamey {}", env!("CARGO_PKG_VERSION")));
+                self.send(&format!(
+                    "id author {}",
+                    env!("CARGO_PKG_AUTHORS").replace(':', ", ")
+                ));
+                self.send("ytpok");
+            }
+            YtpCommand::IsReady => self.send("readyok"),
+            YtpCommand::NewGame { size } => {
+                self.finish_search();
+                self.game = GameY::new(size.unwrap_or(self.game.board_size()));
+            }
+            YtpCommand::Position { setup, moves } => {
+                self.finish_search();
+                if let Err(e) = self.set_position(setup, &moves) {
+                    self.send_error(&e);
+                }
+            }
+            YtpCommand::Go(options) => self.go(options),
+            YtpCommand::Stop => self.finish_search(),
+            YtpCommand::PonderHit => self.ponder_hit(),
+            YtpCommand::Show => self.show(),
+            YtpCommand::Eval => match self.game.next_player() {
+                Some(player) => {
+                    let score = MinimaxState::new(&self.game, player).evaluate();
+                    self.send(&format!("info string eval {}", score));
+                }
+                None => self.send_error("game is over"),
+            },
+            YtpCommand::Quit => {
+                self.finish_search();
+                self.quit = true;
+            }
+        }
+    }
+
+    /// Replaces the game with the given position and plays `moves` from it.
+    ///
+    /// The game is left unchanged if the position or any of the moves is invalid.
+    fn set_position(&mut self, setup: PositionSetup, moves: &[String]) -> Result<(), String> {
+        let mut game = match setup {
+            PositionSetup::StartPos { size } => GameY::new(size.unwrap_or(self.game.board_size())),
+            PositionSetup::PositionString(position) => {
+                GameY::from_position_string(&position).map_err(|e| e.to_string())?
+            }
+        };
+        for cell in moves {
+            let player = game
+                .next_player()
+                .ok_or_else(|| format!("move {} played after the end of the game", cell))?;
+            let movement = if cell == "swap" {
+                Movement::Action {
+                    player,
+                    action: GameAction::Swap,
+                }
+            } else {
+                let idx = parse_idx(cell, game.total_cells())?;
+                Movement::Placement {
+                    player,
+                    coords: Coordinates::from_index(idx, game.board_size()),
+                }
+            };
+            game.add_move(movement).map_err(|e| e.to_string())?;
+        }
+        self.game = game;
+        Ok(())
+    }
+
+    /// Starts a search of the current position on a background thread.
+    fn go(&mut self, options: GoOptions) {
+        if self
+            .search
+            .as_ref()
+            .is_some_and(|s| !s.handle.is_finished())
+        {
+            self.send_error("a search is already running");
+            return;
+        }
+        self.finish_search();
+        let Some(player) = self.game.next_player() else {
+            self.send("bestmove none");
+            return;
+        };
+
+        let mut limits = options.limits;
+        let mut ponder_time_ms = None;
+        if options.infinite {
+            limits = SearchLimits::default();
+        } else if options.ponder {
+            ponder_time_ms = limits.max_time_ms.take();
+        }
+
+        let mut state = MinimaxState::new(&self.game, player);
+        let stop = Arc::new(AtomicBool::new(false));
+        let hold = Arc::new(AtomicBool::new(options.infinite || options.ponder));
+        let output = Arc::clone(&self.output);
+        let (thread_stop, thread_hold) = (Arc::clone(&stop), Arc::clone(&hold));
+        let handle = thread::spawn(move || {
+            let best = search_with_limits(&mut state, &limits, &thread_stop, &mut |info| {
+                send_to(&output, &format_info(info));
+            });
+            while thread_hold.load(Ordering::Relaxed) && !thread_stop.load(Ordering::Relaxed) {
+                thread::sleep(HOLD_POLL_INTERVAL);
+            }
+            let best = best.map_or("none".to_string(), |idx| idx.to_string());
+            send_to(&output, &format!("bestmove {}", best));
+        });
+        self.search = Some(RunningSearch {
+            stop,
+            hold,
+            ponder_time_ms,
+            handle,
+        });
+    }
+
+    /// Turns a ponder search into a normal one, with its time limit
+    /// starting now.
+    fn ponder_hit(&mut self) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        search.hold.store(false, Ordering::Relaxed);
+        if let Some(ms) = search.ponder_time_ms.take() {
+            let stop = Arc::clone(&search.stop);
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(ms));
+                stop.store(true, Ordering::Relaxed);
+            });
+        }
+    }
+
+    /// Stops the running search, if any, and waits for its `bestmove`.
+    fn finish_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            search.stop.store(true, Ordering::Relaxed);
+            if search.handle.join().is_err() {
+                self.send_error("the search thread panicked");
+            }
+        }
+    }
+
+    /// Prints the board and its position string.
+    fn show(&self) {
+        let options = RenderOptions {
+            show_3d_coords: false,
+            show_idx: true,
+            show_colors: false,
+        };
+        for line in self.game.render(&options).lines() {
+            if !line.trim().is_empty() {
+                self.send(line.trim_end());
+            }
+        }
+        self.send(&format!(
+            "position string {}",
+            self.game.to_position_string()
+        ));
+    }
+
+    fn send(&self, line: &str) {
+        send_to(&self.output, line);
+    }
+
+    fn send_error(&self, message: &str) {
+        self.send(&format!("info string error: {}", message));
+    }
+}
+
+impl Drop for YtpEngine {
+    fn drop(&mut self) {
+        self.finish_search();
+    }
+}
+
+/// Formats a search report as an `info` line.
+pub fn format_info(info: &SearchInfo) -> String {
+    let pv: Vec<String> = info.pv.iter().map(|idx| idx.to_string()).collect();
+    format!(
+        "info depth {} score {} nodes {} time {} pv {}",
+        info.depth,
+        info.score,
+        info.nodes,
+        info.elapsed_ms,
+        pv.join(" ")
+    )
+}
+
+/// Writes a line to the shared output and flushes it.
+fn send_to(output: &SharedOutput, line: &str) {
+    let mut output = output
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    // A GUI that closed the pipe cannot be told about it
+    let _ = writeln!(output, "{}", line);
+    let _ = output.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An output buffer that can be read while the engine owns a handle to it.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn lines(&self) -> Vec<String> {
+            let bytes = self.0.lock().unwrap().clone();
+            String::from_utf8(bytes)
+                .unwrap()
+                .lines()
+                .map(str::to_string)
+                .collect()
+        }
+    }
+
+    fn engine(size: u32) -> (YtpEngine, SharedBuffer) {
+        let buffer = SharedBuffer::default();
+        (YtpEngine::new(size, buffer.clone()), buffer)
+    }
+
+    /// Waits until the running search ends on its own.
+    fn wait_for_search(engine: &mut YtpEngine) {
+        let search = engine.search.take().expect("a search is running");
+        search.handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_handshake() {
+        let (mut engine, output) = engine(5);
+        engine.execute_line("ytp");
+        engine.execute_line("isready");
+        let lines = output.lines();
+        assert!(lines[0].starts_with("id name gamey"));
+        assert!(lines[1].starts_with("id author"));
+        assert_eq!(&lines[2..], ["ytpok", "readyok"]);
+    }
+
+    #[test]
+    fn test_position_with_moves() {
+        let (mut engine, output) = engine(5);
+        engine.execute_line("position startpos size 4 moves 0 4 1");
+        assert_eq!(engine.game().board_size(), 4);
+        assert_eq!(engine.game().history().len(), 3);
+
+        // An illegal move leaves the previous position in place
+        engine.execute_line("position startpos moves 0 0");
+        assert_eq!(engine.game().history().len(), 3);
+        assert!(output.lines()[0].starts_with("info string error:"));
+
+        engine.execute_line("position string 1/2/3 R moves 4");
+        assert_eq!(engine.game().to_position_string(), "1/2/1R1 B");
+    }
+
+    #[test]
+    fn test_go_reports_info_and_best_move() {
+        let (mut engine, output) = engine(4);
+        engine.execute_line("go depth 2");
+        wait_for_search(&mut engine);
+        let lines = output.lines();
+        assert!(lines[0].starts_with("info depth 1 score "));
+        assert!(lines[1].starts_with("info depth 2 score "));
+        let pv_first = lines[1]
+            .split(" pv ")
+            .nth(1)
+            .unwrap()
+            .split(' ')
+            .next()
+            .unwrap();
+        assert_eq!(lines[2], format!("bestmove {}", pv_first));
+    }
+
+    #[test]
+    fn test_infinite_search_waits_for_stop() {
+        let (mut engine, output) = engine(3);
+        engine.execute_line("go infinite");
+        thread::sleep(Duration::from_millis(50));
+        assert!(!output.lines().iter().any(|l| l.starts_with("bestmove")));
+        engine.execute_line("stop");
+        assert!(output.lines().last().unwrap().starts_with("bestmove "));
+    }
+
+    #[test]
+    fn test_ponder_hit_starts_the_clock() {
+        let (mut engine, output) = engine(3);
+        engine.execute_line("go ponder movetime 10 depth 1");
+        thread::sleep(Duration::from_millis(30));
+        assert!(!output.lines().iter().any(|l| l.starts_with("bestmove")));
+        engine.execute_line("ponderhit");
+        wait_for_search(&mut engine);
+        assert!(output.lines().last().unwrap().starts_with("bestmove "));
+    }
+
+    #[test]
+    fn test_go_after_game_over() {
+        let (mut engine, output) = engine(2);
+        engine.execute_line("position startpos moves 0 1 2");
+        engine.execute_line("go");
+        assert_eq!(output.lines(), ["bestmove none"]);
+    }
+}
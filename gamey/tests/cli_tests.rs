@@ -390,3 +390,50 @@ fn test_cli_args_version_flag() {
     let result = CliArgs::try_parse_from(["gamey", "--version"]);
     assert!(result.is_err()); // --version causes an error (but it's intentional)
 }
+
+// =============================================================================
+// gamey-cli Tests
+// =============================================================================
+
+use gamey::{Coordinates, Difficulty, PlayArgs, PlayCommand, parse_play_command};
+
+#[test]
+fn test_play_args_defaults() {
+    let args = PlayArgs::try_parse_from(["gamey-cli"]).unwrap();
+    assert_eq!(args.size, 7);
+    assert_eq!(args.difficulty, Difficulty::Medium);
+    assert!(!args.bot_first);
+    assert_eq!(args.time_ms, None);
+}
+
+#[test]
+fn test_play_args_difficulty_and_time() {
+    let args = PlayArgs::try_parse_from([
+        "gamey-cli",
+        "--difficulty",
+        "expert",
+        "--time-ms",
+        "250",
+        "--bot-first",
+    ])
+    .unwrap();
+    assert_eq!(args.difficulty, Difficulty::Expert);
+    assert_eq!(args.time_ms, Some(250));
+    assert!(args.bot_first);
+}
+
+#[test]
+fn test_play_args_invalid_difficulty() {
+    assert!(PlayArgs::try_parse_from(["gamey-cli", "--difficulty", "impossible"]).is_err());
+}
+
+#[test]
+fn test_parse_play_command_cell_name() {
+    let command = parse_play_command("b2", 5).unwrap();
+    assert_eq!(command, Some(PlayCommand::Play(Coordinates::new(3, 1, 0))));
+}
+
+#[test]
+fn test_parse_play_command_unknown() {
+    assert!(parse_play_command("dance", 5).is_err());
+}
@@ -0,0 +1,216 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use gamey::{
+    BotMoveResponse, ErrorResponse, GameServerState, GameStateResponse, RandomBot, YBotRegistry,
+    create_game_router,
+};
+use http_body_util::BodyExt;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+/// Helper to create a game server state with only the random bot
+fn test_state() -> GameServerState {
+    GameServerState::new(YBotRegistry::new().with_bot(Arc::new(RandomBot::new())))
+}
+
+/// Sends a request to the router and returns the status and decoded body
+async fn send<T: DeserializeOwned>(
+    state: &GameServerState,
+    method: &str,
+    uri: &str,
+    body: Option<&str>,
+) -> (StatusCode, T) {
+    let request = Request::builder().method(method).uri(uri);
+    let request = match body {
+        Some(body) => request
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+    let response = create_game_router(state.clone())
+        .oneshot(request)
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+async fn create_game(state: &GameServerState, size: u32) -> GameStateResponse {
+    let body = format!(r#"{{"size":{}}}"#, size);
+    let (status, game) = send(state, "POST", "/v1/games", Some(&body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    game
+}
+
+#[tokio::test]
+async fn test_create_and_get_game() {
+    let state = test_state();
+    let created = create_game(&state, 3).await;
+    assert_eq!(created.yen.size(), 3);
+    assert_eq!(created.next_player, Some(0));
+    assert!(created.history.is_empty());
+
+    let uri = format!("/v1/games/{}", created.game_id);
+    let (status, fetched): (_, GameStateResponse) = send(&state, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched.game_id, created.game_id);
+    assert_eq!(fetched.yen.layout(), created.yen.layout());
+}
+
+#[tokio::test]
+async fn test_play_moves_until_win() {
+    let state = test_state();
+    let game = create_game(&state, 3).await;
+    let uri = format!("/v1/games/{}/moves", game.game_id);
+
+    let mut last = game;
+    for (player, cell) in [(0, 0), (1, 4), (0, 1), (1, 5), (0, 3)] {
+        let body = format!(r#"{{"player":{},"cell":{}}}"#, player, cell);
+        let (status, state) = send(&state, "POST", &uri, Some(&body)).await;
+        assert_eq!(status, StatusCode::OK);
+        last = state;
+    }
+    assert_eq!(last.next_player, None);
+    assert_eq!(last.result.unwrap().winner.id(), 0);
+    assert_eq!(last.history.len(), 5);
+}
+
+#[tokio::test]
+async fn test_invalid_moves_are_rejected() {
+    let state = test_state();
+    let game = create_game(&state, 3).await;
+    let uri = format!("/v1/games/{}/moves", game.game_id);
+
+    let (status, _): (_, ErrorResponse) =
+        send(&state, "POST", &uri, Some(r#"{"player":1,"cell":0}"#)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, error): (_, ErrorResponse) =
+        send(&state, "POST", &uri, Some(r#"{"player":0,"cell":6}"#)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(error.message.contains("out of range"));
+
+    let (status, _): (_, GameStateResponse) =
+        send(&state, "POST", &uri, Some(r#"{"player":0,"cell":0}"#)).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _): (_, ErrorResponse) =
+        send(&state, "POST", &uri, Some(r#"{"player":1,"cell":0}"#)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_bot_move_plays_for_player_to_move() {
+    let state = test_state();
+    let game = create_game(&state, 4).await;
+    let uri = format!("/v1/games/{}/bot-move", game.game_id);
+
+    let (status, reply): (_, BotMoveResponse) =
+        send(&state, "POST", &uri, Some(r#"{"bot_id":"random_bot"}"#)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(reply.bot_id, "random_bot");
+    assert!(reply.cell < 10);
+    assert_eq!(reply.state.next_player, Some(1));
+    assert_eq!(reply.state.history.len(), 1);
+}
+
+#[tokio::test]
+async fn test_bot_move_with_unknown_bot() {
+    let state = test_state();
+    let game = create_game(&state, 4).await;
+    let uri = format!("/v1/games/{}/bot-move", game.game_id);
+
+    let (status, error): (_, ErrorResponse) =
+        send(&state, "POST", &uri, Some(r#"{"bot_id":"nobody"}"#)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(error.message.contains("random_bot"));
+}
+
+#[tokio::test]
+async fn test_resign_ends_game() {
+    let state = test_state();
+    let game = create_game(&state, 3).await;
+    let uri = format!("/v1/games/{}/resign", game.game_id);
+
+    let (status, resigned): (_, GameStateResponse) =
+        send(&state, "POST", &uri, Some(r#"{"player":1}"#)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(resigned.result.unwrap().winner.id(), 0);
+
+    let bot_uri = format!("/v1/games/{}/bot-move", game.game_id);
+    let (status, _): (_, ErrorResponse) =
+        send(&state, "POST", &bot_uri, Some(r#"{"bot_id":"random_bot"}"#)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_unknown_game_and_version() {
+    let state = test_state();
+    let (status, error): (_, ErrorResponse) = send(&state, "GET", "/v1/games/42", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(error.message, "Game not found: 42");
+
+    let (status, error): (_, ErrorResponse) =
+        send(&state, "POST", "/v2/games", Some(r#"{"size":3}"#)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error.api_version, Some("v2".to_string()));
+}
+
+#[tokio::test]
+async fn test_create_game_from_a_record() {
+    let state = test_state();
+    let body = r#"{"size":3,"record":"(;SZ[3];B[aa];W[bb])"}"#;
+    let (status, game): (_, GameStateResponse) =
+        send(&state, "POST", "/v1/games", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(game.history.len(), 2);
+    assert_eq!(game.next_player, Some(0));
+
+    for body in [
+        r#"{"size":3,"record":"(;SZ[3];B[aa];W[aa])"}"#,
+        r#"{"size":4,"record":"(;SZ[3];B[aa])"}"#,
+    ] {
+        let (status, error): (_, ErrorResponse) =
+            send(&state, "POST", "/v1/games", Some(body)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", error.message);
+    }
+    assert_eq!(state.len(), 1);
+}
+
+#[tokio::test]
+async fn test_create_game_with_an_opening_rule() {
+    let state = test_state();
+    let body = r#"{"size":4,"opening_rule":{"rule":"forbidden_centre","edge_distance":0}}"#;
+    let (status, game): (_, GameStateResponse) =
+        send(&state, "POST", "/v1/games", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let uri = format!("/v1/games/{}/moves", game.game_id);
+
+    // The centre of the board is forbidden for the first stone only
+    let (status, error): (_, ErrorResponse) =
+        send(&state, "POST", &uri, Some(r#"{"player":0,"cell":4}"#)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(error.message.contains("opening rule"));
+    for (player, cell) in [(0, 0), (1, 4)] {
+        let body = format!(r#"{{"player":{},"cell":{}}}"#, player, cell);
+        let (status, _): (_, GameStateResponse) = send(&state, "POST", &uri, Some(&body)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let body = r#"{"size":3,"record":"3,a1","opening_rule":{"rule":"one_two_two"}}"#;
+    let (status, _): (_, ErrorResponse) = send(&state, "POST", "/v1/games", Some(body)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_create_game_rejects_bad_size() {
+    let state = test_state();
+    let (status, _): (_, ErrorResponse) =
+        send(&state, "POST", "/v1/games", Some(r#"{"size":0}"#)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(state.is_empty());
+}
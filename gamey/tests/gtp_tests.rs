@@ -0,0 +1,78 @@
+use gamey::{
+    ExternalEngineBot, GtpEngine, MinimaxBot, RandomBot, YBot, YBotRegistry, play_arena_game,
+};
+use std::sync::Arc;
+
+fn run_script(engine: &mut GtpEngine, script: &str) -> String {
+    let mut output = Vec::new();
+    engine.run(script.as_bytes(), &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn test_session_echoes_ids_and_reports_errors() {
+    let mut engine = GtpEngine::new(4, Arc::new(RandomBot::new()));
+    let output = run_script(
+        &mut engine,
+        "1 protocol_version\n# a comment\n\n2 name\n3 play b 100\n4 boardsize 3\n",
+    );
+    assert_eq!(
+        output,
+        "=1 2\n\n=2 gamey\n\n?3 invalid vertex: Index out of bounds: 100 > 9\n\n=4 \n\n"
+    );
+    assert_eq!(engine.game().board_size(), 3);
+}
+
+#[test]
+fn test_quit_stops_reading_commands() {
+    let mut engine = GtpEngine::new(3, Arc::new(RandomBot::new()));
+    let output = run_script(&mut engine, "quit\nplay b 0\n");
+    assert_eq!(output, "= \n\n");
+    assert!(engine.has_quit());
+    assert!(engine.game().history().is_empty());
+}
+
+#[test]
+fn test_bots_play_a_full_game() {
+    let bots = YBotRegistry::new()
+        .with_bot(Arc::new(RandomBot::new()))
+        .with_bot(Arc::new(MinimaxBot::new(10)));
+    let mut engine = GtpEngine::new(4, Arc::new(RandomBot::new())).with_bots(bots);
+    run_script(&mut engine, "gamey-bot minimax_bot\n");
+
+    let mut script = String::new();
+    for _ in 0..5 {
+        script.push_str("genmove b\ngenmove w\n");
+    }
+    run_script(&mut engine, &script);
+    assert!(engine.game().check_game_over());
+    let output = run_script(&mut engine, "final_score\n");
+    assert!(output.starts_with("= B+") || output.starts_with("= W+"));
+}
+
+#[test]
+fn test_showboard_starts_on_a_new_line() {
+    let mut engine = GtpEngine::new(3, Arc::new(RandomBot::new()));
+    let output = run_script(&mut engine, "play b 0\nshowboard\n");
+    assert!(output.starts_with("= \n\n= \n--- Game of Y (Size 3) ---"));
+    let board = output.strip_prefix("= \n\n").unwrap();
+    // The only empty line is the one ending the response
+    assert_eq!(board.find("\n\n"), Some(board.len() - 2));
+}
+
+#[test]
+fn test_gtp_binary_plays_as_an_external_engine() {
+    let engine = ExternalEngineBot::new(env!("CARGO_BIN_EXE_gamey-gtp"))
+        .with_args(["--bot", "random_bot"])
+        .with_timeout_ms(5000);
+    assert_eq!(engine.name(), "gamey-gtp");
+    let minimax = MinimaxBot::with_depth(2);
+    for bots in [
+        [&engine as &dyn YBot, &minimax as &dyn YBot],
+        [&minimax as &dyn YBot, &engine as &dyn YBot],
+    ] {
+        let outcome = play_arena_game(bots, 4, None);
+        assert_eq!(outcome.fault, None);
+        assert!(outcome.game.check_game_over());
+    }
+}
@@ -0,0 +1,200 @@
+use futures_util::{SinkExt, StreamExt};
+use gamey::{
+    GameOverReason, GameServerState, RandomBot, RoomServerMessage, RoomStateResponse, YBotRegistry,
+    create_game_router,
+};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async, tungstenite::Message};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Serves the game router on a free local port and returns its address
+async fn start_server() -> String {
+    let state = GameServerState::new(YBotRegistry::new().with_bot(Arc::new(RandomBot::new())));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, create_game_router(state))
+            .await
+            .unwrap();
+    });
+    addr.to_string()
+}
+
+/// Creates a room through the HTTP API with the given request body
+async fn create_room(addr: &str, body: Value) -> RoomStateResponse {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let body = body.to_string();
+    let request = format!(
+        "POST /v1/rooms HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        addr,
+        body.len(),
+        body
+    );
+    tokio::io::AsyncWriteExt::write_all(&mut stream, request.as_bytes())
+        .await
+        .unwrap();
+    let mut response = String::new();
+    tokio::io::AsyncReadExt::read_to_string(&mut stream, &mut response)
+        .await
+        .unwrap();
+    assert!(response.starts_with("HTTP/1.1 201"), "{}", response);
+    let json = &response[response.find("\r\n\r\n").unwrap() + 4..];
+    serde_json::from_str(json).unwrap()
+}
+
+async fn connect(addr: &str, room_id: u64, query: &str) -> Socket {
+    let url = format!("ws://{}/v1/rooms/{}/ws{}", addr, room_id, query);
+    connect_async(url).await.unwrap().0
+}
+
+async fn send(socket: &mut Socket, message: Value) {
+    socket
+        .send(Message::Text(message.to_string().into()))
+        .await
+        .unwrap();
+}
+
+async fn recv(socket: &mut Socket) -> RoomServerMessage {
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+            .await
+            .expect("timed out waiting for a message")
+            .unwrap()
+            .unwrap();
+        if let Message::Text(text) = message {
+            return serde_json::from_str(&text).unwrap();
+        }
+    }
+}
+
+/// Reads messages until a state matching `done` arrives
+async fn recv_state_until(
+    socket: &mut Socket,
+    done: impl Fn(&RoomStateResponse) -> bool,
+) -> RoomStateResponse {
+    loop {
+        if let RoomServerMessage::State { room } = recv(socket).await
+            && done(&room)
+        {
+            return room;
+        }
+    }
+}
+
+/// Reads messages until the seat token arrives
+async fn recv_joined(socket: &mut Socket) -> (u32, String) {
+    loop {
+        match recv(socket).await {
+            RoomServerMessage::Joined { player, token } => return (player, token),
+            RoomServerMessage::Error { message } => panic!("join rejected: {}", message),
+            RoomServerMessage::State { .. } => {}
+        }
+    }
+}
+
+async fn recv_error(socket: &mut Socket) -> String {
+    loop {
+        if let RoomServerMessage::Error { message } = recv(socket).await {
+            return message;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_two_players_and_a_spectator() {
+    let addr = start_server().await;
+    let room = create_room(&addr, json!({"size": 3})).await;
+
+    let mut first = connect(&addr, room.room_id, "").await;
+    let mut second = connect(&addr, room.room_id, "").await;
+    let mut spectator = connect(&addr, room.room_id, "?spectate=true").await;
+
+    send(&mut first, json!({"type": "join", "name": "ana"})).await;
+    assert_eq!(recv_joined(&mut first).await.0, 0);
+    send(&mut second, json!({"type": "join", "name": "bea"})).await;
+    assert_eq!(recv_joined(&mut second).await.0, 1);
+
+    send(&mut spectator, json!({"type": "join", "name": "cid"})).await;
+    assert_eq!(recv_error(&mut spectator).await, "Spectators cannot play");
+
+    send(&mut second, json!({"type": "play", "cell": 0})).await;
+    assert!(recv_error(&mut second).await.contains("Wrong player"));
+
+    // Each player waits to see the opponent's move, as a real client would
+    send(&mut first, json!({"type": "play", "cell": 0})).await;
+    recv_state_until(&mut second, |room| room.game.history.len() == 1).await;
+    send(&mut second, json!({"type": "play", "cell": 4})).await;
+    recv_state_until(&mut first, |room| room.game.history.len() == 2).await;
+    send(&mut first, json!({"type": "play", "cell": 1})).await;
+    let seen = recv_state_until(&mut spectator, |room| room.game.history.len() == 3).await;
+    assert_eq!(seen.game.next_player, Some(1));
+
+    send(&mut second, json!({"type": "resign"})).await;
+    let over = recv_state_until(&mut first, |room| room.game.result.is_some()).await;
+    assert_eq!(over.game.result.unwrap().winner.id(), 0);
+}
+
+#[tokio::test]
+async fn test_rejoin_after_disconnect() {
+    let addr = start_server().await;
+    let room = create_room(&addr, json!({"size": 3})).await;
+
+    let mut first = connect(&addr, room.room_id, "").await;
+    send(&mut first, json!({"type": "join", "name": "ana"})).await;
+    let (_, token) = recv_joined(&mut first).await;
+    first.close(None).await.unwrap();
+
+    let mut watcher = connect(&addr, room.room_id, "?spectate=true").await;
+    recv_state_until(&mut watcher, |room| !room.seats[0].connected).await;
+
+    let mut again = connect(&addr, room.room_id, "").await;
+    send(&mut again, json!({"type": "rejoin", "token": token})).await;
+    assert_eq!(recv_joined(&mut again).await.0, 0);
+    recv_state_until(&mut watcher, |room| room.seats[0].connected).await;
+
+    let mut intruder = connect(&addr, room.room_id, "").await;
+    send(&mut intruder, json!({"type": "rejoin", "token": "guess"})).await;
+    assert_eq!(recv_error(&mut intruder).await, "Unknown seat token");
+}
+
+#[tokio::test]
+async fn test_bot_replies_to_moves() {
+    let addr = start_server().await;
+    let room = create_room(&addr, json!({"size": 4, "bot_id": "random_bot"})).await;
+    assert!(room.seats[1].occupant.is_some());
+
+    let mut human = connect(&addr, room.room_id, "").await;
+    send(&mut human, json!({"type": "join", "name": "ana"})).await;
+    recv_joined(&mut human).await;
+    send(&mut human, json!({"type": "play", "cell": 4})).await;
+
+    let replied = recv_state_until(&mut human, |room| room.game.history.len() == 2).await;
+    assert_eq!(replied.game.next_player, Some(0));
+}
+
+#[tokio::test]
+async fn test_clock_runs_out_on_server() {
+    let addr = start_server().await;
+    let room = create_room(
+        &addr,
+        json!({"size": 3, "time_control": {"initial_ms": 200}}),
+    )
+    .await;
+
+    let mut first = connect(&addr, room.room_id, "").await;
+    let mut second = connect(&addr, room.room_id, "").await;
+    send(&mut first, json!({"type": "join", "name": "ana"})).await;
+    recv_joined(&mut first).await;
+    send(&mut second, json!({"type": "join", "name": "bea"})).await;
+    recv_joined(&mut second).await;
+
+    let over = recv_state_until(&mut second, |room| room.game.result.is_some()).await;
+    let result = over.game.result.unwrap();
+    assert_eq!(result.winner.id(), 1);
+    assert_eq!(result.reason, GameOverReason::Timeout);
+    assert_eq!(over.seats[0].remaining_ms, Some(0));
+}